@@ -211,7 +211,10 @@ impl McpServer {
         let result = repo.get_messages(&params).await.map_err(mcp_err)?;
 
         let scoped_tools = session_id.map(|_| {
-            extract_tools_from_rows(result.rows.iter().filter(|r| r.trace_id == trace_id))
+            extract_tools_from_rows(
+                result.rows.iter().filter(|r| r.trace_id == trace_id),
+                options.fold_tool_name_case,
+            )
         });
 
         let mut processed = process_spans(result.rows, &options);
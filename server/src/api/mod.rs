@@ -12,5 +12,5 @@ mod server;
 pub mod types;
 
 pub use auth::AuthManager;
-pub use routes::otlp_collector::OtlpGrpcServer;
+pub use routes::otlp_collector::{GrpcAuth, OtlpGrpcServer};
 pub use server::ApiServer;
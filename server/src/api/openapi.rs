@@ -8,10 +8,10 @@ use crate::api::routes::{
     api_keys, auth, favorites, health, organizations, otel, pricing, projects, users,
 };
 use crate::api::types::{OrderDirection, PaginationMeta};
-use crate::data::types::ApiKeyScope;
+use crate::data::types::{ApiKeyScope, FeatureFlags};
 use crate::domain::sideml::{
-    CacheControl, ChatMessage, ChatRole, ContentBlock, FinishReason, JsonSchemaDetails,
-    ResponseFormat, ToolChoice,
+    CacheControl, ChatMessage, ChatRole, ContentBlock, ContentSizeStats, FeedWarning,
+    FeedWarningKind, FinishReason, JsonSchemaDetails, ResponseFormat, ToolChoice, Turn,
 };
 
 #[derive(OpenApi)]
@@ -22,7 +22,7 @@ use crate::domain::sideml::{
         description = "AI Development Workbench"
     ),
     tags(
-        (name = "health", description = "Health check endpoint"),
+        (name = "health", description = "Health check and internal metrics"),
         (name = "auth", description = "Authentication endpoints"),
         (name = "organizations", description = "Organization management"),
         (name = "users", description = "User management"),
@@ -40,6 +40,9 @@ use crate::domain::sideml::{
     paths(
         // Health
         health::health,
+        health::liveness,
+        health::readiness,
+        health::metrics,
         // Auth
         auth::exchange_token,
         auth::auth_status,
@@ -54,6 +57,12 @@ use crate::domain::sideml::{
         organizations::add_org_member,
         organizations::update_member_role,
         organizations::remove_org_member,
+        organizations::get_feature_flags,
+        organizations::update_feature_flags,
+        organizations::get_org_budget,
+        organizations::update_org_budget,
+        organizations::update_org_default_project,
+        organizations::update_org_retention,
         // Users
         users::get_current_user,
         users::update_current_user,
@@ -63,22 +72,32 @@ use crate::domain::sideml::{
         projects::get_project,
         projects::update_project,
         projects::delete_project,
+        projects::delete_project_traces,
         // Pricing
         pricing::calculate_cost,
         pricing::get_model_pricing,
+        pricing::lookup_model_pricing,
+        pricing::compare_family_pricing,
+        pricing::list_held_rate_changes,
+        pricing::list_unmatched_models,
+        pricing::normalize_model,
         // Traces
         otel::traces::list_traces,
         otel::traces::get_trace,
         otel::traces::delete_traces,
         otel::traces::get_trace_filter_options,
         otel::messages::get_trace_messages,
+        otel::messages::get_trace_errors,
         // Spans
         otel::spans::list_spans,
         otel::spans::list_trace_spans,
         otel::spans::get_span,
+        otel::spans::get_span_cost,
+        otel::spans::get_span_events,
         otel::spans::delete_spans,
         otel::spans::get_span_filter_options,
         otel::messages::get_span_messages,
+        otel::messages::get_span_prompt,
         // Sessions
         otel::sessions::list_sessions,
         otel::sessions::get_session,
@@ -87,6 +106,8 @@ use crate::domain::sideml::{
         otel::sessions::get_session_filter_options,
         // Stats
         otel::stats::get_project_stats,
+        otel::stats::get_project_timeseries,
+        otel::stats::get_tool_stats,
         // Feed
         otel::feed::get_feed_messages,
         otel::feed::get_feed_spans,
@@ -111,6 +132,12 @@ use crate::domain::sideml::{
         OrderDirection,
         // Health
         health::HealthResponse,
+        health::ReadinessResponse,
+        health::ComponentStatus,
+        health::MetricsResponse,
+        health::LatencyHistogramDto,
+        health::ConnectionPoolDto,
+        health::LatencyBucketDto,
         // Auth
         auth::ExchangeRequest,
         auth::ExchangeResponse,
@@ -123,6 +150,13 @@ use crate::domain::sideml::{
         organizations::types::UpdateOrgRequest,
         organizations::types::AddMemberRequest,
         organizations::types::UpdateMemberRoleRequest,
+        organizations::types::UpdateFeatureFlagsRequest,
+        organizations::types::UpdateOrgBudgetRequest,
+        organizations::types::UpdateOrgDefaultProjectRequest,
+        organizations::types::UpdateOrgRetentionRequest,
+        organizations::types::OrgBudgetDto,
+        organizations::types::ProjectSpendDto,
+        FeatureFlags,
         organizations::types::ListOrgsQuery,
         organizations::types::ListMembersQuery,
         // Users
@@ -135,11 +169,15 @@ use crate::domain::sideml::{
         projects::types::CreateProjectRequest,
         projects::types::UpdateProjectRequest,
         projects::types::ListProjectsQuery,
+        projects::types::DeleteProjectTracesRequest,
+        projects::types::DeleteProjectTracesResponse,
         // OTEL types
         otel::types::TraceSummaryDto,
         otel::types::TraceDetailDto,
         otel::types::SpanSummaryDto,
         otel::types::SpanDetailDto,
+        otel::types::SpanCostDto,
+        otel::types::SpanEventDto,
         otel::types::SessionSummaryDto,
         otel::types::SessionDetailDto,
         otel::types::TraceInSessionDto,
@@ -157,10 +195,15 @@ use crate::domain::sideml::{
         otel::types::PeriodDto,
         otel::types::CountsDto,
         otel::types::CostsDto,
+        otel::types::CostExtrapolationDto,
         otel::types::TokensDto,
         otel::types::FrameworkBreakdownDto,
         otel::types::ModelBreakdownDto,
         otel::types::TrendBucketDto,
+        otel::types::TimeseriesDto,
+        otel::types::TimeseriesBucketDto,
+        otel::types::ToolStatsDto,
+        otel::types::ToolStatsResponse,
         otel::types::LatencyBucketDto,
         // Feed types
         otel::types::FeedPagination,
@@ -181,7 +224,20 @@ use crate::domain::sideml::{
         pricing::CalculateCostResponse,
         pricing::ModelPricingRequest,
         pricing::ModelPricingResponse,
+        pricing::PricingLookupRequest,
+        pricing::PricingLookupEntry,
+        pricing::PricingLookupResponse,
+        pricing::FamilyPricingRequest,
+        pricing::FamilyPricingResponse,
+        pricing::ProviderRatesDto,
+        pricing::NormalizeModelQuery,
         crate::domain::pricing::MatchType,
+        crate::domain::pricing::HeldRateChange,
+        crate::domain::pricing::UnmatchedModel,
+        crate::domain::pricing::ModelCapabilities,
+        crate::domain::pricing::ModelNormalization,
+        crate::domain::pricing::NormalizedForm,
+        crate::domain::pricing::NormalizationStep,
         // API Keys types
         ApiKeyScope,
         api_keys::types::CreateApiKeyRequest,
@@ -190,7 +246,11 @@ use crate::domain::sideml::{
         // SideML types
         ChatRole,
         ContentBlock,
+        ContentSizeStats,
+        FeedWarning,
+        FeedWarningKind,
         FinishReason,
+        Turn,
         ToolChoice,
         ResponseFormat,
         JsonSchemaDetails,
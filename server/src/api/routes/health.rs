@@ -1,11 +1,23 @@
-//! Health check endpoint
+//! Health check and internal metrics endpoints
+
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Json;
+use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::core::TopicService;
+use crate::core::constants::READINESS_CHECK_TIMEOUT_SECS;
+use crate::data::topics::{HistogramSnapshot, PoolStats};
+use crate::data::{AnalyticsService, TransactionalService};
+use crate::domain::pricing::PricingService;
+
 #[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
     pub status: &'static str,
@@ -30,3 +42,284 @@ pub async fn health() -> impl IntoResponse {
         }),
     )
 }
+
+/// Liveness probe: the process is up and serving requests
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Process is alive", body = HealthResponse)
+    )
+)]
+pub async fn liveness() -> impl IntoResponse {
+    health().await
+}
+
+/// Dependencies checked by the readiness probe
+#[derive(Clone)]
+pub struct ReadinessState {
+    pub database: Arc<TransactionalService>,
+    pub analytics: Arc<AnalyticsService>,
+    pub topics: Arc<TopicService>,
+    pub pricing: Arc<PricingService>,
+}
+
+/// Status of a single readiness dependency
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComponentStatus {
+    pub name: &'static str,
+    pub healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ComponentStatus {
+    fn healthy(name: &'static str) -> Self {
+        Self {
+            name,
+            healthy: true,
+            error: None,
+        }
+    }
+
+    fn unhealthy(name: &'static str, error: impl Into<String>) -> Self {
+        Self {
+            name,
+            healthy: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// "ready" when every component is healthy, "not_ready" otherwise
+    pub status: &'static str,
+    pub components: Vec<ComponentStatus>,
+}
+
+/// Run one dependency check, bounded by `READINESS_CHECK_TIMEOUT_SECS`
+async fn check_component<E: Display>(
+    name: &'static str,
+    check: impl Future<Output = Result<(), E>>,
+) -> ComponentStatus {
+    match tokio::time::timeout(Duration::from_secs(READINESS_CHECK_TIMEOUT_SECS), check).await {
+        Ok(Ok(())) => ComponentStatus::healthy(name),
+        Ok(Err(e)) => ComponentStatus::unhealthy(name, e.to_string()),
+        Err(_) => ComponentStatus::unhealthy(
+            name,
+            format!("timed out after {}s", READINESS_CHECK_TIMEOUT_SECS),
+        ),
+    }
+}
+
+/// Aggregate component statuses: 200 when all are healthy, 503 otherwise
+fn readiness_response(components: Vec<ComponentStatus>) -> (StatusCode, Json<ReadinessResponse>) {
+    let ready = components.iter().all(|c| c.healthy);
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (code, Json(ReadinessResponse { status, components }))
+}
+
+/// Readiness probe: database reachable, topic backend healthy, pricing loaded
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "All dependencies are healthy", body = ReadinessResponse),
+        (status = 503, description = "At least one dependency is unhealthy", body = ReadinessResponse)
+    )
+)]
+pub async fn readiness(State(state): State<ReadinessState>) -> impl IntoResponse {
+    let (database, analytics, topics) = tokio::join!(
+        check_component("database", state.database.ping()),
+        check_component("analytics", state.analytics.ping()),
+        check_component("topics", state.topics.health_check()),
+    );
+    let pricing = match state.pricing.model_count() {
+        0 => ComponentStatus::unhealthy("pricing", "no pricing models loaded"),
+        _ => ComponentStatus::healthy("pricing"),
+    };
+    let components = vec![database, analytics, topics, pricing];
+    for component in components.iter().filter(|c| !c.healthy) {
+        tracing::warn!(
+            component = component.name,
+            error = component.error.as_deref().unwrap_or_default(),
+            "Readiness check failed"
+        );
+    }
+    readiness_response(components)
+}
+
+/// One cumulative histogram bucket
+#[derive(Serialize, ToSchema)]
+pub struct LatencyBucketDto {
+    /// Upper bound in milliseconds (null for the +Inf bucket)
+    pub le_ms: Option<u64>,
+    /// Samples at or below the bound
+    pub count: u64,
+}
+
+/// Latency histogram for a stream topic or pipeline stage
+#[derive(Serialize, ToSchema)]
+pub struct LatencyHistogramDto {
+    pub name: String,
+    pub count: u64,
+    pub sum_ms: f64,
+    pub buckets: Vec<LatencyBucketDto>,
+}
+
+impl LatencyHistogramDto {
+    fn from_snapshot(name: String, snapshot: HistogramSnapshot) -> Self {
+        Self {
+            name,
+            count: snapshot.count,
+            sum_ms: snapshot.sum_ms,
+            buckets: snapshot
+                .buckets
+                .into_iter()
+                .map(|(le_ms, count)| LatencyBucketDto { le_ms, count })
+                .collect(),
+        }
+    }
+}
+
+/// Connection pool usage of the topic backend
+#[derive(Serialize, ToSchema)]
+pub struct ConnectionPoolDto {
+    pub max_size: usize,
+    pub in_use: usize,
+    pub idle: usize,
+    /// Callers waiting for a free connection
+    pub waiting: usize,
+}
+
+impl From<PoolStats> for ConnectionPoolDto {
+    fn from(stats: PoolStats) -> Self {
+        Self {
+            max_size: stats.max_size,
+            in_use: stats.in_use,
+            idle: stats.idle,
+            waiting: stats.waiting,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MetricsResponse {
+    /// Receive-to-ack latency per stream topic, plus pipeline stage timings
+    pub histograms: Vec<LatencyHistogramDto>,
+    /// Redis topic backend pool usage (absent for the in-memory backend)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_pool: Option<ConnectionPoolDto>,
+}
+
+/// Stream processing latency metrics for this instance
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Processing latency histograms", body = MetricsResponse)
+    )
+)]
+pub async fn metrics(State(topics): State<Arc<TopicService>>) -> Json<MetricsResponse> {
+    let histograms = topics
+        .stream_metrics()
+        .snapshot()
+        .into_iter()
+        .map(|(name, snapshot)| LatencyHistogramDto::from_snapshot(name, snapshot))
+        .collect();
+    Json(MetricsResponse {
+        histograms,
+        topic_pool: topics.pool_stats().map(ConnectionPoolDto::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::storage::AppStorage;
+    use crate::data::duckdb::DuckdbService;
+    use crate::data::sqlite::SqliteService;
+
+    #[test]
+    fn test_readiness_all_healthy_returns_ok() {
+        let (code, Json(body)) = readiness_response(vec![
+            ComponentStatus::healthy("database"),
+            ComponentStatus::healthy("topics"),
+            ComponentStatus::healthy("pricing"),
+        ]);
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body.status, "ready");
+        assert!(body.components.iter().all(|c| c.error.is_none()));
+    }
+
+    #[test]
+    fn test_readiness_unhealthy_dependency_returns_503() {
+        let (code, Json(body)) = readiness_response(vec![
+            ComponentStatus::healthy("database"),
+            ComponentStatus::unhealthy("topics", "redis unreachable"),
+            ComponentStatus::healthy("pricing"),
+        ]);
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.status, "not_ready");
+        let topics = body.components.iter().find(|c| c.name == "topics").unwrap();
+        assert!(!topics.healthy);
+        assert_eq!(topics.error.as_deref(), Some("redis unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_check_component_maps_results() {
+        let ok = check_component("database", async { Ok::<_, String>(()) }).await;
+        assert!(ok.healthy);
+
+        let failed = check_component("database", async { Err("connection refused") }).await;
+        assert!(!failed.healthy);
+        assert_eq!(failed.error.as_deref(), Some("connection refused"));
+    }
+
+    async fn readiness_state(temp_dir: &tempfile::TempDir) -> ReadinessState {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .unwrap();
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        ReadinessState {
+            database: Arc::new(TransactionalService::Sqlite(Arc::new(
+                SqliteService::from_pool(pool),
+            ))),
+            analytics: Arc::new(AnalyticsService::Duckdb(Arc::new(
+                DuckdbService::init(&storage).await.unwrap(),
+            ))),
+            topics: Arc::new(TopicService::new()),
+            pricing: Arc::new(PricingService::init_for_test().unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness_handler_all_dependencies_healthy() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state = readiness_state(&temp_dir).await;
+        let response = readiness(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_handler_closed_analytics_returns_503() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state = readiness_state(&temp_dir).await;
+        state.analytics.close().await.unwrap();
+        let response = readiness(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
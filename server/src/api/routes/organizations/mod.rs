@@ -8,6 +8,7 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::routing::{get, put};
 use axum::{Json, Router};
+use chrono::{Datelike, Utc};
 use serde::Deserialize;
 
 use crate::api::auth::{Auth, OrgAdmin, OrgOwner, OrgRead};
@@ -20,7 +21,7 @@ use crate::data::cache::CacheService;
 use crate::data::cleanup::cleanup_organization;
 use crate::data::files::FileService;
 use crate::data::traits::has_min_role_level;
-use crate::data::types::LastOwnerResult;
+use crate::data::types::{FeatureFlags, LastOwnerResult, OrgCostRollup, ProjectCost};
 
 /// Path parameters for member-specific routes
 #[derive(Deserialize)]
@@ -30,8 +31,10 @@ pub struct MemberPath {
 }
 
 use types::{
-    AddMemberRequest, CreateOrgRequest, ListMembersQuery, ListOrgsQuery, MemberDto, OrgWithRoleDto,
-    OrganizationDto, UpdateMemberRoleRequest, UpdateOrgRequest,
+    AddMemberRequest, CreateOrgRequest, ListMembersQuery, ListOrgsQuery, MemberDto, OrgBudgetDto,
+    OrgWithRoleDto, OrganizationDto, UpdateFeatureFlagsRequest, UpdateMemberRoleRequest,
+    UpdateOrgBudgetRequest, UpdateOrgDefaultProjectRequest, UpdateOrgRequest,
+    UpdateOrgRetentionRequest,
 };
 
 /// Shared state for Organizations API endpoints
@@ -68,6 +71,16 @@ pub fn routes(
             "/{org_id}/members/{user_id}",
             put(update_member_role).delete(remove_org_member),
         )
+        .route(
+            "/{org_id}/feature-flags",
+            get(get_feature_flags).put(update_feature_flags),
+        )
+        .route(
+            "/{org_id}/budget",
+            get(get_org_budget).put(update_org_budget),
+        )
+        .route("/{org_id}/default-project", put(update_org_default_project))
+        .route("/{org_id}/retention", put(update_org_retention))
         .with_state(state)
 }
 
@@ -551,3 +564,270 @@ pub async fn remove_org_member(
         }
     }
 }
+
+/// Get feature flags for an organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{org_id}/feature-flags",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Organization feature flags", body = FeatureFlags),
+        (status = 403, description = "Not a member of this organization")
+    )
+)]
+pub async fn get_feature_flags(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgRead,
+) -> Result<Json<FeatureFlags>, ApiError> {
+    let flags = state
+        .database
+        .repository()
+        .get_org_feature_flags(Some(&state.cache), &auth.org_id)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    Ok(Json(flags))
+}
+
+/// Toggle feature flags for an organization (admin+ required)
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{org_id}/feature-flags",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    request_body = UpdateFeatureFlagsRequest,
+    responses(
+        (status = 200, description = "Updated feature flags", body = FeatureFlags),
+        (status = 403, description = "Insufficient permissions")
+    )
+)]
+pub async fn update_feature_flags(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgAdmin,
+    ValidatedJson(body): ValidatedJson<UpdateFeatureFlagsRequest>,
+) -> Result<Json<FeatureFlags>, ApiError> {
+    let repo = state.database.repository();
+    let updated_by = auth.auth.user_id();
+
+    for (flag, enabled) in body.changes() {
+        repo.set_org_feature_flag(Some(&state.cache), &auth.org_id, flag, enabled, updated_by)
+            .await
+            .map_err(ApiError::from_data)?;
+    }
+
+    let flags = repo
+        .get_org_feature_flags(Some(&state.cache), &auth.org_id)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    Ok(Json(flags))
+}
+
+/// Get organization spend for the current month against its cap
+///
+/// Sums each project's cost over the current calendar month (UTC) with one
+/// grouped analytics query.
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{org_id}/budget",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Organization spend and remaining budget", body = OrgBudgetDto),
+        (status = 403, description = "Not a member of this organization"),
+        (status = 404, description = "Organization not found")
+    )
+)]
+pub async fn get_org_budget(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgRead,
+) -> Result<Json<OrgBudgetDto>, ApiError> {
+    let repo = state.database.repository();
+    let org = repo
+        .get_organization(None, &auth.org_id)
+        .await
+        .map_err(ApiError::from_data)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ORG_NOT_FOUND",
+                format!("Organization not found: {}", auth.org_id),
+            )
+        })?;
+    let project_ids = repo
+        .list_project_ids(&auth.org_id)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    let period_end = Utc::now();
+    let period_start = period_end
+        .date_naive()
+        .with_day(1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+        .unwrap_or(period_end);
+
+    let mut costs = state
+        .analytics
+        .repository()
+        .get_project_costs(&project_ids, period_start, period_end)
+        .await
+        .map_err(ApiError::from_data)?;
+    let projects = project_ids
+        .into_iter()
+        .map(|project_id| ProjectCost {
+            cost: costs.remove(&project_id).unwrap_or(0.0),
+            project_id,
+        })
+        .collect();
+
+    let rollup = OrgCostRollup::new(org.monthly_limit_usd, projects);
+    Ok(Json(OrgBudgetDto::new(rollup, period_start, period_end)))
+}
+
+/// Set or clear the organization's monthly spend cap (admin+ required)
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{org_id}/budget",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    request_body = UpdateOrgBudgetRequest,
+    responses(
+        (status = 200, description = "Organization updated", body = OrganizationDto),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Organization not found")
+    )
+)]
+pub async fn update_org_budget(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgAdmin,
+    ValidatedJson(body): ValidatedJson<UpdateOrgBudgetRequest>,
+) -> Result<Json<OrganizationDto>, ApiError> {
+    let org = state
+        .database
+        .repository()
+        .set_org_monthly_limit(Some(&state.cache), &auth.org_id, body.monthly_limit_usd)
+        .await
+        .map_err(ApiError::from_data)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ORG_NOT_FOUND",
+                format!("Organization not found: {}", auth.org_id),
+            )
+        })?;
+
+    Ok(Json(OrganizationDto::from(org)))
+}
+
+/// Set or clear the project that receives unscoped traces (admin+ required)
+///
+/// Traces sent without a project id carry no organization, so ingestion routes
+/// them using the default organization's setting; when unset they go to the
+/// built-in default project.
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{org_id}/default-project",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    request_body = UpdateOrgDefaultProjectRequest,
+    responses(
+        (status = 200, description = "Organization updated", body = OrganizationDto),
+        (status = 400, description = "Project does not belong to this organization"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Organization not found")
+    )
+)]
+pub async fn update_org_default_project(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgAdmin,
+    ValidatedJson(body): ValidatedJson<UpdateOrgDefaultProjectRequest>,
+) -> Result<Json<OrganizationDto>, ApiError> {
+    let repo = state.database.repository();
+    if let Some(project_id) = &body.project_id {
+        let in_org = repo
+            .get_project(None, project_id)
+            .await
+            .map_err(ApiError::from_data)?
+            .is_some_and(|p| p.organization_id == auth.org_id);
+        if !in_org {
+            return Err(ApiError::bad_request(
+                "INVALID_PROJECT",
+                format!(
+                    "Project {} does not belong to this organization",
+                    project_id
+                ),
+            ));
+        }
+    }
+
+    let org = repo
+        .set_org_default_project(Some(&state.cache), &auth.org_id, body.project_id.as_deref())
+        .await
+        .map_err(ApiError::from_data)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ORG_NOT_FOUND",
+                format!("Organization not found: {}", auth.org_id),
+            )
+        })?;
+
+    Ok(Json(OrganizationDto::from(org)))
+}
+
+/// Set or clear the organization's span retention in days (admin+ required)
+///
+/// Expiry is stamped on spans at ingestion, so a change applies to spans
+/// ingested afterwards; existing spans keep their expiry. Only the DuckDB
+/// analytics backend stores span expiry.
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{org_id}/retention",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID")
+    ),
+    request_body = UpdateOrgRetentionRequest,
+    responses(
+        (status = 200, description = "Organization updated", body = OrganizationDto),
+        (status = 400, description = "The analytics backend does not support span expiry"),
+        (status = 403, description = "Insufficient permissions"),
+        (status = 404, description = "Organization not found")
+    )
+)]
+pub async fn update_org_retention(
+    State(state): State<OrganizationsApiState>,
+    auth: OrgAdmin,
+    ValidatedJson(body): ValidatedJson<UpdateOrgRetentionRequest>,
+) -> Result<Json<OrganizationDto>, ApiError> {
+    if body.retention_days.is_some() && !state.analytics.supports_span_expiry() {
+        return Err(ApiError::bad_request(
+            "RETENTION_UNSUPPORTED",
+            "Per-organization retention requires the DuckDB analytics backend",
+        ));
+    }
+
+    let org = state
+        .database
+        .repository()
+        .set_org_retention_days(Some(&state.cache), &auth.org_id, body.retention_days)
+        .await
+        .map_err(ApiError::from_data)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ORG_NOT_FOUND",
+                format!("Organization not found: {}", auth.org_id),
+            )
+        })?;
+
+    Ok(Json(OrganizationDto::from(org)))
+}
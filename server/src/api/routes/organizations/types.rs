@@ -10,7 +10,9 @@ use crate::core::constants::{
     ORG_ROLE_ADMIN, ORG_ROLE_MEMBER, ORG_ROLE_OWNER, ORG_ROLE_VIEWER, ORG_SLUG_MAX_LEN,
     ORG_SLUG_MIN_LEN,
 };
-use crate::data::types::{MemberWithUser, OrgWithRole, OrganizationRow};
+use crate::data::types::{
+    FeatureFlag, MemberWithUser, OrgCostRollup, OrgWithRole, OrganizationRow, ProjectCost,
+};
 
 /// Organization DTO for API responses
 #[derive(Debug, Serialize, ToSchema)]
@@ -20,6 +22,14 @@ pub struct OrganizationDto {
     pub slug: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_limit_usd: Option<f64>,
+    /// Project receiving traces sent without a project id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_project_id: Option<String>,
+    /// Days newly ingested spans are kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<i64>,
 }
 
 impl From<OrganizationRow> for OrganizationDto {
@@ -30,6 +40,9 @@ impl From<OrganizationRow> for OrganizationDto {
             slug: row.slug,
             created_at: DateTime::from_timestamp(row.created_at, 0).unwrap_or_else(Utc::now),
             updated_at: DateTime::from_timestamp(row.updated_at, 0).unwrap_or_else(Utc::now),
+            monthly_limit_usd: row.monthly_limit_usd,
+            default_project_id: row.default_project_id,
+            retention_days: row.retention_days,
         }
     }
 }
@@ -97,6 +110,74 @@ pub struct UpdateOrgRequest {
     pub name: String,
 }
 
+/// Request body for setting the organization's monthly spend cap (null clears it)
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateOrgBudgetRequest {
+    #[validate(range(min = 0.0, message = "Monthly limit must not be negative"))]
+    pub monthly_limit_usd: Option<f64>,
+}
+
+/// Request body for setting the organization's default project (null clears it)
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateOrgDefaultProjectRequest {
+    pub project_id: Option<String>,
+}
+
+/// Request body for setting the organization's span retention (null clears it)
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateOrgRetentionRequest {
+    #[validate(range(min = 1, max = 36500, message = "Retention must be 1-36500 days"))]
+    pub retention_days: Option<i64>,
+}
+
+/// Spend of one project in the organization budget rollup
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectSpendDto {
+    pub project_id: String,
+    pub spent_usd: f64,
+}
+
+impl From<ProjectCost> for ProjectSpendDto {
+    fn from(cost: ProjectCost) -> Self {
+        Self {
+            project_id: cost.project_id,
+            spent_usd: cost.cost,
+        }
+    }
+}
+
+/// Organization spend for the current calendar month (UTC) against its cap
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrgBudgetDto {
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub monthly_limit_usd: Option<f64>,
+    pub spent_usd: f64,
+    /// Budget left under the cap (never negative; null without a cap)
+    pub remaining_usd: Option<f64>,
+    pub exceeded: bool,
+    /// Per-project spend, highest first
+    pub projects: Vec<ProjectSpendDto>,
+}
+
+impl OrgBudgetDto {
+    pub fn new(
+        rollup: OrgCostRollup,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            period_start,
+            period_end,
+            monthly_limit_usd: rollup.monthly_limit_usd,
+            spent_usd: rollup.total_cost,
+            remaining_usd: rollup.remaining_usd,
+            exceeded: rollup.exceeded,
+            projects: rollup.projects.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 /// Request body for adding/updating a member
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct AddMemberRequest {
@@ -114,6 +195,22 @@ pub struct UpdateMemberRoleRequest {
     pub role: String,
 }
 
+/// Request body for toggling organization feature flags (omitted flags are unchanged)
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateFeatureFlagsRequest {
+    pub anonymize_content: Option<bool>,
+}
+
+impl UpdateFeatureFlagsRequest {
+    /// Flags present in the request with their new values
+    pub fn changes(&self) -> Vec<(FeatureFlag, bool)> {
+        [(FeatureFlag::AnonymizeContent, self.anonymize_content)]
+            .into_iter()
+            .filter_map(|(flag, enabled)| enabled.map(|enabled| (flag, enabled)))
+            .collect()
+    }
+}
+
 /// Query params for listing organizations
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ListOrgsQuery {
@@ -11,11 +11,11 @@ use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-use super::OtelApiState;
 use super::types::{
     BlockDto, FeedMessagesMetadata, FeedMessagesResponse, FeedPagination, FeedSpansResponse,
     SpanSummaryDto,
 };
+use super::{OtelApiState, run_feed};
 use crate::api::auth::ProjectRead;
 use crate::api::types::{ApiError, parse_timestamp_param};
 use crate::data::types::{FeedMessagesParams, FeedSpansParams};
@@ -44,6 +44,21 @@ pub struct FeedMessagesQuery {
     pub end_time: Option<String>,
     /// Filter by message role (user, assistant, tool, system)
     pub role: Option<String>,
+    /// Replace message content with placeholder text (for screenshots/demos)
+    pub anonymize: Option<bool>,
+    /// Hide system prompts (still used for history detection)
+    pub exclude_system: Option<bool>,
+    /// Show repeated system prompts once, then as a marker
+    pub collapse_system: Option<bool>,
+    pub fold_tool_case: Option<bool>,
+    /// Annotate blocks with milliseconds since the previous block
+    pub gaps: Option<bool>,
+    /// Show tokens/cost only on each turn's output block
+    pub turn_metrics: Option<bool>,
+    /// Include block content hashes for client-side diffing
+    pub hashes: Option<bool>,
+    /// Group consecutive same-role blocks of each span batch into turns
+    pub turns: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,7 +131,15 @@ fn validate_limit(limit: Option<u32>) -> u32 {
         ("cursor" = Option<String>, Query, description = "Pagination cursor"),
         ("start_time" = Option<String>, Query, description = "Filter by event time >= (ISO 8601)"),
         ("end_time" = Option<String>, Query, description = "Filter by event time < (ISO 8601)"),
-        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, tool, system)")
+        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, tool, system)"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text"),
+        ("exclude_system" = Option<bool>, Query, description = "Hide system prompts (still used for history detection)"),
+        ("collapse_system" = Option<bool>, Query, description = "Show repeated system prompts once, then as a marker"),
+        ("fold_tool_case" = Option<bool>, Query, description = "Merge tool definitions whose names differ only by case or separators"),
+        ("gaps" = Option<bool>, Query, description = "Annotate each block with milliseconds since the previous block"),
+        ("hashes" = Option<bool>, Query, description = "Include each block's content hash for client-side diffing and caching"),
+        ("turns" = Option<bool>, Query, description = "Group consecutive same-role blocks of each span batch into turns"),
+        ("turn_metrics" = Option<bool>, Query, description = "Show tokens/cost only on the output block of each turn")
     ),
     responses(
         (status = 200, description = "Feed messages", body = FeedMessagesResponse)
@@ -169,10 +192,32 @@ pub async fn get_feed_messages(
 
     // Process spans through feed pipeline (handles grouping, dedup, sorting)
     // History filtering is automatic (duplicates are detected and filtered)
-    let options = FeedOptions::new().with_role(query.role.clone());
-
-    let processed = process_feed(spans, &options);
+    let flags = state.feature_flags(&auth.org_id).await?;
+    let options = FeedOptions::new()
+        .with_role(query.role.clone())
+        .with_anonymize(query.anonymize.unwrap_or(false) || flags.anonymize_content)
+        .with_exclude_system(query.exclude_system.unwrap_or(false))
+        .with_collapse_repeated_system(query.collapse_system.unwrap_or(false))
+        .with_fold_tool_name_case(query.fold_tool_case.unwrap_or(false))
+        .with_include_gaps(query.gaps.unwrap_or(false))
+        .with_inline_turn_metrics(query.turn_metrics.unwrap_or(false))
+        .with_include_hashes(query.hashes.unwrap_or(false))
+        .with_group_turns(query.turns.unwrap_or(false))
+        .with_default_roles(state.feed.default_roles.clone())
+        .with_dedup_by_environment(state.feed.dedup_by_environment)
+        .with_event_names(state.feed.event_names.clone())
+        .with_max_tool_result_bytes(state.feed.tool_result_max_bytes)
+        .with_max_session_traces(state.feed.max_session_traces)
+        .with_trace_parallelism(state.feed.trace_parallelism)
+        .with_max_blocks(state.feed.max_blocks)
+        .with_max_prefix_entries(state.feed.max_prefix_entries)
+        .with_clock_skew_tolerance(state.feed.clock_skew_tolerance);
+
+    let processed = run_feed(move || process_feed(spans, &options)).await?;
     let all_messages = processed.messages;
+    let warnings = processed.metadata.warnings;
+    let truncated = processed.metadata.truncated;
+    let turns = processed.turns;
     let tool_definitions = processed.tool_definitions;
     let tool_names = processed.tool_names;
 
@@ -181,11 +226,11 @@ pub async fn get_feed_messages(
     let mut total_tokens = 0i64;
     let mut total_cost = 0.0f64;
 
+    // Span totals are attributed to a single block per span, so a plain sum is exact
     for block in &all_messages {
-        if seen_spans.insert(&block.span_id) {
-            total_tokens += block.tokens.unwrap_or(0);
-            total_cost += block.cost.unwrap_or(0.0);
-        }
+        seen_spans.insert(&block.span_id);
+        total_tokens += block.tokens.unwrap_or(0);
+        total_cost += block.cost.unwrap_or(0.0);
     }
 
     let metadata = FeedMessagesMetadata {
@@ -193,6 +238,8 @@ pub async fn get_feed_messages(
         span_count: seen_spans.len() as u32,
         total_tokens,
         total_cost,
+        warnings,
+        truncated,
     };
 
     // Build response
@@ -203,6 +250,7 @@ pub async fn get_feed_messages(
 
     Ok(Json(FeedMessagesResponse {
         data,
+        turns,
         pagination: FeedPagination {
             next_cursor,
             has_more,
@@ -285,7 +333,10 @@ pub async fn get_feed_spans(
     // Convert to DTOs
     let data: Vec<SpanSummaryDto> = spans
         .iter()
-        .map(|s| SpanSummaryDto::from_row(s, 0, 0, include_raw_span))
+        .map(|s| {
+            SpanSummaryDto::from_row(s, 0, 0, include_raw_span)
+                .with_custom_attributes(s, &state.promoted_attributes)
+        })
         .collect();
 
     Ok(Json(FeedSpansResponse {
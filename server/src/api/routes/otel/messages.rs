@@ -1,19 +1,22 @@
 //! Messages API endpoints for conversation history
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Path, State};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-use super::OtelApiState;
 use super::types::{BlockDto, MessagesMetadataDto, MessagesResponseDto};
-use crate::api::auth::{SessionRead, SpanRead, TraceRead};
+use super::{OtelApiState, run_feed};
+use crate::api::auth::{ProjectRead, SessionRead, SpanRead, TraceRead};
+use crate::api::extractors::is_valid_id;
 use crate::api::types::{ApiError, parse_timestamp_param};
-use crate::data::types::MessageQueryParams;
+use crate::data::AnalyticsRepository;
+use crate::data::types::{FeatureFlags, MessageQueryParams, MessageSpanRow};
 use crate::domain::sideml::{
-    ExtractedTools, FeedOptions, FeedResult, extract_tools_from_rows, process_spans,
+    ContentSizeStats, ExtractedTools, FeedOptions, FeedResult, extract_tools_from_rows,
+    process_prompt, process_spans, retain_errors_with_context,
 };
 
 #[derive(Debug, Deserialize)]
@@ -21,11 +24,45 @@ pub struct MessagesQuery {
     pub from_timestamp: Option<String>,
     pub to_timestamp: Option<String>,
     pub role: Option<String>,
+    pub anonymize: Option<bool>,
+    pub keep_tool_input_json: Option<bool>,
+    pub collapse_system: Option<bool>,
+    pub fold_tool_case: Option<bool>,
+    pub gaps: Option<bool>,
+    pub turn_metrics: Option<bool>,
+    pub hashes: Option<bool>,
+    pub turns: Option<bool>,
+}
+
+/// Path parameters for the span prompt endpoint
+/// Note: project_id is also extracted by ProjectRead for auth, but Axum's Path
+/// extractor requires ALL path params to be captured in the struct.
+#[derive(Debug, Deserialize)]
+pub struct SpanPromptParams {
+    #[allow(dead_code)] // Auth handled by ProjectRead extractor
+    pub project_id: String,
+    pub span_id: String,
 }
 
 impl MessagesQuery {
-    fn to_feed_options(&self) -> FeedOptions {
-        FeedOptions::new().with_role(self.role.clone())
+    fn to_feed_options(&self, state: &OtelApiState, flags: &FeatureFlags) -> FeedOptions {
+        FeedOptions::new()
+            .with_role(self.role.clone())
+            .with_anonymize(self.anonymize.unwrap_or(false) || flags.anonymize_content)
+            .with_keep_tool_input_json(self.keep_tool_input_json.unwrap_or(false))
+            .with_collapse_repeated_system(self.collapse_system.unwrap_or(false))
+            .with_fold_tool_name_case(self.fold_tool_case.unwrap_or(false))
+            .with_include_gaps(self.gaps.unwrap_or(false))
+            .with_inline_turn_metrics(self.turn_metrics.unwrap_or(false))
+            .with_include_hashes(self.hashes.unwrap_or(false))
+            .with_group_turns(self.turns.unwrap_or(false))
+            .with_default_roles(state.feed.default_roles.clone())
+            .with_dedup_by_environment(state.feed.dedup_by_environment)
+            .with_event_names(state.feed.event_names.clone())
+            .with_trace_parallelism(state.feed.trace_parallelism)
+            .with_max_blocks(state.feed.max_blocks)
+            .with_max_prefix_entries(state.feed.max_prefix_entries)
+            .with_clock_skew_tolerance(state.feed.clock_skew_tolerance)
     }
 }
 
@@ -40,7 +77,15 @@ impl MessagesQuery {
         ("span_id" = String, Path, description = "Span ID"),
         ("from_timestamp" = Option<String>, Query, description = "Filter from timestamp (ISO 8601)"),
         ("to_timestamp" = Option<String>, Query, description = "Filter to timestamp (ISO 8601)"),
-        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)")
+        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text"),
+        ("keep_tool_input_json" = Option<bool>, Query, description = "Include tool argument JSON from tool spans"),
+        ("collapse_system" = Option<bool>, Query, description = "Show repeated system prompts once, then as a marker"),
+        ("fold_tool_case" = Option<bool>, Query, description = "Merge tool definitions whose names differ only by case or separators"),
+        ("gaps" = Option<bool>, Query, description = "Annotate each block with milliseconds since the previous block"),
+        ("hashes" = Option<bool>, Query, description = "Include each block's content hash for client-side diffing and caching"),
+        ("turns" = Option<bool>, Query, description = "Group consecutive same-role blocks of each span batch into turns"),
+        ("turn_metrics" = Option<bool>, Query, description = "Show tokens/cost only on the output block of each turn")
     ),
     responses(
         (status = 200, description = "Messages for the span", body = MessagesResponseDto)
@@ -57,7 +102,8 @@ pub async fn get_span_messages(
     let from_timestamp = parse_timestamp_param(&query.from_timestamp)?;
     let to_timestamp = parse_timestamp_param(&query.to_timestamp)?;
 
-    let options = query.to_feed_options();
+    let flags = state.feature_flags(&auth.org_id).await?;
+    let options = query.to_feed_options(&state, &flags);
 
     // Fetch raw span rows
     let repo = state.analytics.repository();
@@ -73,8 +119,106 @@ pub async fn get_span_messages(
         .await
         .map_err(ApiError::from_data)?;
 
+    let stored_parents = stored_parent_links(repo.as_ref(), project_id, &result.rows).await?;
+    let options = options.with_stored_parents(stored_parents);
+
     // Process through feed pipeline
-    let processed = process_spans(result.rows, &options);
+    let processed = run_feed(move || process_spans(result.rows, &options)).await?;
+
+    let response = build_messages_response(processed, None);
+    Ok(Json(response))
+}
+
+/// Load persisted parent links when a row's parent is not among `rows`.
+///
+/// Span-scoped queries return only the requested span, so its ancestors live in
+/// storage. Returns an empty map (no extra query) when every parent is present.
+async fn stored_parent_links(
+    repo: &(dyn AnalyticsRepository + Send + Sync),
+    project_id: &str,
+    rows: &[MessageSpanRow],
+) -> Result<HashMap<String, String>, ApiError> {
+    let in_batch: HashSet<&str> = rows.iter().map(|r| r.span_id.as_str()).collect();
+    let trace_ids: HashSet<&str> = rows
+        .iter()
+        .filter(|r| {
+            r.parent_span_id
+                .as_deref()
+                .is_some_and(|p| !in_batch.contains(p))
+        })
+        .map(|r| r.trace_id.as_str())
+        .collect();
+
+    let mut links = HashMap::new();
+    for trace_id in trace_ids {
+        let trace_links = repo
+            .get_parent_links_for_trace(project_id, trace_id)
+            .await
+            .map_err(ApiError::from_data)?;
+        links.extend(trace_links);
+    }
+    Ok(links)
+}
+
+/// GET /spans/{span_id}/prompt - Get the exact input sent to a span
+///
+/// Unlike the messages endpoints, history is not stripped: the response is the
+/// span's full input in the order it was recorded, without its output.
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/spans/{span_id}/prompt",
+    tag = "spans",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("span_id" = String, Path, description = "Span ID"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text")
+    ),
+    responses(
+        (status = 200, description = "Input messages sent by the span", body = MessagesResponseDto),
+        (status = 404, description = "Span not found or has no messages")
+    )
+)]
+pub async fn get_span_prompt(
+    State(state): State<OtelApiState>,
+    auth: ProjectRead,
+    Path(path): Path<SpanPromptParams>,
+    axum::extract::Query(query): axum::extract::Query<MessagesQuery>,
+) -> Result<Json<MessagesResponseDto>, ApiError> {
+    let project_id = &auth.project_id;
+    let span_id = &path.span_id;
+    if !is_valid_id(span_id) {
+        return Err(ApiError::bad_request("INVALID_SPAN_ID", "Invalid span ID"));
+    }
+
+    let flags = state.feature_flags(&auth.org_id).await?;
+    let options = FeedOptions::new()
+        .with_anonymize(query.anonymize.unwrap_or(false) || flags.anonymize_content)
+        .with_default_roles(state.feed.default_roles.clone())
+        .with_dedup_by_environment(state.feed.dedup_by_environment)
+        .with_event_names(state.feed.event_names.clone())
+        .with_clock_skew_tolerance(state.feed.clock_skew_tolerance);
+
+    let repo = state.analytics.repository();
+    let params = MessageQueryParams {
+        project_id: project_id.to_string(),
+        span_id: Some(span_id.to_string()),
+        ..Default::default()
+    };
+    let result = repo
+        .get_messages(&params)
+        .await
+        .map_err(ApiError::from_data)?;
+    if result.rows.is_empty() {
+        return Err(ApiError::not_found(
+            "SPAN_NOT_FOUND",
+            format!("Span not found: {}", span_id),
+        ));
+    }
+
+    let stored_parents = stored_parent_links(repo.as_ref(), project_id, &result.rows).await?;
+    let options = options.with_stored_parents(stored_parents);
+
+    let processed = process_prompt(result.rows, &options);
 
     let response = build_messages_response(processed, None);
     Ok(Json(response))
@@ -90,7 +234,15 @@ pub async fn get_span_messages(
         ("trace_id" = String, Path, description = "Trace ID"),
         ("from_timestamp" = Option<String>, Query, description = "Filter from timestamp (ISO 8601)"),
         ("to_timestamp" = Option<String>, Query, description = "Filter to timestamp (ISO 8601)"),
-        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)")
+        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text"),
+        ("keep_tool_input_json" = Option<bool>, Query, description = "Include tool argument JSON from tool spans"),
+        ("collapse_system" = Option<bool>, Query, description = "Show repeated system prompts once, then as a marker"),
+        ("fold_tool_case" = Option<bool>, Query, description = "Merge tool definitions whose names differ only by case or separators"),
+        ("gaps" = Option<bool>, Query, description = "Annotate each block with milliseconds since the previous block"),
+        ("hashes" = Option<bool>, Query, description = "Include each block's content hash for client-side diffing and caching"),
+        ("turns" = Option<bool>, Query, description = "Group consecutive same-role blocks of each span batch into turns"),
+        ("turn_metrics" = Option<bool>, Query, description = "Show tokens/cost only on the output block of each turn")
     ),
     responses(
         (status = 200, description = "Messages for the trace", body = MessagesResponseDto)
@@ -101,6 +253,54 @@ pub async fn get_trace_messages(
     auth: TraceRead,
     axum::extract::Query(query): axum::extract::Query<MessagesQuery>,
 ) -> Result<Json<MessagesResponseDto>, ApiError> {
+    let (processed, trace_totals) = load_trace_feed(&state, &auth, &query, true).await?;
+    let response = build_messages_response(processed, trace_totals);
+    Ok(Json(response))
+}
+
+/// GET /traces/{trace_id}/errors - Get a trace's error blocks with their preceding turn
+///
+/// Runs the same pipeline as the trace messages endpoint, then keeps only the
+/// exception blocks of error spans, each with the assistant or tool block
+/// right before it for context.
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/traces/{trace_id}/errors",
+    tag = "traces",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("trace_id" = String, Path, description = "Trace ID"),
+        ("from_timestamp" = Option<String>, Query, description = "Filter from timestamp (ISO 8601)"),
+        ("to_timestamp" = Option<String>, Query, description = "Filter to timestamp (ISO 8601)"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text"),
+        ("keep_tool_input_json" = Option<bool>, Query, description = "Include tool argument JSON from tool spans"),
+        ("hashes" = Option<bool>, Query, description = "Include each block's content hash for client-side diffing and caching")
+    ),
+    responses(
+        (status = 200, description = "Error blocks for the trace with their context", body = MessagesResponseDto)
+    )
+)]
+pub async fn get_trace_errors(
+    State(state): State<OtelApiState>,
+    auth: TraceRead,
+    axum::extract::Query(query): axum::extract::Query<MessagesQuery>,
+) -> Result<Json<MessagesResponseDto>, ApiError> {
+    // Uncapped: errors past `feed_max_blocks` must still be listed
+    let (mut processed, _) = load_trace_feed(&state, &auth, &query, false).await?;
+    retain_errors_with_context(&mut processed);
+    let response = build_messages_response(processed, None);
+    Ok(Json(response))
+}
+
+/// Load and process a trace's feed, returning it with the trace's token/cost totals.
+///
+/// `cap_blocks` applies the configured `feed_max_blocks` limit.
+async fn load_trace_feed(
+    state: &OtelApiState,
+    auth: &TraceRead,
+    query: &MessagesQuery,
+    cap_blocks: bool,
+) -> Result<(FeedResult, Option<(i64, f64)>), ApiError> {
     let project_id = &auth.project_id;
     let trace_id = &auth.trace_id;
 
@@ -108,7 +308,11 @@ pub async fn get_trace_messages(
     let to_timestamp = parse_timestamp_param(&query.to_timestamp)?;
 
     // History filtering is automatic (duplicates are detected and filtered)
-    let options = query.to_feed_options();
+    let flags = state.feature_flags(&auth.org_id).await?;
+    let mut options = query.to_feed_options(state, &flags);
+    if !cap_blocks {
+        options = options.with_max_blocks(None);
+    }
 
     // Fetch trace metadata for session_id and totals
     let repo = state.analytics.repository();
@@ -153,13 +357,14 @@ pub async fn get_trace_messages(
     let scoped_tools = if session_id.is_some() {
         Some(extract_tools_from_rows(
             result.rows.iter().filter(|r| r.trace_id == *trace_id),
+            options.fold_tool_name_case,
         ))
     } else {
         None
     };
 
     // Process through feed pipeline (auto-routes to multi-trace if needed)
-    let mut processed = process_spans(result.rows, &options);
+    let mut processed = run_feed(move || process_spans(result.rows, &options)).await?;
 
     // If session-loaded, retain only the target trace's blocks and apply scoped tools.
     // scoped_tools is Some iff session_id.is_some(), so use it as the single guard.
@@ -169,8 +374,7 @@ pub async fn get_trace_messages(
 
     // Use trace-level totals for metadata (matches trace endpoint)
     let trace_totals = trace.map(|t| (t.total_tokens, t.total_cost));
-    let response = build_messages_response(processed, trace_totals);
-    Ok(Json(response))
+    Ok((processed, trace_totals))
 }
 
 /// GET /sessions/{session_id}/messages - Get conversation messages for a session
@@ -183,7 +387,15 @@ pub async fn get_trace_messages(
         ("session_id" = String, Path, description = "Session ID"),
         ("from_timestamp" = Option<String>, Query, description = "Filter from timestamp (ISO 8601)"),
         ("to_timestamp" = Option<String>, Query, description = "Filter to timestamp (ISO 8601)"),
-        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)")
+        ("role" = Option<String>, Query, description = "Filter by role (user, assistant, etc.)"),
+        ("anonymize" = Option<bool>, Query, description = "Replace message content with placeholder text"),
+        ("keep_tool_input_json" = Option<bool>, Query, description = "Include tool argument JSON from tool spans"),
+        ("collapse_system" = Option<bool>, Query, description = "Show repeated system prompts once, then as a marker"),
+        ("fold_tool_case" = Option<bool>, Query, description = "Merge tool definitions whose names differ only by case or separators"),
+        ("gaps" = Option<bool>, Query, description = "Annotate each block with milliseconds since the previous block"),
+        ("hashes" = Option<bool>, Query, description = "Include each block's content hash for client-side diffing and caching"),
+        ("turns" = Option<bool>, Query, description = "Group consecutive same-role blocks of each span batch into turns"),
+        ("turn_metrics" = Option<bool>, Query, description = "Show tokens/cost only on the output block of each turn")
     ),
     responses(
         (status = 200, description = "Messages for the session", body = MessagesResponseDto)
@@ -201,7 +413,8 @@ pub async fn get_session_messages(
     let to_timestamp = parse_timestamp_param(&query.to_timestamp)?;
 
     // History filtering is automatic (duplicates are detected and filtered)
-    let options = query.to_feed_options();
+    let flags = state.feature_flags(&auth.org_id).await?;
+    let options = query.to_feed_options(&state, &flags);
 
     // Fetch raw span rows
     let repo = state.analytics.repository();
@@ -218,7 +431,7 @@ pub async fn get_session_messages(
         .map_err(ApiError::from_data)?;
 
     // Process through feed pipeline
-    let processed = process_spans(result.rows, &options);
+    let processed = run_feed(move || process_spans(result.rows, &options)).await?;
 
     let response = build_messages_response(processed, None);
     Ok(Json(response))
@@ -231,7 +444,13 @@ pub(crate) fn scope_feed_to_trace(
     trace_id: &str,
 ) {
     processed.messages.retain(|b| b.trace_id == trace_id);
+    processed.regroup_turns();
     processed.metadata.block_count = processed.messages.len();
+    processed.metadata.content_sizes = ContentSizeStats::from_blocks(&processed.messages);
+    processed
+        .metadata
+        .warnings
+        .retain(|w| w.trace_id == trace_id);
     processed.metadata.span_count = processed
         .messages
         .iter()
@@ -254,14 +473,12 @@ pub(crate) fn build_messages_response(
     let mut start_time: Option<DateTime<Utc>> = None;
     let mut end_time: Option<DateTime<Utc>> = None;
 
-    // Track seen span_ids to avoid counting tokens multiple times per span
-    let mut seen_spans: HashSet<String> = HashSet::new();
     let mut aggregated_tokens = 0i64;
     let mut aggregated_cost = 0.0f64;
 
     for block in &processed.messages {
-        // Aggregate tokens/cost from spans (only if not using trace totals)
-        if trace_totals.is_none() && seen_spans.insert(block.span_id.clone()) {
+        // Span totals are attributed to a single block per span, so a plain sum is exact
+        if trace_totals.is_none() {
             aggregated_tokens += block.tokens.unwrap_or(0);
             aggregated_cost += block.cost.unwrap_or(0.0);
         }
@@ -281,12 +498,16 @@ pub(crate) fn build_messages_response(
 
     MessagesResponseDto {
         messages: messages_dto,
+        turns: processed.turns,
         metadata: MessagesMetadataDto {
             total_messages,
             total_tokens,
             total_cost,
             start_time: start_time.unwrap_or_else(Utc::now),
             end_time,
+            content_sizes: processed.metadata.content_sizes,
+            warnings: processed.metadata.warnings,
+            truncated: processed.metadata.truncated,
         },
         tool_definitions: processed.tool_definitions,
         tool_names: processed.tool_names,
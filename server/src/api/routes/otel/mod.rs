@@ -17,10 +17,14 @@ use axum::Router;
 use axum::routing::get;
 use tokio::sync::watch;
 
+use crate::api::types::ApiError;
 use crate::core::TopicService;
+use crate::core::config::{FeedConfig, IngestionConfig, PromotedAttributes, SamplingConfig};
 use crate::data::cache::CacheService;
 use crate::data::files::FileService;
+use crate::data::types::FeatureFlags;
 use crate::data::{AnalyticsService, TransactionalService};
+use crate::domain::pricing::PricingService;
 
 /// Shared state for OTEL API endpoints
 #[derive(Clone)]
@@ -31,26 +35,46 @@ pub struct OtelApiState {
     pub database: Arc<TransactionalService>,
     pub cache: Arc<CacheService>,
     pub shutdown_rx: watch::Receiver<bool>,
+    /// Upstream sampling rates for cost extrapolation
+    pub sampling: SamplingConfig,
+    /// Span attributes surfaced as `custom_attributes` on span DTOs
+    pub promoted_attributes: PromotedAttributes,
+    /// Conversation feed settings
+    pub feed: FeedConfig,
+    /// Pricing data for on-demand span cost breakdowns
+    pub pricing: Arc<PricingService>,
+    /// Ingestion settings; their redaction is re-applied to raw span events
+    pub ingestion: IngestionConfig,
 }
 
-/// Build OTEL API routes
-pub fn routes(
-    analytics: Arc<AnalyticsService>,
-    topics: Arc<TopicService>,
-    file_service: Arc<FileService>,
-    database: Arc<TransactionalService>,
-    cache: Arc<CacheService>,
-    shutdown_rx: watch::Receiver<bool>,
-) -> Router<()> {
-    let state = OtelApiState {
-        analytics,
-        topics,
-        file_service,
-        database,
-        cache,
-        shutdown_rx,
-    };
+impl OtelApiState {
+    /// Resolve feature flags for the organization that owns the requested project
+    pub async fn feature_flags(&self, org_id: &str) -> Result<FeatureFlags, ApiError> {
+        self.database
+            .repository()
+            .get_org_feature_flags(Some(&self.cache), org_id)
+            .await
+            .map_err(ApiError::from_data)
+    }
+}
+
+/// Run CPU-bound feed processing on the blocking thread pool.
+///
+/// Building a feed can also fan out to `feed_trace_parallelism` threads, which
+/// must not tie up an async runtime worker.
+pub(crate) async fn run_feed<T, F>(f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        tracing::error!(error = %e, "Feed processing task failed");
+        ApiError::internal("Feed processing failed")
+    })
+}
 
+/// Build OTEL API routes
+pub fn routes(state: OtelApiState) -> Router<()> {
     Router::new()
         // Traces
         .route(
@@ -66,6 +90,7 @@ pub fn routes(
             "/traces/{trace_id}/messages",
             get(messages::get_trace_messages),
         )
+        .route("/traces/{trace_id}/errors", get(messages::get_trace_errors))
         // Spans (nested under traces)
         .route("/traces/{trace_id}/spans", get(spans::list_trace_spans))
         .route("/traces/{trace_id}/spans/{span_id}", get(spans::get_span))
@@ -73,9 +98,15 @@ pub fn routes(
             "/traces/{trace_id}/spans/{span_id}/messages",
             get(messages::get_span_messages),
         )
+        .route(
+            "/traces/{trace_id}/spans/{span_id}/events",
+            get(spans::get_span_events),
+        )
         // Spans (top-level for cross-trace queries)
         .route("/spans", get(spans::list_spans).delete(spans::delete_spans))
         .route("/spans/filter-options", get(spans::get_span_filter_options))
+        .route("/spans/{span_id}/prompt", get(messages::get_span_prompt))
+        .route("/spans/{span_id}/cost", get(spans::get_span_cost))
         // Sessions
         .route(
             "/sessions",
@@ -94,6 +125,8 @@ pub fn routes(
         .route("/sse", get(sse::sse))
         // Stats
         .route("/stats", get(stats::get_project_stats))
+        .route("/timeseries", get(stats::get_project_timeseries))
+        .route("/tools/stats", get(stats::get_tool_stats))
         // Feed (project-wide message/span activity)
         .route("/feed/messages", get(feed::get_feed_messages))
         .route("/feed/spans", get(feed::get_feed_spans))
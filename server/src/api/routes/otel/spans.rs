@@ -1,26 +1,30 @@
 //! Span API endpoints
 
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
 use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use serde::Deserialize;
+use serde_json::json;
 use utoipa::ToSchema;
 use validator::Validate;
 
 use super::OtelApiState;
-use super::filters::{columns, parse_filters};
+use super::filters::{Filter, StringOp, columns, parse_filters};
 use super::traces::{FilterOptionDto, FilterOptionsResponse};
-use super::types::{SpanDetailDto, SpanSummaryDto, StringOrArray};
+use super::types::{SpanCostDto, SpanDetailDto, SpanEventDto, SpanSummaryDto, StringOrArray};
 use crate::api::auth::{ProjectRead, ProjectWrite, SpanRead, TraceRead};
-use crate::api::extractors::{ValidatedJson, ValidatedQuery};
+use crate::api::extractors::{ValidatedJson, ValidatedQuery, is_valid_id};
 use crate::api::types::{
     ApiError, OrderBy, PaginatedResponse, default_limit, default_page, parse_timestamp_param,
     validate_limit, validate_page,
 };
+use crate::core::config::IngestionConfig;
+use crate::data::AnalyticsService;
 use crate::data::types::{
     ListSpansParams, filter_observations, get_observation_cost, get_observation_tokens,
     get_observation_type, is_observation,
 };
+use crate::domain::traces::redact_span_events;
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct ListSpansQuery {
@@ -163,6 +167,7 @@ pub async fn list_spans(
                 c.map(|c| c.link_count).unwrap_or(0),
                 include_raw_span,
             )
+            .with_custom_attributes(row, &state.promoted_attributes)
         })
         .collect();
 
@@ -265,6 +270,7 @@ pub async fn list_trace_spans(
                 c.map(|c| c.link_count).unwrap_or(0),
                 include_raw_span,
             )
+            .with_custom_attributes(row, &state.promoted_attributes)
         })
         .collect();
 
@@ -353,10 +359,148 @@ pub async fn get_span(
     }
 
     Ok(Json(SpanDetailDto {
-        summary: SpanSummaryDto::from_row(&span, event_count, link_count, include_raw_span),
+        summary: SpanSummaryDto::from_row(&span, event_count, link_count, include_raw_span)
+            .with_custom_attributes(&span, &state.promoted_attributes),
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SpanIdParams {
+    #[allow(dead_code)] // Auth handled by ProjectRead extractor
+    pub project_id: String,
+    pub span_id: String,
+}
+
+/// Get a span's full cost breakdown and pricing match
+///
+/// Costs are recomputed from the span's stored token usage with the current
+/// pricing data, so they can differ from the cost stored at ingestion.
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/spans/{span_id}/cost",
+    tag = "spans",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("span_id" = String, Path, description = "Span ID")
+    ),
+    responses(
+        (status = 200, description = "Cost components, rates and match type", body = SpanCostDto),
+        (status = 404, description = "Span not found")
+    )
+)]
+pub async fn get_span_cost(
+    State(state): State<OtelApiState>,
+    auth: ProjectRead,
+    Path(path): Path<SpanIdParams>,
+) -> Result<Json<SpanCostDto>, ApiError> {
+    let span_id = &path.span_id;
+    if !is_valid_id(span_id) {
+        return Err(ApiError::bad_request("INVALID_SPAN_ID", "Invalid span ID"));
+    }
+
+    let params = ListSpansParams {
+        project_id: auth.project_id.clone(),
+        page: 1,
+        limit: 1,
+        filters: vec![Filter::String {
+            column: "span_id".to_string(),
+            operator: StringOp::Eq,
+            value: span_id.to_string(),
+        }],
+        ..Default::default()
+    };
+    let (rows, _) = state
+        .analytics
+        .repository()
+        .list_spans(&params)
+        .await
+        .map_err(ApiError::from_data)?;
+    let span = rows.into_iter().next().ok_or_else(|| {
+        ApiError::not_found("SPAN_NOT_FOUND", format!("Span not found: {}", span_id))
+    })?;
+
+    Ok(Json(SpanCostDto::from_row(
+        &span,
+        &state.pricing,
+        Some(&auth.org_id),
+    )))
+}
+
+/// Get a span's raw OTEL events
+///
+/// Events are returned as captured at ingestion (name, timestamp, attributes),
+/// so it is visible which events drove message and usage extraction. Tool
+/// results and denylisted URLs or statements are redacted as in the feed.
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/traces/{trace_id}/spans/{span_id}/events",
+    tag = "spans",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("trace_id" = String, Path, description = "Trace ID"),
+        ("span_id" = String, Path, description = "Span ID")
+    ),
+    responses(
+        (status = 200, description = "Span events in recorded order", body = Vec<SpanEventDto>),
+        (status = 404, description = "Span not found")
+    )
+)]
+pub async fn get_span_events(
+    State(state): State<OtelApiState>,
+    auth: SpanRead,
+) -> Result<Json<Vec<SpanEventDto>>, ApiError> {
+    let events = load_span_events(
+        &state.analytics,
+        &state.ingestion,
+        &auth.project_id,
+        &auth.trace_id,
+        &auth.span_id,
+    )
+    .await?;
+    Ok(Json(events))
+}
+
+/// Load a span's events, redacted with the current ingestion settings
+async fn load_span_events(
+    analytics: &AnalyticsService,
+    ingestion: &IngestionConfig,
+    project_id: &str,
+    trace_id: &str,
+    span_id: &str,
+) -> Result<Vec<SpanEventDto>, ApiError> {
+    let repo = analytics.repository();
+    let span = repo
+        .get_span(project_id, trace_id, span_id)
+        .await
+        .map_err(ApiError::from_data)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "SPAN_NOT_FOUND",
+                format!("Span not found: {}/{}", trace_id, span_id),
+            )
+        })?;
+    let rows = repo
+        .get_events_for_span(project_id, trace_id, span_id)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    let mut events: Vec<SpanEventDto> = rows.iter().map(SpanEventDto::from_row).collect();
+    let mut raw_events: Vec<serde_json::Value> = events
+        .iter_mut()
+        .map(|e| json!({ "name": e.name, "attributes": e.attributes.take() }))
+        .collect();
+    redact_span_events(
+        &mut raw_events,
+        span.attributes.as_deref(),
+        &ingestion.redact_tool_results,
+        &ingestion.marker_denylist,
+    );
+    for (event, mut raw) in events.iter_mut().zip(raw_events) {
+        event.attributes = raw["attributes"].take();
+    }
+    Ok(events)
+}
+
 // --- Filter options ---
 
 #[derive(Debug, Deserialize, Validate)]
@@ -525,3 +669,87 @@ pub async fn delete_spans(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::core::constants::REDACTED_TOOL_OUTPUT;
+    use crate::core::storage::AppStorage;
+    use crate::data::duckdb::DuckdbService;
+    use crate::data::types::NormalizedSpan;
+
+    #[tokio::test]
+    async fn test_span_events_are_redacted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        let analytics =
+            AnalyticsService::Duckdb(Arc::new(DuckdbService::init(&storage).await.unwrap()));
+
+        let raw_span = json!({
+            "attributes": {},
+            "events": [
+                {
+                    "name": "gen_ai.choice",
+                    "timestamp": "2026-01-01T00:00:00Z",
+                    "attributes": {
+                        "finish_reason": "stop",
+                        "message": r#"{"role":"assistant","content":"Looking it up"}"#
+                    }
+                },
+                {
+                    "name": "gen_ai.tool.message",
+                    "timestamp": "2026-01-01T00:00:01Z",
+                    "attributes": {"name": "get_secret", "content": "s3cr3t"}
+                },
+                {
+                    "name": "http.request",
+                    "timestamp": "2026-01-01T00:00:02Z",
+                    "attributes": {"url.full": "https://internal.corp/api", "http.method": "GET"}
+                }
+            ]
+        });
+        analytics
+            .repository()
+            .insert_spans(vec![NormalizedSpan {
+                project_id: Some("p1".to_string()),
+                trace_id: "t1".to_string(),
+                span_id: "s1".to_string(),
+                raw_span: Some(raw_span.to_string()),
+                ..Default::default()
+            }])
+            .await
+            .unwrap();
+
+        let ingestion = IngestionConfig {
+            redact_tool_results: vec!["get_*".to_string()],
+            marker_denylist: vec!["INTERNAL.corp".to_string()],
+            ..Default::default()
+        };
+        let events = load_span_events(&analytics, &ingestion, "p1", "t1", "s1")
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].name.as_deref(), Some("gen_ai.choice"));
+        assert_eq!(events[0].attributes["finish_reason"], "stop");
+        assert_eq!(
+            events[0].attributes["message"],
+            r#"{"role":"assistant","content":"Looking it up"}"#
+        );
+        assert_eq!(events[1].attributes["content"], REDACTED_TOOL_OUTPUT);
+        assert!(events[2].attributes.get("url.full").is_none());
+        assert_eq!(events[2].attributes["http.method"], "GET");
+
+        // Unknown spans are not found rather than empty
+        assert!(
+            load_span_events(&analytics, &ingestion, "p1", "t1", "missing")
+                .await
+                .is_err()
+        );
+    }
+}
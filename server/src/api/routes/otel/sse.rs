@@ -2,6 +2,8 @@
 //!
 //! Uses BroadcastTopic for distributed pub/sub. In Redis mode, events are
 //! received via Redis Pub/Sub so all SSE endpoints get events from any worker.
+//! Besides `span` events, the stream carries `trace_complete` events once a
+//! trace has gone quiet (when `trace_complete_after_secs` is configured).
 
 use std::convert::Infallible;
 use std::time::{Duration, Instant};
@@ -15,7 +17,9 @@ use super::OtelApiState;
 use crate::api::auth::ProjectRead;
 use crate::api::types::ApiError;
 use crate::core::TopicError;
+use crate::core::constants::TOPIC_TRACE_COMPLETE;
 use crate::domain::SseSpanEvent;
+use crate::domain::traces::TraceComplete;
 
 /// Maximum events per second per SSE connection (backpressure)
 const MAX_EVENTS_PER_SECOND: u32 = 10;
@@ -39,6 +43,10 @@ pub async fn sse(
     // In Redis mode, this uses Redis Pub/Sub to receive events from any worker
     let topic = state.topics.broadcast_topic::<SseSpanEvent>(&topic_name);
     let subscriber_result = topic.subscribe().await;
+    let completion_topic = state
+        .topics
+        .broadcast_topic::<TraceComplete>(&format!("{}:{}", TOPIC_TRACE_COMPLETE, project_id));
+    let completion_subscriber_result = completion_topic.subscribe().await;
     let mut shutdown_rx = state.shutdown_rx.clone();
 
     let stream = async_stream::stream! {
@@ -50,6 +58,14 @@ pub async fn sse(
                 return;
             }
         };
+        let mut completion_subscriber = match completion_subscriber_result {
+            Ok(sub) => sub,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to subscribe to trace completion topic");
+                yield Ok(Event::default().event("error").data("subscription failed"));
+                return;
+            }
+        };
         let mut events_this_second: u32 = 0;
         let mut second_start = Instant::now();
         let mut dropped_count: u64 = 0;
@@ -109,6 +125,31 @@ pub async fn sse(
                         Err(_) => break,
                     }
                 }
+                // Trace completions are rare, so they bypass the rate limit
+                result = completion_subscriber.recv() => {
+                    match result {
+                        Ok(event) => {
+                            if !matches_completion_filter(&event, &query) {
+                                continue;
+                            }
+                            match serde_json::to_string(&event) {
+                                Ok(data) => {
+                                    yield Ok(Event::default()
+                                        .event("trace_complete")
+                                        .data(data));
+                                }
+                                Err(e) => {
+                                    tracing::error!(error = %e, "Failed to serialize trace completion event");
+                                }
+                            }
+                        }
+                        Err(TopicError::Lagged(n)) => {
+                            tracing::warn!(lagged = n, "SSE trace completion subscriber lagged behind");
+                        }
+                        Err(TopicError::ChannelClosed) => break,
+                        Err(_) => break,
+                    }
+                }
             }
         }
     };
@@ -140,6 +181,24 @@ fn matches_filter(event: &SseSpanEvent, query: &SseQuery) -> bool {
     true
 }
 
+/// A span filter has no trace to match a completion against, so it excludes them.
+fn matches_completion_filter(event: &TraceComplete, query: &SseQuery) -> bool {
+    if query.span_id.is_some() {
+        return false;
+    }
+    if let Some(ref trace_id) = query.trace_id
+        && &event.trace_id != trace_id
+    {
+        return false;
+    }
+    if let Some(ref session_id) = query.session_id
+        && event.session_id.as_ref() != Some(session_id)
+    {
+        return false;
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +329,43 @@ mod tests {
         };
         assert!(!matches_filter(&event, &query));
     }
+
+    #[test]
+    fn test_matches_completion_filter() {
+        let event = TraceComplete {
+            project_id: "proj1".to_string(),
+            trace_id: "trace1".to_string(),
+            session_id: Some("session1".to_string()),
+            span_count: 3,
+            last_span_at: chrono::Utc::now(),
+        };
+        let query =
+            |trace_id: Option<&str>, span_id: Option<&str>, session_id: Option<&str>| SseQuery {
+                trace_id: trace_id.map(String::from),
+                span_id: span_id.map(String::from),
+                session_id: session_id.map(String::from),
+            };
+
+        assert!(matches_completion_filter(&event, &query(None, None, None)));
+        assert!(matches_completion_filter(
+            &event,
+            &query(Some("trace1"), None, None)
+        ));
+        assert!(!matches_completion_filter(
+            &event,
+            &query(Some("trace2"), None, None)
+        ));
+        assert!(matches_completion_filter(
+            &event,
+            &query(None, None, Some("session1"))
+        ));
+        assert!(!matches_completion_filter(
+            &event,
+            &query(None, None, Some("session2"))
+        ));
+        assert!(!matches_completion_filter(
+            &event,
+            &query(Some("trace1"), Some("span1"), None)
+        ));
+    }
 }
@@ -10,14 +10,17 @@ use serde::Deserialize;
 
 use super::OtelApiState;
 use super::types::{
-    CostsDto, CountsDto, FrameworkBreakdownDto, LatencyBucketDto, ModelBreakdownDto, PeriodDto,
-    ProjectStatsDto, TokensDto, TrendBucketDto,
+    CostExtrapolationDto, CostsDto, CountsDto, FrameworkBreakdownDto, LatencyBucketDto,
+    ModelBreakdownDto, PeriodDto, ProjectStatsDto, TimeseriesBucketDto, TimeseriesDto, TokensDto,
+    ToolStatsDto, ToolStatsResponse, TrendBucketDto,
 };
 use crate::api::auth::ProjectRead;
 use crate::api::types::{ApiError, parse_timestamp_param};
-use crate::core::constants::CACHE_TTL_STATS;
+use crate::core::constants::{CACHE_TTL_STATS, QUERY_MAX_TIMESERIES_BUCKETS};
 use crate::data::cache::CacheKey;
-use crate::data::types::{ProjectStatsResult, StatsParams};
+use crate::data::types::{
+    ProjectStatsResult, StatsParams, TimeseriesInterval, TimeseriesMetric, TimeseriesParams,
+};
 
 /// TTL for recent data (data from within the last 5 minutes) - 2 minutes
 const CACHE_TTL_STATS_RECENT: u64 = 120;
@@ -28,6 +31,9 @@ pub struct StatsQuery {
     pub to_timestamp: String,
     /// IANA timezone (e.g., "America/New_York"). Used for time bucketing.
     pub timezone: Option<String>,
+    /// Scale costs by the inverse of the org's configured sampling rate
+    #[serde(default)]
+    pub extrapolate_cost: bool,
 }
 
 /// Get project stats for the given time range
@@ -39,7 +45,8 @@ pub struct StatsQuery {
         ("project_id" = String, Path, description = "Project ID"),
         ("from_timestamp" = String, Query, description = "Start of time range (ISO 8601, required)"),
         ("to_timestamp" = String, Query, description = "End of time range (ISO 8601, required)"),
-        ("timezone" = Option<String>, Query, description = "IANA timezone for bucketing (e.g., America/New_York)")
+        ("timezone" = Option<String>, Query, description = "IANA timezone for bucketing (e.g., America/New_York)"),
+        ("extrapolate_cost" = Option<bool>, Query, description = "Estimate true spend from sampled data using the configured sampling rate (default: false)")
     ),
     responses(
         (status = 200, description = "Project stats for the given time range")
@@ -77,6 +84,11 @@ pub async fn get_project_stats(
     let project_id = auth.project_id.clone();
     let timezone = query.timezone.clone();
     let cache = &state.cache;
+    // Applied after the cache so cached entries always hold observed costs
+    let sampling_rate = query
+        .extrapolate_cost
+        .then(|| state.sampling.rate_for_org(&auth.org_id))
+        .flatten();
 
     // Determine if this query is cacheable and calculate TTL
     // - Don't cache if to_timestamp is in the future (real-time)
@@ -108,8 +120,11 @@ pub async fn get_project_stats(
     // Try cache first (only if cacheable)
     if cache_ttl.is_some() {
         match cache.get::<ProjectStatsDto>(&cache_key).await {
-            Ok(Some(cached_dto)) => {
+            Ok(Some(mut cached_dto)) => {
                 tracing::trace!(%project_id, "Stats cache hit");
+                if let Some(rate) = sampling_rate {
+                    extrapolate_costs(&mut cached_dto, rate);
+                }
                 let mut headers = HeaderMap::new();
                 headers.insert(
                     header::CACHE_CONTROL,
@@ -136,7 +151,7 @@ pub async fn get_project_stats(
         .await
         .map_err(ApiError::from_data)?;
 
-    let dto = stats_result_to_dto(result, from_timestamp, to_timestamp);
+    let mut dto = stats_result_to_dto(result, from_timestamp, to_timestamp);
 
     // Store in cache if cacheable
     if let Some(ttl) = cache_ttl
@@ -145,12 +160,204 @@ pub async fn get_project_stats(
         tracing::warn!(%project_id, error = %e, "Stats cache set error");
     }
 
+    if let Some(rate) = sampling_rate {
+        extrapolate_costs(&mut dto, rate);
+    }
+
     let mut headers = HeaderMap::new();
     headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
 
     Ok((headers, Json(dto)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TimeseriesQuery {
+    pub from_timestamp: String,
+    pub to_timestamp: String,
+    /// IANA timezone (e.g., "America/New_York"). Used for time bucketing.
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub interval: TimeseriesInterval,
+    #[serde(default)]
+    pub metric: TimeseriesMetric,
+}
+
+/// Get a time-bucketed series of cost, tokens, spans or errors
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/timeseries",
+    tag = "stats",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("from_timestamp" = String, Query, description = "Start of time range (ISO 8601, required)"),
+        ("to_timestamp" = String, Query, description = "End of time range (ISO 8601, required)"),
+        ("timezone" = Option<String>, Query, description = "IANA timezone for bucketing (e.g., America/New_York)"),
+        ("interval" = Option<String>, Query, description = "Bucket width: minute, hour (default) or day"),
+        ("metric" = Option<String>, Query, description = "Aggregate: cost (default), tokens, spans or errors")
+    ),
+    responses(
+        (status = 200, description = "Zero-filled buckets covering the time range", body = TimeseriesDto)
+    )
+)]
+pub async fn get_project_timeseries(
+    State(state): State<OtelApiState>,
+    auth: ProjectRead,
+    Query(query): Query<TimeseriesQuery>,
+) -> Result<Json<TimeseriesDto>, ApiError> {
+    let from_timestamp = parse_timestamp_param(&Some(query.from_timestamp))?
+        .ok_or_else(|| ApiError::bad_request("MISSING_PARAM", "from_timestamp is required"))?;
+
+    let to_timestamp = parse_timestamp_param(&Some(query.to_timestamp))?
+        .ok_or_else(|| ApiError::bad_request("MISSING_PARAM", "to_timestamp is required"))?;
+
+    if from_timestamp >= to_timestamp {
+        return Err(ApiError::bad_request(
+            "INVALID_TIME_RANGE",
+            "from_timestamp must be strictly before to_timestamp",
+        ));
+    }
+
+    let bucket_count =
+        (to_timestamp - from_timestamp).num_seconds() / query.interval.duration().num_seconds();
+    if bucket_count > QUERY_MAX_TIMESERIES_BUCKETS {
+        return Err(ApiError::bad_request(
+            "RANGE_TOO_LARGE",
+            format!(
+                "Time range needs more than {QUERY_MAX_TIMESERIES_BUCKETS} buckets; use a wider interval"
+            ),
+        ));
+    }
+
+    let params = TimeseriesParams {
+        project_id: auth.project_id.clone(),
+        from_timestamp,
+        to_timestamp,
+        timezone: query.timezone.clone(),
+        interval: query.interval,
+        metric: query.metric,
+    };
+
+    let points = state
+        .analytics
+        .repository()
+        .get_timeseries(&params)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    Ok(Json(TimeseriesDto {
+        period: PeriodDto {
+            from: from_timestamp,
+            to: to_timestamp,
+        },
+        interval: query.interval,
+        metric: query.metric,
+        timezone: query.timezone.unwrap_or_else(|| "UTC".to_string()),
+        buckets: points
+            .into_iter()
+            .map(|p| TimeseriesBucketDto {
+                bucket: p.bucket,
+                value: p.value,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToolStatsQuery {
+    pub from_timestamp: String,
+    pub to_timestamp: String,
+}
+
+/// Get call count, error rate and average latency per tool
+#[utoipa::path(
+    get,
+    path = "/api/v1/project/{project_id}/otel/tools/stats",
+    tag = "stats",
+    params(
+        ("project_id" = String, Path, description = "Project ID"),
+        ("from_timestamp" = String, Query, description = "Start of time range (ISO 8601, required)"),
+        ("to_timestamp" = String, Query, description = "End of time range (ISO 8601, required)")
+    ),
+    responses(
+        (status = 200, description = "Per-tool stats from tool spans in the time range", body = ToolStatsResponse)
+    )
+)]
+pub async fn get_tool_stats(
+    State(state): State<OtelApiState>,
+    auth: ProjectRead,
+    Query(query): Query<ToolStatsQuery>,
+) -> Result<Json<ToolStatsResponse>, ApiError> {
+    let from_timestamp = parse_timestamp_param(&Some(query.from_timestamp))?
+        .ok_or_else(|| ApiError::bad_request("MISSING_PARAM", "from_timestamp is required"))?;
+
+    let to_timestamp = parse_timestamp_param(&Some(query.to_timestamp))?
+        .ok_or_else(|| ApiError::bad_request("MISSING_PARAM", "to_timestamp is required"))?;
+
+    if from_timestamp >= to_timestamp {
+        return Err(ApiError::bad_request(
+            "INVALID_TIME_RANGE",
+            "from_timestamp must be strictly before to_timestamp",
+        ));
+    }
+
+    let params = StatsParams {
+        project_id: auth.project_id.clone(),
+        from_timestamp,
+        to_timestamp,
+        timezone: None,
+    };
+
+    let tools = state
+        .analytics
+        .repository()
+        .get_tool_stats(&params)
+        .await
+        .map_err(ApiError::from_data)?;
+
+    Ok(Json(ToolStatsResponse {
+        period: PeriodDto {
+            from: from_timestamp,
+            to: to_timestamp,
+        },
+        tools: tools
+            .into_iter()
+            .map(|t| ToolStatsDto {
+                tool_name: t.tool_name,
+                call_count: t.call_count,
+                error_count: t.error_count,
+                error_rate: t.error_rate,
+                avg_duration_ms: t.avg_duration_ms,
+            })
+            .collect(),
+    }))
+}
+
+/// Scale all cost figures by `1 / sampling_rate` and label them as estimated.
+///
+/// Token counts and trace counts are left as observed.
+fn extrapolate_costs(dto: &mut ProjectStatsDto, sampling_rate: f64) {
+    let multiplier = 1.0 / sampling_rate;
+    let costs = &mut dto.costs;
+    for value in [
+        &mut costs.input,
+        &mut costs.output,
+        &mut costs.cache_read,
+        &mut costs.cache_write,
+        &mut costs.reasoning,
+        &mut costs.total,
+    ] {
+        *value *= multiplier;
+    }
+    for model in &mut dto.by_model {
+        model.cost *= multiplier;
+    }
+    dto.cost_extrapolation = Some(CostExtrapolationDto {
+        estimated: true,
+        sampling_rate,
+        multiplier,
+    });
+}
+
 pub(crate) fn stats_result_to_dto(
     result: ProjectStatsResult,
     from_timestamp: DateTime<Utc>,
@@ -221,5 +428,75 @@ pub(crate) fn stats_result_to_dto(
                 avg_duration_ms: t.avg_duration_ms,
             })
             .collect(),
+        cost_extrapolation: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::{CostsResult, CountsResult, ModelBreakdown, TokensResult};
+
+    fn make_stats(total_cost: f64) -> ProjectStatsDto {
+        let result = ProjectStatsResult {
+            counts: CountsResult {
+                traces: 3,
+                ..Default::default()
+            },
+            costs: CostsResult {
+                input: total_cost * 0.25,
+                output: total_cost * 0.75,
+                total: total_cost,
+                ..Default::default()
+            },
+            tokens: TokensResult {
+                total: 1000,
+                ..Default::default()
+            },
+            by_framework: vec![],
+            by_model: vec![ModelBreakdown {
+                model: Some("gpt-4o".to_string()),
+                tokens: 1000,
+                cost: total_cost,
+                percentage: 100.0,
+            }],
+            recent_activity_count: 0,
+            avg_trace_duration_ms: None,
+            trend_data: vec![],
+            latency_trend_data: vec![],
+        };
+        let now = Utc::now();
+        stats_result_to_dto(result, now - chrono::Duration::hours(1), now)
+    }
+
+    #[test]
+    fn test_extrapolate_costs_scales_by_inverse_sampling_rate() {
+        let mut dto = make_stats(2.0);
+        extrapolate_costs(&mut dto, 0.1);
+
+        assert!((dto.costs.total - 20.0).abs() < 1e-9);
+        assert!((dto.costs.input - 5.0).abs() < 1e-9);
+        assert!((dto.costs.output - 15.0).abs() < 1e-9);
+        assert!((dto.by_model[0].cost - 20.0).abs() < 1e-9);
+        // Observed counts are not extrapolated
+        assert_eq!(dto.tokens.total, 1000);
+        assert_eq!(dto.counts.traces, 3);
+
+        let label = dto
+            .cost_extrapolation
+            .expect("extrapolation must be labeled");
+        assert!(label.estimated);
+        assert_eq!(label.sampling_rate, 0.1);
+        assert!((label.multiplier - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_extrapolation_without_sampling() {
+        let sampling = crate::core::config::SamplingConfig::default();
+        assert_eq!(sampling.rate_for_org("org1"), None);
+
+        let dto = make_stats(2.0);
+        assert!(dto.cost_extrapolation.is_none());
+        assert_eq!(dto.costs.total, 2.0);
     }
 }
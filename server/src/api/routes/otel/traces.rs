@@ -13,10 +13,13 @@ use super::types::{SpanDetailDto, SpanSummaryDto, StringOrArray, TraceDetailDto,
 use crate::api::auth::{ProjectRead, ProjectWrite, TraceRead};
 use crate::api::extractors::{ValidatedJson, ValidatedQuery};
 use crate::api::types::{
-    ApiError, OrderBy, PaginatedResponse, default_limit, default_page, parse_timestamp_param,
-    validate_ids_batch, validate_limit, validate_page,
+    ApiError, OrderBy, OrderDirection, PaginatedResponse, default_limit, default_page,
+    parse_timestamp_param, validate_ids_batch, validate_limit, validate_page,
+};
+use crate::data::AnalyticsRepository;
+use crate::data::types::{
+    ListTracesParams, PrimaryModelRule, TraceRow, TraceSort, find_root_span, resolve_trace_models,
 };
-use crate::data::types::{ListTracesParams, TraceRow, find_root_span};
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct ListTracesQuery {
@@ -27,6 +30,8 @@ pub struct ListTracesQuery {
     #[validate(custom(function = "validate_limit"))]
     pub limit: u32,
     pub order_by: Option<String>,
+    /// Sort preset (alternative to `order_by`)
+    pub sort: Option<TraceSort>,
     pub session_id: Option<String>,
     pub user_id: Option<String>,
     pub environment: Option<StringOrArray>,
@@ -36,6 +41,24 @@ pub struct ListTracesQuery {
     /// Include non-GenAI traces (default: false, showing only GenAI traces)
     #[serde(default)]
     pub include_nongenai: bool,
+    /// Rule for resolving the primary model of multi-model traces
+    #[serde(default)]
+    pub primary_model: PrimaryModelRule,
+}
+
+/// Map a sort preset onto the equivalent `order_by` column and direction
+fn sort_order_by(sort: TraceSort) -> OrderBy {
+    let (column, direction) = match sort {
+        TraceSort::TimeDesc => ("start_time", OrderDirection::Desc),
+        TraceSort::CostDesc => ("total_cost", OrderDirection::Desc),
+        TraceSort::CostAsc => ("total_cost", OrderDirection::Asc),
+        TraceSort::TokensDesc => ("total_tokens", OrderDirection::Desc),
+        TraceSort::DurationDesc => ("duration_ms", OrderDirection::Desc),
+    };
+    OrderBy {
+        column: column.to_string(),
+        direction,
+    }
 }
 
 /// List traces with pagination and filters
@@ -48,11 +71,13 @@ pub struct ListTracesQuery {
         ("page" = Option<u32>, Query, description = "Page number"),
         ("limit" = Option<u32>, Query, description = "Items per page"),
         ("order_by" = Option<String>, Query, description = "Sort field (e.g., start_time:desc)"),
+        ("sort" = Option<String>, Query, description = "Sort preset: time_desc (default), cost_desc, cost_asc, tokens_desc, duration_desc. Cannot be combined with order_by"),
         ("session_id" = Option<String>, Query, description = "Filter by session ID"),
         ("user_id" = Option<String>, Query, description = "Filter by user ID"),
         ("from_timestamp" = Option<String>, Query, description = "Filter from timestamp (ISO 8601)"),
         ("to_timestamp" = Option<String>, Query, description = "Filter to timestamp (ISO 8601)"),
-        ("include_nongenai" = Option<bool>, Query, description = "Include non-GenAI traces (default: false)")
+        ("include_nongenai" = Option<bool>, Query, description = "Include non-GenAI traces (default: false)"),
+        ("primary_model" = Option<String>, Query, description = "Primary model rule: most_tokens (default), most_cost, root_generation")
     ),
     responses(
         (status = 200, description = "List of traces with pagination metadata")
@@ -63,11 +88,17 @@ pub async fn list_traces(
     auth: ProjectRead,
     ValidatedQuery(query): ValidatedQuery<ListTracesQuery>,
 ) -> Result<(HeaderMap, Json<PaginatedResponse<TraceSummaryDto>>), ApiError> {
-    // Parse order_by
-    let order_by = if let Some(ref ob) = query.order_by {
-        Some(OrderBy::parse(ob, columns::TRACE_SORTABLE)?)
-    } else {
-        None
+    // Parse order_by or the sort preset
+    let order_by = match (&query.order_by, query.sort) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::bad_request(
+                "INVALID_ORDER",
+                "Use either order_by or sort, not both",
+            ));
+        }
+        (Some(ob), None) => Some(OrderBy::parse(ob, columns::TRACE_SORTABLE)?),
+        (None, Some(sort)) => Some(sort_order_by(sort)),
+        (None, None) => None,
     };
 
     // Parse timestamps
@@ -101,7 +132,14 @@ pub async fn list_traces(
         .await
         .map_err(ApiError::from_data)?;
 
-    let data: Vec<TraceSummaryDto> = rows.into_iter().map(trace_row_to_summary).collect();
+    let mut data: Vec<TraceSummaryDto> = rows.into_iter().map(trace_row_to_summary).collect();
+    apply_trace_models(
+        repo.as_ref(),
+        &auth.project_id,
+        &mut data,
+        query.primary_model,
+    )
+    .await?;
 
     let mut headers = HeaderMap::new();
     headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
@@ -131,6 +169,9 @@ pub struct TraceDetailQuery {
     /// Include raw OTLP span JSON in response (default: false)
     #[serde(default)]
     pub include_raw_span: bool,
+    /// Rule for resolving the primary model of multi-model traces
+    #[serde(default)]
+    pub primary_model: PrimaryModelRule,
 }
 
 /// Get a single trace with all nested spans
@@ -141,7 +182,8 @@ pub struct TraceDetailQuery {
     params(
         ("project_id" = String, Path, description = "Project ID"),
         ("trace_id" = String, Path, description = "Trace ID"),
-        ("include_raw_span" = Option<bool>, Query, description = "Include raw OTLP span JSON (default: false)")
+        ("include_raw_span" = Option<bool>, Query, description = "Include raw OTLP span JSON (default: false)"),
+        ("primary_model" = Option<String>, Query, description = "Primary model rule: most_tokens (default), most_cost, root_generation")
     ),
     responses(
         (status = 200, description = "Trace details with spans", body = TraceDetailDto),
@@ -206,7 +248,8 @@ pub async fn get_trace(
             let link_count = counts.map(|c| c.link_count).unwrap_or(0);
 
             SpanDetailDto {
-                summary: SpanSummaryDto::from_row(span, event_count, link_count, include_raw_span),
+                summary: SpanSummaryDto::from_row(span, event_count, link_count, include_raw_span)
+                    .with_custom_attributes(span, &state.promoted_attributes),
             }
         })
         .collect();
@@ -215,7 +258,14 @@ pub async fn get_trace(
         tracing::warn!(trace_id = %trace_id, total = span_count, returned = MAX_SPANS_PER_TRACE, "Trace response truncated");
     }
 
-    let summary = trace_row_to_summary(trace);
+    let mut summary = trace_row_to_summary(trace);
+    apply_trace_models(
+        repo.as_ref(),
+        project_id,
+        std::slice::from_mut(&mut summary),
+        query.primary_model,
+    )
+    .await?;
 
     // Compute ETag from span_count and end_time
     let end_time_str = summary
@@ -273,7 +323,34 @@ pub(crate) fn trace_row_to_summary(row: TraceRow) -> TraceSummaryDto {
         input_preview: row.input_preview,
         output_preview: row.output_preview,
         has_error: row.has_error,
+        primary_model: None,
+        models: Vec::new(),
+    }
+}
+
+/// Fill `primary_model` and `models` on trace summaries from generation span usage
+async fn apply_trace_models(
+    repo: &(dyn AnalyticsRepository + Send + Sync),
+    project_id: &str,
+    summaries: &mut [TraceSummaryDto],
+    rule: PrimaryModelRule,
+) -> Result<(), ApiError> {
+    if summaries.is_empty() {
+        return Ok(());
+    }
+    let trace_ids: Vec<String> = summaries.iter().map(|s| s.trace_id.clone()).collect();
+    let usage = repo
+        .get_trace_model_usage(project_id, &trace_ids)
+        .await
+        .map_err(ApiError::from_data)?;
+    let mut resolved = resolve_trace_models(&usage, rule);
+    for summary in summaries.iter_mut() {
+        if let Some(models) = resolved.remove(&summary.trace_id) {
+            summary.primary_model = models.primary;
+            summary.models = models.models;
+        }
     }
+    Ok(())
 }
 
 // --- Delete operations ---
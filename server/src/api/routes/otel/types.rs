@@ -1,11 +1,20 @@
 //! OTEL-specific DTOs for API responses
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::data::types::{MessageCategory, SpanRow};
-use crate::domain::sideml::{BlockEntry, ChatRole, ContentBlock, FinishReason};
+use crate::api::routes::pricing::ModelPricingResponse;
+use crate::core::config::PromotedAttributes;
+use crate::data::types::{
+    EventRow, MessageCategory, SpanRow, TimeseriesInterval, TimeseriesMetric,
+};
+use crate::domain::pricing::{MatchType, PricingService, SpanCostInput};
+use crate::domain::sideml::{
+    BlockEntry, ChatRole, ContentBlock, ContentSizeStats, FeedWarning, FinishReason, Turn,
+};
 
 /// Helper for query params that accept string or array
 #[derive(Debug, Deserialize)]
@@ -55,6 +64,10 @@ pub struct TraceSummaryDto {
     pub input_preview: Option<String>,
     pub output_preview: Option<String>,
     pub has_error: bool,
+    /// Primary model of the trace, resolved by the requested `primary_model` rule
+    pub primary_model: Option<String>,
+    /// All models used by generation spans in the trace (primary first)
+    pub models: Vec<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -101,10 +114,31 @@ pub struct SpanSummaryDto {
     pub cache_write_cost: f64,
     pub reasoning_cost: f64,
     pub total_cost: f64,
+    /// Where the stored costs came from: "computed" or "reported" by the span
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_source: Option<String>,
+    /// Embedding output dimensions (embedding spans only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<i64>,
+    /// Embedding encoding format, e.g. "float" or "base64" (embedding spans only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<String>,
+    /// Requested reasoning effort, e.g. "low" or "high" (reasoning models only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    /// Requested response verbosity, e.g. "low" or "high"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbosity: Option<String>,
+    /// Model string as reported, when an ingestion alias rewrote it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_model: Option<String>,
     pub event_count: i64,
     pub link_count: i64,
     pub input_preview: Option<String>,
     pub output_preview: Option<String>,
+    /// Span attributes selected by the `otel.promoted_attributes` config
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_attributes: BTreeMap<String, serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_span: Option<serde_json::Value>,
 }
@@ -116,6 +150,7 @@ impl SpanSummaryDto {
         link_count: i64,
         include_raw_span: bool,
     ) -> Self {
+        let details = usage_details(row);
         Self {
             trace_id: row.trace_id.clone(),
             span_id: row.span_id.clone(),
@@ -148,10 +183,17 @@ impl SpanSummaryDto {
             cache_write_cost: row.gen_ai_cost_cache_write,
             reasoning_cost: row.gen_ai_cost_reasoning,
             total_cost: row.gen_ai_cost_total,
+            cost_source: row.gen_ai_cost_source.clone(),
+            embedding_dimensions: row.gen_ai_embedding_dimensions,
+            encoding_format: row.gen_ai_encoding_format.clone(),
+            reasoning_effort: row.gen_ai_reasoning_effort.clone(),
+            verbosity: row.gen_ai_verbosity.clone(),
+            raw_model: row.gen_ai_raw_model.clone(),
             event_count,
             link_count,
             input_preview: row.input_preview.clone(),
             output_preview: row.output_preview.clone(),
+            custom_attributes: BTreeMap::new(),
             raw_span: if include_raw_span {
                 row.raw_span
                     .as_ref()
@@ -161,6 +203,23 @@ impl SpanSummaryDto {
             },
         }
     }
+
+    /// Copy the span attributes matching `promoted` into `custom_attributes`.
+    pub fn with_custom_attributes(mut self, row: &SpanRow, promoted: &PromotedAttributes) -> Self {
+        if promoted.is_empty() {
+            return self;
+        }
+        let Some(attributes) = row.attributes.as_deref().and_then(|s| {
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(s).ok()
+        }) else {
+            return self;
+        };
+        self.custom_attributes = attributes
+            .into_iter()
+            .filter(|(key, _)| promoted.matches(key))
+            .collect();
+        self
+    }
 }
 
 /// Span detail response.
@@ -172,6 +231,138 @@ pub struct SpanDetailDto {
     pub summary: SpanSummaryDto,
 }
 
+/// OTEL span event as captured at ingestion, before any normalization
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpanEventDto {
+    pub name: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Event attributes as sent by the exporter
+    pub attributes: serde_json::Value,
+}
+
+impl SpanEventDto {
+    pub fn from_row(row: &EventRow) -> Self {
+        Self {
+            name: row.event_name.clone(),
+            timestamp: row.event_time,
+            attributes: row
+                .attributes
+                .as_deref()
+                .and_then(|a| serde_json::from_str(a).ok())
+                .unwrap_or_else(|| serde_json::json!({})),
+        }
+    }
+}
+
+/// Span cost breakdown recomputed from stored usage with current pricing.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpanCostDto {
+    pub trace_id: String,
+    pub span_id: String,
+    /// Model and system used for the pricing lookup
+    pub model: Option<String>,
+    pub system: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub reasoning_tokens: i64,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub cache_read_cost: f64,
+    pub cache_write_cost: f64,
+    pub reasoning_cost: f64,
+    /// Per-call surcharges for built-in tools
+    pub tool_cost: f64,
+    pub total_cost: f64,
+    /// Saved by cache reads versus paying the full input rate for them
+    pub cache_savings: f64,
+    /// Total cost stored at ingestion (differs when pricing changed since)
+    pub stored_total_cost: f64,
+    pub match_type: MatchType,
+    pub confidence: f64,
+    /// Costs are based on estimated (not reported) token counts
+    pub estimated: bool,
+    /// Rates the costs were computed from (absent when `match_type` is `not_found`)
+    pub pricing: Option<ModelPricingResponse>,
+}
+
+impl SpanCostDto {
+    /// Recompute a span's cost components from its stored token usage.
+    ///
+    /// Prices the response model when set, like ingestion does, so the
+    /// breakdown matches the stored cost. Read-only: unmatched models are not
+    /// recorded.
+    pub fn from_row(row: &SpanRow, pricing: &PricingService, org_id: Option<&str>) -> Self {
+        let details = usage_details(row);
+        let estimated = details
+            .as_ref()
+            .and_then(|d| d.get("estimated").and_then(|e| e.as_bool()))
+            .unwrap_or(false);
+        let input = SpanCostInput {
+            system: row.gen_ai_system.clone(),
+            model: row
+                .gen_ai_response_model
+                .clone()
+                .filter(|m| !m.is_empty())
+                .or_else(|| row.gen_ai_request_model.clone()),
+            input_tokens: row.gen_ai_usage_input_tokens,
+            output_tokens: row.gen_ai_usage_output_tokens,
+            total_tokens: row.gen_ai_usage_total_tokens,
+            cache_read_tokens: row.gen_ai_usage_cache_read_tokens,
+            cache_write_tokens: row.gen_ai_usage_cache_write_tokens,
+            reasoning_tokens: row.gen_ai_usage_reasoning_tokens,
+            web_search_calls: row.gen_ai_usage_web_search_calls,
+            embedding_dimensions: row.gen_ai_embedding_dimensions,
+            estimated,
+            org_id: org_id.map(str::to_string),
+        };
+
+        let output = pricing.calculate_cost_untracked(&input);
+        let match_type = output.match_type.unwrap_or_default();
+        let rates = pricing.resolve_pricing(&input).map(|(rates, match_type)| {
+            ModelPricingResponse::from_pricing(
+                input.model.clone().unwrap_or_default(),
+                input.system.clone(),
+                rates,
+                match_type,
+            )
+        });
+
+        Self {
+            trace_id: row.trace_id.clone(),
+            span_id: row.span_id.clone(),
+            model: input.model,
+            system: input.system,
+            input_tokens: row.gen_ai_usage_input_tokens,
+            output_tokens: row.gen_ai_usage_output_tokens,
+            cache_read_tokens: row.gen_ai_usage_cache_read_tokens,
+            cache_write_tokens: row.gen_ai_usage_cache_write_tokens,
+            reasoning_tokens: row.gen_ai_usage_reasoning_tokens,
+            input_cost: output.input_cost,
+            output_cost: output.output_cost,
+            cache_read_cost: output.cache_read_cost,
+            cache_write_cost: output.cache_write_cost,
+            reasoning_cost: output.reasoning_cost,
+            tool_cost: output.tool_cost,
+            total_cost: output.total_cost,
+            cache_savings: output.cache_savings,
+            stored_total_cost: row.gen_ai_cost_total,
+            match_type,
+            confidence: output.confidence(),
+            estimated: output.estimated,
+            pricing: rates,
+        }
+    }
+}
+
+/// Parsed `gen_ai_usage_details` JSON of a stored span.
+fn usage_details(row: &SpanRow) -> Option<serde_json::Value> {
+    row.gen_ai_usage_details
+        .as_deref()
+        .and_then(|d| serde_json::from_str(d).ok())
+}
+
 // --- Session DTOs ---
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -283,9 +474,19 @@ pub struct BlockDto {
     /// Message category for semantic filtering
     pub category: MessageCategory,
 
-    // For deduplication
-    pub content_hash: String,
+    /// Stable content hash for client-side diffing (only when hashes are requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
     pub is_semantic: bool,
+    /// Span carrying the full system prompt this block repeats (collapsed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_of: Option<String>,
+    /// Milliseconds since the previous block (only when gaps are requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_ms: Option<i64>,
+    /// Shared by tool calls requested together in one assistant turn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_group: Option<String>,
 }
 
 impl BlockDto {
@@ -315,8 +516,11 @@ impl BlockDto {
             is_error: entry.is_error,
             source_type: entry.source_type.clone(),
             category: entry.category,
-            content_hash: entry.content_hash.clone(),
+            content_hash: (!entry.content_hash.is_empty()).then(|| entry.content_hash.clone()),
             is_semantic: entry.is_semantic,
+            repeat_of: entry.repeat_of.clone(),
+            gap_ms: entry.gap_ms,
+            parallel_group: entry.parallel_group.clone(),
         }
     }
 }
@@ -328,11 +532,21 @@ pub struct MessagesMetadataDto {
     pub total_cost: f64,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
+    /// Byte sizes of message content by block type
+    pub content_sizes: ContentSizeStats,
+    /// Data-quality issues found while processing (e.g. unparseable messages)
+    pub warnings: Vec<FeedWarning>,
+    /// Older messages were dropped to stay within the server's per-request
+    /// block cap; narrow `from_timestamp`/`to_timestamp` to load them
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct MessagesResponseDto {
     pub messages: Vec<BlockDto>,
+    /// Runs of `messages` grouped into turns (only with `?turns=true`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub turns: Vec<Turn>,
     pub metadata: MessagesMetadataDto,
     /// Deduplicated tool definitions sorted by name
     pub tool_definitions: Vec<serde_json::Value>,
@@ -354,6 +568,20 @@ pub struct ProjectStatsDto {
     pub avg_trace_duration_ms: Option<f64>,
     pub trend_data: Vec<TrendBucketDto>,
     pub latency_trend_data: Vec<LatencyBucketDto>,
+    /// Present when costs were scaled up to compensate for head sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_extrapolation: Option<CostExtrapolationDto>,
+}
+
+/// Label for costs extrapolated from sampled data (values are estimates)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CostExtrapolationDto {
+    /// Always true: extrapolated costs are estimates, not observed spend
+    pub estimated: bool,
+    /// Configured fraction of traces retained by sampling
+    pub sampling_rate: f64,
+    /// Factor applied to costs (1 / sampling_rate)
+    pub multiplier: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -418,6 +646,44 @@ pub struct LatencyBucketDto {
     pub avg_duration_ms: f64,
 }
 
+/// Call outcomes of one tool across the project
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ToolStatsDto {
+    pub tool_name: String,
+    pub call_count: i64,
+    pub error_count: i64,
+    /// Percentage of calls that ended with ERROR status
+    pub error_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+}
+
+/// Per-tool reliability for a time range, most-called tools first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ToolStatsResponse {
+    pub period: PeriodDto,
+    pub tools: Vec<ToolStatsDto>,
+}
+
+/// Time-bucketed series of one metric, zero-filled for contiguous charts
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeseriesDto {
+    pub period: PeriodDto,
+    #[schema(value_type = String, example = "hour")]
+    pub interval: TimeseriesInterval,
+    #[schema(value_type = String, example = "cost")]
+    pub metric: TimeseriesMetric,
+    /// Timezone used to align buckets
+    pub timezone: String,
+    pub buckets: Vec<TimeseriesBucketDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeseriesBucketDto {
+    /// Bucket start (inclusive)
+    pub bucket: DateTime<Utc>,
+    pub value: f64,
+}
+
 // --- Feed DTOs ---
 
 /// Pagination info for feed endpoints
@@ -440,6 +706,11 @@ pub struct FeedMessagesMetadata {
     pub total_tokens: i64,
     /// Total cost from contributing spans
     pub total_cost: f64,
+    /// Data-quality issues found while processing (e.g. unparseable messages)
+    pub warnings: Vec<FeedWarning>,
+    /// Older messages in this page were dropped to stay within the server's
+    /// per-request block cap; request a smaller `limit` and page with `cursor`
+    pub truncated: bool,
 }
 
 /// Feed messages response with cursor-based pagination
@@ -447,6 +718,9 @@ pub struct FeedMessagesMetadata {
 pub struct FeedMessagesResponse {
     /// Blocks sorted by span timestamp DESC
     pub data: Vec<BlockDto>,
+    /// Runs of `data` grouped into turns (only with `?turns=true`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub turns: Vec<Turn>,
     /// Pagination information
     pub pagination: FeedPagination,
     /// Response metadata
@@ -465,3 +739,211 @@ pub struct FeedSpansResponse {
     /// Pagination information
     pub pagination: FeedPagination,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span_row_with_attributes(attributes: &str) -> SpanRow {
+        SpanRow {
+            trace_id: "trace1".to_string(),
+            span_id: "span1".to_string(),
+            parent_span_id: None,
+            span_name: Some("chat".to_string()),
+            span_kind: None,
+            span_category: None,
+            observation_type: None,
+            framework: None,
+            status_code: None,
+            timestamp_start: Utc::now(),
+            timestamp_end: None,
+            duration_ms: None,
+            environment: None,
+            resource_attributes: None,
+            session_id: None,
+            user_id: None,
+            gen_ai_system: None,
+            gen_ai_request_model: None,
+            gen_ai_response_model: None,
+            gen_ai_agent_name: None,
+            gen_ai_finish_reasons: Vec::new(),
+            gen_ai_usage_input_tokens: 0,
+            gen_ai_usage_output_tokens: 0,
+            gen_ai_usage_total_tokens: 0,
+            gen_ai_usage_cache_read_tokens: 0,
+            gen_ai_usage_cache_write_tokens: 0,
+            gen_ai_usage_reasoning_tokens: 0,
+            gen_ai_usage_web_search_calls: 0,
+            gen_ai_cost_input: 0.0,
+            gen_ai_cost_output: 0.0,
+            gen_ai_cost_cache_read: 0.0,
+            gen_ai_cost_cache_write: 0.0,
+            gen_ai_cost_reasoning: 0.0,
+            gen_ai_cost_total: 0.0,
+            gen_ai_cost_source: None,
+            gen_ai_usage_details: None,
+            gen_ai_reasoning_effort: None,
+            gen_ai_verbosity: None,
+            gen_ai_raw_model: None,
+            gen_ai_embedding_dimensions: None,
+            gen_ai_encoding_format: None,
+            metadata: None,
+            attributes: Some(attributes.to_string()),
+            input_preview: None,
+            output_preview: None,
+            raw_span: None,
+            ingested_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_span_dto_promotes_configured_attributes() {
+        let row = span_row_with_attributes(
+            r#"{"user.tier": "gold", "experiment.id": "exp-7", "experiment.arm": 2, "gen_ai.system": "openai"}"#,
+        );
+        let promoted = PromotedAttributes::from_patterns(&[
+            "user.tier".to_string(),
+            "experiment.*".to_string(),
+        ]);
+
+        let dto =
+            SpanSummaryDto::from_row(&row, 0, 0, false).with_custom_attributes(&row, &promoted);
+
+        assert_eq!(dto.custom_attributes.len(), 3);
+        assert_eq!(dto.custom_attributes["user.tier"], "gold");
+        assert_eq!(dto.custom_attributes["experiment.id"], "exp-7");
+        assert_eq!(dto.custom_attributes["experiment.arm"], 2);
+        assert!(!dto.custom_attributes.contains_key("gen_ai.system"));
+
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["custom_attributes"]["user.tier"], "gold");
+
+        let plain = SpanSummaryDto::from_row(&row, 0, 0, false)
+            .with_custom_attributes(&row, &PromotedAttributes::default());
+        let json = serde_json::to_value(&plain).unwrap();
+        assert!(json.get("custom_attributes").is_none());
+    }
+
+    #[test]
+    fn test_span_event_dto_keeps_raw_attributes() {
+        let row = EventRow {
+            span_id: "span1".to_string(),
+            event_index: 0,
+            event_time: Utc::now(),
+            event_name: Some("gen_ai.choice".to_string()),
+            attributes: Some(
+                r#"{"finish_reason": "stop", "index": 0, "message": "{\"role\":\"assistant\"}"}"#
+                    .to_string(),
+            ),
+        };
+        let dto = SpanEventDto::from_row(&row);
+        assert_eq!(dto.name.as_deref(), Some("gen_ai.choice"));
+        assert_eq!(dto.attributes["finish_reason"], "stop");
+        assert_eq!(dto.attributes["index"], 0);
+        assert_eq!(dto.attributes["message"], r#"{"role":"assistant"}"#);
+
+        let empty = SpanEventDto::from_row(&EventRow {
+            attributes: None,
+            ..row
+        });
+        assert_eq!(empty.attributes, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_span_dto_surfaces_reasoning_params() {
+        let mut row = span_row_with_attributes("{}");
+        row.gen_ai_reasoning_effort = Some("high".to_string());
+
+        let dto = SpanSummaryDto::from_row(&row, 0, 0, false);
+        assert_eq!(dto.reasoning_effort.as_deref(), Some("high"));
+        assert_eq!(dto.verbosity, None);
+
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["reasoning_effort"], "high");
+        assert!(json.get("verbosity").is_none());
+    }
+
+    #[test]
+    fn test_span_cost_breakdown_includes_web_search_surcharge() {
+        let mut row = span_row_with_attributes("{}");
+        row.gen_ai_system = Some("openai".to_string());
+        row.gen_ai_request_model = Some("gpt-4o-search-preview".to_string());
+        row.gen_ai_usage_input_tokens = 1000;
+        row.gen_ai_usage_output_tokens = 500;
+        row.gen_ai_usage_total_tokens = 1500;
+        row.gen_ai_usage_web_search_calls = 2;
+        let pricing = PricingService::init_for_test().unwrap();
+        // Total stored at ingestion
+        row.gen_ai_cost_total = pricing
+            .calculate_cost(&SpanCostInput {
+                system: row.gen_ai_system.clone(),
+                model: row.gen_ai_request_model.clone(),
+                input_tokens: 1000,
+                output_tokens: 500,
+                total_tokens: 1500,
+                web_search_calls: 2,
+                ..Default::default()
+            })
+            .total_cost;
+
+        let dto = SpanCostDto::from_row(&row, &pricing, None);
+
+        // Medium search context: $0.035 per query
+        assert!((dto.tool_cost - 0.07).abs() < 1e-9);
+        let sum = dto.input_cost
+            + dto.output_cost
+            + dto.cache_read_cost
+            + dto.cache_write_cost
+            + dto.reasoning_cost
+            + dto.tool_cost;
+        assert!((dto.total_cost - sum).abs() < 1e-12);
+        assert!((dto.total_cost - row.gen_ai_cost_total).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_span_cost_breakdown_for_known_model() {
+        let mut row = span_row_with_attributes("{}");
+        row.gen_ai_system = Some("openai".to_string());
+        row.gen_ai_request_model = Some("gpt-4o".to_string());
+        row.gen_ai_usage_input_tokens = 1000;
+        row.gen_ai_usage_output_tokens = 500;
+        row.gen_ai_usage_total_tokens = 1500;
+        row.gen_ai_usage_cache_read_tokens = 200;
+        let pricing = PricingService::init_for_test().unwrap();
+
+        let dto = SpanCostDto::from_row(&row, &pricing, None);
+
+        assert_eq!(dto.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(dto.system.as_deref(), Some("openai"));
+        assert_eq!(dto.match_type, MatchType::Exact);
+        assert_eq!(dto.confidence, 1.0);
+        assert!(dto.input_cost > 0.0);
+        assert!(dto.output_cost > 0.0);
+        assert!(dto.cache_read_cost > 0.0);
+        let sum = dto.input_cost
+            + dto.output_cost
+            + dto.cache_read_cost
+            + dto.cache_write_cost
+            + dto.reasoning_cost
+            + dto.tool_cost;
+        assert!((dto.total_cost - sum).abs() < 1e-12);
+
+        let rates = dto.pricing.expect("rates for a matched model");
+        assert!((dto.input_cost - 1000.0 * rates.input_cost_per_token).abs() < 1e-12);
+
+        // The response model is priced when set, as at ingestion
+        row.gen_ai_response_model = Some("gpt-4o-mini".to_string());
+        let dto = SpanCostDto::from_row(&row, &pricing, None);
+        assert_eq!(dto.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(dto.pricing.unwrap().model, "gpt-4o-mini");
+
+        row.gen_ai_response_model = None;
+        row.gen_ai_request_model = Some("no-such-model-xyz".to_string());
+        let dto = SpanCostDto::from_row(&row, &pricing, None);
+        assert_eq!(dto.match_type, MatchType::NotFound);
+        assert_eq!(dto.total_cost, 0.0);
+        assert!(dto.pricing.is_none());
+        // Reading a breakdown does not count as an unmatched model
+        assert!(pricing.unmatched_models().is_empty());
+    }
+}
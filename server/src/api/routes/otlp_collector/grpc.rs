@@ -25,18 +25,24 @@ use opentelemetry_proto::tonic::collector::{
     },
 };
 
+use crate::api::auth::api_key::{validate_api_key_for_project, validate_api_key_general};
 use crate::api::extractors::is_valid_project_id;
 use crate::core::config::OtelConfig;
 use crate::core::constants::{OTLP_BODY_LIMIT, TOPIC_LOGS, TOPIC_METRICS, TOPIC_TRACES};
 use crate::core::storage::{AppStorage, DataSubdir};
 use crate::core::{Publisher, TopicService};
+use crate::data::TransactionalService;
+use crate::data::cache::CacheService;
 use crate::data::topics::StreamTopic;
+use crate::data::types::ApiKeyScope;
 use crate::utils::debug::write_debug;
 use crate::utils::otlp::{
     inject_project_id_logs, inject_project_id_metrics, inject_project_id_traces,
+    stamp_unscoped_org_id_traces,
 };
 
 const PROJECT_ID_HEADER: &str = "x-sideseat-project-id";
+const AUTHORIZATION_HEADER: &str = "authorization";
 const DEFAULT_PROJECT_ID: &str = "default";
 
 /// Maximum retry attempts for trace publish
@@ -51,6 +57,7 @@ pub struct OtlpGrpcServer {
     metrics_publisher: Publisher<ExportMetricsServiceRequest>,
     logs_publisher: Publisher<ExportLogsServiceRequest>,
     debug_path: Option<PathBuf>,
+    auth: GrpcAuth,
 }
 
 impl OtlpGrpcServer {
@@ -60,6 +67,7 @@ impl OtlpGrpcServer {
         topics: &Arc<TopicService>,
         storage: &AppStorage,
         debug: bool,
+        auth: GrpcAuth,
     ) -> Result<Self> {
         let addr = SocketAddr::new(host.parse()?, config.grpc_port);
         let debug_path = if debug {
@@ -83,6 +91,7 @@ impl OtlpGrpcServer {
             metrics_publisher,
             logs_publisher,
             debug_path,
+            auth,
         })
     }
 
@@ -97,6 +106,7 @@ impl OtlpGrpcServer {
                 TraceServiceServer::new(OtlpTraceService::new(
                     self.trace_topic,
                     debug_path.clone(),
+                    self.auth,
                 ))
                 .max_decoding_message_size(OTLP_BODY_LIMIT)
                 .max_encoding_message_size(OTLP_BODY_LIMIT),
@@ -127,17 +137,68 @@ impl OtlpGrpcServer {
 /// Extract project_id from gRPC metadata, defaulting to "default"
 /// Returns None if the provided project_id is invalid
 fn extract_project_id<T>(request: &Request<T>) -> Option<String> {
-    let project_id = request
+    let project_id = header_project_id(request).unwrap_or(DEFAULT_PROJECT_ID);
+    is_valid_project_id(project_id).then(|| project_id.to_string())
+}
+
+/// Raw project_id header value, if present and non-empty
+fn header_project_id<T>(request: &Request<T>) -> Option<&str> {
+    request
         .metadata()
         .get(PROJECT_ID_HEADER)
         .and_then(|v| v.to_str().ok())
         .filter(|s| !s.is_empty())
-        .unwrap_or(DEFAULT_PROJECT_ID);
+}
+
+/// API key validation for gRPC trace exports
+pub struct GrpcAuth {
+    pub database: Arc<TransactionalService>,
+    pub cache: Arc<CacheService>,
+    pub api_key_secret: Vec<u8>,
+}
 
-    if is_valid_project_id(project_id) {
-        Some(project_id.to_string())
-    } else {
-        None
+impl GrpcAuth {
+    /// Organization of the export's API key, if one was sent.
+    ///
+    /// A key must have `ingest` scope and, with a project header, own that project.
+    async fn org_id<T>(
+        &self,
+        request: &Request<T>,
+        project_id: Option<&str>,
+    ) -> Result<Option<String>, Status> {
+        let Some(auth_header) = request
+            .metadata()
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return Ok(None);
+        };
+        let validation = match project_id {
+            Some(project_id) => {
+                validate_api_key_for_project(
+                    &self.cache,
+                    self.database.clone(),
+                    &self.api_key_secret,
+                    auth_header,
+                    project_id,
+                    ApiKeyScope::Ingest,
+                )
+                .await
+            }
+            None => {
+                validate_api_key_general(
+                    &self.cache,
+                    self.database.clone(),
+                    &self.api_key_secret,
+                    auth_header,
+                    ApiKeyScope::Ingest,
+                )
+                .await
+            }
+        };
+        validation
+            .map(|v| Some(v.org_id))
+            .map_err(|e| Status::unauthenticated(e.to_string()))
     }
 }
 
@@ -145,14 +206,20 @@ fn extract_project_id<T>(request: &Request<T>) -> Option<String> {
 struct OtlpTraceService {
     topic: Arc<StreamTopic<ExportTraceServiceRequest>>,
     debug_path: Option<PathBuf>,
+    auth: GrpcAuth,
 }
 
 impl OtlpTraceService {
     fn new(
         topic: Arc<StreamTopic<ExportTraceServiceRequest>>,
         debug_path: Option<PathBuf>,
+        auth: GrpcAuth,
     ) -> Self {
-        Self { topic, debug_path }
+        Self {
+            topic,
+            debug_path,
+            auth,
+        }
     }
 }
 
@@ -162,16 +229,28 @@ impl TraceService for OtlpTraceService {
         &self,
         request: Request<ExportTraceServiceRequest>,
     ) -> Result<Response<ExportTraceServiceResponse>, Status> {
-        let project_id = extract_project_id(&request)
-            .ok_or_else(|| Status::invalid_argument("Invalid project_id"))?;
+        // Without the header, resources keep their own project_id; the rest
+        // are assigned the default project of the API key's org (or the
+        // default org) by the trace pipeline
+        let project_id = header_project_id(&request).map(str::to_string);
+        if project_id
+            .as_deref()
+            .is_some_and(|id| !is_valid_project_id(id))
+        {
+            return Err(Status::invalid_argument("Invalid project_id"));
+        }
+        let org_id = self.auth.org_id(&request, project_id.as_deref()).await?;
         let mut req = request.into_inner();
 
-        // Inject project_id into resource attributes
-        inject_project_id_traces(&mut req, &project_id);
+        if let Some(ref project_id) = project_id {
+            inject_project_id_traces(&mut req, project_id);
+        }
+        stamp_unscoped_org_id_traces(&mut req, org_id.as_deref());
 
         // Write to debug file if debug mode is enabled
         if let Some(ref debug_path) = self.debug_path {
-            write_debug(debug_path, "traces.jsonl", &project_id, &req).await;
+            let label = project_id.as_deref().unwrap_or(DEFAULT_PROJECT_ID);
+            write_debug(debug_path, "traces.jsonl", label, &req).await;
         }
 
         // Publish to stream topic with retry (at-least-once delivery)
@@ -3,15 +3,18 @@
 use std::sync::Arc;
 
 use axum::extract::State;
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
-use crate::api::extractors::ValidatedJson;
+use crate::api::extractors::{ValidatedJson, ValidatedQuery};
 use crate::api::types::ApiError;
-use crate::domain::pricing::{MatchType, ModelPricing, PricingService, SpanCostInput};
+use crate::domain::pricing::{
+    HeldRateChange, MatchType, ModelCapabilities, ModelNormalization, ModelPricing, PricingService,
+    SpanCostInput, UnmatchedModel,
+};
 
 // ============================================================================
 // State
@@ -41,6 +44,11 @@ pub struct CalculateCostRequest {
     pub cache_write_tokens: i64,
     #[serde(default)]
     pub reasoning_tokens: i64,
+    /// Built-in web search calls, billed per query where the model has a rate
+    #[serde(default)]
+    pub web_search_calls: i64,
+    /// Embedding output dimensions, for models with dimension-tiered rates
+    pub embedding_dimensions: Option<i64>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -50,15 +58,21 @@ pub struct CalculateCostResponse {
     pub cache_read_cost: f64,
     pub cache_write_cost: f64,
     pub reasoning_cost: f64,
+    /// Per-call surcharges for built-in tools
+    pub tool_cost: f64,
     pub total_cost: f64,
+    /// Saved by cache reads versus paying the full input rate for them
+    pub cache_savings: f64,
     pub match_type: MatchType,
     pub confidence: f64,
 }
 
-#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
 pub struct ModelPricingRequest {
     #[validate(length(min = 1, max = 256))]
     pub model: String,
+    /// Provider, also accepted as `system` (the span's `gen_ai.system`)
+    #[serde(alias = "system")]
     pub provider: Option<String>,
 }
 
@@ -71,13 +85,20 @@ pub struct ModelPricingResponse {
     pub cache_read_input_token_cost: f64,
     pub cache_creation_input_token_cost: f64,
     pub output_cost_per_reasoning_token: f64,
+    /// Surcharge per built-in web search call (0 when not priced)
+    pub web_search_cost_per_query: f64,
     pub mode: String,
+    /// Currency of the rates above when not USD (costs are converted to USD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
     pub match_type: MatchType,
     pub confidence: f64,
+    /// Context limits and feature support, when known
+    pub capabilities: ModelCapabilities,
 }
 
 impl ModelPricingResponse {
-    fn from_pricing(
+    pub(crate) fn from_pricing(
         model: String,
         provider: Option<String>,
         pricing: ModelPricing,
@@ -91,13 +112,78 @@ impl ModelPricingResponse {
             cache_read_input_token_cost: pricing.cache_read_input_token_cost,
             cache_creation_input_token_cost: pricing.cache_creation_input_token_cost,
             output_cost_per_reasoning_token: pricing.output_cost_per_reasoning_token,
+            web_search_cost_per_query: pricing.web_search_cost_per_query,
             mode: pricing.mode,
+            currency: pricing.currency,
             match_type,
             confidence: match_type.confidence(),
+            capabilities: pricing.capabilities,
         }
     }
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PricingLookupRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "models must contain 1-100 items"),
+        nested
+    )]
+    pub models: Vec<ModelPricingRequest>,
+}
+
+/// Lookup result for one requested model
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PricingLookupEntry {
+    pub model: String,
+    pub provider: Option<String>,
+    pub match_type: MatchType,
+    /// Rates and capabilities (absent when `match_type` is `not_found`)
+    pub pricing: Option<ModelPricingResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PricingLookupResponse {
+    /// One entry per requested model, in request order
+    pub results: Vec<PricingLookupEntry>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct FamilyPricingRequest {
+    /// Model family, e.g. "claude-sonnet-4-5" (dated or provider-specific IDs also work)
+    #[validate(length(min = 1, max = 256))]
+    pub family: String,
+}
+
+/// Per-token rates for one provider serving a model family
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProviderRatesDto {
+    /// LiteLLM provider name
+    pub provider: String,
+    /// Pricing key the rates were taken from
+    pub model: String,
+    pub input_cost_per_token: f64,
+    pub output_cost_per_token: f64,
+    pub cache_read_input_token_cost: f64,
+    pub cache_creation_input_token_cost: f64,
+    pub output_cost_per_reasoning_token: f64,
+}
+
+/// Query params for previewing model name normalization
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct NormalizeModelQuery {
+    #[validate(length(min = 1, max = 256))]
+    pub model: String,
+    /// The span's `gen_ai.system`, used for provider-prefixed matches
+    pub system: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FamilyPricingResponse {
+    pub family: String,
+    /// One entry per provider, sorted by provider name
+    pub providers: Vec<ProviderRatesDto>,
+}
+
 // ============================================================================
 // Routes
 // ============================================================================
@@ -107,6 +193,11 @@ pub fn routes(pricing: Arc<PricingService>) -> Router<()> {
     Router::new()
         .route("/calculate", post(calculate_cost))
         .route("/models", post(get_model_pricing))
+        .route("/lookup", post(lookup_model_pricing))
+        .route("/compare", post(compare_family_pricing))
+        .route("/normalize", get(normalize_model))
+        .route("/held", get(list_held_rate_changes))
+        .route("/unmatched", get(list_unmatched_models))
         .with_state(state)
 }
 
@@ -137,6 +228,10 @@ pub async fn calculate_cost(
         cache_read_tokens: req.cache_read_tokens,
         cache_write_tokens: req.cache_write_tokens,
         reasoning_tokens: req.reasoning_tokens,
+        web_search_calls: req.web_search_calls,
+        embedding_dimensions: req.embedding_dimensions,
+        estimated: false,
+        org_id: None,
     };
 
     let output = state.pricing.calculate_cost(&input);
@@ -147,7 +242,9 @@ pub async fn calculate_cost(
         cache_read_cost: output.cache_read_cost,
         cache_write_cost: output.cache_write_cost,
         reasoning_cost: output.reasoning_cost,
+        tool_cost: output.tool_cost,
         total_cost: output.total_cost,
+        cache_savings: output.cache_savings,
         match_type: output.match_type.unwrap_or_default(),
         confidence: output.confidence(),
     }))
@@ -185,3 +282,245 @@ pub async fn get_model_pricing(
         )),
     }
 }
+
+/// Get pricing information for a batch of models
+///
+/// Unknown models are reported per entry with `match_type: not_found` instead
+/// of failing the whole request.
+#[utoipa::path(
+    post,
+    path = "/api/v1/pricing/lookup",
+    tag = "pricing",
+    request_body = PricingLookupRequest,
+    responses(
+        (status = 200, description = "Pricing per requested model", body = PricingLookupResponse),
+        (status = 400, description = "Empty or oversized batch")
+    )
+)]
+pub async fn lookup_model_pricing(
+    State(state): State<PricingApiState>,
+    ValidatedJson(req): ValidatedJson<PricingLookupRequest>,
+) -> Json<PricingLookupResponse> {
+    let results = req
+        .models
+        .into_iter()
+        .map(|entry| {
+            match state
+                .pricing
+                .get_model_pricing(entry.provider.as_deref(), &entry.model)
+            {
+                Some((pricing, match_type)) => PricingLookupEntry {
+                    model: entry.model.clone(),
+                    provider: entry.provider.clone(),
+                    match_type,
+                    pricing: Some(ModelPricingResponse::from_pricing(
+                        entry.model,
+                        entry.provider,
+                        pricing,
+                        match_type,
+                    )),
+                },
+                None => PricingLookupEntry {
+                    model: entry.model,
+                    provider: entry.provider,
+                    match_type: MatchType::NotFound,
+                    pricing: None,
+                },
+            }
+        })
+        .collect();
+
+    Json(PricingLookupResponse { results })
+}
+
+/// List synced price changes held back for review
+///
+/// Models whose synced rates moved by more than `pricing.max_rate_change_ratio`
+/// keep their previous rates until listed in `pricing.accepted_rate_changes`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/pricing/held",
+    tag = "pricing",
+    responses(
+        (status = 200, description = "Held price changes from the last sync", body = Vec<HeldRateChange>)
+    )
+)]
+pub async fn list_held_rate_changes(
+    State(state): State<PricingApiState>,
+) -> Json<Vec<HeldRateChange>> {
+    Json(state.pricing.held_rate_changes())
+}
+
+/// List models seen in spans that matched no pricing entry
+///
+/// Deduplicated by system and model with hit counts, most frequent first.
+/// Persisted across restarts when `pricing.persist_unmatched_models` is set.
+#[utoipa::path(
+    get,
+    path = "/api/v1/pricing/unmatched",
+    tag = "pricing",
+    responses(
+        (status = 200, description = "Unmatched models", body = Vec<UnmatchedModel>)
+    )
+)]
+pub async fn list_unmatched_models(
+    State(state): State<PricingApiState>,
+) -> Json<Vec<UnmatchedModel>> {
+    Json(state.pricing.unmatched_models())
+}
+
+/// Compare per-token rates for a model family across providers
+#[utoipa::path(
+    post,
+    path = "/api/v1/pricing/compare",
+    tag = "pricing",
+    request_body = FamilyPricingRequest,
+    responses(
+        (status = 200, description = "Rates per provider", body = FamilyPricingResponse),
+        (status = 404, description = "No provider prices this model family")
+    )
+)]
+pub async fn compare_family_pricing(
+    State(state): State<PricingApiState>,
+    ValidatedJson(req): ValidatedJson<FamilyPricingRequest>,
+) -> Result<Json<FamilyPricingResponse>, ApiError> {
+    let providers: Vec<ProviderRatesDto> = state
+        .pricing
+        .get_family_pricing(&req.family)
+        .into_iter()
+        .map(|(model, pricing)| ProviderRatesDto {
+            provider: pricing.litellm_provider,
+            model,
+            input_cost_per_token: pricing.input_cost_per_token,
+            output_cost_per_token: pricing.output_cost_per_token,
+            cache_read_input_token_cost: pricing.cache_read_input_token_cost,
+            cache_creation_input_token_cost: pricing.cache_creation_input_token_cost,
+            output_cost_per_reasoning_token: pricing.output_cost_per_reasoning_token,
+        })
+        .collect();
+
+    if providers.is_empty() {
+        return Err(ApiError::not_found(
+            "MODEL_NOT_FOUND",
+            format!("Model family not found: {}", req.family),
+        ));
+    }
+
+    Ok(Json(FamilyPricingResponse {
+        family: req.family,
+        providers,
+    }))
+}
+
+/// Show how a model name is normalized for pricing lookup
+///
+/// Lists each normalized form in lookup order with the pricing key it matched,
+/// to diagnose why a model was not priced.
+#[utoipa::path(
+    get,
+    path = "/api/v1/pricing/normalize",
+    tag = "pricing",
+    params(
+        ("model" = String, Query, description = "Model name as reported by the span"),
+        ("system" = Option<String>, Query, description = "Provider (`gen_ai.system`)")
+    ),
+    responses(
+        (status = 200, description = "Normalized forms and match result", body = ModelNormalization),
+        (status = 400, description = "Missing or oversized model")
+    )
+)]
+pub async fn normalize_model(
+    State(state): State<PricingApiState>,
+    ValidatedQuery(query): ValidatedQuery<NormalizeModelQuery>,
+) -> Json<ModelNormalization> {
+    Json(
+        state
+            .pricing
+            .normalize_model(query.system.as_deref(), &query.model),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::pricing::NormalizationStep;
+
+    #[tokio::test]
+    async fn test_lookup_reports_each_model_including_not_found() {
+        let state = PricingApiState {
+            pricing: Arc::new(PricingService::init_for_test().unwrap()),
+        };
+        let req: PricingLookupRequest = serde_json::from_value(serde_json::json!({
+            "models": [
+                {"system": "openai", "model": "gpt-4o"},
+                {"model": "claude-sonnet-4-5"},
+                {"system": "openai", "model": "no-such-model-xyz"}
+            ]
+        }))
+        .unwrap();
+        assert!(req.validate().is_ok());
+
+        let Json(response) = lookup_model_pricing(State(state), ValidatedJson(req)).await;
+        let results = response.results;
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].model, "gpt-4o");
+        assert_eq!(results[0].provider.as_deref(), Some("openai"));
+        assert_ne!(results[0].match_type, MatchType::NotFound);
+        assert!(results[0].pricing.as_ref().unwrap().input_cost_per_token > 0.0);
+
+        assert_eq!(results[1].model, "claude-sonnet-4-5");
+        assert!(results[1].pricing.is_some());
+
+        assert_eq!(results[2].model, "no-such-model-xyz");
+        assert_eq!(results[2].match_type, MatchType::NotFound);
+        assert!(results[2].pricing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_normalize_shows_date_stripped_family_form() {
+        let state = PricingApiState {
+            pricing: Arc::new(PricingService::init_for_test().unwrap()),
+        };
+        let query = NormalizeModelQuery {
+            model: "Claude-Sonnet-4-5-20250929".to_string(),
+            system: Some("anthropic".to_string()),
+        };
+
+        let Json(response) = normalize_model(State(state), ValidatedQuery(query)).await;
+        assert_eq!(response.provider.as_deref(), Some("anthropic"));
+        assert_ne!(response.match_type, MatchType::NotFound);
+
+        let form = |step: NormalizationStep| {
+            response
+                .steps
+                .iter()
+                .find(|s| s.step == step)
+                .and_then(|s| s.form.as_deref())
+        };
+        assert_eq!(
+            form(NormalizationStep::Lowercase),
+            Some("claude-sonnet-4-5-20250929")
+        );
+        assert_eq!(
+            form(NormalizationStep::StripDateSuffix),
+            Some("claude-sonnet-4-5")
+        );
+        assert_eq!(form(NormalizationStep::BedrockRegionStrip), None);
+        assert_eq!(form(NormalizationStep::FinetuneBase), None);
+    }
+
+    #[test]
+    fn test_lookup_batch_size_is_capped() {
+        let models = (0..101)
+            .map(|i| serde_json::json!({"model": format!("model-{i}")}))
+            .collect::<Vec<_>>();
+        let req: PricingLookupRequest =
+            serde_json::from_value(serde_json::json!({ "models": models })).unwrap();
+        assert!(req.validate().is_err());
+
+        let empty: PricingLookupRequest =
+            serde_json::from_value(serde_json::json!({"models": []})).unwrap();
+        assert!(empty.validate().is_err());
+    }
+}
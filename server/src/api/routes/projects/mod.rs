@@ -6,20 +6,23 @@ use std::sync::Arc;
 
 use axum::extract::State;
 use axum::http::StatusCode;
-use axum::routing::get;
+use axum::routing::{delete, get};
 use axum::{Json, Router};
 
 use crate::api::auth::{Auth, AuthContext, AuthService, ProjectFull, ProjectRead, ProjectWrite};
 use crate::api::extractors::{ValidatedJson, ValidatedQuery};
-use crate::api::types::{ApiError, PaginatedResponse};
+use crate::api::types::{ApiError, MAX_DELETE_BATCH, PaginatedResponse};
 use crate::core::constants::{DEFAULT_PROJECT_ID, ORG_ROLE_ADMIN, ORG_ROLE_MEMBER};
 use crate::data::cache::CacheService;
 use crate::data::cleanup::cleanup_project;
 use crate::data::files::FileService;
-use crate::data::types::ApiKeyScope;
+use crate::data::types::{ApiKeyScope, TraceDeleteFilter};
 use crate::data::{AnalyticsService, TransactionalService};
 
-use types::{CreateProjectRequest, ListProjectsQuery, ProjectDto, UpdateProjectRequest};
+use types::{
+    CreateProjectRequest, DeleteProjectTracesRequest, DeleteProjectTracesResponse,
+    ListProjectsQuery, ProjectDto, UpdateProjectRequest,
+};
 
 /// Shared state for Projects API endpoints
 #[derive(Clone)]
@@ -50,6 +53,7 @@ pub fn routes(
             "/{project_id}",
             get(get_project).put(update_project).delete(delete_project),
         )
+        .route("/{project_id}/traces", delete(delete_project_traces))
         .with_state(state)
 }
 
@@ -297,3 +301,217 @@ pub async fn delete_project(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Bulk-delete a project's traces matching a filter (admin+ role required)
+///
+/// Matching traces are deleted in batches, each batch in its own analytics
+/// transaction, together with their messages, files and favorites. An empty
+/// filter is rejected unless `confirm_all` is set.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/projects/{project_id}/traces",
+    tag = "projects",
+    params(
+        ("project_id" = String, Path, description = "Project ID")
+    ),
+    request_body = DeleteProjectTracesRequest,
+    responses(
+        (status = 200, description = "Traces deleted", body = DeleteProjectTracesResponse),
+        (status = 400, description = "Empty filter without confirm_all"),
+        (status = 403, description = "Insufficient permissions")
+    )
+)]
+pub async fn delete_project_traces(
+    State(state): State<ProjectsApiState>,
+    project: ProjectFull,
+    auth_service: axum::Extension<Arc<AuthService>>,
+    ValidatedJson(body): ValidatedJson<DeleteProjectTracesRequest>,
+) -> Result<Json<DeleteProjectTracesResponse>, ApiError> {
+    // Verify admin role in project's org
+    auth_service
+        .verify_org_role(
+            &project.auth,
+            &project.org_id,
+            ApiKeyScope::Full,
+            ORG_ROLE_ADMIN,
+        )
+        .await?;
+
+    let filter = body.filter();
+    if filter.is_empty() && !body.confirm_all {
+        return Err(ApiError::bad_request(
+            "FILTER_REQUIRED",
+            "Provide at least one filter, or set confirm_all to delete every trace",
+        ));
+    }
+
+    let deleted =
+        delete_matching_traces(&state, &project.project_id, &filter, MAX_DELETE_BATCH).await?;
+
+    tracing::info!(
+        project_id = %project.project_id,
+        deleted,
+        "Bulk deleted traces"
+    );
+
+    Ok(Json(DeleteProjectTracesResponse { deleted }))
+}
+
+/// Delete matching traces one page of `batch_size` trace IDs at a time, so the
+/// matches are never all loaded at once.
+///
+/// Pages are fetched by trace_id after the previous page, which also holds on
+/// backends where deletes apply asynchronously (ClickHouse mutations).
+async fn delete_matching_traces(
+    state: &ProjectsApiState,
+    project_id: &str,
+    filter: &TraceDeleteFilter,
+    batch_size: usize,
+) -> Result<u64, ApiError> {
+    let analytics_repo = state.analytics.repository();
+    let repo = state.database.repository();
+    let mut deleted = 0;
+    let mut after: Option<String> = None;
+    loop {
+        let batch = analytics_repo
+            .get_trace_ids_matching(project_id, filter, after.as_deref(), batch_size)
+            .await
+            .map_err(ApiError::from_data)?;
+        if batch.is_empty() {
+            break;
+        }
+        analytics_repo
+            .delete_traces(project_id, &batch)
+            .await
+            .map_err(ApiError::from_data)?;
+        deleted += batch.len() as u64;
+
+        if let Err(e) = state.file_service.cleanup_traces(project_id, &batch).await {
+            tracing::warn!(
+                error = %e,
+                project_id = %project_id,
+                traces = batch.len(),
+                "Failed to cleanup files after bulk trace deletion"
+            );
+        }
+        if let Err(e) = repo
+            .delete_favorites_by_entity("trace", &batch, project_id)
+            .await
+        {
+            tracing::warn!(
+                error = %e,
+                project_id = %project_id,
+                traces = batch.len(),
+                "Failed to cleanup favorites after bulk trace deletion"
+            );
+        }
+
+        if batch.len() < batch_size {
+            break;
+        }
+        after = batch.last().cloned();
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{
+        CacheBackendType, CacheConfig, EvictionPolicy, FilesConfig, StorageBackend,
+    };
+    use crate::core::storage::AppStorage;
+    use crate::data::duckdb::DuckdbService;
+    use crate::data::sqlite::SqliteService;
+    use crate::data::types::NormalizedSpan;
+
+    async fn projects_state(temp_dir: &tempfile::TempDir) -> ProjectsApiState {
+        let pool = sqlx::SqlitePool::connect(":memory:").await.unwrap();
+        for statement in crate::data::sqlite::schema::SCHEMA
+            .split(';')
+            .filter(|s| !s.trim().is_empty())
+        {
+            sqlx::query(statement.trim()).execute(&pool).await.unwrap();
+        }
+        let database = Arc::new(TransactionalService::Sqlite(Arc::new(
+            SqliteService::from_pool(pool),
+        )));
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        let analytics = Arc::new(AnalyticsService::Duckdb(Arc::new(
+            DuckdbService::init(&storage).await.unwrap(),
+        )));
+        let cache = Arc::new(
+            CacheService::new(&CacheConfig {
+                backend: CacheBackendType::Memory,
+                max_entries: 100,
+                eviction_policy: EvictionPolicy::TinyLfu,
+                redis_url: None,
+                redis_pool_size: 1,
+            })
+            .await
+            .unwrap(),
+        );
+        let files_config = FilesConfig {
+            enabled: false,
+            storage: StorageBackend::Filesystem,
+            quota_bytes: 1024 * 1024,
+            filesystem_path: Some(temp_dir.path().join("files").to_string_lossy().to_string()),
+            s3: None,
+        };
+        let file_service = Arc::new(
+            FileService::new(files_config, &storage, database.clone(), cache.clone())
+                .await
+                .unwrap(),
+        );
+        ProjectsApiState {
+            database,
+            analytics,
+            file_service,
+            cache,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_matching_traces_pages_through_matches() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state = projects_state(&temp_dir).await;
+        let span = |trace_id: &str, environment: &str| NormalizedSpan {
+            project_id: Some("p1".to_string()),
+            trace_id: trace_id.to_string(),
+            span_id: format!("{trace_id}-root"),
+            environment: Some(environment.to_string()),
+            ..Default::default()
+        };
+        let mut spans: Vec<NormalizedSpan> = (1..=5)
+            .map(|i| span(&format!("test-{i}"), "test"))
+            .collect();
+        spans.push(span("prod-1", "production"));
+        state
+            .analytics
+            .repository()
+            .insert_spans(spans)
+            .await
+            .unwrap();
+
+        let filter = TraceDeleteFilter {
+            environment: Some("test".to_string()),
+            ..Default::default()
+        };
+        // Five matches in pages of two
+        let deleted = delete_matching_traces(&state, "p1", &filter, 2)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 5);
+
+        let remaining = state
+            .analytics
+            .repository()
+            .get_trace_ids_matching("p1", &TraceDeleteFilter::default(), None, 100)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["prod-1"]);
+    }
+}
@@ -6,7 +6,7 @@ use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::api::types::{default_limit, default_page, validate_limit, validate_page};
-use crate::data::types::ProjectRow;
+use crate::data::types::{ProjectRow, TraceDeleteFilter};
 
 /// Project DTO for API responses
 #[derive(Debug, Serialize, ToSchema)]
@@ -66,3 +66,40 @@ pub struct ListProjectsQuery {
     /// Optional organization ID filter
     pub org_id: Option<String>,
 }
+
+/// Request body for bulk-deleting a project's traces by filter
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DeleteProjectTracesRequest {
+    /// Only traces with a span starting at or after this time
+    pub from_timestamp: Option<DateTime<Utc>>,
+    /// Only traces with a span starting at or before this time
+    pub to_timestamp: Option<DateTime<Utc>>,
+    #[validate(length(min = 1, max = 256))]
+    pub environment: Option<String>,
+    #[validate(length(min = 1, max = 256))]
+    pub session_id: Option<String>,
+    /// Only traces with (true) or without (false) an ERROR span
+    pub has_error: Option<bool>,
+    /// Required to delete every trace in the project when no filter is set
+    #[serde(default)]
+    pub confirm_all: bool,
+}
+
+impl DeleteProjectTracesRequest {
+    pub fn filter(&self) -> TraceDeleteFilter {
+        TraceDeleteFilter {
+            from_timestamp: self.from_timestamp,
+            to_timestamp: self.to_timestamp,
+            environment: self.environment.clone(),
+            session_id: self.session_id.clone(),
+            has_error: self.has_error,
+        }
+    }
+}
+
+/// Response for bulk trace deletion
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteProjectTracesResponse {
+    /// Number of traces deleted
+    pub deleted: u64,
+}
@@ -141,14 +141,19 @@ impl ApiServer {
         };
 
         // Build otel query routes (rate limited by IP if enabled)
-        let otel_query_routes = otel::routes(
-            app.analytics.clone(),
-            app.topics.clone(),
-            app.files.clone(),
-            app.database.clone(),
-            app.cache.clone(),
-            app.shutdown.subscribe(),
-        )
+        let otel_query_routes = otel::routes(otel::OtelApiState {
+            analytics: app.analytics.clone(),
+            topics: app.topics.clone(),
+            file_service: app.files.clone(),
+            database: app.database.clone(),
+            cache: app.cache.clone(),
+            shutdown_rx: app.shutdown.subscribe(),
+            sampling: app.config.otel.sampling.clone(),
+            promoted_attributes: app.config.otel.promoted_attributes.clone(),
+            feed: app.config.otel.feed.clone(),
+            pricing: app.pricing.clone(),
+            ingestion: app.config.otel.ingestion.clone(),
+        })
         .layer(axum::middleware::from_fn_with_state(
             AuthState {
                 auth_manager: auth_manager.clone(),
@@ -430,9 +435,25 @@ impl ApiServer {
         // store, same topics) so HTTP→WS bridging routes correctly.
         let agui_routes = super::routes::agui::routes(ws_state);
 
+        let readiness_state = health::ReadinessState {
+            database: app.database.clone(),
+            analytics: app.analytics.clone(),
+            topics: app.topics.clone(),
+            pricing: app.pricing.clone(),
+        };
+
         let router = Router::new()
             .route("/", get(|| async { Redirect::temporary("/ui") }))
             .route("/api/v1/health", get(health::health))
+            .route("/healthz", get(health::liveness))
+            .route(
+                "/readyz",
+                get(health::readiness).with_state(readiness_state),
+            )
+            .route(
+                "/api/v1/metrics",
+                get(health::metrics).with_state(app.topics.clone()),
+            )
             .route("/api/openapi.json", get(openapi_json))
             .route("/api/docs", get(swagger_ui_html))
             .route("/api/docs/", get(swagger_ui_html))
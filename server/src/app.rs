@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
-use crate::api::{ApiServer, AuthManager, OtlpGrpcServer};
+use crate::api::{ApiServer, AuthManager, GrpcAuth, OtlpGrpcServer};
 use opentelemetry_proto::tonic::collector::{
     metrics::v1::ExportMetricsServiceRequest, trace::v1::ExportTraceServiceRequest,
 };
@@ -13,7 +13,9 @@ use crate::core::TopicService;
 use crate::core::banner;
 use crate::core::cli::{self, CliConfig, Commands, SystemCommands};
 use crate::core::config::AppConfig;
-use crate::core::constants::{APP_NAME_LOWER, ENV_LOG, TOPIC_METRICS, TOPIC_TRACES};
+use crate::core::constants::{
+    APP_NAME_LOWER, ENV_LOG, TOPIC_METRICS, TOPIC_PROCESSED_TRACES, TOPIC_TRACES,
+};
 use crate::core::shutdown::ShutdownService;
 use crate::core::storage::AppStorage;
 use crate::core::update;
@@ -105,10 +107,6 @@ impl CoreApp {
 
         let database = Arc::new(database);
         let analytics = Arc::new(analytics);
-        let pricing = PricingService::init(&storage, config.pricing.sync_hours)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to initialize pricing service: {}", e))?;
-        let auth = Arc::new(AuthManager::init(&secrets, config.auth.enabled).await?);
         let topics = Arc::new(
             crate::data::topics::TopicService::from_cache_config(&config.database.cache_config())
                 .await
@@ -116,6 +114,10 @@ impl CoreApp {
         );
 
         tracing::debug!(backend = topics.backend_name(), "Topics initialized");
+        let pricing = PricingService::init(&storage, &config.pricing, topics.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to initialize pricing service: {}", e))?;
+        let auth = Arc::new(AuthManager::init(&secrets, config.auth.enabled).await?);
         let files = Arc::new(
             FileService::new(
                 config.files.clone(),
@@ -234,12 +236,18 @@ impl CoreApp {
 
         // Start OTLP gRPC server if enabled
         if app.config.otel.grpc_enabled {
+            let grpc_auth = GrpcAuth {
+                database: app.database.clone(),
+                cache: app.cache.clone(),
+                api_key_secret: app.secrets.get_api_key_secret().await?,
+            };
             let grpc_server = OtlpGrpcServer::new(
                 &app.config.otel,
                 &app.config.server.host,
                 &app.topics,
                 &app.storage,
                 app.config.debug,
+                grpc_auth,
             )?;
             let shutdown_rx = app.shutdown.subscribe();
             let handle = tokio::spawn(async move {
@@ -300,9 +308,24 @@ impl CoreApp {
             self.shutdown.register(h).await;
         }
 
+        if let Some(h) = self.pricing.start_sync_task(
+            self.config.pricing.sync_hours,
+            self.cache.clone(),
+            self.shutdown.subscribe(),
+        ) {
+            self.shutdown.register(h).await;
+        }
+
+        if let Some(h) = self
+            .pricing
+            .start_unmatched_flush_task(self.shutdown.subscribe())
+        {
+            self.shutdown.register(h).await;
+        }
+
         if let Some(h) = self
             .pricing
-            .start_sync_task(self.config.pricing.sync_hours, self.shutdown.subscribe())
+            .start_update_listener(self.config.pricing.sync_hours, self.shutdown.subscribe())
         {
             self.shutdown.register(h).await;
         }
@@ -317,12 +340,29 @@ impl CoreApp {
             self.pricing.clone(),
             self.topics.clone(),
             self.files.clone(),
+            self.database.clone(),
+            self.cache.clone(),
+            self.config.otel.ingestion.clone(),
+            self.config.otel.retention.max_age_minutes,
         );
 
         self.shutdown
             .register(pipeline.start(traces_topic, self.shutdown.subscribe()))
             .await;
 
+        // Durable backends route UI notifications through a stream so each batch
+        // is broadcast once across instances; memory broadcasts directly
+        if self.topics.capabilities().durable_streams {
+            let bridge = crate::domain::NotificationBridge::new(
+                self.topics.clone(),
+                self.config.otel.ingestion.worker_id.clone(),
+            );
+            let processed_topic = self.topics.stream_topic(TOPIC_PROCESSED_TRACES);
+            self.shutdown
+                .register(bridge.start(processed_topic, self.shutdown.subscribe()))
+                .await;
+        }
+
         // Metrics pipeline
         let metrics_topic = self
             .topics
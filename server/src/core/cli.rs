@@ -13,7 +13,7 @@ use super::constants::{
     ENV_FILES_S3_PREFIX, ENV_FILES_S3_REGION, ENV_FILES_STORAGE, ENV_HOST, ENV_MCP_ENABLED,
     ENV_NO_UPDATE_CHECK, ENV_OTEL_AUTH_REQUIRED, ENV_OTEL_GRPC_ENABLED, ENV_OTEL_GRPC_PORT,
     ENV_OTEL_RETENTION_MAX_AGE_MINUTES, ENV_OTEL_RETENTION_MAX_SPANS, ENV_PORT, ENV_POSTGRES_URL,
-    ENV_PRICING_SYNC_HOURS, ENV_RATE_LIMIT_API_RPM, ENV_RATE_LIMIT_AUTH_RPM,
+    ENV_PRICING_FILE, ENV_PRICING_SYNC_HOURS, ENV_RATE_LIMIT_API_RPM, ENV_RATE_LIMIT_AUTH_RPM,
     ENV_RATE_LIMIT_BYPASS_HEADER, ENV_RATE_LIMIT_ENABLED, ENV_RATE_LIMIT_FILES_RPM,
     ENV_RATE_LIMIT_INGESTION_RPM, ENV_RATE_LIMIT_PER_IP, ENV_SECRETS_BACKEND,
     ENV_TRANSACTIONAL_BACKEND,
@@ -70,6 +70,10 @@ pub struct Cli {
     #[arg(long, global = true, env = ENV_PRICING_SYNC_HOURS)]
     pub pricing_sync_hours: Option<u64>,
 
+    /// Pricing file used instead of synced and embedded data
+    #[arg(long, global = true, env = ENV_PRICING_FILE)]
+    pub pricing_file: Option<PathBuf>,
+
     /// Disable update check on startup
     #[arg(long, global = true, env = ENV_NO_UPDATE_CHECK)]
     pub no_update_check: bool,
@@ -293,6 +297,7 @@ pub struct CliConfig {
     pub otel_retention_max_spans: Option<u64>,
     pub otel_auth_required: Option<bool>,
     pub pricing_sync_hours: Option<u64>,
+    pub pricing_file: Option<PathBuf>,
     pub no_update_check: bool,
     pub files_enabled: Option<bool>,
     pub mcp: Option<bool>,
@@ -336,6 +341,7 @@ pub fn parse() -> (CliConfig, Option<Commands>) {
         otel_retention_max_spans: cli.otel_retention_max_spans,
         otel_auth_required: cli.otel_auth_required,
         pricing_sync_hours: cli.pricing_sync_hours,
+        pricing_file: cli.pricing_file,
         no_update_check: cli.no_update_check,
         files_enabled: cli.files_enabled,
         mcp: cli.mcp,
@@ -1,24 +1,31 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::TimeDelta;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use crate::domain::sideml::{ChatRole, DefaultRoles, EventNames};
 use crate::utils::file::expand_path;
 
 use super::cli::CliConfig;
 use super::constants::{
-    APP_DOT_FOLDER, CONFIG_FILE_NAME, DEFAULT_CACHE_MAX_ENTRIES, DEFAULT_HOST,
-    DEFAULT_OTEL_GRPC_PORT, DEFAULT_OTEL_RETENTION_MAX_SPANS, DEFAULT_PORT,
+    APP_DOT_FOLDER, CONFIG_FILE_NAME, DEFAULT_CACHE_MAX_ENTRIES, DEFAULT_CACHED_INPUT_PROVIDERS,
+    DEFAULT_FEED_CLOCK_SKEW_TOLERANCE_MS, DEFAULT_FEED_MAX_BLOCKS, DEFAULT_FEED_MAX_PREFIX_ENTRIES,
+    DEFAULT_FEED_MAX_SESSION_TRACES, DEFAULT_FEED_TOOL_RESULT_MAX_BYTES, DEFAULT_HOST,
+    DEFAULT_MAX_FUTURE_SKEW_SECS, DEFAULT_OTEL_GRPC_PORT, DEFAULT_OTEL_RETENTION_MAX_SPANS,
+    DEFAULT_PORT, DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO, DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO,
     DEFAULT_RATE_LIMIT_API_RPM, DEFAULT_RATE_LIMIT_AUTH_RPM, DEFAULT_RATE_LIMIT_FILES_RPM,
-    DEFAULT_RATE_LIMIT_INGESTION_RPM, ENV_SECRETS_AWS_PREFIX, ENV_SECRETS_AWS_REGION,
-    ENV_SECRETS_ENV_PREFIX, ENV_SECRETS_VAULT_ADDR, ENV_SECRETS_VAULT_MOUNT,
-    ENV_SECRETS_VAULT_PREFIX, ENV_SECRETS_VAULT_TOKEN, FILES_DEFAULT_QUOTA_BYTES,
-    FILES_DEFAULT_S3_PREFIX, POSTGRES_DEFAULT_ACQUIRE_TIMEOUT_SECS,
+    DEFAULT_RATE_LIMIT_INGESTION_RPM, DEFAULT_UNMATCHED_MODELS_LIMIT, ENV_SECRETS_AWS_PREFIX,
+    ENV_SECRETS_AWS_REGION, ENV_SECRETS_ENV_PREFIX, ENV_SECRETS_VAULT_ADDR,
+    ENV_SECRETS_VAULT_MOUNT, ENV_SECRETS_VAULT_PREFIX, ENV_SECRETS_VAULT_TOKEN, ENV_WORKER_ID,
+    FILES_DEFAULT_QUOTA_BYTES, FILES_DEFAULT_S3_PREFIX, POSTGRES_DEFAULT_ACQUIRE_TIMEOUT_SECS,
     POSTGRES_DEFAULT_IDLE_TIMEOUT_SECS, POSTGRES_DEFAULT_MAX_CONNECTIONS,
     POSTGRES_DEFAULT_MAX_LIFETIME_SECS, POSTGRES_DEFAULT_MIN_CONNECTIONS,
-    POSTGRES_DEFAULT_STATEMENT_TIMEOUT_SECS, PRICING_SYNC_INTERVAL_SECS,
+    POSTGRES_DEFAULT_STATEMENT_TIMEOUT_SECS, PRICING_SYNC_INTERVAL_SECS, REDIS_DEFAULT_POOL_SIZE,
     SECRETS_DEFAULT_AWS_PREFIX, SECRETS_DEFAULT_ENV_PREFIX, SECRETS_DEFAULT_VAULT_MOUNT,
     SECRETS_DEFAULT_VAULT_PREFIX,
 };
@@ -245,18 +252,131 @@ pub struct OtelAuthFileConfig {
     pub required: Option<bool>,
 }
 
+/// Ingestion configuration (nested under otel)
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct IngestionFileConfig {
+    /// Exception types that should not mark a span as an error
+    pub non_error_exception_types: Option<Vec<String>>,
+    /// Estimate token usage from message text when a span reports none
+    pub estimate_missing_usage: Option<bool>,
+    /// Merge usage split between a generation span and its immediate child
+    pub merge_split_usage: Option<bool>,
+    /// Store message and tool definition JSON zstd-compressed
+    pub compress_messages: Option<bool>,
+    /// Stable worker ID used as the stream consumer name
+    pub worker_id: Option<String>,
+    /// Seconds a span may start in the future before it is clamped to ingestion time
+    pub max_future_skew_secs: Option<u64>,
+    /// Substrings that hide a URL or DB statement from HTTP/DB feed markers
+    pub marker_denylist: Option<Vec<String>>,
+    /// Keep URL query strings in HTTP feed markers
+    pub marker_keep_url_query: Option<bool>,
+    /// Tool name patterns whose results are redacted at extraction
+    pub redact_tool_results: Option<Vec<String>>,
+    /// Tokens per minute within one session that trigger a runaway-agent alert
+    pub token_rate_alert_per_minute: Option<u64>,
+    /// Span name regexes that force an observation type, checked in order
+    pub observation_type_patterns: Option<Vec<ObservationTypePatternFileConfig>>,
+    /// Model string rewrites applied at extraction (alias -> canonical name)
+    pub model_aliases: Option<HashMap<String, String>>,
+    /// Seconds without new spans before a trace is announced as complete
+    pub trace_complete_after_secs: Option<u64>,
+    /// Frameworks or telemetry SDK names whose spans are ingested (empty = all)
+    pub framework_allowlist: Option<Vec<String>>,
+}
+
+/// Span name pattern forcing an observation type (nested under otel.ingestion)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ObservationTypePatternFileConfig {
+    /// Regex matched against the span name
+    pub pattern: String,
+    /// Observation type assigned on match (e.g. "agent", "tool")
+    pub observation_type: String,
+}
+
+/// Sampling configuration (nested under otel)
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SamplingFileConfig {
+    /// Fraction of traces kept by upstream head sampling (0 < rate <= 1)
+    pub rate: Option<f64>,
+    /// Per-organization overrides of `rate`, keyed by organization ID
+    pub org_rates: Option<HashMap<String, f64>>,
+}
+
+/// Default roles for role-less attribute messages (nested under otel)
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct DefaultRolesFileConfig {
+    /// Role for role-less content in input attributes (default: user)
+    pub input: Option<ChatRole>,
+    /// Role for role-less content in output attributes (default: assistant)
+    pub output: Option<ChatRole>,
+    /// Per-attribute overrides, keyed by attribute key
+    pub by_attribute: Option<HashMap<String, ChatRole>>,
+}
+
+/// Framework-specific message event names (nested under otel)
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct EventNamesFileConfig {
+    /// Event names that carry span output (protected from history marking)
+    pub output: Option<Vec<String>>,
+    /// Event names that carry span input
+    pub input: Option<Vec<String>>,
+}
+
 /// OpenTelemetry configuration section
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct OtelFileConfig {
     pub grpc: Option<GrpcFileConfig>,
     pub retention: Option<RetentionFileConfig>,
     pub auth: Option<OtelAuthFileConfig>,
+    pub ingestion: Option<IngestionFileConfig>,
+    pub sampling: Option<SamplingFileConfig>,
+    pub default_roles: Option<DefaultRolesFileConfig>,
+    /// Span attribute keys copied into `custom_attributes` on span responses.
+    /// A trailing `*` matches every key with that prefix (e.g. `experiment.*`).
+    pub promoted_attributes: Option<Vec<String>>,
+    /// Keep identical conversation content from different environments distinct
+    pub dedup_by_environment: Option<bool>,
+    pub event_names: Option<EventNamesFileConfig>,
+    /// Truncate feed tool results larger than this many bytes (0 disables)
+    pub feed_tool_result_max_bytes: Option<usize>,
+    /// Process feed sessions with more traces than this independently (0 disables)
+    pub feed_max_session_traces: Option<usize>,
+    /// Threads for parsing the traces of a feed session (0 or 1 = sequential)
+    pub feed_trace_parallelism: Option<usize>,
+    /// Return at most this many feed blocks per request, newest kept (0 disables)
+    pub feed_max_blocks: Option<usize>,
+    /// Keep at most this many cross-trace prefix entries per feed session,
+    /// oldest dropped (0 disables)
+    pub feed_max_prefix_entries: Option<usize>,
+    /// Messages are feed history only if they predate span start by more than this
+    pub feed_clock_skew_tolerance_ms: Option<u64>,
 }
 
 /// Pricing configuration section (from JSON config file)
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct PricingFileConfig {
     pub sync_hours: Option<u64>,
+    /// Hold synced prices that change by more than this factor (<= 1 disables)
+    pub max_rate_change_ratio: Option<f64>,
+    /// Pricing keys whose held synced rates are applied on the next sync
+    pub accepted_rate_changes: Option<Vec<String>>,
+    /// Providers whose input token counts already include cache reads
+    pub cached_input_providers: Option<Vec<String>>,
+    /// Per-org Azure deployment name -> model mappings (`{org_id: {deployment: model}}`)
+    pub deployment_models: Option<HashMap<String, HashMap<String, String>>>,
+    /// Distinct unmatched models kept for the coverage report (0 disables tracking)
+    pub unmatched_models_limit: Option<usize>,
+    /// Persist unmatched models to the data directory across restarts
+    pub persist_unmatched_models: Option<bool>,
+    /// Share of `total_tokens` priced as input when usage has no input/output split (0-1)
+    pub total_only_input_ratio: Option<f64>,
+    /// Prefer costs reported by spans (OpenInference `llm.cost.*`) over computed costs
+    pub prefer_reported_cost: Option<bool>,
+    /// USD value of one unit of each currency used by custom pricing entries (`{"EUR": 1.08}`)
+    pub currency_rates: Option<HashMap<String, f64>>,
+    /// Provider guessed for framework-only systems (`{"langchain": "openai"}`)
+    pub framework_providers: Option<HashMap<String, String>>,
 }
 
 /// Update check configuration section (from JSON config file)
@@ -301,6 +421,8 @@ pub struct FilesFileConfig {
 pub struct RedisFileConfig {
     /// Connection URL for Redis-compatible backends
     pub url: Option<String>,
+    /// Max connections per pool (cache and topics each have one)
+    pub pool_size: Option<usize>,
 }
 
 /// Memory cache configuration section (from JSON config file)
@@ -536,6 +658,158 @@ impl FileConfig {
                     current_retention.max_spans = retention.max_spans;
                 }
             }
+
+            if let Some(ingestion) = otel.ingestion {
+                let current_ingestion = current
+                    .ingestion
+                    .get_or_insert_with(IngestionFileConfig::default);
+                if ingestion.non_error_exception_types.is_some() {
+                    tracing::trace!(non_error_exception_types = ?ingestion.non_error_exception_types, "Merging otel.ingestion.non_error_exception_types");
+                    current_ingestion.non_error_exception_types =
+                        ingestion.non_error_exception_types;
+                }
+                if ingestion.estimate_missing_usage.is_some() {
+                    tracing::trace!(estimate_missing_usage = ?ingestion.estimate_missing_usage, "Merging otel.ingestion.estimate_missing_usage");
+                    current_ingestion.estimate_missing_usage = ingestion.estimate_missing_usage;
+                }
+                if ingestion.merge_split_usage.is_some() {
+                    tracing::trace!(merge_split_usage = ?ingestion.merge_split_usage, "Merging otel.ingestion.merge_split_usage");
+                    current_ingestion.merge_split_usage = ingestion.merge_split_usage;
+                }
+                if ingestion.compress_messages.is_some() {
+                    tracing::trace!(compress_messages = ?ingestion.compress_messages, "Merging otel.ingestion.compress_messages");
+                    current_ingestion.compress_messages = ingestion.compress_messages;
+                }
+                if ingestion.worker_id.is_some() {
+                    tracing::trace!(worker_id = ?ingestion.worker_id, "Merging otel.ingestion.worker_id");
+                    current_ingestion.worker_id = ingestion.worker_id;
+                }
+                if ingestion.max_future_skew_secs.is_some() {
+                    tracing::trace!(max_future_skew_secs = ?ingestion.max_future_skew_secs, "Merging otel.ingestion.max_future_skew_secs");
+                    current_ingestion.max_future_skew_secs = ingestion.max_future_skew_secs;
+                }
+                if ingestion.marker_denylist.is_some() {
+                    tracing::trace!(marker_denylist = ?ingestion.marker_denylist, "Merging otel.ingestion.marker_denylist");
+                    current_ingestion.marker_denylist = ingestion.marker_denylist;
+                }
+                if ingestion.marker_keep_url_query.is_some() {
+                    tracing::trace!(marker_keep_url_query = ?ingestion.marker_keep_url_query, "Merging otel.ingestion.marker_keep_url_query");
+                    current_ingestion.marker_keep_url_query = ingestion.marker_keep_url_query;
+                }
+                if ingestion.redact_tool_results.is_some() {
+                    tracing::trace!(redact_tool_results = ?ingestion.redact_tool_results, "Merging otel.ingestion.redact_tool_results");
+                    current_ingestion.redact_tool_results = ingestion.redact_tool_results;
+                }
+                if ingestion.token_rate_alert_per_minute.is_some() {
+                    tracing::trace!(token_rate_alert_per_minute = ?ingestion.token_rate_alert_per_minute, "Merging otel.ingestion.token_rate_alert_per_minute");
+                    current_ingestion.token_rate_alert_per_minute =
+                        ingestion.token_rate_alert_per_minute;
+                }
+                if ingestion.observation_type_patterns.is_some() {
+                    tracing::trace!(observation_type_patterns = ?ingestion.observation_type_patterns, "Merging otel.ingestion.observation_type_patterns");
+                    current_ingestion.observation_type_patterns =
+                        ingestion.observation_type_patterns;
+                }
+                if ingestion.model_aliases.is_some() {
+                    tracing::trace!(model_aliases = ?ingestion.model_aliases, "Merging otel.ingestion.model_aliases");
+                    current_ingestion.model_aliases = ingestion.model_aliases;
+                }
+                if ingestion.trace_complete_after_secs.is_some() {
+                    tracing::trace!(trace_complete_after_secs = ?ingestion.trace_complete_after_secs, "Merging otel.ingestion.trace_complete_after_secs");
+                    current_ingestion.trace_complete_after_secs =
+                        ingestion.trace_complete_after_secs;
+                }
+                if ingestion.framework_allowlist.is_some() {
+                    tracing::trace!(framework_allowlist = ?ingestion.framework_allowlist, "Merging otel.ingestion.framework_allowlist");
+                    current_ingestion.framework_allowlist = ingestion.framework_allowlist;
+                }
+            }
+
+            if let Some(sampling) = otel.sampling {
+                let current_sampling = current
+                    .sampling
+                    .get_or_insert_with(SamplingFileConfig::default);
+                if sampling.rate.is_some() {
+                    tracing::trace!(rate = ?sampling.rate, "Merging otel.sampling.rate");
+                    current_sampling.rate = sampling.rate;
+                }
+                if sampling.org_rates.is_some() {
+                    tracing::trace!(org_rates = ?sampling.org_rates, "Merging otel.sampling.org_rates");
+                    current_sampling.org_rates = sampling.org_rates;
+                }
+            }
+
+            if let Some(default_roles) = otel.default_roles {
+                let current_roles = current
+                    .default_roles
+                    .get_or_insert_with(DefaultRolesFileConfig::default);
+                if default_roles.input.is_some() {
+                    tracing::trace!(input = ?default_roles.input, "Merging otel.default_roles.input");
+                    current_roles.input = default_roles.input;
+                }
+                if default_roles.output.is_some() {
+                    tracing::trace!(output = ?default_roles.output, "Merging otel.default_roles.output");
+                    current_roles.output = default_roles.output;
+                }
+                if default_roles.by_attribute.is_some() {
+                    tracing::trace!(by_attribute = ?default_roles.by_attribute, "Merging otel.default_roles.by_attribute");
+                    current_roles.by_attribute = default_roles.by_attribute;
+                }
+            }
+
+            if otel.promoted_attributes.is_some() {
+                tracing::trace!(promoted_attributes = ?otel.promoted_attributes, "Merging otel.promoted_attributes");
+                current.promoted_attributes = otel.promoted_attributes;
+            }
+
+            if let Some(event_names) = otel.event_names {
+                let current_names = current
+                    .event_names
+                    .get_or_insert_with(EventNamesFileConfig::default);
+                if event_names.output.is_some() {
+                    tracing::trace!(output = ?event_names.output, "Merging otel.event_names.output");
+                    current_names.output = event_names.output;
+                }
+                if event_names.input.is_some() {
+                    tracing::trace!(input = ?event_names.input, "Merging otel.event_names.input");
+                    current_names.input = event_names.input;
+                }
+            }
+
+            if otel.dedup_by_environment.is_some() {
+                tracing::trace!(dedup_by_environment = ?otel.dedup_by_environment, "Merging otel.dedup_by_environment");
+                current.dedup_by_environment = otel.dedup_by_environment;
+            }
+
+            if otel.feed_tool_result_max_bytes.is_some() {
+                tracing::trace!(feed_tool_result_max_bytes = ?otel.feed_tool_result_max_bytes, "Merging otel.feed_tool_result_max_bytes");
+                current.feed_tool_result_max_bytes = otel.feed_tool_result_max_bytes;
+            }
+
+            if otel.feed_max_session_traces.is_some() {
+                tracing::trace!(feed_max_session_traces = ?otel.feed_max_session_traces, "Merging otel.feed_max_session_traces");
+                current.feed_max_session_traces = otel.feed_max_session_traces;
+            }
+
+            if otel.feed_trace_parallelism.is_some() {
+                tracing::trace!(feed_trace_parallelism = ?otel.feed_trace_parallelism, "Merging otel.feed_trace_parallelism");
+                current.feed_trace_parallelism = otel.feed_trace_parallelism;
+            }
+
+            if otel.feed_max_blocks.is_some() {
+                tracing::trace!(feed_max_blocks = ?otel.feed_max_blocks, "Merging otel.feed_max_blocks");
+                current.feed_max_blocks = otel.feed_max_blocks;
+            }
+
+            if otel.feed_max_prefix_entries.is_some() {
+                tracing::trace!(feed_max_prefix_entries = ?otel.feed_max_prefix_entries, "Merging otel.feed_max_prefix_entries");
+                current.feed_max_prefix_entries = otel.feed_max_prefix_entries;
+            }
+
+            if otel.feed_clock_skew_tolerance_ms.is_some() {
+                tracing::trace!(feed_clock_skew_tolerance_ms = ?otel.feed_clock_skew_tolerance_ms, "Merging otel.feed_clock_skew_tolerance_ms");
+                current.feed_clock_skew_tolerance_ms = otel.feed_clock_skew_tolerance_ms;
+            }
         }
 
         // Pricing
@@ -545,6 +819,46 @@ impl FileConfig {
                 tracing::trace!(sync_hours = ?pricing.sync_hours, "Merging pricing.sync_hours");
                 current.sync_hours = pricing.sync_hours;
             }
+            if pricing.max_rate_change_ratio.is_some() {
+                tracing::trace!(max_rate_change_ratio = ?pricing.max_rate_change_ratio, "Merging pricing.max_rate_change_ratio");
+                current.max_rate_change_ratio = pricing.max_rate_change_ratio;
+            }
+            if pricing.accepted_rate_changes.is_some() {
+                tracing::trace!(accepted_rate_changes = ?pricing.accepted_rate_changes, "Merging pricing.accepted_rate_changes");
+                current.accepted_rate_changes = pricing.accepted_rate_changes;
+            }
+            if pricing.cached_input_providers.is_some() {
+                tracing::trace!(cached_input_providers = ?pricing.cached_input_providers, "Merging pricing.cached_input_providers");
+                current.cached_input_providers = pricing.cached_input_providers;
+            }
+            if pricing.deployment_models.is_some() {
+                tracing::trace!(deployment_models = ?pricing.deployment_models, "Merging pricing.deployment_models");
+                current.deployment_models = pricing.deployment_models;
+            }
+            if pricing.unmatched_models_limit.is_some() {
+                tracing::trace!(unmatched_models_limit = ?pricing.unmatched_models_limit, "Merging pricing.unmatched_models_limit");
+                current.unmatched_models_limit = pricing.unmatched_models_limit;
+            }
+            if pricing.persist_unmatched_models.is_some() {
+                tracing::trace!(persist_unmatched_models = ?pricing.persist_unmatched_models, "Merging pricing.persist_unmatched_models");
+                current.persist_unmatched_models = pricing.persist_unmatched_models;
+            }
+            if pricing.total_only_input_ratio.is_some() {
+                tracing::trace!(total_only_input_ratio = ?pricing.total_only_input_ratio, "Merging pricing.total_only_input_ratio");
+                current.total_only_input_ratio = pricing.total_only_input_ratio;
+            }
+            if pricing.prefer_reported_cost.is_some() {
+                tracing::trace!(prefer_reported_cost = ?pricing.prefer_reported_cost, "Merging pricing.prefer_reported_cost");
+                current.prefer_reported_cost = pricing.prefer_reported_cost;
+            }
+            if pricing.currency_rates.is_some() {
+                tracing::trace!(currency_rates = ?pricing.currency_rates, "Merging pricing.currency_rates");
+                current.currency_rates = pricing.currency_rates;
+            }
+            if pricing.framework_providers.is_some() {
+                tracing::trace!(framework_providers = ?pricing.framework_providers, "Merging pricing.framework_providers");
+                current.framework_providers = pricing.framework_providers;
+            }
         }
 
         // Files
@@ -721,6 +1035,10 @@ impl FileConfig {
                     tracing::trace!(url = "***", "Merging database.redis.url");
                     current_redis.url = redis.url;
                 }
+                if redis.pool_size.is_some() {
+                    tracing::trace!(pool_size = ?redis.pool_size, "Merging database.redis.pool_size");
+                    current_redis.pool_size = redis.pool_size;
+                }
             }
             if let Some(memory_cache) = database.memory_cache {
                 let current_mc = current
@@ -831,6 +1149,163 @@ pub struct OtelConfig {
     pub retention: RetentionConfig,
     /// Require API key for OTEL ingestion
     pub auth_required: bool,
+    pub ingestion: IngestionConfig,
+    pub sampling: SamplingConfig,
+    /// Roles for role-less attribute messages in conversation views
+    pub default_roles: DefaultRoles,
+    /// Span attributes surfaced as `custom_attributes` on span responses
+    pub promoted_attributes: PromotedAttributes,
+    /// Conversation feed settings
+    pub feed: FeedConfig,
+}
+
+/// Conversation feed configuration (applied when building message views)
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    /// Roles for role-less attribute messages
+    pub default_roles: DefaultRoles,
+    /// Scope conversation dedup to the span's environment (default false)
+    pub dedup_by_environment: bool,
+    /// Extra input/output event names
+    pub event_names: EventNames,
+    /// Tool result size limit in bytes (None disables truncation)
+    pub tool_result_max_bytes: Option<usize>,
+    /// Session trace count above which cross-trace stripping is skipped
+    /// (None disables the guard)
+    pub max_session_traces: Option<usize>,
+    /// Threads for parsing the traces of a session (1 = sequential)
+    pub trace_parallelism: usize,
+    /// Blocks returned per request above which older blocks are dropped
+    /// (None disables the cap)
+    pub max_blocks: Option<usize>,
+    /// Cross-trace prefix entries per session above which the oldest are
+    /// dropped (None disables the cap)
+    pub max_prefix_entries: Option<usize>,
+    /// Messages predating span start by at most this much are not history
+    pub clock_skew_tolerance: TimeDelta,
+}
+
+/// Ingestion configuration (applied during span extraction)
+#[derive(Debug, Clone)]
+pub struct IngestionConfig {
+    /// Exception types whose ERROR status is downgraded (e.g. handled cancellations)
+    pub non_error_exception_types: Vec<String>,
+    /// Estimate token usage from message text when a span reports none (off by default)
+    pub estimate_missing_usage: bool,
+    /// Move usage reported on an immediate child span onto a generation span
+    /// that only reports the other half (off by default)
+    pub merge_split_usage: bool,
+    /// Store message and tool definition JSON zstd-compressed (off by default).
+    /// Reads handle compressed and plain rows alike.
+    pub compress_messages: bool,
+    /// Stable stream consumer name so a restarted worker resumes its own position.
+    /// When unset, a random per-process name is used.
+    pub worker_id: Option<String>,
+    /// Spans starting more than this many seconds in the future are clamped to
+    /// ingestion time; smaller clock skew is left alone.
+    pub max_future_skew_secs: u64,
+    /// Case-insensitive substrings; HTTP URLs and DB statements containing any
+    /// of them are left out of side-effect feed markers.
+    pub marker_denylist: Vec<String>,
+    /// Keep query strings and fragments of URLs in HTTP feed markers (off by
+    /// default, as they often carry tokens).
+    pub marker_keep_url_query: bool,
+    /// Case-insensitive tool name patterns (`*` wildcard) whose tool results are
+    /// replaced with a placeholder at extraction. Tool calls are kept.
+    pub redact_tool_results: Vec<String>,
+    /// Session token rate (tokens/minute over a sliding window) above which a
+    /// runaway-agent alert is broadcast. None disables the monitor.
+    pub token_rate_alert_per_minute: Option<u64>,
+    /// Span name regexes that force an observation type (lowercase name)
+    /// ahead of the built-in heuristics. The first match wins.
+    pub observation_type_patterns: Vec<(Regex, String)>,
+    /// Exact model strings rewritten to a canonical name before pricing and
+    /// storage. The original is kept in the span's `gen_ai_raw_model` column.
+    pub model_aliases: HashMap<String, String>,
+    /// Quiet period (seconds without new spans) after which a trace complete
+    /// event is broadcast. None disables completion tracking.
+    pub trace_complete_after_secs: Option<u64>,
+    /// Lowercased framework names (e.g. "strandsagents") or `telemetry.sdk.name`
+    /// values whose spans are kept; other spans are dropped before persistence.
+    /// Empty accepts all spans.
+    pub framework_allowlist: Vec<String>,
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self {
+            non_error_exception_types: Vec::new(),
+            estimate_missing_usage: false,
+            merge_split_usage: false,
+            compress_messages: false,
+            worker_id: None,
+            max_future_skew_secs: DEFAULT_MAX_FUTURE_SKEW_SECS,
+            marker_denylist: Vec::new(),
+            marker_keep_url_query: false,
+            redact_tool_results: Vec::new(),
+            token_rate_alert_per_minute: None,
+            observation_type_patterns: Vec::new(),
+            model_aliases: HashMap::new(),
+            trace_complete_after_secs: None,
+            framework_allowlist: Vec::new(),
+        }
+    }
+}
+
+/// Head sampling rates applied upstream, used to extrapolate cost aggregates
+#[derive(Debug, Clone, Default)]
+pub struct SamplingConfig {
+    /// Default sampling rate for all organizations (None = not sampled)
+    pub rate: Option<f64>,
+    /// Per-organization overrides, keyed by organization ID
+    pub org_rates: HashMap<String, f64>,
+}
+
+impl SamplingConfig {
+    /// Effective sampling rate for an organization.
+    ///
+    /// Returns None when sampling is disabled (no rate, or rate >= 1). Invalid
+    /// rates are dropped with a warning when the config is loaded.
+    pub fn rate_for_org(&self, org_id: &str) -> Option<f64> {
+        self.org_rates
+            .get(org_id)
+            .copied()
+            .or(self.rate)
+            .filter(|rate| *rate > 0.0 && *rate < 1.0)
+    }
+}
+
+/// Span attribute keys promoted into span responses, by exact key or prefix
+#[derive(Debug, Clone, Default)]
+pub struct PromotedAttributes {
+    exact: HashSet<String>,
+    prefixes: Vec<String>,
+}
+
+impl PromotedAttributes {
+    /// Build from config patterns; a trailing `*` turns a pattern into a prefix match.
+    pub fn from_patterns(patterns: &[String]) -> Self {
+        let mut promoted = Self::default();
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match pattern.strip_suffix('*') {
+                Some(prefix) => promoted.prefixes.push(prefix.to_string()),
+                None => {
+                    promoted.exact.insert(pattern.to_string());
+                }
+            }
+        }
+        promoted
+    }
+
+    /// True when no attributes are promoted
+    pub fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Whether an attribute key is promoted
+    pub fn matches(&self, key: &str) -> bool {
+        self.exact.contains(key) || self.prefixes.iter().any(|p| key.starts_with(p.as_str()))
+    }
 }
 
 /// Retention configuration
@@ -844,6 +1319,51 @@ pub struct RetentionConfig {
 #[derive(Debug, Clone)]
 pub struct PricingConfig {
     pub sync_hours: u64,
+    /// Operator-provided pricing file used instead of synced and embedded data
+    pub file: Option<PathBuf>,
+    /// Per-model price change factor above which synced prices are held
+    pub max_rate_change_ratio: f64,
+    /// Pricing keys exempt from the rate change guard (lowercase)
+    pub accepted_rate_changes: Vec<String>,
+    /// LiteLLM providers whose input tokens include cache reads (cache reads are not charged twice)
+    pub cached_input_providers: Vec<String>,
+    /// Per-org Azure deployment name -> model mappings
+    pub deployment_models: HashMap<String, HashMap<String, String>>,
+    /// Distinct unmatched models kept (0 disables tracking)
+    pub unmatched_models_limit: usize,
+    /// Persist unmatched models to the data directory (default false)
+    pub persist_unmatched_models: bool,
+    /// Share of `total_tokens` priced as input for total-only usage
+    pub total_only_input_ratio: f64,
+    /// Prefer span-reported costs over computed costs (default true)
+    pub prefer_reported_cost: bool,
+    /// USD value of one unit of each non-USD pricing currency
+    pub currency_rates: HashMap<String, f64>,
+    /// Framework-only system -> provider tried as a last resort (opt-in guess)
+    pub framework_providers: HashMap<String, String>,
+}
+
+impl Default for PricingConfig {
+    /// Defaults with background sync disabled
+    fn default() -> Self {
+        Self {
+            sync_hours: 0,
+            file: None,
+            max_rate_change_ratio: DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO,
+            accepted_rate_changes: Vec::new(),
+            cached_input_providers: DEFAULT_CACHED_INPUT_PROVIDERS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            deployment_models: HashMap::new(),
+            unmatched_models_limit: DEFAULT_UNMATCHED_MODELS_LIMIT,
+            persist_unmatched_models: false,
+            total_only_input_ratio: DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO,
+            prefer_reported_cost: true,
+            currency_rates: HashMap::new(),
+            framework_providers: HashMap::new(),
+        }
+    }
 }
 
 /// S3 configuration (final/runtime)
@@ -889,6 +1409,8 @@ pub struct CredentialsConfig {
 pub struct RedisConfig {
     /// Connection URL for Redis-compatible backends
     pub url: String,
+    /// Max connections per pool (cache and topics each have one)
+    pub pool_size: usize,
 }
 
 /// Memory cache configuration (final/runtime)
@@ -911,6 +1433,8 @@ pub struct CacheConfig {
     pub eviction_policy: EvictionPolicy,
     /// Redis URL (redis backend)
     pub redis_url: Option<String>,
+    /// Max connections per Redis pool (redis backend)
+    pub redis_pool_size: usize,
 }
 
 /// Rate limit configuration (final/runtime)
@@ -1034,6 +1558,10 @@ impl DatabaseConfig {
             max_entries: self.memory_cache.max_entries,
             eviction_policy: self.memory_cache.eviction_policy,
             redis_url: self.redis.as_ref().map(|r| r.url.clone()),
+            redis_pool_size: self
+                .redis
+                .as_ref()
+                .map_or(REDIS_DEFAULT_POOL_SIZE, |r| r.pool_size),
         }
     }
 }
@@ -1108,6 +1636,18 @@ impl AppConfig {
         let file_grpc = file_otel.grpc.unwrap_or_default();
         let file_retention = file_otel.retention.unwrap_or_default();
         let file_otel_auth = file_otel.auth.unwrap_or_default();
+        let file_ingestion = file_otel.ingestion.unwrap_or_default();
+        let file_sampling = file_otel.sampling.unwrap_or_default();
+        let file_default_roles = file_otel.default_roles.unwrap_or_default();
+        let file_promoted_attributes = file_otel.promoted_attributes.unwrap_or_default();
+        let file_dedup_by_environment = file_otel.dedup_by_environment;
+        let file_event_names = file_otel.event_names.unwrap_or_default();
+        let file_feed_tool_result_max_bytes = file_otel.feed_tool_result_max_bytes;
+        let file_feed_max_session_traces = file_otel.feed_max_session_traces;
+        let file_feed_trace_parallelism = file_otel.feed_trace_parallelism;
+        let file_feed_max_blocks = file_otel.feed_max_blocks;
+        let file_feed_max_prefix_entries = file_otel.feed_max_prefix_entries;
+        let file_feed_clock_skew_tolerance_ms = file_otel.feed_clock_skew_tolerance_ms;
         let file_pricing = file_config.pricing.unwrap_or_default();
         let file_files = file_config.files.unwrap_or_default();
         let file_rate_limit = file_config.rate_limit.unwrap_or_default();
@@ -1156,6 +1696,144 @@ impl AppConfig {
             .or(file_otel_auth.required)
             .unwrap_or(false);
 
+        // otel.ingestion: file config only, except worker_id which is usually per-pod
+        let observation_type_patterns = file_ingestion
+            .observation_type_patterns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| {
+                let pattern = Regex::new(&entry.pattern).with_context(|| {
+                    format!(
+                        "Configuration error: otel.ingestion.observation_type_patterns has an invalid regex: {}",
+                        entry.pattern
+                    )
+                })?;
+                Ok((pattern, entry.observation_type.to_lowercase()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ingestion = IngestionConfig {
+            non_error_exception_types: file_ingestion.non_error_exception_types.unwrap_or_default(),
+            estimate_missing_usage: file_ingestion.estimate_missing_usage.unwrap_or(false),
+            merge_split_usage: file_ingestion.merge_split_usage.unwrap_or(false),
+            compress_messages: file_ingestion.compress_messages.unwrap_or(false),
+            worker_id: std::env::var(ENV_WORKER_ID)
+                .ok()
+                .or(file_ingestion.worker_id)
+                .filter(|id| !id.trim().is_empty()),
+            max_future_skew_secs: file_ingestion
+                .max_future_skew_secs
+                .unwrap_or(DEFAULT_MAX_FUTURE_SKEW_SECS),
+            marker_denylist: file_ingestion.marker_denylist.unwrap_or_default(),
+            marker_keep_url_query: file_ingestion.marker_keep_url_query.unwrap_or(false),
+            redact_tool_results: file_ingestion.redact_tool_results.unwrap_or_default(),
+            token_rate_alert_per_minute: file_ingestion
+                .token_rate_alert_per_minute
+                .filter(|rate| *rate > 0),
+            observation_type_patterns,
+            model_aliases: file_ingestion
+                .model_aliases
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(alias, model)| (alias.trim().to_string(), model.trim().to_string()))
+                .filter(|(alias, model)| !alias.is_empty() && !model.is_empty())
+                .collect(),
+            trace_complete_after_secs: file_ingestion
+                .trace_complete_after_secs
+                .filter(|secs| *secs > 0),
+            framework_allowlist: file_ingestion
+                .framework_allowlist
+                .unwrap_or_default()
+                .into_iter()
+                .map(|name| name.trim().to_ascii_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        };
+
+        // otel.sampling: file config only; rates outside (0, 1] are ignored
+        let valid_sampling_rate = |key: &str, rate: f64| {
+            let valid = rate > 0.0 && rate <= 1.0;
+            if !valid {
+                tracing::warn!(
+                    key,
+                    rate,
+                    "Ignoring sampling rate outside (0, 1]; costs are not extrapolated"
+                );
+            }
+            valid
+        };
+        let sampling = SamplingConfig {
+            rate: file_sampling
+                .rate
+                .filter(|rate| valid_sampling_rate("otel.sampling.rate", *rate)),
+            org_rates: file_sampling
+                .org_rates
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(org_id, rate)| {
+                    valid_sampling_rate(&format!("otel.sampling.org_rates.{org_id}"), *rate)
+                })
+                .collect(),
+        };
+
+        // otel.default_roles: file config only
+        let builtin_roles = DefaultRoles::default();
+        let default_roles = DefaultRoles {
+            input: file_default_roles.input.unwrap_or(builtin_roles.input),
+            output: file_default_roles.output.unwrap_or(builtin_roles.output),
+            by_attribute: file_default_roles.by_attribute.unwrap_or_default(),
+        };
+
+        // otel.promoted_attributes: file config only
+        let promoted_attributes = PromotedAttributes::from_patterns(&file_promoted_attributes);
+
+        // otel.dedup_by_environment: file config only, default false
+        let dedup_by_environment = file_dedup_by_environment.unwrap_or(false);
+
+        // otel.event_names: file config only, extends the built-in GenAI events
+        let event_names = EventNames {
+            output: file_event_names.output.unwrap_or_default(),
+            input: file_event_names.input.unwrap_or_default(),
+        };
+
+        // otel.feed_tool_result_max_bytes: file config only, 0 disables truncation
+        let feed_tool_result_max_bytes = match file_feed_tool_result_max_bytes {
+            Some(0) => None,
+            Some(max) => Some(max),
+            None => Some(DEFAULT_FEED_TOOL_RESULT_MAX_BYTES),
+        };
+
+        // otel.feed_max_session_traces: file config only, 0 disables the guard
+        let feed_max_session_traces = match file_feed_max_session_traces {
+            Some(0) => None,
+            Some(max) => Some(max),
+            None => Some(DEFAULT_FEED_MAX_SESSION_TRACES),
+        };
+
+        // otel.feed_trace_parallelism: file config only, sequential by default
+        let feed_trace_parallelism = file_feed_trace_parallelism.unwrap_or(1).max(1);
+
+        // otel.feed_max_blocks: file config only, 0 disables the cap
+        let feed_max_blocks = match file_feed_max_blocks {
+            Some(0) => None,
+            Some(max) => Some(max),
+            None => Some(DEFAULT_FEED_MAX_BLOCKS),
+        };
+
+        // otel.feed_max_prefix_entries: file config only, 0 disables the cap
+        let feed_max_prefix_entries = match file_feed_max_prefix_entries {
+            Some(0) => None,
+            Some(max) => Some(max),
+            None => Some(DEFAULT_FEED_MAX_PREFIX_ENTRIES),
+        };
+
+        // otel.feed_clock_skew_tolerance_ms: file config only
+        let feed_clock_skew_tolerance = file_feed_clock_skew_tolerance_ms
+            .and_then(|ms| i64::try_from(ms).ok())
+            .map_or(
+                TimeDelta::milliseconds(DEFAULT_FEED_CLOCK_SKEW_TOLERANCE_MS),
+                |ms| TimeDelta::try_milliseconds(ms).unwrap_or(TimeDelta::MAX),
+            );
+
         // debug: CLI/env flag takes precedence, then file config, default false
         let debug = cli.debug || file_config.debug.unwrap_or(false);
 
@@ -1165,6 +1843,79 @@ impl AppConfig {
             .pricing_sync_hours
             .or(file_pricing.sync_hours)
             .unwrap_or(default_sync_hours);
+        let pricing_file = cli
+            .pricing_file
+            .clone()
+            .filter(|p| !p.as_os_str().is_empty());
+        let pricing_max_rate_change_ratio = file_pricing
+            .max_rate_change_ratio
+            .unwrap_or(DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO);
+        let pricing_accepted_rate_changes: Vec<String> = file_pricing
+            .accepted_rate_changes
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|model| model.trim().to_lowercase())
+            .filter(|model| !model.is_empty())
+            .collect();
+        let pricing_cached_input_providers = file_pricing
+            .cached_input_providers
+            .clone()
+            .unwrap_or_else(|| {
+                DEFAULT_CACHED_INPUT_PROVIDERS
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect()
+            });
+        let pricing_deployment_models = file_pricing.deployment_models.clone().unwrap_or_default();
+        let pricing_unmatched_models_limit = file_pricing
+            .unmatched_models_limit
+            .unwrap_or(DEFAULT_UNMATCHED_MODELS_LIMIT);
+        let pricing_persist_unmatched_models =
+            file_pricing.persist_unmatched_models.unwrap_or(false);
+        let pricing_total_only_input_ratio = match file_pricing.total_only_input_ratio {
+            Some(ratio) if (0.0..=1.0).contains(&ratio) => ratio,
+            Some(ratio) => {
+                tracing::warn!(
+                    ratio,
+                    "pricing.total_only_input_ratio must be between 0 and 1, using default"
+                );
+                DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO
+            }
+            None => DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO,
+        };
+        let pricing_prefer_reported_cost = file_pricing.prefer_reported_cost.unwrap_or(true);
+        let pricing_currency_rates: HashMap<String, f64> = file_pricing
+            .currency_rates
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(currency, rate)| {
+                let valid = rate.is_finite() && *rate > 0.0;
+                if !valid {
+                    tracing::warn!(
+                        currency = %currency,
+                        rate,
+                        "Ignoring pricing.currency_rates entry that is not a positive number; \
+                         prices in this currency are treated as USD"
+                    );
+                }
+                valid
+            })
+            .collect();
+        let pricing_framework_providers: HashMap<String, String> = file_pricing
+            .framework_providers
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(framework, provider)| {
+                (
+                    framework.trim().to_ascii_lowercase(),
+                    provider.trim().to_ascii_lowercase(),
+                )
+            })
+            .filter(|(framework, provider)| !framework.is_empty() && !provider.is_empty())
+            .collect();
 
         // files config: CLI/env overrides file config
         let storage_backend = cli.files_storage.or(file_files.storage).unwrap_or_default();
@@ -1259,7 +2010,8 @@ impl AppConfig {
                 .clone()
                 .or(file_redis.url)
                 .unwrap_or_default();
-            Some(RedisConfig { url })
+            let pool_size = file_redis.pool_size.unwrap_or(REDIS_DEFAULT_POOL_SIZE);
+            Some(RedisConfig { url, pool_size })
         } else {
             None
         };
@@ -1474,9 +2226,34 @@ impl AppConfig {
                 grpc_port: otel_grpc_port,
                 retention,
                 auth_required: otel_auth_required,
+                ingestion,
+                sampling,
+                promoted_attributes,
+                feed: FeedConfig {
+                    default_roles,
+                    dedup_by_environment,
+                    event_names,
+                    tool_result_max_bytes: feed_tool_result_max_bytes,
+                    max_session_traces: feed_max_session_traces,
+                    trace_parallelism: feed_trace_parallelism,
+                    max_blocks: feed_max_blocks,
+                    max_prefix_entries: feed_max_prefix_entries,
+                    clock_skew_tolerance: feed_clock_skew_tolerance,
+                },
             },
             pricing: PricingConfig {
                 sync_hours: pricing_sync_hours,
+                file: pricing_file,
+                max_rate_change_ratio: pricing_max_rate_change_ratio,
+                accepted_rate_changes: pricing_accepted_rate_changes,
+                cached_input_providers: pricing_cached_input_providers,
+                deployment_models: pricing_deployment_models,
+                unmatched_models_limit: pricing_unmatched_models_limit,
+                persist_unmatched_models: pricing_persist_unmatched_models,
+                total_only_input_ratio: pricing_total_only_input_ratio,
+                prefer_reported_cost: pricing_prefer_reported_cost,
+                currency_rates: pricing_currency_rates,
+                framework_providers: pricing_framework_providers,
             },
             files,
             rate_limit,
@@ -1507,7 +2284,23 @@ impl AppConfig {
             retention_max_age_minutes = ?config.otel.retention.max_age_minutes,
             retention_max_spans = ?config.otel.retention.max_spans,
             otel_auth_required = config.otel.auth_required,
+            non_error_exception_types = ?config.otel.ingestion.non_error_exception_types,
+            estimate_missing_usage = config.otel.ingestion.estimate_missing_usage,
+            merge_split_usage = config.otel.ingestion.merge_split_usage,
+            compress_messages = config.otel.ingestion.compress_messages,
+            worker_id = ?config.otel.ingestion.worker_id,
+            max_future_skew_secs = config.otel.ingestion.max_future_skew_secs,
+            marker_denylist = ?config.otel.ingestion.marker_denylist,
+            marker_keep_url_query = config.otel.ingestion.marker_keep_url_query,
+            redact_tool_results = ?config.otel.ingestion.redact_tool_results,
+            token_rate_alert_per_minute = ?config.otel.ingestion.token_rate_alert_per_minute,
+            observation_type_patterns = ?config.otel.ingestion.observation_type_patterns,
+            model_aliases = ?config.otel.ingestion.model_aliases,
+            trace_complete_after_secs = ?config.otel.ingestion.trace_complete_after_secs,
+            framework_allowlist = ?config.otel.ingestion.framework_allowlist,
+            sampling_rate = ?config.otel.sampling.rate,
             pricing_sync_hours = config.pricing.sync_hours,
+            pricing_file = ?config.pricing.file,
             files_enabled = config.files.enabled,
             files_storage = %config.files.storage,
             files_quota_bytes = config.files.quota_bytes,
@@ -1568,6 +2361,14 @@ impl AppConfig {
                 "Configuration error: database.redis.url is required when database.cache is 'redis'"
             );
         }
+        if self
+            .database
+            .redis
+            .as_ref()
+            .is_some_and(|r| r.pool_size == 0)
+        {
+            anyhow::bail!("Configuration error: database.redis.pool_size must be at least 1");
+        }
 
         // Warn about rate limiting enabled with 0 RPM
         if self.rate_limit.enabled && self.rate_limit.api_rpm == 0 {
@@ -1820,9 +2621,31 @@ mod tests {
                     max_spans: None,
                 }),
                 auth: None,
+                ingestion: None,
+                sampling: None,
+                default_roles: None,
+                promoted_attributes: None,
+                dedup_by_environment: None,
+                feed_tool_result_max_bytes: None,
+                feed_max_session_traces: None,
+                feed_trace_parallelism: None,
+                feed_max_blocks: None,
+                feed_max_prefix_entries: None,
+                feed_clock_skew_tolerance_ms: None,
+                event_names: None,
             }),
             pricing: Some(PricingFileConfig {
                 sync_hours: Some(4),
+                max_rate_change_ratio: None,
+                accepted_rate_changes: None,
+                cached_input_providers: None,
+                deployment_models: None,
+                unmatched_models_limit: None,
+                persist_unmatched_models: None,
+                total_only_input_ratio: None,
+                prefer_reported_cost: None,
+                currency_rates: None,
+                framework_providers: None,
             }),
             files: None,
             rate_limit: None,
@@ -1853,9 +2676,31 @@ mod tests {
                     max_spans: Some(1_000_000),
                 }),
                 auth: None,
+                ingestion: None,
+                sampling: None,
+                default_roles: None,
+                promoted_attributes: None,
+                dedup_by_environment: None,
+                feed_tool_result_max_bytes: None,
+                feed_max_session_traces: None,
+                feed_trace_parallelism: None,
+                feed_max_blocks: None,
+                feed_max_prefix_entries: None,
+                feed_clock_skew_tolerance_ms: None,
+                event_names: None,
             }),
             pricing: Some(PricingFileConfig {
                 sync_hours: Some(8),
+                max_rate_change_ratio: None,
+                accepted_rate_changes: None,
+                cached_input_providers: None,
+                deployment_models: None,
+                unmatched_models_limit: None,
+                persist_unmatched_models: None,
+                total_only_input_ratio: None,
+                prefer_reported_cost: None,
+                currency_rates: None,
+                framework_providers: None,
             }),
             files: None,
             rate_limit: None,
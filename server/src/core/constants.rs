@@ -51,6 +51,9 @@ pub const DEFAULT_HOST: &str = "127.0.0.1";
 /// Default server port
 pub const DEFAULT_PORT: u16 = 5388;
 
+/// Upper bound for each `/readyz` dependency check in seconds
+pub const READINESS_CHECK_TIMEOUT_SECS: u64 = 3;
+
 // =============================================================================
 // Environment Variables - Storage
 // =============================================================================
@@ -168,6 +171,48 @@ pub const ENV_OTEL_GRPC_PORT: &str = "SIDESEAT_OTEL_GRPC_PORT";
 /// Default OTEL gRPC port (standard OTLP gRPC port)
 pub const DEFAULT_OTEL_GRPC_PORT: u16 = 4317;
 
+/// Environment variable for the stream consumer name of this instance
+pub const ENV_WORKER_ID: &str = "SIDESEAT_WORKER_ID";
+
+/// Spans starting further than this ahead of ingestion time are clamped to it
+pub const DEFAULT_MAX_FUTURE_SKEW_SECS: u64 = 60;
+
+/// Sliding window over which a session's token rate is measured
+pub const TOKEN_RATE_WINDOW_SECS: i64 = 60;
+
+/// Minimum time between two token rate alerts for the same session
+pub const TOKEN_RATE_ALERT_COOLDOWN_SECS: i64 = 300;
+
+/// How often idle traces are checked against the completion quiet period
+pub const TRACE_COMPLETE_CHECK_INTERVAL_SECS: u64 = 1;
+
+/// Traces tracked for completion per process; the least recently active is evicted beyond it
+pub const MAX_TRACKED_TRACES: usize = 100_000;
+
+/// Placeholder stored in place of a tool result matched by `otel.ingestion.redact_tool_results`
+pub const REDACTED_TOOL_OUTPUT: &str = "[redacted: tool output]";
+
+/// Tool results above this size are truncated in the feed (full content stays on the span)
+pub const DEFAULT_FEED_TOOL_RESULT_MAX_BYTES: usize = 64 * 1024;
+
+/// Feed sessions with more traces than this skip cross-trace history stripping
+/// (usually a shared default session id rather than one real conversation)
+pub const DEFAULT_FEED_MAX_SESSION_TRACES: usize = 200;
+
+/// Hard cap on blocks returned per feed request; older blocks beyond it are
+/// dropped and the response is flagged as truncated
+pub const DEFAULT_FEED_MAX_BLOCKS: usize = 10_000;
+
+/// Cap on accumulated cross-trace prefix entries per feed session; the oldest
+/// entries are dropped beyond it to bound memory for very long sessions
+pub const DEFAULT_FEED_MAX_PREFIX_ENTRIES: usize = 100_000;
+
+/// Default clock-skew tolerance for timestamp-based feed history detection.
+///
+/// Kept small: message timestamps come from the SDK clock while span start may
+/// be set elsewhere, but genuine history usually predates the span by seconds.
+pub const DEFAULT_FEED_CLOCK_SKEW_TOLERANCE_MS: i64 = 50;
+
 // =============================================================================
 // Request Body Limits
 // =============================================================================
@@ -188,6 +233,21 @@ pub const AUTH_BODY_LIMIT: usize = 64 * 1024;
 /// Topic name for OTLP traces
 pub const TOPIC_TRACES: &str = "traces";
 
+/// Dead-letter stream for trace messages that repeatedly failed to persist
+pub const TOPIC_TRACES_DLQ: &str = "traces_dlq";
+
+/// Topic name for spans persisted by the trace pipeline (UI notifications)
+pub const TOPIC_PROCESSED_TRACES: &str = "processed_traces";
+
+/// Broadcast topic announcing that synced pricing data was applied
+pub const TOPIC_PRICING_UPDATED: &str = "pricing_updated";
+
+/// Broadcast topic prefix for runaway-agent token rate alerts (`{prefix}:{project_id}`)
+pub const TOPIC_TOKEN_RATE_ALERTS: &str = "token_rate_alerts";
+
+/// Broadcast topic prefix for trace completion events (`{prefix}:{project_id}`)
+pub const TOPIC_TRACE_COMPLETE: &str = "trace_complete";
+
 /// Topic name for OTLP metrics
 pub const TOPIC_METRICS: &str = "metrics";
 
@@ -210,6 +270,18 @@ pub const DEFAULT_TOPIC_BUFFER_SIZE: usize = 100 * 1024 * 1024;
 /// Default topic channel capacity (message count)
 pub const DEFAULT_TOPIC_CHANNEL_CAPACITY: usize = 100_000;
 
+/// Environment variable for the topic dispatcher drain timeout on shutdown
+pub const ENV_TOPIC_SHUTDOWN_TIMEOUT_SECS: &str = "SIDESEAT_TOPIC_SHUTDOWN_TIMEOUT_SECS";
+
+/// Default time critical topic dispatchers get to drain on shutdown (seconds)
+pub const DEFAULT_TOPIC_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Environment variable holding the host name used in stream consumer names
+pub const ENV_HOSTNAME: &str = "HOSTNAME";
+
+/// Host name file read when `HOSTNAME` is unset
+pub const HOSTNAME_FILE: &str = "/etc/hostname";
+
 /// Retry-After header value for backpressure (in seconds)
 pub const BACKPRESSURE_RETRY_AFTER_SECS: u64 = 1;
 
@@ -227,9 +299,39 @@ pub const SHUTDOWN_TIMEOUT_SECS: u64 = 300;
 /// Pricing sync interval in seconds (4 hours)
 pub const PRICING_SYNC_INTERVAL_SECS: u64 = 4 * 60 * 60;
 
+/// Consecutive pricing sync failures before the sync interval starts backing off
+pub const PRICING_SYNC_BACKOFF_THRESHOLD: u32 = 3;
+
+/// Upper bound for the backed-off pricing sync interval (24 hours)
+pub const PRICING_SYNC_MAX_BACKOFF_SECS: u64 = 24 * 60 * 60;
+
+/// Synced model prices that move by more than this factor are held for review
+pub const DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO: f64 = 10.0;
+
+/// Share of `total_tokens` priced as input when a span reports no input/output
+/// split (chat traffic is typically prompt-heavy)
+pub const DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO: f64 = 0.75;
+
+/// LiteLLM providers whose prompt token counts include cache reads when
+/// `pricing.cached_input_providers` is not set (Gemini's `prompt_token_count`
+/// includes `cached_content_token_count`; newer Gemini previews are listed under
+/// plain `vertex_ai`, while partner models such as `vertex_ai-anthropic_models`
+/// report input without cache reads)
+pub const DEFAULT_CACHED_INPUT_PROVIDERS: &[&str] =
+    &["gemini", "vertex_ai-language-models", "vertex_ai"];
+
+/// Distinct unmatched models kept for the pricing coverage report
+pub const DEFAULT_UNMATCHED_MODELS_LIMIT: usize = 500;
+
+/// How often persisted unmatched models are written to the data directory
+pub const UNMATCHED_MODELS_FLUSH_SECS: u64 = 300;
+
 /// Environment variable for pricing sync interval (in hours, 0 = disabled)
 pub const ENV_PRICING_SYNC_HOURS: &str = "SIDESEAT_PRICING_SYNC_HOURS";
 
+/// Environment variable for a pricing file that takes precedence over embedded data
+pub const ENV_PRICING_FILE: &str = "SIDESEAT_PRICING_FILE";
+
 // =============================================================================
 // File Storage
 // =============================================================================
@@ -262,6 +364,9 @@ pub const FILE_EXTRACTION_CACHE_MAX_ENTRIES: u64 = 10_000;
 /// failed finalizations within the same server session.
 pub const FILE_EXTRACTION_CACHE_IDLE_SECS: u64 = 300;
 
+/// Max entries in the ingestion pipeline's project -> org cache (moka TinyLFU)
+pub const PROJECT_ORG_CACHE_MAX_ENTRIES: u64 = 10_000;
+
 /// Cache TTL for file quota storage bytes (seconds)
 pub const CACHE_TTL_FILE_QUOTA: u64 = 60;
 
@@ -410,6 +515,9 @@ pub const CACHE_TTL_USER: u64 = 300;
 /// Cache TTL for organization metadata (5 min)
 pub const CACHE_TTL_ORG: u64 = 300;
 
+/// Cache TTL for organization feature flags (1 min)
+pub const CACHE_TTL_ORG_FEATURE_FLAGS: u64 = 60;
+
 /// Cache TTL for orgs list for user (2 min)
 pub const CACHE_TTL_ORG_LIST: u64 = 120;
 
@@ -550,6 +658,13 @@ pub const POSTGRES_DEFAULT_MAX_LIFETIME_SECS: u64 = 1800;
 /// PostgreSQL statement timeout in seconds (prevent runaway queries, 0 = disabled)
 pub const POSTGRES_DEFAULT_STATEMENT_TIMEOUT_SECS: u64 = 60;
 
+// =============================================================================
+// Redis
+// =============================================================================
+
+/// Redis default max connections per pool (cache and topics each have one)
+pub const REDIS_DEFAULT_POOL_SIZE: usize = 32;
+
 // =============================================================================
 // ClickHouse Database
 // =============================================================================
@@ -573,6 +688,9 @@ pub const QUERY_MAX_FILTER_SUGGESTIONS: u32 = 100;
 /// Maximum results for top-N stats queries (top models, providers)
 pub const QUERY_MAX_TOP_STATS: u32 = 10;
 
+/// Maximum buckets returned by a timeseries query
+pub const QUERY_MAX_TIMESERIES_BUCKETS: i64 = 5000;
+
 /// Batch size for file cleanup operations
 pub const FILE_CLEANUP_BATCH_SIZE: u32 = 1000;
 
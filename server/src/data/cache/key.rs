@@ -51,6 +51,11 @@ impl CacheKey {
         format!("{}:org:slug:{}", CACHE_KEY_VERSION, slug)
     }
 
+    /// Cache key for organization feature flags
+    pub fn org_feature_flags(org_id: &str) -> String {
+        format!("{}:org:flags:{}", CACHE_KEY_VERSION, org_id)
+    }
+
     /// Cache key for organizations list for a user
     pub fn orgs_for_user(user_id: &str) -> String {
         format!("{}:orgs:user:{}", CACHE_KEY_VERSION, user_id)
@@ -183,6 +188,15 @@ impl CacheKey {
     pub fn rate_limit(bucket: &str, identifier: &str) -> String {
         format!("rl:{}:{}", bucket, identifier)
     }
+
+    // =========================================================================
+    // Leases
+    // =========================================================================
+
+    /// Lease claimed by the instance running a scheduled pricing sync
+    pub fn pricing_sync_lease() -> String {
+        format!("{}:lease:pricing_sync", CACHE_KEY_VERSION)
+    }
 }
 
 #[cfg(test)]
@@ -119,7 +119,7 @@ impl CacheService {
                     CacheError::Config("redis_url required for Redis backend".into())
                 })?;
                 // Note: RedisCache::new logs sanitized URL internally
-                Arc::new(redis::RedisCache::new(url).await?)
+                Arc::new(redis::RedisCache::new(url, config.redis_pool_size).await?)
             }
         };
 
@@ -301,6 +301,7 @@ mod tests {
             max_entries: 1000,
             eviction_policy: EvictionPolicy::TinyLfu,
             redis_url: None,
+            redis_pool_size: 32,
         }
     }
 
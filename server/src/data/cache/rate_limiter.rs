@@ -230,6 +230,7 @@ mod tests {
             max_entries: 1000,
             eviction_policy: EvictionPolicy::TinyLfu,
             redis_url: None,
+            redis_pool_size: 32,
         };
         Arc::new(CacheService::new(&config).await.unwrap())
     }
@@ -49,7 +49,7 @@ enum RedisBackendType {
 }
 
 impl RedisCache {
-    /// Create a new Redis-compatible cache with the given URL
+    /// Create a new Redis-compatible cache with the given URL and pool size
     ///
     /// # URL Formats
     ///
@@ -59,14 +59,14 @@ impl RedisCache {
     ///
     /// Redis Sentinel:
     /// - `redis+sentinel://[user:password@]sentinel1:port,sentinel2:port/master_name[/db]`
-    pub async fn new(redis_url: &str) -> Result<Self, CacheError> {
+    pub async fn new(redis_url: &str, pool_size: usize) -> Result<Self, CacheError> {
         let sanitized_url = sanitize_redis_url(redis_url);
         let backend_type = detect_backend_type(redis_url);
 
         let mut config = Config::from_url(redis_url);
         // Configure pool with reasonable defaults for production
         config.pool = Some(deadpool_redis::PoolConfig {
-            max_size: 32, // Allow more concurrent connections than default (16)
+            max_size: pool_size,
             timeouts: deadpool_redis::Timeouts {
                 // Timeout for getting a connection from the pool
                 wait: Some(Duration::from_secs(5)),
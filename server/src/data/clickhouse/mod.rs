@@ -254,26 +254,19 @@ impl ClickhouseService {
     }
 
     /// Apply a specific versioned migration
-    #[allow(unused_variables, clippy::match_single_binding)]
     async fn apply_versioned_migration(&self, version: i32) -> Result<(), ClickhouseError> {
         let now = chrono::Utc::now().timestamp();
 
-        // Add future migrations here as match arms
-        let (name, sql): (&str, &str) = match version {
-            // Example:
-            // 2 => ("add_some_column", "ALTER TABLE otel_spans ADD COLUMN ..."),
-            _ => {
-                return Err(ClickhouseError::MigrationFailed {
-                    version,
-                    name: "unknown".to_string(),
-                    error: format!("No migration defined for version {}", version),
-                });
-            }
+        // Add future migrations to schema::migration_statements
+        let Some((name, statements)) = schema::migration_statements(&self.config, version) else {
+            return Err(ClickhouseError::MigrationFailed {
+                version,
+                name: "unknown".to_string(),
+                error: format!("No migration defined for version {}", version),
+            });
         };
 
-        // Execute migration (unreachable until we add migrations above)
-        #[allow(unreachable_code)]
-        {
+        for sql in &statements {
             self.client.query(sql).execute().await.map_err(|e| {
                 ClickhouseError::MigrationFailed {
                     version,
@@ -281,19 +274,19 @@ impl ClickhouseService {
                     error: e.to_string(),
                 }
             })?;
+        }
 
-            // Update schema version
-            self.client
-                .query("ALTER TABLE schema_version UPDATE version = ?, applied_at = ? WHERE id = 1")
-                .bind(version)
-                .bind(now)
-                .execute()
-                .await
-                .map_err(ClickhouseError::from)?;
+        // Update schema version
+        self.client
+            .query("ALTER TABLE schema_version UPDATE version = ?, applied_at = ? WHERE id = 1")
+            .bind(version)
+            .bind(now)
+            .execute()
+            .await
+            .map_err(ClickhouseError::from)?;
 
-            tracing::debug!("ClickHouse migration v{} ({}) applied", version, name);
-            Ok(())
-        }
+        tracing::debug!("ClickHouse migration v{} ({}) applied", version, name);
+        Ok(())
     }
 
     /// Start health check task
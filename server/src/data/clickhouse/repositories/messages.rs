@@ -33,11 +33,13 @@ const CH_MESSAGE_SELECT_COLUMNS: &str = r#"
     tool_names,
     observation_type,
     session_id,
-    toInt64(toUnixTimestamp64Micro(ingested_at)) AS ingested_at_us"#;
+    toInt64(toUnixTimestamp64Micro(ingested_at)) AS ingested_at_us,
+    environment,
+    ingest_warnings"#;
 
 /// Shared content filter for message queries.
-const CH_MESSAGE_CONTENT_FILTER: &str =
-    "(messages != '[]' OR tool_definitions != '[]' OR tool_names != '[]' OR status_code = 'ERROR')";
+const CH_MESSAGE_CONTENT_FILTER: &str = "(messages != '[]' OR tool_definitions != '[]' OR tool_names != '[]' OR status_code = 'ERROR' \
+     OR ingest_warnings != '[]')";
 
 /// ClickHouse row for message span queries
 #[derive(Row, Deserialize)]
@@ -63,6 +65,8 @@ struct ChMessageSpanRow {
     observation_type: Option<String>,
     session_id: Option<String>,
     ingested_at_us: i64,
+    environment: Option<String>,
+    ingest_warnings: String,
 }
 
 impl From<ChMessageSpanRow> for MessageSpanRow {
@@ -93,6 +97,8 @@ impl From<ChMessageSpanRow> for MessageSpanRow {
             session_id: row.session_id,
             ingested_at: DateTime::from_timestamp_micros(row.ingested_at_us)
                 .unwrap_or(DateTime::UNIX_EPOCH),
+            environment: row.environment,
+            ingest_warnings_json: row.ingest_warnings,
         }
     }
 }
@@ -226,7 +226,7 @@ use crate::core::constants::{QUERY_MAX_FILTER_SUGGESTIONS, QUERY_MAX_SPANS_PER_T
 use crate::data::clickhouse::ClickhouseError;
 use crate::data::types::{
     EventRow, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams, ListTracesParams,
-    SessionRow, SpanRow, TraceRow, parse_finish_reasons, parse_tags,
+    SessionRow, SpanRow, TraceDeleteFilter, TraceRow, parse_finish_reasons, parse_tags,
 };
 use crate::utils::time::parse_iso_timestamp;
 
@@ -341,6 +341,14 @@ struct ChSpanRow {
     output_preview: Option<String>,
     raw_span: Option<String>,
     ingested_at_us: i64,
+    gen_ai_response_model: Option<String>,
+    gen_ai_cost_source: Option<String>,
+    gen_ai_reasoning_effort: Option<String>,
+    gen_ai_verbosity: Option<String>,
+    gen_ai_raw_model: Option<String>,
+    gen_ai_usage_web_search_calls: i64,
+    gen_ai_embedding_dimensions: Option<i64>,
+    gen_ai_encoding_format: Option<String>,
 }
 
 impl From<ChSpanRow> for SpanRow {
@@ -365,6 +373,7 @@ impl From<ChSpanRow> for SpanRow {
             user_id: row.user_id,
             gen_ai_system: row.gen_ai_system,
             gen_ai_request_model: row.gen_ai_request_model,
+            gen_ai_response_model: row.gen_ai_response_model,
             gen_ai_agent_name: row.gen_ai_agent_name,
             gen_ai_finish_reasons: parse_finish_reasons(&row.gen_ai_finish_reasons),
             gen_ai_usage_input_tokens: row.gen_ai_usage_input_tokens,
@@ -373,13 +382,20 @@ impl From<ChSpanRow> for SpanRow {
             gen_ai_usage_cache_read_tokens: row.gen_ai_usage_cache_read_tokens,
             gen_ai_usage_cache_write_tokens: row.gen_ai_usage_cache_write_tokens,
             gen_ai_usage_reasoning_tokens: row.gen_ai_usage_reasoning_tokens,
+            gen_ai_usage_web_search_calls: row.gen_ai_usage_web_search_calls,
             gen_ai_cost_input: row.gen_ai_cost_input,
             gen_ai_cost_output: row.gen_ai_cost_output,
             gen_ai_cost_cache_read: row.gen_ai_cost_cache_read,
             gen_ai_cost_cache_write: row.gen_ai_cost_cache_write,
             gen_ai_cost_reasoning: row.gen_ai_cost_reasoning,
             gen_ai_cost_total: row.gen_ai_cost_total,
+            gen_ai_cost_source: row.gen_ai_cost_source,
             gen_ai_usage_details: row.gen_ai_usage_details,
+            gen_ai_reasoning_effort: row.gen_ai_reasoning_effort,
+            gen_ai_verbosity: row.gen_ai_verbosity,
+            gen_ai_raw_model: row.gen_ai_raw_model,
+            gen_ai_embedding_dimensions: row.gen_ai_embedding_dimensions,
+            gen_ai_encoding_format: row.gen_ai_encoding_format,
             metadata: row.metadata,
             attributes: row.attributes,
             input_preview: row.input_preview,
@@ -567,6 +583,7 @@ pub async fn list_traces(
         "end_time" => "max_ts",
         "duration_ms" => "duration_ms",
         "total_cost" => "total_cost",
+        "total_tokens" => "total_tokens",
         "observation_count" => "observation_count",
         _ => "min_ts",
     };
@@ -621,13 +638,14 @@ pub async fn list_traces(
                 max(coalesce(sp.timestamp_end, sp.timestamp_start)) as max_ts,
                 dateDiff('millisecond', min(sp.timestamp_start), max(coalesce(sp.timestamp_end, sp.timestamp_start))) as duration_ms,
                 coalesce(max(gt.total_cost), 0) as total_cost,
+                coalesce(max(gt.total_tokens), 0) as total_tokens,
                 countIf(sp.observation_type != 'span') as observation_count
             FROM otel_spans sp FINAL
             LEFT JOIN gen_totals gt ON sp.trace_id = gt.trace_id
             WHERE {where_clause}
             GROUP BY sp.project_id, sp.trace_id
             {having_clause}
-            ORDER BY {ch_sort_field} {sort_dir}
+            ORDER BY {ch_sort_field} {sort_dir}, sp.trace_id ASC
             LIMIT {limit} OFFSET {offset}
         )
         SELECT
@@ -668,7 +686,7 @@ pub async fn list_traces(
         JOIN otel_spans s FINAL ON t.project_id = s.project_id AND t.trace_id = s.trace_id
         LEFT JOIN gen_totals gt2 ON t.trace_id = gt2.trace_id
         GROUP BY t.trace_id, t.min_ts
-        ORDER BY t.min_ts {sort_dir}
+        ORDER BY max(t.{ch_sort_field}) {sort_dir}, t.trace_id ASC
         "#,
         dedup_cte = dedup.0,
         dedup_condition = TOKEN_DEDUP_CONDITION,
@@ -740,7 +758,15 @@ pub async fn get_spans_for_trace(
             input_preview,
             output_preview,
             raw_span,
-            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us
+            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us,
+            gen_ai_response_model,
+            gen_ai_cost_source,
+            gen_ai_reasoning_effort,
+            gen_ai_verbosity,
+            gen_ai_raw_model,
+            gen_ai_usage_web_search_calls,
+            gen_ai_embedding_dimensions,
+            gen_ai_encoding_format
         FROM otel_spans FINAL
         WHERE project_id = ? AND trace_id = ?
         ORDER BY timestamp_start
@@ -760,6 +786,38 @@ pub async fn get_spans_for_trace(
     Ok(spans)
 }
 
+/// Get `span_id -> parent_span_id` links for a trace (root spans omitted)
+pub async fn get_parent_links_for_trace(
+    client: &Client,
+    project_id: &str,
+    trace_id: &str,
+) -> Result<std::collections::HashMap<String, String>, ClickhouseError> {
+    #[derive(Row, Deserialize)]
+    struct ParentLinkRow {
+        span_id: String,
+        parent_span_id: String,
+    }
+
+    let sql = format!(
+        "SELECT DISTINCT span_id, assumeNotNull(parent_span_id) AS parent_span_id
+         FROM otel_spans
+         WHERE project_id = ? AND trace_id = ? AND parent_span_id IS NOT NULL
+         LIMIT {}",
+        QUERY_MAX_SPANS_PER_TRACE
+    );
+
+    let rows: Vec<ParentLinkRow> = client
+        .query(&sql)
+        .bind(project_id)
+        .bind(trace_id)
+        .fetch_all()
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.span_id, row.parent_span_id))
+        .collect())
+}
+
 /// Get a single span
 pub async fn get_span(
     client: &Client,
@@ -807,7 +865,15 @@ pub async fn get_span(
             input_preview,
             output_preview,
             raw_span,
-            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us
+            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us,
+            gen_ai_response_model,
+            gen_ai_cost_source,
+            gen_ai_reasoning_effort,
+            gen_ai_verbosity,
+            gen_ai_raw_model,
+            gen_ai_usage_web_search_calls,
+            gen_ai_embedding_dimensions,
+            gen_ai_encoding_format
         FROM otel_spans FINAL
         WHERE project_id = ? AND trace_id = ? AND span_id = ?
         LIMIT 1
@@ -949,7 +1015,15 @@ pub async fn list_spans(
             input_preview,
             output_preview,
             raw_span,
-            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us
+            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us,
+            gen_ai_response_model,
+            gen_ai_cost_source,
+            gen_ai_reasoning_effort,
+            gen_ai_verbosity,
+            gen_ai_raw_model,
+            gen_ai_usage_web_search_calls,
+            gen_ai_embedding_dimensions,
+            gen_ai_encoding_format
         FROM otel_spans FINAL
         WHERE {}
         ORDER BY {}
@@ -1036,7 +1110,15 @@ pub async fn get_feed_spans(
             input_preview,
             output_preview,
             raw_span,
-            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us
+            toInt64(toUnixTimestamp64Micro(ingested_at)) as ingested_at_us,
+            gen_ai_response_model,
+            gen_ai_cost_source,
+            gen_ai_reasoning_effort,
+            gen_ai_verbosity,
+            gen_ai_raw_model,
+            gen_ai_usage_web_search_calls,
+            gen_ai_embedding_dimensions,
+            gen_ai_encoding_format
         FROM otel_spans FINAL
         WHERE {}
         ORDER BY ingested_at DESC, span_id DESC
@@ -1571,6 +1653,57 @@ pub async fn get_trace_ids_for_sessions(
     Ok(rows.into_iter().map(|r| r.trace_id).collect())
 }
 
+/// Get up to `limit` trace_ids matching a bulk-delete filter, ordered by
+/// trace_id and strictly after `after`
+pub async fn get_trace_ids_matching(
+    client: &Client,
+    project_id: &str,
+    filter: &TraceDeleteFilter,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<Vec<String>, ClickhouseError> {
+    let mut cb = ConditionBuilder::new();
+    cb.add_eq("project_id", project_id);
+    if let Some(after) = after {
+        cb.conditions.push("trace_id > ?".to_string());
+        cb.params.push(QueryParam::String(after.to_string()));
+    }
+    if let Some(ref from) = filter.from_timestamp {
+        cb.add_timestamp_gte("timestamp_start", from);
+    }
+    if let Some(ref to) = filter.to_timestamp {
+        cb.add_timestamp_lte("timestamp_start", to);
+    }
+    if let Some(ref environment) = filter.environment {
+        cb.add_eq("environment", environment);
+    }
+    if let Some(ref session_id) = filter.session_id {
+        cb.add_eq("session_id", session_id);
+    }
+    if let Some(has_error) = filter.has_error {
+        let op = if has_error { "IN" } else { "NOT IN" };
+        cb.conditions.push(format!(
+            "trace_id {op} (SELECT trace_id FROM otel_spans FINAL WHERE project_id = ? AND status_code = 'ERROR')"
+        ));
+        cb.params.push(QueryParam::String(project_id.to_string()));
+    }
+
+    let sql = format!(
+        "SELECT DISTINCT trace_id FROM otel_spans FINAL WHERE {} ORDER BY trace_id LIMIT {}",
+        cb.build(),
+        limit
+    );
+
+    #[derive(Row, Deserialize)]
+    struct TraceIdRow {
+        trace_id: String,
+    }
+
+    let rows: Vec<TraceIdRow> = cb.bind_to(client.query(&sql)).fetch_all().await?;
+
+    Ok(rows.into_iter().map(|r| r.trace_id).collect())
+}
+
 /// Get span counts (events and links) in bulk
 pub async fn get_span_counts_bulk(
     client: &Client,
@@ -1632,6 +1765,65 @@ pub async fn get_span_counts_bulk(
     Ok(counts)
 }
 
+/// Get per-model usage of generation spans for a set of traces.
+///
+/// Model is the response model, falling back to the request model.
+pub async fn get_trace_model_usage(
+    client: &Client,
+    project_id: &str,
+    trace_ids: &[String],
+) -> Result<Vec<crate::data::types::TraceModelUsageRow>, ClickhouseError> {
+    use crate::data::types::TraceModelUsageRow;
+
+    if trace_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders: Vec<&str> = trace_ids.iter().map(|_| "?").collect();
+    let sql = format!(
+        r#"SELECT
+            trace_id,
+            coalesce(nullIf(gen_ai_response_model, ''), gen_ai_request_model, '') AS model,
+            sum(gen_ai_usage_total_tokens) AS total_tokens,
+            sum(toFloat64(gen_ai_cost_total)) AS total_cost,
+            toInt64(toUnixTimestamp64Micro(min(timestamp_start))) AS first_seen
+         FROM otel_spans FINAL
+         WHERE project_id = ? AND trace_id IN ({})
+           AND observation_type = 'generation'
+           AND model != ''
+         GROUP BY trace_id, model"#,
+        placeholders.join(", ")
+    );
+
+    #[derive(Row, Deserialize)]
+    struct UsageRow {
+        trace_id: String,
+        model: String,
+        total_tokens: i64,
+        total_cost: f64,
+        first_seen: i64,
+    }
+
+    let mut query = client.query(&sql).bind(project_id);
+    for tid in trace_ids {
+        query = query.bind(tid);
+    }
+
+    let rows: Vec<UsageRow> = query.fetch_all().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TraceModelUsageRow {
+            trace_id: row.trace_id,
+            model: row.model,
+            total_tokens: row.total_tokens,
+            total_cost: row.total_cost,
+            first_seen: DateTime::from_timestamp_micros(row.first_seen)
+                .unwrap_or(DateTime::UNIX_EPOCH),
+        })
+        .collect())
+}
+
 /// Delete traces by IDs
 ///
 /// In distributed mode, `table` should be the local table name (e.g., `otel_spans_local`)
@@ -91,6 +91,16 @@ struct SpanRow {
     tool_definitions: String,
     tool_names: String,
     raw_span: Option<String>,
+    ingest_warnings: String,
+    gen_ai_usage_web_search_calls: i64,
+    gen_ai_cost_tool: i64,
+    gen_ai_reasoning_effort: Option<String>,
+    gen_ai_verbosity: Option<String>,
+    gen_ai_raw_model: Option<String>,
+    gen_ai_cost_source: Option<String>,
+    gen_ai_usage_merged_from: Option<String>,
+    gen_ai_embedding_dimensions: Option<i64>,
+    gen_ai_encoding_format: Option<String>,
 }
 
 impl From<&NormalizedSpan> for SpanRow {
@@ -198,6 +208,19 @@ impl From<&NormalizedSpan> for SpanRow {
                 .unwrap_or_else(|| "[]".to_string()),
             tool_names: span.tool_names.clone().unwrap_or_else(|| "[]".to_string()),
             raw_span: span.raw_span.clone(),
+            ingest_warnings: span
+                .ingest_warnings
+                .clone()
+                .unwrap_or_else(|| "[]".to_string()),
+            gen_ai_usage_web_search_calls: span.gen_ai_usage_web_search_calls,
+            gen_ai_cost_tool: to_decimal64(span.gen_ai_cost_tool),
+            gen_ai_reasoning_effort: span.gen_ai_reasoning_effort.clone(),
+            gen_ai_verbosity: span.gen_ai_verbosity.clone(),
+            gen_ai_raw_model: span.gen_ai_raw_model.clone(),
+            gen_ai_cost_source: span.gen_ai_cost_source.clone(),
+            gen_ai_usage_merged_from: span.gen_ai_usage_merged_from.clone(),
+            gen_ai_embedding_dimensions: span.gen_ai_embedding_dimensions,
+            gen_ai_encoding_format: span.gen_ai_encoding_format.clone(),
         }
     }
 }
@@ -1,5 +1,7 @@
 //! Stats repository for project-level aggregations (ClickHouse backend)
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Duration, NaiveTime, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
 use clickhouse::{Client, Row};
@@ -10,7 +12,8 @@ use crate::core::constants::QUERY_MAX_TOP_STATS;
 use crate::data::clickhouse::ClickhouseError;
 use crate::data::types::{
     CostsResult, CountsResult, FrameworkBreakdown, LatencyBucket, ModelBreakdown,
-    ProjectStatsResult, StatsParams, TokensResult, TrendBucket,
+    ProjectStatsResult, StatsParams, TimeseriesMetric, TimeseriesParams, TimeseriesPoint,
+    TokensResult, ToolStats, TrendBucket,
 };
 
 /// ClickHouse row for main aggregation
@@ -77,6 +80,23 @@ struct ChLatencyRow {
     avg_duration_ms: f64,
 }
 
+/// ClickHouse row for a timeseries bucket
+#[derive(Row, Deserialize)]
+struct ChTimeseriesRow {
+    bucket: i64,
+    value: f64,
+}
+
+/// ClickHouse row for per-tool stats
+#[derive(Row, Deserialize)]
+struct ChToolStatsRow {
+    tool_name: String,
+    call_count: i64,
+    error_count: i64,
+    error_rate: f64,
+    avg_duration_ms: Option<f64>,
+}
+
 /// Get project stats for the given time range
 pub async fn get_project_stats(
     client: &Client,
@@ -133,6 +153,82 @@ pub async fn get_project_stats(
     })
 }
 
+/// ClickHouse row for per-project cost
+#[derive(Row, Deserialize)]
+struct ChProjectCostRow {
+    project_id: String,
+    total_cost: f64,
+}
+
+/// Total cost per project over a time range, in one grouped query.
+///
+/// Uses the same token dedup as the project stats totals, so each value
+/// matches `get_project_stats(..).costs.total` for that project. Projects
+/// without cost are absent from the map.
+pub async fn get_project_costs(
+    client: &Client,
+    project_ids: &[String],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<HashMap<String, f64>, ClickhouseError> {
+    if project_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = project_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Same shape as build_time_scoped_dedup, widened to a set of projects
+    let sql = format!(
+        r#"
+        WITH dedup_lookup AS (
+            SELECT span_id, parent_span_id, trace_id, observation_type
+            FROM otel_spans FINAL
+            WHERE project_id IN ({placeholders})
+              AND (gen_ai_usage_input_tokens + gen_ai_usage_output_tokens) > 0
+              AND trace_id IN (
+                  SELECT DISTINCT trace_id FROM otel_spans
+                  WHERE project_id IN ({placeholders})
+                    AND timestamp_start >= fromUnixTimestamp64Micro(?)
+                    AND timestamp_start <= fromUnixTimestamp64Micro(?)
+              )
+        )
+        SELECT
+            g.project_id AS project_id,
+            round(coalesce(sum(toFloat64(g.gen_ai_cost_total)), 0), 4) AS total_cost
+        FROM otel_spans g FINAL
+        WHERE g.project_id IN ({placeholders})
+          AND g.timestamp_start >= fromUnixTimestamp64Micro(?)
+          AND g.timestamp_start <= fromUnixTimestamp64Micro(?)
+          AND {dedup_condition}
+        GROUP BY g.project_id
+        "#,
+        dedup_condition = TOKEN_DEDUP_CONDITION,
+    );
+
+    let from_micros = from.timestamp_micros();
+    let to_micros = to.timestamp_micros();
+    let mut q = client.query(&sql);
+    for pid in project_ids {
+        q = q.bind(pid);
+    }
+    for pid in project_ids {
+        q = q.bind(pid);
+    }
+    q = q.bind(from_micros).bind(to_micros);
+    for pid in project_ids {
+        q = q.bind(pid);
+    }
+    let rows: Vec<ChProjectCostRow> = q.bind(from_micros).bind(to_micros).fetch_all().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.project_id, row.total_cost))
+        .collect())
+}
+
 /// Intermediate counts from main aggregation
 struct MainCounts {
     traces: u64,
@@ -646,6 +742,178 @@ async fn query_latency_trend_data(
         .collect())
 }
 
+/// Get a zero-filled, time-bucketed series of one metric
+pub async fn get_timeseries(
+    client: &Client,
+    params: &TimeseriesParams,
+) -> Result<Vec<TimeseriesPoint>, ClickhouseError> {
+    let buckets = params.buckets();
+    if buckets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let from_micros = params.from_timestamp.timestamp_micros();
+    let to_micros = params.to_timestamp.timestamp_micros();
+
+    // Bucket boundaries as literal tuples
+    // (safe: derived from internal DateTime calculations, not user input)
+    let buckets_array = buckets
+        .iter()
+        .map(|(start, end)| {
+            format!(
+                "(fromUnixTimestamp64Micro({}), fromUnixTimestamp64Micro({}))",
+                start.timestamp_micros(),
+                end.timestamp_micros()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Token and cost series need the dedup lookup; span counts do not
+    let uses_dedup = matches!(
+        params.metric,
+        TimeseriesMetric::Cost | TimeseriesMetric::Tokens
+    );
+    let dedup = if uses_dedup {
+        build_time_scoped_dedup(
+            &params.project_id,
+            Some(&params.from_timestamp),
+            Some(&params.to_timestamp),
+        )
+    } else {
+        (String::new(), Vec::new())
+    };
+
+    let (value_expr, condition) = match params.metric {
+        TimeseriesMetric::Cost => ("coalesce(g.gen_ai_cost_total, 0)", TOKEN_DEDUP_CONDITION),
+        TimeseriesMetric::Tokens => (
+            "coalesce(g.gen_ai_usage_total_tokens, 0)",
+            TOKEN_DEDUP_CONDITION,
+        ),
+        TimeseriesMetric::Spans => ("1", "1"),
+        TimeseriesMetric::Errors => ("1", "g.status_code = 'ERROR'"),
+    };
+    let dedup_cte = if uses_dedup {
+        format!("{},", dedup.0)
+    } else {
+        String::new()
+    };
+
+    let sql = format!(
+        r#"
+        WITH bucket_ranges AS (
+            SELECT
+                tupleElement(b, 1) AS bucket_start,
+                tupleElement(b, 2) AS bucket_end
+            FROM (SELECT arrayJoin([{buckets_array}]) AS b)
+        ),
+        {dedup_cte}
+        metric_rows AS (
+            SELECT g.timestamp_start, toFloat64({value_expr}) AS value
+            FROM otel_spans g FINAL
+            WHERE g.project_id = ?
+              AND g.timestamp_start >= fromUnixTimestamp64Micro(?)
+              AND g.timestamp_start <= fromUnixTimestamp64Micro(?)
+              AND {condition}
+        ),
+        bucketed_data AS (
+            SELECT
+                br.bucket_start AS bucket,
+                sum(m.value) AS value
+            FROM metric_rows m, bucket_ranges br
+            WHERE m.timestamp_start >= br.bucket_start
+              AND m.timestamp_start < br.bucket_end
+            GROUP BY br.bucket_start
+        )
+        SELECT
+            toInt64(toUnixTimestamp64Micro(br.bucket_start)) AS bucket,
+            coalesce(bd.value, 0.0) AS value
+        FROM bucket_ranges br
+        LEFT JOIN bucketed_data bd ON bd.bucket = br.bucket_start
+        ORDER BY bucket ASC
+        "#,
+    );
+
+    // Bind order: dedup_lookup(project_id + time-scope) when used, then metric_rows
+    let mut q = client.query(&sql);
+    if uses_dedup {
+        q = q.bind(&params.project_id);
+        for param in &dedup.1 {
+            q = match param {
+                QueryParam::String(s) => q.bind(s.as_str()),
+                QueryParam::Int64(i) => q.bind(i),
+            };
+        }
+    }
+    let rows: Vec<ChTimeseriesRow> = q
+        .bind(&params.project_id)
+        .bind(from_micros)
+        .bind(to_micros)
+        .fetch_all()
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| TimeseriesPoint {
+            bucket: DateTime::from_timestamp_micros(r.bucket).unwrap_or(DateTime::UNIX_EPOCH),
+            value: r.value,
+        })
+        .collect())
+}
+
+/// Get call count, error count and average latency per tool name
+///
+/// Aggregates tool spans (observation_type = 'tool') started in the time range.
+/// A call counts as an error when its span status is ERROR.
+pub async fn get_tool_stats(
+    client: &Client,
+    params: &StatsParams,
+) -> Result<Vec<ToolStats>, ClickhouseError> {
+    let from_micros = params.from_timestamp.timestamp_micros();
+    let to_micros = params.to_timestamp.timestamp_micros();
+
+    let sql = format!(
+        r#"
+        SELECT
+            assumeNotNull(gen_ai_tool_name) AS tool_name,
+            toInt64(count()) AS call_count,
+            toInt64(countIf(status_code = 'ERROR')) AS error_count,
+            round(100.0 * countIf(status_code = 'ERROR') / count(), 1) AS error_rate,
+            avgOrNull(duration_ms) AS avg_duration_ms
+        FROM otel_spans FINAL
+        WHERE project_id = ?
+          AND timestamp_start >= fromUnixTimestamp64Micro(?)
+          AND timestamp_start <= fromUnixTimestamp64Micro(?)
+          AND observation_type = 'tool'
+          AND gen_ai_tool_name IS NOT NULL
+          AND gen_ai_tool_name != ''
+        GROUP BY gen_ai_tool_name
+        ORDER BY call_count DESC, tool_name
+        LIMIT {limit}
+        "#,
+        limit = QUERY_MAX_TOP_STATS
+    );
+
+    let rows: Vec<ChToolStatsRow> = client
+        .query(&sql)
+        .bind(&params.project_id)
+        .bind(from_micros)
+        .bind(to_micros)
+        .fetch_all()
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| ToolStats {
+            tool_name: r.tool_name,
+            call_count: r.call_count,
+            error_count: r.error_count,
+            error_rate: r.error_rate,
+            avg_duration_ms: r.avg_duration_ms,
+        })
+        .collect())
+}
+
 async fn query_recent_activity(client: &Client, project_id: &str) -> Result<i64, ClickhouseError> {
     let now = Utc::now();
     let five_min_ago = now - Duration::minutes(5);
@@ -14,7 +14,8 @@ use crate::data::traits::{AnalyticsRepository, FilterOptionRow};
 use crate::data::types::{
     EventRow, FeedMessagesParams, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams,
     ListTracesParams, MessageQueryParams, MessageQueryResult, NormalizedMetric, NormalizedSpan,
-    ProjectStatsResult, SessionRow, SpanCounts, SpanRow, StatsParams, TraceRow,
+    ProjectStatsResult, SessionRow, SpanCounts, SpanRow, StatsParams, TimeseriesParams,
+    TimeseriesPoint, ToolStats, TraceDeleteFilter, TraceModelUsageRow, TraceRow,
 };
 
 use super::ClickhouseService;
@@ -72,6 +73,16 @@ impl AnalyticsRepository for Arc<ClickhouseService> {
             .map_err(Into::into)
     }
 
+    async fn get_trace_model_usage(
+        &self,
+        project_id: &str,
+        trace_ids: &[String],
+    ) -> Result<Vec<TraceModelUsageRow>, DataError> {
+        query::get_trace_model_usage(self.client(), project_id, trace_ids)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn delete_traces(
         &self,
         project_id: &str,
@@ -84,6 +95,18 @@ impl AnalyticsRepository for Arc<ClickhouseService> {
             .map_err(Into::into)
     }
 
+    async fn get_trace_ids_matching(
+        &self,
+        project_id: &str,
+        filter: &TraceDeleteFilter,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>, DataError> {
+        query::get_trace_ids_matching(self.client(), project_id, filter, after, limit)
+            .await
+            .map_err(Into::into)
+    }
+
     // ==================== Span Operations ====================
 
     async fn list_spans(&self, params: &ListSpansParams) -> Result<(Vec<SpanRow>, u64), DataError> {
@@ -102,6 +125,16 @@ impl AnalyticsRepository for Arc<ClickhouseService> {
             .map_err(Into::into)
     }
 
+    async fn get_parent_links_for_trace(
+        &self,
+        project_id: &str,
+        trace_id: &str,
+    ) -> Result<HashMap<String, String>, DataError> {
+        query::get_parent_links_for_trace(self.client(), project_id, trace_id)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_span(
         &self,
         project_id: &str,
@@ -285,6 +318,32 @@ impl AnalyticsRepository for Arc<ClickhouseService> {
             .map_err(Into::into)
     }
 
+    async fn get_timeseries(
+        &self,
+        params: &TimeseriesParams,
+    ) -> Result<Vec<TimeseriesPoint>, DataError> {
+        stats::get_timeseries(self.client(), params)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_tool_stats(&self, params: &StatsParams) -> Result<Vec<ToolStats>, DataError> {
+        stats::get_tool_stats(self.client(), params)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_project_costs(
+        &self,
+        project_ids: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>, DataError> {
+        stats::get_project_costs(self.client(), project_ids, from, to)
+            .await
+            .map_err(Into::into)
+    }
+
     // ==================== Ingestion Operations ====================
 
     async fn insert_spans(&self, spans: Vec<NormalizedSpan>) -> Result<(), DataError> {
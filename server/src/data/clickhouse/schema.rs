@@ -14,7 +14,7 @@
 use crate::core::config::ClickhouseConfig;
 
 /// Current schema version
-pub const SCHEMA_VERSION: i32 = 2;
+pub const SCHEMA_VERSION: i32 = 6;
 
 /// Validate and return a cluster name safe for SQL interpolation.
 ///
@@ -184,6 +184,24 @@ CREATE TABLE IF NOT EXISTS otel_spans_local ON CLUSTER {cluster} (
     -- RAW SPAN (compressed)
     raw_span                    Nullable(String) CODEC(ZSTD(3)),
 
+    -- DATA QUALITY (issues found at extraction)
+    ingest_warnings             String DEFAULT '[]',
+
+    -- GEN AI: BUILT-IN TOOLS (billed per call, on top of tokens)
+    gen_ai_usage_web_search_calls   Int64 DEFAULT 0,
+    gen_ai_cost_tool            Decimal64(6) DEFAULT 0,
+
+    -- GEN AI: REQUEST CONTROLS & PROVENANCE
+    gen_ai_reasoning_effort     LowCardinality(Nullable(String)),
+    gen_ai_verbosity            LowCardinality(Nullable(String)),
+    gen_ai_raw_model            Nullable(String),
+    gen_ai_cost_source          LowCardinality(Nullable(String)),
+    gen_ai_usage_merged_from    Nullable(String),
+
+    -- GEN AI: EMBEDDING REQUEST PARAMETERS
+    gen_ai_embedding_dimensions Nullable(Int64),
+    gen_ai_encoding_format      LowCardinality(Nullable(String)),
+
     -- INDICES for fast lookups
     INDEX idx_trace_id trace_id TYPE bloom_filter GRANULARITY 1,
     INDEX idx_session_id session_id TYPE bloom_filter GRANULARITY 1,
@@ -334,6 +352,24 @@ CREATE TABLE IF NOT EXISTS otel_spans (
     -- RAW SPAN (compressed)
     raw_span                    Nullable(String) CODEC(ZSTD(3)),
 
+    -- DATA QUALITY (issues found at extraction)
+    ingest_warnings             String DEFAULT '[]',
+
+    -- GEN AI: BUILT-IN TOOLS (billed per call, on top of tokens)
+    gen_ai_usage_web_search_calls   Int64 DEFAULT 0,
+    gen_ai_cost_tool            Decimal64(6) DEFAULT 0,
+
+    -- GEN AI: REQUEST CONTROLS & PROVENANCE
+    gen_ai_reasoning_effort     LowCardinality(Nullable(String)),
+    gen_ai_verbosity            LowCardinality(Nullable(String)),
+    gen_ai_raw_model            Nullable(String),
+    gen_ai_cost_source          LowCardinality(Nullable(String)),
+    gen_ai_usage_merged_from    Nullable(String),
+
+    -- GEN AI: EMBEDDING REQUEST PARAMETERS
+    gen_ai_embedding_dimensions Nullable(Int64),
+    gen_ai_encoding_format      LowCardinality(Nullable(String)),
+
     -- INDICES for fast lookups
     INDEX idx_trace_id trace_id TYPE bloom_filter GRANULARITY 1,
     INDEX idx_session_id session_id TYPE bloom_filter GRANULARITY 1,
@@ -564,6 +600,77 @@ pub fn generate_schema(config: &ClickhouseConfig) -> Vec<String> {
     statements
 }
 
+/// Generate `ADD COLUMN` statements for the spans table.
+///
+/// In distributed mode the column is added to the local table on every node
+/// first, then to the distributed table that routes queries to it.
+fn add_span_column(config: &ClickhouseConfig, column: &str) -> Vec<String> {
+    if config.distributed {
+        let cluster = safe_cluster_name(config);
+        vec![
+            format!(
+                "ALTER TABLE otel_spans_local ON CLUSTER {cluster} ADD COLUMN IF NOT EXISTS {column}"
+            ),
+            format!(
+                "ALTER TABLE otel_spans ON CLUSTER {cluster} ADD COLUMN IF NOT EXISTS {column}"
+            ),
+        ]
+    } else {
+        vec![format!(
+            "ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS {column}"
+        )]
+    }
+}
+
+/// Generate the statements that migrate the schema to `version`.
+///
+/// Returns the migration name and statements, or `None` for an unknown version.
+pub fn migration_statements(
+    config: &ClickhouseConfig,
+    version: i32,
+) -> Option<(&'static str, Vec<String>)> {
+    match version {
+        3 => Some((
+            "add_ingest_warnings",
+            add_span_column(config, "ingest_warnings String DEFAULT '[]'"),
+        )),
+        4 => Some((
+            "add_tool_usage",
+            [
+                "gen_ai_usage_web_search_calls Int64 DEFAULT 0",
+                "gen_ai_cost_tool Decimal64(6) DEFAULT 0",
+            ]
+            .into_iter()
+            .flat_map(|column| add_span_column(config, column))
+            .collect(),
+        )),
+        5 => Some((
+            "add_span_provenance",
+            [
+                "gen_ai_reasoning_effort LowCardinality(Nullable(String))",
+                "gen_ai_verbosity LowCardinality(Nullable(String))",
+                "gen_ai_raw_model Nullable(String)",
+                "gen_ai_cost_source LowCardinality(Nullable(String))",
+                "gen_ai_usage_merged_from Nullable(String)",
+            ]
+            .into_iter()
+            .flat_map(|column| add_span_column(config, column))
+            .collect(),
+        )),
+        6 => Some((
+            "add_embedding_params",
+            [
+                "gen_ai_embedding_dimensions Nullable(Int64)",
+                "gen_ai_encoding_format LowCardinality(Nullable(String))",
+            ]
+            .into_iter()
+            .flat_map(|column| add_span_column(config, column))
+            .collect(),
+        )),
+        _ => None,
+    }
+}
+
 /// Get the table name to insert into (for distributed mode, insert to local)
 pub fn get_insert_table(config: &ClickhouseConfig, base_name: &str) -> String {
     if config.distributed {
@@ -660,6 +767,48 @@ mod tests {
         assert!(statements[2].contains("ENGINE = Distributed"));
     }
 
+    #[test]
+    fn test_migration_statements() {
+        let (name, statements) =
+            migration_statements(&default_config(), 3).expect("v3 migration exists");
+        assert_eq!(name, "add_ingest_warnings");
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].starts_with("ALTER TABLE otel_spans ADD COLUMN"));
+
+        let distributed = ClickhouseConfig {
+            cluster: Some("test_cluster".to_string()),
+            distributed: true,
+            ..default_config()
+        };
+        let (_, statements) = migration_statements(&distributed, 3).expect("v3 migration exists");
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("otel_spans_local ON CLUSTER test_cluster"));
+        assert!(statements[1].contains("otel_spans ON CLUSTER test_cluster"));
+
+        let (name, statements) =
+            migration_statements(&default_config(), 4).expect("v4 migration exists");
+        assert_eq!(name, "add_tool_usage");
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("gen_ai_usage_web_search_calls"));
+        assert!(statements[1].contains("gen_ai_cost_tool"));
+
+        let (name, statements) =
+            migration_statements(&default_config(), 5).expect("v5 migration exists");
+        assert_eq!(name, "add_span_provenance");
+        assert_eq!(statements.len(), 5);
+        assert!(statements[0].contains("gen_ai_reasoning_effort"));
+        assert!(statements[4].contains("gen_ai_usage_merged_from"));
+
+        let (name, statements) =
+            migration_statements(&default_config(), 6).expect("v6 migration exists");
+        assert_eq!(name, "add_embedding_params");
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("gen_ai_embedding_dimensions"));
+        assert!(statements[1].contains("gen_ai_encoding_format"));
+
+        assert!(migration_statements(&default_config(), 999).is_none());
+    }
+
     #[test]
     fn test_get_insert_table_single_node() {
         let config = default_config();
@@ -12,7 +12,8 @@ use crate::data::traits::{AnalyticsRepository, FilterOptionRow};
 use crate::data::types::{
     EventRow, FeedMessagesParams, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams,
     ListTracesParams, MessageQueryParams, MessageQueryResult, NormalizedMetric, NormalizedSpan,
-    SessionRow, SpanCounts, SpanRow, TraceRow, deduplicate_by_span_identity,
+    SessionRow, SpanCounts, SpanRow, TraceDeleteFilter, TraceModelUsageRow, TraceRow,
+    deduplicate_by_span_identity,
 };
 
 pub struct DedupAnalyticsRepository {
@@ -67,6 +68,16 @@ impl AnalyticsRepository for DedupAnalyticsRepository {
             .await
     }
 
+    async fn get_trace_model_usage(
+        &self,
+        project_id: &str,
+        trace_ids: &[String],
+    ) -> Result<Vec<TraceModelUsageRow>, DataError> {
+        self.inner
+            .get_trace_model_usage(project_id, trace_ids)
+            .await
+    }
+
     async fn delete_traces(
         &self,
         project_id: &str,
@@ -75,6 +86,18 @@ impl AnalyticsRepository for DedupAnalyticsRepository {
         self.inner.delete_traces(project_id, trace_ids).await
     }
 
+    async fn get_trace_ids_matching(
+        &self,
+        project_id: &str,
+        filter: &TraceDeleteFilter,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>, DataError> {
+        self.inner
+            .get_trace_ids_matching(project_id, filter, after, limit)
+            .await
+    }
+
     // ==================== Span Operations (DEDUP Vec<SpanRow>) ====================
 
     async fn list_spans(&self, params: &ListSpansParams) -> Result<(Vec<SpanRow>, u64), DataError> {
@@ -91,6 +114,16 @@ impl AnalyticsRepository for DedupAnalyticsRepository {
         Ok(deduplicate_by_span_identity(spans))
     }
 
+    async fn get_parent_links_for_trace(
+        &self,
+        project_id: &str,
+        trace_id: &str,
+    ) -> Result<HashMap<String, String>, DataError> {
+        self.inner
+            .get_parent_links_for_trace(project_id, trace_id)
+            .await
+    }
+
     async fn get_span(
         &self,
         project_id: &str,
@@ -248,6 +281,29 @@ impl AnalyticsRepository for DedupAnalyticsRepository {
         self.inner.get_project_stats(params).await
     }
 
+    async fn get_timeseries(
+        &self,
+        params: &crate::data::types::TimeseriesParams,
+    ) -> Result<Vec<crate::data::types::TimeseriesPoint>, DataError> {
+        self.inner.get_timeseries(params).await
+    }
+
+    async fn get_tool_stats(
+        &self,
+        params: &crate::data::types::StatsParams,
+    ) -> Result<Vec<crate::data::types::ToolStats>, DataError> {
+        self.inner.get_tool_stats(params).await
+    }
+
+    async fn get_project_costs(
+        &self,
+        project_ids: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>, DataError> {
+        self.inner.get_project_costs(project_ids, from, to).await
+    }
+
     // ==================== Ingestion Operations (pass-through) ====================
 
     async fn insert_spans(&self, spans: Vec<NormalizedSpan>) -> Result<(), DataError> {
@@ -83,9 +83,126 @@ fn apply_initial_schema(conn: &Connection) -> Result<(), DuckdbError> {
     })
 }
 
-fn apply_migration(_conn: &Connection, version: i32) -> Result<(), DuckdbError> {
+/// v2: per-span retention expiry with an index for the sweep.
+///
+/// DuckDB refuses to alter a table that has indexes, so the span indexes
+/// are dropped around the ALTER and recreated afterwards.
+const MIGRATION_V2_SPAN_EXPIRY: &str = r#"
+DROP INDEX IF EXISTS idx_spans_project_trace;
+DROP INDEX IF EXISTS idx_spans_project_ts;
+DROP INDEX IF EXISTS idx_spans_project_ingest;
+DROP INDEX IF EXISTS idx_spans_detail;
+DROP INDEX IF EXISTS idx_spans_project_session;
+DROP INDEX IF EXISTS idx_spans_project_span;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS idx_spans_project_trace ON otel_spans(project_id, trace_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ts ON otel_spans(project_id, timestamp_start DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, ingested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
+"#;
+
+/// v3: extraction-time data-quality warnings, surfaced in feed metadata.
+const MIGRATION_V3_INGEST_WARNINGS: &str = r#"
+DROP INDEX IF EXISTS idx_spans_project_trace;
+DROP INDEX IF EXISTS idx_spans_project_ts;
+DROP INDEX IF EXISTS idx_spans_project_ingest;
+DROP INDEX IF EXISTS idx_spans_detail;
+DROP INDEX IF EXISTS idx_spans_project_session;
+DROP INDEX IF EXISTS idx_spans_project_span;
+DROP INDEX IF EXISTS idx_spans_expires;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS ingest_warnings JSON NOT NULL DEFAULT '[]';
+CREATE INDEX IF NOT EXISTS idx_spans_project_trace ON otel_spans(project_id, trace_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ts ON otel_spans(project_id, timestamp_start DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, ingested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
+"#;
+
+/// v4: built-in tool calls (web search) and their per-call surcharge.
+const MIGRATION_V4_TOOL_USAGE: &str = r#"
+DROP INDEX IF EXISTS idx_spans_project_trace;
+DROP INDEX IF EXISTS idx_spans_project_ts;
+DROP INDEX IF EXISTS idx_spans_project_ingest;
+DROP INDEX IF EXISTS idx_spans_detail;
+DROP INDEX IF EXISTS idx_spans_project_session;
+DROP INDEX IF EXISTS idx_spans_project_span;
+DROP INDEX IF EXISTS idx_spans_expires;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_usage_web_search_calls BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_cost_tool DECIMAL(18,6) NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_spans_project_trace ON otel_spans(project_id, trace_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ts ON otel_spans(project_id, timestamp_start DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, ingested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
+"#;
+
+/// v5: request controls and cost/model provenance, kept out of usage details.
+const MIGRATION_V5_SPAN_PROVENANCE: &str = r#"
+DROP INDEX IF EXISTS idx_spans_project_trace;
+DROP INDEX IF EXISTS idx_spans_project_ts;
+DROP INDEX IF EXISTS idx_spans_project_ingest;
+DROP INDEX IF EXISTS idx_spans_detail;
+DROP INDEX IF EXISTS idx_spans_project_session;
+DROP INDEX IF EXISTS idx_spans_project_span;
+DROP INDEX IF EXISTS idx_spans_expires;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_reasoning_effort VARCHAR;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_verbosity VARCHAR;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_raw_model VARCHAR;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_cost_source VARCHAR;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_usage_merged_from VARCHAR;
+CREATE INDEX IF NOT EXISTS idx_spans_project_trace ON otel_spans(project_id, trace_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ts ON otel_spans(project_id, timestamp_start DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, ingested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
+"#;
+
+/// v6: embedding request parameters, kept out of usage details.
+const MIGRATION_V6_EMBEDDING_PARAMS: &str = r#"
+DROP INDEX IF EXISTS idx_spans_project_trace;
+DROP INDEX IF EXISTS idx_spans_project_ts;
+DROP INDEX IF EXISTS idx_spans_project_ingest;
+DROP INDEX IF EXISTS idx_spans_detail;
+DROP INDEX IF EXISTS idx_spans_project_session;
+DROP INDEX IF EXISTS idx_spans_project_span;
+DROP INDEX IF EXISTS idx_spans_expires;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_embedding_dimensions BIGINT;
+ALTER TABLE otel_spans ADD COLUMN IF NOT EXISTS gen_ai_encoding_format VARCHAR;
+CREATE INDEX IF NOT EXISTS idx_spans_project_trace ON otel_spans(project_id, trace_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ts ON otel_spans(project_id, timestamp_start DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, ingested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
+CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
+"#;
+
+fn apply_migration(conn: &Connection, version: i32) -> Result<(), DuckdbError> {
     match version {
         1 => Ok(()), // Handled by apply_initial_schema
+        2 => apply_versioned_migration(conn, 2, "add_span_expiry", MIGRATION_V2_SPAN_EXPIRY),
+        3 => {
+            apply_versioned_migration(conn, 3, "add_ingest_warnings", MIGRATION_V3_INGEST_WARNINGS)
+        }
+        4 => apply_versioned_migration(conn, 4, "add_tool_usage", MIGRATION_V4_TOOL_USAGE),
+        5 => {
+            apply_versioned_migration(conn, 5, "add_span_provenance", MIGRATION_V5_SPAN_PROVENANCE)
+        }
+        6 => apply_versioned_migration(
+            conn,
+            6,
+            "add_embedding_params",
+            MIGRATION_V6_EMBEDDING_PARAMS,
+        ),
         _ => Err(DuckdbError::MigrationFailed {
             version,
             name: "unknown".to_string(),
@@ -94,7 +211,6 @@ fn apply_migration(_conn: &Connection, version: i32) -> Result<(), DuckdbError>
     }
 }
 
-#[allow(dead_code)]
 /// Apply a versioned migration with transaction safety and audit logging.
 ///
 /// Use this function in `apply_migration` match arms for incremental schema changes.
@@ -190,6 +306,145 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_migrate_v2_adds_ingest_warnings() {
+        let conn = create_test_db();
+        run_migrations(&conn).expect("Initial migrations should succeed");
+
+        // Roll the table back to its v2 shape
+        conn.execute_batch(
+            "DROP INDEX idx_spans_project_trace;
+             DROP INDEX idx_spans_project_ts;
+             DROP INDEX idx_spans_project_ingest;
+             DROP INDEX idx_spans_detail;
+             DROP INDEX idx_spans_project_session;
+             DROP INDEX idx_spans_project_span;
+             DROP INDEX idx_spans_expires;
+             ALTER TABLE otel_spans DROP COLUMN ingest_warnings;
+             UPDATE schema_version SET version = 2 WHERE id = 1;",
+        )
+        .expect("Should roll back to v2");
+
+        run_migrations(&conn).expect("Migration to v3 should succeed");
+
+        let column_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.columns
+                 WHERE table_name = 'otel_spans' AND column_name = 'ingest_warnings'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Should query columns");
+        assert_eq!(column_count, 1);
+    }
+
+    #[test]
+    fn test_migrate_v3_adds_tool_usage() {
+        let conn = create_test_db();
+        run_migrations(&conn).expect("Initial migrations should succeed");
+
+        // Roll the table back to its v3 shape
+        conn.execute_batch(
+            "DROP INDEX idx_spans_project_trace;
+             DROP INDEX idx_spans_project_ts;
+             DROP INDEX idx_spans_project_ingest;
+             DROP INDEX idx_spans_detail;
+             DROP INDEX idx_spans_project_session;
+             DROP INDEX idx_spans_project_span;
+             DROP INDEX idx_spans_expires;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_usage_web_search_calls;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_cost_tool;
+             UPDATE schema_version SET version = 3 WHERE id = 1;",
+        )
+        .expect("Should roll back to v3");
+
+        run_migrations(&conn).expect("Migration to v4 should succeed");
+
+        let column_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.columns
+                 WHERE table_name = 'otel_spans'
+                   AND column_name IN ('gen_ai_usage_web_search_calls', 'gen_ai_cost_tool')",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Should query columns");
+        assert_eq!(column_count, 2);
+    }
+
+    #[test]
+    fn test_migrate_v4_adds_span_provenance() {
+        let conn = create_test_db();
+        run_migrations(&conn).expect("Initial migrations should succeed");
+
+        // Roll the table back to its v4 shape
+        conn.execute_batch(
+            "DROP INDEX idx_spans_project_trace;
+             DROP INDEX idx_spans_project_ts;
+             DROP INDEX idx_spans_project_ingest;
+             DROP INDEX idx_spans_detail;
+             DROP INDEX idx_spans_project_session;
+             DROP INDEX idx_spans_project_span;
+             DROP INDEX idx_spans_expires;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_reasoning_effort;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_verbosity;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_raw_model;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_cost_source;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_usage_merged_from;
+             UPDATE schema_version SET version = 4 WHERE id = 1;",
+        )
+        .expect("Should roll back to v4");
+
+        run_migrations(&conn).expect("Migration to v5 should succeed");
+
+        let column_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.columns
+                 WHERE table_name = 'otel_spans'
+                   AND column_name IN ('gen_ai_reasoning_effort', 'gen_ai_verbosity',
+                                       'gen_ai_raw_model', 'gen_ai_cost_source',
+                                       'gen_ai_usage_merged_from')",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Should query columns");
+        assert_eq!(column_count, 5);
+    }
+
+    #[test]
+    fn test_migrate_v5_adds_embedding_params() {
+        let conn = create_test_db();
+        run_migrations(&conn).expect("Initial migrations should succeed");
+
+        // Roll the table back to its v5 shape
+        conn.execute_batch(
+            "DROP INDEX idx_spans_project_trace;
+             DROP INDEX idx_spans_project_ts;
+             DROP INDEX idx_spans_project_ingest;
+             DROP INDEX idx_spans_detail;
+             DROP INDEX idx_spans_project_session;
+             DROP INDEX idx_spans_project_span;
+             DROP INDEX idx_spans_expires;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_embedding_dimensions;
+             ALTER TABLE otel_spans DROP COLUMN gen_ai_encoding_format;
+             UPDATE schema_version SET version = 5 WHERE id = 1;",
+        )
+        .expect("Should roll back to v5");
+
+        run_migrations(&conn).expect("Migration to v6 should succeed");
+
+        let column_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.columns
+                 WHERE table_name = 'otel_spans'
+                   AND column_name IN ('gen_ai_embedding_dimensions', 'gen_ai_encoding_format')",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Should query columns");
+        assert_eq!(column_count, 2);
+    }
+
     #[test]
     fn test_apply_migration_unknown_version() {
         let conn = create_test_db();
@@ -47,10 +47,14 @@ use crate::core::storage::{AppStorage, DataSubdir};
 /// Uses a single shared connection protected by a mutex.
 pub struct DuckdbService {
     conn: Mutex<Option<Connection>>,
+    /// Separate connection for health probes, so they never wait on the
+    /// shared connection while a long write holds it
+    probe: Mutex<Option<Connection>>,
 }
 
 impl Drop for DuckdbService {
     fn drop(&mut self) {
+        drop(self.probe.get_mut().take());
         if let Some(conn) = self.conn.get_mut().take() {
             // Best-effort close - log but don't panic on error
             if let Err((_, e)) = conn.close() {
@@ -81,10 +85,12 @@ impl DuckdbService {
         .map_err(|e| DuckdbError::Io(std::io::Error::other(e)))??;
 
         migrations::run_migrations(&conn)?;
+        let probe = conn.try_clone()?;
 
         tracing::debug!(path = %storage.subdir(DataSubdir::Duckdb).join(DUCKDB_DB_FILENAME).display(), "DuckdbService initialized");
         Ok(Self {
             conn: Mutex::new(Some(conn)),
+            probe: Mutex::new(Some(probe)),
         })
     }
 
@@ -149,9 +155,34 @@ impl DuckdbService {
         .map_err(|e| DuckdbError::Io(std::io::Error::other(e)))?
     }
 
+    /// Run a trivial query to verify the database is open and responsive.
+    ///
+    /// Uses the probe connection, so it does not block behind running writes.
+    pub async fn ping(self: &Arc<Self>) -> Result<(), DuckdbError> {
+        let db = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            let conn_guard = db.probe.lock();
+            let Some(ref conn) = *conn_guard else {
+                return Err(DuckdbError::Io(std::io::Error::other(
+                    "DuckDB connection is closed",
+                )));
+            };
+            conn.query_row("SELECT 1", [], |row| row.get::<_, i32>(0))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| DuckdbError::Io(std::io::Error::other(e)))?
+    }
+
     /// Close the DuckDB connection gracefully with explicit error handling
     pub async fn close(self: Arc<Self>) -> Result<(), DuckdbError> {
         tokio::task::spawn_blocking(move || {
+            // Close the probe first so the checkpoint below sees the only connection
+            if let Some(probe) = self.probe.lock().take()
+                && let Err((_, e)) = probe.close()
+            {
+                tracing::warn!("DuckDB probe connection close failed: {}", e);
+            }
             let mut conn_guard = self.conn.lock();
             if let Some(conn) = conn_guard.take() {
                 // Best-effort checkpoint before close - log but don't fail on error
@@ -376,6 +407,25 @@ mod tests {
         assert_eq!(version, schema::SCHEMA_VERSION);
     }
 
+    #[tokio::test]
+    async fn test_analytics_service_ping_while_conn_held() {
+        let (_temp_dir, storage) = create_test_storage().await;
+        let service = Arc::new(
+            DuckdbService::init(&storage)
+                .await
+                .expect("Init should succeed"),
+        );
+
+        // A long write holding the shared connection must not fail readiness
+        let conn = service.conn();
+        let result = tokio::time::timeout(Duration::from_secs(5), service.ping()).await;
+        drop(conn);
+        assert!(
+            matches!(result, Ok(Ok(()))),
+            "Ping should not wait on conn()"
+        );
+    }
+
     #[tokio::test]
     async fn test_analytics_service_is_open() {
         let (_temp_dir, storage) = create_test_storage().await;
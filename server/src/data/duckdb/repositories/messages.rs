@@ -39,12 +39,14 @@ const MESSAGE_SELECT_COLUMNS: &str = r#"
     tool_names,
     observation_type,
     session_id,
-    EPOCH_US(ingested_at) AS ingested_at_us"#;
+    EPOCH_US(ingested_at) AS ingested_at_us,
+    environment,
+    ingest_warnings"#;
 
 /// Shared content filter for message queries.
-/// Includes error spans even without messages.
-const MESSAGE_CONTENT_FILTER: &str =
-    "(messages != '[]' OR tool_definitions != '[]' OR tool_names != '[]' OR status_code = 'ERROR')";
+/// Includes error spans and spans with ingest warnings even without messages.
+const MESSAGE_CONTENT_FILTER: &str = "(messages != '[]' OR tool_definitions != '[]' OR tool_names != '[]' OR status_code = 'ERROR' \
+     OR ingest_warnings != '[]')";
 
 // ============================================================================
 // Query functions - return raw unfiltered data
@@ -177,6 +179,8 @@ fn parse_span_row(row: &duckdb::Row) -> Result<MessageSpanRow, duckdb::Error> {
         observation_type: row.get(18)?,
         session_id: row.get(19)?,
         ingested_at: micros_to_datetime(row.get::<_, i64>(20)?),
+        environment: row.get(21)?,
+        ingest_warnings_json: row.get::<_, Option<String>>(22)?.unwrap_or_default(),
     })
 }
 
@@ -244,6 +248,63 @@ mod tests {
         assert_eq!(result.rows[0].trace_id, "trace-1");
     }
 
+    #[tokio::test]
+    async fn test_compressed_messages_roundtrip_through_feed() {
+        use crate::domain::sideml::{ContentBlock, FeedOptions, process_spans};
+        use crate::domain::traces::RawMessage;
+        use crate::utils::compress::compress_json;
+
+        let (_temp_dir, analytics) = create_test_service().await;
+        let project_id = "test-project";
+
+        let long_text = "Summarize the quarterly report for the board. ".repeat(40);
+        let raw = |text: &str| {
+            serde_json::to_string(&[RawMessage::from_event(
+                "gen_ai.user.message",
+                Utc::now(),
+                serde_json::json!({ "content": text }),
+            )])
+            .unwrap()
+        };
+        let stored = compress_json(raw(&long_text));
+        assert!(
+            stored.starts_with('"'),
+            "Long messages should be compressed"
+        );
+
+        let compressed = make_span_with_messages(project_id, "trace-1", "span-1", &stored);
+        // Rows written before compression was enabled stay readable
+        let mut plain = make_span_with_messages(project_id, "trace-1", "span-2", &raw("Hi"));
+        plain.timestamp_start = compressed.timestamp_start + Duration::seconds(1);
+
+        {
+            let conn = analytics.conn();
+            insert_batch(&conn, &[compressed, plain]).expect("Insert should succeed");
+        }
+
+        let conn = analytics.conn();
+        let params = MessageQueryParams {
+            project_id: project_id.to_string(),
+            trace_id: Some("trace-1".to_string()),
+            ..Default::default()
+        };
+        let rows = get_messages(&conn, &params)
+            .expect("Query should succeed")
+            .rows;
+        assert_eq!(rows.len(), 2);
+
+        let result = process_spans(rows, &FeedOptions::default());
+        let texts: Vec<&str> = result
+            .messages
+            .iter()
+            .filter_map(|b| match &b.content {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec![long_text.as_str(), "Hi"]);
+    }
+
     #[tokio::test]
     async fn test_get_project_messages_filters_empty_spans() {
         let (_temp_dir, analytics) = create_test_service().await;
@@ -8,7 +8,7 @@ use crate::core::constants::{QUERY_MAX_FILTER_SUGGESTIONS, QUERY_MAX_SPANS_PER_T
 use crate::data::duckdb::{DuckdbError, in_transaction};
 use crate::data::types::{
     EventRow, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams, ListTracesParams,
-    SessionRow, SpanRow, TraceRow, parse_tags,
+    SessionRow, SpanRow, TraceDeleteFilter, TraceModelUsageRow, TraceRow, parse_tags,
 };
 use crate::utils::time::{micros_to_datetime, parse_iso_timestamp};
 
@@ -30,6 +30,39 @@ pub(crate) const DEDUP_SPANS: &str = "(SELECT s.* FROM otel_spans s \
      ) _d ON s.project_id = _d.project_id AND s.trace_id = _d.trace_id \
      AND s.span_id = _d.span_id AND s.ingested_at = _d._mi)";
 
+/// Spans that own their token usage, for span alias `g` (two-path filter):
+/// (1) generation spans without a token-reporting generation child,
+/// (2) non-generation token spans in traces without any generation spans,
+/// whose parent reports no tokens.
+/// Handles: Strands (agent->cycle->gen->botocore), LangGraph, structured output.
+pub(crate) const GEN_ROOT_CONDITION: &str = r#"(
+                  (g.observation_type = 'generation'
+                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
+                   AND NOT EXISTS (
+                       SELECT 1 FROM otel_spans c
+                       WHERE c.parent_span_id = g.span_id
+                         AND c.project_id = g.project_id
+                         AND c.observation_type = 'generation'
+                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
+                   ))
+                  OR
+                  (g.observation_type != 'generation'
+                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
+                   AND NOT EXISTS (
+                       SELECT 1 FROM otel_spans gen
+                       WHERE gen.trace_id = g.trace_id
+                         AND gen.project_id = g.project_id
+                         AND gen.observation_type = 'generation'
+                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
+                   )
+                   AND NOT EXISTS (
+                       SELECT 1 FROM otel_spans p
+                       WHERE p.span_id = g.parent_span_id
+                         AND p.project_id = g.project_id
+                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
+                   ))
+              )"#;
+
 /// Build span conditions with optional table alias.
 /// Returns (WHERE clause, bind values).
 fn build_trace_span_conditions(params: &ListTracesParams, alias: &str) -> (String, Vec<String>) {
@@ -154,6 +187,7 @@ pub fn list_traces(
         "end_time" => "max_ts",
         "duration_ms" => "duration_ms",
         "total_cost" => "total_cost",
+        "total_tokens" => "total_tokens",
         "observation_count" => "observation_count",
         _ => "min_ts",
     };
@@ -191,33 +225,7 @@ pub fn list_traces(
                 COALESCE(SUM(gen_ai_cost_total), 0) AS total_cost
             FROM {DEDUP_SPANS} g
             WHERE {span_where_g}
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
             GROUP BY g.trace_id
         ),
         filtered_traces AS (
@@ -228,13 +236,14 @@ pub fn list_traces(
                 MAX(COALESCE(sp.timestamp_end, sp.timestamp_start)) as max_ts,
                 DATE_DIFF('millisecond', MIN(sp.timestamp_start), MAX(COALESCE(sp.timestamp_end, sp.timestamp_start))) as duration_ms,
                 COALESCE(MAX(gt.total_cost), 0)::DOUBLE as total_cost,
+                COALESCE(MAX(gt.total_tokens), 0) as total_tokens,
                 COUNT(*) FILTER (WHERE sp.observation_type != 'span') as observation_count
             FROM {DEDUP_SPANS} sp
             LEFT JOIN gen_totals gt ON sp.trace_id = gt.trace_id
             WHERE {span_where_sp}
             GROUP BY sp.project_id, sp.trace_id
             {having_clause}
-            ORDER BY {span_sort_field} {sort_dir}
+            ORDER BY {span_sort_field} {sort_dir}, sp.trace_id ASC
             LIMIT {limit} OFFSET {offset}
         )
         SELECT
@@ -278,7 +287,7 @@ pub fn list_traces(
         JOIN {DEDUP_SPANS} s ON t.project_id = s.project_id AND t.trace_id = s.trace_id
         LEFT JOIN gen_totals gt2 ON t.trace_id = gt2.trace_id
         GROUP BY t.trace_id, t.min_ts
-        ORDER BY t.min_ts {sort_dir}
+        ORDER BY MAX(t.{span_sort_field}) {sort_dir}, t.trace_id ASC
         "#,
         span_where_g = span_where_g,
         span_where_sp = span_where_sp,
@@ -324,33 +333,7 @@ pub fn get_trace(
                 COALESCE(SUM(gen_ai_cost_total), 0) AS total_cost
             FROM {DEDUP_SPANS} g
             WHERE g.project_id = ? AND g.trace_id = ?
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
         )
         SELECT
             s.trace_id,
@@ -523,7 +506,9 @@ pub fn list_spans(
                 gen_ai_cost_input::DOUBLE, gen_ai_cost_output::DOUBLE, gen_ai_cost_cache_read::DOUBLE, gen_ai_cost_cache_write::DOUBLE,
                 gen_ai_cost_reasoning::DOUBLE, gen_ai_cost_total::DOUBLE,
                 gen_ai_usage_details::VARCHAR, metadata::VARCHAR, (raw_span->'attributes')::VARCHAR,
-                input_preview, output_preview, raw_span::VARCHAR, ingested_at
+                input_preview, output_preview, raw_span::VARCHAR, ingested_at, gen_ai_response_model,
+                gen_ai_cost_source, gen_ai_reasoning_effort, gen_ai_verbosity, gen_ai_raw_model,
+                gen_ai_usage_web_search_calls, gen_ai_embedding_dimensions, gen_ai_encoding_format
          FROM {DEDUP_SPANS} WHERE {} ORDER BY {} LIMIT {} OFFSET {}",
         where_clause, order, params.limit, offset,
         DEDUP_SPANS = DEDUP_SPANS
@@ -580,7 +565,9 @@ pub fn get_feed_spans(
                 gen_ai_cost_input::DOUBLE, gen_ai_cost_output::DOUBLE, gen_ai_cost_cache_read::DOUBLE, gen_ai_cost_cache_write::DOUBLE,
                 gen_ai_cost_reasoning::DOUBLE, gen_ai_cost_total::DOUBLE,
                 gen_ai_usage_details::VARCHAR, metadata::VARCHAR, (raw_span->'attributes')::VARCHAR,
-                input_preview, output_preview, raw_span::VARCHAR, ingested_at
+                input_preview, output_preview, raw_span::VARCHAR, ingested_at, gen_ai_response_model,
+                gen_ai_cost_source, gen_ai_reasoning_effort, gen_ai_verbosity, gen_ai_raw_model,
+                gen_ai_usage_web_search_calls, gen_ai_embedding_dimensions, gen_ai_encoding_format
          FROM {DEDUP_SPANS} WHERE {} ORDER BY ingested_at DESC, span_id DESC LIMIT {}",
         where_clause, params.limit,
         DEDUP_SPANS = DEDUP_SPANS
@@ -606,7 +593,9 @@ pub fn get_spans_for_trace(
                gen_ai_cost_input::DOUBLE, gen_ai_cost_output::DOUBLE, gen_ai_cost_cache_read::DOUBLE, gen_ai_cost_cache_write::DOUBLE,
                gen_ai_cost_reasoning::DOUBLE, gen_ai_cost_total::DOUBLE,
                gen_ai_usage_details::VARCHAR, metadata::VARCHAR, (raw_span->'attributes')::VARCHAR,
-               input_preview, output_preview, raw_span::VARCHAR, ingested_at
+               input_preview, output_preview, raw_span::VARCHAR, ingested_at, gen_ai_response_model,
+                gen_ai_cost_source, gen_ai_reasoning_effort, gen_ai_verbosity, gen_ai_raw_model,
+                gen_ai_usage_web_search_calls, gen_ai_embedding_dimensions, gen_ai_encoding_format
                FROM otel_spans WHERE project_id = ? AND trace_id = ? ORDER BY timestamp_start LIMIT {}",
         QUERY_MAX_SPANS_PER_TRACE
     );
@@ -614,6 +603,26 @@ pub fn get_spans_for_trace(
     execute_span_query(conn, &sql, &[project_id.to_string(), trace_id.to_string()])
 }
 
+/// Get `span_id -> parent_span_id` links for a trace (root spans omitted)
+pub fn get_parent_links_for_trace(
+    conn: &Connection,
+    project_id: &str,
+    trace_id: &str,
+) -> Result<std::collections::HashMap<String, String>, DuckdbError> {
+    let sql = format!(
+        "SELECT DISTINCT span_id, parent_span_id FROM otel_spans
+         WHERE project_id = ? AND trace_id = ? AND parent_span_id IS NOT NULL
+         LIMIT {}",
+        QUERY_MAX_SPANS_PER_TRACE
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([project_id, trace_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
 /// Get a single span by trace_id and span_id
 pub fn get_span(
     conn: &Connection,
@@ -631,7 +640,9 @@ pub fn get_span(
                gen_ai_cost_input::DOUBLE, gen_ai_cost_output::DOUBLE, gen_ai_cost_cache_read::DOUBLE, gen_ai_cost_cache_write::DOUBLE,
                gen_ai_cost_reasoning::DOUBLE, gen_ai_cost_total::DOUBLE,
                gen_ai_usage_details::VARCHAR, metadata::VARCHAR, (raw_span->'attributes')::VARCHAR,
-               input_preview, output_preview, raw_span::VARCHAR, ingested_at
+               input_preview, output_preview, raw_span::VARCHAR, ingested_at, gen_ai_response_model,
+                gen_ai_cost_source, gen_ai_reasoning_effort, gen_ai_verbosity, gen_ai_raw_model,
+                gen_ai_usage_web_search_calls, gen_ai_embedding_dimensions, gen_ai_encoding_format
                FROM otel_spans WHERE project_id = ? AND trace_id = ? AND span_id = ?";
 
     let mut stmt = conn.prepare(sql)?;
@@ -853,33 +864,7 @@ pub fn list_sessions(
                 COALESCE(SUM(gen_ai_cost_total), 0) AS total_cost
             FROM {DEDUP_SPANS} g
             WHERE {span_where_g}
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
             GROUP BY g.session_id
         ),
         filtered_sessions AS (
@@ -973,33 +958,7 @@ pub fn get_session(
             FROM {DEDUP_SPANS} g
             WHERE g.project_id = ?
               AND g.trace_id IN (SELECT trace_id FROM session_traces)
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
         )
         SELECT
             ? AS session_id,
@@ -1085,33 +1044,7 @@ pub fn get_traces_for_session(
             FROM {DEDUP_SPANS} g
             WHERE g.project_id = ?
               AND g.trace_id IN (SELECT trace_id FROM session_traces)
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
             GROUP BY g.trace_id
         )
         SELECT
@@ -1249,6 +1182,58 @@ pub fn get_span_counts_bulk(
     Ok(counts)
 }
 
+/// Get per-model usage of generation spans for a set of traces.
+///
+/// Model is the response model, falling back to the request model.
+pub fn get_trace_model_usage(
+    conn: &Connection,
+    project_id: &str,
+    trace_ids: &[String],
+) -> Result<Vec<TraceModelUsageRow>, DuckdbError> {
+    if trace_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders: Vec<&str> = trace_ids.iter().map(|_| "?").collect();
+    let sql = format!(
+        r#"SELECT
+            s.trace_id,
+            COALESCE(NULLIF(s.gen_ai_response_model, ''), s.gen_ai_request_model) AS model,
+            COALESCE(SUM(s.gen_ai_usage_total_tokens), 0)::BIGINT AS total_tokens,
+            COALESCE(SUM(s.gen_ai_cost_total), 0)::DOUBLE AS total_cost,
+            MIN(s.timestamp_start) AS first_seen
+         FROM {DEDUP_SPANS} s
+         WHERE s.project_id = ? AND s.trace_id IN ({})
+           AND s.observation_type = 'generation'
+           AND COALESCE(NULLIF(s.gen_ai_response_model, ''), s.gen_ai_request_model, '') != ''
+         GROUP BY s.trace_id, model"#,
+        placeholders.join(", ")
+    );
+
+    let mut params: Vec<&str> = Vec::with_capacity(1 + trace_ids.len());
+    params.push(project_id);
+    params.extend(trace_ids.iter().map(String::as_str));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn duckdb::ToSql> =
+        params.iter().map(|v| v as &dyn duckdb::ToSql).collect();
+    let mut rows = stmt.query(param_refs.as_slice())?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let first_seen_micros: i64 = row.get(4)?;
+        result.push(TraceModelUsageRow {
+            trace_id: row.get(0)?,
+            model: row.get(1)?,
+            total_tokens: row.get(2)?,
+            total_cost: row.get(3)?,
+            first_seen: micros_to_datetime(first_seen_micros),
+        });
+    }
+
+    Ok(result)
+}
+
 // --- Helper functions ---
 
 fn execute_count(conn: &Connection, sql: &str, bind_values: &[String]) -> Result<u64, DuckdbError> {
@@ -1362,6 +1347,7 @@ fn row_to_span(row: &Row<'_>) -> Result<SpanRow, DuckdbError> {
         user_id: row.get(15)?,
         gen_ai_system: row.get(16)?,
         gen_ai_request_model: row.get(17)?,
+        gen_ai_response_model: row.get(39)?,
         gen_ai_agent_name: row.get(18)?,
         gen_ai_finish_reasons: row
             .get::<_, Option<String>>(19)?
@@ -1373,13 +1359,20 @@ fn row_to_span(row: &Row<'_>) -> Result<SpanRow, DuckdbError> {
         gen_ai_usage_cache_read_tokens: row.get::<_, Option<i64>>(23)?.unwrap_or(0),
         gen_ai_usage_cache_write_tokens: row.get::<_, Option<i64>>(24)?.unwrap_or(0),
         gen_ai_usage_reasoning_tokens: row.get::<_, Option<i64>>(25)?.unwrap_or(0),
+        gen_ai_usage_web_search_calls: row.get::<_, Option<i64>>(44)?.unwrap_or(0),
         gen_ai_cost_input: row.get::<_, Option<f64>>(26)?.unwrap_or(0.0),
         gen_ai_cost_output: row.get::<_, Option<f64>>(27)?.unwrap_or(0.0),
         gen_ai_cost_cache_read: row.get::<_, Option<f64>>(28)?.unwrap_or(0.0),
         gen_ai_cost_cache_write: row.get::<_, Option<f64>>(29)?.unwrap_or(0.0),
         gen_ai_cost_reasoning: row.get::<_, Option<f64>>(30)?.unwrap_or(0.0),
         gen_ai_cost_total: row.get::<_, Option<f64>>(31)?.unwrap_or(0.0),
+        gen_ai_cost_source: row.get(40)?,
         gen_ai_usage_details: row.get(32)?,
+        gen_ai_reasoning_effort: row.get(41)?,
+        gen_ai_verbosity: row.get(42)?,
+        gen_ai_raw_model: row.get(43)?,
+        gen_ai_embedding_dimensions: row.get(45)?,
+        gen_ai_encoding_format: row.get(46)?,
         metadata: row.get(33)?,
         attributes: row.get(34)?,
         input_preview: row.get(35)?,
@@ -1471,6 +1464,64 @@ pub fn delete_traces(
     })
 }
 
+/// Get up to `limit` trace_ids matching a bulk-delete filter, ordered by
+/// trace_id and strictly after `after`
+pub fn get_trace_ids_matching(
+    conn: &Connection,
+    project_id: &str,
+    filter: &TraceDeleteFilter,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<Vec<String>, DuckdbError> {
+    let mut conditions = vec!["project_id = ?".to_string()];
+    let mut all_params: Vec<String> = vec![project_id.to_string()];
+
+    if let Some(after) = after {
+        conditions.push("trace_id > ?".to_string());
+        all_params.push(after.to_string());
+    }
+    if let Some(from) = filter.from_timestamp {
+        conditions.push("timestamp_start >= ?".to_string());
+        all_params.push(from.to_rfc3339());
+    }
+    if let Some(to) = filter.to_timestamp {
+        conditions.push("timestamp_start <= ?".to_string());
+        all_params.push(to.to_rfc3339());
+    }
+    if let Some(ref environment) = filter.environment {
+        conditions.push("environment = ?".to_string());
+        all_params.push(environment.clone());
+    }
+    if let Some(ref session_id) = filter.session_id {
+        conditions.push("session_id = ?".to_string());
+        all_params.push(session_id.clone());
+    }
+    if let Some(has_error) = filter.has_error {
+        let op = if has_error { "IN" } else { "NOT IN" };
+        conditions.push(format!(
+            "trace_id {op} (SELECT trace_id FROM otel_spans WHERE project_id = ? AND status_code = 'ERROR')"
+        ));
+        all_params.push(project_id.to_string());
+    }
+
+    let sql = format!(
+        "SELECT DISTINCT trace_id FROM otel_spans WHERE {} ORDER BY trace_id LIMIT {}",
+        conditions.join(" AND "),
+        limit
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let params: Vec<&dyn duckdb::ToSql> =
+        all_params.iter().map(|v| v as &dyn duckdb::ToSql).collect();
+    let mut rows = stmt.query(params.as_slice())?;
+
+    let mut trace_ids: Vec<String> = vec![];
+    while let Some(row) = rows.next()? {
+        trace_ids.push(row.get(0)?);
+    }
+
+    Ok(trace_ids)
+}
+
 /// Get trace_ids for given session_ids
 pub fn get_trace_ids_for_sessions(
     conn: &Connection,
@@ -2317,6 +2368,61 @@ mod tests {
         );
     }
 
+    /// Test list_traces ordering by cost, with ties broken by trace_id
+    #[tokio::test]
+    async fn test_list_traces_order_by_cost_desc() {
+        use crate::api::types::{OrderBy, OrderDirection};
+
+        let (_temp_dir, analytics) = create_test_service().await;
+        let project_id = "test-project";
+
+        // The most expensive trace is the oldest, so time order would put it last
+        let base_ts = Utc::now();
+        let mut spans = vec![
+            make_generation_span(project_id, "trace-expensive", "gen-1", None, 0.50, 100),
+            make_generation_span(project_id, "trace-b", "gen-2", None, 0.10, 5000),
+            make_generation_span(project_id, "trace-a", "gen-3", None, 0.10, 200),
+        ];
+        for (i, span) in spans.iter_mut().enumerate() {
+            span.timestamp_start = base_ts + chrono::Duration::seconds(i as i64);
+        }
+
+        {
+            let conn = analytics.conn();
+            insert_batch(&conn, &spans).expect("Insert should succeed");
+        }
+
+        let conn = analytics.conn();
+        let list = |column: &str, direction| {
+            let params = ListTracesParams {
+                project_id: project_id.to_string(),
+                page: 1,
+                limit: 100,
+                order_by: Some(OrderBy {
+                    column: column.to_string(),
+                    direction,
+                }),
+                include_nongenai: true,
+                ..Default::default()
+            };
+            let (traces, _total) = list_traces(&conn, &params).expect("Query should succeed");
+            traces.into_iter().map(|t| t.trace_id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            list("total_cost", OrderDirection::Desc),
+            vec!["trace-expensive", "trace-a", "trace-b"]
+        );
+        assert_eq!(
+            list("total_cost", OrderDirection::Asc),
+            vec!["trace-a", "trace-b", "trace-expensive"]
+        );
+        assert_eq!(
+            list("total_tokens", OrderDirection::Desc),
+            vec!["trace-b", "trace-a", "trace-expensive"]
+        );
+    }
+
     /// Test cache tokens with nested generations (Strands pattern)
     /// Cache tokens only exist on parent spans, not leaf spans.
     /// Cache tokens should be summed from ALL generations (not leaf-only).
@@ -3527,6 +3633,39 @@ mod tests {
         assert_eq!(result.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_get_span_reads_provenance_columns() {
+        let (_temp_dir, analytics) = create_test_service().await;
+        let project_id = "test-project";
+
+        let mut span = make_generation_span(project_id, "trace-1", "span-1", None, 0.01, 100);
+        span.gen_ai_reasoning_effort = Some("high".to_string());
+        span.gen_ai_verbosity = Some("low".to_string());
+        span.gen_ai_raw_model = Some("fast-model".to_string());
+        span.gen_ai_cost_source = Some("reported".to_string());
+        span.gen_ai_usage_merged_from = Some("span-2".to_string());
+        span.gen_ai_usage_web_search_calls = 3;
+        span.gen_ai_embedding_dimensions = Some(1024);
+        span.gen_ai_encoding_format = Some("base64".to_string());
+        {
+            let conn = analytics.conn();
+            insert_batch(&conn, &[span]).expect("Insert should succeed");
+        }
+
+        let conn = analytics.conn();
+        let row = get_span(&conn, project_id, "trace-1", "span-1")
+            .expect("Query should succeed")
+            .expect("Span should exist");
+        assert_eq!(row.gen_ai_reasoning_effort.as_deref(), Some("high"));
+        assert_eq!(row.gen_ai_verbosity.as_deref(), Some("low"));
+        assert_eq!(row.gen_ai_raw_model.as_deref(), Some("fast-model"));
+        assert_eq!(row.gen_ai_cost_source.as_deref(), Some("reported"));
+        assert_eq!(row.gen_ai_usage_details, None);
+        assert_eq!(row.gen_ai_usage_web_search_calls, 3);
+        assert_eq!(row.gen_ai_embedding_dimensions, Some(1024));
+        assert_eq!(row.gen_ai_encoding_format.as_deref(), Some("base64"));
+    }
+
     #[tokio::test]
     async fn test_get_feed_spans_is_observation_filter() {
         let (_temp_dir, analytics) = create_test_service().await;
@@ -3683,4 +3822,64 @@ mod tests {
             "Should return empty for nonexistent project"
         );
     }
+
+    #[tokio::test]
+    async fn test_delete_traces_matching_environment() {
+        let (_temp_dir, analytics) = create_test_service().await;
+        let project_id = "test-project";
+
+        let span = |trace_id: &str, span_id: &str, environment: &str, status: &str| {
+            let mut span = make_agent_span(project_id, trace_id, span_id, None);
+            span.environment = Some(environment.to_string());
+            span.status_code = Some(status.to_string());
+            span
+        };
+        let spans = vec![
+            span("trace-test-1", "s1", "test", "OK"),
+            span("trace-test-2", "s2", "test", "ERROR"),
+            span("trace-prod", "s3", "production", "OK"),
+        ];
+        {
+            let conn = analytics.conn();
+            insert_batch(&conn, &spans).expect("Insert should succeed");
+        }
+
+        let conn = analytics.conn();
+        let errored_test = TraceDeleteFilter {
+            environment: Some("test".to_string()),
+            has_error: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_trace_ids_matching(&conn, project_id, &errored_test, None, 100).unwrap(),
+            vec!["trace-test-2"]
+        );
+
+        let by_environment = TraceDeleteFilter {
+            environment: Some("test".to_string()),
+            ..Default::default()
+        };
+        // Pages are ordered by trace_id and continue after the last ID
+        let first = get_trace_ids_matching(&conn, project_id, &by_environment, None, 1).unwrap();
+        assert_eq!(first, vec!["trace-test-1"]);
+        let second =
+            get_trace_ids_matching(&conn, project_id, &by_environment, Some(&first[0]), 1).unwrap();
+        assert_eq!(second, vec!["trace-test-2"]);
+        assert!(
+            get_trace_ids_matching(&conn, project_id, &by_environment, Some(&second[0]), 1)
+                .unwrap()
+                .is_empty()
+        );
+
+        let trace_ids =
+            get_trace_ids_matching(&conn, project_id, &by_environment, None, 100).unwrap();
+        assert_eq!(trace_ids, vec!["trace-test-1", "trace-test-2"]);
+        assert_eq!(delete_traces(&conn, project_id, &trace_ids).unwrap(), 2);
+
+        // Only the production trace remains
+        let remaining =
+            get_trace_ids_matching(&conn, project_id, &TraceDeleteFilter::default(), None, 100)
+                .expect("Query should succeed");
+        assert_eq!(remaining, vec!["trace-prod"]);
+    }
 }
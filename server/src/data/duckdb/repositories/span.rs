@@ -120,6 +120,22 @@ fn insert_spans(conn: &Connection, spans: &[NormalizedSpan]) -> Result<(), Duckd
             span.tool_names.as_deref().unwrap_or("[]"),
             // Pre-serialized raw span JSON
             span.raw_span.as_deref(),
+            // RETENTION
+            SqlOptTimestamp(span.expires_at),
+            // DATA QUALITY
+            span.ingest_warnings.as_deref().unwrap_or("[]"),
+            // GEN AI: BUILT-IN TOOLS
+            span.gen_ai_usage_web_search_calls,
+            span.gen_ai_cost_tool,
+            // GEN AI: REQUEST CONTROLS & PROVENANCE
+            span.gen_ai_reasoning_effort.as_deref(),
+            span.gen_ai_verbosity.as_deref(),
+            span.gen_ai_raw_model.as_deref(),
+            span.gen_ai_cost_source.as_deref(),
+            span.gen_ai_usage_merged_from.as_deref(),
+            // GEN AI: EMBEDDING REQUEST PARAMETERS
+            span.gen_ai_embedding_dimensions,
+            span.gen_ai_encoding_format.as_deref(),
         ])?;
     }
 
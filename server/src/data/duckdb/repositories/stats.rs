@@ -1,15 +1,18 @@
 //! Stats repository for project-level aggregations
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Duration, NaiveTime, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
 use duckdb::Connection;
 
 use crate::core::constants::QUERY_MAX_TOP_STATS;
 use crate::data::duckdb::DuckdbError;
-use crate::data::duckdb::repositories::query::DEDUP_SPANS;
+use crate::data::duckdb::repositories::query::{DEDUP_SPANS, GEN_ROOT_CONDITION};
 use crate::data::types::{
     CostsResult, CountsResult, FrameworkBreakdown, LatencyBucket, ModelBreakdown,
-    ProjectStatsResult, StatsParams, TokensResult, TrendBucket,
+    ProjectStatsResult, StatsParams, TimeseriesMetric, TimeseriesParams, TimeseriesPoint,
+    TokensResult, ToolStats, TrendBucket,
 };
 
 /// Get project stats for the given time range
@@ -73,6 +76,58 @@ pub fn get_project_stats(
     })
 }
 
+/// Total cost per project over a time range, in one grouped query.
+///
+/// Uses the same token filter as the project stats totals, so each value
+/// matches `get_project_stats(..).costs.total` for that project. Projects
+/// without cost are absent from the map.
+pub fn get_project_costs(
+    conn: &Connection,
+    project_ids: &[String],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<HashMap<String, f64>, DuckdbError> {
+    if project_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders: Vec<&str> = project_ids.iter().map(|_| "?").collect();
+    let sql = format!(
+        r#"
+        SELECT
+            g.project_id,
+            ROUND(COALESCE(SUM(g.gen_ai_cost_total), 0)::DOUBLE, 4) AS total_cost
+        FROM {DEDUP_SPANS} g
+        WHERE g.project_id IN ({placeholders})
+          AND g.timestamp_start >= ?
+          AND g.timestamp_start <= ?
+          AND {GEN_ROOT_CONDITION}
+        GROUP BY g.project_id
+    "#,
+        DEDUP_SPANS = DEDUP_SPANS,
+        placeholders = placeholders.join(", "),
+    );
+
+    let from_str = from.to_rfc3339();
+    let to_str = to.to_rfc3339();
+    let mut params: Vec<&dyn duckdb::ToSql> = project_ids
+        .iter()
+        .map(|v| v as &dyn duckdb::ToSql)
+        .collect();
+    params.push(&from_str);
+    params.push(&to_str);
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(params.as_slice())?;
+    let mut result = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let project_id: String = row.get(0)?;
+        let cost: f64 = row.get(1)?;
+        result.insert(project_id, cost);
+    }
+    Ok(result)
+}
+
 /// Intermediate counts from main aggregation
 struct MainCounts {
     traces: i64,
@@ -107,33 +162,7 @@ fn query_main_aggregation(
             WHERE g.project_id = ?
               AND g.timestamp_start >= ?
               AND g.timestamp_start <= ?
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
         )
         SELECT
             COUNT(DISTINCT s.trace_id) AS traces,
@@ -343,33 +372,7 @@ fn query_model_breakdown(
             WHERE g.project_id = ?
               AND g.timestamp_start >= ?
               AND g.timestamp_start <= ?
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
         ),
         model_stats AS (
             SELECT
@@ -457,33 +460,7 @@ fn query_trend_data(
             WHERE g.project_id = ?
               AND g.timestamp_start >= ?
               AND g.timestamp_start <= ?
-              AND (
-                  (g.observation_type = 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans c
-                       WHERE c.parent_span_id = g.span_id
-                         AND c.project_id = g.project_id
-                         AND c.observation_type = 'generation'
-                         AND (c.gen_ai_usage_input_tokens + c.gen_ai_usage_output_tokens) > 0
-                   ))
-                  OR
-                  (g.observation_type != 'generation'
-                   AND (g.gen_ai_usage_input_tokens + g.gen_ai_usage_output_tokens) > 0
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans gen
-                       WHERE gen.trace_id = g.trace_id
-                         AND gen.project_id = g.project_id
-                         AND gen.observation_type = 'generation'
-                         AND (gen.gen_ai_usage_input_tokens + gen.gen_ai_usage_output_tokens) > 0
-                   )
-                   AND NOT EXISTS (
-                       SELECT 1 FROM otel_spans p
-                       WHERE p.span_id = g.parent_span_id
-                         AND p.project_id = g.project_id
-                         AND (p.gen_ai_usage_input_tokens + p.gen_ai_usage_output_tokens) > 0
-                   ))
-              )
+              AND {GEN_ROOT_CONDITION}
         ),
         data_buckets AS (
             SELECT
@@ -608,6 +585,133 @@ fn query_latency_trend_data(
     Ok(result)
 }
 
+/// Get a zero-filled, time-bucketed series of one metric
+pub fn get_timeseries(
+    conn: &Connection,
+    params: &TimeseriesParams,
+) -> Result<Vec<TimeseriesPoint>, DuckdbError> {
+    let buckets = params.buckets();
+    if buckets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Bucket boundaries are computed in Rust (timezone-aware), not user input
+    let values_clause = buckets
+        .iter()
+        .map(|(start, end)| {
+            format!(
+                "('{}'::TIMESTAMP, '{}'::TIMESTAMP)",
+                start.to_rfc3339(),
+                end.to_rfc3339()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let (value_expr, condition) = match params.metric {
+        TimeseriesMetric::Cost => ("COALESCE(g.gen_ai_cost_total, 0)", GEN_ROOT_CONDITION),
+        TimeseriesMetric::Tokens => (
+            "COALESCE(g.gen_ai_usage_total_tokens, 0)",
+            GEN_ROOT_CONDITION,
+        ),
+        TimeseriesMetric::Spans => ("1", "TRUE"),
+        TimeseriesMetric::Errors => ("1", "g.status_code = 'ERROR'"),
+    };
+
+    let sql = format!(
+        r#"
+        WITH bucket_ranges AS (
+            SELECT col0 AS bucket_start, col1 AS bucket_end FROM (VALUES {values_clause})
+        ),
+        metric_rows AS (
+            SELECT g.timestamp_start, ({value_expr})::DOUBLE AS value
+            FROM {DEDUP_SPANS} g
+            WHERE g.project_id = ?
+              AND g.timestamp_start >= ?
+              AND g.timestamp_start <= ?
+              AND {condition}
+        )
+        SELECT EPOCH_US(br.bucket_start) AS bucket, COALESCE(SUM(m.value), 0)::DOUBLE AS value
+        FROM bucket_ranges br
+        LEFT JOIN metric_rows m ON
+            m.timestamp_start >= br.bucket_start
+            AND m.timestamp_start < br.bucket_end
+        GROUP BY br.bucket_start
+        ORDER BY bucket ASC
+        "#,
+    );
+
+    let from_str = params.from_timestamp.to_rfc3339();
+    let to_str = params.to_timestamp.to_rfc3339();
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([&params.project_id, &from_str, &to_str], |row| {
+        let bucket_ts: i64 = row.get(0)?;
+        Ok(TimeseriesPoint {
+            bucket: DateTime::from_timestamp_micros(bucket_ts).unwrap_or(DateTime::UNIX_EPOCH),
+            value: row.get(1)?,
+        })
+    })?;
+
+    let mut result = Vec::new();
+    for row in rows {
+        result.push(row?);
+    }
+    Ok(result)
+}
+
+/// Get call count, error count and average latency per tool name
+///
+/// Aggregates tool spans (observation_type = 'tool') started in the time range.
+/// A call counts as an error when its span status is ERROR.
+pub fn get_tool_stats(
+    conn: &Connection,
+    params: &StatsParams,
+) -> Result<Vec<ToolStats>, DuckdbError> {
+    let sql = format!(
+        r#"
+        SELECT
+            g.gen_ai_tool_name AS tool_name,
+            COUNT(*) AS call_count,
+            COUNT(*) FILTER (WHERE g.status_code = 'ERROR') AS error_count,
+            ROUND(100.0 * COUNT(*) FILTER (WHERE g.status_code = 'ERROR') / COUNT(*), 1)::DOUBLE AS error_rate,
+            AVG(g.duration_ms)::DOUBLE AS avg_duration_ms
+        FROM {DEDUP_SPANS} g
+        WHERE g.project_id = ?
+          AND g.timestamp_start >= ?
+          AND g.timestamp_start <= ?
+          AND g.observation_type = 'tool'
+          AND g.gen_ai_tool_name IS NOT NULL
+          AND g.gen_ai_tool_name != ''
+        GROUP BY g.gen_ai_tool_name
+        ORDER BY call_count DESC, tool_name
+        LIMIT {limit}
+    "#,
+        DEDUP_SPANS = DEDUP_SPANS,
+        limit = QUERY_MAX_TOP_STATS
+    );
+
+    let from_str = params.from_timestamp.to_rfc3339();
+    let to_str = params.to_timestamp.to_rfc3339();
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([&params.project_id, &from_str, &to_str], |row| {
+        Ok(ToolStats {
+            tool_name: row.get(0)?,
+            call_count: row.get(1)?,
+            error_count: row.get(2)?,
+            error_rate: row.get(3)?,
+            avg_duration_ms: row.get(4)?,
+        })
+    })?;
+
+    let mut result = Vec::new();
+    for row in rows {
+        result.push(row?);
+    }
+    Ok(result)
+}
+
 /// Parse timezone string using chrono-tz.
 /// Returns UTC if the timezone is invalid or None.
 fn parse_timezone(tz: Option<&str>) -> Tz {
@@ -701,6 +805,204 @@ mod tests {
     use super::*;
     use chrono::Datelike;
 
+    #[tokio::test]
+    async fn test_timeseries_cost_two_hour_buckets() {
+        use crate::core::storage::AppStorage;
+        use crate::data::duckdb::DuckdbService;
+        use crate::data::duckdb::repositories::span::insert_batch;
+        use crate::data::types::{NormalizedSpan, ObservationType, TimeseriesInterval};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        let service = DuckdbService::init(&storage).await.unwrap();
+
+        let hour = DateTime::parse_from_rfc3339("2025-03-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let generation = |span_id: &str, offset_min: i64, cost: f64| NormalizedSpan {
+            project_id: Some("p1".to_string()),
+            trace_id: format!("trace-{span_id}"),
+            span_id: span_id.to_string(),
+            observation_type: Some(ObservationType::Generation),
+            timestamp_start: hour + Duration::minutes(offset_min),
+            gen_ai_usage_input_tokens: 100,
+            gen_ai_usage_output_tokens: 50,
+            gen_ai_usage_total_tokens: 150,
+            gen_ai_cost_total: cost,
+            ..Default::default()
+        };
+        let spans = vec![
+            generation("a", 5, 0.25),
+            generation("b", 40, 0.5),
+            generation("c", 75, 1.0),
+        ];
+
+        let conn = service.conn();
+        insert_batch(&conn, &spans).unwrap();
+
+        let mut params = TimeseriesParams {
+            project_id: "p1".to_string(),
+            from_timestamp: hour,
+            to_timestamp: hour + Duration::minutes(119),
+            timezone: None,
+            interval: TimeseriesInterval::Hour,
+            metric: TimeseriesMetric::Cost,
+        };
+        let points = get_timeseries(&conn, &params).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].bucket, hour);
+        assert!((points[0].value - 0.75).abs() < 1e-9);
+        assert_eq!(points[1].bucket, hour + Duration::hours(1));
+        assert!((points[1].value - 1.0).abs() < 1e-9);
+
+        params.metric = TimeseriesMetric::Tokens;
+        let tokens: Vec<f64> = get_timeseries(&conn, &params)
+            .unwrap()
+            .iter()
+            .map(|p| p.value)
+            .collect();
+        assert_eq!(tokens, vec![300.0, 150.0]);
+
+        // Buckets without data are zero-filled
+        params.metric = TimeseriesMetric::Errors;
+        let errors = get_timeseries(&conn, &params).unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|p| p.value == 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_project_costs_grouped_by_project() {
+        use crate::core::storage::AppStorage;
+        use crate::data::duckdb::DuckdbService;
+        use crate::data::duckdb::repositories::span::insert_batch;
+        use crate::data::types::{NormalizedSpan, ObservationType};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        let service = DuckdbService::init(&storage).await.unwrap();
+
+        let start = DateTime::parse_from_rfc3339("2025-03-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let generation =
+            |project_id: &str, span_id: &str, parent: Option<&str>, cost: f64| NormalizedSpan {
+                project_id: Some(project_id.to_string()),
+                trace_id: format!("trace-{project_id}"),
+                span_id: span_id.to_string(),
+                parent_span_id: parent.map(String::from),
+                observation_type: Some(ObservationType::Generation),
+                timestamp_start: start,
+                gen_ai_usage_input_tokens: 100,
+                gen_ai_usage_output_tokens: 50,
+                gen_ai_usage_total_tokens: 150,
+                gen_ai_cost_total: cost,
+                ..Default::default()
+            };
+        let spans = vec![
+            // Wrapper generation with a generation child: only the child counts
+            generation("p1", "a", None, 2.0),
+            generation("p1", "b", Some("a"), 1.5),
+            generation("p2", "c", None, 0.25),
+            generation("p3", "d", None, 9.0),
+        ];
+
+        let conn = service.conn();
+        insert_batch(&conn, &spans).unwrap();
+
+        let ids = vec!["p1".to_string(), "p2".to_string(), "empty".to_string()];
+        let costs = get_project_costs(
+            &conn,
+            &ids,
+            start - Duration::hours(1),
+            start + Duration::hours(1),
+        )
+        .unwrap();
+        assert_eq!(costs.len(), 2);
+        assert!((costs["p1"] - 1.5).abs() < 1e-9);
+        assert!((costs["p2"] - 0.25).abs() < 1e-9);
+
+        let stats = get_project_stats(
+            &conn,
+            &StatsParams {
+                project_id: "p1".to_string(),
+                from_timestamp: start - Duration::hours(1),
+                to_timestamp: start + Duration::hours(1),
+                timezone: None,
+            },
+        )
+        .unwrap();
+        assert!((stats.costs.total - costs["p1"]).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_tool_stats_error_rates_per_tool() {
+        use crate::core::storage::AppStorage;
+        use crate::data::duckdb::DuckdbService;
+        use crate::data::duckdb::repositories::span::insert_batch;
+        use crate::data::types::{NormalizedSpan, ObservationType};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        tokio::fs::create_dir_all(temp_dir.path().join("duckdb"))
+            .await
+            .unwrap();
+        let storage = AppStorage::init_for_test(temp_dir.path().to_path_buf());
+        let service = DuckdbService::init(&storage).await.unwrap();
+
+        let start = DateTime::parse_from_rfc3339("2025-03-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let tool_call =
+            |span_id: &str, tool: &str, failed: bool, duration_ms: i64| NormalizedSpan {
+                project_id: Some("p1".to_string()),
+                trace_id: "trace1".to_string(),
+                span_id: span_id.to_string(),
+                observation_type: Some(ObservationType::Tool),
+                gen_ai_tool_name: Some(tool.to_string()),
+                status_code: Some(if failed { "ERROR" } else { "OK" }.to_string()),
+                timestamp_start: start,
+                duration_ms,
+                ..Default::default()
+            };
+        let spans = vec![
+            tool_call("s1", "web_search", false, 100),
+            tool_call("s2", "web_search", true, 300),
+            tool_call("s3", "web_search", false, 200),
+            tool_call("s4", "web_search", true, 400),
+            tool_call("s5", "calculator", false, 10),
+        ];
+
+        let conn = service.conn();
+        insert_batch(&conn, &spans).unwrap();
+
+        let params = StatsParams {
+            project_id: "p1".to_string(),
+            from_timestamp: start - Duration::hours(1),
+            to_timestamp: start + Duration::hours(1),
+            timezone: None,
+        };
+        let stats = get_tool_stats(&conn, &params).unwrap();
+        assert_eq!(stats.len(), 2);
+
+        let search = &stats[0];
+        assert_eq!(search.tool_name, "web_search");
+        assert_eq!(search.call_count, 4);
+        assert_eq!(search.error_count, 2);
+        assert_eq!(search.error_rate, 50.0);
+        assert_eq!(search.avg_duration_ms, Some(250.0));
+
+        let calculator = &stats[1];
+        assert_eq!(calculator.tool_name, "calculator");
+        assert_eq!(calculator.call_count, 1);
+        assert_eq!(calculator.error_count, 0);
+        assert_eq!(calculator.error_rate, 0.0);
+    }
+
     #[test]
     fn test_parse_timezone_valid() {
         // Standard IANA timezones
@@ -18,7 +18,8 @@ use crate::data::traits::{AnalyticsRepository, FilterOptionRow};
 use crate::data::types::{
     EventRow, FeedMessagesParams, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams,
     ListTracesParams, MessageQueryParams, MessageQueryResult, NormalizedMetric, NormalizedSpan,
-    ProjectStatsResult, SessionRow, SpanCounts, SpanRow, StatsParams, TraceRow,
+    ProjectStatsResult, SessionRow, SpanCounts, SpanRow, StatsParams, TimeseriesParams,
+    TimeseriesPoint, ToolStats, TraceDeleteFilter, TraceModelUsageRow, TraceRow,
 };
 
 use super::DuckdbService;
@@ -120,6 +121,23 @@ impl AnalyticsRepository for Arc<DuckdbService> {
             .collect())
     }
 
+    async fn get_trace_model_usage(
+        &self,
+        project_id: &str,
+        trace_ids: &[String],
+    ) -> Result<Vec<TraceModelUsageRow>, DataError> {
+        let db = Arc::clone(self);
+        let pid = project_id.to_string();
+        let tids = trace_ids.to_vec();
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            query::get_trace_model_usage(&conn, &pid, &tids)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
     async fn delete_traces(
         &self,
         project_id: &str,
@@ -137,6 +155,26 @@ impl AnalyticsRepository for Arc<DuckdbService> {
         .map_err(Into::into)
     }
 
+    async fn get_trace_ids_matching(
+        &self,
+        project_id: &str,
+        filter: &TraceDeleteFilter,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>, DataError> {
+        let db = Arc::clone(self);
+        let pid = project_id.to_string();
+        let filter = filter.clone();
+        let after = after.map(str::to_string);
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            query::get_trace_ids_matching(&conn, &pid, &filter, after.as_deref(), limit)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
     // ==================== Span Operations ====================
 
     async fn list_spans(&self, params: &ListSpansParams) -> Result<(Vec<SpanRow>, u64), DataError> {
@@ -168,6 +206,23 @@ impl AnalyticsRepository for Arc<DuckdbService> {
         .map_err(Into::into)
     }
 
+    async fn get_parent_links_for_trace(
+        &self,
+        project_id: &str,
+        trace_id: &str,
+    ) -> Result<HashMap<String, String>, DataError> {
+        let db = Arc::clone(self);
+        let pid = project_id.to_string();
+        let tid = trace_id.to_string();
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            query::get_parent_links_for_trace(&conn, &pid, &tid)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
     async fn get_span(
         &self,
         project_id: &str,
@@ -495,6 +550,50 @@ impl AnalyticsRepository for Arc<DuckdbService> {
         .map_err(Into::into)
     }
 
+    async fn get_timeseries(
+        &self,
+        params: &TimeseriesParams,
+    ) -> Result<Vec<TimeseriesPoint>, DataError> {
+        let db = Arc::clone(self);
+        let params = params.clone();
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            stats::get_timeseries(&conn, &params)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
+    async fn get_tool_stats(&self, params: &StatsParams) -> Result<Vec<ToolStats>, DataError> {
+        let db = Arc::clone(self);
+        let params = params.clone();
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            stats::get_tool_stats(&conn, &params)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
+    async fn get_project_costs(
+        &self,
+        project_ids: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>, DataError> {
+        let db = Arc::clone(self);
+        let ids = project_ids.to_vec();
+        DuckdbService::run_query(move || {
+            let conn = db.conn();
+            stats::get_project_costs(&conn, &ids, from, to)
+        })
+        .await
+        .map_err(DataError::from)?
+        .map_err(Into::into)
+    }
+
     // ==================== Ingestion Operations ====================
 
     async fn insert_spans(&self, spans: Vec<NormalizedSpan>) -> Result<(), DataError> {
@@ -28,6 +28,14 @@ pub fn run_retention(
 ) -> Result<RetentionResult, DuckdbError> {
     let mut result = RetentionResult::default();
 
+    // TTL sweep: spans stamped with expires_at at ingestion (index range scan)
+    let (deleted, trace_ids) = cleanup_expired(conn)?;
+    if deleted > 0 {
+        tracing::debug!(deleted, "Expiry-based span retention cleanup");
+        result.deleted_count += deleted;
+        merge_trace_ids(&mut result.trace_ids_by_project, trace_ids);
+    }
+
     if let Some(max_age_minutes) = config.max_age_minutes {
         // Span cleanup
         let (deleted, trace_ids) = cleanup_by_time(conn, max_age_minutes)?;
@@ -124,6 +132,29 @@ pub fn cleanup_by_time(
     Ok((total_deleted, all_trace_ids))
 }
 
+/// Execute TTL retention (delete spans whose `expires_at` has passed)
+/// Uses the `expires_at` index instead of scanning `timestamp_start`.
+/// Returns (spans_deleted, trace_ids_by_project) for file cleanup
+pub fn cleanup_expired(
+    conn: &Connection,
+) -> Result<(u64, HashMap<String, Vec<String>>), DuckdbError> {
+    let now_str = Utc::now().format("%Y-%m-%d %H:%M:%S%.6f").to_string();
+
+    let mut total_deleted = 0u64;
+    let mut all_trace_ids: HashMap<String, Vec<String>> = HashMap::new();
+
+    for _ in 0..MAX_TIME_CLEANUP_BATCHES {
+        let (deleted, trace_ids) = delete_spans_expired(conn, &now_str, RETENTION_BATCH_SIZE)?;
+        if deleted == 0 {
+            break;
+        }
+        tracing::debug!(deleted, "Deleted batch of spans past expires_at");
+        total_deleted += deleted;
+        merge_trace_ids(&mut all_trace_ids, trace_ids);
+    }
+    Ok((total_deleted, all_trace_ids))
+}
+
 /// Max batches per count-based cleanup cycle (prevents unbounded blocking)
 const MAX_COUNT_CLEANUP_BATCHES: usize = 10;
 
@@ -179,7 +210,25 @@ pub fn cleanup_by_count(
     Ok((total_deleted, all_trace_ids))
 }
 
+/// Delete spans whose expiry is before `now` (for TTL retention)
+/// Returns (deleted_count, trace_ids_by_project)
+fn delete_spans_expired(
+    conn: &Connection,
+    now: &str,
+    limit: i64,
+) -> Result<(u64, HashMap<String, Vec<String>>), DuckdbError> {
+    delete_spans_with_query(
+        conn,
+        "INSERT INTO _retention_batch
+         SELECT trace_id, span_id FROM otel_spans
+         WHERE expires_at < ?1
+         LIMIT ?2",
+        &[&now as &dyn duckdb::ToSql, &limit],
+    )
+}
+
 /// Delete spans before cutoff timestamp (for time-based retention)
+/// Only spans without a TTL: stamped spans keep the expiry they were ingested with.
 /// Returns (deleted_count, trace_ids_by_project)
 fn delete_spans_before(
     conn: &Connection,
@@ -190,7 +239,7 @@ fn delete_spans_before(
         conn,
         "INSERT INTO _retention_batch
          SELECT trace_id, span_id FROM otel_spans
-         WHERE timestamp_start < ?1
+         WHERE timestamp_start < ?1 AND expires_at IS NULL
          ORDER BY timestamp_start ASC
          LIMIT ?2",
         &[&cutoff as &dyn duckdb::ToSql, &limit],
@@ -380,6 +429,64 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    fn insert_expiring_span(conn: &Connection, trace_id: &str, expires_at: &str) {
+        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.6f").to_string();
+        conn.execute(
+            "INSERT INTO otel_spans (trace_id, span_id, span_name, timestamp_start, project_id, expires_at)
+             VALUES (?1, 'span', 'test', ?2, 'default', ?3)",
+            [trace_id, now.as_str(), expires_at],
+        )
+        .expect("Failed to insert test span");
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_expired_sweeps_only_past_expiry() {
+        let (_temp_dir, analytics) = create_test_service().await;
+        let conn = analytics.conn();
+
+        let future = (Utc::now() + TimeDelta::days(1))
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string();
+        insert_expiring_span(&conn, "expired", "2020-01-01 00:00:00");
+        insert_expiring_span(&conn, "live", &future);
+        // Old span without a TTL is left to time-based retention
+        insert_test_span(&conn, "legacy", "span", "2020-01-01 00:00:00");
+
+        let (deleted, trace_ids) = cleanup_expired(&conn).expect("Should cleanup");
+        assert_eq!(deleted, 1);
+        assert_eq!(trace_ids["default"], vec!["expired".to_string()]);
+
+        let mut remaining: Vec<String> = conn
+            .prepare("SELECT trace_id FROM otel_spans")
+            .expect("Should prepare")
+            .query_map([], |row| row.get(0))
+            .expect("Should query")
+            .collect::<Result<_, _>>()
+            .expect("Should collect");
+        remaining.sort();
+        assert_eq!(remaining, vec!["legacy", "live"]);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_by_time_keeps_spans_with_future_expiry() {
+        let (_temp_dir, analytics) = create_test_service().await;
+        let conn = analytics.conn();
+
+        // Stamped under a longer retention than the current one
+        let future = (Utc::now() + TimeDelta::days(1))
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string();
+        conn.execute(
+            "INSERT INTO otel_spans (trace_id, span_id, span_name, timestamp_start, project_id, expires_at)
+             VALUES ('stamped', 'span', 'test', '2020-01-01 00:00:00', 'default', ?1)",
+            [future.as_str()],
+        )
+        .expect("Failed to insert test span");
+
+        let (deleted, _trace_ids) = cleanup_by_time(&conn, 1).expect("Should cleanup");
+        assert_eq!(deleted, 0);
+    }
+
     #[tokio::test]
     async fn test_cleanup_by_count_empty_table() {
         let (_temp_dir, analytics) = create_test_service().await;
@@ -6,7 +6,7 @@
 //! use an inline DEDUP_SPANS subquery.
 
 /// Current schema version
-pub const SCHEMA_VERSION: i32 = 1;
+pub const SCHEMA_VERSION: i32 = 6;
 
 /// Complete schema SQL
 pub const SCHEMA: &str = r#"
@@ -175,6 +175,40 @@ CREATE TABLE IF NOT EXISTS otel_spans (
     -- Stored as JSON for direct querying; includes attributes and resource.attributes
     -- ═══════════════════════════════════════════════════════════════════
     raw_span                    JSON,
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- RETENTION
+    -- Stamped at ingestion (ingest time + otel.retention.max_age_minutes).
+    -- Retention changes only affect spans ingested afterwards.
+    -- ═══════════════════════════════════════════════════════════════════
+    expires_at                  TIMESTAMP,  -- NULL = no TTL (swept by timestamp_start)
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- DATA QUALITY
+    -- Issues found at extraction, surfaced as feed warnings
+    -- ═══════════════════════════════════════════════════════════════════
+    ingest_warnings             JSON NOT NULL DEFAULT '[]',
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- GEN AI: BUILT-IN TOOLS (billed per call, on top of tokens)
+    -- ═══════════════════════════════════════════════════════════════════
+    gen_ai_usage_web_search_calls   BIGINT NOT NULL DEFAULT 0,
+    gen_ai_cost_tool            DECIMAL(18,6) NOT NULL DEFAULT 0,
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- GEN AI: REQUEST CONTROLS & PROVENANCE
+    -- ═══════════════════════════════════════════════════════════════════
+    gen_ai_reasoning_effort     VARCHAR,    -- low, medium, high
+    gen_ai_verbosity            VARCHAR,    -- low, medium, high
+    gen_ai_raw_model            VARCHAR,    -- Model as reported, before aliasing
+    gen_ai_cost_source          VARCHAR,    -- computed, reported
+    gen_ai_usage_merged_from    VARCHAR,    -- Child span whose usage was merged here
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- GEN AI: EMBEDDING REQUEST PARAMETERS
+    -- ═══════════════════════════════════════════════════════════════════
+    gen_ai_embedding_dimensions BIGINT,     -- Requested output dimensions
+    gen_ai_encoding_format      VARCHAR,    -- float, base64
 );
 
 -- Indexes for spans (minimal - DuckDB columnar scans are efficient for low-cardinality filters)
@@ -184,6 +218,7 @@ CREATE INDEX IF NOT EXISTS idx_spans_project_ingest ON otel_spans(project_id, in
 CREATE INDEX IF NOT EXISTS idx_spans_detail ON otel_spans(project_id, trace_id, span_id);
 CREATE INDEX IF NOT EXISTS idx_spans_project_session ON otel_spans(project_id, session_id);
 CREATE INDEX IF NOT EXISTS idx_spans_project_span ON otel_spans(project_id, span_id);
+CREATE INDEX IF NOT EXISTS idx_spans_expires ON otel_spans(expires_at);
 
 -- ═══════════════════════════════════════════════════════════════════════════════
 -- OTEL metrics table: Main table for all OpenTelemetry metric data points
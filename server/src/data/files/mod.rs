@@ -438,6 +438,7 @@ mod tests {
             max_entries: 1000,
             eviction_policy: crate::core::config::EvictionPolicy::TinyLfu,
             redis_url: None,
+            redis_pool_size: 32,
         };
         let cache = Arc::new(CacheService::new(&cache_config).await.unwrap());
 
@@ -133,6 +133,22 @@ impl TransactionalService {
         }
     }
 
+    /// Verify the database is reachable with a trivial query
+    pub async fn ping(&self) -> Result<(), DataError> {
+        match self {
+            Self::Sqlite(s) => sqlx::query("SELECT 1")
+                .execute(s.pool())
+                .await
+                .map(|_| ())
+                .map_err(DataError::Sqlite),
+            Self::Postgres(p) => sqlx::query("SELECT 1")
+                .execute(p.pool())
+                .await
+                .map(|_| ())
+                .map_err(DataError::Postgres),
+        }
+    }
+
     /// Close the database connection gracefully
     pub async fn close(&self) {
         match self {
@@ -207,6 +223,11 @@ impl AnalyticsService {
         }
     }
 
+    /// Whether spans carry an `expires_at` swept by the retention task (DuckDB only)
+    pub fn supports_span_expiry(&self) -> bool {
+        matches!(self, Self::Duckdb(_))
+    }
+
     /// Get exclusive access to the DuckDB connection
     ///
     /// # Panics
@@ -229,6 +250,14 @@ impl AnalyticsService {
         }
     }
 
+    /// Verify the analytics database is reachable with a trivial query
+    pub async fn ping(&self) -> Result<(), DataError> {
+        match self {
+            Self::Duckdb(d) => d.ping().await.map_err(Into::into),
+            Self::Clickhouse(c) => c.health_check().await.map_err(Into::into),
+        }
+    }
+
     /// Close the database connection gracefully
     pub async fn close(&self) -> Result<(), DataError> {
         match self {
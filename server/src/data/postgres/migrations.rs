@@ -180,6 +180,36 @@ CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_project
 CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_org_default
     ON credential_project_permissions(credential_id)
     WHERE project_id IS NULL;
+"#,
+        ),
+        4 => (
+            "add_org_feature_flags",
+            r#"CREATE TABLE IF NOT EXISTS org_feature_flags (
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    flag TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (organization_id, flag)
+);
+"#,
+        ),
+        5 => (
+            "add_org_monthly_limit",
+            r#"ALTER TABLE organizations ADD COLUMN IF NOT EXISTS monthly_limit_usd DOUBLE PRECISION
+    CHECK(monthly_limit_usd IS NULL OR monthly_limit_usd >= 0);
+"#,
+        ),
+        6 => (
+            "add_org_default_project",
+            r#"ALTER TABLE organizations ADD COLUMN IF NOT EXISTS default_project_id TEXT
+    REFERENCES projects(id) ON DELETE SET NULL;
+"#,
+        ),
+        7 => (
+            "add_org_retention_days",
+            r#"ALTER TABLE organizations ADD COLUMN IF NOT EXISTS retention_days INTEGER
+    CHECK(retention_days IS NULL OR retention_days > 0);
 "#,
         ),
         _ => {
@@ -0,0 +1,95 @@
+//! Organization feature flags repository for PostgreSQL operations
+//!
+//! Reads support optional caching. Pass `Some(cache)` to enable caching,
+//! or `None` to bypass cache. Toggling a flag invalidates the cached set.
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::core::constants::CACHE_TTL_ORG_FEATURE_FLAGS;
+use crate::data::cache::{CacheKey, CacheService};
+use crate::data::postgres::PostgresError;
+use crate::data::types::{FeatureFlag, FeatureFlags};
+
+/// Get resolved feature flags for an organization (unset flags are off)
+pub async fn get_org_feature_flags(
+    pool: &PgPool,
+    cache: Option<&CacheService>,
+    org_id: &str,
+) -> Result<FeatureFlags, PostgresError> {
+    let Some(cache) = cache else {
+        return get_org_feature_flags_from_db(pool, org_id).await;
+    };
+    let key = CacheKey::org_feature_flags(org_id);
+
+    match cache.get::<FeatureFlags>(&key).await {
+        Ok(Some(flags)) => return Ok(flags),
+        Err(e) => tracing::warn!(%org_id, error = %e, "Cache get error"),
+        Ok(None) => {}
+    }
+
+    let flags = get_org_feature_flags_from_db(pool, org_id).await?;
+    if let Err(e) = cache
+        .set(
+            &key,
+            &flags,
+            Some(Duration::from_secs(CACHE_TTL_ORG_FEATURE_FLAGS)),
+        )
+        .await
+    {
+        tracing::warn!(%org_id, error = %e, "Cache set error");
+    }
+
+    Ok(flags)
+}
+
+/// Get feature flags directly from database (no caching)
+async fn get_org_feature_flags_from_db(
+    pool: &PgPool,
+    org_id: &str,
+) -> Result<FeatureFlags, PostgresError> {
+    let rows: Vec<(String, bool)> =
+        sqlx::query_as("SELECT flag, enabled FROM org_feature_flags WHERE organization_id = $1")
+            .bind(org_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(FeatureFlags::from_rows(rows))
+}
+
+/// Enable or disable a feature flag for an organization
+pub async fn set_org_feature_flag(
+    pool: &PgPool,
+    cache: Option<&CacheService>,
+    org_id: &str,
+    flag: FeatureFlag,
+    enabled: bool,
+    updated_by: Option<&str>,
+) -> Result<(), PostgresError> {
+    let now = chrono::Utc::now().timestamp();
+
+    sqlx::query(
+        r#"INSERT INTO org_feature_flags (organization_id, flag, enabled, updated_by, updated_at)
+           VALUES ($1, $2, $3, $4, $5)
+           ON CONFLICT (organization_id, flag) DO UPDATE SET
+               enabled = EXCLUDED.enabled,
+               updated_by = EXCLUDED.updated_by,
+               updated_at = EXCLUDED.updated_at"#,
+    )
+    .bind(org_id)
+    .bind(flag.as_str())
+    .bind(enabled)
+    .bind(updated_by)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    if let Some(cache) = cache {
+        cache
+            .invalidate_key(&CacheKey::org_feature_flags(org_id))
+            .await;
+    }
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ pub mod auth_method;
 pub mod credential_permissions;
 pub mod credentials;
 pub mod favorite;
+pub mod feature_flags;
 pub mod file;
 pub mod membership;
 pub mod organization;
@@ -33,6 +34,7 @@ pub use favorite::{
     add_favorite, check_favorites, check_span_favorites, count_favorites,
     delete_favorites_by_entity, list_all_favorite_ids, remove_favorite,
 };
+pub use feature_flags::{get_org_feature_flags, set_org_feature_flag};
 pub use file::{
     decrement_ref_count, delete_file, delete_project_files, delete_trace_files, file_exists,
     get_file, get_file_hashes_for_traces, get_orphan_files, get_project_storage_bytes,
@@ -44,7 +46,8 @@ pub use membership::{
 };
 pub use organization::{
     create_organization, create_organization_with_owner, delete_organization, get_organization,
-    list_for_user as list_orgs_for_user, list_project_ids, update_organization,
+    list_for_user as list_orgs_for_user, list_project_ids, set_default_project, set_monthly_limit,
+    set_retention_days, update_organization,
 };
 pub use project::{
     create_project, delete_project, get_project, list_for_org, list_for_user, list_projects,
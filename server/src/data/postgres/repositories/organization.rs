@@ -46,6 +46,9 @@ pub async fn create_organization(
         slug: slug.to_string(),
         created_at: now,
         updated_at: now,
+        monthly_limit_usd: None,
+        default_project_id: None,
+        retention_days: None,
     })
 }
 
@@ -102,6 +105,9 @@ pub async fn create_organization_with_owner(
         slug: slug.to_string(),
         created_at: now,
         updated_at: now,
+        monthly_limit_usd: None,
+        default_project_id: None,
+        retention_days: None,
     })
 }
 
@@ -147,22 +153,34 @@ async fn get_organization_from_db(
     pool: &PgPool,
     id: &str,
 ) -> Result<Option<OrganizationRow>, PostgresError> {
-    let row = sqlx::query_as::<_, (String, String, String, i64, i64)>(
-        "SELECT id, name, slug, created_at, updated_at FROM organizations WHERE id = $1",
+    let row = sqlx::query_as::<_, (String, String, String, i64, i64, Option<f64>, Option<String>, Option<i64>)>(
+        "SELECT id, name, slug, created_at, updated_at, monthly_limit_usd, default_project_id, retention_days FROM organizations WHERE id = $1",
     )
     .bind(id)
     .fetch_optional(pool)
     .await?;
 
-    Ok(
-        row.map(|(id, name, slug, created_at, updated_at)| OrganizationRow {
+    Ok(row.map(
+        |(
             id,
             name,
             slug,
             created_at,
             updated_at,
-        }),
-    )
+            monthly_limit_usd,
+            default_project_id,
+            retention_days,
+        )| OrganizationRow {
+            id,
+            name,
+            slug,
+            created_at,
+            updated_at,
+            monthly_limit_usd,
+            default_project_id,
+            retention_days,
+        },
+    ))
 }
 
 /// List organizations for a user with their role (with optional caching)
@@ -300,6 +318,92 @@ pub async fn update_organization(
     get_organization_from_db(pool, id).await
 }
 
+/// Set or clear an organization's monthly spend cap
+pub async fn set_monthly_limit(
+    pool: &PgPool,
+    cache: Option<&CacheService>,
+    id: &str,
+    monthly_limit_usd: Option<f64>,
+) -> Result<Option<OrganizationRow>, PostgresError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result = sqlx::query(
+        "UPDATE organizations SET monthly_limit_usd = $1, updated_at = $2 WHERE id = $3",
+    )
+    .bind(monthly_limit_usd)
+    .bind(now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
+/// Set or clear the project that receives an organization's unscoped traces
+pub async fn set_default_project(
+    pool: &PgPool,
+    cache: Option<&CacheService>,
+    id: &str,
+    project_id: Option<&str>,
+) -> Result<Option<OrganizationRow>, PostgresError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result = sqlx::query(
+        "UPDATE organizations SET default_project_id = $1, updated_at = $2 WHERE id = $3",
+    )
+    .bind(project_id)
+    .bind(now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
+/// Set or clear the number of days an organization's new spans are kept
+pub async fn set_retention_days(
+    pool: &PgPool,
+    cache: Option<&CacheService>,
+    id: &str,
+    retention_days: Option<i64>,
+) -> Result<Option<OrganizationRow>, PostgresError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result =
+        sqlx::query("UPDATE organizations SET retention_days = $1, updated_at = $2 WHERE id = $3")
+            .bind(retention_days)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
 /// Delete an organization by ID (transactional cascade only - caller must handle analytics/files)
 pub async fn delete_organization(
     pool: &PgPool,
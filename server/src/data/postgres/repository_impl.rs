@@ -12,14 +12,14 @@ use crate::data::error::DataError;
 use crate::data::traits::TransactionalRepository;
 use crate::data::types::{
     ApiKeyRow, ApiKeyScope, ApiKeyValidation, AuthMethodRow, CredentialPermissionRow,
-    CredentialRow, FileRow, LastOwnerResult, MemberWithUser, MembershipRow, OrgWithRole,
-    OrganizationRow, ProjectRow, UserRow,
+    CredentialRow, FeatureFlag, FeatureFlags, FileRow, LastOwnerResult, MemberWithUser,
+    MembershipRow, OrgWithRole, OrganizationRow, ProjectRow, UserRow,
 };
 
 use super::PostgresService;
 use super::repositories::{
-    api_key, auth_method, credential_permissions, credentials, favorite, file, membership,
-    organization, project, user,
+    api_key, auth_method, credential_permissions, credentials, favorite, feature_flags, file,
+    membership, organization, project, user,
 };
 
 #[async_trait]
@@ -103,6 +103,39 @@ impl TransactionalRepository for Arc<PostgresService> {
             .map_err(Into::into)
     }
 
+    async fn set_org_monthly_limit(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        monthly_limit_usd: Option<f64>,
+    ) -> Result<Option<OrganizationRow>, DataError> {
+        organization::set_monthly_limit(self.pool(), cache, id, monthly_limit_usd)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn set_org_default_project(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        project_id: Option<&str>,
+    ) -> Result<Option<OrganizationRow>, DataError> {
+        organization::set_default_project(self.pool(), cache, id, project_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn set_org_retention_days(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        retention_days: Option<i64>,
+    ) -> Result<Option<OrganizationRow>, DataError> {
+        organization::set_retention_days(self.pool(), cache, id, retention_days)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn list_orgs_for_user(
         &self,
         cache: Option<&CacheService>,
@@ -766,4 +799,29 @@ impl TransactionalRepository for Arc<PostgresService> {
         .await
         .map_err(Into::into)
     }
+
+    // ==================== Feature Flag Operations ====================
+
+    async fn get_org_feature_flags(
+        &self,
+        cache: Option<&CacheService>,
+        org_id: &str,
+    ) -> Result<FeatureFlags, DataError> {
+        feature_flags::get_org_feature_flags(self.pool(), cache, org_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn set_org_feature_flag(
+        &self,
+        cache: Option<&CacheService>,
+        org_id: &str,
+        flag: FeatureFlag,
+        enabled: bool,
+        updated_by: Option<&str>,
+    ) -> Result<(), DataError> {
+        feature_flags::set_org_feature_flag(self.pool(), cache, org_id, flag, enabled, updated_by)
+            .await
+            .map_err(Into::into)
+    }
 }
@@ -3,7 +3,7 @@
 //! Initial schema with all tables. Compatible with SQLite schema structure.
 
 /// Current schema version
-pub const SCHEMA_VERSION: i32 = 3;
+pub const SCHEMA_VERSION: i32 = 7;
 
 /// Complete schema SQL for PostgreSQL
 pub const SCHEMA: &str = r#"
@@ -37,7 +37,9 @@ CREATE TABLE IF NOT EXISTS organizations (
         OR (length(slug) = 1 AND slug ~ '^[a-z0-9]$')
     ),
     created_at BIGINT NOT NULL,
-    updated_at BIGINT NOT NULL
+    updated_at BIGINT NOT NULL,
+    monthly_limit_usd DOUBLE PRECISION CHECK(monthly_limit_usd IS NULL OR monthly_limit_usd >= 0),
+    retention_days INTEGER CHECK(retention_days IS NULL OR retention_days > 0)
 );
 
 CREATE INDEX IF NOT EXISTS idx_organizations_slug ON organizations(slug);
@@ -108,6 +110,10 @@ CREATE TABLE IF NOT EXISTS projects (
 
 CREATE INDEX IF NOT EXISTS idx_projects_org ON projects(organization_id);
 
+-- Default project for unscoped traces (added here: projects must exist for the FK)
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS default_project_id TEXT
+    REFERENCES projects(id) ON DELETE SET NULL;
+
 -- =============================================================================
 -- 6. Files metadata (references projects)
 -- =============================================================================
@@ -235,6 +241,18 @@ CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_project
 CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_org_default
     ON credential_project_permissions(credential_id)
     WHERE project_id IS NULL;
+
+-- =============================================================================
+-- 12. Organization Feature Flags
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS org_feature_flags (
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    flag TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (organization_id, flag)
+);
 "#;
 
 /// Default data SQL for PostgreSQL (inserted separately after schema)
@@ -297,6 +315,7 @@ mod tests {
             "api_keys",
             "credentials",
             "credential_project_permissions",
+            "org_feature_flags",
         ];
 
         for table in required_tables {
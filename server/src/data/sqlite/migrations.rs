@@ -127,6 +127,29 @@ CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_org_default
     WHERE project_id IS NULL;
 "#;
 
+const MIGRATION_V4: &str = r#"
+CREATE TABLE IF NOT EXISTS org_feature_flags (
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    flag TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (organization_id, flag)
+);
+"#;
+
+const MIGRATION_V5: &str = r#"
+ALTER TABLE organizations ADD COLUMN monthly_limit_usd REAL CHECK(monthly_limit_usd IS NULL OR monthly_limit_usd >= 0);
+"#;
+
+const MIGRATION_V6: &str = r#"
+ALTER TABLE organizations ADD COLUMN default_project_id TEXT REFERENCES projects(id) ON DELETE SET NULL;
+"#;
+
+const MIGRATION_V7: &str = r#"
+ALTER TABLE organizations ADD COLUMN retention_days INTEGER CHECK(retention_days IS NULL OR retention_days > 0);
+"#;
+
 async fn apply_migration(pool: &SqlitePool, version: i32) -> Result<(), SqliteError> {
     match version {
         1 => {
@@ -135,6 +158,10 @@ async fn apply_migration(pool: &SqlitePool, version: i32) -> Result<(), SqliteEr
         }
         2 => apply_versioned_migration(pool, 2, "add_hash_algo_to_files", MIGRATION_V2).await,
         3 => apply_versioned_migration(pool, 3, "add_credentials_tables", MIGRATION_V3).await,
+        4 => apply_versioned_migration(pool, 4, "add_org_feature_flags", MIGRATION_V4).await,
+        5 => apply_versioned_migration(pool, 5, "add_org_monthly_limit", MIGRATION_V5).await,
+        6 => apply_versioned_migration(pool, 6, "add_org_default_project", MIGRATION_V6).await,
+        7 => apply_versioned_migration(pool, 7, "add_org_retention_days", MIGRATION_V7).await,
         _ => Err(SqliteError::MigrationFailed {
             version,
             name: "unknown".to_string(),
@@ -0,0 +1,156 @@
+//! Organization feature flags repository for SQLite operations
+//!
+//! Reads support optional caching. Pass `Some(cache)` to enable caching,
+//! or `None` to bypass cache. Toggling a flag invalidates the cached set.
+
+use std::time::Duration;
+
+use sqlx::SqlitePool;
+
+use crate::core::constants::CACHE_TTL_ORG_FEATURE_FLAGS;
+use crate::data::cache::{CacheKey, CacheService};
+use crate::data::sqlite::SqliteError;
+use crate::data::types::{FeatureFlag, FeatureFlags};
+
+/// Get resolved feature flags for an organization (unset flags are off)
+pub async fn get_org_feature_flags(
+    pool: &SqlitePool,
+    cache: Option<&CacheService>,
+    org_id: &str,
+) -> Result<FeatureFlags, SqliteError> {
+    let Some(cache) = cache else {
+        return get_org_feature_flags_from_db(pool, org_id).await;
+    };
+    let key = CacheKey::org_feature_flags(org_id);
+
+    match cache.get::<FeatureFlags>(&key).await {
+        Ok(Some(flags)) => return Ok(flags),
+        Err(e) => tracing::warn!(%org_id, error = %e, "Cache get error"),
+        Ok(None) => {}
+    }
+
+    let flags = get_org_feature_flags_from_db(pool, org_id).await?;
+    if let Err(e) = cache
+        .set(
+            &key,
+            &flags,
+            Some(Duration::from_secs(CACHE_TTL_ORG_FEATURE_FLAGS)),
+        )
+        .await
+    {
+        tracing::warn!(%org_id, error = %e, "Cache set error");
+    }
+
+    Ok(flags)
+}
+
+/// Get feature flags directly from database (no caching)
+async fn get_org_feature_flags_from_db(
+    pool: &SqlitePool,
+    org_id: &str,
+) -> Result<FeatureFlags, SqliteError> {
+    let rows: Vec<(String, bool)> =
+        sqlx::query_as("SELECT flag, enabled FROM org_feature_flags WHERE organization_id = ?")
+            .bind(org_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(FeatureFlags::from_rows(rows))
+}
+
+/// Enable or disable a feature flag for an organization
+pub async fn set_org_feature_flag(
+    pool: &SqlitePool,
+    cache: Option<&CacheService>,
+    org_id: &str,
+    flag: FeatureFlag,
+    enabled: bool,
+    updated_by: Option<&str>,
+) -> Result<(), SqliteError> {
+    let now = chrono::Utc::now().timestamp();
+
+    sqlx::query(
+        r#"INSERT INTO org_feature_flags (organization_id, flag, enabled, updated_by, updated_at)
+           VALUES (?, ?, ?, ?, ?)
+           ON CONFLICT(organization_id, flag) DO UPDATE SET
+               enabled = excluded.enabled,
+               updated_by = excluded.updated_by,
+               updated_at = excluded.updated_at"#,
+    )
+    .bind(org_id)
+    .bind(flag.as_str())
+    .bind(enabled)
+    .bind(updated_by)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    if let Some(cache) = cache {
+        cache
+            .invalidate_key(&CacheKey::org_feature_flags(org_id))
+            .await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::sqlite::repositories::organization::create_organization;
+
+    async fn setup_test_pool() -> SqlitePool {
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        sqlx::query(crate::data::sqlite::schema::SCHEMA)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_flags_default_off() {
+        let pool = setup_test_pool().await;
+        let flags = get_org_feature_flags(&pool, None, "default").await.unwrap();
+        assert_eq!(flags, FeatureFlags::default());
+        assert!(!flags.anonymize_content);
+    }
+
+    #[tokio::test]
+    async fn test_flag_toggle_is_scoped_to_org() {
+        let pool = setup_test_pool().await;
+        let other = create_organization(&pool, None, "Other", "other")
+            .await
+            .unwrap();
+
+        set_org_feature_flag(
+            &pool,
+            None,
+            "default",
+            FeatureFlag::AnonymizeContent,
+            true,
+            Some("local"),
+        )
+        .await
+        .unwrap();
+
+        let default_flags = get_org_feature_flags(&pool, None, "default").await.unwrap();
+        let other_flags = get_org_feature_flags(&pool, None, &other.id).await.unwrap();
+        assert!(default_flags.anonymize_content);
+        assert!(!other_flags.anonymize_content);
+
+        // Toggling back off overwrites the stored value
+        set_org_feature_flag(
+            &pool,
+            None,
+            "default",
+            FeatureFlag::AnonymizeContent,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+        let default_flags = get_org_feature_flags(&pool, None, "default").await.unwrap();
+        assert!(!default_flags.anonymize_content);
+    }
+}
@@ -46,6 +46,9 @@ pub async fn create_organization(
         slug: slug.to_string(),
         created_at: now,
         updated_at: now,
+        monthly_limit_usd: None,
+        default_project_id: None,
+        retention_days: None,
     })
 }
 
@@ -102,6 +105,9 @@ pub async fn create_organization_with_owner(
         slug: slug.to_string(),
         created_at: now,
         updated_at: now,
+        monthly_limit_usd: None,
+        default_project_id: None,
+        retention_days: None,
     })
 }
 
@@ -147,22 +153,34 @@ async fn get_organization_from_db(
     pool: &SqlitePool,
     id: &str,
 ) -> Result<Option<OrganizationRow>, SqliteError> {
-    let row = sqlx::query_as::<_, (String, String, String, i64, i64)>(
-        "SELECT id, name, slug, created_at, updated_at FROM organizations WHERE id = ?",
+    let row = sqlx::query_as::<_, (String, String, String, i64, i64, Option<f64>, Option<String>, Option<i64>)>(
+        "SELECT id, name, slug, created_at, updated_at, monthly_limit_usd, default_project_id, retention_days FROM organizations WHERE id = ?",
     )
     .bind(id)
     .fetch_optional(pool)
     .await?;
 
-    Ok(
-        row.map(|(id, name, slug, created_at, updated_at)| OrganizationRow {
+    Ok(row.map(
+        |(
             id,
             name,
             slug,
             created_at,
             updated_at,
-        }),
-    )
+            monthly_limit_usd,
+            default_project_id,
+            retention_days,
+        )| OrganizationRow {
+            id,
+            name,
+            slug,
+            created_at,
+            updated_at,
+            monthly_limit_usd,
+            default_project_id,
+            retention_days,
+        },
+    ))
 }
 
 /// List organizations for a user with their role (with optional caching)
@@ -300,6 +318,90 @@ pub async fn update_organization(
     get_organization_from_db(pool, id).await
 }
 
+/// Set or clear an organization's monthly spend cap
+pub async fn set_monthly_limit(
+    pool: &SqlitePool,
+    cache: Option<&CacheService>,
+    id: &str,
+    monthly_limit_usd: Option<f64>,
+) -> Result<Option<OrganizationRow>, SqliteError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result =
+        sqlx::query("UPDATE organizations SET monthly_limit_usd = ?, updated_at = ? WHERE id = ?")
+            .bind(monthly_limit_usd)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
+/// Set or clear the project that receives an organization's unscoped traces
+pub async fn set_default_project(
+    pool: &SqlitePool,
+    cache: Option<&CacheService>,
+    id: &str,
+    project_id: Option<&str>,
+) -> Result<Option<OrganizationRow>, SqliteError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result =
+        sqlx::query("UPDATE organizations SET default_project_id = ?, updated_at = ? WHERE id = ?")
+            .bind(project_id)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
+/// Set or clear the number of days an organization's new spans are kept
+pub async fn set_retention_days(
+    pool: &SqlitePool,
+    cache: Option<&CacheService>,
+    id: &str,
+    retention_days: Option<i64>,
+) -> Result<Option<OrganizationRow>, SqliteError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let result =
+        sqlx::query("UPDATE organizations SET retention_days = ?, updated_at = ? WHERE id = ?")
+            .bind(retention_days)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    if let Some(cache) = cache {
+        cache.invalidate_key(&CacheKey::organization(id)).await;
+    }
+
+    get_organization_from_db(pool, id).await
+}
+
 /// Delete an organization by ID (transactional cascade only - caller must handle analytics/files)
 pub async fn delete_organization(
     pool: &SqlitePool,
@@ -431,6 +533,75 @@ mod tests {
         assert_eq!(updated.slug, "test-org"); // slug unchanged
     }
 
+    #[tokio::test]
+    async fn test_set_monthly_limit() {
+        let pool = setup_test_pool().await;
+        let org = create_organization(&pool, None, "Test Org", "test-org")
+            .await
+            .unwrap();
+        assert_eq!(org.monthly_limit_usd, None);
+
+        let updated = set_monthly_limit(&pool, None, &org.id, Some(250.0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.monthly_limit_usd, Some(250.0));
+
+        let cleared = set_monthly_limit(&pool, None, &org.id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cleared.monthly_limit_usd, None);
+
+        let missing = set_monthly_limit(&pool, None, "missing", Some(1.0))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_default_project() {
+        let pool = setup_test_pool().await;
+        let org = get_organization(&pool, None, "default")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(org.default_project_id, None);
+
+        let updated = set_default_project(&pool, None, "default", Some("default"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.default_project_id.as_deref(), Some("default"));
+
+        let cleared = set_default_project(&pool, None, "default", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cleared.default_project_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_set_retention_days() {
+        let pool = setup_test_pool().await;
+        let updated = set_retention_days(&pool, None, "default", Some(30))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.retention_days, Some(30));
+
+        let cleared = set_retention_days(&pool, None, "default", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cleared.retention_days, None);
+
+        let missing = set_retention_days(&pool, None, "missing", Some(30))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
     #[tokio::test]
     async fn test_delete_organization() {
         let pool = setup_test_pool().await;
@@ -3,7 +3,7 @@
 //! Initial schema with all tables. No migrations needed for first version.
 
 /// Current schema version
-pub const SCHEMA_VERSION: i32 = 3;
+pub const SCHEMA_VERSION: i32 = 7;
 
 /// Complete schema SQL
 pub const SCHEMA: &str = r#"
@@ -37,7 +37,10 @@ CREATE TABLE IF NOT EXISTS organizations (
         OR (length(slug) = 1 AND slug GLOB '[a-z0-9]')
     ),
     created_at INTEGER NOT NULL,
-    updated_at INTEGER NOT NULL
+    updated_at INTEGER NOT NULL,
+    monthly_limit_usd REAL CHECK(monthly_limit_usd IS NULL OR monthly_limit_usd >= 0),
+    default_project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
+    retention_days INTEGER CHECK(retention_days IS NULL OR retention_days > 0)
 );
 
 CREATE INDEX IF NOT EXISTS idx_organizations_slug ON organizations(slug);
@@ -259,6 +262,18 @@ CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_project
 CREATE UNIQUE INDEX IF NOT EXISTS idx_cred_perms_unique_org_default
     ON credential_project_permissions(credential_id)
     WHERE project_id IS NULL;
+
+-- =============================================================================
+-- 12. Organization Feature Flags
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS org_feature_flags (
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    flag TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (organization_id, flag)
+);
 "#;
 
 #[cfg(test)]
@@ -293,6 +308,7 @@ mod tests {
             "api_keys",
             "credentials",
             "credential_project_permissions",
+            "org_feature_flags",
         ];
 
         for table in required_tables {
@@ -33,6 +33,17 @@ pub struct StreamSubscription {
     pub receiver: Pin<Box<dyn Stream<Item = Result<StreamMessage, TopicError>> + Send>>,
 }
 
+/// Delivery guarantees of a topic backend
+///
+/// Lets callers adapt to backend differences without matching on the backend name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopicCapabilities {
+    /// Stream messages survive process restarts and are shared across processes
+    pub durable_streams: bool,
+    /// Broadcast messages reach subscribers in other processes
+    pub distributed_broadcast: bool,
+}
+
 /// Topic backend trait
 ///
 /// Defines the interface for topic implementations.
@@ -82,7 +93,8 @@ pub trait TopicBackend: Send + Sync {
     /// # Arguments
     /// - `topic`: Stream name
     /// - `group`: Consumer group name (e.g., "trace_pipeline")
-    /// - `consumer`: Unique consumer name (e.g., "{uuid}:{pid}")
+    /// - `consumer`: Unique consumer name (see `consumer_id`); subscribers sharing
+    ///   a name split the group's messages and replay each other's pending entries
     async fn stream_subscribe(
         &self,
         topic: &str,
@@ -90,6 +102,24 @@ pub trait TopicBackend: Send + Sync {
         consumer: &str,
     ) -> Result<StreamSubscription, TopicError>;
 
+    /// Last message ID delivered to `consumer` in `group`, if any
+    ///
+    /// Lets a restarting worker with a stable consumer name verify where it
+    /// will resume. On resubscribe, both backends first re-deliver the
+    /// consumer's own unacknowledged messages, then continue with new ones.
+    ///
+    /// - **Memory**: exact, but only survives within the process (a restarted
+    ///   subscription task resumes; a restarted process starts empty).
+    /// - **Redis**: tracked in a per-stream hash updated after each read, so it
+    ///   survives process and connection restarts. Unacked messages remain in
+    ///   the consumer's pending list and are re-read on resubscribe.
+    async fn stream_last_delivered(
+        &self,
+        topic: &str,
+        group: &str,
+        consumer: &str,
+    ) -> Result<Option<String>, TopicError>;
+
     /// Acknowledge message processing complete
     ///
     /// Removes the message from the pending list. Must be called after
@@ -97,12 +127,28 @@ pub trait TopicBackend: Send + Sync {
     async fn stream_ack(&self, topic: &str, group: &str, id: &str) -> Result<(), TopicError>;
 
     /// Acknowledge multiple messages in a single call
+    ///
+    /// Defaults to acking each ID with `stream_ack`. Every ID is attempted even
+    /// if some fail; the last error is returned. Backends with a native batch
+    /// ack (e.g. Redis XACK with multiple IDs) should override this.
     async fn stream_ack_batch(
         &self,
         topic: &str,
         group: &str,
         ids: &[String],
-    ) -> Result<(), TopicError>;
+    ) -> Result<(), TopicError> {
+        let mut last_err = None;
+        for id in ids {
+            if let Err(e) = self.stream_ack(topic, group, id).await {
+                tracing::warn!(error = %e, id, "Failed to ack message in batch, continuing");
+                last_err = Some(e);
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
 
     /// Claim pending messages that have been idle too long
     ///
@@ -136,6 +182,27 @@ pub trait TopicBackend: Send + Sync {
 
     /// Backend name for debugging/logging
     fn backend_name(&self) -> &'static str;
+
+    /// Delivery guarantees of this backend
+    fn capabilities(&self) -> TopicCapabilities;
+
+    /// Connection pool usage, for backends that pool connections
+    fn pool_stats(&self) -> Option<PoolStats> {
+        None
+    }
+}
+
+/// Connection pool usage for monitoring
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Configured maximum connections
+    pub max_size: usize,
+    /// Connections checked out by callers
+    pub in_use: usize,
+    /// Open connections waiting in the pool
+    pub idle: usize,
+    /// Callers waiting for a connection
+    pub waiting: usize,
 }
 
 /// Stream statistics for monitoring
@@ -25,6 +25,7 @@ use tokio::sync::{Notify, broadcast};
 
 use super::backend::{
     BroadcastSubscription, StreamMessage, StreamStats, StreamSubscription, TopicBackend,
+    TopicCapabilities,
 };
 use super::error::TopicError;
 
@@ -264,15 +265,48 @@ impl TopicBackend for MemoryTopicBackend {
 
         let stream = stream! {
             let mut last_seen: u64 = 0;
+            let mut replay: VecDeque<u64> = VecDeque::new();
 
-            // Get initial position from consumer's last delivered
+            // Resume from consumer's last delivered, re-delivering its unacked messages first
             {
                 let streams = state.streams.read();
                 if let Some(stream_state) = streams.get(&topic)
                     && let Some(cg) = stream_state.groups.get(&group)
-                    && let Some(&last) = cg.last_delivered.get(&consumer)
                 {
-                    last_seen = last;
+                    if let Some(&last) = cg.last_delivered.get(&consumer) {
+                        last_seen = last;
+                    }
+                    let mut owned: Vec<u64> = cg
+                        .pending
+                        .iter()
+                        .filter(|(_, (owner, _))| *owner == consumer)
+                        .map(|(&id, _)| id)
+                        .collect();
+                    owned.sort_unstable();
+                    replay.extend(owned);
+                }
+            }
+
+            while let Some(id) = replay.pop_front() {
+                let maybe_msg = {
+                    let mut streams = state.streams.write();
+                    streams.get_mut(&topic).and_then(|stream_state| {
+                        let cg = stream_state.groups.get_mut(&group)?;
+                        // Skip if acked or claimed by another consumer since subscribe
+                        let (owner, delivered_at) = cg.pending.get_mut(&id)?;
+                        if *owner != consumer {
+                            return None;
+                        }
+                        *delivered_at = Instant::now();
+                        let entry = stream_state.messages.iter().find(|e| e.id == id)?;
+                        Some(StreamMessage {
+                            id: id.to_string(),
+                            payload: entry.payload.clone(),
+                        })
+                    })
+                };
+                if let Some(msg) = maybe_msg {
+                    yield Ok(msg);
                 }
             }
 
@@ -335,6 +369,20 @@ impl TopicBackend for MemoryTopicBackend {
         })
     }
 
+    async fn stream_last_delivered(
+        &self,
+        topic: &str,
+        group: &str,
+        consumer: &str,
+    ) -> Result<Option<String>, TopicError> {
+        let streams = self.state.streams.read();
+        Ok(streams
+            .get(topic)
+            .and_then(|s| s.groups.get(group))
+            .and_then(|cg| cg.last_delivered.get(consumer))
+            .map(|id| id.to_string()))
+    }
+
     async fn stream_ack(&self, topic: &str, group: &str, id: &str) -> Result<(), TopicError> {
         let id: u64 = id
             .parse()
@@ -353,25 +401,6 @@ impl TopicBackend for MemoryTopicBackend {
         Ok(())
     }
 
-    async fn stream_ack_batch(
-        &self,
-        topic: &str,
-        group: &str,
-        ids: &[String],
-    ) -> Result<(), TopicError> {
-        let mut last_err = None;
-        for id in ids {
-            if let Err(e) = self.stream_ack(topic, group, id).await {
-                tracing::warn!(error = %e, id, "Failed to ack message in batch, continuing");
-                last_err = Some(e);
-            }
-        }
-        match last_err {
-            Some(e) => Err(e),
-            None => Ok(()),
-        }
-    }
-
     async fn stream_claim(
         &self,
         topic: &str,
@@ -464,6 +493,13 @@ impl TopicBackend for MemoryTopicBackend {
     fn backend_name(&self) -> &'static str {
         "memory"
     }
+
+    fn capabilities(&self) -> TopicCapabilities {
+        TopicCapabilities {
+            durable_streams: false,
+            distributed_broadcast: false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -529,6 +565,97 @@ mod tests {
         assert_eq!(stats.pending, 0);
     }
 
+    async fn recv_next(sub: &mut StreamSubscription) -> Option<StreamMessage> {
+        tokio::time::timeout(tokio::time::Duration::from_millis(200), sub.receiver.next())
+            .await
+            .ok()
+            .flatten()
+            .map(|r| r.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_stream_resume_with_stable_consumer_name() {
+        let backend = MemoryTopicBackend::new();
+        for payload in [b"msg1", b"msg2", b"msg3"] {
+            backend.stream_publish("stream", payload).await.unwrap();
+        }
+
+        // First run: receive two messages, ack only the first, then "crash"
+        {
+            let mut sub = backend
+                .stream_subscribe("stream", "group1", "worker-1")
+                .await
+                .unwrap();
+            let first = recv_next(&mut sub).await.unwrap();
+            let second = recv_next(&mut sub).await.unwrap();
+            assert_eq!((first.id.as_str(), second.id.as_str()), ("1", "2"));
+            backend
+                .stream_ack("stream", "group1", &first.id)
+                .await
+                .unwrap();
+        }
+
+        let position = backend
+            .stream_last_delivered("stream", "group1", "worker-1")
+            .await
+            .unwrap();
+        assert_eq!(position.as_deref(), Some("2"));
+
+        // Restart with the same name: unacked message first, then new ones
+        let mut sub = backend
+            .stream_subscribe("stream", "group1", "worker-1")
+            .await
+            .unwrap();
+        let replayed = recv_next(&mut sub).await.unwrap();
+        assert_eq!(replayed.id, "2");
+        assert_eq!(replayed.payload, b"msg2");
+        let next = recv_next(&mut sub).await.unwrap();
+        assert_eq!(next.id, "3");
+        assert!(
+            recv_next(&mut sub).await.is_none(),
+            "msg1 must not be re-read"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_duplicate_consumer_name_splits_messages() {
+        // Two processes sharing a consumer name do not both see every message:
+        // each gets a disjoint subset, which is why names must be unique.
+        let backend = MemoryTopicBackend::new();
+        let mut a = backend
+            .stream_subscribe("stream", "group1", "worker-1")
+            .await
+            .unwrap();
+        let mut b = backend
+            .stream_subscribe("stream", "group1", "worker-1")
+            .await
+            .unwrap();
+        assert!(recv_next(&mut a).await.is_none());
+        assert!(recv_next(&mut b).await.is_none());
+
+        for payload in [b"msg1", b"msg2", b"msg3", b"msg4"] {
+            backend.stream_publish("stream", payload).await.unwrap();
+        }
+
+        let mut seen_a = Vec::new();
+        let mut seen_b = Vec::new();
+        loop {
+            let next_a = recv_next(&mut a).await;
+            let next_b = recv_next(&mut b).await;
+            if next_a.is_none() && next_b.is_none() {
+                break;
+            }
+            seen_a.extend(next_a.map(|m| m.id));
+            seen_b.extend(next_b.map(|m| m.id));
+        }
+
+        assert!(!seen_a.is_empty() && !seen_b.is_empty());
+        assert!(seen_a.iter().all(|id| !seen_b.contains(id)));
+        let mut all: Vec<_> = seen_a.into_iter().chain(seen_b).collect();
+        all.sort();
+        assert_eq!(all, ["1", "2", "3", "4"]);
+    }
+
     #[tokio::test]
     async fn test_stream_stats() {
         let backend = MemoryTopicBackend::new();
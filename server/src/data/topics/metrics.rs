@@ -0,0 +1,152 @@
+//! Stream processing latency metrics
+//!
+//! Each stream subscriber notes when it receives (or claims) a message and
+//! records the elapsed time when the message is acked, into a per-topic
+//! histogram. Stages inside a consumer can record into the same registry under
+//! their own names, so slow extraction and slow persistence are told apart.
+//!
+//! Recording is one `Instant::now()`, a map insert/remove and a few relaxed
+//! atomic adds per message.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::{Mutex, RwLock};
+
+/// Histogram bucket upper bounds in milliseconds (an implicit +Inf bucket follows)
+pub const LATENCY_BUCKETS_MS: &[u64] = &[
+    1, 5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000,
+];
+
+/// Upper bound on tracked in-flight messages per subscriber (reset when exceeded)
+const MAX_IN_FLIGHT: usize = 10_000;
+
+/// Fixed-bucket latency histogram with lock-free recording
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    /// Per-bucket counts; the last entry is the +Inf bucket
+    buckets: Vec<AtomicU64>,
+    sum_us: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..=LATENCY_BUCKETS_MS.len())
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            sum_us: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Record one sample
+    pub fn observe(&self, elapsed: Duration) {
+        let ms = elapsed.as_millis();
+        let idx = LATENCY_BUCKETS_MS.partition_point(|&upper| u128::from(upper) < ms);
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Point-in-time copy with cumulative bucket counts
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(idx, bucket)| {
+                cumulative += bucket.load(Ordering::Relaxed);
+                (LATENCY_BUCKETS_MS.get(idx).copied(), cumulative)
+            })
+            .collect();
+        HistogramSnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            sum_ms: self.sum_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            buckets,
+        }
+    }
+}
+
+/// Snapshot of a latency histogram
+#[derive(Debug, Clone)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum_ms: f64,
+    /// (upper bound in ms, cumulative count); `None` is the +Inf bucket
+    pub buckets: Vec<(Option<u64>, u64)>,
+}
+
+/// Registry of named latency histograms shared by a `TopicService`
+#[derive(Debug, Default)]
+pub struct StreamMetrics {
+    histograms: RwLock<HashMap<String, Arc<LatencyHistogram>>>,
+}
+
+impl StreamMetrics {
+    /// Get or create the histogram for `name`
+    pub fn histogram(&self, name: &str) -> Arc<LatencyHistogram> {
+        if let Some(histogram) = self.histograms.read().get(name) {
+            return Arc::clone(histogram);
+        }
+        Arc::clone(self.histograms.write().entry(name.to_string()).or_default())
+    }
+
+    /// Record one sample into the histogram for `name`
+    pub fn observe(&self, name: &str, elapsed: Duration) {
+        self.histogram(name).observe(elapsed);
+    }
+
+    /// Snapshots of all histograms, sorted by name
+    pub fn snapshot(&self) -> Vec<(String, HistogramSnapshot)> {
+        let mut snapshots: Vec<_> = self
+            .histograms
+            .read()
+            .iter()
+            .map(|(name, histogram)| (name.clone(), histogram.snapshot()))
+            .collect();
+        snapshots.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshots
+    }
+}
+
+/// Receive times of unacked messages for one subscriber
+pub(super) struct InFlight {
+    started: Mutex<HashMap<String, Instant>>,
+    histogram: Arc<LatencyHistogram>,
+}
+
+impl InFlight {
+    pub(super) fn new(histogram: Arc<LatencyHistogram>) -> Self {
+        Self {
+            started: Mutex::new(HashMap::new()),
+            histogram,
+        }
+    }
+
+    /// Note that a message was received or claimed. A redelivery restarts the clock.
+    pub(super) fn start(&self, id: &str) {
+        let mut started = self.started.lock();
+        // Messages that are never acked (e.g. dropped on shutdown) must not accumulate
+        if started.len() >= MAX_IN_FLIGHT {
+            started.clear();
+        }
+        started.insert(id.to_string(), Instant::now());
+    }
+
+    /// Record the latency of an acked message, if its receive time is known
+    pub(super) fn finish(&self, id: &str) {
+        if let Some(started) = self.started.lock().remove(id) {
+            self.histogram.observe(started.elapsed());
+        }
+    }
+}
@@ -21,14 +21,16 @@
 mod backend;
 mod error;
 mod memory;
+mod metrics;
 mod pubsub;
 mod redis;
 
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use futures::StreamExt;
 use parking_lot::RwLock;
@@ -39,15 +41,19 @@ use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::task::JoinHandle;
 
 pub use backend::{
-    BroadcastSubscription, StreamMessage, StreamStats, StreamSubscription, TopicBackend,
+    BroadcastSubscription, PoolStats, StreamMessage, StreamStats, StreamSubscription, TopicBackend,
+    TopicCapabilities,
 };
 pub use error::TopicError;
 use memory::MemoryTopicBackend;
+use metrics::InFlight;
+pub use metrics::{HistogramSnapshot, LATENCY_BUCKETS_MS, LatencyHistogram, StreamMetrics};
 
 use crate::core::config::{CacheBackendType, CacheConfig};
 use crate::core::constants::{
-    DEFAULT_TOPIC_BUFFER_SIZE, DEFAULT_TOPIC_CHANNEL_CAPACITY, ENV_TOPIC_BUFFER_SIZE,
-    ENV_TOPIC_CHANNEL_CAPACITY,
+    DEFAULT_TOPIC_BUFFER_SIZE, DEFAULT_TOPIC_CHANNEL_CAPACITY, DEFAULT_TOPIC_SHUTDOWN_TIMEOUT_SECS,
+    ENV_HOSTNAME, ENV_TOPIC_BUFFER_SIZE, ENV_TOPIC_CHANNEL_CAPACITY,
+    ENV_TOPIC_SHUTDOWN_TIMEOUT_SECS, HOSTNAME_FILE,
 };
 
 // ============================================================================
@@ -73,6 +79,8 @@ pub trait TopicMessage: Clone + Send + Sync + 'static {
 pub struct TopicConfig {
     pub buffer_size: usize,
     pub channel_capacity: usize,
+    /// How long critical dispatchers may drain on shutdown before being aborted
+    pub shutdown_timeout: Duration,
 }
 
 impl Default for TopicConfig {
@@ -87,13 +95,47 @@ impl Default for TopicConfig {
             .and_then(|s| s.parse().ok())
             .unwrap_or(DEFAULT_TOPIC_CHANNEL_CAPACITY);
 
+        let shutdown_timeout_secs = std::env::var(ENV_TOPIC_SHUTDOWN_TIMEOUT_SECS)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_TOPIC_SHUTDOWN_TIMEOUT_SECS);
+
         Self {
             buffer_size,
             channel_capacity,
+            shutdown_timeout: Duration::from_secs(shutdown_timeout_secs),
         }
     }
 }
 
+// ============================================================================
+// CONSUMER NAMES
+// ============================================================================
+
+static CONSUMER_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Random per-process token, so names differ across restarts even when the
+/// hostname and pid repeat (pid 1 in containers)
+static PROCESS_TOKEN: LazyLock<String> =
+    LazyLock::new(|| uuid::Uuid::new_v4().simple().to_string()[..8].to_string());
+
+/// Derive a stream consumer name unique to this process start:
+/// `{hostname}:{pid}:{token}:{seq}`.
+///
+/// Consumers sharing a name within a group split its messages between them, and
+/// each can ack or reclaim the other's pending entries. Pods should either use
+/// this or configure distinct worker IDs.
+pub fn consumer_id() -> String {
+    let host = std::env::var(ENV_HOSTNAME)
+        .ok()
+        .or_else(|| std::fs::read_to_string(HOSTNAME_FILE).ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    let seq = CONSUMER_SEQ.fetch_add(1, Ordering::Relaxed);
+    format!("{}:{}:{}:{}", host, std::process::id(), *PROCESS_TOKEN, seq)
+}
+
 // ============================================================================
 // LOCAL PUBLISHER/SUBSCRIBER (For backward compatibility)
 // ============================================================================
@@ -195,6 +237,8 @@ pub struct TopicService {
     default_config: TopicConfig,
     /// Distributed backend (memory or Redis)
     backend: Arc<dyn TopicBackend>,
+    /// Stream processing latency histograms (receive to ack, per topic)
+    metrics: Arc<StreamMetrics>,
 }
 
 impl TopicService {
@@ -210,6 +254,7 @@ impl TopicService {
             dispatchers: RwLock::new(Vec::new()),
             default_config: config,
             backend: Arc::new(MemoryTopicBackend::new()),
+            metrics: Arc::default(),
         }
     }
 
@@ -221,7 +266,7 @@ impl TopicService {
                 let url = cache_config.redis_url.as_ref().ok_or_else(|| {
                     TopicError::Config("redis_url required for Redis backend".into())
                 })?;
-                Arc::new(redis::RedisTopicBackend::new(url).await?)
+                Arc::new(redis::RedisTopicBackend::new(url, cache_config.redis_pool_size).await?)
             }
         };
 
@@ -230,6 +275,7 @@ impl TopicService {
             dispatchers: RwLock::new(Vec::new()),
             default_config: TopicConfig::default(),
             backend,
+            metrics: Arc::default(),
         })
     }
 
@@ -238,6 +284,21 @@ impl TopicService {
         self.backend.backend_name()
     }
 
+    /// Delivery guarantees of the configured backend
+    pub fn capabilities(&self) -> TopicCapabilities {
+        self.backend.capabilities()
+    }
+
+    /// Connection pool usage of the backend (`None` for the in-memory backend)
+    pub fn pool_stats(&self) -> Option<PoolStats> {
+        self.backend.pool_stats()
+    }
+
+    /// Stream processing latency histograms recorded by this service's subscribers
+    pub fn stream_metrics(&self) -> &StreamMetrics {
+        &self.metrics
+    }
+
     // ========================================================================
     // LOCAL TOPIC API (Backward compatible)
     // ========================================================================
@@ -392,6 +453,7 @@ impl TopicService {
         StreamTopic {
             name: name.to_string(),
             backend: Arc::clone(&self.backend),
+            metrics: Arc::clone(&self.metrics),
             _phantom: PhantomData,
         }
     }
@@ -423,7 +485,8 @@ impl TopicService {
 
     /// Gracefully shutdown all dispatcher tasks
     ///
-    /// - Critical topics: Signal to drain mpsc channels, then wait
+    /// - Critical topics: Signal to drain mpsc channels, then wait up to
+    ///   `shutdown_timeout`; dispatchers still running after that are aborted
     /// - Ephemeral topics: Abort immediately (no draining needed)
     pub async fn shutdown(&self) {
         let entries: Vec<_> = {
@@ -445,9 +508,26 @@ impl TopicService {
             }
         }
 
-        // Wait for critical dispatchers to finish draining
-        for handle in critical_handles {
-            let _ = handle.await;
+        // Wait for critical dispatchers to finish draining, bounded by the timeout
+        let timeout = self.default_config.shutdown_timeout;
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut aborted = 0;
+        for mut handle in critical_handles {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                handle.abort();
+                let _ = handle.await;
+                aborted += 1;
+            }
+        }
+        if aborted > 0 {
+            tracing::warn!(
+                aborted,
+                timeout_secs = timeout.as_secs_f64(),
+                "Topic dispatchers did not drain before shutdown timeout, aborted"
+            );
         }
     }
 }
@@ -507,6 +587,7 @@ where
 {
     name: String,
     backend: Arc<dyn TopicBackend>,
+    metrics: Arc<StreamMetrics>,
     _phantom: PhantomData<T>,
 }
 
@@ -522,10 +603,25 @@ where
         self.backend.stream_publish(&self.name, &payload).await
     }
 
+    /// Last message ID delivered to `consumer` in `group`
+    ///
+    /// Use with a stable consumer name to verify the resume position after a
+    /// restart. See `TopicBackend::stream_last_delivered` for backend semantics.
+    pub async fn last_delivered(
+        &self,
+        group: &str,
+        consumer: &str,
+    ) -> Result<Option<String>, TopicError> {
+        self.backend
+            .stream_last_delivered(&self.name, group, consumer)
+            .await
+    }
+
     /// Subscribe to the stream with a consumer group
     ///
     /// Messages are distributed across consumers in the group.
-    /// Call `ack()` after processing each message.
+    /// Call `ack()` after processing each message; the time from receive (or
+    /// claim) to ack is recorded in the topic's latency histogram.
     pub async fn subscribe(
         &self,
         group: &str,
@@ -540,6 +636,7 @@ where
             group: group.to_string(),
             backend: Arc::clone(&self.backend),
             subscription,
+            in_flight: Arc::new(InFlight::new(self.metrics.histogram(&self.name))),
             _phantom: PhantomData,
         })
     }
@@ -556,19 +653,26 @@ pub struct StreamAcker {
     name: String,
     group: String,
     backend: Arc<dyn TopicBackend>,
+    in_flight: Arc<InFlight>,
 }
 
 impl StreamAcker {
     /// Acknowledge message processing complete
     pub async fn ack(&self, id: &str) -> Result<(), TopicError> {
-        self.backend.stream_ack(&self.name, &self.group, id).await
+        self.backend.stream_ack(&self.name, &self.group, id).await?;
+        self.in_flight.finish(id);
+        Ok(())
     }
 
     /// Acknowledge multiple messages in a single call
     pub async fn ack_batch(&self, ids: &[String]) -> Result<(), TopicError> {
         self.backend
             .stream_ack_batch(&self.name, &self.group, ids)
-            .await
+            .await?;
+        for id in ids {
+            self.in_flight.finish(id);
+        }
+        Ok(())
     }
 }
 
@@ -578,6 +682,7 @@ pub struct StreamClaimer {
     name: String,
     group: String,
     backend: Arc<dyn TopicBackend>,
+    in_flight: Arc<InFlight>,
 }
 
 impl StreamClaimer {
@@ -592,9 +697,14 @@ impl StreamClaimer {
         min_idle_ms: u64,
         count: usize,
     ) -> Result<Vec<StreamMessage>, TopicError> {
-        self.backend
+        let messages = self
+            .backend
             .stream_claim(&self.name, &self.group, consumer, min_idle_ms, count)
-            .await
+            .await?;
+        for msg in &messages {
+            self.in_flight.start(&msg.id);
+        }
+        Ok(messages)
     }
 }
 
@@ -607,6 +717,7 @@ where
     group: String,
     backend: Arc<dyn TopicBackend>,
     subscription: StreamSubscription,
+    in_flight: Arc<InFlight>,
     _phantom: PhantomData<T>,
 }
 
@@ -622,6 +733,7 @@ where
             let msg = result?;
             let decoded = T::decode(&msg.payload[..])
                 .map_err(|e| TopicError::Serialization(e.to_string()))?;
+            self.in_flight.start(&msg.id);
             Ok((msg.id, decoded))
         } else {
             Err(TopicError::ChannelClosed)
@@ -634,6 +746,7 @@ where
             name: self.name.clone(),
             group: self.group.clone(),
             backend: Arc::clone(&self.backend),
+            in_flight: Arc::clone(&self.in_flight),
         }
     }
 
@@ -643,6 +756,7 @@ where
             name: self.name.clone(),
             group: self.group.clone(),
             backend: Arc::clone(&self.backend),
+            in_flight: Arc::clone(&self.in_flight),
         }
     }
 
@@ -662,6 +776,7 @@ where
         for msg in messages {
             let decoded = T::decode(&msg.payload[..])
                 .map_err(|e| TopicError::Serialization(e.to_string()))?;
+            self.in_flight.start(&msg.id);
             result.push((msg.id, decoded));
         }
         Ok(result)
@@ -764,11 +879,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_consumer_id_is_unique() {
+        let first = consumer_id();
+        let second = consumer_id();
+        assert_ne!(first, second);
+        assert!(first.contains(&format!(":{}:{}:", std::process::id(), *PROCESS_TOKEN)));
+    }
+
     #[tokio::test]
     async fn test_publisher_buffer_full() {
         let config = TopicConfig {
             buffer_size: 100,
             channel_capacity: 10,
+            ..Default::default()
         };
         let service = TopicService::with_config(config);
         let topic = service.topic::<TestMessage>("test").unwrap();
@@ -842,6 +966,7 @@ mod tests {
         let config = TopicConfig {
             buffer_size: 100,
             channel_capacity: 10,
+            ..Default::default()
         };
         let service = TopicService::with_config(config);
         let topic = service.topic::<TestMessage>("test").unwrap();
@@ -926,4 +1051,181 @@ mod tests {
         let service = TopicService::new();
         assert_eq!(service.backend_name(), "memory");
     }
+
+    /// Backend that only implements single-message ack
+    #[derive(Default)]
+    struct SingleAckBackend {
+        acked: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TopicBackend for SingleAckBackend {
+        async fn publish(&self, _topic: &str, _payload: &[u8]) -> Result<(), TopicError> {
+            Err(TopicError::Config("publish not supported".to_string()))
+        }
+
+        async fn subscribe(&self, _topic: &str) -> Result<BroadcastSubscription, TopicError> {
+            Err(TopicError::Config("subscribe not supported".to_string()))
+        }
+
+        async fn stream_publish(
+            &self,
+            _topic: &str,
+            _payload: &[u8],
+        ) -> Result<String, TopicError> {
+            Err(TopicError::Config(
+                "stream_publish not supported".to_string(),
+            ))
+        }
+
+        async fn stream_subscribe(
+            &self,
+            _topic: &str,
+            _group: &str,
+            _consumer: &str,
+        ) -> Result<StreamSubscription, TopicError> {
+            Err(TopicError::Config(
+                "stream_subscribe not supported".to_string(),
+            ))
+        }
+
+        async fn stream_last_delivered(
+            &self,
+            _topic: &str,
+            _group: &str,
+            _consumer: &str,
+        ) -> Result<Option<String>, TopicError> {
+            Ok(None)
+        }
+
+        async fn stream_ack(&self, _topic: &str, _group: &str, id: &str) -> Result<(), TopicError> {
+            if id == "bad" {
+                return Err(TopicError::Stream(format!("invalid message id: {}", id)));
+            }
+            self.acked.lock().push(id.to_string());
+            Ok(())
+        }
+
+        async fn stream_claim(
+            &self,
+            _topic: &str,
+            _group: &str,
+            _consumer: &str,
+            _min_idle_ms: u64,
+            _count: usize,
+        ) -> Result<Vec<StreamMessage>, TopicError> {
+            Ok(Vec::new())
+        }
+
+        async fn stream_stats(
+            &self,
+            _topic: &str,
+            _group: &str,
+        ) -> Result<StreamStats, TopicError> {
+            Ok(StreamStats::default())
+        }
+
+        async fn health_check(&self) -> Result<(), TopicError> {
+            Ok(())
+        }
+
+        fn backend_name(&self) -> &'static str {
+            "single_ack"
+        }
+
+        fn capabilities(&self) -> TopicCapabilities {
+            TopicCapabilities {
+                durable_streams: false,
+                distributed_broadcast: false,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ack_batch_falls_back_to_single_ack() {
+        let backend = Arc::new(SingleAckBackend::default());
+        let acker = StreamAcker {
+            name: "stream".to_string(),
+            group: "group".to_string(),
+            backend: backend.clone(),
+            in_flight: Arc::new(InFlight::new(Arc::default())),
+        };
+
+        let ids = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        acker.ack_batch(&ids).await.unwrap();
+        assert_eq!(*backend.acked.lock(), ids);
+
+        // Failures are reported but do not stop the remaining acks
+        let ids = vec!["4".to_string(), "bad".to_string(), "5".to_string()];
+        assert!(acker.ack_batch(&ids).await.is_err());
+        assert_eq!(backend.acked.lock().len(), 5);
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct StreamPayload {
+        #[prost(string, tag = "1")]
+        data: String,
+    }
+
+    impl TopicMessage for StreamPayload {
+        fn size_bytes(&self) -> usize {
+            ProstMessage::encoded_len(self)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_latency_recorded_on_ack() {
+        let service = TopicService::new();
+        let topic = service.stream_topic::<StreamPayload>("latency");
+        let mut subscriber = topic.subscribe("group", "worker").await.unwrap();
+        topic
+            .publish(&StreamPayload {
+                data: "payload".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let (id, _) = subscriber.recv().await.unwrap();
+        let histogram = service.stream_metrics().histogram("latency");
+        assert_eq!(histogram.snapshot().count, 0, "nothing recorded before ack");
+
+        subscriber.acker().ack(&id).await.unwrap();
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 1);
+        assert_eq!(snapshot.buckets.last().map(|b| b.1), Some(1));
+
+        // Acking again does not record a second sample
+        subscriber.acker().ack(&id).await.unwrap();
+        assert_eq!(histogram.snapshot().count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_stuck_dispatcher_after_timeout() {
+        let config = TopicConfig {
+            shutdown_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let service = TopicService::with_config(config);
+        let topic = service.topic::<TestMessage>("drains").unwrap();
+        let mut subscriber = topic.subscribe();
+        topic.publisher().publish(msg("a", 10)).unwrap();
+
+        // A dispatcher that ignores its shutdown signal and never finishes
+        let (shutdown_tx, _shutdown_rx) = oneshot::channel();
+        let stuck = tokio::spawn(std::future::pending::<()>());
+        let stuck_abort = stuck.abort_handle();
+        service.dispatchers.write().push(DispatcherEntry {
+            handle: stuck,
+            shutdown_tx,
+            drain_on_shutdown: true,
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), service.shutdown())
+            .await
+            .expect("shutdown returns after the timeout");
+        assert!(stuck_abort.is_finished(), "stuck dispatcher was aborted");
+
+        // The healthy dispatcher still drained its message first
+        assert_eq!(subscriber.recv().await.unwrap().data, "a");
+    }
 }
@@ -12,10 +12,11 @@ use crate::data::cache::CacheService;
 use crate::data::error::DataError;
 use crate::data::types::{
     ApiKeyRow, ApiKeyScope, ApiKeyValidation, AuthMethodRow, CredentialPermissionRow,
-    CredentialRow, EventRow, FeedMessagesParams, FeedSpansParams, FileRow, LastOwnerResult,
-    LinkRow, ListSessionsParams, ListSpansParams, ListTracesParams, MemberWithUser, MembershipRow,
-    MessageQueryParams, MessageQueryResult, NormalizedMetric, NormalizedSpan, OrgWithRole,
-    OrganizationRow, ProjectRow, SessionRow, SpanCounts, SpanRow, TraceRow, UserRow,
+    CredentialRow, EventRow, FeatureFlag, FeatureFlags, FeedMessagesParams, FeedSpansParams,
+    FileRow, LastOwnerResult, LinkRow, ListSessionsParams, ListSpansParams, ListTracesParams,
+    MemberWithUser, MembershipRow, MessageQueryParams, MessageQueryResult, NormalizedMetric,
+    NormalizedSpan, OrgWithRole, OrganizationRow, ProjectRow, SessionRow, SpanCounts, SpanRow,
+    TraceDeleteFilter, TraceModelUsageRow, TraceRow, UserRow,
 };
 
 // ============================================================================
@@ -70,10 +71,27 @@ pub trait AnalyticsRepository: Send + Sync {
         to_timestamp: Option<DateTime<Utc>>,
     ) -> Result<Vec<FilterOptionRow>, DataError>;
 
+    /// Get per-model token/cost usage of generation spans for the given traces
+    async fn get_trace_model_usage(
+        &self,
+        project_id: &str,
+        trace_ids: &[String],
+    ) -> Result<Vec<TraceModelUsageRow>, DataError>;
+
     /// Delete traces by IDs
     async fn delete_traces(&self, project_id: &str, trace_ids: &[String])
     -> Result<u64, DataError>;
 
+    /// Get up to `limit` trace_ids matching a bulk-delete filter, in trace_id
+    /// order and strictly after `after` (keyset pagination)
+    async fn get_trace_ids_matching(
+        &self,
+        project_id: &str,
+        filter: &TraceDeleteFilter,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>, DataError>;
+
     // ==================== Span Operations ====================
 
     /// List spans with pagination and filters
@@ -86,6 +104,13 @@ pub trait AnalyticsRepository: Send + Sync {
         trace_id: &str,
     ) -> Result<Vec<SpanRow>, DataError>;
 
+    /// Get `span_id -> parent_span_id` links for a trace (root spans omitted)
+    async fn get_parent_links_for_trace(
+        &self,
+        project_id: &str,
+        trace_id: &str,
+    ) -> Result<HashMap<String, String>, DataError>;
+
     /// Get a single span by ID
     async fn get_span(
         &self,
@@ -206,6 +231,27 @@ pub trait AnalyticsRepository: Send + Sync {
         params: &crate::data::types::StatsParams,
     ) -> Result<crate::data::types::ProjectStatsResult, DataError>;
 
+    /// Get a zero-filled, time-bucketed series of one metric
+    async fn get_timeseries(
+        &self,
+        params: &crate::data::types::TimeseriesParams,
+    ) -> Result<Vec<crate::data::types::TimeseriesPoint>, DataError>;
+
+    /// Get per-tool call, error and latency aggregates
+    async fn get_tool_stats(
+        &self,
+        params: &crate::data::types::StatsParams,
+    ) -> Result<Vec<crate::data::types::ToolStats>, DataError>;
+
+    /// Total cost per project over a time range, in one grouped query.
+    /// Used for org-level budget rollups; projects without cost are absent.
+    async fn get_project_costs(
+        &self,
+        project_ids: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashMap<String, f64>, DataError>;
+
     // ==================== Ingestion Operations ====================
 
     /// Insert spans in batch (takes ownership to avoid clone for spawn_blocking)
@@ -294,6 +340,30 @@ pub trait TransactionalRepository: Send + Sync {
         name: &str,
     ) -> Result<Option<OrganizationRow>, DataError>;
 
+    /// Set or clear an organization's monthly spend cap
+    async fn set_org_monthly_limit(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        monthly_limit_usd: Option<f64>,
+    ) -> Result<Option<OrganizationRow>, DataError>;
+
+    /// Set or clear the project that receives an organization's unscoped traces
+    async fn set_org_default_project(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        project_id: Option<&str>,
+    ) -> Result<Option<OrganizationRow>, DataError>;
+
+    /// Set or clear the number of days an organization's new spans are kept
+    async fn set_org_retention_days(
+        &self,
+        cache: Option<&CacheService>,
+        id: &str,
+        retention_days: Option<i64>,
+    ) -> Result<Option<OrganizationRow>, DataError>;
+
     /// List organizations for a user with their role
     async fn list_orgs_for_user(
         &self,
@@ -713,6 +783,25 @@ pub trait TransactionalRepository: Send + Sync {
         org_id: &str,
         project_id: &str,
     ) -> Result<Vec<String>, DataError>;
+
+    // ==================== Feature Flag Operations ====================
+
+    /// Get resolved feature flags for an organization (unset flags are off)
+    async fn get_org_feature_flags(
+        &self,
+        cache: Option<&CacheService>,
+        org_id: &str,
+    ) -> Result<FeatureFlags, DataError>;
+
+    /// Enable or disable a feature flag for an organization
+    async fn set_org_feature_flag(
+        &self,
+        cache: Option<&CacheService>,
+        org_id: &str,
+        flag: FeatureFlag,
+        enabled: bool,
+        updated_by: Option<&str>,
+    ) -> Result<(), DataError>;
 }
 
 // ============================================================================
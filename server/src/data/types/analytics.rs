@@ -3,11 +3,16 @@
 //! This module contains query result types and parameters that are used
 //! by both DuckDB and ClickHouse backends.
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 
 use crate::api::routes::otel::filters::Filter;
 use crate::api::types::OrderBy;
 
+use super::PrimaryModelRule;
+
 // ============================================================================
 // Row types (query results)
 // ============================================================================
@@ -65,6 +70,7 @@ pub struct SpanRow {
     pub user_id: Option<String>,
     pub gen_ai_system: Option<String>,
     pub gen_ai_request_model: Option<String>,
+    pub gen_ai_response_model: Option<String>,
     pub gen_ai_agent_name: Option<String>,
     pub gen_ai_finish_reasons: Vec<String>,
     pub gen_ai_usage_input_tokens: i64,
@@ -73,13 +79,20 @@ pub struct SpanRow {
     pub gen_ai_usage_cache_read_tokens: i64,
     pub gen_ai_usage_cache_write_tokens: i64,
     pub gen_ai_usage_reasoning_tokens: i64,
+    pub gen_ai_usage_web_search_calls: i64,
     pub gen_ai_cost_input: f64,
     pub gen_ai_cost_output: f64,
     pub gen_ai_cost_cache_read: f64,
     pub gen_ai_cost_cache_write: f64,
     pub gen_ai_cost_reasoning: f64,
     pub gen_ai_cost_total: f64,
+    pub gen_ai_cost_source: Option<String>,
     pub gen_ai_usage_details: Option<String>,
+    pub gen_ai_reasoning_effort: Option<String>,
+    pub gen_ai_verbosity: Option<String>,
+    pub gen_ai_raw_model: Option<String>,
+    pub gen_ai_embedding_dimensions: Option<i64>,
+    pub gen_ai_encoding_format: Option<String>,
     pub metadata: Option<String>,
     pub attributes: Option<String>,
     pub input_preview: Option<String>,
@@ -95,6 +108,17 @@ pub struct SpanCounts {
     pub link_count: i64,
 }
 
+/// Per-trace, per-model usage aggregated from generation spans
+#[derive(Debug, Clone)]
+pub struct TraceModelUsageRow {
+    pub trace_id: String,
+    pub model: String,
+    pub total_tokens: i64,
+    pub total_cost: f64,
+    /// Start time of the earliest generation span using this model
+    pub first_seen: DateTime<Utc>,
+}
+
 /// Result row for span events (extracted from raw_span JSON)
 #[derive(Debug, Clone)]
 pub struct EventRow {
@@ -171,6 +195,30 @@ pub struct ListTracesParams {
     pub include_nongenai: bool,
 }
 
+/// Filter selecting traces for bulk deletion.
+///
+/// Time range, environment and session match on any span of the trace;
+/// `has_error` matches on whether any span of the trace has ERROR status.
+#[derive(Debug, Default, Clone)]
+pub struct TraceDeleteFilter {
+    pub from_timestamp: Option<DateTime<Utc>>,
+    pub to_timestamp: Option<DateTime<Utc>>,
+    pub environment: Option<String>,
+    pub session_id: Option<String>,
+    pub has_error: Option<bool>,
+}
+
+impl TraceDeleteFilter {
+    /// True when no criterion is set (the filter matches every trace)
+    pub fn is_empty(&self) -> bool {
+        self.from_timestamp.is_none()
+            && self.to_timestamp.is_none()
+            && self.environment.is_none()
+            && self.session_id.is_none()
+            && self.has_error.is_none()
+    }
+}
+
 /// Parameters for list_spans query
 #[derive(Debug, Default, Clone)]
 pub struct ListSpansParams {
@@ -297,6 +345,54 @@ pub fn deduplicate_by_span_identity<T: SpanIdentity>(items: Vec<T>) -> Vec<T> {
         .collect()
 }
 
+/// Models used by a trace, resolved according to a [`PrimaryModelRule`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceModels {
+    pub primary: Option<String>,
+    /// All models used by the trace, ranked by the rule (primary first)
+    pub models: Vec<String>,
+}
+
+/// Resolve the primary model and model set for each trace.
+///
+/// Ties are broken by the other metrics and finally by model name so the
+/// result does not depend on row order.
+pub fn resolve_trace_models(
+    rows: &[TraceModelUsageRow],
+    rule: PrimaryModelRule,
+) -> HashMap<String, TraceModels> {
+    let mut by_trace: HashMap<&str, Vec<&TraceModelUsageRow>> = HashMap::new();
+    for row in rows.iter().filter(|r| !r.model.is_empty()) {
+        by_trace.entry(&row.trace_id).or_default().push(row);
+    }
+
+    by_trace
+        .into_iter()
+        .map(|(trace_id, mut usage)| {
+            usage.sort_by(|a, b| compare_model_usage(a, b, rule));
+            let models: Vec<String> = usage.iter().map(|u| u.model.clone()).collect();
+            let primary = models.first().cloned();
+            (trace_id.to_string(), TraceModels { primary, models })
+        })
+        .collect()
+}
+
+fn compare_model_usage(
+    a: &TraceModelUsageRow,
+    b: &TraceModelUsageRow,
+    rule: PrimaryModelRule,
+) -> Ordering {
+    let by_tokens = b.total_tokens.cmp(&a.total_tokens);
+    let by_cost = b.total_cost.total_cmp(&a.total_cost);
+    let by_first_seen = a.first_seen.cmp(&b.first_seen);
+    let primary = match rule {
+        PrimaryModelRule::MostTokens => by_tokens.then(by_cost).then(by_first_seen),
+        PrimaryModelRule::MostCost => by_cost.then(by_tokens).then(by_first_seen),
+        PrimaryModelRule::RootGeneration => by_first_seen.then(by_tokens).then(by_cost),
+    };
+    primary.then_with(|| a.model.cmp(&b.model))
+}
+
 /// Parse tags from JSON string
 pub fn parse_tags(s: &Option<String>) -> Vec<String> {
     s.as_ref()
@@ -331,4 +427,34 @@ mod tests {
             vec!["a".to_string(), "b".to_string()]
         );
     }
+
+    fn usage(model: &str, tokens: i64, cost: f64, offset_secs: i64) -> TraceModelUsageRow {
+        TraceModelUsageRow {
+            trace_id: "t1".to_string(),
+            model: model.to_string(),
+            total_tokens: tokens,
+            total_cost: cost,
+            first_seen: DateTime::from_timestamp(1_700_000_000 + offset_secs, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_trace_models_most_cost() {
+        let rows = vec![
+            usage("gpt-4o-mini", 50_000, 0.01, 0),
+            usage("claude-opus-4", 2_000, 0.15, 5),
+            usage("gpt-4o", 8_000, 0.04, 10),
+        ];
+
+        let resolved = resolve_trace_models(&rows, PrimaryModelRule::MostCost);
+        let trace = &resolved["t1"];
+        assert_eq!(trace.primary.as_deref(), Some("claude-opus-4"));
+        assert_eq!(trace.models, vec!["claude-opus-4", "gpt-4o", "gpt-4o-mini"]);
+
+        let by_tokens = resolve_trace_models(&rows, PrimaryModelRule::MostTokens);
+        assert_eq!(by_tokens["t1"].primary.as_deref(), Some("gpt-4o-mini"));
+
+        let by_root = resolve_trace_models(&rows, PrimaryModelRule::RootGeneration);
+        assert_eq!(by_root["t1"].primary.as_deref(), Some("gpt-4o-mini"));
+    }
 }
@@ -253,6 +253,40 @@ impl AggregationTemporality {
     }
 }
 
+// ============================================================================
+// TRACE ENUMS
+// ============================================================================
+
+/// Rule for picking the primary model of a trace that used several models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PrimaryModelRule {
+    /// Model with the highest total token usage
+    #[default]
+    MostTokens,
+    /// Model with the highest total cost
+    MostCost,
+    /// Model of the earliest generation span
+    RootGeneration,
+}
+
+/// Sort presets for the trace list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceSort {
+    /// Newest traces first
+    #[default]
+    TimeDesc,
+    /// Most expensive traces first
+    CostDesc,
+    /// Cheapest traces first
+    CostAsc,
+    /// Traces with the most tokens first
+    TokensDesc,
+    /// Longest traces first
+    DurationDesc,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
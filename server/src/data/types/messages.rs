@@ -28,6 +28,8 @@ pub struct MessageSpanRow {
     pub tool_definitions_json: String,
     /// Tool names (JSON string)
     pub tool_names_json: String,
+    /// Extraction-time data-quality warnings (JSON array of `IngestWarning`)
+    pub ingest_warnings_json: String,
     /// Span metadata
     pub model: Option<String>,
     pub provider: Option<String>,
@@ -45,6 +47,8 @@ pub struct MessageSpanRow {
     pub session_id: Option<String>,
     /// Ingestion time for cursor-based pagination in feed API
     pub ingested_at: DateTime<Utc>,
+    /// Deployment environment (e.g., "dev", "prod") for environment-scoped dedup
+    pub environment: Option<String>,
 }
 
 impl SpanIdentity for MessageSpanRow {
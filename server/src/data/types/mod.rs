@@ -13,18 +13,21 @@ mod transactional;
 // Re-export enum types
 pub use enums::{
     AggregationTemporality, Framework, MessageCategory, MessageSourceType, MetricType,
-    ObservationType, SpanCategory,
+    ObservationType, PrimaryModelRule, SpanCategory, TraceSort,
 };
 
 // Re-export normalized types (for ingestion)
-pub use normalized::{NormalizedMetric, NormalizedSpan, json_to_pre_serialized};
+pub use normalized::{
+    IngestWarning, IngestWarningKind, NormalizedMetric, NormalizedSpan, json_to_pre_serialized,
+};
 
 // Re-export analytics types (query results and params)
 pub use analytics::{
     EventRow, FeedSpansParams, LinkRow, ListSessionsParams, ListSpansParams, ListTracesParams,
-    ObservationTokens, SessionRow, SpanCounts, SpanIdentity, SpanRow, TraceRow,
-    deduplicate_by_span_identity, filter_observations, find_root_span, get_observation_cost,
-    get_observation_tokens, get_observation_type, is_observation, parse_finish_reasons, parse_tags,
+    ObservationTokens, SessionRow, SpanCounts, SpanIdentity, SpanRow, TraceDeleteFilter,
+    TraceModelUsageRow, TraceModels, TraceRow, deduplicate_by_span_identity, filter_observations,
+    find_root_span, get_observation_cost, get_observation_tokens, get_observation_type,
+    is_observation, parse_finish_reasons, parse_tags, resolve_trace_models,
 };
 
 // Re-export message types
@@ -32,13 +35,14 @@ pub use messages::{FeedMessagesParams, MessageQueryParams, MessageQueryResult, M
 
 // Re-export stats types
 pub use stats::{
-    CostsResult, CountsResult, FrameworkBreakdown, LatencyBucket, ModelBreakdown,
-    ProjectStatsResult, StatsParams, TokensResult, TrendBucket,
+    CostsResult, CountsResult, FrameworkBreakdown, LatencyBucket, ModelBreakdown, OrgCostRollup,
+    ProjectCost, ProjectStatsResult, StatsParams, TimeseriesInterval, TimeseriesMetric,
+    TimeseriesParams, TimeseriesPoint, TokensResult, ToolStats, TrendBucket,
 };
 
 // Re-export transactional types (SQLite/PostgreSQL)
 pub use transactional::{
     ApiKeyRow, ApiKeyScope, ApiKeyValidation, AuthMethodRow, CredentialPermissionRow,
-    CredentialRow, FileRow, LastOwnerResult, MemberWithUser, MembershipRow, OrgWithRole,
-    OrganizationRow, ProjectRow, UserRow,
+    CredentialRow, FeatureFlag, FeatureFlags, FileRow, LastOwnerResult, MemberWithUser,
+    MembershipRow, OrgWithRole, OrganizationRow, ProjectRow, UserRow,
 };
@@ -4,6 +4,7 @@
 //! and enrichment, ready for storage in any analytics backend.
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use super::{AggregationTemporality, Framework, MetricType, ObservationType, SpanCategory};
@@ -17,6 +18,30 @@ pub fn json_to_pre_serialized(value: &JsonValue) -> Option<String> {
     }
 }
 
+// ============================================================================
+// INGEST WARNINGS
+// ============================================================================
+
+/// Kind of data-quality issue detected while extracting a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestWarningKind {
+    /// The span started beyond the future-skew tolerance and was clamped
+    FutureTimestamp,
+    /// A message attribute held malformed JSON, so its messages were dropped
+    ParseError,
+}
+
+/// A data-quality issue detected at ingestion.
+///
+/// Stored with the span so the feed can report it next to the warnings it
+/// finds at query time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestWarning {
+    pub kind: IngestWarningKind,
+    pub message: String,
+}
+
 // ============================================================================
 // NORMALIZED METRIC
 // ============================================================================
@@ -142,6 +167,8 @@ pub struct NormalizedSpan {
     pub gen_ai_request_model: Option<String>,
     pub gen_ai_response_model: Option<String>,
     pub gen_ai_response_id: Option<String>,
+    // Model string as reported, when an ingestion alias rewrote it
+    pub gen_ai_raw_model: Option<String>,
 
     // GenAI request parameters
     pub gen_ai_temperature: Option<f64>,
@@ -151,6 +178,10 @@ pub struct NormalizedSpan {
     pub gen_ai_frequency_penalty: Option<f64>,
     pub gen_ai_presence_penalty: Option<f64>,
     pub gen_ai_stop_sequences: Vec<String>,
+    pub gen_ai_reasoning_effort: Option<String>,
+    pub gen_ai_verbosity: Option<String>,
+    pub gen_ai_embedding_dimensions: Option<i64>,
+    pub gen_ai_encoding_format: Option<String>,
 
     // GenAI response
     pub gen_ai_finish_reasons: Vec<String>,
@@ -178,9 +209,13 @@ pub struct NormalizedSpan {
 
     // Reasoning token usage - defaults to 0, never NULL
     pub gen_ai_usage_reasoning_tokens: i64,
+    // Built-in web search calls, billed per call (not a token count)
+    pub gen_ai_usage_web_search_calls: i64,
 
     // Usage details (provider-specific overflow, pre-serialized JSON)
     pub gen_ai_usage_details: Option<String>,
+    // Child span whose usage was merged into this one
+    pub gen_ai_usage_merged_from: Option<String>,
 
     // Cost fields - defaults to 0.0, never NULL
     pub gen_ai_cost_input: f64,
@@ -188,7 +223,11 @@ pub struct NormalizedSpan {
     pub gen_ai_cost_cache_read: f64,
     pub gen_ai_cost_cache_write: f64,
     pub gen_ai_cost_reasoning: f64,
+    // Per-call surcharges for built-in tools (included in the total)
+    pub gen_ai_cost_tool: f64,
     pub gen_ai_cost_total: f64,
+    // Where the costs came from: "computed" or "reported" by the span
+    pub gen_ai_cost_source: Option<String>,
 
     // External services
     pub http_method: Option<String>,
@@ -230,7 +269,15 @@ pub struct NormalizedSpan {
     // Raw span JSON (includes attributes and resource.attributes, pre-serialized JSON)
     pub raw_span: Option<String>,
 
+    // Data-quality issues found at extraction (pre-serialized JSON array of IngestWarning)
+    // None means "[]" at write time
+    pub ingest_warnings: Option<String>,
+
     // Ingestion time (server time when span was received, for feed cursor)
     // Note: Not used in insert - populated by DB default (now())
     pub ingested_at: Option<DateTime<Utc>>,
+
+    // Retention expiry (stamped at ingestion from otel.retention.max_age_minutes)
+    // None means the span never expires by TTL
+    pub expires_at: Option<DateTime<Utc>>,
 }
@@ -2,7 +2,9 @@
 //!
 //! This module contains stats query result types and parameters.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Result types
@@ -78,6 +80,17 @@ pub struct LatencyBucket {
     pub avg_duration_ms: f64,
 }
 
+/// Call outcomes of one tool, aggregated from its tool spans
+#[derive(Debug)]
+pub struct ToolStats {
+    pub tool_name: String,
+    pub call_count: i64,
+    pub error_count: i64,
+    /// Percentage of calls with ERROR status
+    pub error_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+}
+
 // ============================================================================
 // Query parameters
 // ============================================================================
@@ -92,10 +105,232 @@ pub struct StatsParams {
     pub timezone: Option<String>,
 }
 
+// ============================================================================
+// Timeseries
+// ============================================================================
+
+/// Aggregate charted by the timeseries endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeseriesMetric {
+    /// Total cost (USD) of deduplicated generation spans
+    #[default]
+    Cost,
+    /// Total tokens of deduplicated generation spans
+    Tokens,
+    /// Number of spans
+    Spans,
+    /// Number of spans with ERROR status
+    Errors,
+}
+
+/// Bucket width for the timeseries endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeseriesInterval {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl TimeseriesInterval {
+    /// Nominal bucket width (days may be 23 or 25 hours across DST changes)
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Minute => Duration::minutes(1),
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+        }
+    }
+
+    /// Contiguous `[start, end)` buckets covering `from..=to`, aligned to
+    /// minute/hour/day boundaries in `tz`.
+    ///
+    /// Day buckets follow local midnights, so they stay aligned across DST.
+    pub fn buckets(
+        self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        tz: Tz,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let local_from = from.with_timezone(&tz);
+        let start = match self {
+            Self::Minute => {
+                local_from
+                    .date_naive()
+                    .and_hms_opt(local_from.hour(), local_from.minute(), 0)
+            }
+            Self::Hour => local_from.date_naive().and_hms_opt(local_from.hour(), 0, 0),
+            Self::Day => Some(local_from.date_naive().and_time(NaiveTime::MIN)),
+        };
+        let Some(start) = start else {
+            return Vec::new();
+        };
+
+        let mut buckets = Vec::new();
+        let mut current = match resolve_local(tz, start) {
+            Some(dt) => dt,
+            None => return buckets,
+        };
+        while current <= to {
+            let next = match self {
+                Self::Day => {
+                    let next_day = current.with_timezone(&tz).date_naive() + Duration::days(1);
+                    match resolve_local(tz, next_day.and_time(NaiveTime::MIN)) {
+                        Some(dt) => dt,
+                        None => break,
+                    }
+                }
+                _ => current + self.duration(),
+            };
+            buckets.push((current, next));
+            current = next;
+        }
+        buckets
+    }
+}
+
+/// Map a local wall-clock time to UTC, taking the earliest instant for
+/// ambiguous times and skipping forward over DST gaps.
+fn resolve_local(tz: Tz, local: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&local) {
+        chrono::LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        chrono::LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        chrono::LocalResult::None => match tz.from_local_datetime(&(local + Duration::hours(1))) {
+            chrono::LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+            chrono::LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+            chrono::LocalResult::None => None,
+        },
+    }
+}
+
+/// Parameters for a bucketed timeseries query
+#[derive(Debug, Clone)]
+pub struct TimeseriesParams {
+    pub project_id: String,
+    pub from_timestamp: DateTime<Utc>,
+    pub to_timestamp: DateTime<Utc>,
+    /// IANA timezone (e.g., "America/New_York") for bucketing. Defaults to UTC.
+    pub timezone: Option<String>,
+    pub interval: TimeseriesInterval,
+    pub metric: TimeseriesMetric,
+}
+
+impl TimeseriesParams {
+    /// Bucket ranges for this query (timezone falls back to UTC when invalid)
+    pub fn buckets(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let tz = self
+            .timezone
+            .as_deref()
+            .and_then(|s| s.parse::<Tz>().ok())
+            .unwrap_or(chrono_tz::UTC);
+        self.interval
+            .buckets(self.from_timestamp, self.to_timestamp, tz)
+    }
+}
+
+/// One zero-filled bucket of a timeseries
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeseriesPoint {
+    pub bucket: DateTime<Utc>,
+    pub value: f64,
+}
+
+// ============================================================================
+// Organization cost rollup
+// ============================================================================
+
+/// Spend of a single project within an organization rollup
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectCost {
+    pub project_id: String,
+    pub cost: f64,
+}
+
+/// Organization-wide spend for a window, compared against the org cap.
+///
+/// Projects are listed individually so project-level limits can be checked
+/// on their own figures; the org cap only applies to the sum.
+#[derive(Debug, Clone)]
+pub struct OrgCostRollup {
+    pub monthly_limit_usd: Option<f64>,
+    pub total_cost: f64,
+    /// Budget left under the cap (never negative; None without a cap)
+    pub remaining_usd: Option<f64>,
+    pub exceeded: bool,
+    pub projects: Vec<ProjectCost>,
+}
+
+impl OrgCostRollup {
+    /// Sum project costs and compare the total to `monthly_limit_usd`
+    pub fn new(monthly_limit_usd: Option<f64>, mut projects: Vec<ProjectCost>) -> Self {
+        let total_cost: f64 = projects.iter().map(|p| p.cost).sum();
+        projects.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        Self {
+            monthly_limit_usd,
+            total_cost,
+            remaining_usd: monthly_limit_usd.map(|limit| (limit - total_cost).max(0.0)),
+            exceeded: monthly_limit_usd.is_some_and(|limit| total_cost > limit),
+            projects,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_org_cost_rollup_sums_projects_against_cap() {
+        let projects = vec![
+            ProjectCost {
+                project_id: "p1".to_string(),
+                cost: 12.5,
+            },
+            ProjectCost {
+                project_id: "p2".to_string(),
+                cost: 30.0,
+            },
+        ];
+
+        let rollup = OrgCostRollup::new(Some(100.0), projects.clone());
+        assert_eq!(rollup.total_cost, 42.5);
+        assert_eq!(rollup.remaining_usd, Some(57.5));
+        assert!(!rollup.exceeded);
+        // Highest spend first, each project keeps its own figure
+        assert_eq!(rollup.projects[0].project_id, "p2");
+        assert_eq!(rollup.projects[1].cost, 12.5);
+
+        let over = OrgCostRollup::new(Some(40.0), projects.clone());
+        assert_eq!(over.remaining_usd, Some(0.0));
+        assert!(over.exceeded);
+
+        let uncapped = OrgCostRollup::new(None, projects);
+        assert_eq!(uncapped.remaining_usd, None);
+        assert!(!uncapped.exceeded);
+    }
+
+    #[test]
+    fn test_timeseries_day_buckets_follow_local_midnight() {
+        // DST ends in New York on 2024-11-03: that local day is 25 hours long
+        let from = DateTime::parse_from_rfc3339("2024-11-02T12:00:00-04:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let to = DateTime::parse_from_rfc3339("2024-11-04T12:00:00-05:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let buckets = TimeseriesInterval::Day.buckets(from, to, chrono_tz::America::New_York);
+
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].0.to_rfc3339(), "2024-11-02T04:00:00+00:00");
+        assert_eq!(buckets[1].0.to_rfc3339(), "2024-11-03T04:00:00+00:00");
+        assert_eq!(buckets[1].1 - buckets[1].0, Duration::hours(25));
+        assert_eq!(buckets[2].0.to_rfc3339(), "2024-11-04T05:00:00+00:00");
+        // Contiguous: each bucket ends where the next starts
+        assert_eq!(buckets[0].1, buckets[1].0);
+    }
+
     #[test]
     fn test_stats_params() {
         let params = StatsParams {
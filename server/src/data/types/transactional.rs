@@ -32,6 +32,15 @@ pub struct OrganizationRow {
     pub slug: String,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Org-wide monthly spend cap in USD (None = no cap)
+    #[serde(default)]
+    pub monthly_limit_usd: Option<f64>,
+    /// Project that receives traces arriving without a project id (None = built-in default)
+    #[serde(default)]
+    pub default_project_id: Option<String>,
+    /// Days new spans are kept before expiring (None = global retention)
+    #[serde(default)]
+    pub retention_days: Option<i64>,
 }
 
 /// Organization with user's role (for list_for_user)
@@ -252,6 +261,77 @@ pub struct CredentialPermissionRow {
     pub updated_at: i64,
 }
 
+// ============================================================================
+// Feature flag types
+// ============================================================================
+
+/// Per-organization feature flag (stored by name in `org_feature_flags`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    /// Always anonymize message content returned by the feed and messages APIs
+    AnonymizeContent,
+}
+
+impl FeatureFlag {
+    /// All known flags
+    pub const ALL: &'static [FeatureFlag] = &[FeatureFlag::AnonymizeContent];
+
+    /// Parse from string representation
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "anonymize_content" => Some(Self::AnonymizeContent),
+            _ => None,
+        }
+    }
+
+    /// Convert to string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AnonymizeContent => "anonymize_content",
+        }
+    }
+}
+
+impl fmt::Display for FeatureFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Resolved feature flags for an organization. Every flag defaults to off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FeatureFlags {
+    pub anonymize_content: bool,
+}
+
+impl FeatureFlags {
+    /// Check whether a flag is enabled
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        match flag {
+            FeatureFlag::AnonymizeContent => self.anonymize_content,
+        }
+    }
+
+    /// Set a flag
+    pub fn set(&mut self, flag: FeatureFlag, enabled: bool) {
+        match flag {
+            FeatureFlag::AnonymizeContent => self.anonymize_content = enabled,
+        }
+    }
+
+    /// Build from stored `(flag, enabled)` rows, ignoring unknown flag names
+    pub fn from_rows(rows: impl IntoIterator<Item = (String, bool)>) -> Self {
+        let mut flags = Self::default();
+        for (name, enabled) in rows {
+            if let Some(flag) = FeatureFlag::parse(&name) {
+                flags.set(flag, enabled);
+            }
+        }
+        flags
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,7 +12,7 @@ pub mod sideml;
 pub mod traces;
 
 pub use metrics::MetricsPipeline;
-pub use traces::{MessageSource, RawMessage, SseSpanEvent, TracePipeline};
+pub use traces::{MessageSource, NotificationBridge, RawMessage, SseSpanEvent, TracePipeline};
 
 use crate::core::TopicMessage;
 use opentelemetry_proto::tonic::collector::{
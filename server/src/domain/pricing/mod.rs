@@ -5,21 +5,40 @@
 //! - Multi-strategy model lookup (exact → provider-prefixed → alias → family)
 //! - Provider-aware normalization (20+ gen_ai.system mappings)
 //! - Background sync from GitHub with atomic updates
+//! - Scheduled syncs run on one instance at a time (cache-backed lease)
+//! - Cross-instance `pricing_updated` broadcast after each applied sync
 //! - Thread-safe with read-heavy optimized locking
+//! - Optional token estimation for spans without reported usage (`estimate`)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 
-use parking_lot::RwLock;
-use serde::Serialize;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use utoipa::ToSchema;
 
+use crate::core::config::PricingConfig;
+use crate::core::constants::{
+    DEFAULT_CACHED_INPUT_PROVIDERS, DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO,
+    DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO, DEFAULT_UNMATCHED_MODELS_LIMIT,
+    PRICING_SYNC_BACKOFF_THRESHOLD, PRICING_SYNC_MAX_BACKOFF_SECS, TOPIC_PRICING_UPDATED,
+    UNMATCHED_MODELS_FLUSH_SECS,
+};
 use crate::core::storage::AppStorage;
+use crate::core::{TopicMessage, TopicService};
+use crate::data::cache::{CacheKey, CacheService};
+use crate::data::topics::TopicError;
+
+pub mod estimate;
+mod unmatched;
+
+pub use unmatched::{UnmatchedModel, UnmatchedModels};
 
 // ============================================================================
 // CONSTANTS
@@ -32,6 +51,9 @@ const EMBEDDED_PRICING_JSON: &str =
 /// Pricing file name in data directory
 const PRICING_FILE_NAME: &str = "model_prices.json";
 
+/// Persisted unmatched models file name in data directory
+const UNMATCHED_MODELS_FILE_NAME: &str = "unmatched_models.json";
+
 /// GitHub raw URL for LiteLLM pricing data
 const PRICING_SYNC_URL: &str =
     "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
@@ -73,10 +95,214 @@ pub struct ModelPricing {
     /// Reasoning tokens cost (o1, Claude thinking)
     pub output_cost_per_reasoning_token: f64,
 
+    /// Surcharge per built-in web search call (OpenAI, Anthropic)
+    pub web_search_cost_per_query: f64,
+
+    /// Embedding input rates for outputs above a dimension count, ascending
+    /// by threshold (`input_cost_per_token_above_{N}_dimensions`)
+    pub input_cost_per_token_by_dimensions: Vec<(u64, f64)>,
+
     /// LiteLLM provider name
     pub litellm_provider: String,
     /// Mode: "chat", "embedding", "completion", etc.
     pub mode: String,
+    /// ISO currency code of the rates above (None is USD)
+    pub currency: Option<String>,
+
+    /// Context limits and feature support (informational, not used for cost)
+    pub capabilities: ModelCapabilities,
+}
+
+/// Model capability metadata from LiteLLM entries
+///
+/// All fields are optional: LiteLLM omits most of them for many models, and
+/// a missing field means "unknown", not "unsupported".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ModelCapabilities {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_input_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_vision: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_function_calling: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_pdf_input: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_reasoning: Option<bool>,
+}
+
+impl ModelCapabilities {
+    /// Parse capability fields leniently (wrong types are treated as missing)
+    fn from_entry(entry: &serde_json::Map<String, serde_json::Value>) -> Self {
+        let limit = |key: &str| {
+            entry.get(key).and_then(|v| {
+                v.as_u64()
+                    .or_else(|| v.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64))
+            })
+        };
+        let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool());
+        Self {
+            max_input_tokens: limit("max_input_tokens"),
+            // Older entries only carry the ambiguous `max_tokens`
+            max_output_tokens: limit("max_output_tokens").or_else(|| limit("max_tokens")),
+            supports_vision: flag("supports_vision"),
+            supports_function_calling: flag("supports_function_calling"),
+            supports_pdf_input: flag("supports_pdf_input"),
+            supports_reasoning: flag("supports_reasoning"),
+        }
+    }
+}
+
+/// Per-query web search surcharge from a LiteLLM entry.
+///
+/// LiteLLM prices search by context size (`search_context_size_low/medium/high`);
+/// spans do not report the size, so the provider default (medium) is used.
+/// A plain number is accepted as a flat per-query rate.
+fn web_search_cost(entry: &serde_json::Map<String, serde_json::Value>) -> f64 {
+    entry
+        .get("search_context_cost_per_query")
+        .and_then(|v| {
+            v.as_f64()
+                .or_else(|| v.get("search_context_size_medium").and_then(|m| m.as_f64()))
+        })
+        .unwrap_or(0.0)
+        .max(0.0)
+}
+
+/// Factor between two rates in either direction.
+///
+/// 1.0 when both are zero; infinite when only one is, as a rate appearing or
+/// disappearing is as suspicious as any jump.
+fn rate_change_ratio(old: f64, new: f64) -> f64 {
+    match (old > 0.0, new > 0.0) {
+        (true, true) => (new / old).max(old / new),
+        (false, false) => 1.0,
+        _ => f64::INFINITY,
+    }
+}
+
+/// Rates compared by the sync guard: input, output, cache read, cache write
+fn guarded_rates(pricing: &ModelPricing) -> [f64; 4] {
+    [
+        pricing.input_cost_per_token,
+        pricing.output_cost_per_token,
+        pricing.cache_read_input_token_cost,
+        pricing.cache_creation_input_token_cost,
+    ]
+}
+
+/// Pricing entry fields holding the rates of `guarded_rates`, in the same order
+const GUARDED_RATE_FIELDS: [&str; 4] = [
+    "input_cost_per_token",
+    "output_cost_per_token",
+    "cache_read_input_token_cost",
+    "cache_creation_input_token_cost",
+];
+
+/// A synced model price held back because it changed too much at once
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct HeldRateChange {
+    /// Pricing key of the model
+    pub model: String,
+    /// Rates still in use
+    pub current_input_cost_per_token: f64,
+    pub current_output_cost_per_token: f64,
+    pub current_cache_read_input_token_cost: f64,
+    pub current_cache_creation_input_token_cost: f64,
+    /// Rates from the synced data that were not applied
+    pub synced_input_cost_per_token: f64,
+    pub synced_output_cost_per_token: f64,
+    pub synced_cache_read_input_token_cost: f64,
+    pub synced_cache_creation_input_token_cost: f64,
+    /// Largest change factor across the compared rates; null when a rate moved
+    /// between zero and non-zero
+    pub ratio: Option<f64>,
+}
+
+impl HeldRateChange {
+    fn new(model: &str, current: &ModelPricing, synced: &ModelPricing, ratio: f64) -> Self {
+        Self {
+            model: model.to_string(),
+            current_input_cost_per_token: current.input_cost_per_token,
+            current_output_cost_per_token: current.output_cost_per_token,
+            current_cache_read_input_token_cost: current.cache_read_input_token_cost,
+            current_cache_creation_input_token_cost: current.cache_creation_input_token_cost,
+            synced_input_cost_per_token: synced.input_cost_per_token,
+            synced_output_cost_per_token: synced.output_cost_per_token,
+            synced_cache_read_input_token_cost: synced.cache_read_input_token_cost,
+            synced_cache_creation_input_token_cost: synced.cache_creation_input_token_cost,
+            ratio: ratio.is_finite().then_some(ratio),
+        }
+    }
+
+    /// Current rates in `guarded_rates` order
+    fn current_rates(&self) -> [f64; 4] {
+        [
+            self.current_input_cost_per_token,
+            self.current_output_cost_per_token,
+            self.current_cache_read_input_token_cost,
+            self.current_cache_creation_input_token_cost,
+        ]
+    }
+}
+
+/// Write the current rates of held models back into synced pricing JSON.
+///
+/// The result is what gets saved to disk, so a restart keeps holding the
+/// flagged models while every other model takes the synced data. Returns
+/// `None` if the JSON is not an object.
+fn restore_held_rates(json: &str, held: &[HeldRateChange]) -> Option<String> {
+    let mut raw: serde_json::Value = serde_json::from_str(json).ok()?;
+    let obj = raw.as_object_mut()?;
+    let held: HashMap<&str, &HeldRateChange> = held.iter().map(|h| (h.model.as_str(), h)).collect();
+    for (key, entry) in obj.iter_mut() {
+        let (Some(change), Some(entry)) =
+            (held.get(key.to_lowercase().as_str()), entry.as_object_mut())
+        else {
+            continue;
+        };
+        for (field, rate) in GUARDED_RATE_FIELDS.iter().zip(change.current_rates()) {
+            entry.insert(field.to_string(), serde_json::json!(rate));
+        }
+    }
+    serde_json::to_string(&raw).ok()
+}
+
+/// Dimension-tiered embedding input rates from a LiteLLM-style entry.
+///
+/// Keys follow LiteLLM's `_above_` tier naming, e.g.
+/// `input_cost_per_token_above_1024_dimensions`. Sorted by threshold.
+fn dimension_tiers(entry: &serde_json::Map<String, serde_json::Value>) -> Vec<(u64, f64)> {
+    let mut tiers: Vec<(u64, f64)> = entry
+        .iter()
+        .filter_map(|(key, value)| {
+            let threshold = key
+                .strip_prefix("input_cost_per_token_above_")?
+                .strip_suffix("_dimensions")?
+                .parse()
+                .ok()?;
+            Some((threshold, value.as_f64().filter(|rate| *rate >= 0.0)?))
+        })
+        .collect();
+    tiers.sort_by_key(|(threshold, _)| *threshold);
+    tiers
+}
+
+impl ModelPricing {
+    /// Input rate for an embedding with the given output dimensions: the rate of
+    /// the highest tier the dimensions exceed, else the base rate.
+    pub fn input_rate_for_dimensions(&self, dimensions: Option<i64>) -> f64 {
+        let Some(dimensions) = dimensions.filter(|&d| d > 0) else {
+            return self.input_cost_per_token;
+        };
+        self.input_cost_per_token_by_dimensions
+            .iter()
+            .rev()
+            .find(|(threshold, _)| dimensions as u64 > *threshold)
+            .map_or(self.input_cost_per_token, |(_, rate)| *rate)
+    }
 }
 
 /// Match type for cost confidence scoring
@@ -90,6 +316,8 @@ pub enum MatchType {
     Exact,
     /// Matched via provider prefix, e.g., "azure/gpt-4o" (confidence: 95%)
     ProviderPrefix,
+    /// Azure deployment name mapped to a model by org config (confidence: 95%)
+    Deployment,
     /// Matched via alias, e.g., "-latest" suffix stripped (confidence: 85%)
     Alias,
     /// Matched base model family, e.g., date stripped (confidence: 70%)
@@ -104,7 +332,7 @@ impl MatchType {
     pub fn confidence(self) -> f64 {
         match self {
             MatchType::Exact => 1.0,
-            MatchType::ProviderPrefix => 0.95,
+            MatchType::ProviderPrefix | MatchType::Deployment => 0.95,
             MatchType::Alias => 0.85,
             MatchType::Family => 0.70,
             MatchType::NotFound => 0.0,
@@ -112,6 +340,48 @@ impl MatchType {
     }
 }
 
+/// A model name normalization tried during pricing lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationStep {
+    /// Model name as reported, lowercased
+    Lowercase,
+    /// Bedrock cross-region prefix removed (`us.`, `global.`, ...)
+    BedrockRegionStrip,
+    /// Model name taken from a Vertex AI resource path
+    VertexResourceExtract,
+    /// Base model of a fine-tuned model ID
+    FinetuneBase,
+    /// `-latest`, routing, `@date` and Bedrock `-v1:0` suffixes removed
+    NormalizeModelName,
+    /// Trailing release date removed (family form)
+    StripDateSuffix,
+}
+
+/// Model name produced by one normalization step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct NormalizedForm {
+    pub step: NormalizationStep,
+    /// Name after the step (absent when the step does not apply or changes nothing)
+    pub form: Option<String>,
+    /// Pricing key the form matched, bare or provider-prefixed
+    pub matched_key: Option<String>,
+}
+
+/// Step-by-step view of how a model name is normalized for pricing lookup
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ModelNormalization {
+    pub model: String,
+    pub system: Option<String>,
+    /// LiteLLM provider the system maps to (absent for framework-only systems)
+    pub provider: Option<String>,
+    /// Steps in lookup order
+    pub steps: Vec<NormalizedForm>,
+    /// Result of the full lookup, which also tries provider indexes and
+    /// aliases not listed as steps
+    pub match_type: MatchType,
+}
+
 // ============================================================================
 // PRICING DATA
 // ============================================================================
@@ -129,6 +399,9 @@ pub struct PricingData {
 
     /// Model count for logging and comparison
     pub model_count: usize,
+
+    /// Content hash of the source JSON, identifies the data version across instances
+    pub version: String,
 }
 
 impl PricingData {
@@ -218,6 +491,8 @@ impl PricingData {
                     .and_then(|v| v.as_f64())
                     .unwrap_or(0.0)
                     .max(0.0),
+                web_search_cost_per_query: web_search_cost(entry),
+                input_cost_per_token_by_dimensions: dimension_tiers(entry),
                 litellm_provider: entry
                     .get("litellm_provider")
                     .and_then(|v| v.as_str())
@@ -228,6 +503,12 @@ impl PricingData {
                     .and_then(|v| v.as_str())
                     .unwrap_or("chat")
                     .to_string(),
+                currency: entry
+                    .get("currency")
+                    .and_then(|v| v.as_str())
+                    .map(|c| c.trim().to_ascii_uppercase())
+                    .filter(|c| !c.is_empty() && c != "USD"),
+                capabilities: ModelCapabilities::from_entry(entry),
             };
 
             let key_lower = key.to_lowercase();
@@ -254,14 +535,142 @@ impl PricingData {
             models,
             provider_models,
             model_count,
+            version: blake3::hash(json.as_bytes()).to_hex().to_string(),
         })
     }
 
+    /// Keep `current` rates for models whose price moved by more than `max_ratio`.
+    ///
+    /// Compares the input, output, cache read and cache write rates of models
+    /// present in both datasets; a rate moving between zero and non-zero always
+    /// counts as too large. Flagged models get their current rates back, and
+    /// every other field and model keeps the new data. Models in `accepted`
+    /// are never held. Returns the held changes sorted by model.
+    fn hold_rate_spikes(
+        &mut self,
+        current: &PricingData,
+        max_ratio: f64,
+        accepted: &HashSet<String>,
+    ) -> Vec<HeldRateChange> {
+        let mut held = Vec::new();
+        for (key, pricing) in self.models.iter_mut() {
+            let Some(old) = current.models.get(key) else {
+                continue;
+            };
+            if accepted.contains(key) {
+                continue;
+            }
+            let ratio = guarded_rates(old)
+                .into_iter()
+                .zip(guarded_rates(pricing))
+                .map(|(old, new)| rate_change_ratio(old, new))
+                .fold(1.0, f64::max);
+            if ratio > max_ratio {
+                held.push(HeldRateChange::new(key, old, pricing, ratio));
+                pricing.input_cost_per_token = old.input_cost_per_token;
+                pricing.output_cost_per_token = old.output_cost_per_token;
+                pricing.cache_read_input_token_cost = old.cache_read_input_token_cost;
+                pricing.cache_creation_input_token_cost = old.cache_creation_input_token_cost;
+            }
+        }
+        held.sort_by(|a, b| a.model.cmp(&b.model));
+        held
+    }
+
+    /// Pricing for a model family across every provider that serves it
+    ///
+    /// Matches entries whose key reduces to the same family (see `model_family`),
+    /// keeping one per LiteLLM provider: the shortest key, which is usually the
+    /// base model without regional or versioned variants. Sorted by provider.
+    pub fn family_pricing(&self, family: &str) -> Vec<(&str, &ModelPricing)> {
+        let family = model_family(&family.to_lowercase());
+        if family.is_empty() {
+            return Vec::new();
+        }
+
+        let mut best: HashMap<&str, (&str, &ModelPricing)> = HashMap::new();
+        for (key, pricing) in &self.models {
+            if model_family(key) != family {
+                continue;
+            }
+            let slot = best
+                .entry(pricing.litellm_provider.as_str())
+                .or_insert((key.as_str(), pricing));
+            if (key.len(), key.as_str()) < (slot.0.len(), slot.0) {
+                *slot = (key.as_str(), pricing);
+            }
+        }
+
+        let mut result: Vec<_> = best.into_values().collect();
+        result.sort_by(|a, b| a.1.litellm_provider.cmp(&b.1.litellm_provider));
+        result
+    }
+
+    /// Intermediate forms `lookup` derives from a model name, for diagnosing misses
+    pub fn normalization(&self, system: Option<&str>, model: &str) -> ModelNormalization {
+        let model_lower = model.to_lowercase();
+        let provider = system
+            .map(map_system_to_litellm_provider)
+            .filter(|p| !p.is_empty());
+        let matched_key = |form: &str| -> Option<String> {
+            if self.models.contains_key(form) {
+                return Some(form.to_string());
+            }
+            let prefixed = format!("{}/{}", provider?, form);
+            self.models.contains_key(&prefixed).then_some(prefixed)
+        };
+
+        let normalized = normalize_model_name(&model_lower);
+        let base = strip_date_suffix(&model_lower);
+        let forms = [
+            (NormalizationStep::Lowercase, Some(model_lower.clone())),
+            (
+                NormalizationStep::BedrockRegionStrip,
+                strip_bedrock_region_prefix(&model_lower).map(str::to_string),
+            ),
+            (
+                NormalizationStep::VertexResourceExtract,
+                extract_vertex_resource_model(&model_lower).map(str::to_string),
+            ),
+            (
+                NormalizationStep::FinetuneBase,
+                extract_finetune_base_model(&model_lower).map(str::to_string),
+            ),
+            (
+                NormalizationStep::NormalizeModelName,
+                (normalized != model_lower).then(|| normalized.to_string()),
+            ),
+            (
+                NormalizationStep::StripDateSuffix,
+                (base != model_lower).then_some(base.clone()),
+            ),
+        ];
+        let steps = forms
+            .into_iter()
+            .map(|(step, form)| NormalizedForm {
+                step,
+                matched_key: form.as_deref().and_then(matched_key),
+                form,
+            })
+            .collect();
+
+        ModelNormalization {
+            model: model.to_string(),
+            system: system.map(str::to_string),
+            provider: provider.map(str::to_string),
+            steps,
+            match_type: self
+                .lookup(system, model)
+                .map_or(MatchType::NotFound, |(_, match_type)| match_type),
+        }
+    }
+
     /// Look up pricing for a model with multi-strategy fallback
     ///
     /// Lookup order:
     /// 1. Exact match on model name
     ///    - 1b. Strip Bedrock regional prefix (global., us., eu., etc.) and retry
+    ///    - 1b1. Vertex AI MaaS partner forms (publisher prefix, `-maas` suffix)
     ///    - 1c. Extract fine-tuned base model (ft:gpt-3.5-turbo:org::id → gpt-3.5-turbo)
     /// 2. Provider-prefixed match (e.g., "azure/gpt-4o")
     /// 3. Provider + model via index
@@ -286,6 +695,22 @@ impl PricingData {
             return Some((pricing, MatchType::Exact));
         }
 
+        // Strategy 1b1: Vertex AI MaaS partner models (before the generic slash strip,
+        // which would fall through to the partner's own API pricing)
+        // Handles "meta/llama-3.1-405b-instruct" → "vertex_ai/meta/llama-3.1-405b-instruct-maas"
+        if provider == Some("vertex_ai") || model_lower.ends_with("-maas") {
+            for candidate in vertex_maas_candidates(&model_lower) {
+                if let Some(pricing) = self.models.get(&candidate) {
+                    let match_type = if candidate.starts_with("vertex_ai/") {
+                        MatchType::ProviderPrefix
+                    } else {
+                        MatchType::Alias
+                    };
+                    return Some((pricing, match_type));
+                }
+            }
+        }
+
         // Strategy 1b2: Strip LiteLLM slash prefix (e.g. "bedrock/model" → "model")
         if let Some((_, model_part)) = model_lower.split_once('/')
             && !model_part.is_empty()
@@ -632,6 +1057,42 @@ fn strip_bedrock_region_prefix(model: &str) -> Option<&str> {
     None
 }
 
+/// Bedrock model vendors, the `vendor.` part of Bedrock model IDs
+const BEDROCK_VENDORS: &[&str] = &[
+    "anthropic.",
+    "amazon.",
+    "meta.",
+    "mistral.",
+    "cohere.",
+    "ai21.",
+    "deepseek.",
+    "qwen.",
+    "openai.",
+    "writer.",
+];
+
+/// Reduce a pricing key or model ID to its provider-neutral family name
+///
+/// Examples:
+/// - `vertex_ai/claude-sonnet-4-5@20250929` → `claude-sonnet-4-5`
+/// - `us.anthropic.claude-sonnet-4-5-20250929-v1:0` → `claude-sonnet-4-5`
+/// - `bedrock/us-gov-east-1/claude-sonnet-4-5-20250929-v1:0` → `claude-sonnet-4-5`
+fn model_family(model: &str) -> String {
+    let name = model.rsplit('/').next().unwrap_or(model);
+
+    // Drop Bedrock region and vendor prefixes (us.anthropic., jp.anthropic., ...)
+    let name = BEDROCK_VENDORS
+        .iter()
+        .find_map(|vendor| {
+            name.match_indices(vendor)
+                .find(|(i, _)| *i == 0 || name[..*i].ends_with('.'))
+                .map(|(i, _)| &name[i + vendor.len()..])
+        })
+        .unwrap_or(name);
+
+    strip_date_suffix(normalize_model_name(name))
+}
+
 /// Strip date suffixes from model names (last resort fallback only)
 ///
 /// Examples:
@@ -708,6 +1169,52 @@ fn extract_vertex_resource_model(model: &str) -> Option<&str> {
     None
 }
 
+/// Publishers serving partner models through Vertex AI Model-as-a-Service
+const VERTEX_MAAS_PUBLISHERS: &[&str] = &[
+    "meta",
+    "mistralai",
+    "deepseek-ai",
+    "qwen",
+    "openai",
+    "zai-org",
+    "moonshotai",
+    "minimaxai",
+];
+
+/// Candidate pricing keys for Vertex AI Model-as-a-Service partner models
+///
+/// SDKs report MaaS models with or without the publisher prefix and `-maas`
+/// suffix, while LiteLLM keys mix both styles:
+/// - `meta/llama-3.1-405b-instruct-maas` → `vertex_ai/meta/llama-3.1-405b-instruct-maas`
+/// - `llama-3.1-405b-instruct` → `vertex_ai/meta/llama-3.1-405b-instruct-maas`
+/// - `mistralai/mistral-large@2407` → `vertex_ai/mistral-large@2407`
+///
+/// Without a publisher every known MaaS publisher is tried. Resource paths
+/// (more than one slash) are handled by `extract_vertex_resource_model`.
+fn vertex_maas_candidates(model: &str) -> Vec<String> {
+    let model = model.strip_prefix("vertex_ai/").unwrap_or(model);
+    let (publisher, name) = match model.split_once('/') {
+        Some((_, name)) if name.contains('/') => return Vec::new(),
+        Some((publisher, name)) => (Some(publisher), name),
+        None => (None, model),
+    };
+    let base = name.strip_suffix("-maas").unwrap_or(name);
+    if base.is_empty() {
+        return Vec::new();
+    }
+
+    let publishers = publisher.map_or(VERTEX_MAAS_PUBLISHERS.to_vec(), |p| vec![p]);
+    let mut candidates = Vec::with_capacity(publishers.len() * 2 + 3);
+    for publisher in publishers {
+        candidates.push(format!("vertex_ai/{}/{}-maas", publisher, base));
+        candidates.push(format!("vertex_ai/{}/{}", publisher, base));
+    }
+    candidates.push(format!("vertex_ai/{}-maas", base));
+    candidates.push(format!("vertex_ai/{}", base));
+    candidates.push(base.to_string());
+    candidates
+}
+
 /// Strip Replicate version hash from model IDs
 ///
 /// Replicate uses versioned model references with SHA hashes:
@@ -751,6 +1258,14 @@ pub struct SpanCostInput {
     pub cache_read_tokens: i64,
     pub cache_write_tokens: i64,
     pub reasoning_tokens: i64,
+    /// Built-in web search calls billed per query on top of tokens
+    pub web_search_calls: i64,
+    /// Embedding output dimensions, for dimension-tiered embedding pricing
+    pub embedding_dimensions: Option<i64>,
+    /// Token counts were estimated from message text, not reported by the span
+    pub estimated: bool,
+    /// Organization owning the span, for org-specific deployment mappings
+    pub org_id: Option<String>,
 }
 
 /// Calculated costs for a span - always returns values (0.0 if no pricing data)
@@ -761,10 +1276,17 @@ pub struct SpanCostOutput {
     pub cache_read_cost: f64,
     pub cache_write_cost: f64,
     pub reasoning_cost: f64,
+    /// Per-call surcharges for built-in tools (web search)
+    pub tool_cost: f64,
     pub total_cost: f64,
+    /// What the cache-read tokens would have cost at the input rate, minus
+    /// what they cost at the cache read rate (not part of `total_cost`)
+    pub cache_savings: f64,
 
     /// Confidence scoring: indicates how the model was matched
     pub match_type: Option<MatchType>,
+    /// Costs are based on estimated (not reported) token counts
+    pub estimated: bool,
 }
 
 impl SpanCostOutput {
@@ -779,6 +1301,28 @@ impl SpanCostOutput {
     }
 }
 
+// ============================================================================
+// PRICING SYNC EVENTS
+// ============================================================================
+
+/// Broadcast after an instance applies newly synced pricing data.
+///
+/// Other instances compare `version` with their own data and re-sync on
+/// mismatch, so handling the same event twice is harmless.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingUpdated {
+    /// Number of priced models in the new data
+    pub model_count: usize,
+    /// Content hash of the new data
+    pub version: String,
+}
+
+impl TopicMessage for PricingUpdated {
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<usize>() + self.version.len()
+    }
+}
+
 // ============================================================================
 // PRICING SERVICE
 // ============================================================================
@@ -793,21 +1337,86 @@ pub struct PricingService {
 
     /// Reusable HTTP client for sync
     http_client: reqwest::Client,
+
+    /// Topic service for `pricing_updated` events (None in tests and fallbacks)
+    topics: Option<Arc<TopicService>>,
+
+    /// Per-model rate change factor above which synced prices are held (<= 1 disables)
+    max_rate_change_ratio: f64,
+
+    /// Price changes from the last sync that were held for review
+    held_rate_changes: RwLock<Vec<HeldRateChange>>,
+
+    /// Pricing keys (lowercase) whose synced rates bypass the change guard
+    accepted_rate_changes: HashSet<String>,
+
+    /// LiteLLM providers (lowercase) whose input token counts already include cache reads
+    cached_input_providers: Vec<String>,
+
+    /// Per-org Azure deployment name -> model key mappings
+    deployment_models: HashMap<String, HashMap<String, String>>,
+
+    /// Models with no pricing match, for coverage reports
+    unmatched: UnmatchedModels,
+
+    /// Where unmatched models are persisted (None keeps them in memory only)
+    unmatched_path: Option<PathBuf>,
+
+    /// Consecutive failed GitHub fetches, drives sync interval backoff
+    sync_failures: AtomicU32,
+
+    /// Share of `total_tokens` priced as input when usage has no input/output split
+    total_only_input_ratio: f64,
+
+    /// Prefer costs reported by the instrumentation over computed ones
+    prefer_reported_cost: bool,
+
+    /// USD value of one unit of each non-USD currency (uppercase ISO codes)
+    currency_rates: HashMap<String, f64>,
+
+    /// Currencies already warned about for lacking a rate (warned once each)
+    unrated_currencies: Mutex<HashSet<String>>,
+
+    /// Framework-only system -> provider guessed when nothing else matches (lowercase)
+    framework_providers: HashMap<String, String>,
 }
 
 impl PricingService {
     /// Initialize pricing service
     ///
     /// Loading priority:
-    /// 1. Try local file from data directory
-    /// 2. If local valid and has >= models than embedded, use it
-    /// 3. Otherwise, use embedded data and save to disk
+    /// 1. If `config.file` (`SIDESEAT_PRICING_FILE`) is a valid file, use it
+    /// 2. Try local file from data directory
+    /// 3. If local valid and has >= models than embedded, use it
+    /// 4. Otherwise, use embedded data and save to disk
     ///
     /// If sync_hours > 0, spawns background fetch from GitHub after init.
-    pub async fn init(storage: &AppStorage, sync_hours: u64) -> Result<Arc<Self>, PricingError> {
+    /// With `persist_unmatched_models`, unmatched models saved by a previous run
+    /// are loaded back.
+    pub async fn init(
+        storage: &AppStorage,
+        config: &PricingConfig,
+        topics: Arc<TopicService>,
+    ) -> Result<Arc<Self>, PricingError> {
         let local_path = storage.data_dir().join(PRICING_FILE_NAME);
 
-        let data = Self::load_pricing_data(&local_path).await?;
+        let unmatched = UnmatchedModels::new(config.unmatched_models_limit);
+        let unmatched_path = (config.persist_unmatched_models && config.unmatched_models_limit > 0)
+            .then(|| storage.data_dir().join(UNMATCHED_MODELS_FILE_NAME));
+        if let Some(path) = &unmatched_path
+            && let Err(e) = unmatched.load(path).await
+        {
+            tracing::warn!(error = %e, "Failed to load unmatched models, starting empty");
+        }
+
+        let override_data = match &config.file {
+            Some(path) => Self::load_override(path).await,
+            None => None,
+        };
+        let data = match override_data {
+            Some(data) => data,
+            None => Self::load_pricing_data(&local_path).await?,
+        };
 
         let http_client = reqwest::Client::builder()
             .timeout(Duration::from_secs(30))
@@ -819,9 +1428,35 @@ impl PricingService {
             data: RwLock::new(data),
             local_path,
             http_client,
+            topics: Some(topics),
+            max_rate_change_ratio: config.max_rate_change_ratio,
+            held_rate_changes: RwLock::new(Vec::new()),
+            accepted_rate_changes: config
+                .accepted_rate_changes
+                .iter()
+                .map(|m| m.to_lowercase())
+                .collect(),
+            cached_input_providers: config
+                .cached_input_providers
+                .iter()
+                .map(|p| p.to_ascii_lowercase())
+                .collect(),
+            deployment_models: config.deployment_models.clone(),
+            unmatched,
+            unmatched_path,
+            sync_failures: AtomicU32::new(0),
+            total_only_input_ratio: config.total_only_input_ratio,
+            prefer_reported_cost: config.prefer_reported_cost,
+            currency_rates: config
+                .currency_rates
+                .iter()
+                .map(|(code, rate)| (code.to_ascii_uppercase(), *rate))
+                .collect(),
+            unrated_currencies: Mutex::new(HashSet::new()),
+            framework_providers: config.framework_providers.clone(),
         });
 
-        if sync_hours > 0 {
+        if config.sync_hours > 0 {
             let service_clone = Arc::clone(&service);
             tokio::spawn(async move {
                 service_clone.sync().await;
@@ -831,6 +1466,35 @@ impl PricingService {
         Ok(service)
     }
 
+    /// Load the operator-provided pricing file (`SIDESEAT_PRICING_FILE`).
+    ///
+    /// The file may be mounted read-only, so it is never written back. Rejected
+    /// (with a warning) if it fails to parse or has fewer than half the embedded
+    /// models, in which case the regular loading order applies.
+    async fn load_override(path: &Path) -> Option<PricingData> {
+        let data = match Self::try_load_local(path).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to load pricing override, ignoring");
+                return None;
+            }
+        };
+
+        let min_acceptable = Self::count_embedded_models() / 2;
+        if data.model_count < min_acceptable {
+            tracing::warn!(
+                path = %path.display(),
+                models = data.model_count,
+                min_acceptable,
+                "Pricing override has too few models, ignoring"
+            );
+            return None;
+        }
+
+        tracing::debug!(path = %path.display(), models = data.model_count, "Loaded pricing override");
+        Some(data)
+    }
+
     /// Load pricing data with fallback: local file → embedded
     async fn load_pricing_data(local_path: &Path) -> Result<PricingData, PricingError> {
         if !local_path.exists() {
@@ -883,6 +1547,23 @@ impl PricingService {
             data: RwLock::new(data),
             local_path: std::env::temp_dir().join("sideseat_test_pricing.json"),
             http_client: reqwest::Client::new(),
+            topics: None,
+            max_rate_change_ratio: DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO,
+            held_rate_changes: RwLock::new(Vec::new()),
+            accepted_rate_changes: HashSet::new(),
+            cached_input_providers: DEFAULT_CACHED_INPUT_PROVIDERS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            deployment_models: HashMap::new(),
+            unmatched: UnmatchedModels::new(DEFAULT_UNMATCHED_MODELS_LIMIT),
+            unmatched_path: None,
+            sync_failures: AtomicU32::new(0),
+            total_only_input_ratio: DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO,
+            prefer_reported_cost: true,
+            currency_rates: HashMap::new(),
+            unrated_currencies: Mutex::new(HashSet::new()),
+            framework_providers: HashMap::new(),
         })
     }
 
@@ -912,13 +1593,22 @@ impl PricingService {
     /// Thread-safe: acquires read lock on pricing data.
     /// Fail-safe: returns zero costs if model not found (debug log only).
     pub fn calculate_cost(&self, input: &SpanCostInput) -> SpanCostOutput {
+        self.compute_cost(input, true)
+    }
+
+    /// Same as `calculate_cost`, without recording unmatched models (for read paths)
+    pub fn calculate_cost_untracked(&self, input: &SpanCostInput) -> SpanCostOutput {
+        self.compute_cost(input, false)
+    }
+
+    fn compute_cost(&self, input: &SpanCostInput, track_unmatched: bool) -> SpanCostOutput {
         let model = match &input.model {
             Some(m) if !m.is_empty() => m.as_str(),
             _ => return SpanCostOutput::default(),
         };
 
         let data = self.data.read();
-        let (pricing, match_type) = match data.lookup(input.system.as_deref(), model) {
+        let (pricing, match_type) = match self.lookup_input(&data, input, model) {
             Some(result) => result,
             None => {
                 tracing::trace!(
@@ -926,35 +1616,92 @@ impl PricingService {
                     system = input.system.as_deref().unwrap_or("none"),
                     "No pricing found for model"
                 );
+                if track_unmatched {
+                    self.unmatched.record(input.system.as_deref(), model);
+                }
                 return SpanCostOutput {
                     match_type: Some(MatchType::NotFound),
+                    estimated: input.estimated,
                     ..Default::default()
                 };
             }
         };
 
+        // Rates in another currency are converted to USD at the configured rate
+        let usd_rate = match pricing.currency.as_deref() {
+            None => 1.0,
+            Some(currency) => match self.currency_rates.get(currency) {
+                Some(rate) => *rate,
+                None => {
+                    if self.unrated_currencies.lock().insert(currency.to_string()) {
+                        tracing::warn!(
+                            model = model,
+                            currency = currency,
+                            "No pricing.currency_rates entry for pricing currency, treating its rates as USD"
+                        );
+                    }
+                    1.0
+                }
+            },
+        };
+
         // For embedding models, only input tokens are charged
         let is_embedding = pricing.mode.eq_ignore_ascii_case("embedding");
 
         // Clamp token counts to prevent negative costs from data corruption
-        let input_tokens = input.input_tokens.max(0) as f64;
-        let output_tokens = input.output_tokens.max(0) as f64;
+        let mut input_tokens = input.input_tokens.max(0) as f64;
+        let mut output_tokens = input.output_tokens.max(0) as f64;
+
+        // Usage with only a total: split it by the configured ratio (all input
+        // for embeddings) and flag the result as estimated
+        let split_estimated = input_tokens == 0.0 && output_tokens == 0.0 && input.total_tokens > 0;
+        if split_estimated {
+            let total_tokens = input.total_tokens as f64;
+            let input_ratio = if is_embedding {
+                1.0
+            } else {
+                self.total_only_input_ratio
+            };
+            input_tokens = (total_tokens * input_ratio).round();
+            output_tokens = total_tokens - input_tokens;
+        }
         let cache_read_tokens = input.cache_read_tokens.max(0) as f64;
         let cache_write_tokens = input.cache_write_tokens.max(0) as f64;
         let reasoning_tokens = input.reasoning_tokens.max(0) as f64;
 
+        // Some providers report cache reads inside the prompt token count;
+        // bill that portion only at the cache read rate
+        let billable_input_tokens = if self.input_includes_cache_read(pricing) {
+            (input_tokens - cache_read_tokens).max(0.0)
+        } else {
+            input_tokens
+        };
+
         // Calculate costs
-        let input_cost = input_tokens * pricing.input_cost_per_token;
+        let input_rate = if is_embedding {
+            pricing.input_rate_for_dimensions(input.embedding_dimensions)
+        } else {
+            pricing.input_cost_per_token
+        };
+        let input_cost = billable_input_tokens * input_rate * usd_rate;
 
         // Output cost: zero for embeddings (they only have input)
         let output_cost = if is_embedding {
             0.0
         } else {
-            output_tokens * pricing.output_cost_per_token
+            output_tokens * pricing.output_cost_per_token * usd_rate
         };
 
-        let cache_read_cost = cache_read_tokens * pricing.cache_read_input_token_cost;
-        let cache_write_cost = cache_write_tokens * pricing.cache_creation_input_token_cost;
+        let cache_read_cost = cache_read_tokens * pricing.cache_read_input_token_cost * usd_rate;
+        // A zero cache read rate means unknown, not free: claim no savings
+        let cache_savings = if pricing.cache_read_input_token_cost > 0.0 {
+            (cache_read_tokens * (input_rate - pricing.cache_read_input_token_cost) * usd_rate)
+                .max(0.0)
+        } else {
+            0.0
+        };
+        let cache_write_cost =
+            cache_write_tokens * pricing.cache_creation_input_token_cost * usd_rate;
 
         // Reasoning tokens: use dedicated rate if available, else output rate
         let reasoning_cost = if is_embedding {
@@ -965,11 +1712,19 @@ impl PricingService {
             } else {
                 pricing.output_cost_per_token
             };
-            reasoning_tokens * reasoning_rate
+            reasoning_tokens * reasoning_rate * usd_rate
         };
 
-        let total_cost =
-            input_cost + output_cost + cache_read_cost + cache_write_cost + reasoning_cost;
+        // Built-in tool surcharges: zero when the pricing entry has no rate
+        let tool_cost =
+            input.web_search_calls.max(0) as f64 * pricing.web_search_cost_per_query * usd_rate;
+
+        let total_cost = input_cost
+            + output_cost
+            + cache_read_cost
+            + cache_write_cost
+            + reasoning_cost
+            + tool_cost;
 
         tracing::trace!(
             model = model,
@@ -985,11 +1740,86 @@ impl PricingService {
             cache_read_cost,
             cache_write_cost,
             reasoning_cost,
+            tool_cost,
             total_cost,
+            cache_savings,
             match_type: Some(match_type),
+            estimated: input.estimated || split_estimated,
         }
     }
 
+    /// Resolve the pricing entry `calculate_cost` uses for this input
+    ///
+    /// Applies the same deployment mapping and lookup rules, without recording
+    /// unmatched models. Returns `None` when no model is set or nothing matches.
+    pub fn resolve_pricing(&self, input: &SpanCostInput) -> Option<(ModelPricing, MatchType)> {
+        let model = input.model.as_deref().filter(|m| !m.is_empty())?;
+        let data = self.data.read();
+        self.lookup_input(&data, input, model)
+            .map(|(pricing, match_type)| (pricing.clone(), match_type))
+    }
+
+    /// Look up pricing for a span: org deployment mapping first, then the model itself
+    fn lookup_input<'a>(
+        &self,
+        data: &'a PricingData,
+        input: &SpanCostInput,
+        model: &str,
+    ) -> Option<(&'a ModelPricing, MatchType)> {
+        self.deployment_model(input, model)
+            .and_then(|target| data.lookup(input.system.as_deref(), target))
+            .map(|(pricing, _)| (pricing, MatchType::Deployment))
+            .or_else(|| data.lookup(input.system.as_deref(), model))
+            .or_else(|| {
+                let provider = self.framework_provider(input.system.as_deref()?)?;
+                data.lookup(Some(provider), model)
+                    .map(|(pricing, _)| (pricing, MatchType::Family))
+            })
+    }
+
+    /// Provider configured for a framework-only system, the last resort before
+    /// NotFound. A guess, so matches through it only count as family matches.
+    fn framework_provider(&self, system: &str) -> Option<&str> {
+        if !map_system_to_litellm_provider(system).is_empty() {
+            return None;
+        }
+        self.framework_providers
+            .get(&system.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Whether any org has Azure deployment mappings (callers can skip org lookups otherwise)
+    pub fn has_deployment_models(&self) -> bool {
+        !self.deployment_models.is_empty()
+    }
+
+    /// Whether span-reported costs (OpenInference `llm.cost.*`) win over computed ones
+    pub fn prefers_reported_cost(&self) -> bool {
+        self.prefer_reported_cost
+    }
+
+    /// Model key configured for an Azure deployment name in the span's org
+    fn deployment_model(&self, input: &SpanCostInput, deployment: &str) -> Option<&str> {
+        let system = input.system.as_deref()?.to_ascii_lowercase();
+        if !(system.starts_with("azure") || system.starts_with("az.")) {
+            return None;
+        }
+        self.deployment_models
+            .get(input.org_id.as_deref()?)?
+            .get(deployment)
+            .map(String::as_str)
+    }
+
+    /// Whether the matched model's input token count already includes cache reads
+    ///
+    /// Decided by the exact LiteLLM provider of the pricing entry, not the span
+    /// system: one platform can host model families with different conventions
+    /// (Gemini and Claude on Vertex AI).
+    fn input_includes_cache_read(&self, pricing: &ModelPricing) -> bool {
+        let provider = pricing.litellm_provider.to_ascii_lowercase();
+        self.cached_input_providers.contains(&provider)
+    }
+
     /// Get model pricing information (per-token rates)
     ///
     /// Returns the pricing rates and match type for a given model.
@@ -1008,28 +1838,101 @@ impl PricingService {
             .map(|(pricing, match_type)| (pricing.clone(), match_type))
     }
 
+    /// Get pricing for a model family across providers
+    ///
+    /// Returns `(pricing key, pricing)` per provider.
+    /// Thread-safe: acquires read lock on pricing data.
+    pub fn get_family_pricing(&self, family: &str) -> Vec<(String, ModelPricing)> {
+        let data = self.data.read();
+        data.family_pricing(family)
+            .into_iter()
+            .map(|(key, pricing)| (key.to_string(), pricing.clone()))
+            .collect()
+    }
+
+    /// How a model name is normalized during lookup, step by step
+    pub fn normalize_model(&self, system: Option<&str>, model: &str) -> ModelNormalization {
+        self.data.read().normalization(system, model)
+    }
+
     /// Sync pricing data from GitHub
     async fn sync(&self) {
         let request = self.http_client.get(PRICING_SYNC_URL);
 
-        match request.send().await {
+        let text = match request.send().await {
             Ok(resp) if resp.status().is_success() => match resp.text().await {
-                Ok(text) => self.apply_sync_data(&text).await,
-                Err(e) => tracing::warn!(error = %e, "Failed to read pricing response"),
+                Ok(text) => text,
+                Err(e) => {
+                    self.record_sync_failure();
+                    tracing::warn!(error = %e, "Failed to read pricing response");
+                    return;
+                }
             },
             Ok(resp) => {
+                self.record_sync_failure();
                 tracing::warn!(status = %resp.status(), "Pricing sync HTTP error");
+                return;
             }
             Err(e) => {
+                self.record_sync_failure();
                 tracing::warn!(error = %e, "Pricing sync request failed");
+                return;
             }
+        };
+
+        self.record_sync_success();
+        self.apply_sync_data(&text).await;
+    }
+
+    /// Count a failed fetch toward the sync backoff
+    fn record_sync_failure(&self) {
+        let failures = self.sync_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures == PRICING_SYNC_BACKOFF_THRESHOLD {
+            tracing::warn!(
+                failures,
+                "Pricing sync keeps failing, backing off until it succeeds"
+            );
+        }
+    }
+
+    /// Reset the sync backoff after a successful fetch
+    fn record_sync_success(&self) {
+        let failures = self.sync_failures.swap(0, Ordering::Relaxed);
+        if failures >= PRICING_SYNC_BACKOFF_THRESHOLD {
+            tracing::info!(failures, "Pricing sync recovered");
+        }
+    }
+
+    /// Interval until the next scheduled sync
+    ///
+    /// Once `PRICING_SYNC_BACKOFF_THRESHOLD` consecutive fetches fail, `base`
+    /// doubles with every further failure, capped at
+    /// `PRICING_SYNC_MAX_BACKOFF_SECS` (or `base`, if that is longer).
+    fn effective_sync_interval(&self, base: Duration) -> Duration {
+        let failures = self.sync_failures.load(Ordering::Relaxed);
+        if failures < PRICING_SYNC_BACKOFF_THRESHOLD {
+            return base;
         }
+        let cap = base.max(Duration::from_secs(PRICING_SYNC_MAX_BACKOFF_SECS));
+        let shift = (failures - PRICING_SYNC_BACKOFF_THRESHOLD + 1).min(31);
+        base.saturating_mul(1 << shift).min(cap)
+    }
+
+    /// Version of the pricing data currently in memory
+    pub fn version(&self) -> String {
+        self.data.read().version.clone()
+    }
+
+    /// Number of models in the pricing data currently in memory
+    pub fn model_count(&self) -> usize {
+        self.data.read().model_count
     }
 
-    /// Apply synced data: parse, save to disk atomically, update memory
+    /// Apply synced data: parse, save to disk atomically, update memory,
+    /// then announce the new version if it changed
     async fn apply_sync_data(&self, json: &str) {
         // Parse first to validate
-        let new_data = match PricingData::from_json_str(json) {
+        let mut new_data = match PricingData::from_json_str(json) {
             Ok(data) => data,
             Err(e) => {
                 tracing::warn!(error = %e, "Failed to parse synced pricing data");
@@ -1062,22 +1965,106 @@ impl PricingService {
             );
         }
 
-        // Save to disk atomically
-        if let Err(e) = Self::save_to_file(&self.local_path, json).await {
+        // Per-model safety net: keep current rates for suspicious jumps
+        let held = if self.max_rate_change_ratio > 1.0 {
+            new_data.hold_rate_spikes(
+                &self.data.read(),
+                self.max_rate_change_ratio,
+                &self.accepted_rate_changes,
+            )
+        } else {
+            Vec::new()
+        };
+        for change in &held {
+            tracing::warn!(
+                model = %change.model,
+                ratio = ?change.ratio,
+                current_input = change.current_input_cost_per_token,
+                synced_input = change.synced_input_cost_per_token,
+                current_output = change.current_output_cost_per_token,
+                synced_output = change.synced_output_cost_per_token,
+                "Synced price changed beyond max_rate_change_ratio, holding current rates"
+            );
+        }
+
+        // Save to disk atomically, with held models at their current rates so
+        // a restart does not bring the held prices back unchecked
+        let saved = if held.is_empty() {
+            Some(json.to_string())
+        } else {
+            restore_held_rates(json, &held)
+        };
+        if let Some(saved) = saved
+            && let Err(e) = Self::save_to_file(&self.local_path, &saved).await
+        {
             tracing::warn!(error = %e, "Failed to save pricing data to disk");
         }
+        *self.held_rate_changes.write() = held;
+
+        let version = new_data.version.clone();
 
         // Update in-memory data
-        {
+        let previous_version = {
             let mut data = self.data.write();
-            *data = new_data;
+            std::mem::replace(&mut *data, new_data).version
+        };
+
+        if version != previous_version {
+            self.publish_updated(new_count, version).await;
+        }
+    }
+
+    /// Synced price changes held back by the last sync, for operator review
+    ///
+    /// Listing a model in `pricing.accepted_rate_changes` lets the next sync
+    /// apply its new rates; raising `pricing.max_rate_change_ratio` (or setting
+    /// it to 1 or below) does so for every model.
+    pub fn held_rate_changes(&self) -> Vec<HeldRateChange> {
+        self.held_rate_changes.read().clone()
+    }
+
+    /// Models that found no pricing, most frequent first
+    pub fn unmatched_models(&self) -> Vec<UnmatchedModel> {
+        self.unmatched.snapshot()
+    }
+
+    /// Write unmatched models to the data directory (no-op unless persistence is on)
+    pub async fn persist_unmatched_models(&self) {
+        let Some(path) = &self.unmatched_path else {
+            return;
+        };
+        if let Err(e) = self.unmatched.save(path).await {
+            tracing::warn!(error = %e, "Failed to persist unmatched models");
+        }
+    }
+
+    /// Broadcast a `pricing_updated` event (best-effort)
+    async fn publish_updated(&self, model_count: usize, version: String) {
+        let Some(topics) = &self.topics else {
+            return;
+        };
+        let event = PricingUpdated {
+            model_count,
+            version,
+        };
+        let topic = topics.broadcast_topic::<PricingUpdated>(TOPIC_PRICING_UPDATED);
+        if let Err(e) = topic.publish(&event).await {
+            tracing::warn!(error = %e, "Failed to publish pricing update");
         }
     }
 
     /// Start background sync task
     ///
+    /// The interval backs off while GitHub fetches keep failing
+    /// (see `effective_sync_interval`).
+    ///
+    /// Each round is gated by a lease in the shared cache, so with several
+    /// instances only the first to wake fetches; the others pick up the new
+    /// data through the `pricing_updated` broadcast.
+    ///
     /// # Arguments
     /// * `sync_hours` - Sync interval in hours. 0 disables sync. Minimum 1 hour.
+    /// * `cache` - Shared cache holding the sync lease
     /// * `shutdown_rx` - Shutdown signal receiver
     ///
     /// # Returns
@@ -1085,6 +2072,7 @@ impl PricingService {
     pub fn start_sync_task(
         self: &Arc<Self>,
         sync_hours: u64,
+        cache: Arc<CacheService>,
         mut shutdown_rx: watch::Receiver<bool>,
     ) -> Option<JoinHandle<()>> {
         if sync_hours == 0 {
@@ -1097,7 +2085,40 @@ impl PricingService {
         let service = Arc::clone(self);
 
         Some(tokio::spawn(async move {
-            let mut timer = tokio::time::interval(interval);
+            loop {
+                let delay = service.effective_sync_interval(interval);
+                tokio::select! {
+                    biased;
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(delay) => {
+                        if claim_sync_lease(&cache, delay / 2).await {
+                            service.sync().await;
+                        } else {
+                            tracing::debug!("Pricing sync claimed by another instance, skipping");
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Start task that periodically persists unmatched models, and once more on shutdown
+    ///
+    /// # Returns
+    /// `Some(JoinHandle)` if persistence is enabled, `None` otherwise
+    pub fn start_unmatched_flush_task(
+        self: &Arc<Self>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> Option<JoinHandle<()>> {
+        self.unmatched_path.as_ref()?;
+        let service = Arc::clone(self);
+
+        Some(tokio::spawn(async move {
+            let mut timer = tokio::time::interval(Duration::from_secs(UNMATCHED_MODELS_FLUSH_SECS));
             timer.tick().await; // Skip immediate first tick
 
             loop {
@@ -1105,11 +2126,71 @@ impl PricingService {
                     biased;
                     _ = shutdown_rx.changed() => {
                         if *shutdown_rx.borrow() {
+                            service.persist_unmatched_models().await;
                             break;
                         }
                     }
                     _ = timer.tick() => {
-                        service.sync().await;
+                        service.persist_unmatched_models().await;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Start listener that re-syncs when another instance applied a newer version
+    ///
+    /// Only runs when sync is enabled and the topic backend spans instances;
+    /// events for the version already loaded are ignored.
+    pub fn start_update_listener(
+        self: &Arc<Self>,
+        sync_hours: u64,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> Option<JoinHandle<()>> {
+        let topics = self.topics.clone()?;
+        if sync_hours == 0 || !topics.capabilities().distributed_broadcast {
+            return None;
+        }
+        let service = Arc::clone(self);
+
+        Some(tokio::spawn(async move {
+            let topic = topics.broadcast_topic::<PricingUpdated>(TOPIC_PRICING_UPDATED);
+            let mut subscriber = match topic.subscribe().await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to subscribe to pricing updates");
+                    return;
+                }
+            };
+
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    result = subscriber.recv() => match result {
+                        Ok(event) => {
+                            if event.version == service.version() {
+                                continue;
+                            }
+                            tracing::debug!(
+                                model_count = event.model_count,
+                                version = %event.version,
+                                "Pricing updated on another instance, syncing"
+                            );
+                            service.sync().await;
+                        }
+                        Err(TopicError::Lagged(n)) => {
+                            tracing::warn!(lagged = n, "Pricing update listener lagged");
+                        }
+                        Err(TopicError::ChannelClosed) => break,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Pricing update listener receive error");
+                            break;
+                        }
                     }
                 }
             }
@@ -1117,6 +2198,24 @@ impl PricingService {
     }
 }
 
+/// Claim the scheduled pricing sync for this instance.
+///
+/// The lease outlives concurrent wake-ups but expires before the next round.
+/// Fails open when the cache is unreachable: a duplicate fetch is harmless,
+/// a skipped one leaves prices stale.
+async fn claim_sync_lease(cache: &CacheService, lease: Duration) -> bool {
+    match cache
+        .incr(&CacheKey::pricing_sync_lease(), Some(lease))
+        .await
+    {
+        Ok(claims) => claims == 1,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to claim pricing sync lease, syncing anyway");
+            true
+        }
+    }
+}
+
 impl Default for PricingService {
     fn default() -> Self {
         // Fallback for cases where async init isn't possible
@@ -1126,6 +2225,23 @@ impl Default for PricingService {
             data: RwLock::new(data),
             local_path: PathBuf::new(),
             http_client: reqwest::Client::new(),
+            topics: None,
+            max_rate_change_ratio: DEFAULT_PRICING_MAX_RATE_CHANGE_RATIO,
+            held_rate_changes: RwLock::new(Vec::new()),
+            accepted_rate_changes: HashSet::new(),
+            cached_input_providers: DEFAULT_CACHED_INPUT_PROVIDERS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            deployment_models: HashMap::new(),
+            unmatched: UnmatchedModels::new(DEFAULT_UNMATCHED_MODELS_LIMIT),
+            unmatched_path: None,
+            sync_failures: AtomicU32::new(0),
+            total_only_input_ratio: DEFAULT_PRICING_TOTAL_ONLY_INPUT_RATIO,
+            prefer_reported_cost: true,
+            currency_rates: HashMap::new(),
+            unrated_currencies: Mutex::new(HashSet::new()),
+            framework_providers: HashMap::new(),
         }
     }
 }
@@ -1137,6 +2253,7 @@ impl Default for PricingService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::config::{CacheBackendType, CacheConfig, EvictionPolicy};
 
     /// Test helper: Map system to provider string with lowercasing for unknown providers
     fn map_system_to_provider_string(system: &str) -> String {
@@ -1164,6 +2281,31 @@ mod tests {
         assert!(pricing.input_cost_per_token > 0.0);
     }
 
+    #[test]
+    fn test_model_capabilities_vision_model() {
+        let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
+        let (pricing, _) = data.lookup(Some("openai"), "gpt-4o").unwrap();
+        let caps = &pricing.capabilities;
+        assert_eq!(caps.supports_vision, Some(true));
+        assert_eq!(caps.supports_function_calling, Some(true));
+        assert_eq!(caps.max_input_tokens, Some(128_000));
+        assert_eq!(caps.max_output_tokens, Some(16_384));
+    }
+
+    #[test]
+    fn test_model_capabilities_lenient_parsing() {
+        let entry = serde_json::json!({
+            "max_input_tokens": 8192.0,
+            "max_tokens": 4096,
+            "supports_vision": "yes"
+        });
+        let caps = ModelCapabilities::from_entry(entry.as_object().unwrap());
+        assert_eq!(caps.max_input_tokens, Some(8192));
+        assert_eq!(caps.max_output_tokens, Some(4096));
+        assert_eq!(caps.supports_vision, None);
+        assert_eq!(caps.supports_function_calling, None);
+    }
+
     #[test]
     fn test_lookup_provider_prefix() {
         let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
@@ -1216,6 +2358,40 @@ mod tests {
         assert_eq!(output.match_type, Some(MatchType::NotFound));
     }
 
+    #[test]
+    fn test_framework_provider_is_last_resort() {
+        // Only priced under the mistral/ prefix, so a bare name needs provider context
+        let input = SpanCostInput {
+            system: Some("langchain".to_string()),
+            model: Some("codestral-2405".to_string()),
+            input_tokens: 1000,
+            output_tokens: 500,
+            ..Default::default()
+        };
+
+        let mut service = PricingService::init_for_test().unwrap();
+        assert_eq!(
+            service.calculate_cost(&input).match_type,
+            Some(MatchType::NotFound)
+        );
+
+        service.framework_providers =
+            HashMap::from([("langchain".to_string(), "mistral".to_string())]);
+        let output = service.calculate_cost(&input);
+        assert_eq!(output.match_type, Some(MatchType::Family));
+        assert!(output.total_cost > 0.0);
+
+        // Models matched without the guess keep their own match type
+        let exact = SpanCostInput {
+            model: Some("gpt-4o".to_string()),
+            ..input
+        };
+        assert_eq!(
+            service.calculate_cost(&exact).match_type,
+            Some(MatchType::Exact)
+        );
+    }
+
     #[test]
     fn test_calculate_cost_no_model() {
         let service = PricingService::init_for_test().unwrap();
@@ -1230,6 +2406,337 @@ mod tests {
         assert_eq!(output.total_cost, 0.0);
     }
 
+    #[test]
+    fn test_calculate_cost_estimates_split_for_total_only_usage() {
+        let service = PricingService::init_for_test().unwrap();
+        let total_only = SpanCostInput {
+            system: Some("openai".to_string()),
+            model: Some("gpt-4o".to_string()),
+            total_tokens: 1000,
+            ..Default::default()
+        };
+        let output = service.calculate_cost(&total_only);
+        assert!(output.estimated);
+        assert!(output.total_cost > 0.0);
+
+        // Priced like the default 75/25 input/output split
+        let split = service.calculate_cost(&SpanCostInput {
+            input_tokens: 750,
+            output_tokens: 250,
+            ..total_only.clone()
+        });
+        assert!(!split.estimated);
+        assert!((output.input_cost - split.input_cost).abs() < 1e-12);
+        assert!((output.output_cost - split.output_cost).abs() < 1e-12);
+
+        // A reported split is priced exactly, whatever the total says
+        let exact = service.calculate_cost(&SpanCostInput {
+            input_tokens: 100,
+            output_tokens: 0,
+            ..total_only
+        });
+        assert!(!exact.estimated);
+        assert_eq!(exact.output_cost, 0.0);
+    }
+
+    #[test]
+    fn test_dimension_tiered_embedding_rate() {
+        let entry = serde_json::json!({
+            "input_cost_per_token": 1e-7,
+            "input_cost_per_token_above_1024_dimensions": 2e-7,
+            "input_cost_per_token_above_256_dimensions": 1.5e-7,
+            "input_cost_per_token_above_tokens": 9.0,
+        });
+        let pricing = ModelPricing {
+            input_cost_per_token: 1e-7,
+            input_cost_per_token_by_dimensions: dimension_tiers(entry.as_object().unwrap()),
+            mode: "embedding".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            pricing.input_cost_per_token_by_dimensions,
+            vec![(256, 1.5e-7), (1024, 2e-7)]
+        );
+        assert_eq!(pricing.input_rate_for_dimensions(None), 1e-7);
+        assert_eq!(pricing.input_rate_for_dimensions(Some(256)), 1e-7);
+        assert_eq!(pricing.input_rate_for_dimensions(Some(512)), 1.5e-7);
+        assert_eq!(pricing.input_rate_for_dimensions(Some(3072)), 2e-7);
+    }
+
+    #[test]
+    fn test_calculate_cost_converts_override_currency() {
+        let mut service = PricingService::init_for_test().unwrap();
+        let mut raw: serde_json::Value = serde_json::from_str(EMBEDDED_PRICING_JSON).unwrap();
+        raw["internal-eur-model"] = serde_json::json!({
+            "input_cost_per_token": 0.000002,
+            "output_cost_per_token": 0.000004,
+            "currency": "eur"
+        });
+        *service.data.write() = PricingData::from_json_str(&raw.to_string()).unwrap();
+        let input = SpanCostInput {
+            model: Some("internal-eur-model".to_string()),
+            input_tokens: 1000,
+            output_tokens: 500,
+            ..Default::default()
+        };
+
+        // No configured rate: the rates are taken as USD
+        let unconverted = service.calculate_cost(&input);
+        assert_eq!(unconverted.match_type, Some(MatchType::Exact));
+        assert!((unconverted.total_cost - 0.004).abs() < 1e-12);
+
+        service.currency_rates = HashMap::from([("EUR".to_string(), 1.1)]);
+        let output = service.calculate_cost(&input);
+        // 1000 * 2e-6 EUR = 0.002 EUR = 0.0022 USD; 500 * 4e-6 EUR = 0.002 EUR = 0.0022 USD
+        assert!((output.input_cost - 0.0022).abs() < 1e-12);
+        assert!((output.output_cost - 0.0022).abs() < 1e-12);
+        assert!((output.total_cost - 0.0044).abs() < 1e-12);
+
+        // Entries without a currency stay USD
+        let usd = service.calculate_cost(&SpanCostInput {
+            model: Some("gpt-4o".to_string()),
+            ..input
+        });
+        assert!((usd.input_cost - 1000.0 * 2.5e-6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_calculate_cost_adds_web_search_surcharge() {
+        let service = PricingService::init_for_test().unwrap();
+        let tokens_only = SpanCostInput {
+            system: Some("openai".to_string()),
+            model: Some("gpt-4o-search-preview".to_string()),
+            input_tokens: 1000,
+            output_tokens: 500,
+            ..Default::default()
+        };
+        let with_search = SpanCostInput {
+            web_search_calls: 2,
+            ..tokens_only.clone()
+        };
+
+        let base = service.calculate_cost(&tokens_only);
+        let output = service.calculate_cost(&with_search);
+        assert_eq!(base.tool_cost, 0.0);
+        // Medium search context: $0.035 per query
+        assert!((output.tool_cost - 0.07).abs() < 1e-9);
+        assert!((output.total_cost - (base.total_cost + 0.07)).abs() < 1e-9);
+        assert_eq!(output.input_cost, base.input_cost);
+
+        // No surcharge field in the pricing entry: searches cost nothing extra
+        let no_rate = service.calculate_cost(&SpanCostInput {
+            model: Some("gpt-4o".to_string()),
+            ..with_search
+        });
+        assert_eq!(no_rate.tool_cost, 0.0);
+    }
+
+    #[test]
+    fn test_pricing_misses_are_tracked_as_unmatched_models() {
+        let service = PricingService::init_for_test().unwrap();
+        let unknown = SpanCostInput {
+            system: Some("openai".to_string()),
+            model: Some("gpt-internal-preview".to_string()),
+            input_tokens: 10,
+            ..Default::default()
+        };
+        service.calculate_cost(&unknown);
+        service.calculate_cost(&unknown);
+        service.calculate_cost(&SpanCostInput {
+            model: Some("gpt-4o".to_string()),
+            ..unknown.clone()
+        });
+
+        let unmatched = service.unmatched_models();
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].model, "gpt-internal-preview");
+        assert_eq!(unmatched[0].system.as_deref(), Some("openai"));
+        assert_eq!(unmatched[0].count, 2);
+    }
+
+    #[test]
+    fn test_cached_input_providers_do_not_double_charge_cache_reads() {
+        // OpenAI-style usage: prompt_tokens (1000) includes cached_tokens (800)
+        let input = SpanCostInput {
+            system: Some("openai".to_string()),
+            model: Some("gpt-4o".to_string()),
+            input_tokens: 1000,
+            output_tokens: 100,
+            cache_read_tokens: 800,
+            ..Default::default()
+        };
+
+        let default_service = PricingService::init_for_test().unwrap();
+        let mut service = PricingService::init_for_test().unwrap();
+        service.cached_input_providers = vec!["openai".to_string()];
+
+        let (input_rate, output_rate, cache_rate) = {
+            let data = service.data.read();
+            let (p, _) = data.lookup(Some("openai"), "gpt-4o").unwrap();
+            (
+                p.input_cost_per_token,
+                p.output_cost_per_token,
+                p.cache_read_input_token_cost,
+            )
+        };
+        assert!(cache_rate > 0.0);
+
+        let output = service.calculate_cost(&input);
+        let expected = 200.0 * input_rate + 100.0 * output_rate + 800.0 * cache_rate;
+        assert!((output.input_cost - 200.0 * input_rate).abs() < 1e-12);
+        assert!((output.total_cost - expected).abs() < 1e-12);
+
+        // Without the provider opt-in, input tokens are charged in full
+        let charged_twice = default_service.calculate_cost(&input);
+        assert!((charged_twice.input_cost - 1000.0 * input_rate).abs() < 1e-12);
+        assert!(charged_twice.total_cost > output.total_cost);
+    }
+
+    #[test]
+    fn test_google_providers_include_cache_reads_by_default() {
+        let service = PricingService::init_for_test().unwrap();
+        for system in ["gemini", "vertex_ai", "google_vertexai"] {
+            let input = SpanCostInput {
+                system: Some(system.to_string()),
+                model: Some("gemini-2.5-flash".to_string()),
+                input_tokens: 1000,
+                cache_read_tokens: 800,
+                ..Default::default()
+            };
+            let (pricing, _) = service
+                .get_model_pricing(input.system.as_deref(), "gemini-2.5-flash")
+                .unwrap();
+            let output = service.calculate_cost(&input);
+            assert!(
+                (output.input_cost - 200.0 * pricing.input_cost_per_token).abs() < 1e-12,
+                "{system} input charged twice"
+            );
+        }
+    }
+
+    #[test]
+    fn test_claude_on_vertex_input_excludes_cache_reads() {
+        let service = PricingService::init_for_test().unwrap();
+        for system in ["vertex_ai", "google_vertexai"] {
+            let input = SpanCostInput {
+                system: Some(system.to_string()),
+                model: Some("claude-sonnet-4-5".to_string()),
+                input_tokens: 1000,
+                cache_read_tokens: 800,
+                ..Default::default()
+            };
+            let (pricing, _) = service
+                .get_model_pricing(input.system.as_deref(), "claude-sonnet-4-5")
+                .unwrap();
+            assert_eq!(pricing.litellm_provider, "vertex_ai-anthropic_models");
+            let output = service.calculate_cost(&input);
+            assert!(
+                (output.input_cost - 1000.0 * pricing.input_cost_per_token).abs() < 1e-12,
+                "{system} input reduced by cache reads"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cache_savings_zero_without_cache_read_rate() {
+        let service = PricingService::init_for_test().unwrap();
+        let input = SpanCostInput {
+            system: Some("vertex_ai".to_string()),
+            model: Some("claude-3-5-sonnet".to_string()),
+            input_tokens: 200,
+            output_tokens: 100,
+            cache_read_tokens: 50_000,
+            ..Default::default()
+        };
+        let (pricing, _) = service
+            .get_model_pricing(Some("vertex_ai"), "claude-3-5-sonnet")
+            .unwrap();
+        assert!(pricing.input_cost_per_token > 0.0);
+        assert_eq!(pricing.cache_read_input_token_cost, 0.0);
+
+        let output = service.calculate_cost(&input);
+        assert_eq!(output.cache_read_cost, 0.0);
+        assert_eq!(output.cache_savings, 0.0);
+    }
+
+    #[test]
+    fn test_cache_savings_is_rate_difference_times_cached_tokens() {
+        let service = PricingService::init_for_test().unwrap();
+        let input = SpanCostInput {
+            system: Some("anthropic".to_string()),
+            model: Some("claude-sonnet-4-5".to_string()),
+            input_tokens: 200,
+            output_tokens: 100,
+            cache_read_tokens: 50_000,
+            ..Default::default()
+        };
+
+        let (input_rate, cache_rate) = {
+            let data = service.data.read();
+            let (p, _) = data.lookup(Some("anthropic"), "claude-sonnet-4-5").unwrap();
+            (p.input_cost_per_token, p.cache_read_input_token_cost)
+        };
+        assert!(cache_rate > 0.0 && cache_rate < input_rate);
+
+        let output = service.calculate_cost(&input);
+        let expected = 50_000.0 * (input_rate - cache_rate);
+        assert!(output.cache_savings > 0.0);
+        assert!((output.cache_savings - expected).abs() < 1e-12);
+        // Savings are informational and never change the billed total
+        let billed = output.input_cost
+            + output.output_cost
+            + output.cache_read_cost
+            + output.cache_write_cost
+            + output.reasoning_cost
+            + output.tool_cost;
+        assert!((output.total_cost - billed).abs() < 1e-12);
+
+        // No cache reads, no savings
+        let uncached = service.calculate_cost(&SpanCostInput {
+            cache_read_tokens: 0,
+            ..input
+        });
+        assert_eq!(uncached.cache_savings, 0.0);
+    }
+
+    #[test]
+    fn test_azure_deployment_name_resolves_via_org_mapping() {
+        let mut service = PricingService::init_for_test().unwrap();
+        service.deployment_models = HashMap::from([(
+            "org-1".to_string(),
+            HashMap::from([("my-gpt4-prod".to_string(), "gpt-4o".to_string())]),
+        )]);
+        let input = SpanCostInput {
+            system: Some("azure".to_string()),
+            model: Some("my-gpt4-prod".to_string()),
+            input_tokens: 1000,
+            output_tokens: 500,
+            org_id: Some("org-1".to_string()),
+            ..Default::default()
+        };
+
+        let mapped = service.calculate_cost(&input);
+        let direct = service.calculate_cost(&SpanCostInput {
+            model: Some("gpt-4o".to_string()),
+            ..input.clone()
+        });
+        assert_eq!(mapped.match_type, Some(MatchType::Deployment));
+        assert!(mapped.total_cost > 0.0);
+        assert_eq!(mapped.total_cost, direct.total_cost);
+
+        // Other orgs and non-Azure systems are unaffected
+        let other_org = service.calculate_cost(&SpanCostInput {
+            org_id: Some("org-2".to_string()),
+            ..input.clone()
+        });
+        assert_eq!(other_org.match_type, Some(MatchType::NotFound));
+        let openai = service.calculate_cost(&SpanCostInput {
+            system: Some("openai".to_string()),
+            ..input
+        });
+        assert_eq!(openai.match_type, Some(MatchType::NotFound));
+    }
+
     #[test]
     fn test_count_embedded_models() {
         let count = PricingService::count_embedded_models();
@@ -1482,11 +2989,48 @@ mod tests {
         // When sync_hours = 0, init should not spawn any background tasks
         // This is verified by checking that no HTTP requests are made
         let storage = AppStorage::init_for_test(std::env::temp_dir());
-        let service = PricingService::init(&storage, 0).await.unwrap();
+        let service = PricingService::init(
+            &storage,
+            &PricingConfig::default(),
+            Arc::new(TopicService::new()),
+        )
+        .await
+        .unwrap();
         // If we got here without network, sync was disabled correctly
         assert!(service.data.read().model_count > 0);
     }
 
+    #[tokio::test]
+    async fn test_init_prefers_pricing_file_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let override_path = dir.path().join("override_prices.json");
+        let mut raw: serde_json::Value = serde_json::from_str(EMBEDDED_PRICING_JSON).unwrap();
+        raw["test-override-model"] = serde_json::json!({
+            "input_cost_per_token": 0.000001,
+            "output_cost_per_token": 0.000002
+        });
+        std::fs::write(&override_path, raw.to_string()).unwrap();
+
+        let storage = AppStorage::init_for_test(dir.path().to_path_buf());
+        let service = PricingService::init(
+            &storage,
+            &PricingConfig {
+                file: Some(override_path.clone()),
+                ..Default::default()
+            },
+            Arc::new(TopicService::new()),
+        )
+        .await
+        .unwrap();
+        assert!(
+            service
+                .get_model_pricing(None, "test-override-model")
+                .is_some()
+        );
+        // Read-only mount: nothing is written back to the data directory
+        assert!(!dir.path().join(PRICING_FILE_NAME).exists());
+    }
+
     #[test]
     fn test_count_embedded_models_robust() {
         let count = PricingService::count_embedded_models();
@@ -1496,6 +3040,165 @@ mod tests {
         assert!(count < 100000, "Should not be unreasonably large");
     }
 
+    #[tokio::test]
+    async fn test_apply_sync_data_publishes_single_update() {
+        let topics = Arc::new(TopicService::new());
+        let mut updates = topics
+            .broadcast_topic::<PricingUpdated>(TOPIC_PRICING_UPDATED)
+            .subscribe()
+            .await
+            .unwrap();
+
+        let mut service = PricingService::init_for_test().unwrap();
+        service.local_path = std::env::temp_dir().join("sideseat_test_pricing_updated.json");
+        service.topics = Some(topics);
+        let current_count = service.data.read().model_count;
+
+        // Embedded data plus one extra model, so the version changes
+        let mut raw: serde_json::Value = serde_json::from_str(EMBEDDED_PRICING_JSON).unwrap();
+        raw["test-sync-model"] = serde_json::json!({
+            "input_cost_per_token": 0.000001,
+            "output_cost_per_token": 0.000002
+        });
+        let json = raw.to_string();
+
+        service.apply_sync_data(&json).await;
+
+        let event = tokio::time::timeout(Duration::from_secs(1), updates.recv())
+            .await
+            .expect("pricing update not received")
+            .unwrap();
+        assert_eq!(event.model_count, current_count + 1);
+        assert_eq!(event.version, service.version());
+
+        // Re-applying the same data is a no-op for subscribers
+        service.apply_sync_data(&json).await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), updates.recv())
+                .await
+                .is_err(),
+            "expected exactly one pricing update"
+        );
+
+        let _ = std::fs::remove_file(&service.local_path);
+    }
+
+    #[tokio::test]
+    async fn test_apply_sync_data_holds_model_with_rate_spike() {
+        let mut service = PricingService::init_for_test().unwrap();
+        service.local_path = std::env::temp_dir().join("sideseat_test_pricing_rate_spike.json");
+        let _ = std::fs::remove_file(&service.local_path);
+        let rates = |model: &str| {
+            let p = service.get_model_pricing(None, model).unwrap().0;
+            (p.input_cost_per_token, p.output_cost_per_token)
+        };
+        let (gpt4o_input, gpt4o_output) = rates("gpt-4o");
+        let (mini_input, _) = rates("gpt-4o-mini");
+
+        let mut raw: serde_json::Value = serde_json::from_str(EMBEDDED_PRICING_JSON).unwrap();
+        raw["gpt-4o"]["input_cost_per_token"] = serde_json::json!(gpt4o_input * 20.0);
+        raw["gpt-4o-mini"]["input_cost_per_token"] = serde_json::json!(mini_input * 1.5);
+        service.apply_sync_data(&raw.to_string()).await;
+
+        // The 20x jump is held at current rates, the modest change applies
+        assert_eq!(rates("gpt-4o"), (gpt4o_input, gpt4o_output));
+        assert!((rates("gpt-4o-mini").0 - mini_input * 1.5).abs() < 1e-15);
+
+        let held = service.held_rate_changes();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].model, "gpt-4o");
+        assert!((held[0].ratio.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(held[0].synced_input_cost_per_token, gpt4o_input * 20.0);
+
+        // The saved file keeps the held model at its current rates
+        let saved = PricingData::from_json_str(
+            &std::fs::read_to_string(&service.local_path).expect("pricing data saved"),
+        )
+        .unwrap();
+        assert_eq!(saved.models["gpt-4o"].input_cost_per_token, gpt4o_input);
+        assert!(
+            (saved.models["gpt-4o-mini"].input_cost_per_token - mini_input * 1.5).abs() < 1e-15
+        );
+
+        let _ = std::fs::remove_file(&service.local_path);
+    }
+
+    #[test]
+    fn test_hold_rate_spikes_checks_zero_and_cache_rates() {
+        let current = PricingData::from_json_str(
+            r#"{
+                "free-model": {"input_cost_per_token": 0.0, "output_cost_per_token": 0.000002},
+                "cached-model": {
+                    "input_cost_per_token": 0.000001,
+                    "output_cost_per_token": 0.000002,
+                    "cache_read_input_token_cost": 0.0000001
+                },
+                "accepted-model": {"input_cost_per_token": 0.000001, "output_cost_per_token": 0.000002}
+            }"#,
+        )
+        .unwrap();
+        let mut synced = PricingData::from_json_str(
+            r#"{
+                "free-model": {"input_cost_per_token": 0.000001, "output_cost_per_token": 0.000002},
+                "cached-model": {
+                    "input_cost_per_token": 0.000001,
+                    "output_cost_per_token": 0.000002,
+                    "cache_read_input_token_cost": 0.000005,
+                    "max_input_tokens": 1000
+                },
+                "accepted-model": {"input_cost_per_token": 0.0001, "output_cost_per_token": 0.000002}
+            }"#,
+        )
+        .unwrap();
+        let accepted = HashSet::from(["accepted-model".to_string()]);
+
+        let held = synced.hold_rate_spikes(&current, 10.0, &accepted);
+        let models: Vec<_> = held.iter().map(|h| h.model.as_str()).collect();
+        assert_eq!(models, ["cached-model", "free-model"]);
+
+        // An input rate appearing has no finite ratio
+        assert_eq!(held[1].ratio, None);
+        assert_eq!(synced.models["free-model"].input_cost_per_token, 0.0);
+
+        // Only the guarded rates are restored, other synced fields stay
+        let cached = &synced.models["cached-model"];
+        assert_eq!(cached.cache_read_input_token_cost, 0.0000001);
+        assert_eq!(cached.max_input_tokens, Some(1000));
+
+        assert_eq!(synced.models["accepted-model"].input_cost_per_token, 0.0001);
+    }
+
+    #[test]
+    fn test_sync_interval_backs_off_and_resets() {
+        let service = PricingService::init_for_test().unwrap();
+        let base = Duration::from_secs(4 * 3600);
+        let cap = Duration::from_secs(PRICING_SYNC_MAX_BACKOFF_SECS);
+
+        // Below the threshold the configured interval is kept
+        for _ in 1..PRICING_SYNC_BACKOFF_THRESHOLD {
+            service.record_sync_failure();
+            assert_eq!(service.effective_sync_interval(base), base);
+        }
+
+        // Then it doubles per failure until the cap
+        service.record_sync_failure();
+        assert_eq!(service.effective_sync_interval(base), base * 2);
+        service.record_sync_failure();
+        assert_eq!(service.effective_sync_interval(base), base * 4);
+        for _ in 0..40 {
+            service.record_sync_failure();
+        }
+        assert_eq!(service.effective_sync_interval(base), cap);
+
+        // An interval already above the cap is never shortened
+        let long = cap * 2;
+        assert_eq!(service.effective_sync_interval(long), long);
+
+        // A successful fetch resets the backoff
+        service.record_sync_success();
+        assert_eq!(service.effective_sync_interval(base), base);
+    }
+
     #[test]
     fn test_apply_sync_data_percentage_threshold() {
         // Test that sync accepts data with 51% of models (above 50% threshold)
@@ -2036,6 +3739,137 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lookup_vertex_ai_maas_partner_models() {
+        let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
+        let llama = data
+            .models
+            .get("vertex_ai/meta/llama-3.1-405b-instruct-maas")
+            .expect("pricing data should include Llama 3.1 405B MaaS");
+        for model in [
+            "meta/llama-3.1-405b-instruct-maas",
+            "meta/llama-3.1-405b-instruct",
+            "llama-3.1-405b-instruct-maas",
+            "llama-3.1-405b-instruct",
+        ] {
+            let (pricing, _) = data
+                .lookup(Some("vertex_ai"), model)
+                .unwrap_or_else(|| panic!("Should find Vertex MaaS model: {}", model));
+            assert!(std::ptr::eq(pricing, llama), "wrong pricing for {}", model);
+        }
+
+        let (pricing, match_type) = data
+            .lookup(Some("vertex_ai"), "mistralai/mistral-medium-3")
+            .unwrap();
+        assert!(std::ptr::eq(
+            pricing,
+            &data.models["vertex_ai/mistralai/mistral-medium-3"]
+        ));
+        assert_eq!(match_type, MatchType::ProviderPrefix);
+
+        // -maas suffix is recognized even without a system
+        assert!(
+            data.lookup(None, "deepseek-ai/deepseek-v3.1-maas")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_lookup_vertex_ai_maas_unknown_model_not_found() {
+        let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
+        assert!(
+            data.lookup(Some("vertex_ai"), "meta/llama-99-unknown-instruct-maas")
+                .is_none()
+        );
+        assert!(
+            data.lookup(Some("vertex_ai"), "acme/unknown-model-maas")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_family_pricing_across_providers() {
+        let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
+        let rates = data.family_pricing("claude-sonnet-4-5");
+
+        let by_provider: HashMap<&str, &str> = rates
+            .iter()
+            .map(|(key, pricing)| (pricing.litellm_provider.as_str(), *key))
+            .collect();
+        assert_eq!(by_provider.get("anthropic"), Some(&"claude-sonnet-4-5"));
+        assert_eq!(
+            by_provider.get("bedrock_converse"),
+            Some(&"anthropic.claude-sonnet-4-5-20250929-v1:0")
+        );
+        assert_eq!(
+            by_provider.get("vertex_ai-anthropic_models"),
+            Some(&"vertex_ai/claude-sonnet-4-5")
+        );
+
+        for (_, pricing) in &rates {
+            if [
+                "anthropic",
+                "bedrock_converse",
+                "vertex_ai-anthropic_models",
+            ]
+            .contains(&pricing.litellm_provider.as_str())
+            {
+                assert!(pricing.input_cost_per_token > 0.0);
+                assert!(pricing.output_cost_per_token > pricing.input_cost_per_token);
+                assert!(pricing.cache_read_input_token_cost > 0.0);
+            }
+        }
+
+        // Dated or provider-specific input resolves to the same family
+        assert_eq!(
+            data.family_pricing("vertex_ai/claude-sonnet-4-5@20250929")
+                .len(),
+            rates.len()
+        );
+        assert!(data.family_pricing("claude-sonnet-99").is_empty());
+    }
+
+    #[test]
+    fn test_model_family() {
+        assert_eq!(
+            model_family("claude-sonnet-4-5-20250929"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(
+            model_family("vertex_ai/claude-sonnet-4-5@20250929"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(
+            model_family("us-gov.anthropic.claude-sonnet-4-5-20250929-v1:0"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(
+            model_family("bedrock/us-gov-east-1/claude-sonnet-4-5-20250929-v1:0"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(model_family("gpt-4o-2024-11-20"), "gpt-4o");
+    }
+
+    #[test]
+    fn test_vertex_maas_candidates() {
+        let candidates = vertex_maas_candidates("meta/llama-3.1-8b-instruct-maas");
+        assert_eq!(
+            candidates,
+            vec![
+                "vertex_ai/meta/llama-3.1-8b-instruct-maas",
+                "vertex_ai/meta/llama-3.1-8b-instruct",
+                "vertex_ai/llama-3.1-8b-instruct-maas",
+                "vertex_ai/llama-3.1-8b-instruct",
+                "llama-3.1-8b-instruct",
+            ]
+        );
+        assert_eq!(
+            vertex_maas_candidates("gpt-oss-20b").len(),
+            VERTEX_MAAS_PUBLISHERS.len() * 2 + 3
+        );
+        assert!(vertex_maas_candidates("projects/p/publishers/meta/models/llama").is_empty());
+    }
+
     #[test]
     fn test_lookup_vertex_ai_image_models() {
         let data = PricingData::from_json_str(EMBEDDED_PRICING_JSON).unwrap();
@@ -3356,4 +5190,25 @@ mod tests {
         );
         assert_eq!(result.unwrap().1, MatchType::ProviderPrefix);
     }
+
+    #[tokio::test]
+    async fn test_sync_lease_claimed_once_per_round() {
+        let cache = CacheService::new(&CacheConfig {
+            backend: CacheBackendType::Memory,
+            max_entries: 100,
+            eviction_policy: EvictionPolicy::TinyLfu,
+            redis_url: None,
+            redis_pool_size: 1,
+        })
+        .await
+        .unwrap();
+        let lease = Duration::from_millis(50);
+
+        assert!(claim_sync_lease(&cache, lease).await);
+        assert!(!claim_sync_lease(&cache, lease).await);
+
+        // Next round after the lease expired
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert!(claim_sync_lease(&cache, lease).await);
+    }
 }
@@ -0,0 +1,186 @@
+//! Models that could not be priced, for pricing coverage reports
+//!
+//! Entries are deduplicated by `(system, model)` with a hit count and
+//! first/last-seen timestamps. The set is bounded: when full, the entry seen
+//! least recently is evicted. It can be persisted to the data directory and
+//! merged back on startup so coverage accumulates across restarts.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::PricingError;
+
+/// A model with no pricing match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UnmatchedModel {
+    /// `gen_ai.system` reported with the model, if any
+    pub system: Option<String>,
+    pub model: String,
+    /// Cost calculations that found no pricing for this model
+    pub count: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+type Key = (Option<String>, String);
+
+/// Bounded, deduplicated set of unmatched models
+#[derive(Debug)]
+pub struct UnmatchedModels {
+    entries: RwLock<HashMap<Key, UnmatchedModel>>,
+    /// Maximum distinct entries kept (0 disables tracking)
+    limit: usize,
+}
+
+impl UnmatchedModels {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            limit,
+        }
+    }
+
+    /// Count a pricing miss for `model`
+    pub fn record(&self, system: Option<&str>, model: &str) {
+        self.record_at(system, model, Utc::now());
+    }
+
+    fn record_at(&self, system: Option<&str>, model: &str, now: DateTime<Utc>) {
+        self.merge(UnmatchedModel {
+            system: system.map(String::from),
+            model: model.to_string(),
+            count: 1,
+            first_seen: now,
+            last_seen: now,
+        });
+    }
+
+    /// Add an entry's count and widen its seen range, inserting it if new
+    fn merge(&self, entry: UnmatchedModel) {
+        if self.limit == 0 {
+            return;
+        }
+        let mut entries = self.entries.write();
+        let key = (entry.system.clone(), entry.model.clone());
+        if let Some(existing) = entries.get_mut(&key) {
+            existing.count = existing.count.saturating_add(entry.count);
+            existing.first_seen = existing.first_seen.min(entry.first_seen);
+            existing.last_seen = existing.last_seen.max(entry.last_seen);
+            return;
+        }
+        if entries.len() >= self.limit {
+            let stalest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_seen)
+                .map(|(k, _)| k.clone());
+            match stalest {
+                Some(k) if entries[&k].last_seen <= entry.last_seen => {
+                    entries.remove(&k);
+                }
+                _ => return,
+            }
+        }
+        entries.insert(key, entry);
+    }
+
+    /// All entries, most frequent first
+    pub fn snapshot(&self) -> Vec<UnmatchedModel> {
+        let mut models: Vec<UnmatchedModel> = self.entries.read().values().cloned().collect();
+        models.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+                .then_with(|| a.model.cmp(&b.model))
+        });
+        models
+    }
+
+    /// Merge entries persisted by a previous run (missing file is not an error)
+    pub async fn load(&self, path: &Path) -> Result<(), PricingError> {
+        let json = match tokio::fs::read_to_string(path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let persisted: Vec<UnmatchedModel> =
+            serde_json::from_str(&json).map_err(|e| PricingError::ParseError(e.to_string()))?;
+        for entry in persisted {
+            self.merge(entry);
+        }
+        Ok(())
+    }
+
+    /// Write all entries to `path` atomically (write to temp, then rename)
+    pub async fn save(&self, path: &Path) -> Result<(), PricingError> {
+        let json = serde_json::to_string(&self.snapshot())
+            .map_err(|e| PricingError::ParseError(e.to_string()))?;
+        let temp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&temp_path, json).await?;
+
+        // Windows-safe atomic replace: remove destination first if exists
+        #[cfg(target_os = "windows")]
+        if path.exists() {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+
+        tokio::fs::rename(&temp_path, path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_bounded_set_evicts_least_recently_seen() {
+        let models = UnmatchedModels::new(2);
+        models.record_at(Some("openai"), "a", at(0));
+        models.record_at(Some("openai"), "b", at(1));
+        models.record_at(Some("openai"), "a", at(2));
+        models.record_at(None, "c", at(3));
+
+        let names: Vec<String> = models.snapshot().into_iter().map(|m| m.model).collect();
+        assert_eq!(names, vec!["a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_entries_survive_restart_and_merge_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("unmatched_models.json");
+
+        let before = UnmatchedModels::new(10);
+        before.record_at(Some("openai"), "gpt-internal", at(0));
+        before.record_at(Some("openai"), "gpt-internal", at(10));
+        before.record_at(None, "my-finetune", at(5));
+        before.save(&path).await.unwrap();
+
+        // Simulated restart: new process loads the file, then sees more misses
+        let after = UnmatchedModels::new(10);
+        after.record_at(Some("openai"), "gpt-internal", at(100));
+        after.load(&path).await.unwrap();
+
+        let snapshot = after.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].model, "gpt-internal");
+        assert_eq!(snapshot[0].count, 3);
+        assert_eq!(snapshot[0].first_seen, at(0));
+        assert_eq!(snapshot[0].last_seen, at(100));
+        assert_eq!(snapshot[1].model, "my-finetune");
+        assert_eq!(snapshot[1].count, 1);
+
+        // Missing file on first start is fine
+        let fresh = UnmatchedModels::new(10);
+        fresh.load(&dir.path().join("missing.json")).await.unwrap();
+        assert!(fresh.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+//! Citation extraction for search-augmented responses.
+//!
+//! Providers attach the sources behind a grounded answer in different shapes:
+//! - Gemini: `groundingMetadata.groundingChunks` (with `groundingSupports`
+//!   linking answer segments to chunks) and `citationMetadata.citations`
+//! - OpenAI: `url_citation` annotations on the message (Chat Completions) or
+//!   on `output_text` content parts (Responses API)
+//!
+//! Gemini and other message-level metadata is read from the raw context blocks
+//! built by `extract_citation_contexts`, so both share one field list. Each
+//! source becomes a `Context` block with `context_type = "citation"` and
+//! `{url, title, snippet}` data, so the feed can list sources uniformly.
+
+use serde_json::{Value as JsonValue, json};
+
+use super::types::ContentBlock;
+
+/// Context type for normalized citation blocks
+pub const CITATION_CONTEXT_TYPE: &str = "citation";
+
+#[derive(Debug, Default, PartialEq)]
+struct Citation {
+    url: Option<String>,
+    title: Option<String>,
+    snippet: Option<String>,
+}
+
+impl Citation {
+    fn into_block(self) -> ContentBlock {
+        let mut data = serde_json::Map::new();
+        for (key, value) in [
+            ("url", self.url),
+            ("title", self.title),
+            ("snippet", self.snippet),
+        ] {
+            if let Some(value) = value {
+                data.insert(key.to_string(), json!(value));
+            }
+        }
+        ContentBlock::Context {
+            data: JsonValue::Object(data),
+            context_type: Some(CITATION_CONTEXT_TYPE.to_string()),
+        }
+    }
+}
+
+/// Extract one citation block per cited source (deduplicated by URL).
+///
+/// `contexts` are the raw citation context blocks of the same message.
+pub(super) fn extract_citations(raw: &JsonValue, contexts: &[ContentBlock]) -> Vec<ContentBlock> {
+    let mut citations = Vec::new();
+    for block in contexts {
+        let ContentBlock::Context {
+            data,
+            context_type: Some(context_type),
+        } = block
+        else {
+            continue;
+        };
+        match context_type.as_str() {
+            "grounding" => citations.extend(gemini_grounding(data)),
+            "citations" => citations.extend(citation_list(data)),
+            _ => {}
+        }
+    }
+    citations.extend(openai_annotations(raw));
+
+    let mut seen = std::collections::HashSet::new();
+    citations
+        .into_iter()
+        .filter(|c| c.url.is_some() || c.title.is_some())
+        .filter(|c| c.url.as_ref().is_none_or(|url| seen.insert(url.clone())))
+        .map(Citation::into_block)
+        .collect()
+}
+
+fn get_any<'a>(value: &'a JsonValue, keys: &[&str]) -> Option<&'a JsonValue> {
+    keys.iter().find_map(|k| value.get(*k))
+}
+
+fn get_str(value: &JsonValue, keys: &[&str]) -> Option<String> {
+    get_any(value, keys)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .map(String::from)
+}
+
+/// Gemini `groundingMetadata`: chunks are the sources, supports carry the
+/// answer segment each chunk backs (used as the snippet).
+fn gemini_grounding(metadata: &JsonValue) -> Vec<Citation> {
+    let Some(chunks) =
+        get_any(metadata, &["groundingChunks", "grounding_chunks"]).and_then(|c| c.as_array())
+    else {
+        return Vec::new();
+    };
+    let supports = get_any(metadata, &["groundingSupports", "grounding_supports"])
+        .and_then(|s| s.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, chunk)| {
+            let source = get_any(chunk, &["web", "retrievedContext", "retrieved_context"])?;
+            let snippet = supports
+                .iter()
+                .find(|support| {
+                    get_any(
+                        support,
+                        &["groundingChunkIndices", "grounding_chunk_indices"],
+                    )
+                    .and_then(|i| i.as_array())
+                    .is_some_and(|indices| indices.iter().any(|i| i.as_u64() == Some(index as u64)))
+                })
+                .and_then(|support| support.get("segment"))
+                .and_then(|segment| get_str(segment, &["text"]))
+                .or_else(|| get_str(source, &["text"]));
+            Some(Citation {
+                url: get_str(source, &["uri", "url"]),
+                title: get_str(source, &["title"]),
+                snippet,
+            })
+        })
+        .collect()
+}
+
+/// Citation lists: Gemini `citationMetadata` (an object wrapping the list) or
+/// a bare list of source objects or URLs.
+fn citation_list(data: &JsonValue) -> Vec<Citation> {
+    let items = match data {
+        JsonValue::Array(items) => items,
+        _ => match get_any(data, &["citations", "citationSources", "citation_sources"]) {
+            Some(JsonValue::Array(items)) => items,
+            _ => return Vec::new(),
+        },
+    };
+    items
+        .iter()
+        .map(|item| match item.as_str() {
+            Some(url) => Citation {
+                url: Some(url.to_string()).filter(|u| !u.trim().is_empty()),
+                ..Default::default()
+            },
+            None => Citation {
+                url: get_str(item, &["uri", "url"]),
+                title: get_str(item, &["title"]),
+                snippet: None,
+            },
+        })
+        .collect()
+}
+
+/// OpenAI `url_citation` annotations on the message and on content parts.
+///
+/// The snippet is the cited span of the answer text (`start_index..end_index`).
+fn openai_annotations(raw: &JsonValue) -> Vec<Citation> {
+    let mut citations = Vec::new();
+    let message_text = raw.get("content").and_then(|c| c.as_str());
+    if let Some(annotations) = raw.get("annotations").and_then(|a| a.as_array()) {
+        citations.extend(url_citations(annotations, message_text));
+    }
+    if let Some(parts) = raw.get("content").and_then(|c| c.as_array()) {
+        for part in parts {
+            if let Some(annotations) = part.get("annotations").and_then(|a| a.as_array()) {
+                let text = part.get("text").and_then(|t| t.as_str());
+                citations.extend(url_citations(annotations, text));
+            }
+        }
+    }
+    citations
+}
+
+fn url_citations(annotations: &[JsonValue], text: Option<&str>) -> Vec<Citation> {
+    annotations
+        .iter()
+        .filter(|a| a.get("type").and_then(|t| t.as_str()) == Some("url_citation"))
+        .map(|a| {
+            // Chat Completions nests fields under `url_citation`; Responses API is flat
+            let fields = a.get("url_citation").unwrap_or(a);
+            let snippet = text.and_then(|text| {
+                let start = fields.get("start_index")?.as_u64()? as usize;
+                let end = fields.get("end_index")?.as_u64()? as usize;
+                let cited: String = text
+                    .chars()
+                    .skip(start)
+                    .take(end.saturating_sub(start))
+                    .collect();
+                Some(cited).filter(|s| !s.trim().is_empty())
+            });
+            Citation {
+                url: get_str(fields, &["url"]),
+                title: get_str(fields, &["title"]),
+                snippet,
+            }
+        })
+        .collect()
+}
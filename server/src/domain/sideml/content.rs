@@ -816,7 +816,7 @@ fn try_gemini_format(block: &JsonValue) -> Option<JsonValue> {
         return Some(json!({"type": "thinking", "text": thinking, "signature": null}));
     }
 
-    // Gemini/ADK thought block: {"text": "...", "thought": true}
+    // Gemini/ADK thought block: {"text": "...", "thought": true, "thoughtSignature": "..."}
     // ADK sends thinking content as text blocks with a thought flag
     if let Some(obj) = block.as_object()
         && obj
@@ -824,7 +824,11 @@ fn try_gemini_format(block: &JsonValue) -> Option<JsonValue> {
             .is_some_and(|t| t.as_bool().unwrap_or(false))
         && let Some(text) = obj.get("text").and_then(|t| t.as_str())
     {
-        return Some(json!({"type": "thinking", "text": text, "signature": null}));
+        let signature = obj
+            .get("thought_signature")
+            .or_else(|| obj.get("thoughtSignature"))
+            .and_then(|s| s.as_str());
+        return Some(json!({"type": "thinking", "text": text, "signature": signature}));
     }
 
     // inline_data (base64)
@@ -1940,6 +1944,20 @@ mod tests {
         assert_eq!(normalized["signature"], serde_json::Value::Null);
     }
 
+    #[test]
+    fn test_gemini_thought_block_keeps_signature() {
+        let block = json!({
+            "text": "Checking the units first.",
+            "thought": true,
+            "thoughtSignature": "CiQB0e2Kb3"
+        });
+
+        let normalized = try_gemini_format(&block).unwrap();
+        assert_eq!(normalized["type"], "thinking");
+        assert_eq!(normalized["text"], "Checking the units first.");
+        assert_eq!(normalized["signature"], "CiQB0e2Kb3");
+    }
+
     #[test]
     fn test_gemini_adk_thought_false_not_thinking() {
         // thought=false should NOT be treated as thinking
@@ -0,0 +1,202 @@
+//! Display-only content anonymization for the feed pipeline.
+//!
+//! Replaces user-visible text with deterministic lorem-ipsum of the same length
+//! so screenshots and demos keep a realistic layout without leaking content.
+//! Roles, tool names, ids, token counts and costs are left untouched.
+//!
+//! Tool inputs/outputs and other JSON payloads keep their shape: object keys,
+//! array lengths, booleans and nulls are preserved, strings become lorem of the
+//! same length and numbers get fake digits of the same width.
+
+use serde_json::Value as JsonValue;
+
+use super::types::BlockEntry;
+use crate::domain::sideml::types::ContentBlock;
+
+const LOREM_WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+];
+
+/// JSON keys whose string values are structural discriminators, not content.
+const STRUCTURAL_KEYS: &[&str] = &["type", "role"];
+
+/// Anonymize the content of all blocks in place.
+pub(super) fn anonymize_blocks(blocks: &mut [BlockEntry]) {
+    for block in blocks.iter_mut() {
+        anonymize_content(&mut block.content);
+    }
+}
+
+fn anonymize_content(block: &mut ContentBlock) {
+    match block {
+        ContentBlock::Text { text } => *text = lorem_like(text),
+        ContentBlock::Thinking { text, .. } => *text = lorem_like(text),
+        ContentBlock::Refusal { message } => *message = lorem_like(message),
+        ContentBlock::ToolUse { input, .. } => *input = anonymize_json(input),
+        ContentBlock::ToolResult { content, .. } => *content = anonymize_json(content),
+        ContentBlock::Json { data } => *data = anonymize_json(data),
+        ContentBlock::Context { data, .. } => *data = anonymize_json(data),
+        ContentBlock::Unknown { raw } => *raw = anonymize_json(raw),
+        ContentBlock::Image { .. }
+        | ContentBlock::Audio { .. }
+        | ContentBlock::Video { .. }
+        | ContentBlock::Document { .. }
+        | ContentBlock::File { .. }
+        | ContentBlock::ToolDefinitions { .. }
+        | ContentBlock::RedactedThinking { .. } => {}
+    }
+}
+
+/// Structure-preserving fake JSON value.
+fn anonymize_json(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::String(s) => JsonValue::String(lorem_like(s)),
+        JsonValue::Number(n) => fake_number(n),
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(anonymize_json).collect()),
+        JsonValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(key, val)| {
+                    let val = match val {
+                        JsonValue::String(_) if STRUCTURAL_KEYS.contains(&key.as_str()) => {
+                            val.clone()
+                        }
+                        _ => anonymize_json(val),
+                    };
+                    (key.clone(), val)
+                })
+                .collect(),
+        ),
+        JsonValue::Bool(_) | JsonValue::Null => value.clone(),
+    }
+}
+
+/// Replace every digit with a deterministic fake digit, keeping sign, dot and exponent.
+fn fake_number(n: &serde_json::Number) -> JsonValue {
+    let original = n.to_string();
+    let mut seed = seed_for(&original);
+    let faked: String = original
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if !c.is_ascii_digit() {
+                return c;
+            }
+            seed = next_seed(seed);
+            // Avoid a leading zero so the width (and magnitude) stays the same
+            let leading = i == 0 || original[..i].ends_with('-');
+            let digit = if leading { 1 + seed % 9 } else { seed % 10 };
+            char::from(b'0' + digit as u8)
+        })
+        .collect();
+    serde_json::from_str(&faked).unwrap_or(JsonValue::Number(n.clone()))
+}
+
+/// Deterministic lorem-ipsum with the same line structure and line lengths.
+fn lorem_like(text: &str) -> String {
+    text.split('\n')
+        .map(lorem_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn lorem_line(line: &str) -> String {
+    let body = line.trim_start();
+    let indent = &line[..line.len() - body.len()];
+    let target = body.chars().count();
+    if target == 0 {
+        return line.to_string();
+    }
+
+    let mut seed = seed_for(body);
+    let mut out = String::with_capacity(indent.len() + target);
+    out.push_str(indent);
+    let mut len = 0;
+    while len < target {
+        if len > 0 {
+            out.push(' ');
+            len += 1;
+        }
+        seed = next_seed(seed);
+        let word = LOREM_WORDS[(seed % LOREM_WORDS.len() as u64) as usize];
+        out.push_str(word);
+        len += word.len();
+    }
+    // Lorem words are ASCII, so byte truncation is char-safe
+    out.truncate(indent.len() + target);
+    out
+}
+
+/// Seed derived from a stable hash, so the same text anonymizes identically
+/// across processes and Rust releases.
+fn seed_for(s: &str) -> u64 {
+    let hash = blake3::hash(s.as_bytes());
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_le_bytes(seed)
+}
+
+#[inline]
+fn next_seed(seed: u64) -> u64 {
+    seed.wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407)
+        >> 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lorem_like_preserves_length_and_lines() {
+        let text = "Hello world, my SSN is 123\n  indented secret";
+        let out = lorem_like(text);
+        assert_ne!(out, text);
+        assert_eq!(out.lines().count(), 2);
+        for (orig, fake) in text.lines().zip(out.lines()) {
+            assert_eq!(orig.chars().count(), fake.chars().count());
+        }
+        assert!(out.lines().nth(1).unwrap().starts_with("  "));
+        assert_eq!(out, lorem_like(text), "must be deterministic");
+    }
+
+    #[test]
+    fn test_anonymize_json_preserves_structure() {
+        let input = json!({
+            "query": "secret",
+            "limit": 25,
+            "exact": true,
+            "items": [{"type": "text", "text": "private"}]
+        });
+        let out = anonymize_json(&input);
+
+        assert_ne!(out["query"], input["query"]);
+        assert_eq!(out["query"].as_str().unwrap().len(), 6);
+        assert!(
+            out["limit"]
+                .as_u64()
+                .is_some_and(|n| (10..100).contains(&n))
+        );
+        assert_eq!(out["exact"], json!(true));
+        assert_eq!(out["items"][0]["type"], json!("text"));
+        assert_ne!(out["items"][0]["text"], json!("private"));
+    }
+}
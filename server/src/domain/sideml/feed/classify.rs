@@ -139,8 +139,12 @@ mod tests {
             category,
             content_hash: "hash".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
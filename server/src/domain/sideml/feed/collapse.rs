@@ -0,0 +1,49 @@
+//! Display-only collapsing of repeated system prompts.
+//!
+//! Agents resend the same system prompt on every call, so multi-trace feeds
+//! repeat the same boilerplate once per trace. The first occurrence keeps its
+//! full content; later blocks with the same fingerprint get a short marker and
+//! `repeat_of` pointing at the span that carries the original.
+//!
+//! Runs after history detection and dedup, which still see the real content,
+//! and after the block cap, so `repeat_of` always names a block in the same
+//! result (or feed page).
+
+use std::collections::HashMap;
+
+use super::types::BlockEntry;
+use crate::domain::sideml::types::{ChatRole, ContentBlock};
+
+/// Marker text shown in place of a collapsed system prompt.
+pub(super) const REPEATED_SYSTEM_MARKER: &str = "[Same system prompt as previous]";
+
+/// Collapse System-role blocks whose fingerprint was already seen.
+///
+/// `newest_first` tells the block order (feeds sort DESC, conversations ASC);
+/// either way the oldest occurrence keeps its content. The fingerprint is the
+/// block's `content_hash`, which is stable across traces for identical content.
+pub(super) fn collapse_repeated_system(blocks: &mut [BlockEntry], newest_first: bool) {
+    let mut first_seen: HashMap<String, String> = HashMap::new();
+    let mut collapse = |block: &mut BlockEntry| {
+        if block.role != ChatRole::System {
+            return;
+        }
+        match first_seen.get(&block.content_hash) {
+            Some(span_id) => {
+                block.repeat_of = Some(span_id.clone());
+                block.content = ContentBlock::Text {
+                    text: REPEATED_SYSTEM_MARKER.to_string(),
+                };
+            }
+            None => {
+                first_seen.insert(block.content_hash.clone(), block.span_id.clone());
+            }
+        }
+    };
+
+    if newest_first {
+        blocks.iter_mut().rev().for_each(&mut collapse);
+    } else {
+        blocks.iter_mut().for_each(&mut collapse);
+    }
+}
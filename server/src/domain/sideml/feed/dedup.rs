@@ -38,11 +38,15 @@
 //! - Event source (+2) - vs attribute source
 //! - Has model info (+1)
 //!
+//! Equal scores fall back to the lowest `(span_id, message_index, entry_index)`,
+//! so the same input always keeps the same copy.
+//!
 //! # Ordering
 //!
 //! For messages with the same birth time, original message order is preserved
 //! (by message_index, then entry_index). This maintains the order as it
-//! appeared in the source data.
+//! appeared in the source data. Remaining ties are broken by trace and span id,
+//! so output never depends on hash map iteration order.
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -673,24 +677,36 @@ fn deduplicate_blocks(blocks: Vec<BlockEntry>) -> Vec<BlockEntry> {
     // Identity-based dedup: non-history will win due to quality scoring.
     // For tool results with tool_use_id, this collapses all versions
     // (raw + transformed) into the highest quality one.
-    let mut candidates: HashMap<MessageIdentity, (BlockEntry, u32)> = HashMap::new();
+    // Candidates stay in first-seen order (the map only indexes them) so the
+    // output never depends on hash iteration order.
+    let mut slots: HashMap<MessageIdentity, usize> = HashMap::new();
+    let mut candidates: Vec<(BlockEntry, u32)> = Vec::new();
 
     for block in blocks {
         let identity = MessageIdentity::from_block(&block);
         let quality = compute_quality(&block);
 
-        candidates
-            .entry(identity)
-            .and_modify(|(existing, existing_quality)| {
-                if quality > *existing_quality {
-                    *existing = block.clone();
+        match slots.get(&identity) {
+            Some(&slot) => {
+                let (existing, existing_quality) = &mut candidates[slot];
+                let wins = match quality.cmp(existing_quality) {
+                    Ordering::Greater => true,
+                    Ordering::Less => false,
+                    Ordering::Equal => tie_break_key(&block) < tie_break_key(existing),
+                };
+                if wins {
+                    *existing = block;
                     *existing_quality = quality;
                 }
-            })
-            .or_insert((block, quality));
+            }
+            None => {
+                slots.insert(identity, candidates.len());
+                candidates.push((block, quality));
+            }
+        }
     }
 
-    let result: Vec<_> = candidates.into_values().map(|(block, _)| block).collect();
+    let result: Vec<_> = candidates.into_iter().map(|(block, _)| block).collect();
 
     tracing::trace!(
         input = input_count,
@@ -705,6 +721,11 @@ fn deduplicate_blocks(blocks: Vec<BlockEntry>) -> Vec<BlockEntry> {
     result
 }
 
+/// Stable key deciding between equal-quality duplicates: the lowest wins.
+fn tie_break_key(block: &BlockEntry) -> (&str, i32, i32) {
+    (&block.span_id, block.message_index, block.entry_index)
+}
+
 // ============================================================================
 // PUBLIC API
 // ============================================================================
@@ -787,7 +808,15 @@ pub fn process_dedup(
             other => return other,
         }
 
-        a.entry_index.cmp(&b.entry_index)
+        match a.entry_index.cmp(&b.entry_index) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // Full tie across spans: order by span so the result is reproducible
+        a.trace_id
+            .cmp(&b.trace_id)
+            .then_with(|| a.span_id.cmp(&b.span_id))
     });
 
     // Materialize computed timestamps for API clients.
@@ -852,8 +881,12 @@ mod tests {
             category: MessageCategory::GenAIUserMessage,
             content_hash: "test".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -897,10 +930,14 @@ mod tests {
             category: MessageCategory::GenAIChoice,
             content_hash: "test".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             // ToolUse uses event_time (not span_end) - the decision to call a tool
             // happens DURING generation, not at completion. See classify::uses_span_end().
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -944,8 +981,12 @@ mod tests {
             category: MessageCategory::GenAIToolMessage,
             content_hash: "test".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false, // Tool results are INPUT
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -1115,6 +1156,56 @@ mod tests {
         assert_eq!(result[0].span_id, "span1"); // Keep original
     }
 
+    #[test]
+    fn test_tied_duplicates_resolve_deterministically() {
+        let t0 = utc(0);
+        let mut reply = make_test_block("trace1", "span_a", ChatRole::Assistant, "World", t0);
+        reply.message_index = 1;
+        let blocks = vec![
+            make_test_block("trace1", "span_c", ChatRole::User, "Hello", t0),
+            make_test_block("trace1", "span_a", ChatRole::User, "Hello", t0),
+            make_test_block("trace1", "span_b", ChatRole::Assistant, "World", t0),
+            reply,
+            make_test_block("trace1", "span_d", ChatRole::User, "Again", t0),
+        ];
+        let span_timestamps: HashMap<String, SpanTimestamps> =
+            ["span_a", "span_b", "span_c", "span_d"]
+                .into_iter()
+                .map(|id| {
+                    (
+                        id.to_string(),
+                        SpanTimestamps {
+                            span_start: t0,
+                            span_end: Some(t0),
+                        },
+                    )
+                })
+                .collect();
+
+        let expected = process_dedup(blocks.clone(), span_timestamps.clone());
+        assert_eq!(expected.len(), 3);
+        // Equal quality: the lowest span id wins
+        for block in expected
+            .iter()
+            .filter(|b| !matches!(&b.content, ContentBlock::Text { text } if text == "Again"))
+        {
+            assert_eq!(block.span_id, "span_a");
+        }
+
+        // Same blocks in any input order, repeatedly, yield identical output
+        for rotation in 0..blocks.len() {
+            let mut input = blocks.clone();
+            input.rotate_left(rotation);
+            for reversed in [false, true] {
+                if reversed {
+                    input.reverse();
+                }
+                let result = process_dedup(input.clone(), span_timestamps.clone());
+                assert_eq!(format!("{result:?}"), format!("{expected:?}"));
+            }
+        }
+    }
+
     #[test]
     fn test_same_content_different_traces_both_kept() {
         let t0 = utc(0);
@@ -0,0 +1,21 @@
+//! Display-only latency annotation between consecutive blocks.
+//!
+//! Each block gets the elapsed time since the block before it, so long waits
+//! (e.g. between a tool call and its result) stand out. Runs after sorting and
+//! does not affect history detection, dedup or ordering.
+
+use chrono::{DateTime, Utc};
+
+use super::types::BlockEntry;
+
+/// Set `gap_ms` on each block to the time since the previous block.
+///
+/// Blocks must be in chronological order. The first block has no previous
+/// block and keeps `gap_ms = None`.
+pub(super) fn annotate_gaps(blocks: &mut [BlockEntry]) {
+    let mut previous: Option<DateTime<Utc>> = None;
+    for block in blocks.iter_mut() {
+        block.gap_ms = previous.map(|prev| (block.timestamp - prev).num_milliseconds());
+        previous = Some(block.timestamp);
+    }
+}
@@ -27,7 +27,8 @@
 //! The algorithm uses multiple signals to identify history:
 //!
 //! 1. **Protected = Current**: GenAIChoice, finish_reason → always kept
-//! 2. **Timestamp-based**: Message timestamp < span start → historical context
+//! 2. **Timestamp-based**: Message timestamp < span start (minus clock-skew
+//!    tolerance) → historical context
 //! 3. **Tool linking**: Tool_results are current iff their tool_use_id is current
 //! 4. **Intermediate filtering**: Assistant text in generation spans (when agent
 //!    spans exist) without finish_reason → intermediate output
@@ -45,6 +46,8 @@
 
 use std::collections::{HashMap, HashSet};
 
+use chrono::TimeDelta;
+
 use super::dedup::{SpanTimestamps, effective_timestamp};
 use super::types::BlockEntry;
 use crate::domain::sideml::types::{ChatRole, ContentBlock};
@@ -156,7 +159,7 @@ fn detect_session_history(blocks: &[BlockEntry]) -> SessionHistoryInfo {
 /// # Algorithm
 ///
 /// 1. Build set of current tool_use_ids (from protected blocks and agent spans)
-/// 2. Timestamp-based: Mark messages with timestamp < span_start
+/// 2. Timestamp-based: Mark messages with timestamp < span_start - clock_skew_tolerance
 /// 3. Accumulator spans: Mark input events from non-root accumulator spans
 /// 4. Intermediate text: Mark assistant text from generation spans (when has_agent_spans)
 /// 5. Multi-turn: If tool_results in generation, mark all unprotected generation content
@@ -165,6 +168,7 @@ fn detect_session_history(blocks: &[BlockEntry]) -> SessionHistoryInfo {
 pub fn mark_history(
     blocks: &mut [BlockEntry],
     span_timestamps: &HashMap<String, SpanTimestamps>,
+    clock_skew_tolerance: TimeDelta,
 ) -> HistoryStats {
     let mut stats = HistoryStats {
         protected: blocks.iter().filter(|b| b.is_protected()).count(),
@@ -201,9 +205,10 @@ pub fn mark_history(
             continue;
         }
 
-        // Check if block timestamp is before span start
+        // Check if block timestamp is before span start. The tolerance absorbs
+        // skew between the SDK clock (message time) and the span clock.
         if let Some(span_ts) = span_timestamps.get(&block.span_id)
-            && block.timestamp < span_ts.span_start
+            && block.timestamp < span_ts.span_start - clock_skew_tolerance
         {
             block.is_history = true;
             stats.generation_history += 1;
@@ -563,8 +568,12 @@ mod tests {
             category,
             content_hash: "hash".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -661,8 +670,12 @@ mod tests {
             category,
             content_hash: format!("hash_{counter}"),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -750,7 +763,7 @@ mod tests {
         );
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // User message should not be history
         assert!(!blocks[0].is_history, "user message should not be history");
@@ -793,7 +806,7 @@ mod tests {
         ];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // Assistant text from generation span IS history in Strands (events bubble up)
         assert!(
@@ -842,7 +855,7 @@ mod tests {
         blocks[0].parent_span_id = None;
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // User message from root should not be history
         assert!(
@@ -877,7 +890,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // Chain span JSON should be history even if root
         assert!(
@@ -903,7 +916,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // Root agent span text should not be history
         assert!(
@@ -934,7 +947,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        let stats = mark_history(&mut blocks, &span_timestamps);
+        let stats = mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert!(
             blocks[0].is_history,
@@ -961,7 +974,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        let stats = mark_history(&mut blocks, &span_timestamps);
+        let stats = mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert!(
             !blocks[0].is_history,
@@ -989,7 +1002,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert!(
             !blocks[0].is_history,
@@ -1015,7 +1028,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert!(
             !blocks[0].is_history,
@@ -1041,7 +1054,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        mark_history(&mut blocks, &span_timestamps);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert!(
             !blocks[0].is_history,
@@ -1066,7 +1079,7 @@ mod tests {
         }];
 
         let span_timestamps = HashMap::new();
-        let stats = mark_history(&mut blocks, &span_timestamps);
+        let stats = mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         assert_eq!(
             stats.input_source_history, 0,
@@ -1190,7 +1203,7 @@ mod tests {
         ];
 
         let span_timestamps = HashMap::new();
-        let stats = mark_history(&mut blocks, &span_timestamps);
+        let stats = mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
 
         // NYC tool_result (index 2) should be orphan — its tool_use_id not in gen_ai.choice
         assert!(
@@ -1206,4 +1219,39 @@ mod tests {
 
         assert!(stats.orphan_tool_results >= 1, "at least 1 orphan expected");
     }
+
+    #[test]
+    fn test_clock_skew_tolerance_preserves_marginally_early_message() {
+        let make = || {
+            let mut block = make_block(
+                "text",
+                Some("generation"),
+                None,
+                MessageCategory::GenAIUserMessage,
+                None,
+            );
+            block.role = ChatRole::User;
+            block
+        };
+        let span_start = Utc::now();
+        let mut span_timestamps = HashMap::new();
+        span_timestamps.insert(
+            "span1".to_string(),
+            SpanTimestamps {
+                span_start,
+                span_end: None,
+            },
+        );
+
+        // Message stamped 200ms before span start (SDK clock behind server clock)
+        let mut blocks = vec![make()];
+        blocks[0].timestamp = span_start - TimeDelta::milliseconds(200);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::milliseconds(500));
+        assert!(!blocks[0].is_history, "within tolerance should be kept");
+
+        let mut blocks = vec![make()];
+        blocks[0].timestamp = span_start - TimeDelta::milliseconds(200);
+        mark_history(&mut blocks, &span_timestamps, TimeDelta::zero());
+        assert!(blocks[0].is_history, "zero tolerance should strip it");
+    }
 }
@@ -64,30 +64,47 @@
 //! - **With history**: Strands, LangGraph, LangChain (duplicates detected/filtered)
 //! - **Without history**: AutoGen, CrewAI (passes through unchanged)
 
+mod anonymize;
 mod classify;
+mod collapse;
 mod dedup;
+mod gaps;
 mod history;
+mod prompt;
+mod truncate;
+mod turns;
 mod types;
 
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde_json::{Value as JsonValue, json};
 
-use super::normalize::to_sideml_with_context;
+use super::normalize::{DefaultRoles, to_sideml_with_roles};
 use super::tools::{extract_tool_name, normalize_tools, tool_definition_quality};
 use super::types::ContentBlock;
-use crate::data::types::{MessageCategory, MessageSpanRow};
+use crate::data::types::{IngestWarning, MessageCategory, MessageSpanRow};
 use crate::domain::traces::{MessageSource, RawMessage};
+use crate::utils::compress::decompress_json;
 
+use anonymize::anonymize_blocks;
 use classify::uses_span_end;
+use collapse::collapse_repeated_system;
 use dedup::{
     SpanTimestamps, normalize_json_for_hash, normalize_tool_result_content, process_dedup,
 };
+use gaps::annotate_gaps;
 use history::mark_history;
+use truncate::truncate_tool_results;
+use turns::group_turns;
 
 // Re-exports for public API
-pub use types::{BlockEntry, ExtractedTools, FeedMetadata, FeedOptions, FeedResult};
+pub use prompt::process_prompt;
+pub use types::{
+    BlockEntry, ContentSizeStats, EventDirection, EventNames, ExtractedTools, FeedMetadata,
+    FeedOptions, FeedResult, FeedWarning, FeedWarningKind, Turn,
+};
 
 // ============================================================================
 // SHARED CONSTANTS
@@ -149,38 +166,100 @@ struct ParsedMessage {
     total_tokens: i64,
     cost_total: f64,
     observation_type: Option<String>,
+    parallel_group: Option<usize>,
 }
 
 /// Incremental cross-trace prefix state for replay stripping.
 ///
 /// Stores an ordered prefix plus an index for O(log n) next-position lookup.
+/// Positions are absolute (they keep counting after eviction), so dropping
+/// the oldest entries never shifts the positions of the remaining ones.
 #[derive(Debug, Default)]
 struct CrossTracePrefixState {
-    len: usize,
-    positions_by_role: HashMap<super::types::ChatRole, HashMap<String, Vec<usize>>>,
+    /// Absolute position of the oldest retained entry
+    start: usize,
+    /// Absolute position one past the newest entry
+    end: usize,
+    /// Retained `(role, content_hash)` entries in push order, for FIFO eviction
+    entries: VecDeque<(super::types::ChatRole, String)>,
+    positions_by_role: HashMap<super::types::ChatRole, HashMap<String, VecDeque<usize>>>,
+    /// Keep at most this many entries, dropping the oldest (None = unbounded)
+    max_entries: Option<usize>,
+    /// Entries dropped so far to stay within `max_entries`
+    dropped: usize,
 }
 
 impl CrossTracePrefixState {
+    fn new(max_entries: Option<usize>) -> Self {
+        Self {
+            max_entries,
+            ..Self::default()
+        }
+    }
+
     #[inline]
     fn is_empty(&self) -> bool {
-        self.len == 0
+        self.entries.is_empty()
     }
 
+    /// Number of retained entries.
     #[inline]
     fn len(&self) -> usize {
-        self.len
+        self.entries.len()
     }
 
-    /// Add a block to accumulated cross-trace prefix history.
+    /// Absolute position of the oldest retained entry.
+    #[inline]
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Absolute position one past the newest entry.
+    #[inline]
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Add a block to accumulated cross-trace prefix history, dropping the
+    /// oldest entries once `max_entries` is exceeded.
     fn push_block(&mut self, block: &BlockEntry) {
-        let idx = self.len;
-        self.len += 1;
+        let idx = self.end;
+        self.end += 1;
+        self.entries
+            .push_back((block.role, block.content_hash.clone()));
         self.positions_by_role
             .entry(block.role)
             .or_default()
             .entry(block.content_hash.clone())
             .or_default()
-            .push(idx);
+            .push_back(idx);
+        if let Some(max) = self.max_entries {
+            while self.entries.len() > max {
+                self.evict_oldest();
+            }
+        }
+    }
+
+    /// Drop the oldest entry and its index position.
+    fn evict_oldest(&mut self) {
+        let Some((role, content_hash)) = self.entries.pop_front() else {
+            return;
+        };
+        if let Some(by_hash) = self.positions_by_role.get_mut(&role) {
+            if let Some(positions) = by_hash.get_mut(&content_hash) {
+                // The oldest entry is always the first position for its key
+                debug_assert_eq!(positions.front(), Some(&self.start));
+                positions.pop_front();
+                if positions.is_empty() {
+                    by_hash.remove(&content_hash);
+                }
+            }
+            if by_hash.is_empty() {
+                self.positions_by_role.remove(&role);
+            }
+        }
+        self.start += 1;
+        self.dropped += 1;
     }
 
     /// Find first accumulated position >= `min_index` for `(role, content_hash)`.
@@ -211,12 +290,68 @@ pub fn process_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRe
             .first()
             .map(|first| rows.iter().any(|r| r.trace_id != first.trace_id))
             .unwrap_or(false);
+    let span_metrics = span_metrics(&rows);
 
-    if is_multi_trace {
+    let mut result = if is_multi_trace {
         process_multi_trace_spans(rows, options)
     } else {
         process_trace_spans(rows, options)
+    };
+
+    if options.include_gaps {
+        annotate_gaps(&mut result.messages);
+    }
+    if let Some(max_bytes) = options.max_tool_result_bytes {
+        truncate_tool_results(&mut result.messages, max_bytes);
+    }
+    if let Some(max_blocks) = options.max_blocks {
+        cap_blocks(&mut result, max_blocks, false);
+    }
+    // Display-only: after dedup, and after the cap so the original survives
+    if options.collapse_repeated_system {
+        collapse_system_prompts(&mut result, false);
+    }
+    // After every stage that drops blocks, so each span keeps its totals
+    attribute_span_metrics(
+        &mut result.messages,
+        &span_metrics,
+        options.inline_turn_metrics,
+    );
+    if options.group_turns {
+        result.turns = group_turns(&result.messages);
+    }
+    if !options.include_hashes {
+        for block in &mut result.messages {
+            block.content_hash.clear();
+        }
+    }
+    result
+}
+
+/// Keep at most `max_blocks` of the most recent blocks, flagging the result
+/// as truncated when any were dropped.
+///
+/// `newest_first` tells which end holds the most recent blocks (feeds sort
+/// DESC, conversations ASC).
+fn cap_blocks(result: &mut FeedResult, max_blocks: usize, newest_first: bool) {
+    let len = result.messages.len();
+    if len <= max_blocks {
+        return;
     }
+    if newest_first {
+        result.messages.truncate(max_blocks);
+    } else {
+        result.messages.drain(..len - max_blocks);
+    }
+    result.metadata.truncated = true;
+    result.metadata.block_count = result.messages.len();
+    result.metadata.content_sizes = ContentSizeStats::from_blocks(&result.messages);
+}
+
+/// Collapse repeated system prompts and refresh `content_sizes` to match.
+fn collapse_system_prompts(result: &mut FeedResult, newest_first: bool) {
+    collapse_repeated_system(&mut result.messages, newest_first);
+    result.metadata.content_sizes = ContentSizeStats::from_blocks(&result.messages);
 }
 
 /// Process span rows from a single trace through the complete feed pipeline.
@@ -247,17 +382,37 @@ fn process_trace_spans_core(
     options: &FeedOptions,
     cross_trace_prefix: Option<&CrossTracePrefixState>,
 ) -> FeedResult {
+    finish_trace(prepare_trace(rows, options), options, cross_trace_prefix)
+}
+
+/// A trace after the stages that do not depend on other traces (parse, flatten).
+///
+/// Preparing traces is independent of cross-trace state, so multi-trace
+/// processing can run it in parallel and apply prefixes in order afterwards.
+struct PreparedTrace {
+    rows: Vec<MessageSpanRow>,
+    blocks: Vec<BlockEntry>,
+    span_timestamps: HashMap<String, SpanTimestamps>,
+    extracted_tools: ExtractedTools,
+    warnings: Vec<FeedWarning>,
+}
+
+/// Stages 1-2: parse raw messages and flatten them to blocks.
+fn prepare_trace(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> PreparedTrace {
+    let mut warnings: Vec<FeedWarning> = Vec::new();
+
     // Build span hierarchy for span_path computation
-    let span_hierarchy = build_span_hierarchy(&rows);
+    let span_hierarchy = build_span_hierarchy(&rows, &options.stored_parents, &mut warnings);
 
     // Build span timestamps map for birth time computation
     let span_timestamps = build_span_timestamps(&rows);
 
     // Stage 1: Parse raw messages and convert to SideML
-    let mut parsed_messages = parse_span_rows(&rows);
+    let mut parsed_messages = parse_span_rows(&rows, &options.default_roles, &mut warnings);
+    collect_ingest_warnings(&rows, &mut warnings);
 
     // Extract tools from all rows
-    let extracted_tools = extract_tools_from_rows(&rows);
+    let extracted_tools = extract_tools_from_rows(&rows, options.fold_tool_name_case);
 
     // Stage 1b: Append error messages from leaf error spans
     append_error_messages(&mut parsed_messages, &rows);
@@ -280,7 +435,30 @@ fn process_trace_spans_core(
 
     // Stage 2: Flatten to individual blocks with metadata
     // All blocks start with is_history = false
-    let mut blocks = flatten_to_blocks(parsed_messages, &span_hierarchy, options);
+    let blocks = flatten_to_blocks(parsed_messages, &span_hierarchy, options);
+
+    PreparedTrace {
+        rows,
+        blocks,
+        span_timestamps,
+        extracted_tools,
+        warnings,
+    }
+}
+
+/// Stages 2.5-7: cross-trace marking, classification, dedup and metadata.
+fn finish_trace(
+    prepared: PreparedTrace,
+    options: &FeedOptions,
+    cross_trace_prefix: Option<&CrossTracePrefixState>,
+) -> FeedResult {
+    let PreparedTrace {
+        rows,
+        mut blocks,
+        span_timestamps,
+        extracted_tools,
+        warnings,
+    } = prepared;
 
     // Stage 2.5: Cross-trace prefix marking (multi-trace sessions only)
     // MUST run BEFORE classify_blocks (which includes Phase 7 duplicate detection).
@@ -295,7 +473,7 @@ fn process_trace_spans_core(
     // Stages 3-4: Classify blocks and mark history
     // - uses_span_end: determines timestamp strategy (span_end vs event_time)
     // - is_history: marks non-authoritative blocks for filtering
-    classify_blocks(&mut blocks, &span_timestamps);
+    classify_blocks(&mut blocks, &span_timestamps, options.clock_skew_tolerance);
 
     // Debug: Log block counts by entry_type after flatten
     if tracing::enabled!(tracing::Level::DEBUG) {
@@ -316,7 +494,7 @@ fn process_trace_spans_core(
     }
 
     // Stages 5-6: Deduplicate by identity, sort by birth time
-    let blocks = process_dedup(blocks, span_timestamps);
+    let mut blocks = process_dedup(blocks, span_timestamps);
 
     // Debug: Log block counts after dedup
     if tracing::enabled!(tracing::Level::DEBUG) {
@@ -334,11 +512,23 @@ fn process_trace_spans_core(
         );
     }
 
+    // Filtering here rather than before parsing keeps system prompts visible to
+    // history detection and dedup, so results match the unfiltered feed
+    if options.exclude_system {
+        blocks.retain(|b| b.role != super::types::ChatRole::System);
+    }
+
+    // Display-only anonymization runs last so hashing/dedup see real content
+    if options.anonymize {
+        anonymize_blocks(&mut blocks);
+    }
+
     // Stage 7: Compute metadata and return
-    let metadata = compute_metadata(&blocks, &rows);
+    let metadata = compute_metadata(&blocks, &rows, warnings);
 
     FeedResult {
         messages: blocks,
+        turns: Vec::new(),
         tool_definitions: extracted_tools.tool_definitions,
         tool_names: extracted_tools.tool_names,
         metadata,
@@ -365,27 +555,52 @@ fn process_trace_spans_core(
 ///   Prefix scan consumes matched Phase 4b entries without re-marking.
 fn process_multi_trace_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedResult {
     let trace_groups = group_and_sort_traces(rows);
+    let mut warnings: Vec<FeedWarning> = Vec::new();
+
+    // Over-grouped session (e.g. a shared default session id): stripping across
+    // unrelated traces is both wrong and quadratic, so process each on its own
+    let independent = options
+        .max_session_traces
+        .is_some_and(|max| trace_groups.len() > max);
+    if independent && let Some(first) = trace_groups.first().and_then(|g| g.first()) {
+        warnings.push(FeedWarning::new(
+            FeedWarningKind::SessionTooLarge,
+            first,
+            format!(
+                "Session groups {} traces (limit {}); cross-trace history stripping skipped",
+                trace_groups.len(),
+                options.max_session_traces.unwrap_or_default()
+            ),
+        ));
+    }
 
-    let mut accumulated = CrossTracePrefixState::default();
+    // One prefix per environment when environment-scoped, so a prod trace is
+    // never stripped against dev history with the same content
+    let mut accumulated_by_env: HashMap<Option<String>, CrossTracePrefixState> = HashMap::new();
     let mut all_blocks: Vec<BlockEntry> = Vec::new();
     let mut all_tool_defs: Vec<serde_json::Value> = Vec::new();
     let mut all_tool_names: Vec<String> = Vec::new();
     let mut total_tokens: i64 = 0;
     let mut total_cost: f64 = 0.0;
 
-    for (trace_idx, trace_rows) in trace_groups.into_iter().enumerate() {
-        let trace_tokens: i64 = trace_rows.iter().map(|r| r.total_tokens).sum();
-        let trace_cost: f64 = trace_rows.iter().map(|r| r.cost_total).sum();
+    // Parse/flatten is per-trace work; prefixes are applied in order below
+    let prepared_traces = prepare_traces(trace_groups, options);
+
+    for (trace_idx, prepared) in prepared_traces.into_iter().enumerate() {
+        let trace_tokens: i64 = prepared.rows.iter().map(|r| r.total_tokens).sum();
+        let trace_cost: f64 = prepared.rows.iter().map(|r| r.cost_total).sum();
+        let env_key = environment_key(&prepared.rows, options);
 
         // First trace: no prefix. Subsequent traces: pass accumulated prefix
         // for pre-dedup marking of history re-sends.
-        let cross_trace_prefix = if trace_idx == 0 {
+        let cross_trace_prefix = if trace_idx == 0 || independent {
             None
         } else {
-            Some(&accumulated)
+            accumulated_by_env.get(&env_key)
         };
 
-        let result = process_trace_spans_core(trace_rows, options, cross_trace_prefix);
+        let mut result = finish_trace(prepared, options, cross_trace_prefix);
+        warnings.append(&mut result.metadata.warnings);
 
         // First trace always contributes. Subsequent traces contribute only if
         // they have new non-system content (pure replay traces are skipped).
@@ -400,9 +615,38 @@ fn process_multi_trace_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -
             // The prefix scan matches these against input-source blocks in
             // subsequent traces, handling both root gen spans (where assistant
             // blocks survive) and non-root gen spans (where Phase 4b marks them).
-            for block in &result.messages {
-                if block.role != super::types::ChatRole::System {
-                    accumulated.push_block(block);
+            if !independent {
+                let accumulated = accumulated_by_env
+                    .entry(env_key)
+                    .or_insert_with(|| CrossTracePrefixState::new(options.max_prefix_entries));
+                let dropped_before = accumulated.dropped;
+                for block in &result.messages {
+                    if block.role != super::types::ChatRole::System {
+                        accumulated.push_block(block);
+                    }
+                }
+                // Warn once per prefix, when it first overflows
+                if dropped_before == 0
+                    && accumulated.dropped > 0
+                    && let Some(first) = result.messages.first()
+                {
+                    let message = format!(
+                        "Cross-trace prefix exceeded {} entries; the oldest entries were \
+                         dropped, so a replay of them is only stripped when it continues \
+                         into the retained history",
+                        options.max_prefix_entries.unwrap_or_default()
+                    );
+                    tracing::warn!(
+                        trace_id = %first.trace_id,
+                        max_entries = options.max_prefix_entries.unwrap_or_default(),
+                        "Cross-trace prefix exceeded its limit; dropping oldest entries"
+                    );
+                    warnings.push(FeedWarning {
+                        kind: FeedWarningKind::PrefixTruncated,
+                        trace_id: first.trace_id.clone(),
+                        span_id: first.span_id.clone(),
+                        message,
+                    });
                 }
             }
             all_blocks.extend(result.messages);
@@ -419,11 +663,13 @@ fn process_multi_trace_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -
         .map(|b| &b.span_id)
         .collect::<HashSet<_>>()
         .len();
-    let tool_definitions = deduplicate_tools(all_tool_defs);
-    let tool_names = deduplicate_names(all_tool_names);
+    let tool_definitions = deduplicate_tools(all_tool_defs, options.fold_tool_name_case);
+    let tool_names = deduplicate_names(all_tool_names, options.fold_tool_name_case);
+    let content_sizes = ContentSizeStats::from_blocks(&all_blocks);
 
     FeedResult {
         messages: all_blocks,
+        turns: Vec::new(),
         tool_definitions,
         tool_names,
         metadata: FeedMetadata {
@@ -431,10 +677,70 @@ fn process_multi_trace_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -
             span_count,
             total_tokens,
             total_cost,
+            content_sizes,
+            warnings,
+            truncated: false,
         },
     }
 }
 
+/// Prepare each trace group, in order, using up to `trace_parallelism` threads
+/// (capped at the available cores).
+fn prepare_traces(
+    trace_groups: Vec<Vec<MessageSpanRow>>,
+    options: &FeedOptions,
+) -> Vec<PreparedTrace> {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let workers = options.trace_parallelism.min(cores).min(trace_groups.len());
+    if workers <= 1 {
+        return trace_groups
+            .into_iter()
+            .map(|rows| prepare_trace(rows, options))
+            .collect();
+    }
+
+    // Contiguous chunks keep the output in trace order when joined in order
+    let chunk_size = trace_groups.len().div_ceil(workers);
+    let mut groups = trace_groups.into_iter().peekable();
+    let mut chunks: Vec<Vec<Vec<MessageSpanRow>>> = Vec::with_capacity(workers);
+    while groups.peek().is_some() {
+        chunks.push(groups.by_ref().take(chunk_size).collect());
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .into_iter()
+                        .map(|rows| prepare_trace(rows, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// Environment that scopes cross-trace matching for a group of rows.
+///
+/// Always `None` unless `dedup_by_environment` is enabled, so all rows share
+/// one scope by default.
+fn environment_key(rows: &[MessageSpanRow], options: &FeedOptions) -> Option<String> {
+    if !options.dedup_by_environment {
+        return None;
+    }
+    rows.iter().find_map(|r| r.environment.clone())
+}
+
 /// Mark input-source blocks matching the accumulated cross-trace prefix as history.
 ///
 /// Runs BEFORE `classify_blocks` (before Phase 4b and Phase 7) so that:
@@ -449,6 +755,11 @@ fn process_multi_trace_spans(rows: Vec<MessageSpanRow>, options: &FeedOptions) -
 /// 2. **Per-span sequential scan**: For each span, iterate input-source blocks
 ///    in order, matching against accumulated prefix entries. Mark matches as
 ///    history. Stop at first non-match for that span.
+/// 3. **Evicted head**: Once the oldest entries were dropped (`start > 0`), a
+///    full replay opens with blocks that can no longer match, so misses before
+///    the span's first match are skipped instead of ending the prefix. If that
+///    first match is the oldest retained entry, the skipped blocks are marked
+///    as history too.
 fn mark_cross_trace_prefix(blocks: &mut [BlockEntry], accumulated: &CrossTracePrefixState) {
     if accumulated.is_empty() {
         return;
@@ -473,22 +784,29 @@ fn mark_cross_trace_prefix(blocks: &mut [BlockEntry], accumulated: &CrossTracePr
 
     // Sequential prefix match per span on strippable input blocks.
     // Since this runs before any history marking, no blocks are is_history yet.
-    let mut acc_idx = 0;
+    let mut acc_idx = accumulated.start();
+    let head_evicted = accumulated.start() > 0;
     let mut current_span_id: Option<String> = None;
     let mut span_prefix_active = true;
+    let mut span_matched = false;
+    // Misses before the span's first match, while the prefix head is evicted
+    let mut evicted_head: Vec<usize> = Vec::new();
+    let mut evicted_replays: Vec<usize> = Vec::new();
     let mut marked = 0;
     let mut skipped = 0;
     let mut spans_scanned = 0;
-    for block in blocks.iter_mut() {
+    for (i, block) in blocks.iter_mut().enumerate() {
         // Prefix scan resets at each span boundary. ADK/LangGraph often replay
         // history at the start of every generation span, not just trace start.
         if current_span_id.as_deref() != Some(block.span_id.as_str()) {
             current_span_id = Some(block.span_id.clone());
-            acc_idx = 0;
+            acc_idx = accumulated.start();
             span_prefix_active = true;
+            span_matched = false;
+            evicted_head.clear();
             spans_scanned += 1;
         }
-        if acc_idx >= accumulated.len() || !span_prefix_active {
+        if acc_idx >= accumulated.end() || !span_prefix_active {
             continue;
         }
         let strippable = (block.is_input_source() && block.source_type == source_type::ATTRIBUTE)
@@ -512,10 +830,23 @@ fn mark_cross_trace_prefix(blocks: &mut [BlockEntry], accumulated: &CrossTracePr
             skipped += next_idx.saturating_sub(acc_idx);
             acc_idx = next_idx + 1;
             marked += 1;
-        } else {
+            // A first match on the oldest retained entry means the misses
+            // before it replayed the evicted head of the history
+            if !span_matched && next_idx == accumulated.start() {
+                evicted_replays.append(&mut evicted_head);
+            }
+            span_matched = true;
+            evicted_head.clear();
+        } else if span_matched || !head_evicted {
             span_prefix_active = false; // Prefix ends for this span
+        } else {
+            evicted_head.push(i);
         }
     }
+    for &i in &evicted_replays {
+        blocks[i].is_history = true;
+    }
+    marked += evicted_replays.len();
 
     tracing::debug!(
         accumulated_len = accumulated.len(),
@@ -578,9 +909,26 @@ pub fn process_feed(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRes
             .filter(|s| !s.is_empty())
             .cloned()
             .unwrap_or_else(|| format!("trace:{}", row.trace_id));
+        let key = match environment_key(std::slice::from_ref(&row), options) {
+            Some(env) => format!("env:{env}:{key}"),
+            None => key,
+        };
         spans_by_conversation.entry(key).or_default().push(row);
     }
 
+    // Collapse once over the capped page below rather than per conversation,
+    // so `repeat_of` never names a block the cap dropped (needs the hashes)
+    let conversation_options = if options.collapse_repeated_system {
+        Cow::Owned(
+            options
+                .clone()
+                .with_collapse_repeated_system(false)
+                .with_include_hashes(true),
+        )
+    } else {
+        Cow::Borrowed(options)
+    };
+
     // Process each conversation separately
     let mut all_blocks: Vec<BlockEntry> = Vec::new();
     let mut all_tool_defs: Vec<JsonValue> = Vec::new();
@@ -588,14 +936,20 @@ pub fn process_feed(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRes
     let mut total_tokens: i64 = 0;
     let mut total_cost: f64 = 0.0;
     let mut span_ids: HashSet<String> = HashSet::new();
+    let mut all_span_metrics = SpanMetrics::new();
+    let mut warnings: Vec<FeedWarning> = Vec::new();
+    let mut truncated = false;
 
     for (_, conversation_spans) in spans_by_conversation {
+        all_span_metrics.extend(span_metrics(&conversation_spans));
         for row in &conversation_spans {
             span_ids.insert(row.span_id.clone());
             total_tokens += row.total_tokens;
             total_cost += row.cost_total;
         }
-        let processed = process_spans(conversation_spans, options);
+        let processed = process_spans(conversation_spans, &conversation_options);
+        truncated |= processed.metadata.truncated;
+        warnings.extend(processed.metadata.warnings);
         all_blocks.extend(processed.messages);
         all_tool_defs.extend(processed.tool_definitions);
         all_tool_names.extend(processed.tool_names);
@@ -629,12 +983,14 @@ pub fn process_feed(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRes
     });
 
     // Deduplicate tools across conversations
-    let tool_definitions = deduplicate_tools(all_tool_defs);
-    let tool_names = deduplicate_names(all_tool_names);
+    let tool_definitions = deduplicate_tools(all_tool_defs, options.fold_tool_name_case);
+    let tool_names = deduplicate_names(all_tool_names, options.fold_tool_name_case);
     let block_count = all_blocks.len();
+    let content_sizes = ContentSizeStats::from_blocks(&all_blocks);
 
-    FeedResult {
+    let mut result = FeedResult {
         messages: all_blocks,
+        turns: Vec::new(),
         tool_definitions,
         tool_names,
         metadata: FeedMetadata {
@@ -642,8 +998,33 @@ pub fn process_feed(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRes
             span_count: span_ids.len(),
             total_tokens,
             total_cost,
+            content_sizes,
+            warnings,
+            truncated,
         },
+    };
+    if let Some(max_blocks) = options.max_blocks {
+        cap_blocks(&mut result, max_blocks, true);
+        // The cap may drop a span's representative block
+        attribute_span_metrics(
+            &mut result.messages,
+            &all_span_metrics,
+            options.inline_turn_metrics,
+        );
     }
+    if options.collapse_repeated_system {
+        collapse_system_prompts(&mut result, true);
+        if !options.include_hashes {
+            for block in &mut result.messages {
+                block.content_hash.clear();
+            }
+        }
+    }
+    // Grouped again over the merged, newest-first order
+    if options.group_turns {
+        result.turns = group_turns(&result.messages);
+    }
+    result
 }
 
 // ============================================================================
@@ -651,7 +1032,11 @@ pub fn process_feed(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedRes
 // ============================================================================
 
 /// Parse span rows into parsed messages.
-fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
+fn parse_span_rows(
+    rows: &[MessageSpanRow],
+    default_roles: &DefaultRoles,
+    warnings: &mut Vec<FeedWarning>,
+) -> Vec<ParsedMessage> {
     let mut messages: Vec<ParsedMessage> = Vec::with_capacity(rows.len() * 4);
 
     for row in rows {
@@ -659,7 +1044,7 @@ fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
         let is_tool_span = row.observation_type.as_deref() == Some(obs_type::TOOL);
 
         // Parse raw messages and convert to SideML
-        match serde_json::from_str::<Vec<RawMessage>>(&row.messages_json) {
+        match serde_json::from_str::<Vec<RawMessage>>(&decompress_json(&row.messages_json)) {
             Ok(raw_msgs) => {
                 // Debug: Log raw message count
                 tracing::trace!(
@@ -667,7 +1052,7 @@ fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
                     raw_msg_count = raw_msgs.len(),
                     "parse_span_rows: raw messages parsed"
                 );
-                let sideml_msgs = to_sideml_with_context(&raw_msgs, is_tool_span);
+                let sideml_msgs = to_sideml_with_roles(&raw_msgs, is_tool_span, default_roles);
                 tracing::trace!(
                     span_id = %row.span_id,
                     sideml_msg_count = sideml_msgs.len(),
@@ -691,6 +1076,7 @@ fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
                         total_tokens: row.total_tokens,
                         cost_total: row.cost_total,
                         observation_type: row.observation_type.clone(),
+                        parallel_group: msg.parallel_group,
                     });
                 }
             }
@@ -700,6 +1086,11 @@ fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
                     error = %e,
                     "Failed to parse messages JSON"
                 );
+                warnings.push(FeedWarning::new(
+                    FeedWarningKind::ParseError,
+                    row,
+                    format!("Failed to parse messages: {e}"),
+                ));
             }
         }
     }
@@ -707,18 +1098,78 @@ fn parse_span_rows(rows: &[MessageSpanRow]) -> Vec<ParsedMessage> {
     messages
 }
 
+/// Surface the data-quality issues recorded for each span at extraction.
+fn collect_ingest_warnings(rows: &[MessageSpanRow], warnings: &mut Vec<FeedWarning>) {
+    for row in rows {
+        if row.ingest_warnings_json.is_empty() || row.ingest_warnings_json == "[]" {
+            continue;
+        }
+        match serde_json::from_str::<Vec<IngestWarning>>(&row.ingest_warnings_json) {
+            Ok(found) => warnings.extend(
+                found
+                    .into_iter()
+                    .map(|w| FeedWarning::new(w.kind.into(), row, w.message)),
+            ),
+            Err(e) => tracing::debug!(
+                span_id = %row.span_id,
+                error = %e,
+                "Failed to parse ingest warnings JSON"
+            ),
+        }
+    }
+}
+
+/// Reduce a feed to its error blocks, each with the turn that preceded it.
+///
+/// Error blocks are the exception blocks composed by `append_error_messages`.
+/// Each keeps the nearest earlier assistant or tool block of the same trace as
+/// context; everything else is dropped. Block and span counts are recomputed.
+pub fn retain_errors_with_context(result: &mut FeedResult) {
+    use super::types::ChatRole;
+
+    let mut keep = vec![false; result.messages.len()];
+    for (i, block) in result.messages.iter().enumerate() {
+        if block.category != MessageCategory::Exception {
+            continue;
+        }
+        keep[i] = true;
+        let context = result.messages[..i].iter().rposition(|b| {
+            b.trace_id == block.trace_id
+                && b.category != MessageCategory::Exception
+                && matches!(b.role, ChatRole::Assistant | ChatRole::Tool)
+        });
+        if let Some(j) = context {
+            keep[j] = true;
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    result.messages.retain(|_| keep.next().unwrap_or(false));
+    result.regroup_turns();
+    result.metadata.block_count = result.messages.len();
+    result.metadata.span_count = result
+        .messages
+        .iter()
+        .map(|b| &b.span_id)
+        .collect::<HashSet<_>>()
+        .len();
+    result.metadata.content_sizes = ContentSizeStats::from_blocks(&result.messages);
+}
+
 /// Extract tool definitions and names from span rows.
 ///
 /// Standalone function decoupled from message parsing so handlers can
 /// scope tool extraction to specific rows (e.g., a single trace).
+/// `fold_case` is passed through to [`deduplicate_tools`].
 pub fn extract_tools_from_rows<'a>(
     rows: impl IntoIterator<Item = &'a MessageSpanRow>,
+    fold_case: bool,
 ) -> ExtractedTools {
     let mut tool_defs: Vec<JsonValue> = Vec::new();
     let mut tool_names_raw: Vec<String> = Vec::new();
 
     for row in rows {
-        match serde_json::from_str::<Vec<JsonValue>>(&row.tool_definitions_json) {
+        match serde_json::from_str::<Vec<JsonValue>>(&decompress_json(&row.tool_definitions_json)) {
             Ok(defs) => tool_defs.extend(defs),
             Err(e) => {
                 tracing::debug!(
@@ -742,8 +1193,8 @@ pub fn extract_tools_from_rows<'a>(
     }
 
     ExtractedTools {
-        tool_definitions: deduplicate_tools(tool_defs),
-        tool_names: deduplicate_names(tool_names_raw),
+        tool_definitions: deduplicate_tools(tool_defs, fold_case),
+        tool_names: deduplicate_names(tool_names_raw, fold_case),
     }
 }
 
@@ -839,6 +1290,7 @@ fn append_error_messages(messages: &mut Vec<ParsedMessage>, rows: &[MessageSpanR
             total_tokens: 0,
             cost_total: 0.0,
             observation_type: row.observation_type.clone(),
+            parallel_group: None,
         });
     }
 }
@@ -849,8 +1301,15 @@ fn append_error_messages(messages: &mut Vec<ParsedMessage>, rows: &[MessageSpanR
 
 /// Build span hierarchy map for span_path computation.
 ///
-/// Includes cycle detection to prevent infinite loops from malformed data.
-fn build_span_hierarchy(span_rows: &[MessageSpanRow]) -> HashMap<String, Vec<String>> {
+/// Parents missing from `span_rows` are looked up in `stored_parents`, so a
+/// span whose ancestors were exported in an earlier batch still gets its full
+/// path. Includes cycle detection and a depth limit to prevent infinite loops
+/// from malformed data.
+fn build_span_hierarchy(
+    span_rows: &[MessageSpanRow],
+    stored_parents: &HashMap<String, String>,
+    warnings: &mut Vec<FeedWarning>,
+) -> HashMap<String, Vec<String>> {
     let parent_map: HashMap<_, _> = span_rows
         .iter()
         .filter_map(|s| {
@@ -869,7 +1328,10 @@ fn build_span_hierarchy(span_rows: &[MessageSpanRow]) -> HashMap<String, Vec<Str
         let mut visited = HashSet::with_capacity(max_depth.min(32));
         visited.insert(current.clone());
 
-        while let Some(parent) = parent_map.get(&current) {
+        while let Some(parent) = parent_map
+            .get(&current)
+            .or_else(|| stored_parents.get(&current))
+        {
             // Cycle detection: stop if we've seen this parent before
             if !visited.insert(parent.clone()) {
                 tracing::warn!(
@@ -877,6 +1339,11 @@ fn build_span_hierarchy(span_rows: &[MessageSpanRow]) -> HashMap<String, Vec<Str
                     cycle_at = %parent,
                     "Cycle detected in span hierarchy, truncating path"
                 );
+                warnings.push(FeedWarning::new(
+                    FeedWarningKind::HierarchyCycle,
+                    span,
+                    format!("Parent chain loops back to span {parent}"),
+                ));
                 break;
             }
 
@@ -887,6 +1354,11 @@ fn build_span_hierarchy(span_rows: &[MessageSpanRow]) -> HashMap<String, Vec<Str
                     depth = path.len(),
                     "Span hierarchy depth exceeded limit, truncating path"
                 );
+                warnings.push(FeedWarning::new(
+                    FeedWarningKind::HierarchyTooDeep,
+                    span,
+                    format!("Parent chain deeper than {max_depth} spans"),
+                ));
                 break;
             }
 
@@ -978,13 +1450,18 @@ fn flatten_to_blocks(
             &msg.source,
             MessageSource::Attribute { key, .. } if key == "output.value" || key.starts_with("output.")
         );
-        if is_tool_span
+        let is_tool_input_json = is_tool_span
             && !is_output_attr
             && msg.message.content.len() == 1
-            && matches!(msg.message.content.first(), Some(ContentBlock::Json { .. }))
-        {
+            && matches!(msg.message.content.first(), Some(ContentBlock::Json { .. }));
+        if is_tool_input_json && !options.keep_tool_input_json {
             continue;
         }
+        let category = if is_tool_input_json {
+            MessageCategory::GenAIToolInput
+        } else {
+            msg.category
+        };
 
         let span_path = span_hierarchy
             .get(&msg.span_id)
@@ -998,6 +1475,14 @@ fn flatten_to_blocks(
                 (source_type::ATTRIBUTE, None, Some(key.clone()))
             }
         };
+        let event_direction = event_name
+            .as_deref()
+            .and_then(|name| options.event_names.direction(name));
+
+        // Tool calls requested together in one message share a group id
+        let parallel_group = msg
+            .parallel_group
+            .map(|group| format!("{}:{}", msg.span_id, group));
 
         // Flatten each content block into its own BlockEntry
         // is_history starts as false; will be set by mark_history()
@@ -1051,12 +1536,16 @@ fn flatten_to_blocks(
                 source_type: src_type.to_string(),
                 event_name: event_name.clone(),
                 source_attribute: source_attribute.clone(),
-                category: msg.category,
+                category,
 
                 content_hash: format!("{:016x}", content_hash),
                 is_semantic,
+                repeat_of: None,
+                gap_ms: None,
+                parallel_group: parallel_group.clone(),
                 uses_span_end: false, // Will be set by classify_blocks()
                 is_history: false,    // Will be set by classify_blocks()
+                event_direction,
             });
         }
     }
@@ -1084,7 +1573,11 @@ fn flatten_to_blocks(
 /// ```text
 /// Parse → Flatten → [CLASSIFY] → Dedup → Sort
 /// ```
-fn classify_blocks(blocks: &mut [BlockEntry], span_timestamps: &HashMap<String, SpanTimestamps>) {
+fn classify_blocks(
+    blocks: &mut [BlockEntry],
+    span_timestamps: &HashMap<String, SpanTimestamps>,
+    clock_skew_tolerance: TimeDelta,
+) {
     // Step 1: Classify timestamp strategy for each block
     let mut output_count = 0;
     for block in blocks.iter_mut() {
@@ -1138,7 +1631,7 @@ fn classify_blocks(blocks: &mut [BlockEntry], span_timestamps: &HashMap<String,
     );
 
     // Step 2: Detect and mark history blocks
-    let stats = mark_history(blocks, span_timestamps);
+    let stats = mark_history(blocks, span_timestamps, clock_skew_tolerance);
 
     tracing::trace!(
         total_history = stats.total_history(),
@@ -1282,8 +1775,56 @@ fn compute_block_hash(block: &ContentBlock) -> u64 {
 // INTERNAL: METADATA
 // ============================================================================
 
+/// Span-level tokens and cost, keyed by span ID.
+type SpanMetrics = HashMap<String, (i64, f64)>;
+
+/// Collect span totals from the rows, before any block is dropped.
+fn span_metrics(rows: &[MessageSpanRow]) -> SpanMetrics {
+    rows.iter()
+        .map(|r| (r.span_id.clone(), (r.total_tokens, r.cost_total)))
+        .collect()
+}
+
+/// Put span-level tokens/cost on a single representative block per span.
+///
+/// Totals come from the span rows rather than the surviving blocks, so a span
+/// keeps its full totals whichever of its blocks were filtered, deduplicated
+/// or capped away. The representative is the span's first output block (or
+/// its first block if it has no output); all other blocks of the span are
+/// zeroed, or cleared when `inline` is set so only the representative shows
+/// metrics. Idempotent, so it can run again after further blocks are dropped.
+fn attribute_span_metrics(blocks: &mut [BlockEntry], metrics: &SpanMetrics, inline: bool) {
+    let mut representative: HashMap<&str, usize> = HashMap::new();
+    for (idx, block) in blocks.iter().enumerate() {
+        let current = representative.entry(&block.span_id).or_insert(idx);
+        if !blocks[*current].is_output_source() && block.is_output_source() {
+            *current = idx;
+        }
+    }
+    let keep: HashSet<usize> = representative.into_values().collect();
+
+    for (idx, block) in blocks.iter_mut().enumerate() {
+        if keep.contains(&idx) {
+            if let Some(&(tokens, cost)) = metrics.get(&block.span_id) {
+                block.tokens = Some(tokens);
+                block.cost = Some(cost);
+            }
+        } else if inline {
+            block.tokens = None;
+            block.cost = None;
+        } else {
+            block.tokens = Some(0);
+            block.cost = Some(0.0);
+        }
+    }
+}
+
 /// Compute metadata from processed blocks.
-fn compute_metadata(blocks: &[BlockEntry], span_rows: &[MessageSpanRow]) -> FeedMetadata {
+fn compute_metadata(
+    blocks: &[BlockEntry],
+    span_rows: &[MessageSpanRow],
+    warnings: Vec<FeedWarning>,
+) -> FeedMetadata {
     let span_ids: HashSet<_> = blocks.iter().map(|b| &b.span_id).collect();
     let total_tokens: i64 = span_rows.iter().map(|r| r.total_tokens).sum();
     let total_cost: f64 = span_rows.iter().map(|r| r.cost_total).sum();
@@ -1293,6 +1834,9 @@ fn compute_metadata(blocks: &[BlockEntry], span_rows: &[MessageSpanRow]) -> Feed
         span_count: span_ids.len(),
         total_tokens,
         total_cost,
+        content_sizes: ContentSizeStats::from_blocks(blocks),
+        warnings,
+        truncated: false,
     }
 }
 
@@ -1304,9 +1848,10 @@ fn compute_metadata(blocks: &[BlockEntry], span_rows: &[MessageSpanRow]) -> Feed
 ///
 /// Strategy:
 /// 1. Normalize provider-specific formats to OpenAI-style tool definitions.
-/// 2. Merge definitions with the same name to preserve complementary fields.
+/// 2. Merge definitions with the same name key (see [`tool_name_key`]) to
+///    preserve complementary fields.
 /// 3. Use quality score only to choose merge base / break ties.
-pub fn deduplicate_tools(raw: Vec<JsonValue>) -> Vec<JsonValue> {
+pub fn deduplicate_tools(raw: Vec<JsonValue>, fold_case: bool) -> Vec<JsonValue> {
     let mut by_name: HashMap<String, JsonValue> = HashMap::with_capacity(raw.len());
 
     for def in raw {
@@ -1317,10 +1862,15 @@ pub fn deduplicate_tools(raw: Vec<JsonValue>) -> Vec<JsonValue> {
         };
 
         for tool in defs {
-            let canonical = canonicalize_tool_definition(tool);
+            let mut canonical = canonicalize_tool_definition(tool);
             if let Some(name) = extract_tool_name(&canonical) {
+                if name.trim() != name
+                    && let Some(func) = function_map_mut(&mut canonical)
+                {
+                    func.insert("name".to_string(), json!(name.trim()));
+                }
                 by_name
-                    .entry(name)
+                    .entry(tool_name_key(&name, fold_case))
                     .and_modify(|existing| {
                         let merged = merge_tool_definitions(existing.clone(), canonical.clone());
                         *existing = merged;
@@ -1335,6 +1885,23 @@ pub fn deduplicate_tools(raw: Vec<JsonValue>) -> Vec<JsonValue> {
     tools.into_iter().map(|(_, def)| def).collect()
 }
 
+/// Grouping key for tool names.
+///
+/// Surrounding whitespace never distinguishes tools. With `fold_case`, case and
+/// `_`/`-` separators are ignored as well, so `getWeather` and `get_weather`
+/// merge. Folding is opt-in because case-sensitive frameworks can register
+/// distinct tools whose names differ only by case.
+fn tool_name_key(name: &str, fold_case: bool) -> String {
+    let name = name.trim();
+    if !fold_case {
+        return name.to_string();
+    }
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 fn canonicalize_tool_definition(tool: JsonValue) -> JsonValue {
     if tool.get("function").is_some() {
         return tool;
@@ -1547,14 +2114,14 @@ fn merge_required(primary_obj: &mut serde_json::Map<String, JsonValue>, secondar
     }
 }
 
-/// Deduplicate tool names, sort alphabetically.
-pub fn deduplicate_names(raw: Vec<String>) -> Vec<String> {
+/// Deduplicate tool names with the same grouping as [`deduplicate_tools`], sort alphabetically.
+pub fn deduplicate_names(raw: Vec<String>, fold_case: bool) -> Vec<String> {
     let mut seen: HashSet<String> = HashSet::with_capacity(raw.len());
     let mut names: Vec<String> = Vec::with_capacity(raw.len());
 
     for name in raw {
-        if seen.insert(name.clone()) {
-            names.push(name);
+        if seen.insert(tool_name_key(&name, fold_case)) {
+            names.push(name.trim().to_string());
         }
     }
 
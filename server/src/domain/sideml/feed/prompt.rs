@@ -0,0 +1,57 @@
+//! Prompt reconstruction for a single span.
+//!
+//! The conversation feed strips history so each message appears once. When
+//! debugging a specific LLM call the opposite is needed: the exact input the
+//! model received, history included. This path parses and flattens the span's
+//! raw messages like the feed does, keeps only input blocks, and skips history
+//! marking and dedup entirely.
+
+use super::types::{BlockEntry, FeedOptions, FeedResult};
+use super::{
+    anonymize_blocks, build_span_hierarchy, compute_metadata, extract_tools_from_rows,
+    flatten_to_blocks, parse_span_rows,
+};
+use crate::data::types::MessageSpanRow;
+
+/// Reconstruct the input messages sent by the spans in `rows`.
+///
+/// Output blocks (completion events, output attributes, blocks with a finish
+/// reason) are dropped. Input from events and from attributes is kept in the
+/// order it was recorded on the span.
+pub fn process_prompt(rows: Vec<MessageSpanRow>, options: &FeedOptions) -> FeedResult {
+    let mut warnings = Vec::new();
+
+    let parsed_messages = parse_span_rows(&rows, &options.default_roles, &mut warnings);
+    let extracted_tools = extract_tools_from_rows(&rows, options.fold_tool_name_case);
+
+    let span_hierarchy = build_span_hierarchy(&rows, &options.stored_parents, &mut warnings);
+    let mut blocks = flatten_to_blocks(parsed_messages, &span_hierarchy, options);
+
+    blocks.retain(is_prompt_block);
+    blocks.sort_by(|a, b| {
+        (&a.span_id, a.message_index, a.entry_index).cmp(&(
+            &b.span_id,
+            b.message_index,
+            b.entry_index,
+        ))
+    });
+
+    if options.anonymize {
+        anonymize_blocks(&mut blocks);
+    }
+
+    let metadata = compute_metadata(&blocks, &rows, warnings);
+
+    FeedResult {
+        messages: blocks,
+        turns: Vec::new(),
+        tool_definitions: extracted_tools.tool_definitions,
+        tool_names: extracted_tools.tool_names,
+        metadata,
+    }
+}
+
+/// Whether a block was part of the span's input rather than its output.
+fn is_prompt_block(block: &BlockEntry) -> bool {
+    !block.is_output_source() && !block.is_protected()
+}
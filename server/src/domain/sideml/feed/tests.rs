@@ -40,6 +40,7 @@ fn make_span_row(
         messages_json: messages_json.to_string(),
         tool_definitions_json: tool_definitions_json.to_string(),
         tool_names_json: tool_names_json.to_string(),
+        ingest_warnings_json: "[]".to_string(),
         model: Some("gpt-4".to_string()),
         provider: Some("openai".to_string()),
         status_code: None,
@@ -53,6 +54,7 @@ fn make_span_row(
         observation_type: None,
         session_id: None,
         ingested_at: ts,
+        environment: None,
     }
 }
 
@@ -96,6 +98,7 @@ fn make_span_row_full(
         messages_json: messages_json.to_string(),
         tool_definitions_json: "[]".to_string(),
         tool_names_json: "[]".to_string(),
+        ingest_warnings_json: "[]".to_string(),
         model: Some("gpt-4".to_string()),
         provider: Some("openai".to_string()),
         status_code: None,
@@ -109,6 +112,7 @@ fn make_span_row_full(
         observation_type: observation_type.map(String::from),
         session_id: None,
         ingested_at: span_start,
+        environment: None,
     }
 }
 
@@ -190,7 +194,7 @@ fn test_deduplicate_tools() {
         json!({"type": "function", "function": {"name": "tool_a", "description": "A again"}}),
     ];
 
-    let deduped = deduplicate_tools(tools);
+    let deduped = deduplicate_tools(tools, false);
     assert_eq!(deduped.len(), 2);
 
     let names: Vec<_> = deduped
@@ -219,7 +223,7 @@ fn test_deduplicate_tools_prefers_richer_definition() {
         }),
     ];
 
-    let deduped = deduplicate_tools(tools);
+    let deduped = deduplicate_tools(tools, false);
     assert_eq!(deduped.len(), 1);
 
     let func = &deduped[0]["function"];
@@ -252,7 +256,7 @@ fn test_deduplicate_tools_merges_complementary_fields() {
         }),
     ];
 
-    let deduped = deduplicate_tools(tools);
+    let deduped = deduplicate_tools(tools, false);
     assert_eq!(deduped.len(), 1);
 
     let func = &deduped[0]["function"];
@@ -295,7 +299,7 @@ fn test_deduplicate_tools_merges_parameter_properties_and_required() {
         }),
     ];
 
-    let deduped = deduplicate_tools(tools);
+    let deduped = deduplicate_tools(tools, false);
     assert_eq!(deduped.len(), 1);
 
     let params = &deduped[0]["function"]["parameters"];
@@ -313,6 +317,37 @@ fn test_deduplicate_tools_merges_parameter_properties_and_required() {
     assert!(required.contains(&json!("days")));
 }
 
+#[test]
+fn test_deduplicate_tools_normalizes_names() {
+    let tools = vec![
+        json!({"type": "function", "function": {"name": "get_weather", "description": "Get weather"}}),
+        json!({"type": "function", "function": {"name": " get_weather  "}}),
+        json!({"type": "function", "function": {"name": "getWeather"}}),
+    ];
+
+    // Whitespace never distinguishes tools; case stays significant by default
+    let deduped = deduplicate_tools(tools.clone(), false);
+    let names: Vec<&str> = deduped
+        .iter()
+        .map(|t| t["function"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["getWeather", "get_weather"]);
+
+    let deduped = deduplicate_tools(tools, true);
+    assert_eq!(deduped.len(), 1);
+    assert_eq!(
+        deduped[0]["function"]["description"].as_str(),
+        Some("Get weather")
+    );
+
+    let names = vec!["get_weather ".to_string(), "getWeather".to_string()];
+    assert_eq!(
+        deduplicate_names(names.clone(), false),
+        vec!["getWeather", "get_weather"]
+    );
+    assert_eq!(deduplicate_names(names, true).len(), 1);
+}
+
 #[test]
 fn test_deduplicate_names() {
     let names = vec![
@@ -322,7 +357,7 @@ fn test_deduplicate_names() {
         "tool_c".to_string(),
     ];
 
-    let deduped = deduplicate_names(names);
+    let deduped = deduplicate_names(names, false);
     assert_eq!(deduped, vec!["tool_a", "tool_b", "tool_c"]);
 }
 
@@ -363,7 +398,7 @@ fn test_block_entry_metadata() {
     row.session_id = Some("session1".to_string());
     row.status_code = Some("OK".to_string());
 
-    let options = FeedOptions::default();
+    let options = FeedOptions::default().with_include_hashes(true);
     let result = process_spans(vec![row], &options);
 
     assert_eq!(result.messages.len(), 1);
@@ -446,6 +481,57 @@ fn test_span_path_computation() {
     );
 }
 
+#[test]
+fn test_parallel_tool_calls_share_group() {
+    let msg = json!([
+        {
+            "source": {"event": {"name": "gen_ai.assistant.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {
+                "role": "assistant",
+                "content": [
+                    {"type": "text", "text": "Checking all three cities"},
+                    {"type": "tool_use", "id": "call_1", "name": "weather", "input": {"city": "Paris"}},
+                    {"type": "tool_use", "id": "call_2", "name": "weather", "input": {"city": "Rome"}},
+                    {"type": "tool_use", "id": "call_3", "name": "weather", "input": {"city": "Oslo"}}
+                ]
+            }
+        },
+        {
+            "source": {"event": {"name": "gen_ai.assistant.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {
+                "role": "assistant",
+                "content": [{"type": "tool_use", "id": "call_4", "name": "summarize", "input": {}}]
+            }
+        }
+    ]);
+
+    let row = make_span_row("trace1", "span1", None, &msg.to_string(), "[]", "[]");
+    let result = process_spans(vec![row], &FeedOptions::default());
+
+    let group_of = |id: &str| {
+        result
+            .messages
+            .iter()
+            .find(|b| b.tool_use_id.as_deref() == Some(id))
+            .unwrap_or_else(|| panic!("missing tool call {id}"))
+            .parallel_group
+            .clone()
+    };
+    let group = group_of("call_1");
+    assert!(group.is_some());
+    assert_eq!(group_of("call_2"), group);
+    assert_eq!(group_of("call_3"), group);
+
+    // A lone tool call and non-tool blocks are not grouped
+    assert_eq!(group_of("call_4"), None);
+    let text = result
+        .messages
+        .iter()
+        .find(|b| b.entry_type == "text")
+        .unwrap();
+    assert_eq!(text.parallel_group, None);
+}
+
 #[test]
 fn test_tool_use_extraction() {
     let msg = json!([{
@@ -528,6 +614,60 @@ fn test_sorting_by_timestamp_message_entry() {
     assert!(matches!(&result.messages[1].content, ContentBlock::Text { text } if text == "Second"));
 }
 
+#[test]
+fn test_malformed_messages_json_produces_parse_warning() {
+    let msg = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+        "content": {"role": "user", "content": "Fine"}
+    }]);
+
+    let good = make_span_row("trace1", "span1", None, &msg.to_string(), "[]", "[]");
+    let bad = make_span_row("trace1", "span2", Some("span1"), "[{not json", "[]", "[]");
+    let result = process_spans(vec![good, bad], &FeedOptions::default());
+
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.metadata.warnings.len(), 1);
+    let warning = &result.metadata.warnings[0];
+    assert_eq!(warning.kind, FeedWarningKind::ParseError);
+    assert_eq!(warning.trace_id, "trace1");
+    assert_eq!(warning.span_id, "span2");
+    assert!(warning.message.starts_with("Failed to parse messages"));
+}
+
+#[test]
+fn test_ingest_warnings_surface_in_metadata() {
+    let msg = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+        "content": {"role": "user", "content": "Fine"}
+    }]);
+
+    let clean = make_span_row("trace1", "span1", None, &msg.to_string(), "[]", "[]");
+    let mut flagged = make_span_row("trace1", "span2", Some("span1"), "[]", "[]", "[]");
+    flagged.ingest_warnings_json = json!([
+        {"kind": "future_timestamp", "message": "Span started 3600s in the future"},
+        {"kind": "parse_error", "message": "Failed to parse gen_ai.input.messages"}
+    ])
+    .to_string();
+    let result = process_spans(vec![clean, flagged], &FeedOptions::default());
+
+    assert_eq!(result.messages.len(), 1);
+    let kinds: Vec<_> = result.metadata.warnings.iter().map(|w| w.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            FeedWarningKind::FutureTimestamp,
+            FeedWarningKind::ParseError
+        ]
+    );
+    assert!(
+        result
+            .metadata
+            .warnings
+            .iter()
+            .all(|w| w.span_id == "span2")
+    );
+}
+
 #[test]
 fn test_metadata() {
     let msg = json!([{
@@ -545,6 +685,264 @@ fn test_metadata() {
     assert!((result.metadata.total_cost - 0.01).abs() < 0.001);
 }
 
+#[test]
+fn test_span_metrics_attributed_once_per_span() {
+    let msg1 = json!([
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {"role": "user", "content": "Question"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.choice", "time": "2025-01-01T00:00:01Z"}},
+            "content": {"role": "assistant", "content": "Answer"}
+        }
+    ]);
+    let msg2 = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:02Z"}},
+        "content": {"role": "user", "content": "Follow-up"}
+    }]);
+
+    let rows = vec![
+        make_span_row("trace1", "span1", None, &msg1.to_string(), "[]", "[]"),
+        make_span_row("trace1", "span2", None, &msg2.to_string(), "[]", "[]"),
+    ];
+    let expected_tokens: i64 = rows.iter().map(|r| r.total_tokens).sum();
+    let expected_cost: f64 = rows.iter().map(|r| r.cost_total).sum();
+
+    let result = process_spans(rows, &FeedOptions::default());
+    assert_eq!(result.messages.len(), 3);
+
+    let block_tokens: i64 = result.messages.iter().filter_map(|b| b.tokens).sum();
+    let block_cost: f64 = result.messages.iter().filter_map(|b| b.cost).sum();
+    assert_eq!(block_tokens, expected_tokens);
+    assert!((block_cost - expected_cost).abs() < 1e-12);
+    assert_eq!(block_tokens, result.metadata.total_tokens);
+
+    // Output block carries the span totals, input block of the same span is zeroed
+    let answer = result
+        .messages
+        .iter()
+        .find(|b| matches!(&b.content, ContentBlock::Text { text } if text == "Answer"))
+        .unwrap();
+    let question = result
+        .messages
+        .iter()
+        .find(|b| matches!(&b.content, ContentBlock::Text { text } if text == "Question"))
+        .unwrap();
+    assert_eq!(answer.tokens, Some(150));
+    assert_eq!(question.tokens, Some(0));
+    assert_eq!(question.cost, Some(0.0));
+}
+
+#[test]
+fn test_inline_turn_metrics_only_on_output_block() {
+    let msg = json!([
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {"role": "user", "content": "Question"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.choice", "time": "2025-01-01T00:00:01Z"}},
+            "content": {"role": "assistant", "content": "Answer"}
+        }
+    ]);
+    let row = make_span_row("trace1", "span1", None, &msg.to_string(), "[]", "[]");
+    let (span_tokens, span_cost) = (row.total_tokens, row.cost_total);
+
+    let options = FeedOptions::new().with_inline_turn_metrics(true);
+    let result = process_spans(vec![row], &options);
+    assert_eq!(result.messages.len(), 2);
+
+    let answer = result
+        .messages
+        .iter()
+        .find(|b| matches!(&b.content, ContentBlock::Text { text } if text == "Answer"))
+        .unwrap();
+    let question = result
+        .messages
+        .iter()
+        .find(|b| matches!(&b.content, ContentBlock::Text { text } if text == "Question"))
+        .unwrap();
+    assert_eq!(answer.tokens, Some(span_tokens));
+    assert_eq!(answer.cost, Some(span_cost));
+    assert_eq!(question.tokens, None);
+    assert_eq!(question.cost, None);
+
+    // Totals are unchanged by hiding the zeroed metrics
+    let block_tokens: i64 = result.messages.iter().filter_map(|b| b.tokens).sum();
+    assert_eq!(block_tokens, result.metadata.total_tokens);
+}
+
+#[test]
+fn test_span_metrics_kept_when_only_error_block_survives() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(1);
+
+    // Failed generation with usage but no messages: its only block is the
+    // synthesized error block, which starts with zero metrics
+    let mut error_row = make_span_row_full(
+        "t1",
+        "error-span",
+        None,
+        "[]",
+        t0,
+        Some(t1),
+        Some("generation"),
+    );
+    error_row.status_code = Some("ERROR".to_string());
+    error_row.exception_message = Some("Output parser failed".into());
+    let (span_tokens, span_cost) = (error_row.total_tokens, error_row.cost_total);
+
+    let result = process_spans(vec![error_row], &FeedOptions::default());
+    assert_eq!(result.messages.len(), 1);
+    assert!(result.messages[0].is_error);
+    assert_eq!(result.messages[0].tokens, Some(span_tokens));
+    assert_eq!(result.messages[0].cost, Some(span_cost));
+}
+
+#[test]
+fn test_max_blocks_keeps_most_recent_and_flags_truncation() {
+    let events: Vec<JsonValue> = (0..6)
+        .map(|i| {
+            let (event, role) = if i % 2 == 0 {
+                ("gen_ai.user.message", "user")
+            } else {
+                ("gen_ai.choice", "assistant")
+            };
+            json!({
+                "source": {"event": {"name": event, "time": format!("2025-01-01T00:00:0{i}Z")}},
+                "content": {"role": role, "content": format!("Message {i}")}
+            })
+        })
+        .collect();
+    let row = make_span_row(
+        "trace1",
+        "span1",
+        None,
+        &JsonValue::Array(events).to_string(),
+        "[]",
+        "[]",
+    );
+    let texts = |result: &FeedResult| -> Vec<String> {
+        result
+            .messages
+            .iter()
+            .filter_map(|b| get_text(&b.content).map(str::to_string))
+            .collect()
+    };
+
+    let uncapped = process_spans(vec![row.clone()], &FeedOptions::new());
+    assert_eq!(uncapped.messages.len(), 6);
+    assert!(!uncapped.metadata.truncated);
+
+    // Conversation view (ASC): the oldest blocks are dropped
+    let options = FeedOptions::new().with_max_blocks(Some(4));
+    let result = process_spans(vec![row.clone()], &options);
+    assert_eq!(result.messages.len(), 4);
+    assert_eq!(result.metadata.block_count, 4);
+    assert!(result.metadata.truncated);
+    assert_eq!(
+        texts(&result),
+        ["Message 2", "Message 3", "Message 4", "Message 5"]
+    );
+
+    // Feed view (DESC): the newest blocks are kept at the front
+    let result = process_feed(vec![row.clone()], &options);
+    assert_eq!(result.messages.len(), 4);
+    assert!(result.metadata.truncated);
+    assert_eq!(texts(&result)[0], "Message 5");
+
+    // At or under the cap nothing is flagged
+    let result = process_spans(vec![row], &FeedOptions::new().with_max_blocks(Some(6)));
+    assert_eq!(result.messages.len(), 6);
+    assert!(!result.metadata.truncated);
+}
+
+#[test]
+fn test_anonymize_preserves_structure() {
+    let msg = json!([
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {"role": "user", "content": "Look up order 12345 for alice@example.com"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.assistant.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {
+                "role": "assistant",
+                "content": [{
+                    "type": "tool_use",
+                    "id": "call_1",
+                    "name": "lookup_order",
+                    "input": {"email": "alice@example.com", "order_id": 12345}
+                }]
+            }
+        },
+        {
+            "source": {"event": {"name": "gen_ai.tool.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {"role": "tool", "tool_use_id": "call_1", "content": "Order shipped to Berlin"}
+        }
+    ]);
+
+    let plain = process_spans(
+        vec![make_span_row(
+            "trace1",
+            "span1",
+            None,
+            &msg.to_string(),
+            "[]",
+            "[]",
+        )],
+        &FeedOptions::default(),
+    );
+    let anon = process_spans(
+        vec![make_span_row(
+            "trace1",
+            "span1",
+            None,
+            &msg.to_string(),
+            "[]",
+            "[]",
+        )],
+        &FeedOptions::new().with_anonymize(true),
+    );
+
+    assert_eq!(plain.messages.len(), 3);
+    assert_eq!(anon.messages.len(), plain.messages.len());
+    assert_eq!(anon.metadata.total_tokens, plain.metadata.total_tokens);
+    assert_eq!(anon.metadata.total_cost, plain.metadata.total_cost);
+
+    for (p, a) in plain.messages.iter().zip(&anon.messages) {
+        assert_eq!(p.role, a.role);
+        assert_eq!(p.entry_type, a.entry_type);
+        assert_eq!(p.tool_name, a.tool_name);
+        assert_eq!(p.tool_use_id, a.tool_use_id);
+        assert_eq!(p.tokens, a.tokens);
+        assert_ne!(
+            serde_json::to_value(&p.content).unwrap(),
+            serde_json::to_value(&a.content).unwrap(),
+            "content should be scrubbed"
+        );
+    }
+
+    let user = &anon.messages[0];
+    let ContentBlock::Text { text } = &user.content else {
+        panic!("Expected text block");
+    };
+    assert!(!text.contains("alice"));
+    assert_eq!(
+        text.chars().count(),
+        "Look up order 12345 for alice@example.com".chars().count()
+    );
+
+    let ContentBlock::ToolUse { name, input, .. } = &anon.messages[1].content else {
+        panic!("Expected tool_use block");
+    };
+    assert_eq!(name, "lookup_order");
+    assert!(input["email"].is_string());
+    assert_ne!(input["email"], json!("alice@example.com"));
+    assert!(input["order_id"].is_u64());
+}
+
 // ============================================================================
 // DEDUPLICATION INTEGRATION TESTS
 // ============================================================================
@@ -1101,6 +1499,55 @@ fn test_regression_no_spurious_tool_input_block() {
     );
 }
 
+#[test]
+fn test_keep_tool_input_json_option() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(1);
+    let tool_msg = json!([
+        {
+            "source": {"event": {"name": "tool_handler.invoke", "time": t0.to_rfc3339()}},
+            "content": {"city": "Los Angeles", "days": 7}
+        },
+        {
+            "source": {"event": {"name": "tool_handler.result", "time": t1.to_rfc3339()}},
+            "content": {
+                "role": "tool",
+                "tool_use_id": "tooluse_ABC",
+                "content": {"json": {"result": "sunny"}}
+            }
+        }
+    ]);
+    let rows = || {
+        vec![make_tool_span_row(
+            "trace1",
+            "tool1",
+            Some("root"),
+            &tool_msg.to_string(),
+            t0,
+            Some(t1),
+        )]
+    };
+
+    // Off (default): tool arguments are dropped
+    let result = process_spans(rows(), &FeedOptions::default());
+    assert!(result.messages.iter().all(|m| m.entry_type != "json"));
+
+    // On: tool arguments surface as a json block tagged as tool input
+    let options = FeedOptions::new().with_keep_tool_input_json(true);
+    let result = process_spans(rows(), &options);
+    let inputs: Vec<_> = result
+        .messages
+        .iter()
+        .filter(|m| m.entry_type == "json")
+        .collect();
+    assert_eq!(inputs.len(), 1, "tool input JSON should be kept");
+    assert_eq!(inputs[0].category, MessageCategory::GenAIToolInput);
+    assert!(matches!(
+        &inputs[0].content,
+        ContentBlock::Json { data } if data["city"] == "Los Angeles"
+    ));
+}
+
 // ----------------------------------------------------------------------------
 // ISSUE 6: Tool Results with Same tool_use_id but Different Content Hash
 // ----------------------------------------------------------------------------
@@ -1625,7 +2072,7 @@ fn test_regression_hash_function_consistency() {
 
     let row =
         make_span_row_with_timestamps("trace1", "span1", None, &msg.to_string(), t0, Some(t0));
-    let options = FeedOptions::default();
+    let options = FeedOptions::default().with_include_hashes(true);
     let result = process_spans(vec![row], &options);
 
     assert_eq!(result.messages.len(), 1);
@@ -2052,6 +2499,35 @@ fn test_regression_deep_hierarchy_span_path() {
     );
 }
 
+#[test]
+fn test_span_path_resolves_parent_from_stored_spans() {
+    let msg = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": fixed_time().to_rfc3339()}},
+        "content": {"role": "user", "content": "Late child"}
+    }]);
+
+    // Only the child is in this batch; "agent" and "root" were persisted earlier
+    let rows = vec![make_span_row(
+        "trace1",
+        "llm",
+        Some("agent"),
+        &msg.to_string(),
+        "[]",
+        "[]",
+    )];
+    let stored = HashMap::from([
+        ("agent".to_string(), "root".to_string()),
+        ("llm".to_string(), "agent".to_string()),
+    ]);
+
+    let batch_only = process_spans(rows.clone(), &FeedOptions::default());
+    assert_eq!(batch_only.messages[0].span_path, vec!["agent", "llm"]);
+
+    let options = FeedOptions::default().with_stored_parents(stored);
+    let result = process_spans(rows, &options);
+    assert_eq!(result.messages[0].span_path, vec!["root", "agent", "llm"]);
+}
+
 // ----------------------------------------------------------------------------
 // ISSUE 19: Thinking and Text in Same Message
 // ----------------------------------------------------------------------------
@@ -5545,7 +6021,7 @@ fn test_regression_tool_use_flow_complete() {
         "generation",
     )];
 
-    let options = FeedOptions::default();
+    let options = FeedOptions::default().with_include_hashes(true);
     let result = process_spans(rows, &options);
 
     // Verify message count and order
@@ -5698,12 +6174,78 @@ fn test_error_messages_leaf_only() {
 }
 
 #[test]
-fn test_error_messages_no_status_message() {
+fn test_retain_errors_with_context() {
     let t0 = fixed_time();
     let t1 = t0 + chrono::Duration::seconds(1);
+    let t2 = t0 + chrono::Duration::seconds(2);
 
-    // Error span without exception fields -> should NOT get error block
-    let mut error_row = make_span_row_full(
+    let msgs = json!([
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {"role": "user", "content": "Fetch the latest invoice"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.assistant.message", "time": "2025-01-01T00:00:00Z"}},
+            "content": {
+                "role": "assistant",
+                "content": [{
+                    "type": "tool_use",
+                    "id": "call_1",
+                    "name": "fetch_invoice",
+                    "input": {"latest": true}
+                }]
+            }
+        }
+    ]);
+    let generation = make_span_row_full(
+        "t1",
+        "gen-span",
+        Some("root-span"),
+        &msgs.to_string(),
+        t0,
+        Some(t1),
+        Some("generation"),
+    );
+    let mut tool = make_span_row_full(
+        "t1",
+        "tool-span",
+        Some("root-span"),
+        "[]",
+        t1,
+        Some(t2),
+        Some("tool"),
+    );
+    tool.status_code = Some("ERROR".to_string());
+    tool.exception_type = Some("ConnectionError".to_string());
+    tool.exception_message = Some("Connection refused".to_string());
+
+    let mut result = process_spans(vec![generation, tool], &FeedOptions::new());
+    assert!(result.messages.len() > 2);
+
+    retain_errors_with_context(&mut result);
+
+    assert_eq!(result.messages.len(), 2);
+    let context = &result.messages[0];
+    assert_eq!(context.span_id, "gen-span");
+    assert_eq!(context.entry_type, "tool_use");
+    let error = &result.messages[1];
+    assert_eq!(error.span_id, "tool-span");
+    assert_eq!(error.category, MessageCategory::Exception);
+    assert_eq!(
+        get_text(&error.content),
+        Some("ConnectionError: Connection refused")
+    );
+    assert_eq!(result.metadata.block_count, 2);
+    assert_eq!(result.metadata.span_count, 2);
+}
+
+#[test]
+fn test_error_messages_no_status_message() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(1);
+
+    // Error span without exception fields -> should NOT get error block
+    let mut error_row = make_span_row_full(
         "t1",
         "error-span",
         None,
@@ -7360,6 +7902,448 @@ fn test_cross_trace_system_per_trace() {
     assert!(has_welcome, "asst('Welcome') should be present from trace2");
 }
 
+#[test]
+fn test_exclude_system_keeps_history_detection() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(10);
+
+    let msg1 = json!([
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t0.to_rfc3339()}},
+            "content": {"role": "system", "content": "You are helpful"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t0.to_rfc3339()}},
+            "content": {"role": "user", "content": "Hello"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": t0.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Hi"}
+        }
+    ]);
+    let msg2 = json!([
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "system", "content": "You are helpful"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "user", "content": "Hello"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Hi"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "user", "content": "Thanks"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": t1.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Welcome"}
+        }
+    ]);
+    let rows = || {
+        vec![
+            make_span_row_full(
+                "trace1",
+                "s1",
+                None,
+                &msg1.to_string(),
+                t0,
+                Some(t0),
+                Some("generation"),
+            ),
+            make_span_row_full(
+                "trace2",
+                "s2",
+                None,
+                &msg2.to_string(),
+                t1,
+                Some(t1),
+                Some("generation"),
+            ),
+        ]
+    };
+
+    let full = process_spans(rows(), &FeedOptions::new());
+    let filtered = process_spans(rows(), &FeedOptions::new().with_exclude_system(true));
+
+    assert!(full.messages.iter().any(|b| b.role == ChatRole::System));
+    assert!(filtered.messages.iter().all(|b| b.role != ChatRole::System));
+
+    let key = |b: &BlockEntry| {
+        (
+            b.span_id.clone(),
+            b.role,
+            b.content_hash.clone(),
+            b.is_history,
+        )
+    };
+    let expected: Vec<_> = full
+        .messages
+        .iter()
+        .filter(|b| b.role != ChatRole::System)
+        .map(key)
+        .collect();
+    let actual: Vec<_> = filtered.messages.iter().map(key).collect();
+    assert_eq!(actual, expected);
+    assert_eq!(
+        filtered.metadata.total_tokens, full.metadata.total_tokens,
+        "span totals must not depend on which blocks are shown"
+    );
+}
+
+#[test]
+fn test_collapse_repeated_system_prompt_across_traces() {
+    let t0 = fixed_time();
+    let system = "You are a careful assistant. Always cite your sources.";
+    let rows = || {
+        ["first", "second", "third"]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let ts = t0 + chrono::Duration::seconds(10 * i as i64);
+                let msgs = json!([
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": ts.to_rfc3339()}},
+                        "content": {"role": "system", "content": system}
+                    },
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": ts.to_rfc3339()}},
+                        "content": {"role": "user", "content": format!("Question {word}")}
+                    },
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": ts.to_rfc3339()}},
+                        "content": {"role": "assistant", "content": format!("Answer {word}")}
+                    }
+                ]);
+                make_span_row_full(
+                    &format!("trace{i}"),
+                    &format!("s{i}"),
+                    None,
+                    &msgs.to_string(),
+                    ts,
+                    Some(ts),
+                    Some("generation"),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let full = process_spans(rows(), &FeedOptions::new());
+    let collapsed = process_spans(
+        rows(),
+        &FeedOptions::new().with_collapse_repeated_system(true),
+    );
+
+    let systems: Vec<&BlockEntry> = collapsed
+        .messages
+        .iter()
+        .filter(|b| b.role == ChatRole::System)
+        .collect();
+    assert_eq!(systems.len(), 3, "every trace keeps its system block");
+    assert!(matches!(&systems[0].content, ContentBlock::Text { text } if text == system));
+    assert!(systems[0].repeat_of.is_none());
+    for repeat in &systems[1..] {
+        assert!(
+            matches!(&repeat.content, ContentBlock::Text { text } if text == super::collapse::REPEATED_SYSTEM_MARKER)
+        );
+        assert_eq!(
+            repeat.repeat_of.as_deref(),
+            Some(systems[0].span_id.as_str())
+        );
+        assert_eq!(repeat.content_hash, systems[0].content_hash);
+    }
+
+    // Display-only: the same blocks survive history detection and dedup
+    let key = |b: &BlockEntry| (b.span_id.clone(), b.role, b.content_hash.clone());
+    let expected: Vec<_> = full.messages.iter().map(key).collect();
+    let actual: Vec<_> = collapsed.messages.iter().map(key).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_collapse_repeated_system_after_block_cap() {
+    let t0 = fixed_time();
+    let system = "You are a careful assistant. Always cite your sources.";
+    let rows = || {
+        (0..3)
+            .map(|i| {
+                let ts = t0 + chrono::Duration::seconds(10 * i as i64);
+                let msgs = json!([
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": ts.to_rfc3339()}},
+                        "content": {"role": "system", "content": system}
+                    },
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": ts.to_rfc3339()}},
+                        "content": {"role": "user", "content": format!("Question {i}")}
+                    },
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": ts.to_rfc3339()}},
+                        "content": {"role": "assistant", "content": format!("Answer {i}")}
+                    }
+                ]);
+                make_span_row_full(
+                    &format!("trace{i}"),
+                    &format!("s{i}"),
+                    None,
+                    &msgs.to_string(),
+                    ts,
+                    Some(ts),
+                    Some("generation"),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let options = FeedOptions::new()
+        .with_collapse_repeated_system(true)
+        .with_max_blocks(Some(6));
+
+    // Conversation (ASC) and project feed (DESC) both drop the oldest trace
+    for result in [
+        process_spans(rows(), &options),
+        process_feed(rows(), &options),
+    ] {
+        assert!(result.metadata.truncated);
+        let systems: Vec<&BlockEntry> = result
+            .messages
+            .iter()
+            .filter(|b| b.role == ChatRole::System)
+            .collect();
+        assert_eq!(systems.len(), 2);
+        let (original, repeat) = if systems[0].repeat_of.is_none() {
+            (systems[0], systems[1])
+        } else {
+            (systems[1], systems[0])
+        };
+        assert_eq!(original.span_id, "s1");
+        assert!(matches!(&original.content, ContentBlock::Text { text } if text == system));
+        assert_eq!(repeat.repeat_of.as_deref(), Some("s1"));
+        assert_eq!(
+            result.metadata.content_sizes,
+            ContentSizeStats::from_blocks(&result.messages)
+        );
+    }
+}
+
+#[test]
+fn test_configured_output_event_is_protected() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(5);
+    let msgs = json!([
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": t0.to_rfc3339()}},
+            "content": {"role": "user", "content": "Plan the trip"}
+        },
+        {
+            "source": {"event": {"name": "acme.llm.response", "time": t0.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Here is the plan"}
+        }
+    ]);
+    let rows = || {
+        vec![make_span_row_full(
+            "trace1",
+            "s1",
+            None,
+            &msgs.to_string(),
+            t0,
+            Some(t1),
+            Some("generation"),
+        )]
+    };
+    let output = |result: &FeedResult| {
+        result
+            .messages
+            .iter()
+            .find(|b| b.event_name.as_deref() == Some("acme.llm.response"))
+            .cloned()
+            .unwrap()
+    };
+
+    let unknown = output(&process_spans(rows(), &FeedOptions::new()));
+    assert!(!unknown.is_protected());
+    assert_eq!(unknown.timestamp, t0);
+
+    let options = FeedOptions::new().with_event_names(EventNames {
+        output: vec!["acme.llm.response".to_string()],
+        input: Vec::new(),
+    });
+    let registered = output(&process_spans(rows(), &options));
+    assert!(registered.is_output_event());
+    assert!(registered.is_protected());
+    assert_eq!(registered.timestamp, t1, "output events use span end");
+}
+
+#[test]
+fn test_gaps_match_timestamp_difference() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::milliseconds(1500);
+    let call = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": t0.to_rfc3339()}},
+        "content": {"role": "user", "content": "Look up the weather"}
+    }]);
+    let result = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": t1.to_rfc3339()}},
+        "content": {"role": "user", "content": "Now summarize it"}
+    }]);
+    let rows = || {
+        vec![
+            make_span_row_full("trace1", "s1", None, &call.to_string(), t0, Some(t0), None),
+            make_span_row_full(
+                "trace1",
+                "s2",
+                None,
+                &result.to_string(),
+                t1,
+                Some(t1),
+                None,
+            ),
+        ]
+    };
+
+    let plain = process_spans(rows(), &FeedOptions::new());
+    assert!(plain.messages.iter().all(|b| b.gap_ms.is_none()));
+
+    let result = process_spans(rows(), &FeedOptions::new().with_include_gaps(true));
+    assert_eq!(result.messages.len(), 2);
+    assert_eq!(
+        result.messages[0].gap_ms, None,
+        "first block has no previous"
+    );
+    assert_eq!(
+        result.messages[1].gap_ms,
+        Some((result.messages[1].timestamp - result.messages[0].timestamp).num_milliseconds())
+    );
+    assert_eq!(result.messages[1].gap_ms, Some(1500));
+}
+
+#[test]
+fn test_group_turns_merges_consecutive_same_role_blocks() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(1);
+    let answer = json!([{
+        "source": {"event": {"name": "gen_ai.choice", "time": t0.to_rfc3339()}},
+        "content": {
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "text": "The user wants the weather."},
+                {"type": "text", "text": "Let me check."},
+                {"type": "text", "text": "One moment."},
+                {"type": "tool_use", "id": "call_1", "name": "get_weather", "input": {"city": "Paris"}}
+            ],
+            "finish_reason": "tool_calls"
+        }
+    }]);
+    let tool_result = json!([{
+        "source": {"event": {"name": "gen_ai.tool.message", "time": t1.to_rfc3339()}},
+        "content": {"role": "tool", "tool_use_id": "call_1", "content": "Sunny, 22C"}
+    }]);
+    let rows = || {
+        vec![
+            make_span_row_full(
+                "trace1",
+                "s1",
+                None,
+                &answer.to_string(),
+                t0,
+                Some(t0),
+                None,
+            ),
+            make_span_row_full(
+                "trace1",
+                "s2",
+                None,
+                &tool_result.to_string(),
+                t1,
+                Some(t1),
+                None,
+            ),
+        ]
+    };
+
+    let plain = process_spans(rows(), &FeedOptions::new());
+    assert!(plain.turns.is_empty(), "turns are opt-in");
+
+    let result = process_spans(rows(), &FeedOptions::new().with_group_turns(true));
+    assert_eq!(result.messages.len(), 5);
+    let shape: Vec<(ChatRole, &str, usize, usize)> = result
+        .turns
+        .iter()
+        .map(|t| (t.role, t.span_id.as_str(), t.start, t.block_count))
+        .collect();
+    assert_eq!(
+        shape,
+        vec![
+            (ChatRole::Assistant, "s1", 0, 3),
+            (ChatRole::Assistant, "s1", 3, 1),
+            (ChatRole::Tool, "s2", 4, 1),
+        ],
+        "thinking and text share a turn; the tool call and its result stand alone"
+    );
+    assert_eq!(result.messages[3].entry_type, "tool_use");
+    assert_eq!(result.messages[4].role, ChatRole::Tool);
+}
+
+#[test]
+fn test_include_hashes_exposes_stable_content_hashes() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(1);
+    let question = json!([{
+        "source": {"event": {"name": "gen_ai.user.message", "time": t0.to_rfc3339()}},
+        "content": {"role": "user", "content": "What is the capital of France?"}
+    }]);
+    let answer = json!([{
+        "source": {"event": {"name": "gen_ai.choice", "time": t1.to_rfc3339()}},
+        "content": {"role": "assistant", "content": "Paris."}
+    }]);
+    let rows = || {
+        vec![
+            make_span_row_full(
+                "trace1",
+                "s1",
+                None,
+                &question.to_string(),
+                t0,
+                Some(t0),
+                None,
+            ),
+            make_span_row_full(
+                "trace1",
+                "s2",
+                None,
+                &answer.to_string(),
+                t1,
+                Some(t1),
+                None,
+            ),
+        ]
+    };
+
+    let plain = process_spans(rows(), &FeedOptions::new());
+    assert!(plain.messages.iter().all(|b| b.content_hash.is_empty()));
+    let json = serde_json::to_value(&plain.messages[0]).unwrap();
+    assert!(json.get("content_hash").is_none());
+
+    let options = FeedOptions::new().with_include_hashes(true);
+    let first = process_spans(rows(), &options);
+    let second = process_spans(rows(), &options);
+    assert_eq!(first.messages.len(), 2);
+    assert!(first.messages.iter().all(|b| !b.content_hash.is_empty()));
+    assert_ne!(
+        first.messages[0].content_hash,
+        first.messages[1].content_hash
+    );
+    let hashes = |r: &FeedResult| {
+        r.messages
+            .iter()
+            .map(|b| b.content_hash.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(hashes(&first), hashes(&second));
+}
+
 // ----------------------------------------------------------------------------
 // Test: ADK multi-span trace in session + Phase 4b
 // ----------------------------------------------------------------------------
@@ -7633,6 +8617,384 @@ fn test_cross_trace_same_timestamp_trace_ordering() {
     );
 }
 
+#[test]
+fn test_dedup_by_environment_keeps_identical_content_distinct() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::seconds(10);
+    let msg1 = json!([
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t0.to_rfc3339()}},
+            "content": {"role": "user", "content": "Summarize the report"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": t0.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Here is the summary"}
+        }
+    ]);
+    // Same prompt replayed as the prefix of a later call
+    let msg2 = json!([
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "user", "content": "Summarize the report"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "assistant", "content": "Here is the summary"}
+        },
+        {
+            "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t1.to_rfc3339()}},
+            "content": {"role": "user", "content": "Shorter please"}
+        }
+    ]);
+    let rows = || {
+        let mut dev = make_span_row_full(
+            "trace-dev",
+            "s1",
+            None,
+            &msg1.to_string(),
+            t0,
+            Some(t0),
+            Some("generation"),
+        );
+        let mut prod = make_span_row_full(
+            "trace-prod",
+            "s2",
+            None,
+            &msg2.to_string(),
+            t1,
+            Some(t1),
+            Some("generation"),
+        );
+        dev.session_id = Some("session1".to_string());
+        prod.session_id = Some("session1".to_string());
+        dev.environment = Some("dev".to_string());
+        prod.environment = Some("prod".to_string());
+        vec![dev, prod]
+    };
+    let prod_texts = |result: &FeedResult| -> Vec<String> {
+        result
+            .messages
+            .iter()
+            .filter(|b| b.trace_id == "trace-prod")
+            .filter_map(|b| match &b.content {
+                ContentBlock::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // Default: the prod copy is treated as a replay of the dev conversation
+    let shared = process_spans(rows(), &FeedOptions::new());
+    assert_eq!(prod_texts(&shared), vec!["Shorter please"]);
+
+    let scoped = process_spans(rows(), &FeedOptions::new().with_dedup_by_environment(true));
+    assert_eq!(
+        prod_texts(&scoped),
+        vec![
+            "Summarize the report",
+            "Here is the summary",
+            "Shorter please"
+        ]
+    );
+
+    // Feed view: each environment is its own conversation
+    let feed = process_feed(rows(), &FeedOptions::new().with_dedup_by_environment(true));
+    assert_eq!(
+        feed.messages
+            .iter()
+            .filter(|b| matches!(&b.content, ContentBlock::Text { text } if text == "Summarize the report"))
+            .count(),
+        2
+    );
+}
+
+// ----------------------------------------------------------------------------
+// Test: an over-grouped session skips cross-trace stripping
+// ----------------------------------------------------------------------------
+
+#[test]
+fn test_oversized_session_processes_traces_independently() {
+    let t0 = fixed_time();
+    // Unrelated traces that happen to share a default session id and prompt
+    let rows = |count: usize| -> Vec<MessageSpanRow> {
+        (0..count)
+            .map(|i| {
+                let t = t0 + chrono::Duration::seconds(i as i64);
+                let msg = json!([
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t.to_rfc3339()}},
+                        "content": {"role": "user", "content": "Check the job status"}
+                    },
+                    {
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_response", "time": t.to_rfc3339()}},
+                        "content": {"role": "assistant", "content": format!("Job {i} is done")}
+                    }
+                ]);
+                let mut row = make_span_row_full(
+                    &format!("trace{i}"),
+                    &format!("span{i}"),
+                    None,
+                    &msg.to_string(),
+                    t,
+                    Some(t),
+                    Some("generation"),
+                );
+                row.session_id = Some("default".to_string());
+                row
+            })
+            .collect()
+    };
+    let prompt_count = |result: &FeedResult| {
+        result
+            .messages
+            .iter()
+            .filter(|b| get_text(&b.content) == Some("Check the job status"))
+            .count()
+    };
+
+    // Under the limit: later prompts are stripped as replays of the first
+    let grouped = process_feed(
+        rows(3),
+        &FeedOptions::new().with_max_session_traces(Some(100)),
+    );
+    assert_eq!(prompt_count(&grouped), 1);
+    assert!(grouped.metadata.warnings.is_empty());
+
+    let guarded = process_feed(
+        rows(1000),
+        &FeedOptions::new().with_max_session_traces(Some(100)),
+    );
+    assert_eq!(prompt_count(&guarded), 1000);
+    assert_eq!(guarded.metadata.block_count, 2000);
+    assert_eq!(guarded.metadata.warnings.len(), 1);
+    assert_eq!(
+        guarded.metadata.warnings[0].kind,
+        FeedWarningKind::SessionTooLarge
+    );
+}
+
+// ----------------------------------------------------------------------------
+// Test: cross-trace prefix stays bounded for huge sessions
+// ----------------------------------------------------------------------------
+
+/// Session where each trace replays the previous two turns before a new one.
+fn sliding_history_session(traces: usize) -> Vec<MessageSpanRow> {
+    let t0 = fixed_time();
+    (0..traces)
+        .map(|i| {
+            let t = t0 + chrono::Duration::seconds(i as i64 * 10);
+            let mut msg: Vec<JsonValue> = Vec::new();
+            for turn in i.saturating_sub(2)..=i {
+                msg.push(json!({
+                    "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t.to_rfc3339()}},
+                    "content": {"role": "user", "content": format!("Question {turn}")}
+                }));
+                let key = if turn == i {
+                    "gcp.vertex.agent.llm_response"
+                } else {
+                    "gcp.vertex.agent.llm_request"
+                };
+                msg.push(json!({
+                    "source": {"attribute": {"key": key, "time": t.to_rfc3339()}},
+                    "content": {"role": "assistant", "content": format!("Answer {turn}")}
+                }));
+            }
+            let mut row = make_span_row_full(
+                &format!("trace{i}"),
+                &format!("span{i}"),
+                None,
+                &JsonValue::Array(msg).to_string(),
+                t,
+                Some(t),
+                Some("generation"),
+            );
+            row.session_id = Some("session1".to_string());
+            row
+        })
+        .collect()
+}
+
+#[test]
+fn test_huge_session_prefix_is_bounded_and_strips_recent_replays() {
+    let unbounded = process_spans(sliding_history_session(500), &FeedOptions::new());
+    assert_eq!(unbounded.messages.len(), 1000, "one new turn per trace");
+    assert!(unbounded.metadata.warnings.is_empty());
+
+    let bounded = process_spans(
+        sliding_history_session(500),
+        &FeedOptions::new().with_max_prefix_entries(Some(8)),
+    );
+    // Replays only reach two turns back, well within the retained window
+    assert_eq!(bounded.messages.len(), 1000);
+    assert!(bounded.messages.iter().all(|b| !b.is_history));
+    assert_eq!(
+        get_text(&bounded.messages.last().unwrap().content),
+        Some("Answer 499")
+    );
+    assert_eq!(bounded.metadata.warnings.len(), 1, "warned once");
+    assert_eq!(
+        bounded.metadata.warnings[0].kind,
+        FeedWarningKind::PrefixTruncated
+    );
+}
+
+/// Session where each trace replays every prior turn before a new one.
+fn full_history_session(traces: usize) -> Vec<MessageSpanRow> {
+    let t0 = fixed_time();
+    (0..traces)
+        .map(|i| {
+            let t = t0 + chrono::Duration::seconds(i as i64 * 10);
+            let mut msg: Vec<JsonValue> = Vec::new();
+            for turn in 0..=i {
+                msg.push(json!({
+                    "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t.to_rfc3339()}},
+                    "content": {"role": "user", "content": format!("Question {turn}")}
+                }));
+                let key = if turn == i {
+                    "gcp.vertex.agent.llm_response"
+                } else {
+                    "gcp.vertex.agent.llm_request"
+                };
+                msg.push(json!({
+                    "source": {"attribute": {"key": key, "time": t.to_rfc3339()}},
+                    "content": {"role": "assistant", "content": format!("Answer {turn}")}
+                }));
+            }
+            let mut row = make_span_row_full(
+                &format!("trace{i}"),
+                &format!("span{i}"),
+                None,
+                &JsonValue::Array(msg).to_string(),
+                t,
+                Some(t),
+                Some("generation"),
+            );
+            row.session_id = Some("session1".to_string());
+            row
+        })
+        .collect()
+}
+
+#[test]
+fn test_bounded_prefix_strips_full_history_replays() {
+    let unbounded = process_spans(full_history_session(40), &FeedOptions::new());
+    assert_eq!(unbounded.messages.len(), 80, "one new turn per trace");
+
+    // Every replay opens with evicted entries; the retained tail still anchors it
+    let bounded = process_spans(
+        full_history_session(40),
+        &FeedOptions::new().with_max_prefix_entries(Some(8)),
+    );
+    assert_eq!(bounded.messages.len(), 80);
+    let texts: Vec<_> = bounded
+        .messages
+        .iter()
+        .map(|b| get_text(&b.content).unwrap_or_default().to_string())
+        .collect();
+    let expected: Vec<_> = (0..40)
+        .flat_map(|turn| [format!("Question {turn}"), format!("Answer {turn}")])
+        .collect();
+    assert_eq!(texts, expected);
+    assert_eq!(bounded.metadata.warnings.len(), 1, "warned once");
+}
+
+#[test]
+fn test_prefix_state_evicts_oldest_and_keeps_index_consistent() {
+    let blocks = process_spans(
+        sliding_history_session(50),
+        &FeedOptions::new().with_include_hashes(true),
+    )
+    .messages;
+    assert_eq!(blocks.len(), 100);
+
+    let mut state = CrossTracePrefixState::new(Some(8));
+    for block in &blocks {
+        state.push_block(block);
+        assert!(state.len() <= 8);
+    }
+    assert_eq!(state.len(), 8);
+    assert_eq!(state.start(), 92);
+    assert_eq!(state.end(), 100);
+    assert_eq!(state.dropped, 92);
+
+    // Index holds exactly the retained entries
+    let indexed: usize = state
+        .positions_by_role
+        .values()
+        .flat_map(|by_hash| by_hash.values())
+        .map(|positions| positions.len())
+        .sum();
+    assert_eq!(indexed, 8);
+
+    // Evicted entries no longer match; retained ones keep their positions
+    let oldest = &blocks[0];
+    assert_eq!(
+        state.next_position(oldest.role, &oldest.content_hash, 0),
+        None
+    );
+    let recent = &blocks[95];
+    assert_eq!(
+        state.next_position(recent.role, &recent.content_hash, state.start()),
+        Some(95)
+    );
+}
+
+// ----------------------------------------------------------------------------
+// Test: parallel trace preparation matches sequential processing
+// ----------------------------------------------------------------------------
+
+#[test]
+fn test_parallel_trace_processing_matches_sequential() {
+    let t0 = fixed_time();
+    // Each trace re-sends all prior turns as history before its new turn
+    let rows = || -> Vec<MessageSpanRow> {
+        (0..7)
+            .map(|i| {
+                let t = t0 + chrono::Duration::seconds(i as i64 * 10);
+                let mut msg: Vec<JsonValue> = Vec::new();
+                for turn in 0..=i {
+                    msg.push(json!({
+                        "source": {"attribute": {"key": "gcp.vertex.agent.llm_request", "time": t.to_rfc3339()}},
+                        "content": {"role": "user", "content": format!("Question {turn}")}
+                    }));
+                    let key = if turn == i {
+                        "gcp.vertex.agent.llm_response"
+                    } else {
+                        "gcp.vertex.agent.llm_request"
+                    };
+                    msg.push(json!({
+                        "source": {"attribute": {"key": key, "time": t.to_rfc3339()}},
+                        "content": {"role": "assistant", "content": format!("Answer {turn}")}
+                    }));
+                }
+                let mut row = make_span_row_full(
+                    &format!("trace{i}"),
+                    &format!("span{i}"),
+                    None,
+                    &JsonValue::Array(msg).to_string(),
+                    t,
+                    Some(t),
+                    Some("generation"),
+                );
+                row.session_id = Some("session1".to_string());
+                row
+            })
+            .collect()
+    };
+
+    let sequential = process_spans(rows(), &FeedOptions::new());
+    assert_eq!(sequential.messages.len(), 14, "one new turn per trace");
+
+    for threads in [2, 3, 16] {
+        let parallel = process_spans(rows(), &FeedOptions::new().with_trace_parallelism(threads));
+        assert_eq!(
+            format!("{parallel:?}"),
+            format!("{sequential:?}"),
+            "trace_parallelism = {threads}"
+        );
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Test: system blocks in prefix are transparent (do not break scan)
 // ----------------------------------------------------------------------------
@@ -8846,8 +10208,12 @@ fn test_no_promotion_when_choice_exists() {
         category: crate::data::types::MessageCategory::GenAIAssistantMessage,
         content_hash: "hash_prev".to_string(),
         is_semantic: true,
+        repeat_of: None,
+        gap_ms: None,
+        parallel_group: None,
         uses_span_end: false,
         is_history: false,
+        event_direction: None,
     };
 
     let choice_block = BlockEntry {
@@ -8881,12 +10247,20 @@ fn test_no_promotion_when_choice_exists() {
         category: crate::data::types::MessageCategory::GenAIChoice,
         content_hash: "hash_4".to_string(),
         is_semantic: true,
+        repeat_of: None,
+        gap_ms: None,
+        parallel_group: None,
         uses_span_end: false,
         is_history: false,
+        event_direction: None,
     };
 
     let mut blocks = vec![assistant_block.clone(), choice_block.clone()];
-    super::classify_blocks(&mut blocks, &span_timestamps);
+    super::classify_blocks(
+        &mut blocks,
+        &span_timestamps,
+        FeedOptions::default().clock_skew_tolerance,
+    );
 
     // gen_ai.choice should be classified normally (uses_span_end from is_protected)
     let choice = &blocks[1];
@@ -8905,3 +10279,127 @@ fn test_no_promotion_when_choice_exists() {
         "gen_ai.assistant.message should NOT use span_end when choice exists"
     );
 }
+
+// ============================================================================
+// PROMPT RECONSTRUCTION
+// ============================================================================
+
+/// The prompt view returns a generation span's full input, including history
+/// the feed strips, and leaves out the span's own output.
+#[test]
+fn test_process_prompt_keeps_history_and_drops_output() {
+    let t0 = fixed_time();
+    let t1 = t0 + chrono::Duration::milliseconds(100);
+    let t2 = t0 + chrono::Duration::milliseconds(200);
+    let t_end = t0 + chrono::Duration::seconds(1);
+
+    let gen_span_msg = json!([
+        {
+            "source": {"attribute": {"key": "llm.input_messages", "time": t1.to_rfc3339()}},
+            "content": {"role": "system", "content": "You are a weather bot"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": t0.to_rfc3339()}},
+            "content": {"role": "user", "content": "NYC weather"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.assistant.message", "time": t0.to_rfc3339()}},
+            "content": {
+                "role": "assistant",
+                "content": [{"type": "tool_use", "id": "old_call_1", "name": "search", "input": {"query": "NYC"}}]
+            }
+        },
+        {
+            "source": {"event": {"name": "gen_ai.tool.message", "time": t0.to_rfc3339()}},
+            "content": {"role": "tool", "tool_call_id": "old_call_1", "content": "NYC weather: sunny"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.user.message", "time": t1.to_rfc3339()}},
+            "content": {"role": "user", "content": "LA weather"}
+        },
+        {
+            "source": {"event": {"name": "gen_ai.choice", "time": t2.to_rfc3339()}},
+            "content": {
+                "role": "assistant",
+                "content": [{"type": "text", "text": "LA is sunny"}],
+                "finish_reason": "stop"
+            }
+        }
+    ]);
+
+    let rows = vec![make_span_row_full(
+        "trace1",
+        "gen_span",
+        Some("agent_span"),
+        &gen_span_msg.to_string(),
+        t1,
+        Some(t_end),
+        Some("generation"),
+    )];
+
+    // The feed treats the earlier turn as history
+    let feed = process_spans(rows.clone(), &FeedOptions::default());
+    assert!(
+        feed.messages
+            .iter()
+            .all(|b| get_text(&b.content) != Some("NYC weather")),
+        "feed should strip the earlier turn"
+    );
+
+    let prompt = process_prompt(rows, &FeedOptions::default());
+    let roles: Vec<ChatRole> = prompt.messages.iter().map(|b| b.role).collect();
+    assert_eq!(
+        roles,
+        vec![
+            ChatRole::System,
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::Tool,
+            ChatRole::User
+        ]
+    );
+    assert_eq!(get_text(&prompt.messages[1].content), Some("NYC weather"));
+    assert!(prompt.messages[2].is_tool_use());
+    assert!(prompt.messages[3].is_tool_result());
+    assert_eq!(get_text(&prompt.messages[4].content), Some("LA weather"));
+    assert_eq!(prompt.metadata.block_count, 5);
+}
+
+// Large tool output is trimmed for the feed but stays intact on the span,
+// which the span messages endpoint processes without a size limit.
+#[test]
+fn test_large_tool_result_truncated_in_feed_but_fully_fetchable() {
+    let page = format!("<html>{}</html>", "x".repeat(1024 * 1024));
+    let msg = json!([{
+        "source": {"event": {"name": "gen_ai.tool.message", "time": "2025-01-01T00:00:00Z"}},
+        "content": {"role": "tool", "tool_use_id": "call_1", "content": page}
+    }]);
+    let rows = || {
+        vec![make_span_row(
+            "trace1",
+            "span1",
+            None,
+            &msg.to_string(),
+            "[]",
+            "[]",
+        )]
+    };
+    let tool_text = |result: &FeedResult| match &result.messages[0].content {
+        ContentBlock::ToolResult { content, .. } => content.as_str().unwrap().to_string(),
+        other => panic!("expected tool result, got {other:?}"),
+    };
+
+    let feed = process_feed(
+        rows(),
+        &FeedOptions::new().with_max_tool_result_bytes(Some(64 * 1024)),
+    );
+    let truncated = tool_text(&feed);
+    assert!(truncated.len() < 64 * 1024 + 100);
+    assert!(truncated.starts_with("<html>xxx"));
+    assert!(truncated.ends_with("xxx</html>"));
+    let removed = page.len() - 64 * 1024;
+    assert!(truncated.contains(&format!("({removed} bytes truncated)")));
+
+    let detail = process_spans(rows(), &FeedOptions::new());
+    assert_eq!(tool_text(&detail), page);
+}
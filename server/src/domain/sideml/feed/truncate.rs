@@ -0,0 +1,70 @@
+//! Display-only truncation of large tool results.
+//!
+//! Tool outputs such as full HTML pages or large JSON documents can dominate
+//! the feed payload. Oversized results keep their head and tail around a
+//! `(N bytes truncated)` marker; the stored span is untouched, so the full
+//! content stays available from the span messages endpoint.
+
+use serde_json::Value as JsonValue;
+
+use super::types::BlockEntry;
+use crate::domain::sideml::types::ContentBlock;
+
+/// Truncate tool result content larger than `max_bytes`.
+///
+/// String content is cut directly; other JSON is serialized first, so the
+/// truncated result is always a string. Runs after dedup so hashing sees the
+/// full content.
+pub(super) fn truncate_tool_results(blocks: &mut [BlockEntry], max_bytes: usize) {
+    for block in blocks.iter_mut() {
+        let ContentBlock::ToolResult { content, .. } = &mut block.content else {
+            continue;
+        };
+        let truncated = match &*content {
+            JsonValue::String(s) => truncate_middle(s, max_bytes),
+            other => truncate_middle(&other.to_string(), max_bytes),
+        };
+        if let Some(truncated) = truncated {
+            *content = JsonValue::String(truncated);
+        }
+    }
+}
+
+/// Keep the first and last `max_bytes / 2` bytes of `text` (on char boundaries)
+/// with a marker between them. Returns None when `text` already fits.
+fn truncate_middle(text: &str, max_bytes: usize) -> Option<String> {
+    if text.len() <= max_bytes {
+        return None;
+    }
+    let half = max_bytes / 2;
+    let mut head_end = half;
+    while !text.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = text.len() - half;
+    while !text.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+    let removed = tail_start - head_end;
+    Some(format!(
+        "{}\n... ({removed} bytes truncated) ...\n{}",
+        &text[..head_end],
+        &text[tail_start..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_middle_respects_char_boundaries() {
+        // 20 bytes; a 10-byte budget splits mid-character on both sides
+        let text = "é".repeat(10);
+        let out = truncate_middle(&text, 10).unwrap();
+        assert!(out.starts_with("éé\n"));
+        assert!(out.ends_with("\néé"));
+        assert!(out.contains("(12 bytes truncated)"));
+        assert_eq!(truncate_middle("short", 5), None);
+    }
+}
@@ -0,0 +1,47 @@
+//! Display-only grouping of consecutive blocks into conversational turns.
+//!
+//! The feed is a flat block list; a turn gathers consecutive blocks of the
+//! same role from the same span batch (same span and event timestamp), so a
+//! UI can render one bubble with several content parts. Tool calls and tool
+//! results always form turns of their own.
+//!
+//! Runs on the final, sorted block list and does not change it.
+
+use super::types::{BlockEntry, Turn};
+use crate::domain::sideml::types::ContentBlock;
+
+/// Group consecutive blocks into turns, in block order.
+pub(super) fn group_turns(blocks: &[BlockEntry]) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+
+    for (index, block) in blocks.iter().enumerate() {
+        if let Some(turn) = turns.last_mut()
+            && !is_tool_block(block)
+            && !is_tool_block(&blocks[turn.start])
+            && same_turn(&blocks[turn.start], block)
+        {
+            turn.block_count += 1;
+            continue;
+        }
+        turns.push(Turn {
+            role: block.role,
+            trace_id: block.trace_id.clone(),
+            span_id: block.span_id.clone(),
+            start: index,
+            block_count: 1,
+        });
+    }
+    turns
+}
+
+/// Blocks share a turn when they have one role and come from one span batch.
+fn same_turn(first: &BlockEntry, block: &BlockEntry) -> bool {
+    first.role == block.role && first.span_id == block.span_id && first.timestamp == block.timestamp
+}
+
+fn is_tool_block(block: &BlockEntry) -> bool {
+    matches!(
+        block.content,
+        ContentBlock::ToolUse { .. } | ContentBlock::ToolResult { .. }
+    )
+}
@@ -2,23 +2,139 @@
 //!
 //! Core types for the SideML feed processing pipeline.
 
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::Serialize;
 use serde_json::Value as JsonValue;
+use utoipa::ToSchema;
 
+use super::super::normalize::{DefaultRoles, is_input_attribute, is_output_attribute};
 use super::super::types::{ChatRole, ContentBlock, FinishReason};
 use super::{GENAI_INPUT_EVENTS, GENAI_OUTPUT_EVENTS, obs_type, source_type};
-use crate::data::types::MessageCategory;
+use crate::core::constants::DEFAULT_FEED_CLOCK_SKEW_TOLERANCE_MS;
+use crate::data::types::{IngestWarningKind, MessageCategory, MessageSpanRow};
 
 // ============================================================================
 // FEED OPTIONS
 // ============================================================================
 
-/// Options for feed processing.
+/// Whether an event carries input to a span or output from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDirection {
+    Input,
+    Output,
+}
+
+/// Framework-specific event names registered as span input or output, on top
+/// of the built-in GenAI semantic-convention events.
 #[derive(Debug, Clone, Default)]
+pub struct EventNames {
+    pub output: Vec<String>,
+    pub input: Vec<String>,
+}
+
+impl EventNames {
+    /// Classify an event name against the built-in lists, then the configured ones.
+    pub fn direction(&self, name: &str) -> Option<EventDirection> {
+        if GENAI_OUTPUT_EVENTS.contains(&name) || self.output.iter().any(|n| n == name) {
+            Some(EventDirection::Output)
+        } else if GENAI_INPUT_EVENTS.contains(&name) || self.input.iter().any(|n| n == name) {
+            Some(EventDirection::Input)
+        } else {
+            None
+        }
+    }
+}
+
+/// Options for feed processing.
+#[derive(Debug, Clone)]
 pub struct FeedOptions {
     /// Filter by specific role (e.g., "user", "assistant", "system", "tool").
     pub role: Option<String>,
+    /// Replace text and tool payloads with deterministic placeholder content
+    /// (display-only; roles, tool names, tokens and costs are preserved).
+    pub anonymize: bool,
+    /// Drop System-role blocks from the output. Applied after history
+    /// detection and dedup, so it does not change which blocks are history.
+    pub exclude_system: bool,
+    /// Messages are history only if they predate span start by more than this.
+    pub clock_skew_tolerance: TimeDelta,
+    /// Keep single-JSON input blocks from tool spans (tool arguments), tagged
+    /// with `GenAIToolInput`. Dropped by default as non-conversational.
+    pub keep_tool_input_json: bool,
+    /// Roles for attribute messages that carry no role of their own.
+    pub default_roles: DefaultRoles,
+    /// Show a repeated system prompt in full once, then as a short marker
+    /// referencing the first occurrence (display-only).
+    pub collapse_repeated_system: bool,
+    /// Merge tool definitions whose names differ only by case or `_`/`-`
+    /// separators. Off by default: some frameworks treat those as distinct tools.
+    pub fold_tool_name_case: bool,
+    /// Persisted `span_id -> parent_span_id` links for spans outside the rows
+    /// being processed, so span paths reach ancestors from other batches.
+    pub stored_parents: HashMap<String, String>,
+    /// Annotate each block with the elapsed time since the previous block
+    /// (display-only, computed after sorting).
+    pub include_gaps: bool,
+    /// Scope cross-trace and cross-conversation matching to the span's
+    /// environment, so identical content in dev and prod is never collapsed.
+    pub dedup_by_environment: bool,
+    /// Extra event names classified as span input or output.
+    pub event_names: EventNames,
+    /// Truncate tool results larger than this many bytes, keeping head and
+    /// tail (display-only; the full result stays in the stored span).
+    pub max_tool_result_bytes: Option<usize>,
+    /// Sessions with more traces than this are processed trace by trace,
+    /// skipping cross-trace history stripping (guards against a shared
+    /// default session id grouping unrelated traces).
+    pub max_session_traces: Option<usize>,
+    /// Show span tokens/cost only on the block that carries them (each span's
+    /// output block); other blocks omit the metrics instead of reporting zero.
+    pub inline_turn_metrics: bool,
+    /// Threads used to parse and flatten traces of a multi-trace session
+    /// (1 = sequential). Cross-trace marking always runs in trace order.
+    pub trace_parallelism: usize,
+    /// Keep at most this many blocks, dropping the oldest (a safety valve for
+    /// huge traces, independent of request pagination).
+    pub max_blocks: Option<usize>,
+    /// Keep each block's `content_hash` in the output so clients can diff and
+    /// cache feeds. Off by default to keep payloads small.
+    pub include_hashes: bool,
+    /// Keep at most this many entries in a session's cross-trace prefix,
+    /// dropping the oldest (bounds memory for huge sessions; replays of the
+    /// dropped turns are no longer stripped).
+    pub max_prefix_entries: Option<usize>,
+    /// Group consecutive same-role blocks of each span batch into
+    /// `FeedResult::turns` (display-only; the block list is unchanged).
+    pub group_turns: bool,
+}
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        Self {
+            role: None,
+            anonymize: false,
+            exclude_system: false,
+            clock_skew_tolerance: TimeDelta::milliseconds(DEFAULT_FEED_CLOCK_SKEW_TOLERANCE_MS),
+            keep_tool_input_json: false,
+            default_roles: DefaultRoles::default(),
+            collapse_repeated_system: false,
+            fold_tool_name_case: false,
+            stored_parents: HashMap::new(),
+            include_gaps: false,
+            dedup_by_environment: false,
+            event_names: EventNames::default(),
+            max_tool_result_bytes: None,
+            max_session_traces: None,
+            inline_turn_metrics: false,
+            trace_parallelism: 1,
+            max_blocks: None,
+            include_hashes: false,
+            max_prefix_entries: None,
+            group_turns: false,
+        }
+    }
 }
 
 impl FeedOptions {
@@ -34,6 +150,139 @@ impl FeedOptions {
         self.role = role;
         self
     }
+
+    /// Enable display-only content anonymization (for screenshots/demos).
+    #[must_use]
+    pub fn with_anonymize(mut self, anonymize: bool) -> Self {
+        self.anonymize = anonymize;
+        self
+    }
+
+    /// Hide System-role blocks from the output (history detection still sees them).
+    #[must_use]
+    pub fn with_exclude_system(mut self, exclude_system: bool) -> Self {
+        self.exclude_system = exclude_system;
+        self
+    }
+
+    /// Set the clock-skew tolerance for timestamp-based history detection.
+    #[must_use]
+    pub fn with_clock_skew_tolerance(mut self, tolerance: TimeDelta) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Surface tool argument JSON blocks from tool spans (for debugging).
+    #[must_use]
+    pub fn with_keep_tool_input_json(mut self, keep: bool) -> Self {
+        self.keep_tool_input_json = keep;
+        self
+    }
+
+    /// Set the roles assigned to role-less input and output attribute messages.
+    #[must_use]
+    pub fn with_default_roles(mut self, default_roles: DefaultRoles) -> Self {
+        self.default_roles = default_roles;
+        self
+    }
+
+    /// Collapse repeated system prompts to a marker after their first occurrence.
+    #[must_use]
+    pub fn with_collapse_repeated_system(mut self, collapse: bool) -> Self {
+        self.collapse_repeated_system = collapse;
+        self
+    }
+
+    /// Merge tool definitions whose names differ only by case or separators.
+    #[must_use]
+    pub fn with_fold_tool_name_case(mut self, fold: bool) -> Self {
+        self.fold_tool_name_case = fold;
+        self
+    }
+
+    /// Resolve span paths through stored parent links when a parent is not in the rows.
+    #[must_use]
+    pub fn with_stored_parents(mut self, stored_parents: HashMap<String, String>) -> Self {
+        self.stored_parents = stored_parents;
+        self
+    }
+
+    /// Annotate blocks with the elapsed time since the previous block.
+    #[must_use]
+    pub fn with_include_gaps(mut self, include_gaps: bool) -> Self {
+        self.include_gaps = include_gaps;
+        self
+    }
+
+    /// Group consecutive same-role blocks into turns for bubble rendering.
+    #[must_use]
+    pub fn with_group_turns(mut self, group_turns: bool) -> Self {
+        self.group_turns = group_turns;
+        self
+    }
+
+    /// Keep identical content from different environments distinct.
+    #[must_use]
+    pub fn with_dedup_by_environment(mut self, dedup_by_environment: bool) -> Self {
+        self.dedup_by_environment = dedup_by_environment;
+        self
+    }
+
+    /// Register framework-specific input and output event names.
+    #[must_use]
+    pub fn with_event_names(mut self, event_names: EventNames) -> Self {
+        self.event_names = event_names;
+        self
+    }
+
+    /// Truncate oversized tool results to at most `max_bytes` plus a marker.
+    #[must_use]
+    pub fn with_max_tool_result_bytes(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_tool_result_bytes = max_bytes;
+        self
+    }
+
+    /// Skip cross-trace stripping for sessions with more than `max_traces` traces.
+    #[must_use]
+    pub fn with_max_session_traces(mut self, max_traces: Option<usize>) -> Self {
+        self.max_session_traces = max_traces;
+        self
+    }
+
+    /// Surface span tokens/cost only on each turn's representative output block.
+    #[must_use]
+    pub fn with_inline_turn_metrics(mut self, inline: bool) -> Self {
+        self.inline_turn_metrics = inline;
+        self
+    }
+
+    /// Parse and flatten the traces of a multi-trace session on up to `threads` threads.
+    #[must_use]
+    pub fn with_trace_parallelism(mut self, threads: usize) -> Self {
+        self.trace_parallelism = threads.max(1);
+        self
+    }
+
+    /// Cap the result at `max_blocks` blocks, keeping the most recent ones.
+    #[must_use]
+    pub fn with_max_blocks(mut self, max_blocks: Option<usize>) -> Self {
+        self.max_blocks = max_blocks;
+        self
+    }
+
+    /// Keep block content hashes in the output.
+    #[must_use]
+    pub fn with_include_hashes(mut self, include_hashes: bool) -> Self {
+        self.include_hashes = include_hashes;
+        self
+    }
+
+    /// Bound the cross-trace prefix of multi-trace sessions to `max_entries`.
+    #[must_use]
+    pub fn with_max_prefix_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.max_prefix_entries = max_entries;
+        self
+    }
 }
 
 // ============================================================================
@@ -105,7 +354,7 @@ pub struct BlockEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_name: Option<String>,
 
-    // Metrics
+    // Metrics (span totals on one representative block per span, zero on the rest)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tokens: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,9 +378,23 @@ pub struct BlockEntry {
     /// Message category for semantic filtering
     pub category: MessageCategory,
 
-    // For future deduplication (hash as string for JSON safety)
+    // For deduplication (hash as string for JSON safety); emptied after
+    // processing unless hashes are requested
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub content_hash: String,
     pub is_semantic: bool,
+    /// Set on a collapsed repeat of an earlier system prompt: the span_id of
+    /// the block that still carries the full content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_of: Option<String>,
+    /// Milliseconds since the previous block (display-only, set when gaps are
+    /// requested; `None` for the first block).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_ms: Option<i64>,
+    /// Shared by tool calls emitted together in one assistant message, so they
+    /// can be shown as a concurrent batch (`None` for single tool calls).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_group: Option<String>,
 
     // Classification flags (computed during pipeline, not serialized)
     /// True if this block should use span_end for effective timestamp.
@@ -151,6 +414,11 @@ pub struct BlockEntry {
     /// the full eight-phase detection algorithm (phases 2-7 plus 4b).
     #[serde(skip_serializing)]
     pub is_history: bool,
+
+    /// Input/output classification of `event_name` from the built-in and
+    /// configured event lists (set during flatten).
+    #[serde(skip_serializing)]
+    pub event_direction: Option<EventDirection>,
 }
 
 impl BlockEntry {
@@ -246,24 +514,30 @@ impl BlockEntry {
     // EVENT CLASSIFICATION HELPERS
     // ========================================================================
 
-    /// Check if this block's event is a GenAI output event (gen_ai.choice, etc.).
+    /// Check if this block's event is a GenAI output event (gen_ai.choice, or a
+    /// configured output event).
     ///
     /// Output events represent LLM completions.
     #[inline]
     pub fn is_output_event(&self) -> bool {
-        self.event_name
-            .as_ref()
-            .is_some_and(|name| GENAI_OUTPUT_EVENTS.contains(&name.as_str()))
+        self.event_direction == Some(EventDirection::Output)
+            || self
+                .event_name
+                .as_ref()
+                .is_some_and(|name| GENAI_OUTPUT_EVENTS.contains(&name.as_str()))
     }
 
-    /// Check if this block's event is a GenAI input event (user.message, etc.).
+    /// Check if this block's event is a GenAI input event (user.message, etc.,
+    /// or a configured input event).
     ///
     /// Input events represent context/history passed to the LLM.
     #[inline]
     pub fn is_input_event(&self) -> bool {
-        self.event_name
-            .as_ref()
-            .is_some_and(|name| GENAI_INPUT_EVENTS.contains(&name.as_str()))
+        self.event_direction == Some(EventDirection::Input)
+            || self
+                .event_name
+                .as_ref()
+                .is_some_and(|name| GENAI_INPUT_EVENTS.contains(&name.as_str()))
     }
 
     // ========================================================================
@@ -286,31 +560,10 @@ impl BlockEntry {
     /// - Input events (gen_ai.user.message, etc.)
     #[inline]
     pub fn is_input_source(&self) -> bool {
-        self.source_attribute.as_ref().is_some_and(|attr| {
-            // Standard OTel / OpenInference
-            attr.starts_with("llm.input_messages")
-                || attr.starts_with("gen_ai.input.")
-                || attr.starts_with("gen_ai.prompt.")
-                || attr == "input.value"
-                // ADK / Vertex
-                || attr == "gcp.vertex.agent.llm_request"
-                || attr == "gcp.vertex.agent.data"
-                // Vercel AI SDK
-                || attr == "ai.prompt"
-                // LiveKit
-                || attr == "lk.input_text"
-                || attr == "lk.user_input"
-                || attr == "lk.instructions"
-                || attr == "lk.chat_ctx"
-                // MLflow
-                || attr == "mlflow.spanInputs"
-                // TraceLoop
-                || attr == "traceloop.entity.input"
-                // Pydantic AI
-                || attr == "pydantic_ai.all_messages"
-                // Logfire (instrument_openai, instrument_anthropic)
-                || attr == "request_data"
-        }) || self.is_input_event()
+        self.source_attribute
+            .as_deref()
+            .is_some_and(is_input_attribute)
+            || self.is_input_event()
     }
 
     /// Check if this block came from OUTPUT attributes (results FROM the span).
@@ -328,25 +581,10 @@ impl BlockEntry {
     /// - Output events (gen_ai.choice, etc.)
     #[inline]
     pub fn is_output_source(&self) -> bool {
-        self.source_attribute.as_ref().is_some_and(|attr| {
-            // Standard OTel / OpenInference
-            attr.starts_with("llm.output_messages")
-                || attr.starts_with("gen_ai.output.")
-                || attr.starts_with("gen_ai.completion.")
-                || attr == "output.value"
-                // ADK / Vertex
-                || attr == "gcp.vertex.agent.llm_response"
-                // Vercel AI SDK
-                || attr.starts_with("ai.result.")
-                // LiveKit
-                || attr.starts_with("lk.response.")
-                // MLflow
-                || attr == "mlflow.spanOutputs"
-                // TraceLoop
-                || attr == "traceloop.entity.output"
-                // Logfire (instrument_openai, instrument_anthropic)
-                || attr == "response_data"
-        }) || self.is_output_event()
+        self.source_attribute
+            .as_deref()
+            .is_some_and(is_output_attribute)
+            || self.is_output_event()
     }
 
     /// Check if this block has the GenAIChoice category.
@@ -382,11 +620,37 @@ impl BlockEntry {
 #[derive(Debug)]
 pub struct FeedResult {
     pub messages: Vec<BlockEntry>,
+    /// Turns over `messages` (empty unless `FeedOptions::group_turns` is set)
+    pub turns: Vec<Turn>,
     pub tool_definitions: Vec<JsonValue>,
     pub tool_names: Vec<String>,
     pub metadata: FeedMetadata,
 }
 
+impl FeedResult {
+    /// Recompute turns after blocks were filtered out (no-op when turns are off).
+    pub fn regroup_turns(&mut self) {
+        if !self.turns.is_empty() {
+            self.turns = super::turns::group_turns(&self.messages);
+        }
+    }
+}
+
+/// Consecutive blocks of one role from the same span batch.
+///
+/// Refers to a run of the result's block list, so clients render the blocks
+/// `start..start + block_count` as one bubble.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Turn {
+    pub role: ChatRole,
+    pub trace_id: String,
+    pub span_id: String,
+    /// Index of the turn's first block
+    pub start: usize,
+    /// Number of consecutive blocks in the turn
+    pub block_count: usize,
+}
+
 /// Tool definitions and names extracted from span rows.
 ///
 /// Separated from the feed pipeline so handlers can scope tool extraction
@@ -404,6 +668,122 @@ pub struct FeedMetadata {
     pub span_count: usize,
     pub total_tokens: i64,
     pub total_cost: f64,
+    pub content_sizes: ContentSizeStats,
+    /// Data-quality issues found while processing, surfaced instead of only logged
+    pub warnings: Vec<FeedWarning>,
+    /// Older blocks were dropped to stay within `FeedOptions::max_blocks`
+    pub truncated: bool,
+}
+
+/// Kind of data-quality issue found while processing a feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedWarningKind {
+    /// A span's stored messages JSON could not be parsed
+    ParseError,
+    /// A span's parent chain loops back on itself
+    HierarchyCycle,
+    /// A span's parent chain exceeds the depth limit
+    HierarchyTooDeep,
+    /// A session groups more traces than the limit; traces were processed
+    /// independently without cross-trace history stripping
+    SessionTooLarge,
+    /// A session's cross-trace prefix hit its entry limit; the oldest entries
+    /// were dropped, so replays of early turns may remain visible
+    PrefixTruncated,
+    /// A span started in the future and was clamped to its ingestion time
+    FutureTimestamp,
+}
+
+impl From<IngestWarningKind> for FeedWarningKind {
+    fn from(kind: IngestWarningKind) -> Self {
+        match kind {
+            IngestWarningKind::FutureTimestamp => Self::FutureTimestamp,
+            IngestWarningKind::ParseError => Self::ParseError,
+        }
+    }
+}
+
+/// A data-quality issue attributed to a single span.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FeedWarning {
+    #[serde(rename = "type")]
+    pub kind: FeedWarningKind,
+    pub trace_id: String,
+    pub span_id: String,
+    pub message: String,
+}
+
+impl FeedWarning {
+    pub(super) fn new(kind: FeedWarningKind, row: &MessageSpanRow, message: String) -> Self {
+        Self {
+            kind,
+            trace_id: row.trace_id.clone(),
+            span_id: row.span_id.clone(),
+            message,
+        }
+    }
+}
+
+/// Byte sizes of block content, for spotting traces bloated by large payloads.
+///
+/// Text is measured in UTF-8 bytes, media by the stored `data` string (base64
+/// or URL) and JSON payloads by their serialized length.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ContentSizeStats {
+    pub total_bytes: usize,
+    pub largest_block_bytes: usize,
+    /// Text, thinking and refusal blocks
+    pub text_bytes: usize,
+    /// Images, audio, video, documents and files
+    pub media_bytes: usize,
+    /// Tool calls, tool results and tool definitions
+    pub tool_bytes: usize,
+    /// Context, JSON, redacted thinking and unknown blocks
+    pub other_bytes: usize,
+}
+
+impl ContentSizeStats {
+    /// Compute size stats over a set of blocks.
+    pub fn from_blocks(blocks: &[BlockEntry]) -> Self {
+        let mut stats = Self::default();
+        for block in blocks {
+            stats.add(&block.content);
+        }
+        stats
+    }
+
+    fn add(&mut self, content: &ContentBlock) {
+        let (bucket, bytes) = match content {
+            ContentBlock::Text { text } | ContentBlock::Thinking { text, .. } => {
+                (&mut self.text_bytes, text.len())
+            }
+            ContentBlock::Refusal { message } => (&mut self.text_bytes, message.len()),
+            ContentBlock::Image { data, .. }
+            | ContentBlock::Audio { data, .. }
+            | ContentBlock::Video { data, .. }
+            | ContentBlock::Document { data, .. }
+            | ContentBlock::File { data, .. } => (&mut self.media_bytes, data.len()),
+            ContentBlock::ToolUse { name, input, .. } => {
+                (&mut self.tool_bytes, name.len() + input.to_string().len())
+            }
+            ContentBlock::ToolResult { content, .. } => {
+                (&mut self.tool_bytes, content.to_string().len())
+            }
+            ContentBlock::ToolDefinitions { tools, .. } => (
+                &mut self.tool_bytes,
+                tools.iter().map(|t| t.to_string().len()).sum(),
+            ),
+            ContentBlock::Context { data, .. } | ContentBlock::Json { data } => {
+                (&mut self.other_bytes, data.to_string().len())
+            }
+            ContentBlock::RedactedThinking { data } => (&mut self.other_bytes, data.len()),
+            ContentBlock::Unknown { raw } => (&mut self.other_bytes, raw.to_string().len()),
+        };
+        *bucket += bytes;
+        self.total_bytes += bytes;
+        self.largest_block_bytes = self.largest_block_bytes.max(bytes);
+    }
 }
 
 // ============================================================================
@@ -448,8 +828,12 @@ mod tests {
             category: MessageCategory::GenAIUserMessage,
             content_hash: "hash".to_string(),
             is_semantic: true,
+            repeat_of: None,
+            gap_ms: None,
+            parallel_group: None,
             uses_span_end: false,
             is_history: false,
+            event_direction: None,
         }
     }
 
@@ -666,4 +1050,48 @@ mod tests {
         };
         assert!(block.is_json_block());
     }
+
+    #[test]
+    fn test_content_size_stats_by_type() {
+        let with_content = |content: ContentBlock| BlockEntry {
+            content,
+            ..make_test_block()
+        };
+        let blocks = vec![
+            with_content(ContentBlock::Text {
+                text: "héllo".to_string(), // 6 bytes
+            }),
+            with_content(ContentBlock::Image {
+                media_type: Some("image/png".to_string()),
+                source: "base64".to_string(),
+                data: "A".repeat(1000),
+                detail: None,
+            }),
+            with_content(ContentBlock::ToolUse {
+                id: Some("call_1".to_string()),
+                name: "search".to_string(),              // 6 bytes
+                input: serde_json::json!({"q": "rust"}), // {"q":"rust"} = 12 bytes
+            }),
+            with_content(ContentBlock::ToolResult {
+                tool_use_id: Some("call_1".to_string()),
+                content: serde_json::json!("ok"), // "ok" = 4 bytes
+                is_error: false,
+            }),
+            with_content(ContentBlock::Json {
+                data: serde_json::json!([1, 2]), // [1,2] = 5 bytes
+            }),
+        ];
+
+        let stats = ContentSizeStats::from_blocks(&blocks);
+        assert_eq!(stats.text_bytes, 6);
+        assert_eq!(stats.media_bytes, 1000);
+        assert_eq!(stats.tool_bytes, 22);
+        assert_eq!(stats.other_bytes, 5);
+        assert_eq!(stats.total_bytes, 1033);
+        assert_eq!(stats.largest_block_bytes, 1000);
+        assert_eq!(
+            ContentSizeStats::from_blocks(&[]),
+            ContentSizeStats::default()
+        );
+    }
 }
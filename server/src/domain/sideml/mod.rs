@@ -43,6 +43,7 @@
 // INTERNAL MODULES
 // ============================================================================
 
+mod citations;
 pub(crate) mod content;
 pub(crate) mod tools;
 mod types;
@@ -76,13 +77,17 @@ pub use types::{
 };
 
 pub use feed::{
-    BlockEntry, ExtractedTools, FeedMetadata, FeedOptions, FeedResult, deduplicate_names,
-    deduplicate_tools, extract_tools_from_rows, process_feed, process_spans,
+    BlockEntry, ContentSizeStats, EventNames, ExtractedTools, FeedMetadata, FeedOptions,
+    FeedResult, FeedWarning, FeedWarningKind, Turn, deduplicate_names, deduplicate_tools,
+    extract_tools_from_rows, process_feed, process_prompt, process_spans,
+    retain_errors_with_context,
 };
 
 pub use tools::extract_tool_name;
 
-pub use normalize::{SideMLMessage, to_sideml, to_sideml_batch, to_sideml_with_context};
+pub use normalize::{
+    DefaultRoles, SideMLMessage, to_sideml, to_sideml_batch, to_sideml_with_context,
+};
 
 // ============================================================================
 // PUBLIC API - Normalization Functions
@@ -229,6 +234,33 @@ pub fn normalize(raw: &JsonValue) -> ChatMessage {
         )
         .collect();
 
+    // Message-level error flag on tool results (e.g. LiveKit lk.function_tool.is_error)
+    if ChatRole::is_tool_role(role_str)
+        && raw.get("is_error").and_then(|e| e.as_bool()) == Some(true)
+    {
+        for block in &mut content_vec {
+            if let ContentBlock::ToolResult { is_error, .. } = block {
+                *is_error = true;
+            }
+        }
+    }
+
+    // Message-level reasoning (DeepSeek R1 and OpenAI-compatible reasoning models)
+    // precedes the answer, so it goes first
+    if let Some(reasoning) = raw
+        .get("reasoning_content")
+        .and_then(|r| r.as_str())
+        .filter(|r| !r.trim().is_empty())
+    {
+        content_vec.insert(
+            0,
+            ContentBlock::Thinking {
+                text: reasoning.to_string(),
+                signature: None,
+            },
+        );
+    }
+
     // Handle message-level refusal field (OpenAI)
     if let Some(refusal) = raw.get("refusal").and_then(|r| r.as_str())
         && !refusal.is_empty()
@@ -265,7 +297,15 @@ pub fn normalize(raw: &JsonValue) -> ChatMessage {
     }
 
     // Extract citation/grounding metadata
-    content_vec.extend(extract_citation_contexts(&raw));
+    let contexts = extract_citation_contexts(&raw);
+    // Normalized sources behind a grounded answer
+    let sources = if role == ChatRole::Assistant {
+        citations::extract_citations(&raw, &contexts)
+    } else {
+        Vec::new()
+    };
+    content_vec.extend(contexts);
+    content_vec.extend(sources);
 
     // API error extraction
     if let Some(error) = raw
@@ -34,6 +34,107 @@ pub struct SideMLMessage {
     pub timestamp: DateTime<Utc>,
     /// The normalized SideML message
     pub sideml: ChatMessage,
+    /// Shared by the tool calls split out of one message that requested
+    /// several at once (parallel tool calls); `None` otherwise
+    pub parallel_group: Option<usize>,
+}
+
+// ============================================================================
+// DEFAULT ROLES
+// ============================================================================
+
+/// Roles for attribute messages that carry no role of their own.
+///
+/// Some frameworks write bare prompts and completions into attributes, leaving
+/// the role to the reader. The attribute's position decides the default:
+/// output attributes get `output`, input attributes get `input`, and
+/// `by_attribute` overrides either for specific keys. Attributes in neither
+/// position keep the normalizer's fallback (user).
+///
+/// Roles derived from structure always win: content with tool calls stays
+/// assistant and tool results stay tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefaultRoles {
+    /// Role for role-less content in input attributes
+    pub input: ChatRole,
+    /// Role for role-less content in output attributes
+    pub output: ChatRole,
+    /// Per-attribute overrides, keyed by exact attribute key
+    pub by_attribute: HashMap<String, ChatRole>,
+}
+
+impl Default for DefaultRoles {
+    fn default() -> Self {
+        Self {
+            input: ChatRole::User,
+            output: ChatRole::Assistant,
+            by_attribute: HashMap::new(),
+        }
+    }
+}
+
+impl DefaultRoles {
+    /// Default role for role-less content from the attribute `key`.
+    pub fn for_attribute(&self, key: &str) -> Option<ChatRole> {
+        if let Some(role) = self.by_attribute.get(key) {
+            return Some(*role);
+        }
+        if is_output_attribute(key) {
+            Some(self.output)
+        } else if is_input_attribute(key) {
+            Some(self.input)
+        } else {
+            None
+        }
+    }
+}
+
+/// Check if an attribute key carries span INPUT (see `BlockEntry::is_input_source`).
+pub(crate) fn is_input_attribute(attr: &str) -> bool {
+    // Standard OTel / OpenInference
+    attr.starts_with("llm.input_messages")
+        || attr.starts_with("gen_ai.input.")
+        || attr.starts_with("gen_ai.prompt.")
+        || attr == "input.value"
+        // ADK / Vertex
+        || attr == "gcp.vertex.agent.llm_request"
+        || attr == "gcp.vertex.agent.data"
+        // Vercel AI SDK
+        || attr == "ai.prompt"
+        // LiveKit
+        || attr == "lk.input_text"
+        || attr == "lk.user_input"
+        || attr == "lk.instructions"
+        || attr == "lk.chat_ctx"
+        // MLflow
+        || attr == "mlflow.spanInputs"
+        // TraceLoop
+        || attr == "traceloop.entity.input"
+        // Pydantic AI
+        || attr == "pydantic_ai.all_messages"
+        // Logfire (instrument_openai, instrument_anthropic)
+        || attr == "request_data"
+}
+
+/// Check if an attribute key carries span OUTPUT (see `BlockEntry::is_output_source`).
+pub(crate) fn is_output_attribute(attr: &str) -> bool {
+    // Standard OTel / OpenInference
+    attr.starts_with("llm.output_messages")
+        || attr.starts_with("gen_ai.output.")
+        || attr.starts_with("gen_ai.completion.")
+        || attr == "output.value"
+        // ADK / Vertex
+        || attr == "gcp.vertex.agent.llm_response"
+        // Vercel AI SDK
+        || attr.starts_with("ai.result.")
+        // LiveKit
+        || attr.starts_with("lk.response.")
+        // MLflow
+        || attr == "mlflow.spanOutputs"
+        // TraceLoop
+        || attr == "traceloop.entity.output"
+        // Logfire (instrument_openai, instrument_anthropic)
+        || attr == "response_data"
 }
 
 // ============================================================================
@@ -65,6 +166,18 @@ pub fn to_sideml(raw_messages: &[RawMessage]) -> Vec<SideMLMessage> {
 pub fn to_sideml_with_context(
     raw_messages: &[RawMessage],
     is_tool_span: bool,
+) -> Vec<SideMLMessage> {
+    to_sideml_with_roles(raw_messages, is_tool_span, &DefaultRoles::default())
+}
+
+/// Convert raw messages to SideML format with span context and default roles.
+///
+/// Like [`to_sideml_with_context`], but role-less attribute messages get their
+/// role from `default_roles` instead of the built-in defaults.
+pub fn to_sideml_with_roles(
+    raw_messages: &[RawMessage],
+    is_tool_span: bool,
+    default_roles: &DefaultRoles,
 ) -> Vec<SideMLMessage> {
     // Pre-process: split bundled tool results into separate messages
     let expanded = expand_bundled_tool_results(raw_messages);
@@ -78,7 +191,11 @@ pub fn to_sideml_with_context(
         let content_with_role = derive_role_from_source_with_context(raw, is_tool_span);
 
         // Normalize to SideML format
-        let sideml = normalize(&content_with_role);
+        let mut sideml = normalize(&content_with_role);
+        let default_role = default_role_for(raw, &sideml, default_roles);
+        if let Some(role) = default_role {
+            sideml.role = role;
+        }
 
         // Collect tool_use_id -> name mappings from tool_use content blocks
         for block in &sideml.content {
@@ -92,7 +209,10 @@ pub fn to_sideml_with_context(
 
         // Determine category based on source and derived role
         // IMPORTANT: Use content_with_role (not raw.content) to see the derived role
-        let category = determine_category(&raw.source, &content_with_role);
+        let category = match default_role {
+            Some(role) => category_from_role_with_content(role.as_str(), &raw.content),
+            None => determine_category(&raw.source, &content_with_role),
+        };
 
         // Determine source type and time
         let (source_type, timestamp) = match &raw.source {
@@ -106,6 +226,7 @@ pub fn to_sideml_with_context(
             source_type,
             timestamp,
             sideml,
+            parallel_group: None,
         });
     }
 
@@ -445,7 +566,7 @@ fn expand_bundled_tool_result(result: &mut Vec<RawMessage>, raw: &RawMessage) ->
 fn flatten_tool_blocks(messages: Vec<SideMLMessage>) -> Vec<SideMLMessage> {
     let mut result = Vec::with_capacity(messages.len() * 2);
 
-    for msg in messages {
+    for (msg_index, msg) in messages.into_iter().enumerate() {
         // Single-pass count of tool blocks
         let (tool_use_count, tool_result_count) =
             msg.sideml
@@ -489,6 +610,7 @@ fn flatten_tool_blocks(messages: Vec<SideMLMessage>) -> Vec<SideMLMessage> {
                         source_type: msg.source_type,
                         timestamp: msg.timestamp,
                         sideml: new_sideml,
+                        parallel_group: (tool_use_count > 1).then_some(msg_index),
                     });
                 }
                 ContentBlock::ToolResult { tool_use_id, .. } => {
@@ -514,6 +636,7 @@ fn flatten_tool_blocks(messages: Vec<SideMLMessage>) -> Vec<SideMLMessage> {
                         source_type: msg.source_type,
                         timestamp: msg.timestamp,
                         sideml: new_sideml,
+                        parallel_group: None,
                     });
                 }
                 _ => {
@@ -553,6 +676,7 @@ fn emit_non_tool_message(
         source_type: msg.source_type,
         timestamp: msg.timestamp,
         sideml: new_sideml,
+        parallel_group: None,
     });
 }
 
@@ -674,6 +798,32 @@ fn derive_role_from_source_with_context(raw: &RawMessage, is_tool_span: bool) ->
     }
 }
 
+/// Role for a role-less attribute message whose normalizer role is a fallback.
+///
+/// Structure wins over position: tool calls already infer assistant, and tool
+/// results (which normalize to user, as in Anthropic's format) become tool.
+/// Only content without either gets the configured default role.
+fn default_role_for(
+    raw: &RawMessage,
+    sideml: &ChatMessage,
+    default_roles: &DefaultRoles,
+) -> Option<ChatRole> {
+    let MessageSource::Attribute { key, .. } = &raw.source else {
+        return None;
+    };
+    if raw.content.get("role").is_some() || sideml.role != ChatRole::User {
+        return None;
+    }
+    let has_block = |f: fn(&ContentBlock) -> bool| sideml.content.iter().any(f);
+    if sideml.tool_use_id.is_some() || has_block(|b| matches!(b, ContentBlock::ToolResult { .. })) {
+        return Some(ChatRole::Tool);
+    }
+    if has_block(|b| matches!(b, ContentBlock::ToolUse { .. })) {
+        return None;
+    }
+    default_roles.for_attribute(key)
+}
+
 // ============================================================================
 // MESSAGE CATEGORIZATION
 // ============================================================================
@@ -1130,6 +1280,7 @@ mod tests {
                 ],
                 ..Default::default()
             },
+            parallel_group: None,
         };
 
         let result = flatten_tool_blocks(vec![msg]);
@@ -1178,6 +1329,7 @@ mod tests {
                 ],
                 ..Default::default()
             },
+            parallel_group: None,
         };
 
         let result = flatten_tool_blocks(vec![msg]);
@@ -1221,6 +1373,7 @@ mod tests {
                 ],
                 ..Default::default()
             },
+            parallel_group: None,
         };
 
         let result = flatten_tool_blocks(vec![msg.clone()]);
@@ -1257,6 +1410,7 @@ mod tests {
                 ],
                 ..Default::default()
             },
+            parallel_group: None,
         };
 
         let result = flatten_tool_blocks(vec![msg]);
@@ -1316,6 +1470,7 @@ mod tests {
                 ],
                 ..Default::default()
             },
+            parallel_group: None,
         };
 
         let result = flatten_tool_blocks(vec![msg]);
@@ -4047,6 +4047,110 @@ fn test_gemini_grounding_metadata_as_context() {
     );
 }
 
+fn citation_blocks(message: &ChatMessage) -> Vec<&JsonValue> {
+    message
+        .content
+        .iter()
+        .filter_map(|b| match b {
+            ContentBlock::Context { data, context_type }
+                if context_type.as_deref() == Some("citation") =>
+            {
+                Some(data)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_grounded_responses_produce_citation_blocks() {
+    // Gemini: chunks are sources, supports link answer segments to chunks
+    let gemini = normalize(&json!({
+        "role": "model",
+        "content": "Global temperatures rose 1.2C. Sea levels also climbed.",
+        "groundingMetadata": {
+            "groundingChunks": [
+                {"web": {"uri": "https://example.com/temp", "title": "Temperature Report"}},
+                {"web": {"uri": "https://example.com/sea", "title": "Sea Level Study"}}
+            ],
+            "groundingSupports": [
+                {"segment": {"startIndex": 0, "endIndex": 30, "text": "Global temperatures rose 1.2C."},
+                 "groundingChunkIndices": [0]},
+                {"segment": {"startIndex": 31, "endIndex": 55, "text": "Sea levels also climbed."},
+                 "groundingChunkIndices": [1]}
+            ]
+        }
+    }));
+    let cited = citation_blocks(&gemini);
+    assert_eq!(cited.len(), 2);
+    assert_eq!(
+        cited[0],
+        &json!({
+            "url": "https://example.com/temp",
+            "title": "Temperature Report",
+            "snippet": "Global temperatures rose 1.2C."
+        })
+    );
+    assert_eq!(cited[1]["snippet"], "Sea levels also climbed.");
+
+    // OpenAI Chat Completions: nested url_citation annotations on the message
+    let openai = normalize(&json!({
+        "role": "assistant",
+        "content": "Rust 1.80 shipped LazyLock.",
+        "annotations": [{
+            "type": "url_citation",
+            "url_citation": {
+                "url": "https://blog.rust-lang.org/1.80",
+                "title": "Announcing Rust 1.80",
+                "start_index": 0,
+                "end_index": 27
+            }
+        }]
+    }));
+    let cited = citation_blocks(&openai);
+    assert_eq!(cited.len(), 1);
+    assert_eq!(cited[0]["url"], "https://blog.rust-lang.org/1.80");
+    assert_eq!(cited[0]["snippet"], "Rust 1.80 shipped LazyLock.");
+
+    // OpenAI Responses API: flat annotations on output_text parts
+    let responses = normalize(&json!({
+        "role": "assistant",
+        "content": [{
+            "type": "output_text",
+            "text": "See the docs.",
+            "annotations": [{
+                "type": "url_citation",
+                "url": "https://docs.example.com",
+                "title": "Docs",
+                "start_index": 4,
+                "end_index": 12
+            }]
+        }]
+    }));
+    let cited = citation_blocks(&responses);
+    assert_eq!(cited.len(), 1);
+    assert_eq!(cited[0]["snippet"], "the docs");
+
+    // Bare citation lists share the raw `citations` context field
+    let listed = normalize(&json!({
+        "role": "assistant",
+        "content": "Answer.",
+        "citations": ["https://a.example.com", {"url": "https://b.example.com", "title": "B"}]
+    }));
+    let cited = citation_blocks(&listed);
+    assert_eq!(cited.len(), 2);
+    assert_eq!(cited[0], &json!({"url": "https://a.example.com"}));
+    assert_eq!(cited[1]["title"], "B");
+
+    // Sources are only attached to assistant turns
+    let user = normalize(&json!({
+        "role": "user",
+        "content": "hi",
+        "groundingMetadata": {"groundingChunks": [{"web": {"uri": "https://x.test"}}]}
+    }));
+    assert!(citation_blocks(&user).is_empty());
+}
+
 #[test]
 fn test_gemini_citation_metadata_as_context() {
     let input = json!({
@@ -4277,11 +4381,14 @@ fn test_multiple_citation_sources_create_separate_blocks() {
         "citations should create separate Context block"
     );
 
-    // Count Context blocks - should be exactly 2
+    // Count raw Context blocks (normalized citation blocks are separate) - should be exactly 2
     let context_count = output
         .content
         .iter()
-        .filter(|b| matches!(b, ContentBlock::Context { .. }))
+        .filter(|b| {
+            matches!(b, ContentBlock::Context { context_type, .. }
+                if context_type.as_deref() != Some(citations::CITATION_CONTEXT_TYPE))
+        })
         .count();
     assert_eq!(
         context_count, 2,
@@ -4809,6 +4916,73 @@ fn test_chat_span_role_derivation_with_gen_ai_choice() {
     );
 }
 
+#[test]
+fn test_default_roles_apply_only_to_ambiguous_attributes() {
+    use crate::domain::sideml::normalize::to_sideml_with_roles;
+
+    let attr = |key: &str, content: JsonValue| RawMessage {
+        source: MessageSource::Attribute {
+            key: key.to_string(),
+            time: Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap(),
+        },
+        content,
+    };
+    let raw_messages = vec![
+        attr("output.value", json!({"content": "The answer is 42"})),
+        attr("input.value", json!({"content": "What is the answer?"})),
+        attr("custom.io", json!({"content": "Unknown position"})),
+        attr(
+            "output.value",
+            json!({"role": "user", "content": "Explicit"}),
+        ),
+        attr(
+            "output.value",
+            json!({"tool_calls": [{"id": "c1", "function": {"name": "calc", "arguments": "{}"}}]}),
+        ),
+        attr(
+            "output.value",
+            json!({"content": [{"type": "tool_result", "tool_use_id": "c1", "content": "42"}]}),
+        ),
+    ];
+    let roles = |msgs: Vec<SideMLMessage>| msgs.iter().map(|m| m.sideml.role).collect::<Vec<_>>();
+
+    // Built-in defaults: output position is assistant, input position is user;
+    // structure wins, so tool calls stay assistant and tool results are tool
+    let result = to_sideml_with_context(&raw_messages, false);
+    assert_eq!(result[0].category, MessageCategory::GenAIAssistantMessage);
+    assert_eq!(result[5].category, MessageCategory::GenAIToolMessage);
+    assert_eq!(
+        roles(result),
+        vec![
+            ChatRole::Assistant,
+            ChatRole::User,
+            ChatRole::User,
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::Tool,
+        ]
+    );
+
+    // Overrides only touch role-less content without tool structure
+    let overrides = DefaultRoles {
+        input: ChatRole::System,
+        output: ChatRole::User,
+        by_attribute: [("custom.io".to_string(), ChatRole::Assistant)].into(),
+    };
+    let result = to_sideml_with_roles(&raw_messages, false, &overrides);
+    assert_eq!(
+        roles(result),
+        vec![
+            ChatRole::User,
+            ChatRole::System,
+            ChatRole::Assistant,
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::Tool,
+        ]
+    );
+}
+
 // === Documents Role Tests ===
 
 #[test]
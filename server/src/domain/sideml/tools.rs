@@ -45,13 +45,17 @@ pub fn normalize_tool_calls(msg: &JsonValue) -> Option<JsonValue> {
                     .unwrap_or_default();
                 (func.get("name")?.as_str()?.to_string(), args, tc.get("id"))
             } else {
-                // Already flat format (includes LangChain which uses "args")
+                // Already flat format (includes LangChain which uses "args", and
+                // Vercel AI SDK which uses toolCallId/toolName with args or input)
                 let args = tc
                     .get("arguments")
                     .or_else(|| tc.get("args"))
+                    .or_else(|| tc.get("input"))
                     .map(json_value_to_string)
                     .unwrap_or_default();
-                (tc.get("name")?.as_str()?.to_string(), args, tc.get("id"))
+                let name = tc.get("name").or_else(|| tc.get("toolName"))?;
+                let id = tc.get("id").or_else(|| tc.get("toolCallId"));
+                (name.as_str()?.to_string(), args, id)
             };
 
             Some(json!({
@@ -542,4 +546,24 @@ mod tests {
         assert_eq!(calls[0]["name"], "get_weather");
         assert_eq!(calls[0]["arguments"], "{\"city\":\"NYC\"}");
     }
+
+    #[test]
+    fn test_normalize_tool_calls_vercel_format() {
+        // Vercel AI SDK uses toolCallId/toolName with stringified args (v4) or input (v5)
+        let msg = json!({
+            "tool_calls": [
+                {"toolCallType": "function", "toolCallId": "call_1", "toolName": "get_weather", "args": "{\"city\":\"NYC\"}"},
+                {"type": "tool-call", "toolCallId": "call_2", "toolName": "get_time", "input": {"tz": "EST"}}
+            ]
+        });
+        let result = normalize_tool_calls(&msg).unwrap();
+        let calls = result.as_array().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0]["id"], "call_1");
+        assert_eq!(calls[0]["name"], "get_weather");
+        assert_eq!(calls[0]["arguments"], "{\"city\":\"NYC\"}");
+        assert_eq!(calls[1]["id"], "call_2");
+        assert_eq!(calls[1]["name"], "get_time");
+        assert_eq!(calls[1]["arguments"], "{\"tz\":\"EST\"}");
+    }
 }
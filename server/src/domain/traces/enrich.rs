@@ -1,15 +1,22 @@
 //! Span enrichment (Stage 3)
 //!
 //! Calculates derived data from spans and messages:
+//! - Optional token estimation for spans without reported usage
+//! - Optional merging of usage split between a generation span and its child
 //! - Cost calculation from token usage and model pricing
 //! - Input/output preview extraction from messages
 //!
 //! Returns enrichment data separately; persist stage applies it to DB records.
 
-use crate::data::types::MessageCategory;
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Value as JsonValue, json};
+
+use crate::data::types::{MessageCategory, ObservationType};
+use crate::domain::pricing::estimate::TokenEstimator;
 use crate::domain::pricing::{PricingService, SpanCostInput};
-use crate::domain::sideml::{ChatMessage, SideMLMessage};
-use crate::domain::traces::SpanData;
+use crate::domain::sideml::{ChatMessage, ContentBlock, SideMLMessage};
+use crate::domain::traces::{MessageSource, SpanData};
 use crate::utils::string::{PREVIEW_MAX_LENGTH, truncate_preview};
 
 // ============================================================================
@@ -32,14 +39,36 @@ pub(super) struct SpanEnrichment {
     pub cache_write_cost: f64,
     /// Cost for reasoning tokens
     pub reasoning_cost: f64,
+    /// Per-call surcharges for built-in tools (web search)
+    pub tool_cost: f64,
     /// Total cost
     pub total_cost: f64,
+    /// Where the costs came from (None when the span has no cost)
+    pub cost_source: Option<CostSource>,
     /// Preview of input (from user/system messages)
     pub input_preview: Option<String>,
     /// Preview of output (from assistant/choice messages)
     pub output_preview: Option<String>,
 }
 
+/// Origin of a span's stored costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CostSource {
+    /// Computed from token usage and model pricing
+    Computed,
+    /// Reported by the instrumentation (OpenInference `llm.cost.*`)
+    Reported,
+}
+
+impl CostSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Computed => "computed",
+            Self::Reported => "reported",
+        }
+    }
+}
+
 // ============================================================================
 // BATCH ENRICHMENT
 // ============================================================================
@@ -52,11 +81,19 @@ pub(super) fn enrich_batch(
     spans: &[SpanData],
     messages: &[Vec<SideMLMessage>],
     pricing: &PricingService,
+    project_orgs: &HashMap<String, String>,
 ) -> Vec<SpanEnrichment> {
     spans
         .iter()
         .zip(messages.iter())
-        .map(|(span, msgs)| enrich_one(span, msgs, pricing))
+        .map(|(span, msgs)| {
+            let org_id = span
+                .project_id
+                .as_ref()
+                .and_then(|p| project_orgs.get(p))
+                .map(String::as_str);
+            enrich_one(span, msgs, pricing, org_id)
+        })
         .collect()
 }
 
@@ -65,8 +102,9 @@ fn enrich_one(
     span: &SpanData,
     messages: &[SideMLMessage],
     pricing: &PricingService,
+    org_id: Option<&str>,
 ) -> SpanEnrichment {
-    let costs = calculate_span_cost(span, pricing);
+    let costs = calculate_span_cost(span, pricing, org_id);
     let (input_preview, output_preview) = extract_io_preview(messages);
 
     // Fall back to exception_message when no output messages produced a preview
@@ -83,12 +121,220 @@ fn enrich_one(
         cache_read_cost: costs.cache_read_cost,
         cache_write_cost: costs.cache_write_cost,
         reasoning_cost: costs.reasoning_cost,
+        tool_cost: costs.tool_cost,
         total_cost: costs.total_cost,
+        cost_source: costs.source,
         input_preview,
         output_preview,
     }
 }
 
+// ============================================================================
+// USAGE ESTIMATION
+// ============================================================================
+
+/// Estimate token usage for spans that have a model and messages but no usage.
+///
+/// Fills the span's input/output/total tokens from message text and flags the
+/// span (`usage_estimated`, plus `gen_ai_usage_details.estimated`) so estimates
+/// stay distinguishable from reported usage. Spans without a model are skipped
+/// since they cannot be priced.
+pub(super) fn estimate_missing_usage(
+    spans: &mut [SpanData],
+    messages: &[Vec<SideMLMessage>],
+    estimator: &dyn TokenEstimator,
+) {
+    for (span, msgs) in spans.iter_mut().zip(messages.iter()) {
+        let has_usage = span.gen_ai_usage_input_tokens > 0
+            || span.gen_ai_usage_output_tokens > 0
+            || span.gen_ai_usage_total_tokens > 0;
+        let has_model = span
+            .gen_ai_response_model
+            .as_deref()
+            .or(span.gen_ai_request_model.as_deref())
+            .is_some_and(|m| !m.is_empty());
+        if has_usage || !has_model || span.extracted_cost_total.is_some() || msgs.is_empty() {
+            continue;
+        }
+
+        let mut input_tokens = 0;
+        let mut output_tokens = 0;
+        for msg in msgs {
+            let tokens = estimate_message_tokens(&msg.sideml, estimator);
+            if is_output_message(msg) {
+                output_tokens += tokens;
+            } else {
+                input_tokens += tokens;
+            }
+        }
+        if input_tokens == 0 && output_tokens == 0 {
+            continue;
+        }
+
+        span.gen_ai_usage_input_tokens = input_tokens;
+        span.gen_ai_usage_output_tokens = output_tokens;
+        span.gen_ai_usage_total_tokens = input_tokens + output_tokens;
+        span.usage_estimated = true;
+        match &mut span.gen_ai_usage_details {
+            JsonValue::Object(details) => {
+                details.insert("estimated".to_string(), json!(true));
+            }
+            details => *details = json!({ "estimated": true }),
+        }
+    }
+}
+
+/// Whether a message is model output (as opposed to prompt/context input).
+fn is_output_message(msg: &SideMLMessage) -> bool {
+    match msg.category {
+        MessageCategory::GenAIChoice => true,
+        MessageCategory::GenAIAssistantMessage => match &msg.source {
+            MessageSource::Attribute { key, .. } => {
+                key.contains("output") || key.contains("completion") || key.contains("response")
+            }
+            MessageSource::Event { .. } => false,
+        },
+        _ => false,
+    }
+}
+
+/// Estimate tokens for all textual content in a message.
+fn estimate_message_tokens(msg: &ChatMessage, estimator: &dyn TokenEstimator) -> i64 {
+    msg.content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Text { text } | ContentBlock::Thinking { text, .. } => {
+                estimator.estimate(text)
+            }
+            ContentBlock::Refusal { message } => estimator.estimate(message),
+            ContentBlock::ToolUse { name, input, .. } => {
+                estimator.estimate(name) + estimator.estimate(&input.to_string())
+            }
+            ContentBlock::ToolResult { content, .. } => match content {
+                JsonValue::String(s) => estimator.estimate(s),
+                other => estimator.estimate(&other.to_string()),
+            },
+            ContentBlock::Json { data } => estimator.estimate(&data.to_string()),
+            _ => 0,
+        })
+        .sum()
+}
+
+// ============================================================================
+// SPLIT USAGE MERGING
+// ============================================================================
+
+/// Prompt-side tokens (input plus cache reads/writes)
+fn input_side_tokens(span: &SpanData) -> i64 {
+    span.gen_ai_usage_input_tokens
+        + span.gen_ai_usage_cache_read_tokens
+        + span.gen_ai_usage_cache_write_tokens
+}
+
+/// Completion-side tokens (output plus reasoning)
+fn output_side_tokens(span: &SpanData) -> i64 {
+    span.gen_ai_usage_output_tokens + span.gen_ai_usage_reasoning_tokens
+}
+
+/// Move usage split across a generation span and its immediate child onto the parent.
+///
+/// Some frameworks report input tokens on the generation span and output tokens
+/// on a child LLM span (or the reverse), so neither span prices correctly. A
+/// generation span reporting only one side takes the other side from its one
+/// immediate child that reports only that side. The child's tokens are moved,
+/// not copied, so trace totals stay the same. Anything ambiguous is left
+/// alone: several candidate children, a child reporting both sides or the same
+/// side, reported costs, or a child that is itself merging.
+///
+/// Only spans within the same batch are considered.
+pub(super) fn merge_split_usage(spans: &mut [SpanData]) {
+    let mut children: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (idx, span) in spans.iter().enumerate() {
+        if let Some(parent) = span.parent_span_id.as_deref() {
+            children
+                .entry((span.trace_id.as_str(), parent))
+                .or_default()
+                .push(idx);
+        }
+    }
+
+    let mut merges: Vec<(usize, usize)> = Vec::new();
+    for (idx, span) in spans.iter().enumerate() {
+        if span.observation_type != Some(ObservationType::Generation)
+            || span.extracted_cost_total.is_some()
+        {
+            continue;
+        }
+        let (has_input, has_output) = (input_side_tokens(span) > 0, output_side_tokens(span) > 0);
+        if has_input == has_output {
+            continue;
+        }
+        let Some(child_idxs) = children.get(&(span.trace_id.as_str(), span.span_id.as_str()))
+        else {
+            continue;
+        };
+
+        let mut candidate = None;
+        let mut ambiguous = false;
+        for &child_idx in child_idxs {
+            let child = &spans[child_idx];
+            let (child_input, child_output) =
+                (input_side_tokens(child) > 0, output_side_tokens(child) > 0);
+            if !child_input && !child_output {
+                continue;
+            }
+            let complements = child_input != has_input && child_output != has_output;
+            if !complements || child.extracted_cost_total.is_some() || candidate.is_some() {
+                ambiguous = true;
+                break;
+            }
+            candidate = Some(child_idx);
+        }
+        if let (false, Some(child_idx)) = (ambiguous, candidate) {
+            merges.push((idx, child_idx));
+        }
+    }
+
+    // A span receiving usage must not also give its own away
+    let parents: HashSet<usize> = merges.iter().map(|(p, _)| *p).collect();
+    for (parent_idx, child_idx) in merges {
+        if parents.contains(&child_idx) {
+            continue;
+        }
+        let child = &mut spans[child_idx];
+        let moved_total = child
+            .gen_ai_usage_total_tokens
+            .max(child.gen_ai_usage_input_tokens + child.gen_ai_usage_output_tokens);
+        let input = std::mem::take(&mut child.gen_ai_usage_input_tokens);
+        let cache_read = std::mem::take(&mut child.gen_ai_usage_cache_read_tokens);
+        let cache_write = std::mem::take(&mut child.gen_ai_usage_cache_write_tokens);
+        let output = std::mem::take(&mut child.gen_ai_usage_output_tokens);
+        let reasoning = std::mem::take(&mut child.gen_ai_usage_reasoning_tokens);
+        child.gen_ai_usage_total_tokens = 0;
+        let child_id = child.span_id.clone();
+        let child_request_model = child.gen_ai_request_model.clone();
+        let child_response_model = child.gen_ai_response_model.clone();
+        let child_system = child.gen_ai_system.clone();
+
+        let parent = &mut spans[parent_idx];
+        parent.gen_ai_usage_input_tokens += input;
+        parent.gen_ai_usage_cache_read_tokens += cache_read;
+        parent.gen_ai_usage_cache_write_tokens += cache_write;
+        parent.gen_ai_usage_output_tokens += output;
+        parent.gen_ai_usage_reasoning_tokens += reasoning;
+        parent.gen_ai_usage_total_tokens += moved_total;
+        // A parent without its own model is priced with the child's
+        if parent.gen_ai_request_model.is_none() && parent.gen_ai_response_model.is_none() {
+            parent.gen_ai_request_model = child_request_model;
+            parent.gen_ai_response_model = child_response_model;
+        }
+        if parent.gen_ai_system.is_none() {
+            parent.gen_ai_system = child_system;
+        }
+        parent.gen_ai_usage_merged_from = Some(child_id);
+    }
+}
+
 // ============================================================================
 // COST CALCULATION
 // ============================================================================
@@ -101,18 +347,48 @@ struct CostResult {
     cache_read_cost: f64,
     cache_write_cost: f64,
     reasoning_cost: f64,
+    tool_cost: f64,
     total_cost: f64,
+    source: Option<CostSource>,
+}
+
+impl CostResult {
+    /// Costs reported by the span (OpenInference `llm.cost.*` or `gen_ai.usage.cost`)
+    fn reported(span: &SpanData) -> Option<Self> {
+        span.extracted_cost_total.map(|total| Self {
+            input_cost: span.extracted_cost_input.unwrap_or(0.0),
+            output_cost: span.extracted_cost_output.unwrap_or(0.0),
+            total_cost: total,
+            source: Some(CostSource::Reported),
+            ..Default::default()
+        })
+    }
 }
 
 /// Calculate costs for a span using the pricing service.
 ///
-/// Falls back to pre-calculated costs from OpenInference (llm.cost.*) if:
-/// - No model is available for pricing lookup, or
-/// - Pricing service returns zero costs
-fn calculate_span_cost(span: &SpanData, pricing: &PricingService) -> CostResult {
+/// With `pricing.prefer_reported_cost` (the default), costs reported by the
+/// span via OpenInference (llm.cost.*) or a gateway (gen_ai.usage.cost) win
+/// over computed ones. Otherwise they
+/// are only used as a fallback if:
+/// - No model is available for pricing lookup,
+/// - Pricing service returns zero costs, or
+/// - Pricing service only priced an estimated split of `total_tokens`
+fn calculate_span_cost(
+    span: &SpanData,
+    pricing: &PricingService,
+    org_id: Option<&str>,
+) -> CostResult {
+    if pricing.prefers_reported_cost()
+        && let Some(reported) = CostResult::reported(span)
+    {
+        return reported;
+    }
+
     let model = span
         .gen_ai_response_model
         .as_deref()
+        .filter(|m| !m.is_empty())
         .or(span.gen_ai_request_model.as_deref())
         .unwrap_or("");
 
@@ -127,36 +403,33 @@ fn calculate_span_cost(span: &SpanData, pricing: &PricingService) -> CostResult
             cache_read_tokens: span.gen_ai_usage_cache_read_tokens,
             cache_write_tokens: span.gen_ai_usage_cache_write_tokens,
             reasoning_tokens: span.gen_ai_usage_reasoning_tokens,
+            web_search_calls: span.gen_ai_usage_web_search_calls,
+            embedding_dimensions: span.gen_ai_embedding_dimensions,
+            estimated: span.usage_estimated,
+            org_id: org_id.map(str::to_string),
         };
 
         let output = pricing.calculate_cost(&input);
 
-        // If pricing service found the model, use calculated costs
-        if output.total_cost > 0.0 {
+        // If pricing service found the model, use calculated costs, unless
+        // they rest on an estimated token split and the span reported its cost
+        let prefer_reported = output.estimated && span.extracted_cost_total.is_some();
+        if output.total_cost > 0.0 && !prefer_reported {
             return CostResult {
                 input_cost: output.input_cost,
                 output_cost: output.output_cost,
                 cache_read_cost: output.cache_read_cost,
                 cache_write_cost: output.cache_write_cost,
                 reasoning_cost: output.reasoning_cost,
+                tool_cost: output.tool_cost,
                 total_cost: output.total_cost,
+                source: Some(CostSource::Computed),
             };
         }
     }
 
-    // Fallback to pre-calculated costs (OpenInference llm.cost.* attributes)
-    if let Some(total) = span.extracted_cost_total {
-        return CostResult {
-            input_cost: span.extracted_cost_input.unwrap_or(0.0),
-            output_cost: span.extracted_cost_output.unwrap_or(0.0),
-            cache_read_cost: 0.0,
-            cache_write_cost: 0.0,
-            reasoning_cost: 0.0,
-            total_cost: total,
-        };
-    }
-
-    CostResult::default()
+    // Fallback to pre-calculated costs (llm.cost.* or gen_ai.usage.cost)
+    CostResult::reported(span).unwrap_or_default()
 }
 
 // ============================================================================
@@ -217,8 +490,6 @@ fn extract_io_preview(messages: &[SideMLMessage]) -> (Option<String>, Option<Str
 /// Iterates through strongly-typed content blocks and extracts the first text.
 /// When `include_tools` is false, ToolUse and ToolResult blocks are skipped.
 fn extract_content_preview(msg: &ChatMessage, max_len: usize, include_tools: bool) -> String {
-    use crate::domain::sideml::ContentBlock;
-
     for block in &msg.content {
         match block {
             ContentBlock::Text { text } => {
@@ -288,6 +559,7 @@ mod tests {
                 }],
                 ..Default::default()
             },
+            parallel_group: None,
         }
     }
 
@@ -309,7 +581,7 @@ mod tests {
             vec![],
         ];
 
-        let enrichments = enrich_batch(&spans, &messages, &pricing);
+        let enrichments = enrich_batch(&spans, &messages, &pricing, &HashMap::new());
 
         assert_eq!(enrichments.len(), 2);
         assert_eq!(enrichments[0].input_preview, Some("Hello".to_string()));
@@ -337,7 +609,7 @@ mod tests {
         ];
         let pricing = PricingService::init_for_test().unwrap();
 
-        let enrichment = enrich_one(&span, &messages, &pricing);
+        let enrichment = enrich_one(&span, &messages, &pricing, None);
 
         assert_eq!(enrichment.input_preview, Some("Hello world".to_string()));
         assert_eq!(enrichment.output_preview, Some("Hi there!".to_string()));
@@ -349,7 +621,7 @@ mod tests {
         let messages = vec![];
         let pricing = PricingService::init_for_test().unwrap();
 
-        let enrichment = enrich_one(&span, &messages, &pricing);
+        let enrichment = enrich_one(&span, &messages, &pricing, None);
 
         assert_eq!(enrichment.input_preview, None);
         assert_eq!(enrichment.output_preview, None);
@@ -368,11 +640,249 @@ mod tests {
         };
 
         let pricing = PricingService::init_for_test().unwrap();
-        let cost = calculate_span_cost(&span, &pricing);
+        let cost = calculate_span_cost(&span, &pricing, None);
 
         assert_eq!(cost.total_cost, 0.0);
     }
 
+    #[test]
+    fn test_calculate_span_cost_skips_empty_response_model() {
+        // Embedding spans resolve their model from embedding.model_name
+        let span = SpanData {
+            gen_ai_response_model: Some(String::new()),
+            gen_ai_request_model: Some("text-embedding-3-small".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_total_tokens: 1000,
+            ..make_span()
+        };
+
+        let pricing = PricingService::init_for_test().unwrap();
+        let cost = calculate_span_cost(&span, &pricing, None);
+
+        assert!(cost.input_cost > 0.0);
+        assert_eq!(cost.total_cost, cost.input_cost);
+    }
+
+    #[test]
+    fn test_calculate_span_cost_prefers_reported_cost() {
+        let priced = SpanData {
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_output_tokens: 500,
+            gen_ai_usage_total_tokens: 1500,
+            ..make_span()
+        };
+        let reported = SpanData {
+            extracted_cost_total: Some(0.42),
+            extracted_cost_input: Some(0.3),
+            extracted_cost_output: Some(0.12),
+            ..priced.clone()
+        };
+
+        let pricing = PricingService::init_for_test().unwrap();
+
+        let cost = calculate_span_cost(&reported, &pricing, None);
+        assert_eq!(cost.source, Some(CostSource::Reported));
+        assert_eq!(cost.total_cost, 0.42);
+        assert_eq!(cost.input_cost, 0.3);
+        assert_eq!(cost.output_cost, 0.12);
+
+        let cost = calculate_span_cost(&priced, &pricing, None);
+        assert_eq!(cost.source, Some(CostSource::Computed));
+        assert!(cost.total_cost > 0.0);
+        assert_ne!(cost.total_cost, 0.42);
+    }
+
+    #[test]
+    fn test_calculate_span_cost_uses_gateway_reported_total() {
+        let computed = SpanData {
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_output_tokens: 500,
+            gen_ai_usage_total_tokens: 1500,
+            ..make_span()
+        };
+        // Gateways report only the final total (gen_ai.usage.cost)
+        let gateway = SpanData {
+            extracted_cost_total: Some(0.0123),
+            ..computed.clone()
+        };
+
+        let pricing = PricingService::init_for_test().unwrap();
+
+        let cost = calculate_span_cost(&gateway, &pricing, None);
+        assert_eq!(cost.source, Some(CostSource::Reported));
+        assert_eq!(cost.total_cost, 0.0123);
+        assert_eq!(cost.input_cost, 0.0);
+        assert_eq!(cost.output_cost, 0.0);
+
+        let cost = calculate_span_cost(&computed, &pricing, None);
+        assert_eq!(cost.source, Some(CostSource::Computed));
+        assert!(cost.total_cost > 0.0);
+        assert_ne!(cost.total_cost, 0.0123);
+    }
+
+    #[test]
+    fn test_calculate_span_cost_keeps_web_search_surcharge() {
+        let span = SpanData {
+            gen_ai_system: Some("openai".to_string()),
+            gen_ai_request_model: Some("gpt-4o-search-preview".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_output_tokens: 500,
+            gen_ai_usage_total_tokens: 1500,
+            gen_ai_usage_web_search_calls: 2,
+            ..make_span()
+        };
+
+        let pricing = PricingService::init_for_test().unwrap();
+
+        let cost = calculate_span_cost(&span, &pricing, None);
+        // Medium search context: $0.035 per query
+        assert!((cost.tool_cost - 0.07).abs() < 1e-9);
+        let token_cost = cost.input_cost + cost.output_cost;
+        assert!((cost.total_cost - (token_cost + cost.tool_cost)).abs() < 1e-9);
+    }
+
+    // === Usage Estimation Tests ===
+
+    #[test]
+    fn test_estimate_missing_usage() {
+        use crate::domain::pricing::estimate::BpeApproxEstimator;
+
+        let mut spans = vec![SpanData {
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            ..make_span()
+        }];
+        let user = "Summarize the quarterly report for the board meeting tomorrow.";
+        let answer = "Revenue grew 12% while operating costs stayed flat.";
+        let mut choice = make_message(MessageCategory::GenAIChoice, ChatRole::Assistant, answer);
+        choice.source = MessageSource::Event {
+            name: "gen_ai.choice".to_string(),
+            time: Utc::now(),
+        };
+        let messages = vec![vec![
+            make_message(MessageCategory::GenAIUserMessage, ChatRole::User, user),
+            choice,
+        ]];
+
+        estimate_missing_usage(&mut spans, &messages, &BpeApproxEstimator);
+
+        let span = &spans[0];
+        assert!(span.usage_estimated);
+        assert_eq!(span.gen_ai_usage_details["estimated"], json!(true));
+        // Real cl100k counts: 12 input, 11 output
+        assert!((9..=16).contains(&span.gen_ai_usage_input_tokens));
+        assert!((8..=15).contains(&span.gen_ai_usage_output_tokens));
+        assert_eq!(
+            span.gen_ai_usage_total_tokens,
+            span.gen_ai_usage_input_tokens + span.gen_ai_usage_output_tokens
+        );
+
+        let pricing = PricingService::init_for_test().unwrap();
+        let cost = calculate_span_cost(span, &pricing, None);
+        assert!(cost.total_cost > 0.0);
+    }
+
+    #[test]
+    fn test_estimate_missing_usage_keeps_reported_usage() {
+        use crate::domain::pricing::estimate::BpeApproxEstimator;
+
+        let mut spans = vec![SpanData {
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_input_tokens: 42,
+            ..make_span()
+        }];
+        let messages = vec![vec![make_message(
+            MessageCategory::GenAIUserMessage,
+            ChatRole::User,
+            "Hello",
+        )]];
+
+        estimate_missing_usage(&mut spans, &messages, &BpeApproxEstimator);
+
+        assert!(!spans[0].usage_estimated);
+        assert_eq!(spans[0].gen_ai_usage_input_tokens, 42);
+    }
+
+    // === Split Usage Merging Tests ===
+
+    #[test]
+    fn test_merge_split_usage_moves_child_output_to_parent() {
+        let parent = SpanData {
+            observation_type: Some(ObservationType::Generation),
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_total_tokens: 1000,
+            ..make_span()
+        };
+        let child = SpanData {
+            span_id: "span2".to_string(),
+            parent_span_id: Some("span1".to_string()),
+            observation_type: Some(ObservationType::Generation),
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_output_tokens: 500,
+            gen_ai_usage_total_tokens: 500,
+            ..make_span()
+        };
+        let mut spans = vec![parent, child];
+
+        merge_split_usage(&mut spans);
+
+        assert_eq!(spans[0].gen_ai_usage_input_tokens, 1000);
+        assert_eq!(spans[0].gen_ai_usage_output_tokens, 500);
+        assert_eq!(spans[0].gen_ai_usage_total_tokens, 1500);
+        assert_eq!(spans[0].gen_ai_usage_merged_from.as_deref(), Some("span2"));
+        // Moved, not copied: the child no longer reports usage
+        assert_eq!(spans[1].gen_ai_usage_output_tokens, 0);
+        assert_eq!(spans[1].gen_ai_usage_total_tokens, 0);
+
+        let pricing = PricingService::init_for_test().unwrap();
+        let whole = SpanData {
+            gen_ai_request_model: Some("gpt-4o".to_string()),
+            gen_ai_usage_input_tokens: 1000,
+            gen_ai_usage_output_tokens: 500,
+            gen_ai_usage_total_tokens: 1500,
+            ..make_span()
+        };
+        let merged_cost = calculate_span_cost(&spans[0], &pricing, None);
+        let expected = calculate_span_cost(&whole, &pricing, None);
+        assert!(merged_cost.output_cost > 0.0);
+        assert!((merged_cost.total_cost - expected.total_cost).abs() < 1e-12);
+        assert_eq!(
+            calculate_span_cost(&spans[1], &pricing, None).total_cost,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_merge_split_usage_skips_ambiguous_children() {
+        let parent = SpanData {
+            observation_type: Some(ObservationType::Generation),
+            gen_ai_usage_input_tokens: 1000,
+            ..make_span()
+        };
+        let child = |id: &str| SpanData {
+            span_id: id.to_string(),
+            parent_span_id: Some("span1".to_string()),
+            gen_ai_usage_output_tokens: 500,
+            ..make_span()
+        };
+        let mut spans = vec![parent.clone(), child("a"), child("b")];
+        merge_split_usage(&mut spans);
+        assert_eq!(spans[0].gen_ai_usage_output_tokens, 0);
+        assert_eq!(spans[1].gen_ai_usage_output_tokens, 500);
+
+        // A child with complete usage is its own call, not the missing half
+        let complete = SpanData {
+            gen_ai_usage_input_tokens: 900,
+            ..child("a")
+        };
+        let mut spans = vec![parent, complete];
+        merge_split_usage(&mut spans);
+        assert_eq!(spans[0].gen_ai_usage_output_tokens, 0);
+        assert_eq!(spans[1].gen_ai_usage_input_tokens, 900);
+    }
+
     #[test]
     fn test_span_enrichment_default() {
         let enrichment = SpanEnrichment::default();
@@ -551,6 +1061,7 @@ mod tests {
                     }],
                     ..Default::default()
                 },
+                parallel_group: None,
             },
         ];
 
@@ -609,6 +1120,7 @@ mod tests {
                 }],
                 ..Default::default()
             },
+            parallel_group: None,
         }
     }
 
@@ -742,7 +1254,7 @@ mod tests {
         )];
         let pricing = PricingService::init_for_test().unwrap();
 
-        let enrichment = enrich_one(&span, &messages, &pricing);
+        let enrichment = enrich_one(&span, &messages, &pricing, None);
         assert_eq!(
             enrichment.output_preview,
             Some("Connection refused".to_string())
@@ -6,18 +6,23 @@
 
 use std::collections::{HashMap, HashSet};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use opentelemetry_proto::tonic::trace::v1::Span;
+use opentelemetry_proto::tonic::trace::v1::span::Event;
+use regex::Regex;
 use serde_json::{Value as JsonValue, json};
 
 use crate::core::constants;
-use crate::data::types::{Framework, ObservationType, SpanCategory};
+use crate::data::types::{
+    Framework, IngestWarning, IngestWarningKind, ObservationType, SpanCategory,
+};
+use crate::utils::otlp::extract_attributes;
 use crate::utils::string::parse_string_array;
 use crate::utils::time::nanos_to_datetime;
 
 use super::truncate_bytes;
 
-use super::{extract_json, keys};
+use super::{extract_json, is_openai_responses, keys};
 
 // ============================================================================
 // SHARED HELPER FUNCTIONS
@@ -60,6 +65,15 @@ pub(super) fn get_first(attrs: &HashMap<String, String>, keys: &[&str]) -> Optio
     keys.iter().find_map(|k| attrs.get(*k).cloned())
 }
 
+/// Get first non-empty value from multiple possible keys.
+pub(super) fn get_first_non_empty(
+    attrs: &HashMap<String, String>,
+    keys: &[&str],
+) -> Option<String> {
+    keys.iter()
+        .find_map(|k| attrs.get(*k).filter(|v| !v.is_empty()).cloned())
+}
+
 /// Parse a value from attributes.
 pub(super) fn parse_opt<T: std::str::FromStr>(
     attrs: &HashMap<String, String>,
@@ -116,6 +130,40 @@ pub(super) fn set_core_fields(s: &mut SpanData, span: &Span) {
     };
 }
 
+/// Clamp a span dated more than `max_skew` after `now` back to `now`.
+///
+/// A span from a fast SDK clock would otherwise sort after everything else
+/// in birth-time ordering indefinitely. The end is shifted by the same amount
+/// so the duration is kept, and the shift is recorded in `clock_shift` so
+/// message extraction moves event times with it. Small skew within the
+/// tolerance is left alone.
+pub(super) fn clamp_future_timestamps(
+    span: &mut SpanData,
+    now: DateTime<Utc>,
+    max_skew: TimeDelta,
+) {
+    let ahead = span.timestamp_start - now;
+    if ahead <= max_skew {
+        return;
+    }
+    tracing::warn!(
+        trace_id = %span.trace_id,
+        span_id = %span.span_id,
+        ahead_secs = ahead.num_seconds(),
+        "Span starts in the future, clamping to ingestion time"
+    );
+    span.timestamp_start = now;
+    span.timestamp_end = span.timestamp_end.map(|end| end - ahead);
+    span.clock_shift = ahead;
+    span.ingest_warnings.push(IngestWarning {
+        kind: IngestWarningKind::FutureTimestamp,
+        message: format!(
+            "Span started {}s in the future; clamped to ingestion time",
+            ahead.num_seconds()
+        ),
+    });
+}
+
 fn span_kind_to_string(kind: i32) -> &'static str {
     match kind {
         0 => "UNSPECIFIED",
@@ -137,6 +185,28 @@ fn status_code_to_string(code: i32) -> &'static str {
     }
 }
 
+// ============================================================================
+// STATUS OVERRIDES
+// ============================================================================
+
+/// Downgrade an ERROR status whose exception type is configured as a non-error.
+///
+/// Intended for exceptions that signal control flow rather than failure
+/// (e.g. `asyncio.CancelledError`, `GeneratorExit`). Only the effective
+/// `status_code` changes: status message and exception fields are kept, and the
+/// original status remains in the raw span JSON.
+pub(super) fn downgrade_non_error_exception(span: &mut SpanData, non_error_types: &[String]) {
+    if span.status_code.as_deref() != Some("ERROR") {
+        return;
+    }
+    let Some(exception_type) = span.exception_type.as_deref() else {
+        return;
+    };
+    if non_error_types.iter().any(|t| t == exception_type) {
+        span.status_code = Some(status_code_to_string(0).to_string());
+    }
+}
+
 // ============================================================================
 // SPAN NAME RESOLUTION
 // ============================================================================
@@ -217,6 +287,16 @@ pub struct SpanData {
     pub gen_ai_presence_penalty: Option<f64>,
     pub gen_ai_stop_sequences: Vec<String>,
     pub gen_ai_finish_reasons: Vec<String>,
+    /// Output vector size of an embedding request (requested or reported)
+    pub gen_ai_embedding_dimensions: Option<i64>,
+    /// Embedding encoding format (e.g. "float", "base64")
+    pub gen_ai_encoding_format: Option<String>,
+    /// Reasoning effort requested from a reasoning model (e.g. "low", "high")
+    pub gen_ai_reasoning_effort: Option<String>,
+    /// Requested response verbosity (e.g. "low", "medium", "high")
+    pub gen_ai_verbosity: Option<String>,
+    /// Model string as reported, when `otel.ingestion.model_aliases` rewrote it
+    pub gen_ai_raw_model: Option<String>,
 
     // GenAI Agent/Tool
     pub gen_ai_agent_id: Option<String>,
@@ -236,8 +316,14 @@ pub struct SpanData {
     pub gen_ai_usage_cache_write_tokens: i64,
     pub gen_ai_usage_reasoning_tokens: i64,
     pub gen_ai_usage_details: JsonValue,
-
-    // Pre-calculated costs (from OpenInference llm.cost.* or other sources)
+    /// Built-in web search calls billed per query (not a token count)
+    pub gen_ai_usage_web_search_calls: i64,
+    /// Child span whose usage was merged into this one
+    pub gen_ai_usage_merged_from: Option<String>,
+    /// Usage tokens were estimated from message text (no reported usage)
+    pub usage_estimated: bool,
+
+    // Pre-calculated costs (from OpenInference llm.cost.* or gen_ai.usage.cost)
     // These are used as fallback when pricing service cannot calculate costs
     pub extracted_cost_total: Option<f64>,
     pub extracted_cost_input: Option<f64>,
@@ -260,6 +346,12 @@ pub struct SpanData {
     // Tags/Metadata
     pub tags: Vec<String>,
     pub metadata: JsonValue,
+
+    // Data-quality issues found during extraction (persisted with the span)
+    pub ingest_warnings: Vec<IngestWarning>,
+
+    // Amount the span was moved back by future-timestamp clamping (zero if not)
+    pub clock_shift: TimeDelta,
 }
 
 // ============================================================================
@@ -315,6 +407,7 @@ const CACHE_READ_TOKENS: TokenConfig = TokenConfig::new(
         "gen_ai.usage.cache_read_tokens",
         "llm.usage.cache_read_input_tokens",
         "ai.usage.cachedInputTokens",
+        "gen_ai.usage.cached_content_token_count", // Gemini
     ],
 );
 
@@ -331,10 +424,20 @@ const REASONING_TOKENS: TokenConfig = TokenConfig::new(
     "gen_ai.usage.output_reasoning_tokens",
     &[
         "gen_ai.usage.thoughts_token_count",
+        "gen_ai.usage.completion_tokens_details.reasoning_tokens",
+        "llm.token_count.completion_details.reasoning",
         "ai.usage.reasoningTokens",
     ],
 );
 
+const WEB_SEARCH_CALLS: TokenConfig = TokenConfig::new(
+    "gen_ai.usage.server_tool_use.web_search_requests",
+    &[
+        "gen_ai.usage.web_search_requests",
+        "gen_ai.usage.web_search_calls",
+    ],
+);
+
 const KNOWN_USAGE_FIELDS: &[&str] = &[
     "input_tokens",
     "output_tokens",
@@ -347,6 +450,9 @@ const KNOWN_USAGE_FIELDS: &[&str] = &[
     "cache_write_tokens",
     "output_reasoning_tokens",
     "thoughts_token_count",
+    "cached_content_token_count",
+    "completion_tokens_details.reasoning_tokens",
+    "cost",
 ];
 
 // ============================================================================
@@ -658,14 +764,16 @@ enum SemanticKind {
 }
 
 impl SemanticKind {
+    /// Parse an OpenInference span kind or LangSmith run type (case-insensitive).
     fn parse(kind: &str) -> Option<Self> {
         match kind.to_uppercase().as_str() {
             "LLM" => Some(Self::LLM),
             "EMBEDDING" => Some(Self::Embedding),
             "AGENT" => Some(Self::Agent),
             "TOOL" => Some(Self::Tool),
-            "CHAIN" => Some(Self::Chain),
-            "RETRIEVER" => Some(Self::Retriever),
+            // LangSmith prompt/parser runs are pipeline steps
+            "CHAIN" | "PROMPT" | "PARSER" => Some(Self::Chain),
+            "RETRIEVER" | "RERANKER" => Some(Self::Retriever),
             "GUARDRAIL" => Some(Self::Guardrail),
             "EVALUATOR" => Some(Self::Evaluator),
             _ => None,
@@ -779,14 +887,41 @@ pub(crate) fn categorize_span(span_name: &str, attrs: &HashMap<String, String>)
     SpanCategory::Other
 }
 
+/// Parse an observation type name (lowercase) through its serde representation.
+pub(crate) fn parse_observation_type(name: &str) -> Option<ObservationType> {
+    serde_json::from_value(JsonValue::String(name.to_string())).ok()
+}
+
 /// Detect observation type from span attributes.
+///
+/// `name_patterns` (from `otel.ingestion.observation_type_patterns`) are
+/// checked first, in order; the first span name match with a known type wins
+/// over everything else.
 pub(crate) fn detect_observation_type(
     span_name: &str,
     attrs: &HashMap<String, String>,
+    name_patterns: &[(Regex, String)],
 ) -> ObservationType {
-    // Priority 0: External service calls (HTTP/RPC/DB) are NEVER GenAI spans
-    // This must be checked FIRST to prevent AWS Bedrock API calls (rpc.system=aws-api)
-    // from being classified as Generation even if they have gen_ai.* attributes.
+    if let Some(forced) = name_patterns
+        .iter()
+        .filter(|(re, _)| re.is_match(span_name))
+        .find_map(|(_, name)| parse_observation_type(name))
+    {
+        return forced;
+    }
+
+    // Explicit SDK span kinds are authoritative: the instrumentation knows what
+    // the span is, while everything below is inferred
+    for key in [keys::OPENINFERENCE_SPAN_KIND, keys::LANGSMITH_SPAN_KIND] {
+        if let Some(kind) = attrs.get(key).and_then(|k| SemanticKind::parse(k)) {
+            return kind.to_observation_type();
+        }
+    }
+
+    // External service calls (HTTP/RPC/DB) are NEVER GenAI spans. Checked before
+    // every inferred signal (only name patterns and explicit span kinds come
+    // first) so AWS Bedrock API calls (rpc.system=aws-api) are not classified as
+    // Generation even if they have gen_ai.* attributes.
     if attrs.contains_key(keys::HTTP_METHOD)
         || attrs.contains_key(keys::HTTP_REQUEST_METHOD)
         || attrs.contains_key(keys::RPC_SYSTEM)
@@ -823,14 +958,7 @@ pub(crate) fn detect_observation_type(
         }
     }
 
-    // Priority 2: SDK span kinds
-    for key in [keys::OPENINFERENCE_SPAN_KIND, keys::LANGSMITH_SPAN_KIND] {
-        if let Some(kind) = attrs.get(key).and_then(|k| SemanticKind::parse(k)) {
-            return kind.to_observation_type();
-        }
-    }
-
-    // Priority 3: Vercel AI SDK
+    // Priority 2: Vercel AI SDK
     if attrs.contains_key(keys::AI_MODEL_ID) || attrs.contains_key(keys::AI_MODEL_PROVIDER) {
         if attrs
             .get(keys::AI_OPERATION_ID)
@@ -841,7 +969,7 @@ pub(crate) fn detect_observation_type(
         return ObservationType::Generation;
     }
 
-    // Priority 4: Attribute presence
+    // Priority 3: Attribute presence
     if attrs.contains_key(keys::GEN_AI_AGENT_NAME) || attrs.contains_key(keys::GEN_AI_AGENT_ID) {
         return ObservationType::Agent;
     }
@@ -849,7 +977,7 @@ pub(crate) fn detect_observation_type(
         return ObservationType::Tool;
     }
 
-    // Priority 5: Span name patterns
+    // Priority 4: Span name patterns
     let name_lower = span_name.to_lowercase();
     for (pattern, obs_type) in [
         ("embed", ObservationType::Embedding),
@@ -864,7 +992,7 @@ pub(crate) fn detect_observation_type(
         }
     }
 
-    // Priority 6: Logfire tags (logfire.tags: ["LLM"])
+    // Priority 5: Logfire tags (logfire.tags: ["LLM"])
     if let Some(tags) = attrs.get("logfire.tags") {
         let tags_lower = tags.to_lowercase();
         if tags_lower.contains("llm") {
@@ -872,7 +1000,7 @@ pub(crate) fn detect_observation_type(
         }
     }
 
-    // Priority 7: Has model = Generation
+    // Priority 6: Has model = Generation
     if attrs.contains_key(keys::GEN_AI_REQUEST_MODEL)
         || attrs.contains_key(keys::GEN_AI_RESPONSE_MODEL)
     {
@@ -882,6 +1010,45 @@ pub(crate) fn detect_observation_type(
     ObservationType::Span
 }
 
+/// Reasoning token count from a provider usage object.
+///
+/// OpenAI and DeepSeek report it under `completion_tokens_details`; some
+/// compatible providers put `reasoning_tokens` at the top level. Gemini's
+/// `usage_metadata` reports it as `thoughts_token_count` (`thoughtsTokenCount`
+/// in the REST encoding). The OpenAI Responses API uses `output_tokens_details`.
+fn reasoning_tokens_from_usage(usage: &JsonValue) -> Option<i64> {
+    usage
+        .get("completion_tokens_details")
+        .or_else(|| usage.get("output_tokens_details"))
+        .and_then(|d| d.get("reasoning_tokens"))
+        .or_else(|| usage.get("reasoning_tokens"))
+        .or_else(|| usage.get("thoughts_token_count"))
+        .or_else(|| usage.get("thoughtsTokenCount"))
+        .and_then(|v| v.as_i64())
+        .filter(|&n| n > 0)
+}
+
+/// Cached content token count from a Gemini `usage_metadata` object.
+///
+/// Implicit and explicit context caching both report cache hits as
+/// `cached_content_token_count` (`cachedContentTokenCount` in the REST encoding).
+/// The count is part of `prompt_token_count`, like OpenAI's `cached_tokens`.
+fn gemini_cached_tokens(usage: &JsonValue) -> Option<i64> {
+    usage
+        .get("cached_content_token_count")
+        .or_else(|| usage.get("cachedContentTokenCount"))
+        .and_then(|v| v.as_i64())
+        .filter(|&n| n > 0)
+}
+
+/// Usage object of a response JSON, either top-level or inside a wrapping
+/// `response` (OpenAI Responses API stream/result objects).
+fn response_data_usage(resp: &JsonValue) -> Option<&JsonValue> {
+    resp.get("usage")
+        .or_else(|| resp.get("response").and_then(|r| r.get("usage")))
+        .filter(|u| u.is_object())
+}
+
 /// Sum `models_usage.prompt_tokens` / `completion_tokens` from AutoGen `output.value`.
 /// Only extracts from chain spans (`output.value.messages[]`) to avoid double-counting —
 /// the same message appears in multiple routing (process) spans.
@@ -1007,8 +1174,9 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
     );
     span.gen_ai_operation_name = attrs.get(keys::GEN_AI_OPERATION_NAME).cloned();
 
-    // Models (including embedding/reranker model names as fallback)
-    span.gen_ai_request_model = get_first(
+    // Models (including embedding/reranker model names as fallback). Empty
+    // values are skipped so an empty gen_ai.request.model does not hide them.
+    span.gen_ai_request_model = get_first_non_empty(
         attrs,
         &[
             keys::GEN_AI_REQUEST_MODEL,
@@ -1019,7 +1187,7 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
         ],
     );
     span.gen_ai_response_model =
-        get_first(attrs, &[keys::GEN_AI_RESPONSE_MODEL, "llm.response.model"]);
+        get_first_non_empty(attrs, &[keys::GEN_AI_RESPONSE_MODEL, "llm.response.model"]);
     span.gen_ai_response_id = attrs.get(keys::GEN_AI_RESPONSE_ID).cloned();
 
     // Google ADK: model from llm_request JSON
@@ -1063,7 +1231,13 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
                 // Anthropic: top-level "system" key (string/array); OpenAI: messages[0].role=system
                 if req.get("system").is_some() {
                     span.gen_ai_system = Some("anthropic".to_string());
-                } else if req.get("messages").is_some() {
+                } else if req.get("messages").is_some()
+                    || is_openai_responses(
+                        &req,
+                        extract_json::<JsonValue>(attrs, keys::RESPONSE_DATA).as_ref(),
+                    )
+                {
+                    // Chat Completions or Responses API
                     span.gen_ai_system = Some("openai".to_string());
                 }
             }
@@ -1086,6 +1260,13 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
     span.gen_ai_max_tokens = parse_opt(attrs, keys::GEN_AI_MAX_TOKENS);
     span.gen_ai_frequency_penalty = parse_opt(attrs, keys::GEN_AI_FREQUENCY_PENALTY);
     span.gen_ai_presence_penalty = parse_opt(attrs, keys::GEN_AI_PRESENCE_PENALTY);
+    span.gen_ai_embedding_dimensions = parse_opt(attrs, keys::GEN_AI_EMBEDDINGS_DIMENSION_COUNT)
+        .or_else(|| parse_opt(attrs, keys::GEN_AI_REQUEST_DIMENSIONS));
+    span.gen_ai_encoding_format = attrs
+        .get(keys::GEN_AI_ENCODING_FORMATS)
+        .and_then(|formats| parse_string_array(formats).into_iter().next());
+    span.gen_ai_reasoning_effort = attrs.get(keys::GEN_AI_REASONING_EFFORT).cloned();
+    span.gen_ai_verbosity = attrs.get(keys::GEN_AI_VERBOSITY).cloned();
 
     // OpenInference llm.invocation_parameters fallback
     if let Some(params_json) = attrs.get(keys::LLM_INVOCATION_PARAMETERS) {
@@ -1113,6 +1294,31 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
                 span.gen_ai_presence_penalty =
                     params.get("presence_penalty").and_then(|v| v.as_f64());
             }
+            if span.gen_ai_embedding_dimensions.is_none() {
+                span.gen_ai_embedding_dimensions =
+                    params.get("dimensions").and_then(|v| v.as_i64());
+            }
+            if span.gen_ai_encoding_format.is_none() {
+                span.gen_ai_encoding_format = params
+                    .get("encoding_format")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            // Chat Completions uses flat keys; the Responses API nests them
+            if span.gen_ai_reasoning_effort.is_none() {
+                span.gen_ai_reasoning_effort = params
+                    .get("reasoning_effort")
+                    .or_else(|| params.pointer("/reasoning/effort"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            if span.gen_ai_verbosity.is_none() {
+                span.gen_ai_verbosity = params
+                    .get("verbosity")
+                    .or_else(|| params.pointer("/text/verbosity"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
         }
     }
 
@@ -1199,9 +1405,10 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
     // Logfire: tokens from response_data.usage JSON
     // Anthropic: {input_tokens, output_tokens, cache_read_input_tokens, cache_creation_input_tokens}
     // OpenAI: {prompt_tokens, completion_tokens}
+    // OpenAI Responses: {input_tokens, output_tokens, *_tokens_details}
     if span.gen_ai_usage_input_tokens == 0 && span.gen_ai_usage_output_tokens == 0 {
         if let Some(resp) = extract_json::<JsonValue>(attrs, keys::RESPONSE_DATA) {
-            if let Some(usage) = resp.get("usage") {
+            if let Some(usage) = response_data_usage(&resp) {
                 span.gen_ai_usage_input_tokens = usage
                     .get("input_tokens")
                     .or_else(|| usage.get("prompt_tokens"))
@@ -1223,13 +1430,68 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
     span.gen_ai_usage_cache_write_tokens = CACHE_WRITE_TOKENS.extract(attrs);
     span.gen_ai_usage_reasoning_tokens = REASONING_TOKENS.extract(attrs);
 
+    // OpenAI-compatible reasoning models (DeepSeek R1, o-series): reasoning tokens
+    // only in the usage block of the full response JSON
+    if span.gen_ai_usage_reasoning_tokens == 0 {
+        span.gen_ai_usage_reasoning_tokens = [keys::GEN_AI_COMPLETION, keys::RESPONSE_DATA]
+            .iter()
+            .filter_map(|key| extract_json::<JsonValue>(attrs, key))
+            .find_map(|resp| response_data_usage(&resp).and_then(reasoning_tokens_from_usage))
+            .unwrap_or(0);
+    }
+
+    // Built-in web search: flat attributes, else Anthropic's usage.server_tool_use
+    span.gen_ai_usage_web_search_calls = WEB_SEARCH_CALLS.extract(attrs);
+    if span.gen_ai_usage_web_search_calls == 0 {
+        span.gen_ai_usage_web_search_calls = [keys::GEN_AI_COMPLETION, keys::RESPONSE_DATA]
+            .iter()
+            .filter_map(|key| extract_json::<JsonValue>(attrs, key))
+            .find_map(|resp| {
+                resp.get("usage")
+                    .and_then(|u| u.get("server_tool_use"))
+                    .and_then(|t| t.get("web_search_requests"))
+                    .and_then(|v| v.as_i64())
+                    .filter(|&n| n > 0)
+            })
+            .unwrap_or(0);
+    }
+
+    // Google ADK / Gemini: thinking tokens in llm_response usage_metadata
+    if span.gen_ai_usage_reasoning_tokens == 0
+        && let Some(resp) = extract_json::<JsonValue>(attrs, keys::GCP_VERTEX_LLM_RESPONSE)
+    {
+        span.gen_ai_usage_reasoning_tokens = resp
+            .get("usage_metadata")
+            .or_else(|| resp.get("usageMetadata"))
+            .and_then(reasoning_tokens_from_usage)
+            .unwrap_or(0);
+    }
+
+    // Google ADK / Gemini: cache hits in the raw response usage_metadata
+    if span.gen_ai_usage_cache_read_tokens == 0 {
+        span.gen_ai_usage_cache_read_tokens = [keys::GCP_VERTEX_LLM_RESPONSE, keys::RESPONSE_DATA]
+            .iter()
+            .filter_map(|key| extract_json::<JsonValue>(attrs, key))
+            .find_map(|resp| {
+                resp.get("usage_metadata")
+                    .or_else(|| resp.get("usageMetadata"))
+                    .and_then(gemini_cached_tokens)
+            })
+            .unwrap_or(0);
+    }
+
     // Logfire: cache tokens from response_data.usage (after flat attribute extraction)
     if span.gen_ai_usage_cache_read_tokens == 0 || span.gen_ai_usage_cache_write_tokens == 0 {
         if let Some(resp) = extract_json::<JsonValue>(attrs, keys::RESPONSE_DATA) {
-            if let Some(usage) = resp.get("usage") {
+            if let Some(usage) = response_data_usage(&resp) {
                 if span.gen_ai_usage_cache_read_tokens == 0 {
                     span.gen_ai_usage_cache_read_tokens = usage
                         .get("cache_read_input_tokens")
+                        .or_else(|| {
+                            usage
+                                .get("input_tokens_details")
+                                .and_then(|d| d.get("cached_tokens"))
+                        })
                         .and_then(|v| v.as_i64())
                         .unwrap_or(0);
                 }
@@ -1304,18 +1566,130 @@ pub(crate) fn extract_genai(span: &mut SpanData, attrs: &HashMap<String, String>
             details.insert(field.to_string(), json_val);
         }
     }
+    span.gen_ai_embedding_dimensions = span.gen_ai_embedding_dimensions.filter(|&d| d > 0);
+    span.gen_ai_encoding_format = span.gen_ai_encoding_format.take().filter(|f| !f.is_empty());
+    span.gen_ai_reasoning_effort = span
+        .gen_ai_reasoning_effort
+        .take()
+        .filter(|e| !e.is_empty());
+    span.gen_ai_verbosity = span.gen_ai_verbosity.take().filter(|v| !v.is_empty());
     span.gen_ai_usage_details = if details.is_empty() {
         JsonValue::Null
     } else {
         JsonValue::Object(details)
     };
 
-    // Pre-calculated costs (OpenInference llm.cost.* attributes)
-    span.extracted_cost_total = parse_opt(attrs, keys::LLM_COST_TOTAL);
+    // Pre-calculated costs (OpenInference llm.cost.* or a gateway's gen_ai.usage.cost)
+    span.extracted_cost_total = parse_opt(attrs, keys::LLM_COST_TOTAL)
+        .or_else(|| parse_opt(attrs, keys::GEN_AI_USAGE_COST));
     span.extracted_cost_input = parse_opt(attrs, keys::LLM_COST_PROMPT);
     span.extracted_cost_output = parse_opt(attrs, keys::LLM_COST_COMPLETION);
 }
 
+/// Rewrite request/response model strings through the configured alias table.
+///
+/// Pricing and display then use the canonical name. The original (response
+/// model first, as that is what pricing prefers) is kept in `gen_ai_raw_model`.
+pub(crate) fn apply_model_aliases(span: &mut SpanData, aliases: &HashMap<String, String>) {
+    if aliases.is_empty() {
+        return;
+    }
+    let mut raw_model = None;
+    for model in [
+        &mut span.gen_ai_response_model,
+        &mut span.gen_ai_request_model,
+    ] {
+        if let Some(canonical) = model.as_deref().and_then(|m| aliases.get(m)) {
+            let original = model.replace(canonical.clone());
+            raw_model = raw_model.or(original);
+        }
+    }
+    if raw_model.is_some() {
+        span.gen_ai_raw_model = raw_model;
+    }
+}
+
+/// Token usage from Anthropic streaming events forwarded as span events.
+///
+/// A streamed message reports usage across `message_start` (input, cache and an
+/// initial output count) and `message_delta` (cumulative output so far), so the
+/// largest value per message is its final count. A span holding several
+/// streamed messages sums them. Only used when attributes carried no usage.
+pub(super) fn extract_streaming_usage(span: &mut SpanData, events: &[Event]) {
+    if span.gen_ai_usage_input_tokens != 0 || span.gen_ai_usage_output_tokens != 0 {
+        return;
+    }
+
+    // [input, output, cache_read, cache_write]
+    let mut totals = [0i64; 4];
+    let mut current: Option<[i64; 4]> = None;
+    for event in events {
+        let is_start = event.name.ends_with("message_start");
+        if !is_start && !event.name.ends_with("message_delta") {
+            continue;
+        }
+        let Some(usage) = streaming_event_usage(&extract_attributes(&event.attributes)) else {
+            continue;
+        };
+        if is_start && let Some(done) = current.take() {
+            totals.iter_mut().zip(done).for_each(|(t, n)| *t += n);
+        }
+        let counts = current.get_or_insert([0; 4]);
+        let keys = [
+            "input_tokens",
+            "output_tokens",
+            "cache_read_input_tokens",
+            "cache_creation_input_tokens",
+        ];
+        for (count, key) in counts.iter_mut().zip(keys) {
+            *count = (*count).max(usage_count(&usage, key));
+        }
+    }
+    let Some(last) = current else {
+        return;
+    };
+    totals.iter_mut().zip(last).for_each(|(t, n)| *t += n);
+
+    let [input, output, cache_read, cache_write] = totals;
+    span.gen_ai_usage_input_tokens = input;
+    span.gen_ai_usage_output_tokens = output;
+    span.gen_ai_usage_total_tokens = span.gen_ai_usage_total_tokens.max(input + output);
+    if span.gen_ai_usage_cache_read_tokens == 0 {
+        span.gen_ai_usage_cache_read_tokens = cache_read;
+    }
+    if span.gen_ai_usage_cache_write_tokens == 0 {
+        span.gen_ai_usage_cache_write_tokens = cache_write;
+    }
+}
+
+/// Usage object of a streaming event: a `usage` attribute (JSON or flattened
+/// `usage.*` keys), or the `usage` of a `message` payload (`message_start`).
+fn streaming_event_usage(attrs: &HashMap<String, String>) -> Option<JsonValue> {
+    if let Some(usage) = extract_json::<JsonValue>(attrs, "usage").filter(|u| u.is_object()) {
+        return Some(usage);
+    }
+    if let Some(usage) = extract_json::<JsonValue>(attrs, "message")
+        .and_then(|m| m.get("usage").cloned())
+        .filter(|u| u.is_object())
+    {
+        return Some(usage);
+    }
+    let flattened: serde_json::Map<String, JsonValue> = attrs
+        .iter()
+        .filter_map(|(k, v)| Some((k.strip_prefix("usage.")?.to_string(), json!(v))))
+        .collect();
+    (!flattened.is_empty()).then_some(JsonValue::Object(flattened))
+}
+
+/// Token count from a usage object; kvlist attributes arrive as strings.
+fn usage_count(usage: &JsonValue, key: &str) -> i64 {
+    match usage.get(key) {
+        Some(JsonValue::String(s)) => s.parse().unwrap_or(0),
+        Some(v) => v.as_i64().unwrap_or(0),
+        None => 0,
+    }
+}
+
 #[cfg(test)]
 #[path = "attributes_tests.rs"]
 mod tests;
@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use serde_json::json;
+
 use crate::data::types::{Framework, ObservationType, SpanCategory};
 
 use super::*;
@@ -193,22 +195,45 @@ fn test_crewai_framework_detection() {
 fn test_detect_observation_type_agent() {
     let attrs = make_attrs(&[("gen_ai.agent.name", "Weather Forecaster")]);
     assert_eq!(
-        detect_observation_type("agent", &attrs),
+        detect_observation_type("agent", &attrs, &[]),
         ObservationType::Agent
     );
 
     let attrs2 = make_attrs(&[("gen_ai.agent.id", "123")]);
     assert_eq!(
-        detect_observation_type("test", &attrs2),
+        detect_observation_type("test", &attrs2, &[]),
+        ObservationType::Agent
+    );
+}
+
+#[test]
+fn test_detect_observation_type_name_patterns() {
+    let patterns = vec![
+        (
+            regex::Regex::new(r"^agent\.").unwrap(),
+            "unknown".to_string(),
+        ),
+        (regex::Regex::new(r"^agent\.").unwrap(), "agent".to_string()),
+    ];
+    let attrs = make_attrs(&[("gen_ai.operation.name", "chat")]);
+
+    // A matching pattern with a known type wins over attribute heuristics
+    assert_eq!(
+        detect_observation_type("agent.plan", &attrs, &patterns),
         ObservationType::Agent
     );
+    // No match: the built-in heuristics apply
+    assert_eq!(
+        detect_observation_type("planner.agent.step", &attrs, &patterns),
+        ObservationType::Generation
+    );
 }
 
 #[test]
 fn test_detect_observation_type_embedding() {
     let attrs = make_attrs(&[("gen_ai.operation.name", "embeddings")]);
     assert_eq!(
-        detect_observation_type("test", &attrs),
+        detect_observation_type("test", &attrs, &[]),
         ObservationType::Embedding
     );
 }
@@ -217,7 +242,7 @@ fn test_detect_observation_type_embedding() {
 fn test_detect_observation_type_from_model() {
     let attrs = make_attrs(&[("gen_ai.request.model", "gpt-4")]);
     assert_eq!(
-        detect_observation_type("test", &attrs),
+        detect_observation_type("test", &attrs, &[]),
         ObservationType::Generation
     );
 }
@@ -226,7 +251,7 @@ fn test_detect_observation_type_from_model() {
 fn test_detect_observation_type_from_name() {
     let attrs = HashMap::new();
     assert_eq!(
-        detect_observation_type("my-retriever-span", &attrs),
+        detect_observation_type("my-retriever-span", &attrs, &[]),
         ObservationType::Retriever
     );
 }
@@ -235,16 +260,57 @@ fn test_detect_observation_type_from_name() {
 fn test_detect_observation_type_from_openinference() {
     let attrs = make_attrs(&[("openinference.span.kind", "AGENT")]);
     assert_eq!(
-        detect_observation_type("test", &attrs),
+        detect_observation_type("test", &attrs, &[]),
         ObservationType::Agent
     );
 }
 
+#[test]
+fn test_detect_observation_type_span_kind_overrides_heuristics() {
+    // Name, operation and model all suggest otherwise; the explicit kind wins
+    let attrs = make_attrs(&[
+        ("openinference.span.kind", "RETRIEVER"),
+        ("gen_ai.operation.name", "chat"),
+        ("gen_ai.request.model", "gpt-4o"),
+    ]);
+    assert_eq!(
+        detect_observation_type("agent_tool_call", &attrs, &[]),
+        ObservationType::Retriever
+    );
+
+    for (kind, expected) in [
+        ("llm", ObservationType::Generation),
+        ("chain", ObservationType::Chain),
+        ("tool", ObservationType::Tool),
+        ("retriever", ObservationType::Retriever),
+        ("embedding", ObservationType::Embedding),
+        ("prompt", ObservationType::Chain),
+        ("parser", ObservationType::Chain),
+    ] {
+        let attrs = make_attrs(&[("langsmith.span.kind", kind)]);
+        assert_eq!(
+            detect_observation_type("RunnableSequence", &attrs, &[]),
+            expected,
+            "langsmith.span.kind = {kind}"
+        );
+    }
+
+    // Unknown kinds fall through to the heuristics
+    let attrs = make_attrs(&[
+        ("openinference.span.kind", "UNKNOWN"),
+        ("gen_ai.operation.name", "chat"),
+    ]);
+    assert_eq!(
+        detect_observation_type("test", &attrs, &[]),
+        ObservationType::Generation
+    );
+}
+
 #[test]
 fn test_detect_observation_type_generation() {
     let attrs = make_attrs(&[("gen_ai.operation.name", "chat")]);
     assert_eq!(
-        detect_observation_type("test", &attrs),
+        detect_observation_type("test", &attrs, &[]),
         ObservationType::Generation
     );
 }
@@ -252,7 +318,7 @@ fn test_detect_observation_type_generation() {
 #[test]
 fn test_detect_observation_type_tool_from_operation() {
     let attrs = make_attrs(&[("gen_ai.operation.name", "execute_tool")]);
-    let obs = detect_observation_type("execute_tool weather_forecast", &attrs);
+    let obs = detect_observation_type("execute_tool weather_forecast", &attrs, &[]);
     assert_eq!(obs, ObservationType::Tool);
 }
 
@@ -260,7 +326,7 @@ fn test_detect_observation_type_tool_from_operation() {
 fn test_detect_observation_type_rpc_not_retriever() {
     // RPC spans should not be classified as Retriever even if name contains "retriev"
     let attrs = make_attrs(&[("rpc.system", "aws-api")]);
-    let obs = detect_observation_type("Bedrock AgentCore.RetrieveMemoryRecords", &attrs);
+    let obs = detect_observation_type("Bedrock AgentCore.RetrieveMemoryRecords", &attrs, &[]);
     assert_eq!(obs, ObservationType::Span);
 }
 
@@ -268,7 +334,7 @@ fn test_detect_observation_type_rpc_not_retriever() {
 fn test_detect_observation_type_http_not_retriever() {
     // HTTP spans should not be classified as Retriever even if name contains "retriev"
     let attrs = make_attrs(&[("http.method", "GET")]);
-    let obs = detect_observation_type("retrieve-data", &attrs);
+    let obs = detect_observation_type("retrieve-data", &attrs, &[]);
     assert_eq!(obs, ObservationType::Span);
 }
 
@@ -281,7 +347,7 @@ fn test_regression_rpc_with_genai_attrs_not_generation() {
         ("gen_ai.operation.name", "chat"),
         ("gen_ai.request.model", "anthropic.claude-3-sonnet"),
     ]);
-    let obs = detect_observation_type("Bedrock Runtime.Converse", &attrs);
+    let obs = detect_observation_type("Bedrock Runtime.Converse", &attrs, &[]);
     assert_eq!(
         obs,
         ObservationType::Span,
@@ -297,7 +363,7 @@ fn test_regression_embedding_model_with_text_completion_op() {
         ("gen_ai.operation.name", "text_completion"),
         ("gen_ai.request.model", "amazon.titan-embed-text-v2:0"),
     ]);
-    let obs = detect_observation_type("text_completion amazon.titan-embed-text-v2:0", &attrs);
+    let obs = detect_observation_type("text_completion amazon.titan-embed-text-v2:0", &attrs, &[]);
     assert_eq!(
         obs,
         ObservationType::Embedding,
@@ -305,6 +371,38 @@ fn test_regression_embedding_model_with_text_completion_op() {
     );
 }
 
+#[test]
+fn test_extract_embedding_model_name_fallback() {
+    let attrs = make_attrs(&[
+        ("openinference.span.kind", "EMBEDDING"),
+        ("embedding.model_name", "text-embedding-3-small"),
+        ("llm.token_count.prompt", "1000"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "embed");
+    assert_eq!(
+        span.gen_ai_request_model.as_deref(),
+        Some("text-embedding-3-small")
+    );
+    assert_eq!(span.gen_ai_usage_input_tokens, 1000);
+}
+
+#[test]
+fn test_extract_model_name_skips_empty_standard_key() {
+    let attrs = make_attrs(&[
+        ("gen_ai.request.model", ""),
+        ("gen_ai.response.model", ""),
+        ("reranker.model_name", "cohere/rerank-english-v3.0"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "rerank");
+    assert_eq!(
+        span.gen_ai_request_model.as_deref(),
+        Some("cohere/rerank-english-v3.0")
+    );
+    assert_eq!(span.gen_ai_response_model, None);
+}
+
 #[test]
 fn test_extract_agent_tool_from_span_name() {
     let attrs = HashMap::new();
@@ -967,6 +1065,53 @@ fn test_google_adk_tokens_from_llm_response() {
     assert_eq!(span.gen_ai_usage_total_tokens, 3880);
 }
 
+#[test]
+fn test_gemini_cached_content_tokens_are_costed_as_cache_reads() {
+    use crate::domain::pricing::{PricingService, SpanCostInput};
+
+    let attrs = make_attrs(&[
+        ("gen_ai.system", "gemini"),
+        ("gen_ai.request.model", "gemini-2.5-flash"),
+        (
+            "gcp.vertex.agent.llm_response",
+            r#"{"usage_metadata":{"prompt_token_count":10000,"candidates_token_count":100,"cached_content_token_count":8000,"total_token_count":10100}}"#,
+        ),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "call_llm");
+    assert_eq!(span.gen_ai_usage_input_tokens, 10000);
+    assert_eq!(span.gen_ai_usage_cache_read_tokens, 8000);
+
+    // REST (camelCase) encoding
+    let attrs = make_attrs(&[(
+        "response_data",
+        r#"{"usageMetadata":{"promptTokenCount":500,"cachedContentTokenCount":400}}"#,
+    )]);
+    let mut rest = SpanData::default();
+    extract_genai(&mut rest, &attrs, "generate_content");
+    assert_eq!(rest.gen_ai_usage_cache_read_tokens, 400);
+
+    let pricing = PricingService::init_for_test().unwrap();
+    let cost = pricing.calculate_cost(&SpanCostInput {
+        system: span.gen_ai_system.clone(),
+        model: span.gen_ai_request_model.clone(),
+        input_tokens: span.gen_ai_usage_input_tokens,
+        output_tokens: span.gen_ai_usage_output_tokens,
+        total_tokens: span.gen_ai_usage_total_tokens,
+        cache_read_tokens: span.gen_ai_usage_cache_read_tokens,
+        ..Default::default()
+    });
+    let (rates, _) = pricing
+        .get_model_pricing(Some("gemini"), "gemini-2.5-flash")
+        .unwrap();
+    assert!(rates.cache_read_input_token_cost > 0.0);
+    assert!(rates.cache_read_input_token_cost < rates.input_cost_per_token);
+    // prompt_token_count includes the cached tokens: only the rest is billed as input
+    assert!((cost.input_cost - 2000.0 * rates.input_cost_per_token).abs() < 1e-12);
+    assert!((cost.cache_read_cost - 8000.0 * rates.cache_read_input_token_cost).abs() < 1e-12);
+    assert!(cost.cache_savings > 0.0);
+}
+
 #[test]
 fn test_google_adk_standard_attrs_not_overwritten() {
     let attrs = make_attrs(&[
@@ -996,6 +1141,161 @@ fn test_google_adk_standard_attrs_not_overwritten() {
     assert_eq!(span.gen_ai_usage_output_tokens, 50);
 }
 
+#[test]
+fn test_web_search_calls_from_anthropic_usage() {
+    let attrs = make_attrs(&[(
+        "response_data",
+        r#"{"usage":{"input_tokens":1200,"output_tokens":300,"server_tool_use":{"web_search_requests":3}}}"#,
+    )]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat");
+    assert_eq!(span.gen_ai_usage_web_search_calls, 3);
+
+    let attrs = make_attrs(&[("gen_ai.usage.web_search_requests", "2")]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat");
+    assert_eq!(span.gen_ai_usage_web_search_calls, 2);
+}
+
+#[test]
+fn test_embedding_dimensions_and_encoding_format() {
+    let attrs = make_attrs(&[
+        ("gen_ai.operation.name", "embeddings"),
+        ("gen_ai.request.model", "text-embedding-3-large"),
+        ("gen_ai.request.encoding_formats", r#"["base64"]"#),
+        ("gen_ai.embeddings.dimension.count", "1024"),
+        ("gen_ai.usage.input_tokens", "12"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "embeddings text-embedding-3-large");
+    assert_eq!(span.gen_ai_embedding_dimensions, Some(1024));
+    assert_eq!(span.gen_ai_encoding_format.as_deref(), Some("base64"));
+    // Request parameters have their own columns, not usage details
+    assert!(span.gen_ai_usage_details.is_null());
+
+    // OpenInference invocation parameters
+    let attrs = make_attrs(&[(
+        "llm.invocation_parameters",
+        r#"{"dimensions": 256, "encoding_format": "float"}"#,
+    )]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "embed");
+    assert_eq!(span.gen_ai_embedding_dimensions, Some(256));
+    assert_eq!(span.gen_ai_encoding_format.as_deref(), Some("float"));
+
+    // Absent parameters leave the fields unset
+    let mut span = SpanData::default();
+    extract_genai(
+        &mut span,
+        &make_attrs(&[("gen_ai.usage.input_tokens", "5")]),
+        "embed",
+    );
+    assert_eq!(span.gen_ai_embedding_dimensions, None);
+    assert_eq!(span.gen_ai_encoding_format, None);
+    assert!(span.gen_ai_usage_details.is_null());
+}
+
+#[test]
+fn test_reported_cost_attributes() {
+    // Gateway-attached total cost
+    let attrs = make_attrs(&[
+        ("gen_ai.request.model", "gpt-4o"),
+        ("gen_ai.usage.input_tokens", "100"),
+        ("gen_ai.usage.cost", "0.0123"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat gpt-4o");
+    assert_eq!(span.extracted_cost_total, Some(0.0123));
+    // Not duplicated into usage details
+    assert!(span.gen_ai_usage_details.is_null());
+
+    // OpenInference totals take precedence
+    let attrs = make_attrs(&[("llm.cost.total", "0.5"), ("gen_ai.usage.cost", "0.4")]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat");
+    assert_eq!(span.extracted_cost_total, Some(0.5));
+
+    // Absent: cost is computed from usage later
+    let attrs = make_attrs(&[("gen_ai.usage.input_tokens", "100")]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat");
+    assert_eq!(span.extracted_cost_total, None);
+}
+
+#[test]
+fn test_reasoning_effort_and_verbosity() {
+    let attrs = make_attrs(&[
+        ("gen_ai.request.model", "o3"),
+        ("gen_ai.request.reasoning_effort", "high"),
+        ("gen_ai.usage.input_tokens", "10"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat o3");
+    assert_eq!(span.gen_ai_reasoning_effort.as_deref(), Some("high"));
+    assert_eq!(span.gen_ai_verbosity, None);
+    // Request controls are not token usage
+    assert!(span.gen_ai_usage_details.is_null());
+
+    // Responses API invocation parameters nest both controls
+    let attrs = make_attrs(&[(
+        "llm.invocation_parameters",
+        r#"{"reasoning": {"effort": "low"}, "text": {"verbosity": "medium"}}"#,
+    )]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "responses");
+    assert_eq!(span.gen_ai_reasoning_effort.as_deref(), Some("low"));
+    assert_eq!(span.gen_ai_verbosity.as_deref(), Some("medium"));
+
+    // Absent parameters leave the fields unset
+    let mut span = SpanData::default();
+    extract_genai(
+        &mut span,
+        &make_attrs(&[("llm.invocation_parameters", r#"{"temperature": 0.2}"#)]),
+        "chat",
+    );
+    assert_eq!(span.gen_ai_reasoning_effort, None);
+    assert_eq!(span.gen_ai_verbosity, None);
+    assert!(span.gen_ai_usage_details.is_null());
+}
+
+#[test]
+fn test_model_aliases_rewrite_for_pricing_and_keep_raw_model() {
+    let pricing = crate::domain::pricing::PricingService::init_for_test().unwrap();
+    let aliases = HashMap::from([("fast-model".to_string(), "gpt-4o-mini".to_string())]);
+    let attrs = make_attrs(&[
+        ("gen_ai.system", "openai"),
+        ("gen_ai.request.model", "fast-model"),
+        ("gen_ai.usage.input_tokens", "10"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat fast-model");
+    assert!(
+        pricing
+            .get_model_pricing(Some("openai"), "fast-model")
+            .is_none()
+    );
+
+    apply_model_aliases(&mut span, &aliases);
+    assert_eq!(span.gen_ai_request_model.as_deref(), Some("gpt-4o-mini"));
+    assert_eq!(span.gen_ai_raw_model.as_deref(), Some("fast-model"));
+    assert!(span.gen_ai_usage_details.is_null());
+    assert!(
+        pricing
+            .get_model_pricing(span.gen_ai_system.as_deref(), "gpt-4o-mini")
+            .is_some()
+    );
+
+    // Unaliased models are left alone
+    let mut span = SpanData {
+        gen_ai_request_model: Some("gpt-4o".to_string()),
+        ..Default::default()
+    };
+    apply_model_aliases(&mut span, &aliases);
+    assert_eq!(span.gen_ai_request_model.as_deref(), Some("gpt-4o"));
+    assert_eq!(span.gen_ai_raw_model, None);
+    assert!(span.gen_ai_usage_details.is_null());
+}
+
 #[test]
 fn test_crewai_tokens_from_output_value() {
     let attrs = make_attrs(&[
@@ -1200,3 +1500,120 @@ fn test_resolve_span_name_braces_in_name_no_template() {
 
     assert_eq!(span.span_name, "process {\"key\": \"value\"}");
 }
+
+#[test]
+fn test_downgrade_non_error_exception() {
+    let non_error = vec!["asyncio.CancelledError".to_string()];
+
+    let mut cancelled = SpanData {
+        status_code: Some("ERROR".to_string()),
+        status_message: Some("asyncio.CancelledError: cancelled".to_string()),
+        exception_type: Some("asyncio.CancelledError".to_string()),
+        ..Default::default()
+    };
+    downgrade_non_error_exception(&mut cancelled, &non_error);
+    assert_eq!(cancelled.status_code.as_deref(), Some("UNSET"));
+    assert_eq!(
+        cancelled.exception_type.as_deref(),
+        Some("asyncio.CancelledError")
+    );
+    assert!(cancelled.status_message.is_some());
+
+    let mut failed = SpanData {
+        status_code: Some("ERROR".to_string()),
+        exception_type: Some("ValueError".to_string()),
+        ..Default::default()
+    };
+    downgrade_non_error_exception(&mut failed, &non_error);
+    assert_eq!(failed.status_code.as_deref(), Some("ERROR"));
+}
+
+#[test]
+fn test_clamp_future_timestamps() {
+    let now = Utc::now();
+    let tolerance = TimeDelta::seconds(60);
+    let make_span = |ahead: TimeDelta| SpanData {
+        timestamp_start: now + ahead,
+        timestamp_end: Some(now + ahead + TimeDelta::milliseconds(500)),
+        ..Default::default()
+    };
+
+    // An hour ahead: clamped to ingestion time, duration kept
+    let mut far = make_span(TimeDelta::hours(1));
+    clamp_future_timestamps(&mut far, now, tolerance);
+    assert_eq!(far.timestamp_start, now);
+    assert_eq!(far.timestamp_end, Some(now + TimeDelta::milliseconds(500)));
+    assert_eq!(far.clock_shift, TimeDelta::hours(1));
+    assert_eq!(far.ingest_warnings.len(), 1);
+    assert_eq!(
+        far.ingest_warnings[0].kind,
+        IngestWarningKind::FutureTimestamp
+    );
+
+    // One second ahead: within tolerance, left alone
+    let mut near = make_span(TimeDelta::seconds(1));
+    clamp_future_timestamps(&mut near, now, tolerance);
+    assert_eq!(near.timestamp_start, now + TimeDelta::seconds(1));
+    assert_eq!(
+        near.timestamp_end,
+        Some(now + TimeDelta::seconds(1) + TimeDelta::milliseconds(500))
+    );
+    assert_eq!(near.clock_shift, TimeDelta::zero());
+    assert!(near.ingest_warnings.is_empty());
+}
+
+#[test]
+fn test_anthropic_streaming_usage_from_events() {
+    use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue, any_value};
+
+    let event = |name: &str, key: &str, value: &str| Event {
+        name: name.to_string(),
+        time_unix_nano: 1704067200000000000,
+        attributes: vec![KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        }],
+        dropped_attributes_count: 0,
+    };
+    let events = vec![
+        event(
+            "message_start",
+            "message",
+            r#"{"id":"msg_1","usage":{"input_tokens":25,"output_tokens":1,"cache_read_input_tokens":10}}"#,
+        ),
+        // message_delta output_tokens is cumulative, not incremental
+        event("message_delta", "usage", r#"{"output_tokens":15}"#),
+        event("message_delta", "usage", r#"{"output_tokens":42}"#),
+    ];
+
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &HashMap::new(), "anthropic.messages.stream");
+    extract_streaming_usage(&mut span, &events);
+
+    assert_eq!(span.gen_ai_usage_input_tokens, 25);
+    assert_eq!(span.gen_ai_usage_output_tokens, 42);
+    assert_eq!(span.gen_ai_usage_total_tokens, 67);
+    assert_eq!(span.gen_ai_usage_cache_read_tokens, 10);
+
+    // A second streamed message in the same span adds to the totals
+    let mut two = events.clone();
+    two.push(event("message_start", "usage.input_tokens", "30"));
+    two.push(event("message_delta", "usage.output_tokens", "5"));
+    let mut span = SpanData::default();
+    extract_streaming_usage(&mut span, &two);
+    assert_eq!(span.gen_ai_usage_input_tokens, 55);
+    assert_eq!(span.gen_ai_usage_output_tokens, 47);
+
+    // Attribute usage takes precedence over events
+    let attrs = make_attrs(&[
+        ("gen_ai.usage.input_tokens", "100"),
+        ("gen_ai.usage.output_tokens", "50"),
+    ]);
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "chat");
+    extract_streaming_usage(&mut span, &events);
+    assert_eq!(span.gen_ai_usage_input_tokens, 100);
+    assert_eq!(span.gen_ai_usage_output_tokens, 50);
+}
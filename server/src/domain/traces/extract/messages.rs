@@ -6,18 +6,21 @@
 
 use std::collections::{BTreeSet, HashMap};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use opentelemetry_proto::tonic::trace::v1::Span;
 use opentelemetry_proto::tonic::trace::v1::span::Event;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue, json};
 
+use crate::core::constants::REDACTED_TOOL_OUTPUT;
+use crate::data::types::{IngestWarning, IngestWarningKind};
 use crate::domain::sideml::is_plain_data_value;
 use crate::domain::sideml::tools::tool_definition_quality;
 use crate::utils::otlp::extract_attributes;
 use crate::utils::time::nanos_to_datetime;
 
-use super::{extract_json, keys};
+use super::attributes::SpanData;
+use super::{extract_json, is_openai_responses, keys};
 
 // ============================================================================
 // JSON PARSING HELPERS
@@ -355,6 +358,10 @@ const EXTRACTORS: &[NamedExtractor] = &[
         name: "logfire_events",
         extractor: try_logfire_events,
     },
+    NamedExtractor {
+        name: "openai_responses",
+        extractor: try_openai_responses,
+    },
     NamedExtractor {
         name: "vercel_ai",
         extractor: try_vercel_ai,
@@ -447,14 +454,26 @@ pub(crate) fn extract_tool_definitions(
         }
     }
 
-    // gen_ai.agent.tools - list of tool names (separate from full definitions)
+    // gen_ai.agent.tools - tools available to the agent, as names or definition objects.
+    // Objects become definitions (their names still join the name list), so the
+    // tool catalog is complete even when no tool was called.
     if let Some(tools_json) = attrs.get(keys::GEN_AI_AGENT_TOOLS) {
         if let Ok(content) = serde_json::from_str::<JsonValue>(tools_json) {
-            tool_names.push(RawToolNames::from_attr(
-                keys::GEN_AI_AGENT_TOOLS,
-                timestamp,
-                content,
-            ));
+            let (definitions, names) = split_agent_tools(content);
+            if !definitions.is_empty() {
+                tool_definitions.push(RawToolDefinition::from_attr(
+                    keys::GEN_AI_AGENT_TOOLS,
+                    timestamp,
+                    JsonValue::Array(definitions),
+                ));
+            }
+            if !names.is_empty() {
+                tool_names.push(RawToolNames::from_attr(
+                    keys::GEN_AI_AGENT_TOOLS,
+                    timestamp,
+                    JsonValue::Array(names),
+                ));
+            }
         }
     }
 
@@ -906,6 +925,15 @@ pub(crate) fn try_openinference(
         found = true;
     }
 
+    // embedding.embeddings.N.embedding.text - Batch embedding inputs (vectors skipped)
+    let embedding_indices = extract_indices(attrs, keys::EMBEDDING_EMBEDDINGS);
+    if !embedding_indices.is_empty()
+        && let Some(msg) = extract_openinference_embeddings(attrs, &embedding_indices, timestamp)
+    {
+        messages.push(msg);
+        found = true;
+    }
+
     if found {
         enrich_oi_multimodal_from_input_value(messages, attrs);
     }
@@ -1229,6 +1257,190 @@ fn extract_logfire_event_array(
     found
 }
 
+/// OpenAI Responses API: `request_data` with `input`/`instructions`,
+/// `response_data` with `output` items (bare or wrapped in `response`).
+///
+/// Unlike Chat Completions, conversation state is a flat list of typed items
+/// (`message`, `reasoning`, `function_call`, `function_call_output`), so items
+/// are regrouped into chat-style messages here: consecutive assistant items
+/// form one message carrying text, thinking and `tool_calls`. Request `tools`
+/// are picked up by `extract_tool_definitions`.
+pub(crate) fn try_openai_responses(
+    messages: &mut Vec<RawMessage>,
+    _tool_definitions: &mut Vec<RawToolDefinition>,
+    attrs: &HashMap<String, String>,
+    _: &str,
+    timestamp: DateTime<Utc>,
+) -> bool {
+    let raw_response = extract_json::<JsonValue>(attrs, keys::RESPONSE_DATA);
+    let request = extract_json::<JsonValue>(attrs, keys::REQUEST_DATA)
+        .filter(|r| is_openai_responses(r, raw_response.as_ref()));
+    let response = raw_response
+        .map(|r| match r.get("response") {
+            Some(inner) if inner.get("output").is_some() => inner.clone(),
+            _ => r,
+        })
+        .filter(|r| r.get("output").is_some_and(|o| o.is_array()));
+    if request.is_none() && response.is_none() {
+        return false;
+    }
+
+    let mut found = false;
+    if let Some(request) = &request {
+        if let Some(instructions) = request
+            .get("instructions")
+            .and_then(|i| i.as_str())
+            .filter(|s| !s.is_empty())
+        {
+            messages.push(RawMessage::from_attr(
+                keys::REQUEST_DATA,
+                timestamp,
+                json!({"role": "system", "content": instructions}),
+            ));
+            found = true;
+        }
+        let input = match request.get("input") {
+            Some(JsonValue::String(text)) => vec![json!({"role": "user", "content": text})],
+            Some(JsonValue::Array(items)) => normalize_responses_items(items),
+            _ => Vec::new(),
+        };
+        for msg in input {
+            messages.push(RawMessage::from_attr(keys::REQUEST_DATA, timestamp, msg));
+            found = true;
+        }
+    }
+
+    if let Some(response) = &response {
+        let items = response
+            .get("output")
+            .and_then(|o| o.as_array())
+            .map(Vec::as_slice);
+        let mut output = items.map(normalize_responses_items).unwrap_or_default();
+        if let Some(last) = output
+            .iter_mut()
+            .rev()
+            .find(|m| m.get("role").and_then(|r| r.as_str()) == Some("assistant"))
+        {
+            last["finish_reason"] = json!(responses_finish_reason(response, items));
+        }
+        for msg in output {
+            messages.push(RawMessage::from_attr(keys::RESPONSE_DATA, timestamp, msg));
+            found = true;
+        }
+    }
+
+    found
+}
+
+/// Regroup Responses API items into chat-style messages.
+fn normalize_responses_items(items: &[JsonValue]) -> Vec<JsonValue> {
+    let mut messages: Vec<JsonValue> = Vec::new();
+    // Content parts and tool calls of the assistant turn being assembled
+    let mut parts: Vec<JsonValue> = Vec::new();
+    let mut tool_calls: Vec<JsonValue> = Vec::new();
+
+    for item in items {
+        // Input items may omit `type` for plain `{role, content}` messages
+        let item_type = item
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("message");
+        match item_type {
+            "message" => {
+                let Some(role) = item.get("role").and_then(|r| r.as_str()) else {
+                    continue;
+                };
+                match (role, item.get("content")) {
+                    ("assistant", Some(JsonValue::Array(content))) => {
+                        parts.extend(content.iter().cloned());
+                    }
+                    ("assistant", Some(JsonValue::String(text))) => {
+                        parts.push(json!({"type": "text", "text": text}));
+                    }
+                    ("assistant", _) => {}
+                    (_, content) => {
+                        flush_responses_assistant(&mut messages, &mut parts, &mut tool_calls);
+                        messages.push(json!({"role": role, "content": content}));
+                    }
+                }
+            }
+            "reasoning" => {
+                // Only the summary is readable; encrypted_content is opaque
+                let summary: Vec<&str> = item
+                    .get("summary")
+                    .and_then(|s| s.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                    .collect();
+                if !summary.is_empty() {
+                    parts.push(json!({"type": "thinking", "text": summary.join("\n\n")}));
+                }
+            }
+            "function_call" => {
+                let Some(name) = item.get("name").and_then(|n| n.as_str()) else {
+                    continue;
+                };
+                tool_calls.push(json!({
+                    "id": item.get("call_id").or_else(|| item.get("id")),
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "arguments": item.get("arguments").cloned().unwrap_or(json!("{}"))
+                    }
+                }));
+            }
+            "function_call_output" => {
+                flush_responses_assistant(&mut messages, &mut parts, &mut tool_calls);
+                messages.push(json!({
+                    "role": "tool",
+                    "tool_call_id": item.get("call_id"),
+                    "content": item.get("output"),
+                }));
+            }
+            other => {
+                tracing::trace!(item_type = other, "Skipping unsupported Responses API item");
+            }
+        }
+    }
+    flush_responses_assistant(&mut messages, &mut parts, &mut tool_calls);
+    messages
+}
+
+/// Emit the pending assistant turn, if any, as one chat-style message.
+fn flush_responses_assistant(
+    messages: &mut Vec<JsonValue>,
+    parts: &mut Vec<JsonValue>,
+    tool_calls: &mut Vec<JsonValue>,
+) {
+    if parts.is_empty() && tool_calls.is_empty() {
+        return;
+    }
+    let mut message = json!({"role": "assistant", "content": std::mem::take(parts)});
+    if !tool_calls.is_empty() {
+        message["tool_calls"] = JsonValue::Array(std::mem::take(tool_calls));
+    }
+    messages.push(message);
+}
+
+/// Chat-style finish reason for a Responses API response.
+///
+/// The Responses API reports `status` instead of a finish reason: function
+/// calls end the turn for tool use, and `incomplete` means a token limit hit.
+fn responses_finish_reason(response: &JsonValue, items: Option<&[JsonValue]>) -> &'static str {
+    let calls_tool = items
+        .into_iter()
+        .flatten()
+        .any(|item| item.get("type").and_then(|t| t.as_str()) == Some("function_call"));
+    if calls_tool {
+        "tool_calls"
+    } else if response.get("status").and_then(|s| s.as_str()) == Some("incomplete") {
+        "length"
+    } else {
+        "stop"
+    }
+}
+
 pub(crate) fn try_vercel_ai(
     messages: &mut Vec<RawMessage>,
     _tool_definitions: &mut Vec<RawToolDefinition>,
@@ -1834,6 +2046,11 @@ pub(crate) fn try_mlflow(
 }
 
 /// TraceLoop message extraction
+///
+/// `traceloop.entity.input`/`output` carry the serialized arguments and return
+/// value of a workflow/task/agent. Inputs containing a chat messages array
+/// become individual messages; any other value is kept whole as a single user
+/// (input) or assistant (output) message.
 pub(crate) fn try_traceloop(
     messages: &mut Vec<RawMessage>,
     _tool_definitions: &mut Vec<RawToolDefinition>,
@@ -1843,22 +2060,33 @@ pub(crate) fn try_traceloop(
 ) -> bool {
     let mut found = false;
 
-    // traceloop.entity.input - JSON string
-    if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::TRACELOOP_ENTITY_INPUT) {
-        messages.push(RawMessage::from_attr(
-            keys::TRACELOOP_ENTITY_INPUT,
-            timestamp,
-            parsed,
-        ));
+    if let Some(raw) = attrs.get(keys::TRACELOOP_ENTITY_INPUT) {
+        let parsed = parse_json_with_fallback(raw, keys::TRACELOOP_ENTITY_INPUT);
+        match find_traceloop_messages(&parsed) {
+            Some(msgs) => {
+                for msg in msgs {
+                    messages.push(RawMessage::from_attr(
+                        keys::TRACELOOP_ENTITY_INPUT,
+                        timestamp,
+                        msg.clone(),
+                    ));
+                }
+            }
+            None => messages.push(RawMessage::from_attr(
+                keys::TRACELOOP_ENTITY_INPUT,
+                timestamp,
+                wrap_traceloop_value(parsed, "user"),
+            )),
+        }
         found = true;
     }
 
-    // traceloop.entity.output - JSON string
-    if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::TRACELOOP_ENTITY_OUTPUT) {
+    if let Some(raw) = attrs.get(keys::TRACELOOP_ENTITY_OUTPUT) {
+        let parsed = parse_json_with_fallback(raw, keys::TRACELOOP_ENTITY_OUTPUT);
         messages.push(RawMessage::from_attr(
             keys::TRACELOOP_ENTITY_OUTPUT,
             timestamp,
-            parsed,
+            wrap_traceloop_value(parsed, "assistant"),
         ));
         found = true;
     }
@@ -1866,6 +2094,32 @@ pub(crate) fn try_traceloop(
     found
 }
 
+/// Locate a chat messages array in a TraceLoop entity input.
+///
+/// Checks a top-level array and the `messages` field at the top level, under
+/// `inputs` (LangChain chains) and under `kwargs` (Python decorator args).
+fn find_traceloop_messages(value: &JsonValue) -> Option<&Vec<JsonValue>> {
+    let candidates = [
+        value.as_array(),
+        value.get("messages").and_then(|m| m.as_array()),
+        value.pointer("/inputs/messages").and_then(|m| m.as_array()),
+        value.pointer("/kwargs/messages").and_then(|m| m.as_array()),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|msgs| !msgs.is_empty() && msgs.iter().all(is_chat_message))
+}
+
+/// Wrap a TraceLoop entity value that is not already a chat message.
+fn wrap_traceloop_value(value: JsonValue, role: &str) -> JsonValue {
+    if is_chat_message(&value) {
+        value
+    } else {
+        json!({"role": role, "content": value})
+    }
+}
+
 /// Pydantic AI (via Logfire) message extraction
 pub(crate) fn try_pydantic_ai(
     messages: &mut Vec<RawMessage>,
@@ -2372,7 +2626,7 @@ pub(crate) fn try_autogen(
 
                         // Extract response as assistant message
                         if let Some(response) = parsed.get("response") {
-                            if let Some(normalized) = normalize_autogen_response(response) {
+                            for normalized in normalize_autogen_response(response) {
                                 messages.push(RawMessage::from_attr(key, timestamp, normalized));
                                 found = true;
                             }
@@ -2416,6 +2670,57 @@ pub(crate) fn try_autogen(
         }
     }
 
+    // Multi-agent run results in output.value, one message per agent turn
+    if !found {
+        found = extract_autogen_conversation(messages, attrs, timestamp);
+    }
+
+    found
+}
+
+/// Extract agent turns from an AutoGen run result in `output.value`.
+///
+/// Handles AgentChat `TaskResult` (`{"messages": [typed messages], "stop_reason"}`)
+/// and v0.2 `ChatResult` (`{"chat_history": [{role, name, content}]}`). Each
+/// message keeps its agent as `name` so the feed shows turns per agent.
+fn extract_autogen_conversation(
+    messages: &mut Vec<RawMessage>,
+    attrs: &HashMap<String, String>,
+    timestamp: DateTime<Utc>,
+) -> bool {
+    let Some(parsed) = attrs
+        .get(keys::OUTPUT_VALUE)
+        .and_then(|v| serde_json::from_str::<JsonValue>(v).ok())
+    else {
+        return false;
+    };
+
+    let mut found = false;
+    if let Some(msgs) = parsed.get("messages").and_then(|m| m.as_array()) {
+        // Typed messages with a `source` agent; plain chat arrays are left to raw_io
+        let is_task_result = parsed.get("stop_reason").is_some()
+            || msgs
+                .iter()
+                .any(|m| m.get("type").is_some() && m.get("source").is_some());
+        if !is_task_result {
+            return false;
+        }
+        for msg in msgs {
+            for n in normalize_autogen_message(msg) {
+                messages.push(RawMessage::from_attr(keys::OUTPUT_VALUE, timestamp, n));
+                found = true;
+            }
+        }
+    } else if let Some(history) = parsed.get("chat_history").and_then(|h| h.as_array()) {
+        for msg in history.iter().filter(|m| is_chat_message(m)) {
+            messages.push(RawMessage::from_attr(
+                keys::OUTPUT_VALUE,
+                timestamp,
+                msg.clone(),
+            ));
+            found = true;
+        }
+    }
     found
 }
 
@@ -2731,8 +3036,34 @@ fn normalize_autogen_message(msg: &JsonValue) -> Vec<JsonValue> {
     }
 }
 
-/// Normalize AutoGen LLM response to assistant message
-fn normalize_autogen_response(response: &JsonValue) -> Option<JsonValue> {
+/// Normalize AutoGen LLM response to assistant messages
+///
+/// Multi-choice responses (`n > 1`) yield one assistant message per choice,
+/// each with its own finish reason; a single choice yields one message.
+fn normalize_autogen_response(response: &JsonValue) -> Vec<JsonValue> {
+    if let Some(choices) = response
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .filter(|c| c.len() > 1)
+    {
+        return choices
+            .iter()
+            .filter_map(|choice| {
+                let mut msg = choice_to_message(choice)?;
+                if let Some(index) = choice.get("index").filter(|i| i.is_u64()) {
+                    msg["index"] = index.clone();
+                }
+                Some(msg)
+            })
+            .collect();
+    }
+    normalize_autogen_single_response(response)
+        .into_iter()
+        .collect()
+}
+
+/// Normalize a single-choice AutoGen LLM response to an assistant message
+fn normalize_autogen_single_response(response: &JsonValue) -> Option<JsonValue> {
     // Response may have content directly or in choices
     let content = response
         .get("content")
@@ -3365,6 +3696,37 @@ fn finalize_crewai_tools(
     if tools.is_empty() { None } else { Some(tools) }
 }
 
+/// Split a `gen_ai.agent.tools` value into (definitions, names).
+///
+/// Strings are tool names; objects are definitions whose `name` (or
+/// `function.name`) is also added to the names.
+fn split_agent_tools(content: JsonValue) -> (Vec<JsonValue>, Vec<JsonValue>) {
+    let items = match content {
+        JsonValue::Array(items) => items,
+        other => vec![other],
+    };
+
+    let mut definitions = Vec::new();
+    let mut names = Vec::new();
+    for item in items {
+        match item {
+            JsonValue::String(_) => names.push(item),
+            JsonValue::Object(ref obj) => {
+                let name = obj
+                    .get("name")
+                    .or_else(|| obj.get("function").and_then(|f| f.get("name")))
+                    .and_then(|n| n.as_str());
+                if let Some(name) = name {
+                    names.push(json!(name));
+                }
+                definitions.push(item);
+            }
+            _ => {}
+        }
+    }
+    (definitions, names)
+}
+
 /// Check if a JSON object looks like a chat message (has role + content or tool_calls).
 fn is_chat_message(msg: &JsonValue) -> bool {
     msg.get("role").is_some()
@@ -3419,18 +3781,12 @@ pub(crate) fn try_crewai(
                 }
             }
 
-            // Also check tasks_output for messages
+            // Also check tasks_output (one entry per task, each run by one agent)
             if let Some(tasks) = parsed.get("tasks_output").and_then(|t| t.as_array()) {
                 for task in tasks {
-                    if let Some(msgs) = task.get("messages").and_then(|m| m.as_array()) {
-                        for msg in msgs.iter().filter(|m| is_chat_message(m)) {
-                            messages.push(RawMessage::from_attr(
-                                keys::OUTPUT_VALUE,
-                                timestamp,
-                                msg.clone(),
-                            ));
-                            extracted_messages = true;
-                        }
+                    for msg in crewai_task_messages(task) {
+                        messages.push(RawMessage::from_attr(keys::OUTPUT_VALUE, timestamp, msg));
+                        extracted_messages = true;
                     }
                 }
             }
@@ -3446,6 +3802,48 @@ pub(crate) fn try_crewai(
     found
 }
 
+/// Messages for one CrewAI `TaskOutput`, attributed to the agent that ran the task.
+///
+/// Uses the task's own `messages` when present (assistant messages without a
+/// `name` get the agent's), otherwise its `raw` result as one assistant turn.
+fn crewai_task_messages(task: &JsonValue) -> Vec<JsonValue> {
+    let agent = task
+        .get("agent")
+        .and_then(|a| a.as_str())
+        .filter(|a| !a.is_empty());
+
+    if let Some(msgs) = task.get("messages").and_then(|m| m.as_array()) {
+        let chat: Vec<JsonValue> = msgs
+            .iter()
+            .filter(|m| is_chat_message(m))
+            .map(|m| {
+                let mut msg = m.clone();
+                if let Some(agent) = agent
+                    && msg.get("role").and_then(|r| r.as_str()) == Some("assistant")
+                    && msg.get("name").is_none()
+                {
+                    msg["name"] = json!(agent);
+                }
+                msg
+            })
+            .collect();
+        if !chat.is_empty() {
+            return chat;
+        }
+    }
+
+    match task.get("raw").and_then(|r| r.as_str()) {
+        Some(raw) if !raw.trim().is_empty() => {
+            let mut msg = json!({"role": "assistant", "content": raw});
+            if let Some(agent) = agent {
+                msg["name"] = json!(agent);
+            }
+            vec![msg]
+        }
+        _ => Vec::new(),
+    }
+}
+
 /// Wrap a plain data object (structured output) with message structure.
 /// If the value is a plain data object without message-structure keys,
 /// wraps it as `{"role": <role>, "content": <value>}` so normalize() can process it.
@@ -3467,39 +3865,102 @@ pub(crate) fn try_raw_io(
 ) -> bool {
     // Note: system_prompt is extracted in extract_messages_for_span (mod.rs)
 
-    // input.value - preserve raw JSON, wrap plain data as user message
-    if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::INPUT_VALUE) {
-        let wrapped = wrap_plain_data(parsed, "user");
-        messages.push(RawMessage::from_attr(keys::INPUT_VALUE, timestamp, wrapped));
+    // raw_input / response are Logfire fallbacks, used only without the OI keys
+    let input_key = if attrs.contains_key(keys::INPUT_VALUE) {
+        keys::INPUT_VALUE
+    } else {
+        keys::RAW_INPUT
+    };
+    if let Some(value) = attrs.get(input_key) {
+        messages.extend(sniff_raw_io(input_key, value, "user", timestamp));
     }
 
-    // raw_input (Logfire fallback) - preserve raw array
-    if attrs.get(keys::INPUT_VALUE).is_none() {
-        if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::RAW_INPUT) {
-            let wrapped = wrap_plain_data(parsed, "user");
-            messages.push(RawMessage::from_attr(keys::RAW_INPUT, timestamp, wrapped));
-        }
+    let output_key = if attrs.contains_key(keys::OUTPUT_VALUE) {
+        keys::OUTPUT_VALUE
+    } else {
+        keys::RESPONSE
+    };
+    if let Some(value) = attrs.get(output_key) {
+        messages.extend(sniff_raw_io(output_key, value, "assistant", timestamp));
     }
 
-    // output.value - preserve raw JSON, wrap plain data as assistant message
-    if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::OUTPUT_VALUE) {
-        let wrapped = wrap_plain_data(parsed, "assistant");
-        messages.push(RawMessage::from_attr(
-            keys::OUTPUT_VALUE,
+    !messages.is_empty()
+}
+
+/// Detect the shape of a generic I/O value and convert it to raw messages.
+///
+/// - Array of chat messages: one message per element
+/// - Object with `choices` (OpenAI completion response): assistant message per choice
+/// - Plain text (or a JSON string): single message with `role` and text content
+/// - Anything else: raw JSON, plain data wrapped with `role`
+fn sniff_raw_io(key: &str, value: &str, role: &str, timestamp: DateTime<Utc>) -> Vec<RawMessage> {
+    let parsed = match serde_json::from_str::<JsonValue>(value) {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            let text = value.trim();
+            if text.is_empty() {
+                return Vec::new();
+            }
+            return vec![RawMessage::from_attr(
+                key,
+                timestamp,
+                json!({"role": role, "content": text}),
+            )];
+        }
+    };
+
+    if let Some(text) = parsed.as_str() {
+        if text.trim().is_empty() {
+            return Vec::new();
+        }
+        return vec![RawMessage::from_attr(
+            key,
             timestamp,
-            wrapped,
-        ));
+            json!({"role": role, "content": text}),
+        )];
     }
 
-    // response (Logfire fallback) - preserve raw JSON
-    if attrs.get(keys::OUTPUT_VALUE).is_none() {
-        if let Some(parsed) = extract_json::<JsonValue>(attrs, keys::RESPONSE) {
-            let wrapped = wrap_plain_data(parsed, "assistant");
-            messages.push(RawMessage::from_attr(keys::RESPONSE, timestamp, wrapped));
+    if let Some(items) = parsed.as_array()
+        && !items.is_empty()
+        && items.iter().all(is_chat_message)
+    {
+        return items
+            .iter()
+            .map(|item| RawMessage::from_attr(key, timestamp, item.clone()))
+            .collect();
+    }
+
+    if let Some(choices) = parsed.get("choices").and_then(|c| c.as_array()) {
+        let choices: Vec<RawMessage> = choices
+            .iter()
+            .filter_map(choice_to_message)
+            .map(|msg| RawMessage::from_attr(key, timestamp, msg))
+            .collect();
+        if !choices.is_empty() {
+            return choices;
         }
     }
 
-    !messages.is_empty()
+    vec![RawMessage::from_attr(
+        key,
+        timestamp,
+        wrap_plain_data(parsed, role),
+    )]
+}
+
+/// Assistant message from a completion choice (`message` for chat, `text` for legacy).
+fn choice_to_message(choice: &JsonValue) -> Option<JsonValue> {
+    let mut msg = if let Some(message) = choice.get("message").filter(|m| m.is_object()) {
+        message.clone()
+    } else {
+        json!({"content": choice.get("text")?.as_str()?})
+    };
+    let obj = msg.as_object_mut()?;
+    obj.entry("role").or_insert_with(|| json!("assistant"));
+    if let Some(reason) = choice.get("finish_reason").filter(|r| !r.is_null()) {
+        obj.entry("finish_reason").or_insert_with(|| reason.clone());
+    }
+    Some(msg)
 }
 
 // ============================================================================
@@ -3639,6 +4100,66 @@ fn extract_openinference_message(
     ))
 }
 
+/// Maximum number of embedded texts kept from `embedding.embeddings.*`
+const MAX_EMBEDDING_TEXTS: usize = 20;
+
+/// Extract OpenInference batch embedding inputs as a single context message.
+///
+/// Only `embedding.embeddings.N.embedding.text` is read; the vectors are large
+/// and not useful to display. Texts beyond `MAX_EMBEDDING_TEXTS` are dropped and
+/// the total count is recorded so the UI can show the batch was truncated.
+fn extract_openinference_embeddings(
+    attrs: &HashMap<String, String>,
+    indices: &BTreeSet<usize>,
+    timestamp: DateTime<Utc>,
+) -> Option<RawMessage> {
+    let texts: Vec<&String> = indices
+        .iter()
+        .filter_map(|idx| {
+            attrs.get(&format!(
+                "{}.{}.embedding.text",
+                keys::EMBEDDING_EMBEDDINGS,
+                idx
+            ))
+        })
+        .collect();
+
+    if texts.is_empty() {
+        return None;
+    }
+
+    let total = texts.len();
+    let mut data = serde_json::Map::new();
+    data.insert(
+        "texts".to_string(),
+        json!(
+            texts
+                .into_iter()
+                .take(MAX_EMBEDDING_TEXTS)
+                .collect::<Vec<_>>()
+        ),
+    );
+    if let Some(model) = attrs.get(keys::EMBEDDING_MODEL_NAME) {
+        data.insert("model".to_string(), json!(model));
+    }
+    if total > MAX_EMBEDDING_TEXTS {
+        data.insert("total".to_string(), json!(total));
+        data.insert("truncated".to_string(), json!(true));
+    }
+
+    let mut msg = serde_json::Map::new();
+    msg.insert("role".to_string(), json!("context"));
+    msg.insert("type".to_string(), json!("embeddings"));
+    msg.insert("content".to_string(), JsonValue::Object(data));
+    msg.insert("_source".to_string(), json!(keys::EMBEDDING_EMBEDDINGS));
+
+    Some(RawMessage::from_attr(
+        keys::EMBEDDING_EMBEDDINGS,
+        timestamp,
+        JsonValue::Object(msg),
+    ))
+}
+
 /// Extract OpenInference documents (retrieval.documents.N.* or reranker.*.documents.N.*)
 fn extract_openinference_documents(
     attrs: &HashMap<String, String>,
@@ -3745,6 +4266,7 @@ pub(super) fn extract_messages_for_span(
     }
 
     extract_messages_from_events(&mut raw_messages, &otlp_span.events, is_tool_span);
+    merge_tool_call_deltas(&mut raw_messages);
 
     // Enrich tool span messages with metadata from span attributes
     // Check event name (not role) since role is now derived at query-time
@@ -3832,11 +4354,15 @@ pub(super) fn extract_messages_for_span(
         }
 
         if let Some(tool_result) = span_attrs.get(keys::AI_TOOLCALL_RESULT) {
+            let tool_name = span_attrs.get(keys::AI_TOOLCALL_NAME).map(|s| s.as_str());
             let tool_id = span_attrs.get(keys::AI_TOOLCALL_ID).map(|s| s.as_str());
             let result_val =
                 serde_json::from_str::<JsonValue>(tool_result).unwrap_or(json!(tool_result));
             let mut msg = serde_json::Map::new();
             msg.insert("role".to_string(), json!("tool"));
+            if let Some(name) = tool_name {
+                msg.insert("name".to_string(), json!(name));
+            }
             if let Some(id) = tool_id {
                 msg.insert("tool_call_id".to_string(), json!(id));
             }
@@ -3890,6 +4416,8 @@ pub(super) fn extract_messages_for_span(
         );
     }
 
+    mark_structured_output(&mut raw_messages, span_attrs);
+
     // Debug: Log AutoGen extraction results
     if otlp_span.name.starts_with("autogen") && span_attrs.contains_key("message") {
         tracing::trace!(
@@ -3906,6 +4434,672 @@ pub(super) fn extract_messages_for_span(
     (raw_messages, tool_definitions, tool_names)
 }
 
+/// Reassemble tool calls whose arguments were streamed across several events.
+///
+/// Streaming SDKs emit the first delta with the call id, name and an empty or
+/// partial `arguments` string, then further deltas carrying only the argument
+/// fragment (matched by id, or by `index` when the id is omitted). Fragments are
+/// appended to the first occurrence until its arguments form valid JSON; the
+/// emptied delta messages are dropped. The joined arguments are stored as
+/// parsed JSON, or kept as the raw string if they never form valid JSON.
+///
+/// Indexes are scoped to one assistant turn: a message without tool calls (a
+/// tool result, the next user turn) forgets them, and a call with a new id
+/// takes over its index.
+fn merge_tool_call_deltas(messages: &mut Vec<RawMessage>) {
+    // call id -> (message index, tool call index, accumulated arguments)
+    let mut open: HashMap<String, (usize, usize, String)> = HashMap::new();
+    let mut complete = Vec::new();
+    let mut id_by_index: HashMap<u64, String> = HashMap::new();
+    let mut drained = Vec::new();
+
+    for (msg_idx, msg) in messages.iter_mut().enumerate() {
+        let Some(calls) = tool_calls_mut(&mut msg.content) else {
+            id_by_index.clear();
+            continue;
+        };
+        let mut merged = Vec::new();
+        for (call_idx, call) in calls.iter().enumerate() {
+            let Some(fragment) = tool_call_arguments(call).and_then(|a| a.as_str()) else {
+                continue;
+            };
+            let index = call.get("index").and_then(|i| i.as_u64());
+            let id = call
+                .get("id")
+                .and_then(|i| i.as_str())
+                .map(String::from)
+                .or_else(|| index.and_then(|i| id_by_index.get(&i).cloned()));
+            let Some(id) = id else {
+                continue;
+            };
+            if let Some((_, _, buffer)) = open.get_mut(&id) {
+                buffer.push_str(fragment);
+                merged.push(call_idx);
+                if serde_json::from_str::<JsonValue>(buffer).is_ok() {
+                    // Arguments are complete: later fragments belong to other calls
+                    complete.extend(open.remove(&id));
+                    id_by_index.retain(|_, owner| *owner != id);
+                }
+                continue;
+            }
+            if serde_json::from_str::<JsonValue>(fragment).is_err() {
+                // Incomplete arguments: the start of a streamed call
+                if let Some(index) = index {
+                    id_by_index.insert(index, id.clone());
+                }
+                open.insert(id, (msg_idx, call_idx, fragment.to_string()));
+            } else if let Some(index) = index {
+                // A complete call takes its index over from any earlier call
+                id_by_index.remove(&index);
+            }
+        }
+        if merged.is_empty() {
+            continue;
+        }
+        for call_idx in merged.into_iter().rev() {
+            calls.remove(call_idx);
+        }
+        if calls.is_empty() && !has_message_content(&msg.content) {
+            drained.push(msg_idx);
+        }
+    }
+
+    for (msg_idx, call_idx, buffer) in complete.into_iter().chain(open.into_values()) {
+        let arguments = serde_json::from_str::<JsonValue>(&buffer).unwrap_or(json!(buffer));
+        if let Some(call) =
+            tool_calls_mut(&mut messages[msg_idx].content).and_then(|calls| calls.get_mut(call_idx))
+            && let Some(slot) = tool_call_arguments_mut(call)
+        {
+            *slot = arguments;
+        }
+    }
+    for msg_idx in drained.into_iter().rev() {
+        messages.remove(msg_idx);
+    }
+}
+
+/// `tool_calls` of a message, also under the `message` wrapper of choice events.
+fn tool_calls_mut(content: &mut JsonValue) -> Option<&mut Vec<JsonValue>> {
+    let target = if content.get("tool_calls").is_some() {
+        content
+    } else {
+        content.get_mut("message")?
+    };
+    target.get_mut("tool_calls")?.as_array_mut()
+}
+
+fn tool_call_arguments(call: &JsonValue) -> Option<&JsonValue> {
+    call.get("function")
+        .and_then(|f| f.get("arguments"))
+        .or_else(|| call.get("arguments"))
+}
+
+fn tool_call_arguments_mut(call: &mut JsonValue) -> Option<&mut JsonValue> {
+    if call
+        .get("function")
+        .is_some_and(|f| f.get("arguments").is_some())
+    {
+        call.get_mut("function")?.get_mut("arguments")
+    } else {
+        call.get_mut("arguments")
+    }
+}
+
+/// Whether a delta message carries anything besides its (now merged) tool calls.
+fn has_message_content(content: &JsonValue) -> bool {
+    let target = content
+        .get("message")
+        .filter(|m| m.is_object())
+        .unwrap_or(content);
+    target.get("content").is_some_and(|c| match c {
+        JsonValue::Null => false,
+        JsonValue::String(s) => !s.is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        _ => true,
+    })
+}
+
+/// Turn assistant text that is really structured output into a `json` content block.
+///
+/// With `gen_ai.output.type = "json"` any text that parses as a JSON object or
+/// array is converted. Otherwise only text that is a complete JSON object is, so
+/// prose, numbers and bare lists stay text. Structured output already carried
+/// as an object (e.g. `ai.result.object`) needs no conversion.
+fn mark_structured_output(messages: &mut [RawMessage], attrs: &HashMap<String, String>) {
+    let declared_json = attrs
+        .get(keys::GEN_AI_OUTPUT_TYPE)
+        .is_some_and(|t| t.eq_ignore_ascii_case("json"));
+
+    for msg in messages.iter_mut() {
+        let is_choice = matches!(
+            &msg.source,
+            MessageSource::Event { name, .. } if name == keys::EVENT_CHOICE
+        );
+        // Attribute arrays (e.g. gen_ai.output.messages) are expanded at query time
+        match &mut msg.content {
+            JsonValue::Array(items) => {
+                for item in items {
+                    mark_structured_message(item, false, declared_json);
+                }
+            }
+            content => mark_structured_message(content, is_choice, declared_json),
+        }
+    }
+}
+
+fn mark_structured_message(message: &mut JsonValue, is_choice: bool, declared_json: bool) {
+    // gen_ai.choice events nest the reply under "message"
+    let target = if message.get("message").is_some_and(|m| m.is_object()) {
+        &mut message["message"]
+    } else {
+        message
+    };
+    let role = target.get("role").and_then(|r| r.as_str());
+    if !(role == Some("assistant") || (role.is_none() && is_choice)) {
+        return;
+    }
+    // OTel semconv messages carry their content as "parts"
+    let key = if target.get("content").is_some() {
+        "content"
+    } else {
+        "parts"
+    };
+    let Some(data) = target
+        .get(key)
+        .and_then(structured_output_text)
+        .and_then(|text| parse_structured_output(text, declared_json))
+    else {
+        return;
+    };
+    target[key] = json!([{"type": "json", "data": data}]);
+}
+
+/// Text of a content value that is a plain string or a single text part.
+fn structured_output_text(content: &JsonValue) -> Option<&str> {
+    match content {
+        JsonValue::String(s) => Some(s),
+        JsonValue::Array(parts) if parts.len() == 1 => {
+            let part = &parts[0];
+            if part.get("type").and_then(|t| t.as_str()) != Some("text") {
+                return None;
+            }
+            part.get("text")
+                .or_else(|| part.get("content"))
+                .and_then(|t| t.as_str())
+        }
+        _ => None,
+    }
+}
+
+fn parse_structured_output(text: &str, declared_json: bool) -> Option<JsonValue> {
+    let trimmed = text.trim();
+    let looks_like_json = if declared_json {
+        trimmed.starts_with('{') || trimmed.starts_with('[')
+    } else {
+        trimmed.starts_with('{') && trimmed.ends_with('}')
+    };
+    if !looks_like_json {
+        return None;
+    }
+    serde_json::from_str::<JsonValue>(trimmed)
+        .ok()
+        .filter(|v| v.is_object() || (declared_json && v.is_array()))
+}
+
+/// Move event-sourced message times back by a span's `clock_shift`.
+///
+/// Attribute messages already carry the clamped span start, but event times
+/// come straight from the SDK clock and would otherwise keep the skew, landing
+/// after the span that contains them.
+pub(super) fn shift_event_times(messages: &mut [RawMessage], shift: TimeDelta) {
+    for msg in messages {
+        if let MessageSource::Event { time, .. } = &mut msg.source {
+            *time -= shift;
+        }
+    }
+}
+
+/// Message attributes whose value must be a JSON array of messages.
+const JSON_MESSAGE_ATTRIBUTES: &[&str] = &[
+    keys::GEN_AI_INPUT_MESSAGES,
+    keys::GEN_AI_OUTPUT_MESSAGES,
+    keys::AI_PROMPT_MESSAGES,
+];
+
+/// Report message attributes holding malformed JSON (e.g. cut off by an SDK
+/// attribute length limit).
+///
+/// Extraction skips such attributes, so without a warning the span would just
+/// show fewer messages.
+pub(super) fn malformed_message_warnings(attrs: &HashMap<String, String>) -> Vec<IngestWarning> {
+    JSON_MESSAGE_ATTRIBUTES
+        .iter()
+        .filter_map(|key| {
+            let value = attrs.get(*key)?;
+            let error = serde_json::from_str::<serde::de::IgnoredAny>(value).err()?;
+            Some(IngestWarning {
+                kind: IngestWarningKind::ParseError,
+                message: format!("Failed to parse {key}: {error}"),
+            })
+        })
+        .collect()
+}
+
+/// Build a context marker for a span's external side effect (storage, DB or HTTP).
+///
+/// Only used for spans without conversation content, so the feed shows what an
+/// agent touched inline with its messages. The most specific kind wins: storage
+/// calls often also carry HTTP attributes. URLs and statements containing any
+/// denylist entry (case-insensitive) are dropped and the marker flagged `redacted`.
+/// URL query strings and fragments often carry tokens, so they are stripped
+/// unless `keep_url_query` is set.
+pub(super) fn extract_side_effect_marker(
+    span: &SpanData,
+    timestamp: DateTime<Utc>,
+    denylist: &[String],
+    keep_url_query: bool,
+) -> Option<RawMessage> {
+    let mut data = serde_json::Map::new();
+    let mut insert = |key: &str, value: Option<&String>| {
+        if let Some(v) = value.filter(|v| !v.is_empty()) {
+            data.insert(key.to_string(), json!(v));
+        }
+    };
+
+    let (marker_type, source, sensitive) = if span.storage_bucket.is_some() {
+        insert("system", span.storage_system.as_ref());
+        insert("bucket", span.storage_bucket.as_ref());
+        insert("key", span.storage_object.as_ref());
+        ("storage_operation", keys::AWS_S3_BUCKET, None)
+    } else if span.db_statement.is_some() || span.db_operation.is_some() {
+        insert("system", span.db_system.as_ref());
+        insert("name", span.db_name.as_ref());
+        insert("operation", span.db_operation.as_ref());
+        (
+            "db_query",
+            keys::DB_STATEMENT,
+            Some(("statement", span.db_statement.as_ref())),
+        )
+    } else if span.http_url.is_some() {
+        insert("method", span.http_method.as_ref());
+        (
+            "http_request",
+            keys::HTTP_URL,
+            Some(("url", span.http_url.as_ref())),
+        )
+    } else {
+        return None;
+    };
+
+    if let Some((field, Some(value))) = sensitive {
+        if is_denylisted(value, denylist) {
+            data.insert("redacted".to_string(), json!(true));
+        } else if field == "url" && !keep_url_query {
+            data.insert(field.to_string(), json!(strip_url_query(value)));
+        } else {
+            data.insert(field.to_string(), json!(value));
+        }
+    }
+    if marker_type == "http_request"
+        && let Some(status) = span.http_status_code
+    {
+        data.insert("status_code".to_string(), json!(status));
+    }
+
+    let mut msg = serde_json::Map::new();
+    msg.insert("role".to_string(), json!("context"));
+    msg.insert("type".to_string(), json!(marker_type));
+    msg.insert("content".to_string(), JsonValue::Object(data));
+    msg.insert("_source".to_string(), json!(source));
+
+    Some(RawMessage::from_attr(
+        source,
+        timestamp,
+        JsonValue::Object(msg),
+    ))
+}
+
+/// Whether a value contains any denylist entry (case-insensitive)
+fn is_denylisted(value: &str, denylist: &[String]) -> bool {
+    let lower = value.to_lowercase();
+    denylist
+        .iter()
+        .any(|entry| !entry.is_empty() && lower.contains(&entry.to_lowercase()))
+}
+
+/// Drop the query string and fragment from a URL.
+fn strip_url_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Replace the results of sensitive tools with [`REDACTED_TOOL_OUTPUT`].
+///
+/// A result's tool name comes from its own `name`, else from the matching tool
+/// call in the same span (by call ID), else from the span's `gen_ai.tool.name`.
+/// Patterns are case-insensitive and may use `*` as a wildcard. Tool calls and
+/// their arguments are left untouched.
+pub(super) fn redact_tool_results(
+    messages: &mut [RawMessage],
+    span_tool_name: Option<&str>,
+    patterns: &[String],
+) {
+    if patterns.is_empty() {
+        return;
+    }
+    let mut call_names = HashMap::new();
+    for msg in messages.iter() {
+        collect_tool_call_names(&msg.content, &mut call_names);
+    }
+    let is_sensitive = |name: &str| {
+        patterns
+            .iter()
+            .any(|pattern| tool_name_matches(pattern, name))
+    };
+    for msg in messages.iter_mut() {
+        redact_results_in(&mut msg.content, &call_names, span_tool_name, &is_sensitive);
+    }
+}
+
+/// Span attributes holding a tool execution span's own output
+const TOOL_OUTPUT_ATTRS: &[&str] = &[
+    keys::GEN_AI_TOOL_CALL_RESULT,
+    keys::AI_TOOLCALL_RESULT,
+    keys::LK_FUNCTION_TOOL_OUTPUT,
+    keys::GCP_VERTEX_TOOL_RESPONSE,
+    keys::TOOL_RESPONSE,
+    keys::OUTPUT_VALUE,
+    keys::TRACELOOP_ENTITY_OUTPUT,
+    keys::MLFLOW_SPAN_OUTPUTS,
+];
+
+/// Apply [`redact_tool_results`] to the archived raw span JSON.
+///
+/// Attribute values (JSON-encoded strings included) and event attributes are
+/// redacted like raw messages. `gen_ai.tool.message` events are tool results by
+/// name, and a sensitive tool execution span loses its output attributes.
+pub(crate) fn redact_raw_span_tool_results(
+    raw_span: &mut JsonValue,
+    span_tool_name: Option<&str>,
+    patterns: &[String],
+) {
+    if patterns.is_empty() {
+        return;
+    }
+    let Some(span) = raw_span.as_object_mut() else {
+        return;
+    };
+    let is_sensitive = |name: &str| {
+        patterns
+            .iter()
+            .any(|pattern| tool_name_matches(pattern, name))
+    };
+
+    if span_tool_name.is_some_and(is_sensitive)
+        && let Some(JsonValue::Object(attrs)) = span.get_mut("attributes")
+    {
+        for key in TOOL_OUTPUT_ATTRS {
+            if let Some(value) = attrs.get_mut(*key) {
+                *value = json!(REDACTED_TOOL_OUTPUT);
+            }
+        }
+    }
+
+    let mut maps = raw_span_attribute_maps(span);
+    let mut call_names = HashMap::new();
+    for (_, attrs) in &maps {
+        for value in attrs.values() {
+            match decode_json_attribute(value) {
+                Some(decoded) => collect_tool_call_names(&decoded, &mut call_names),
+                None => collect_tool_call_names(value, &mut call_names),
+            }
+        }
+    }
+    for (is_tool_message, attrs) in &mut maps {
+        if *is_tool_message
+            && attrs.contains_key("content")
+            && result_tool_name(attrs, &call_names, span_tool_name).is_some_and(is_sensitive)
+        {
+            attrs.insert("content".to_string(), json!(REDACTED_TOOL_OUTPUT));
+        }
+        for value in attrs.values_mut() {
+            match decode_json_attribute(value) {
+                Some(mut decoded) => {
+                    let before = decoded.clone();
+                    redact_results_in(&mut decoded, &call_names, span_tool_name, &is_sensitive);
+                    if decoded != before {
+                        *value = json!(decoded.to_string());
+                    }
+                }
+                None => redact_results_in(value, &call_names, span_tool_name, &is_sensitive),
+            }
+        }
+    }
+}
+
+/// Event attributes checked against the side-effect marker denylist
+const MARKER_SENSITIVE_ATTRS: &[&str] = &[keys::HTTP_URL, keys::URL_FULL, keys::DB_STATEMENT];
+
+/// Redact stored span events before serving them.
+///
+/// `events` are raw span events (`name` and `attributes`). Tool results are
+/// redacted as in [`redact_raw_span_tool_results`], so patterns added after
+/// ingestion apply too, and URLs and statements containing a denylist entry are
+/// dropped, as they are from feed markers. `span_attributes` is the span's own
+/// attribute JSON, used for its tool name.
+pub(crate) fn redact_span_events(
+    events: &mut [JsonValue],
+    span_attributes: Option<&str>,
+    tool_patterns: &[String],
+    denylist: &[String],
+) {
+    let span_tool_name = span_attributes
+        .and_then(|a| serde_json::from_str::<JsonValue>(a).ok())
+        .and_then(|a| a.get(keys::GEN_AI_TOOL_NAME)?.as_str().map(str::to_string));
+    let mut raw_span =
+        json!({ "events": events.iter_mut().map(JsonValue::take).collect::<Vec<_>>() });
+    redact_raw_span_tool_results(&mut raw_span, span_tool_name.as_deref(), tool_patterns);
+
+    let redacted = raw_span["events"]
+        .as_array_mut()
+        .map(std::mem::take)
+        .unwrap_or_default();
+    for (event, mut redacted_event) in events.iter_mut().zip(redacted) {
+        if let Some(attrs) = redacted_event
+            .get_mut("attributes")
+            .and_then(JsonValue::as_object_mut)
+        {
+            attrs.retain(|key, value| {
+                !MARKER_SENSITIVE_ATTRS.contains(&key.as_str())
+                    || !value.as_str().is_some_and(|v| is_denylisted(v, denylist))
+            });
+        }
+        *event = redacted_event;
+    }
+}
+
+/// Attribute maps of a raw span and its events, flagged when the event is a
+/// `gen_ai.tool.message`
+fn raw_span_attribute_maps(
+    span: &mut serde_json::Map<String, JsonValue>,
+) -> Vec<(bool, &mut serde_json::Map<String, JsonValue>)> {
+    let mut maps = Vec::new();
+    for (key, value) in span.iter_mut() {
+        match (key.as_str(), value) {
+            ("attributes", JsonValue::Object(attrs)) => maps.push((false, attrs)),
+            ("events", JsonValue::Array(events)) => {
+                for event in events {
+                    let is_tool_message = event.get("name").and_then(|n| n.as_str())
+                        == Some(keys::EVENT_TOOL_MESSAGE);
+                    if let Some(JsonValue::Object(attrs)) = event.get_mut("attributes") {
+                        maps.push((is_tool_message, attrs));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    maps
+}
+
+/// Parse a JSON-encoded object or array attribute value
+fn decode_json_attribute(value: &JsonValue) -> Option<JsonValue> {
+    value
+        .as_str()
+        .and_then(|s| serde_json::from_str::<JsonValue>(s).ok())
+        .filter(|v| v.is_object() || v.is_array())
+}
+
+const TOOL_ID_KEYS: &[&str] = &[
+    "tool_call_id",
+    "tool_use_id",
+    "toolUseId",
+    "toolCallId",
+    "call_id",
+    "id",
+];
+const TOOL_RESULT_TYPES: &[&str] = &[
+    "tool_result",
+    "tool-result",
+    "tool_call_response",
+    "function_call_output",
+];
+const TOOL_RESULT_FIELDS: &[&str] = &["content", "output", "result", "response"];
+
+fn first_str<'a>(obj: &'a serde_json::Map<String, JsonValue>, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+}
+
+/// Map call ID -> tool name for every tool call found in `value`.
+fn collect_tool_call_names(value: &JsonValue, names: &mut HashMap<String, String>) {
+    match value {
+        JsonValue::Object(obj) => {
+            let is_call = ["input", "arguments", "args", "function"]
+                .iter()
+                .any(|k| obj.contains_key(*k));
+            let name = first_str(obj, &["name", "toolName"]).or_else(|| {
+                obj.get("function")
+                    .and_then(|f| f.get("name"))
+                    .and_then(|n| n.as_str())
+            });
+            if is_call && let (Some(id), Some(name)) = (first_str(obj, TOOL_ID_KEYS), name) {
+                names.insert(id.to_string(), name.to_string());
+            }
+            for child in obj.values() {
+                collect_tool_call_names(child, names);
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_tool_call_names(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_results_in(
+    value: &mut JsonValue,
+    call_names: &HashMap<String, String>,
+    span_tool_name: Option<&str>,
+    is_sensitive: &impl Fn(&str) -> bool,
+) {
+    match value {
+        JsonValue::Object(obj) => {
+            let result_field = if obj.get("role").and_then(|r| r.as_str()) == Some("tool") {
+                Some("content")
+            } else if obj
+                .get("type")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| TOOL_RESULT_TYPES.contains(&t))
+            {
+                TOOL_RESULT_FIELDS
+                    .iter()
+                    .copied()
+                    .find(|f| obj.contains_key(*f))
+            } else {
+                None
+            };
+            if let Some(field) = result_field {
+                if result_tool_name(obj, call_names, span_tool_name).is_some_and(is_sensitive) {
+                    obj.insert(field.to_string(), json!(REDACTED_TOOL_OUTPUT));
+                }
+                return;
+            }
+
+            // Bedrock toolResult and Gemini functionResponse wrappers
+            for (key, field, redacted) in [
+                (
+                    "toolResult",
+                    "content",
+                    json!([{"text": REDACTED_TOOL_OUTPUT}]),
+                ),
+                (
+                    "functionResponse",
+                    "response",
+                    json!({"content": REDACTED_TOOL_OUTPUT}),
+                ),
+            ] {
+                if let Some(JsonValue::Object(inner)) = obj.get_mut(key)
+                    && inner.contains_key(field)
+                    && result_tool_name(inner, call_names, span_tool_name).is_some_and(is_sensitive)
+                {
+                    inner.insert(field.to_string(), redacted);
+                }
+            }
+            for child in obj.values_mut() {
+                redact_results_in(child, call_names, span_tool_name, is_sensitive);
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                redact_results_in(item, call_names, span_tool_name, is_sensitive);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn result_tool_name<'a>(
+    obj: &'a serde_json::Map<String, JsonValue>,
+    call_names: &'a HashMap<String, String>,
+    span_tool_name: Option<&'a str>,
+) -> Option<&'a str> {
+    first_str(obj, &["name", "toolName", "tool_name"])
+        .or_else(|| {
+            first_str(obj, TOOL_ID_KEYS)
+                .and_then(|id| call_names.get(id))
+                .map(String::as_str)
+        })
+        .or(span_tool_name)
+}
+
+/// Case-insensitive match of a tool name against a pattern where `*` matches
+/// any run of characters.
+fn tool_name_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let name = name.to_lowercase();
+    if pattern.is_empty() {
+        return false;
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || name.len() < first.len() + last.len() || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 /// Check if messages contain only non-conversation content (system prompts) or are empty.
 ///
 /// Returns true if attribute extraction fallback should be attempted.
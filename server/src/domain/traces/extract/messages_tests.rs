@@ -158,6 +158,44 @@ fn test_autogen_llm_stream_end_event() {
     );
 }
 
+#[test]
+fn test_autogen_multi_choice_response_yields_message_per_choice() {
+    use crate::domain::sideml::{ChatRole, FinishReason, to_sideml};
+
+    let stream_end_json = r#"{
+        "type": "LLMStreamEnd",
+        "response": {
+            "choices": [
+                {"index": 0, "finish_reason": "stop", "message": {"content": "Sunny all week."}},
+                {"index": 1, "finish_reason": "length", "message": {"content": "Sunny on Monday, then"}}
+            ]
+        }
+    }"#;
+    let attrs = make_attrs(&[("log.body", stream_end_json)]);
+    let mut messages = Vec::new();
+    let found = try_autogen(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].content["content"], "Sunny all week.");
+    assert_eq!(messages[1].content["content"], "Sunny on Monday, then");
+
+    // Each choice becomes its own assistant message with its own finish reason
+    let sideml = to_sideml(&messages);
+    assert_eq!(sideml.len(), 2);
+    let choices: Vec<_> = sideml
+        .iter()
+        .map(|m| (m.sideml.role, m.sideml.finish_reason, m.sideml.index))
+        .collect();
+    assert_eq!(
+        choices,
+        vec![
+            (ChatRole::Assistant, Some(FinishReason::Stop), Some(0)),
+            (ChatRole::Assistant, Some(FinishReason::Length), Some(1)),
+        ]
+    );
+}
+
 #[test]
 fn test_autogen_message_attribute_with_messages_array() {
     // AutoGen outer message format with messages array (OpenInference TextMessage)
@@ -181,6 +219,74 @@ fn test_autogen_message_attribute_with_messages_array() {
     assert_eq!(msg.content["role"].as_str(), Some("user"));
 }
 
+#[test]
+fn test_autogen_task_result_output_named_agents() {
+    // AgentChat team run: TaskResult with one message per agent turn
+    let output_json = json!({
+        "messages": [
+            {"type": "TextMessage", "source": "user", "content": "Write a haiku about rain"},
+            {"type": "TextMessage", "source": "poet", "content": "Soft rain on the roof"},
+            {"type": "TextMessage", "source": "critic", "content": "Good imagery. APPROVE"}
+        ],
+        "stop_reason": "Text 'APPROVE' mentioned"
+    })
+    .to_string();
+    let attrs = make_attrs(&[("output.value", &output_json)]);
+    let mut messages = Vec::new();
+    let found = try_autogen(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].content["role"].as_str(), Some("user"));
+    assert_eq!(messages[1].content["role"].as_str(), Some("assistant"));
+    assert_eq!(messages[1].content["name"].as_str(), Some("poet"));
+    assert_eq!(messages[2].content["name"].as_str(), Some("critic"));
+    assert_eq!(
+        messages[2].content["content"].as_str(),
+        Some("Good imagery. APPROVE")
+    );
+}
+
+#[test]
+fn test_autogen_chat_history_output_named_agents() {
+    // v0.2 ChatResult from initiate_chat between two agents
+    let output_json = json!({
+        "chat_history": [
+            {"role": "assistant", "name": "user_proxy", "content": "Plot NVDA stock"},
+            {"role": "user", "name": "coder", "content": "```python\nplot()\n```"},
+            {"role": "assistant", "name": "user_proxy", "content": "exitcode: 0"}
+        ],
+        "summary": "Plotted",
+        "cost": {}
+    })
+    .to_string();
+    let attrs = make_attrs(&[("output.value", &output_json)]);
+    let mut messages = Vec::new();
+    let found = try_autogen(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    let names: Vec<_> = messages
+        .iter()
+        .map(|m| m.content["name"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![Some("user_proxy"), Some("coder"), Some("user_proxy")]
+    );
+}
+
+#[test]
+fn test_autogen_plain_output_messages_not_claimed() {
+    // A plain chat array without AutoGen typing is left to the generic extractor
+    let output_json = r#"{"messages":[{"role":"assistant","content":"Hi"}]}"#;
+    let attrs = make_attrs(&[("output.value", output_json)]);
+    let mut messages = Vec::new();
+    let found = try_autogen(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(!found);
+    assert!(messages.is_empty());
+}
+
 #[test]
 fn test_autogen_no_message_skipped() {
     let attrs = make_attrs(&[("message", "No Message")]);
@@ -442,6 +548,55 @@ fn test_crewai_output_with_messages_array() {
     assert_eq!(messages[2].content["role"].as_str(), Some("assistant"));
 }
 
+#[test]
+fn test_crewai_tasks_output_attributed_to_agents() {
+    // CrewOutput from a sequential crew: one TaskOutput per agent, no messages
+    let output_json = json!({
+        "raw": "Final itinerary",
+        "tasks_output": [
+            {"description": "Research", "agent": "Researcher", "raw": "Found 3 hotels"},
+            {"description": "Plan", "agent": "Planner", "raw": "Final itinerary"},
+            {
+                "description": "Review",
+                "agent": "Reviewer",
+                "messages": [
+                    {"role": "user", "content": "Review the plan"},
+                    {"role": "assistant", "content": "Looks good"}
+                ]
+            }
+        ]
+    })
+    .to_string();
+    let attrs = make_attrs(&[("output.value", &output_json), ("crew_key", "test-key")]);
+    let mut messages = Vec::new();
+    let found = try_crewai(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    let turns: Vec<(Option<&str>, Option<&str>, Option<&str>)> = messages
+        .iter()
+        .map(|m| {
+            (
+                m.content["role"].as_str(),
+                m.content["name"].as_str(),
+                m.content["content"].as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        turns,
+        vec![
+            (
+                Some("assistant"),
+                Some("Researcher"),
+                Some("Found 3 hotels")
+            ),
+            (Some("assistant"), Some("Planner"), Some("Final itinerary")),
+            (Some("user"), None, Some("Review the plan")),
+            (Some("assistant"), Some("Reviewer"), Some("Looks good")),
+        ]
+    );
+}
+
 #[test]
 fn test_crewai_output_with_tool_calls_without_content() {
     // CrewAI/OpenAI-style assistant tool call message can omit explicit content.
@@ -940,6 +1095,40 @@ fn test_gen_ai_tool_names_extraction() {
     }
 }
 
+#[test]
+fn test_gen_ai_agent_tools_definition_objects() {
+    // Agent span listing three available tools: two full definitions, one bare name
+    let tools_json = r#"[
+        {"type":"function","function":{"name":"get_weather","description":"Get weather","parameters":{"type":"object","properties":{"city":{"type":"string"}}}}},
+        {"name":"send_email","description":"Send an email","parameters":{"type":"object"}},
+        "search"
+    ]"#;
+    let attrs = make_attrs(&[("gen_ai.agent.tools", tools_json)]);
+
+    let (tool_definitions, tool_names) = extract_tool_definitions(&attrs, Utc::now());
+
+    assert_eq!(tool_definitions.len(), 1);
+    assert_eq!(tool_definitions[0].content.as_array().unwrap().len(), 2);
+    assert_eq!(tool_names.len(), 1);
+
+    // Flattened the same way persistence stores them, then deduplicated by the feed
+    let defs: Vec<JsonValue> = tool_definitions
+        .iter()
+        .flat_map(|d| d.content.as_array().cloned().unwrap_or_default())
+        .collect();
+    let names: Vec<String> = tool_names
+        .iter()
+        .flat_map(|n| n.content.as_array().cloned().unwrap_or_default())
+        .filter_map(|n| n.as_str().map(String::from))
+        .collect();
+
+    let defs = crate::domain::sideml::feed::deduplicate_tools(defs, false);
+    let names = crate::domain::sideml::feed::deduplicate_names(names, false);
+
+    assert_eq!(defs.len(), 2);
+    assert_eq!(names, vec!["get_weather", "search", "send_email"]);
+}
+
 #[test]
 fn test_gen_ai_tool_names_with_conversation_messages() {
     // Tool definitions alongside regular conversation messages
@@ -1788,6 +1977,114 @@ fn test_langsmith_completion_with_choices() {
     assert_eq!(messages[0].content["finish_reason"].as_str(), Some("stop"));
 }
 
+#[test]
+fn test_deepseek_reasoning_content_becomes_thinking() {
+    use crate::domain::sideml::{ContentBlock, to_sideml_with_context};
+
+    let completion_json = r#"{
+        "id": "chatcmpl-ds",
+        "model": "deepseek-reasoner",
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": "The answer is 42.",
+                "reasoning_content": "The user asks for the answer. Recall the guide: 42."
+            },
+            "finish_reason": "stop"
+        }],
+        "usage": {
+            "prompt_tokens": 12,
+            "completion_tokens": 90,
+            "completion_tokens_details": {"reasoning_tokens": 75}
+        }
+    }"#;
+    let attrs = make_attrs(&[
+        ("gen_ai.completion", completion_json),
+        ("langsmith.span.kind", "llm"),
+    ]);
+    let mut messages = Vec::new();
+    assert!(try_langsmith(
+        &mut messages,
+        &mut Vec::new(),
+        &attrs,
+        "",
+        Utc::now()
+    ));
+
+    let sideml = to_sideml_with_context(&messages, false);
+    assert_eq!(sideml.len(), 1);
+    let content = &sideml[0].sideml.content;
+    assert_eq!(content.len(), 2, "reasoning must not fold into the answer");
+    assert!(matches!(
+        &content[0],
+        ContentBlock::Thinking { text, signature: None } if text.starts_with("The user asks")
+    ));
+    assert!(matches!(
+        &content[1],
+        ContentBlock::Text { text } if text == "The answer is 42."
+    ));
+
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "ChatDeepSeek");
+    assert_eq!(span.gen_ai_usage_reasoning_tokens, 75);
+}
+
+#[test]
+fn test_gemini_thought_parts_become_thinking() {
+    use crate::domain::sideml::{ContentBlock, to_sideml_with_context};
+
+    let response_json = r#"{
+        "content": {
+            "role": "model",
+            "parts": [
+                {"text": "Convert both values to Celsius first.", "thought": true, "thought_signature": "EpYBCpMB"},
+                {"text": "It is warmer in Madrid."}
+            ]
+        },
+        "finish_reason": "STOP",
+        "usage_metadata": {
+            "prompt_token_count": 30,
+            "candidates_token_count": 10,
+            "thoughts_token_count": 120,
+            "total_token_count": 160
+        }
+    }"#;
+    let attrs = make_attrs(&[
+        ("gcp.vertex.agent.llm_request", "{}"),
+        ("gcp.vertex.agent.llm_response", response_json),
+    ]);
+    let mut messages = Vec::new();
+    assert!(try_google_adk(
+        &mut messages,
+        &mut Vec::new(),
+        &attrs,
+        "",
+        Utc::now()
+    ));
+
+    let sideml = to_sideml_with_context(&messages, false);
+    let assistant = sideml
+        .iter()
+        .find(|m| !m.sideml.content.is_empty())
+        .expect("assistant message");
+    let content = &assistant.sideml.content;
+    assert_eq!(content.len(), 2);
+    assert!(matches!(
+        &content[0],
+        ContentBlock::Thinking { text, signature: Some(sig) }
+            if text.starts_with("Convert both") && sig == "EpYBCpMB"
+    ));
+    assert!(matches!(
+        &content[1],
+        ContentBlock::Text { text } if text == "It is warmer in Madrid."
+    ));
+
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "call_llm");
+    assert_eq!(span.gen_ai_usage_output_tokens, 10);
+    assert_eq!(span.gen_ai_usage_reasoning_tokens, 120);
+}
+
 #[test]
 fn test_langsmith_not_detected_without_attrs() {
     let prompt_json = r#"{"messages": [{"role": "user", "content": "Test"}]}"#;
@@ -2335,6 +2632,61 @@ fn test_openinference_embedding_text() {
     );
 }
 
+#[test]
+fn test_openinference_embedding_embeddings_array() {
+    let attrs = make_attrs(&[
+        ("embedding.model_name", "text-embedding-3-small"),
+        ("embedding.embeddings.0.embedding.text", "first chunk"),
+        ("embedding.embeddings.0.embedding.vector", "[0.1, 0.2, 0.3]"),
+        ("embedding.embeddings.1.embedding.text", "second chunk"),
+        ("embedding.embeddings.1.embedding.vector", "[0.4, 0.5, 0.6]"),
+        ("embedding.embeddings.2.embedding.text", "third chunk"),
+        ("embedding.embeddings.2.embedding.vector", "[0.7, 0.8, 0.9]"),
+    ]);
+
+    let mut messages = Vec::new();
+    let found = try_openinference(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 1);
+
+    let msg = &messages[0].content;
+    assert_eq!(msg["role"], "context");
+    assert_eq!(msg["type"], "embeddings");
+    assert_eq!(msg["_source"], "embedding.embeddings");
+    assert_eq!(
+        msg["content"]["texts"],
+        json!(["first chunk", "second chunk", "third chunk"])
+    );
+    assert_eq!(msg["content"]["model"], "text-embedding-3-small");
+    assert!(msg["content"].get("truncated").is_none());
+    assert!(
+        !msg.to_string().contains("0.5"),
+        "vectors must not be stored"
+    );
+}
+
+#[test]
+fn test_openinference_embedding_embeddings_truncated() {
+    let pairs: Vec<(String, String)> = (0..MAX_EMBEDDING_TEXTS + 5)
+        .map(|i| {
+            (
+                format!("embedding.embeddings.{i}.embedding.text"),
+                format!("chunk {i}"),
+            )
+        })
+        .collect();
+    let attrs: HashMap<String, String> = pairs.into_iter().collect();
+
+    let mut messages = Vec::new();
+    try_openinference(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    let data = &messages[0].content["content"];
+    assert_eq!(data["texts"].as_array().unwrap().len(), MAX_EMBEDDING_TEXTS);
+    assert_eq!(data["total"], MAX_EMBEDDING_TEXTS + 5);
+    assert_eq!(data["truncated"], true);
+}
+
 #[test]
 fn test_openinference_invocation_parameters() {
     let params_json = r#"{"temperature":0.7,"max_tokens":1000,"top_p":0.9}"#;
@@ -3788,6 +4140,100 @@ fn test_traceloop_entity_output() {
     assert!(!messages.is_empty());
 }
 
+#[test]
+fn test_structured_output_becomes_json_block() {
+    use crate::domain::sideml::{ContentBlock, to_sideml_with_context};
+
+    let structured = [
+        (
+            "gen_ai.input.messages",
+            r#"[{"role":"user","parts":[{"type":"text","content":"Extract the city"}]}]"#,
+        ),
+        (
+            "gen_ai.output.messages",
+            r#"[{"role":"assistant","parts":[{"type":"text","content":"{\"city\": \"Paris\", \"country\": \"FR\"}"}]}]"#,
+        ),
+        ("gen_ai.output.type", "json"),
+    ];
+    let span = make_attr_span("chat", &structured);
+    let (raw, _, _) = extract_messages_for_span(&span, &make_attrs(&structured), Utc::now());
+    let sideml = to_sideml_with_context(&raw, false);
+    let output = sideml.last().unwrap();
+    assert!(matches!(
+        &output.sideml.content[0],
+        ContentBlock::Json { data } if data["city"] == "Paris"
+    ));
+    assert!(matches!(
+        &sideml[0].sideml.content[0],
+        ContentBlock::Text { text } if text == "Extract the city"
+    ));
+
+    let plain = [
+        (
+            "gen_ai.input.messages",
+            r#"[{"role":"user","parts":[{"type":"text","content":"Where is Paris?"}]}]"#,
+        ),
+        (
+            "gen_ai.output.messages",
+            r#"[{"role":"assistant","parts":[{"type":"text","content":"Paris is in {France}."}]}]"#,
+        ),
+    ];
+    let span = make_attr_span("chat", &plain);
+    let (raw, _, _) = extract_messages_for_span(&span, &make_attrs(&plain), Utc::now());
+    let sideml = to_sideml_with_context(&raw, false);
+    assert!(matches!(
+        &sideml.last().unwrap().sideml.content[0],
+        ContentBlock::Text { text } if text == "Paris is in {France}."
+    ));
+}
+
+#[test]
+fn test_traceloop_entity_messages_input_and_text_output() {
+    use crate::domain::sideml::{ChatRole, ContentBlock, to_sideml_with_context};
+
+    let attrs = [
+        (
+            "traceloop.entity.input",
+            r#"{"messages":[{"role":"system","content":"Be brief"},{"role":"user","content":"What is 2+2?"}]}"#,
+        ),
+        ("traceloop.entity.output", "2 + 2 = 4"),
+    ];
+    let span = make_attr_span("calc.workflow", &attrs);
+    let (raw, _, _) = extract_messages_for_span(&span, &make_attrs(&attrs), Utc::now());
+    assert_eq!(raw.len(), 3, "two input messages + one output");
+
+    let sideml = to_sideml_with_context(&raw, false);
+    let roles: Vec<ChatRole> = sideml.iter().map(|m| m.sideml.role).collect();
+    assert_eq!(
+        roles,
+        vec![ChatRole::System, ChatRole::User, ChatRole::Assistant]
+    );
+    assert!(matches!(
+        &sideml[1].sideml.content[0],
+        ContentBlock::Text { text } if text == "What is 2+2?"
+    ));
+    assert!(matches!(
+        &sideml[2].sideml.content[0],
+        ContentBlock::Text { text } if text == "2 + 2 = 4"
+    ));
+}
+
+#[test]
+fn test_traceloop_entity_raw_input_kept_whole() {
+    let attrs = make_attrs(&[(
+        "traceloop.entity.input",
+        r#"{"args":[],"kwargs":{"city":"Paris","days":3}}"#,
+    )]);
+
+    let mut messages = Vec::new();
+    let found = try_traceloop(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].content["role"], "user");
+    assert_eq!(messages[0].content["content"]["kwargs"]["city"], "Paris");
+}
+
 #[test]
 fn test_user_id_from_ai_telemetry_metadata() {
     let attrs = make_attrs(&[("ai.telemetry.metadata.userId", "user-67890")]);
@@ -4098,17 +4544,186 @@ fn test_vercel_ai_tool_result_with_id() {
     assert_eq!(tool_result.content["tool_call_id"], "call_abc123");
 }
 
+fn make_attr_span(name: &str, attrs: &[(&str, &str)]) -> Span {
+    Span {
+        trace_id: vec![0; 16],
+        span_id: vec![0; 8],
+        parent_span_id: vec![],
+        name: name.to_string(),
+        kind: 1,
+        start_time_unix_nano: 1767099299293126000,
+        end_time_unix_nano: 1767099299294119000,
+        attributes: attrs.iter().map(|(k, v)| make_kv(k, v)).collect(),
+        events: vec![],
+        links: vec![],
+        status: None,
+        trace_state: String::new(),
+        flags: 0,
+        dropped_attributes_count: 0,
+        dropped_events_count: 0,
+        dropped_links_count: 0,
+    }
+}
+
 #[test]
-fn test_vercel_response_with_tool_calls() {
-    let tool_calls = r#"[{"id":"call_abc","type":"function","function":{"name":"search","arguments":"{\"query\":\"rust\"}"}}]"#;
-    let attrs = make_attrs(&[
-        ("ai.response.text", "Let me search for that."),
-        ("ai.response.toolCalls", tool_calls),
-    ]);
-    let mut messages = Vec::new();
-    let found = try_vercel_ai(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+fn test_vercel_ai_multi_tool_calls_linked_to_results() {
+    use crate::domain::sideml::{ContentBlock, to_sideml_with_context};
 
-    assert!(found);
+    // Generation span: one response with two tool calls (SDK v4 toolCallId/toolName/args)
+    let gen_attrs = [
+        (
+            "ai.prompt.messages",
+            r#"[{"role":"user","content":[{"type":"text","text":"Weather and time in NYC?"}]}]"#,
+        ),
+        (
+            "ai.response.toolCalls",
+            r#"[{"toolCallType":"function","toolCallId":"call_1","toolName":"get_weather","args":"{\"city\":\"NYC\"}"},{"toolCallType":"function","toolCallId":"call_2","toolName":"get_time","args":"{\"tz\":\"EST\"}"}]"#,
+        ),
+        (
+            "ai.prompt.tools",
+            r#"["{\"type\":\"function\",\"name\":\"get_weather\",\"parameters\":{\"type\":\"object\"}}","{\"type\":\"function\",\"name\":\"get_time\",\"parameters\":{\"type\":\"object\"}}"]"#,
+        ),
+    ];
+    let gen_span = make_attr_span("ai.generateText.doGenerate", &gen_attrs);
+    let (gen_messages, tool_defs, _) =
+        extract_messages_for_span(&gen_span, &make_attrs(&gen_attrs), Utc::now());
+
+    let tool_uses: Vec<(String, String)> = to_sideml_with_context(&gen_messages, false)
+        .iter()
+        .flat_map(|m| m.sideml.content.iter())
+        .filter_map(|b| match b {
+            ContentBlock::ToolUse { id, name, input } => {
+                assert!(input.is_object(), "args should be parsed into an object");
+                Some((id.clone().unwrap(), name.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        tool_uses,
+        vec![
+            ("call_1".to_string(), "get_weather".to_string()),
+            ("call_2".to_string(), "get_time".to_string()),
+        ]
+    );
+
+    // ai.prompt.tools feeds tool_definitions
+    assert_eq!(tool_defs.len(), 1);
+    let defs = tool_defs[0].content.as_array().unwrap();
+    assert_eq!(defs.len(), 2);
+    assert_eq!(defs[0]["name"], "get_weather");
+
+    // Tool spans: each result links back via ai.toolCall.id
+    for (id, name, result) in [
+        ("call_1", "get_weather", r#"{"temp":"72F"}"#),
+        ("call_2", "get_time", r#""10:00""#),
+    ] {
+        let tool_attrs = [
+            ("ai.toolCall.name", name),
+            ("ai.toolCall.id", id),
+            ("ai.toolCall.args", "{}"),
+            ("ai.toolCall.result", result),
+        ];
+        let tool_span = make_attr_span("ai.toolCall", &tool_attrs);
+        let (tool_messages, _, _) =
+            extract_messages_for_span(&tool_span, &make_attrs(&tool_attrs), Utc::now());
+        assert_eq!(tool_messages.len(), 2, "args + result for {id}");
+
+        let sideml = to_sideml_with_context(&tool_messages, true);
+        let result_msg = sideml
+            .iter()
+            .find(|m| {
+                m.sideml
+                    .content
+                    .iter()
+                    .any(|b| matches!(b, ContentBlock::ToolResult { .. }))
+            })
+            .expect("tool result block");
+        assert_eq!(result_msg.sideml.tool_use_id.as_deref(), Some(id));
+        assert_eq!(result_msg.sideml.name.as_deref(), Some(name));
+        assert!(result_msg.sideml.content.iter().any(|b| matches!(
+            b,
+            ContentBlock::ToolResult { tool_use_id: Some(t), .. } if t == id
+        )));
+    }
+}
+
+#[test]
+fn test_livekit_voice_turn_with_tool_call_and_error_result() {
+    use crate::data::types::MessageCategory;
+    use crate::domain::sideml::{ChatRole, ContentBlock, to_sideml_with_context};
+
+    let attrs = [
+        ("lk.input_text", "Book a table for two at eight"),
+        ("lk.function_tool.id", "fc_42"),
+        ("lk.function_tool.name", "book_table"),
+        (
+            "lk.function_tool.arguments",
+            r#"{"party_size":2,"time":"20:00"}"#,
+        ),
+        ("lk.function_tool.output", "No tables available"),
+        ("lk.function_tool.is_error", "true"),
+        (
+            "lk.response.text",
+            "Sorry, there are no tables left at eight.",
+        ),
+    ];
+    let span = make_attr_span("function_tool", &attrs);
+    let (raw, _, _) = extract_messages_for_span(&span, &make_attrs(&attrs), Utc::now());
+    assert_eq!(raw.len(), 4, "user + tool call + tool result + response");
+
+    let sideml = to_sideml_with_context(&raw, true);
+
+    let user = &sideml[0];
+    assert_eq!(user.sideml.role, ChatRole::User);
+    assert_eq!(user.category, MessageCategory::GenAIUserMessage);
+
+    let tool_use = sideml
+        .iter()
+        .flat_map(|m| &m.sideml.content)
+        .find_map(|b| match b {
+            ContentBlock::ToolUse { id, name, input } => Some((id, name, input)),
+            _ => None,
+        })
+        .expect("tool use block");
+    assert_eq!(tool_use.0.as_deref(), Some("fc_42"));
+    assert_eq!(tool_use.1, "book_table");
+    assert_eq!(tool_use.2["party_size"], 2);
+
+    let (tool_use_id, is_error) = sideml
+        .iter()
+        .flat_map(|m| &m.sideml.content)
+        .find_map(|b| match b {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                is_error,
+                ..
+            } => Some((tool_use_id.clone(), *is_error)),
+            _ => None,
+        })
+        .expect("tool result block");
+    assert_eq!(tool_use_id.as_deref(), Some("fc_42"));
+    assert!(is_error);
+
+    let response = sideml.last().unwrap();
+    assert_eq!(response.sideml.role, ChatRole::Assistant);
+    assert!(matches!(
+        &response.sideml.content[0],
+        ContentBlock::Text { text } if text.starts_with("Sorry")
+    ));
+}
+
+#[test]
+fn test_vercel_response_with_tool_calls() {
+    let tool_calls = r#"[{"id":"call_abc","type":"function","function":{"name":"search","arguments":"{\"query\":\"rust\"}"}}]"#;
+    let attrs = make_attrs(&[
+        ("ai.response.text", "Let me search for that."),
+        ("ai.response.toolCalls", tool_calls),
+    ]);
+    let mut messages = Vec::new();
+    let found = try_vercel_ai(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
     let response = messages
         .iter()
         .find(|m| m.content.get("role").and_then(|r| r.as_str()) == Some("assistant"))
@@ -5854,6 +6469,63 @@ fn test_try_raw_io_doesnt_wrap_message_shaped_output() {
     assert_eq!(raw["content"], "Hello!");
 }
 
+// === Raw I/O Format Detection Tests ===
+
+#[test]
+fn test_try_raw_io_messages_array_split() {
+    let input = r#"[{"role":"system","content":"Be brief"},{"role":"user","content":"Hi"}]"#;
+    let attrs = make_attrs(&[("raw_input", input)]);
+    let mut messages = Vec::new();
+
+    let found = try_raw_io(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].content["role"], "system");
+    assert_eq!(messages[1].content["role"], "user");
+    assert_eq!(messages[1].content["content"], "Hi");
+    assert!(matches!(
+        &messages[1].source,
+        MessageSource::Attribute { key, .. } if key == "raw_input"
+    ));
+}
+
+#[test]
+fn test_try_raw_io_choices_object_as_assistant() {
+    let response = r#"{"id":"chatcmpl-1","object":"chat.completion","choices":[{"index":0,"message":{"content":"Sunny, 24C"},"finish_reason":"stop"}]}"#;
+    let attrs = make_attrs(&[("response", response)]);
+    let mut messages = Vec::new();
+
+    try_raw_io(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert_eq!(messages.len(), 1);
+    let raw = &messages[0].content;
+    assert_eq!(raw["role"], "assistant");
+    assert_eq!(raw["content"], "Sunny, 24C");
+    assert_eq!(raw["finish_reason"], "stop");
+}
+
+#[test]
+fn test_try_raw_io_plain_text_single_block() {
+    let attrs = make_attrs(&[
+        ("input.value", "What's the weather in Paris?"),
+        ("output.value", "It is sunny."),
+    ]);
+    let mut messages = Vec::new();
+
+    let found = try_raw_io(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+
+    assert!(found);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].content["role"], "user");
+    assert_eq!(
+        messages[0].content["content"],
+        "What's the weather in Paris?"
+    );
+    assert_eq!(messages[1].content["role"], "assistant");
+    assert_eq!(messages[1].content["content"], "It is sunny.");
+}
+
 // ========== OI multimodal enrichment tests ==========
 
 #[test]
@@ -6214,6 +6886,198 @@ fn test_logfire_responses_api_skipped() {
     assert!(messages.is_empty());
 }
 
+// ============================================================================
+// OpenAI Responses API extraction
+// ============================================================================
+
+#[test]
+fn test_openai_responses_tool_call_and_reasoning() {
+    use crate::domain::sideml::{ChatRole, ContentBlock, FinishReason, to_sideml_with_context};
+
+    let request_json = r#"{
+        "model": "o4-mini",
+        "instructions": "You are a weather assistant.",
+        "input": [{"role": "user", "content": [{"type": "input_text", "text": "Weather in Paris?"}]}],
+        "tools": [{"type": "function", "name": "get_weather", "parameters": {"type": "object"}}]
+    }"#;
+    let response_json = r#"{
+        "id": "resp_1",
+        "object": "response",
+        "model": "o4-mini-2025-04-16",
+        "status": "completed",
+        "output": [
+            {"type": "reasoning", "id": "rs_1", "summary": [{"type": "summary_text", "text": "Need live weather data."}]},
+            {"type": "message", "id": "msg_1", "role": "assistant", "content": [{"type": "output_text", "text": "Let me check.", "annotations": []}]},
+            {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+        ],
+        "usage": {
+            "input_tokens": 120,
+            "input_tokens_details": {"cached_tokens": 100},
+            "output_tokens": 80,
+            "output_tokens_details": {"reasoning_tokens": 64},
+            "total_tokens": 200
+        }
+    }"#;
+    let attrs = make_attrs(&[
+        ("request_data", request_json),
+        ("response_data", response_json),
+    ]);
+
+    let mut messages = Vec::new();
+    let found = try_openai_responses(&mut messages, &mut Vec::new(), &attrs, "", Utc::now());
+    assert!(found);
+    let (tool_definitions, _) = extract_tool_definitions(&attrs, Utc::now());
+    assert_eq!(tool_definitions.len(), 1, "request tools are captured");
+
+    let sideml = to_sideml_with_context(&messages, false);
+    assert_eq!(sideml.len(), 3, "system + user + one assistant turn");
+    assert_eq!(sideml[0].sideml.role, ChatRole::System);
+    assert_eq!(sideml[1].sideml.role, ChatRole::User);
+    assert!(matches!(
+        &sideml[1].sideml.content[0],
+        ContentBlock::Text { text } if text == "Weather in Paris?"
+    ));
+
+    let assistant = &sideml[2].sideml;
+    assert_eq!(assistant.role, ChatRole::Assistant);
+    assert_eq!(assistant.finish_reason, Some(FinishReason::ToolUse));
+    assert!(matches!(
+        &assistant.content[0],
+        ContentBlock::Thinking { text, .. } if text == "Need live weather data."
+    ));
+    assert!(matches!(
+        &assistant.content[1],
+        ContentBlock::Text { text } if text == "Let me check."
+    ));
+    assert!(matches!(
+        &assistant.content[2],
+        ContentBlock::ToolUse { id: Some(id), name, input }
+            if id == "call_1" && name == "get_weather" && input["city"] == "Paris"
+    ));
+    assert!(
+        matches!(&messages[2].source, MessageSource::Attribute { key, .. } if key == "response_data")
+    );
+
+    // Cost inputs: cached and reasoning details come from *_tokens_details
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "Responses API");
+    assert_eq!(span.gen_ai_usage_input_tokens, 120);
+    assert_eq!(span.gen_ai_usage_output_tokens, 80);
+    assert_eq!(span.gen_ai_usage_total_tokens, 200);
+    assert_eq!(span.gen_ai_usage_cache_read_tokens, 100);
+    assert_eq!(span.gen_ai_usage_reasoning_tokens, 64);
+    assert_eq!(span.gen_ai_system.as_deref(), Some("openai"));
+    assert_eq!(span.gen_ai_request_model.as_deref(), Some("o4-mini"));
+}
+
+#[test]
+fn test_openai_responses_tool_output_input_items() {
+    use crate::domain::sideml::{ChatRole, ContentBlock, FinishReason, to_sideml_with_context};
+
+    // Follow-up request replays the call and supplies its output as input items
+    let request_json = r#"{
+        "model": "gpt-4.1",
+        "input": [
+            {"role": "user", "content": "Weather in Paris?"},
+            {"type": "function_call", "call_id": "call_1", "name": "get_weather", "arguments": "{}"},
+            {"type": "function_call_output", "call_id": "call_1", "output": "18C and sunny"}
+        ]
+    }"#;
+    let response_json = r#"{
+        "response": {
+            "status": "incomplete",
+            "output": [{"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "It is 18C"}]}],
+            "usage": {"input_tokens": 40, "output_tokens": 5}
+        }
+    }"#;
+    let attrs = make_attrs(&[
+        ("request_data", request_json),
+        ("response_data", response_json),
+    ]);
+
+    let mut messages = Vec::new();
+    assert!(try_openai_responses(
+        &mut messages,
+        &mut Vec::new(),
+        &attrs,
+        "",
+        Utc::now()
+    ));
+
+    let sideml = to_sideml_with_context(&messages, false);
+    let roles: Vec<ChatRole> = sideml.iter().map(|m| m.sideml.role).collect();
+    assert_eq!(
+        roles,
+        vec![
+            ChatRole::User,
+            ChatRole::Assistant,
+            ChatRole::Tool,
+            ChatRole::Assistant
+        ]
+    );
+    assert_eq!(sideml[2].sideml.tool_use_id.as_deref(), Some("call_1"));
+    assert!(matches!(
+        &sideml[1].sideml.content[0],
+        ContentBlock::ToolUse { id: Some(id), .. } if id == "call_1"
+    ));
+    assert_eq!(sideml[3].sideml.finish_reason, Some(FinishReason::Length));
+
+    // Usage nested in a wrapping `response` object
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "Responses API");
+    assert_eq!(span.gen_ai_usage_input_tokens, 40);
+    assert_eq!(span.gen_ai_usage_output_tokens, 5);
+}
+
+#[test]
+fn test_openai_responses_ignores_chat_completions_request() {
+    let attrs = make_attrs(&[(
+        "request_data",
+        r#"{"messages":[{"role":"user","content":"Hello"}],"input":"ignored"}"#,
+    )]);
+    let mut messages = Vec::new();
+    assert!(!try_openai_responses(
+        &mut messages,
+        &mut Vec::new(),
+        &attrs,
+        "",
+        Utc::now()
+    ));
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn test_openai_responses_ignores_embeddings_request() {
+    // Embeddings requests share the `{"model", "input"}` shape but carry no Responses marker
+    let attrs = make_attrs(&[
+        (
+            "request_data",
+            r#"{"model":"text-embedding-3-small","input":["first chunk","second chunk"]}"#,
+        ),
+        (
+            "response_data",
+            r#"{"object":"list","data":[{"object":"embedding","index":0,"embedding":[0.1]}],"usage":{"prompt_tokens":4,"total_tokens":4}}"#,
+        ),
+    ]);
+    let mut messages = Vec::new();
+    assert!(!try_openai_responses(
+        &mut messages,
+        &mut Vec::new(),
+        &attrs,
+        "",
+        Utc::now()
+    ));
+    assert!(messages.is_empty());
+
+    let mut span = SpanData::default();
+    extract_genai(&mut span, &attrs, "Embeddings");
+    assert_eq!(span.gen_ai_system, None);
+    assert_eq!(
+        span.gen_ai_request_model.as_deref(),
+        Some("text-embedding-3-small")
+    );
+}
+
 #[test]
 fn test_logfire_events_take_precedence() {
     // When events attribute is present, request_data/response_data should be skipped
@@ -6286,3 +7150,276 @@ fn test_logfire_streaming_response() {
     );
     assert!(messages[0].content.get("combined_chunk_content").is_some());
 }
+
+#[test]
+fn test_http_get_span_produces_request_marker() {
+    let attrs = make_attrs(&[
+        ("http.request.method", "GET"),
+        ("url.full", "https://api.example.com/v1/weather?city=Paris"),
+        ("http.response.status_code", "200"),
+    ]);
+    let mut span = SpanData::default();
+    extract_semantic(&mut span, &attrs);
+
+    let marker = extract_side_effect_marker(&span, Utc::now(), &[], true).expect("http marker");
+    assert_eq!(marker.content["role"], "context");
+    assert_eq!(marker.content["type"], "http_request");
+    assert_eq!(
+        marker.content["content"],
+        json!({
+            "method": "GET",
+            "url": "https://api.example.com/v1/weather?city=Paris",
+            "status_code": 200
+        })
+    );
+
+    // Query strings are stripped unless kept explicitly
+    let stripped = extract_side_effect_marker(&span, Utc::now(), &[], false).unwrap();
+    assert_eq!(
+        stripped.content["content"]["url"],
+        "https://api.example.com/v1/weather"
+    );
+
+    // Denylisted URLs keep the marker but drop the URL, matching the query too
+    let denylist = vec!["city=".to_string()];
+    let redacted = extract_side_effect_marker(&span, Utc::now(), &denylist, false).unwrap();
+    assert_eq!(
+        redacted.content["content"],
+        json!({"method": "GET", "redacted": true, "status_code": 200})
+    );
+}
+
+#[test]
+fn test_db_query_span_produces_query_marker() {
+    let attrs = make_attrs(&[
+        ("db.system", "postgresql"),
+        ("db.name", "orders"),
+        ("db.operation", "SELECT"),
+        (
+            "db.statement",
+            "SELECT id, total FROM orders WHERE user_id = $1",
+        ),
+    ]);
+    let mut span = SpanData::default();
+    extract_semantic(&mut span, &attrs);
+
+    let marker = extract_side_effect_marker(&span, Utc::now(), &[], false).expect("db marker");
+    assert_eq!(marker.content["type"], "db_query");
+    assert_eq!(
+        marker.content["content"],
+        json!({
+            "system": "postgresql",
+            "name": "orders",
+            "operation": "SELECT",
+            "statement": "SELECT id, total FROM orders WHERE user_id = $1"
+        })
+    );
+
+    // Spans without side-effect attributes produce no marker
+    assert!(extract_side_effect_marker(&SpanData::default(), Utc::now(), &[], false).is_none());
+}
+
+#[test]
+fn test_streamed_tool_call_argument_deltas_are_reassembled() {
+    use crate::domain::sideml::{ContentBlock, to_sideml_with_context};
+
+    let delta = |offset: u64, tool_calls: &str| Event {
+        name: "gen_ai.choice".to_string(),
+        time_unix_nano: 1767099299293126000 + offset,
+        attributes: vec![make_kv("tool_calls", tool_calls)],
+        dropped_attributes_count: 0,
+    };
+    let mut span = make_attr_span("chat", &[]);
+    span.events = vec![
+        delta(
+            0,
+            r#"[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":""}}]"#,
+        ),
+        delta(
+            1,
+            r#"[{"index":0,"function":{"arguments":"{\"city\": \"Pa"}}]"#,
+        ),
+        delta(
+            2,
+            r#"[{"index":0,"id":"call_1","function":{"arguments":"ris\", \"days\": 3}"}}]"#,
+        ),
+        // A call that never completes keeps its raw fragment
+        delta(
+            3,
+            r#"[{"index":1,"id":"call_2","type":"function","function":{"name":"search","arguments":"{\"q\": \"ru"}}]"#,
+        ),
+    ];
+
+    let (raw, _, _) = extract_messages_for_span(&span, &HashMap::new(), Utc::now());
+    assert_eq!(raw.len(), 2, "delta-only events are folded into the first");
+    assert_eq!(
+        raw[0].content["tool_calls"][0]["function"]["arguments"],
+        json!({"city": "Paris", "days": 3})
+    );
+    assert_eq!(
+        raw[1].content["tool_calls"][0]["function"]["arguments"],
+        json!("{\"q\": \"ru")
+    );
+
+    let sideml = to_sideml_with_context(&raw, false);
+    assert!(matches!(
+        &sideml[0].sideml.content[..],
+        [ContentBlock::ToolUse { id, name, input }]
+            if id.as_deref() == Some("call_1")
+                && name == "get_weather"
+                && input == &json!({"city": "Paris", "days": 3})
+    ));
+}
+
+#[test]
+fn test_streamed_tool_call_deltas_stay_within_their_turn() {
+    let delta = |offset: u64, name: &str, attr: (&str, &str)| Event {
+        name: name.to_string(),
+        time_unix_nano: 1767099299293126000 + offset,
+        attributes: vec![make_kv(attr.0, attr.1)],
+        dropped_attributes_count: 0,
+    };
+    let choice =
+        |offset: u64, tool_calls: &str| delta(offset, "gen_ai.choice", ("tool_calls", tool_calls));
+    let mut span = make_attr_span("chat", &[]);
+    span.events = vec![
+        // Turn 1: arguments complete after one delta, the stray fragment is not appended
+        choice(
+            0,
+            r#"[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":"{\"city\": "}}]"#,
+        ),
+        choice(1, r#"[{"index":0,"function":{"arguments":"\"Paris\"}"}}]"#),
+        choice(2, r#"[{"index":0,"function":{"arguments":"{}"}}]"#),
+        delta(3, "gen_ai.tool.message", ("content", "sunny")),
+        // Turn 2: index 0 belongs to the new call
+        choice(
+            4,
+            r#"[{"index":0,"id":"call_2","type":"function","function":{"name":"get_time","arguments":"{\"tz\": "}}]"#,
+        ),
+        choice(5, r#"[{"index":0,"function":{"arguments":"\"CET\"}"}}]"#),
+    ];
+
+    let (raw, _, _) = extract_messages_for_span(&span, &HashMap::new(), Utc::now());
+    let calls: Vec<_> = raw
+        .iter()
+        .filter_map(|m| m.content.get("tool_calls"))
+        .flat_map(|c| c.as_array().cloned().unwrap_or_default())
+        .filter(|c| c.get("id").is_some())
+        .map(|c| (c["id"].clone(), c["function"]["arguments"].clone()))
+        .collect();
+    assert_eq!(
+        calls,
+        vec![
+            (json!("call_1"), json!({"city": "Paris"})),
+            (json!("call_2"), json!({"tz": "CET"})),
+        ]
+    );
+}
+
+#[test]
+fn test_redact_tool_results_for_configured_tools() {
+    let now = Utc::now();
+    let mut raw = vec![
+        RawMessage::from_attr(
+            "gen_ai.input.messages",
+            now,
+            json!({
+                "role": "assistant",
+                "tool_calls": [
+                    {"id": "call_1", "type": "function", "function": {"name": "read_secret", "arguments": "{\"key\": \"db\"}"}},
+                    {"id": "call_2", "type": "function", "function": {"name": "get_weather", "arguments": "{\"city\": \"Paris\"}"}}
+                ]
+            }),
+        ),
+        RawMessage::from_attr(
+            "gen_ai.input.messages",
+            now,
+            json!({"role": "tool", "tool_call_id": "call_1", "content": "hunter2"}),
+        ),
+        RawMessage::from_attr(
+            "gen_ai.input.messages",
+            now,
+            json!({"role": "tool", "tool_call_id": "call_2", "content": "sunny"}),
+        ),
+        // Anthropic blocks resolve the name through the matching tool_use
+        RawMessage::from_attr(
+            "gen_ai.input.messages",
+            now,
+            json!({"role": "user", "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "Get_Credentials", "input": {}},
+                {"type": "tool_result", "tool_use_id": "toolu_1", "content": "AKIA..."}
+            ]}),
+        ),
+    ];
+
+    let patterns = vec!["read_secret".to_string(), "get_cred*".to_string()];
+    redact_tool_results(&mut raw, None, &patterns);
+
+    assert_eq!(raw[1].content["content"], REDACTED_TOOL_OUTPUT);
+    assert_eq!(raw[2].content["content"], "sunny");
+    assert_eq!(
+        raw[3].content["content"][1]["content"],
+        REDACTED_TOOL_OUTPUT
+    );
+    // Tool calls themselves are preserved
+    assert_eq!(
+        raw[0].content["tool_calls"][0]["function"]["arguments"],
+        "{\"key\": \"db\"}"
+    );
+    assert_eq!(raw[3].content["content"][0]["name"], "Get_Credentials");
+
+    // Tool execution spans fall back to the span's tool name
+    let mut tool_span = vec![RawMessage::from_attr(
+        "gen_ai.tool.call.result",
+        now,
+        json!({"role": "tool", "content": {"password": "hunter2"}}),
+    )];
+    redact_tool_results(&mut tool_span, Some("read_secret"), &patterns);
+    assert_eq!(tool_span[0].content["content"], REDACTED_TOOL_OUTPUT);
+}
+
+#[test]
+fn test_malformed_message_attributes_are_reported() {
+    let attrs = make_attrs(&[
+        (
+            keys::GEN_AI_INPUT_MESSAGES,
+            r#"[{"role": "user", "content": "cut o"#,
+        ),
+        (
+            keys::GEN_AI_OUTPUT_MESSAGES,
+            r#"[{"role": "assistant", "content": "ok"}]"#,
+        ),
+    ]);
+
+    let warnings = malformed_message_warnings(&attrs);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, IngestWarningKind::ParseError);
+    assert!(
+        warnings[0]
+            .message
+            .starts_with("Failed to parse gen_ai.input.messages")
+    );
+
+    assert!(malformed_message_warnings(&HashMap::new()).is_empty());
+}
+
+#[test]
+fn test_shift_event_times_moves_only_event_messages() {
+    let start = Utc::now();
+    let event_time = start + chrono::TimeDelta::hours(1) + chrono::TimeDelta::seconds(2);
+    let mut messages = vec![
+        RawMessage::from_event("gen_ai.user.message", event_time, json!({"content": "hi"})),
+        RawMessage::from_attr(keys::GEN_AI_INPUT_MESSAGES, start, json!([])),
+    ];
+
+    shift_event_times(&mut messages, chrono::TimeDelta::hours(1));
+
+    assert!(matches!(
+        &messages[0].source,
+        MessageSource::Event { time, .. } if *time == start + chrono::TimeDelta::seconds(2)
+    ));
+    assert!(matches!(
+        &messages[1].source,
+        MessageSource::Attribute { time, .. } if *time == start
+    ));
+}
@@ -37,11 +37,13 @@ mod messages;
 
 use std::collections::HashMap;
 
+use chrono::{TimeDelta, Utc};
 use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
 use serde_json::Value as JsonValue;
 
+use crate::core::config::IngestionConfig;
 use crate::core::constants;
-use crate::utils::otlp::extract_attributes;
+use crate::utils::otlp::{any_value_to_string, extract_attributes};
 
 // ============================================================================
 // SHARED HELPER FUNCTIONS
@@ -67,8 +69,28 @@ pub(super) fn extract_json<T: serde::de::DeserializeOwned>(
     attrs.get(key).and_then(|s| serde_json::from_str(s).ok())
 }
 
+/// Whether a Logfire-style `request_data` with `input` is an OpenAI Responses
+/// call. Embeddings requests also carry `{"model", "input"}`, so a Responses
+/// marker is required: request `instructions`, or a response that is
+/// `object: "response"` or has `output` (bare or wrapped in `response`).
+pub(super) fn is_openai_responses(request: &JsonValue, response: Option<&JsonValue>) -> bool {
+    if request.get("messages").is_some() || request.get("input").is_none() {
+        return false;
+    }
+    request.get("instructions").is_some()
+        || response.is_some_and(|r| {
+            r.get("object").and_then(|o| o.as_str()) == Some("response")
+                || r.get("output").is_some()
+                || r.get("response")
+                    .is_some_and(|inner| inner.get("output").is_some())
+        })
+}
+
 // Re-export public types
 pub use self::attributes::SpanData;
+pub(super) use self::attributes::parse_observation_type;
+pub(super) use self::messages::redact_raw_span_tool_results;
+pub(crate) use self::messages::redact_span_events;
 pub use self::messages::{MessageSource, RawMessage, RawToolDefinition, RawToolNames};
 
 // ============================================================================
@@ -104,6 +126,11 @@ pub(super) mod keys {
     pub const GEN_AI_FREQUENCY_PENALTY: &str = "gen_ai.request.frequency_penalty";
     pub const GEN_AI_PRESENCE_PENALTY: &str = "gen_ai.request.presence_penalty";
     pub const GEN_AI_STOP_SEQUENCES: &str = "gen_ai.request.stop_sequences";
+    pub const GEN_AI_ENCODING_FORMATS: &str = "gen_ai.request.encoding_formats";
+    pub const GEN_AI_REQUEST_DIMENSIONS: &str = "gen_ai.request.dimensions";
+    pub const GEN_AI_EMBEDDINGS_DIMENSION_COUNT: &str = "gen_ai.embeddings.dimension.count";
+    pub const GEN_AI_REASONING_EFFORT: &str = "gen_ai.request.reasoning_effort";
+    pub const GEN_AI_VERBOSITY: &str = "gen_ai.request.verbosity";
     pub const GEN_AI_FINISH_REASONS: &str = "gen_ai.response.finish_reasons";
 
     // GenAI Agent/Tool
@@ -151,9 +178,12 @@ pub(super) mod keys {
     pub const LLM_COST_TOTAL: &str = "llm.cost.total";
     pub const LLM_COST_PROMPT: &str = "llm.cost.prompt";
     pub const LLM_COST_COMPLETION: &str = "llm.cost.completion";
+    /// Final cost attached by gateways (e.g. LiteLLM proxy) at their negotiated rates
+    pub const GEN_AI_USAGE_COST: &str = "gen_ai.usage.cost";
 
     // OpenInference Embedding attributes
     pub const EMBEDDING_TEXT: &str = "embedding.text";
+    pub const EMBEDDING_EMBEDDINGS: &str = "embedding.embeddings";
     pub const EMBEDDING_MODEL_NAME: &str = "embedding.model_name";
 
     // OpenInference Reranker attributes
@@ -221,6 +251,7 @@ pub(super) mod keys {
     // OTEL Standard GenAI Messages
     pub const GEN_AI_INPUT_MESSAGES: &str = "gen_ai.input.messages";
     pub const GEN_AI_OUTPUT_MESSAGES: &str = "gen_ai.output.messages";
+    pub const GEN_AI_OUTPUT_TYPE: &str = "gen_ai.output.type";
     pub const GEN_AI_TOOL_CALL_ARGUMENTS: &str = "gen_ai.tool.call.arguments";
     pub const GEN_AI_TOOL_CALL_RESULT: &str = "gen_ai.tool.call.result";
 
@@ -303,8 +334,16 @@ pub(super) mod keys {
 /// Extract span attributes from an OTLP trace request.
 ///
 /// Pipeline Step 1a: Parses protobuf, extracts GenAI attributes, and classifies spans.
-pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> Vec<SpanData> {
+pub(super) fn extract_attributes_batch(
+    request: &ExportTraceServiceRequest,
+    ingestion: &IngestionConfig,
+) -> Vec<SpanData> {
     let mut spans = Vec::new();
+    let now = Utc::now();
+    let max_future_skew = i64::try_from(ingestion.max_future_skew_secs)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX);
 
     for resource_spans in &request.resource_spans {
         let resource_attrs = resource_spans
@@ -320,6 +359,7 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
 
                 // Core OTLP fields
                 attributes::set_core_fields(&mut span, otlp_span);
+                attributes::clamp_future_timestamps(&mut span, now, max_future_skew);
 
                 // Resolve display name from attributes when the raw span name
                 // is a template or otherwise not human-readable
@@ -337,16 +377,18 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
 
                 // Extract GenAI attributes
                 attributes::extract_genai(&mut span, &span_attrs, &otlp_span.name);
+                attributes::apply_model_aliases(&mut span, &ingestion.model_aliases);
+                attributes::extract_streaming_usage(&mut span, &otlp_span.events);
 
                 // Extract finish_reason from various sources if not already set
                 if span.gen_ai_finish_reasons.is_empty() {
-                    // 1. Try gen_ai.choice event (Strands, OpenTelemetry GenAI)
+                    // 1. Try gen_ai.choice events (Strands, OpenTelemetry GenAI),
+                    //    one per choice when n > 1
                     for event in &otlp_span.events {
                         if event.name == "gen_ai.choice" {
                             let event_attrs = extract_attributes(&event.attributes);
                             if let Some(reason) = event_attrs.get("finish_reason") {
-                                span.gen_ai_finish_reasons = vec![reason.clone()];
-                                break;
+                                span.gen_ai_finish_reasons.push(reason.clone());
                             }
                         }
                     }
@@ -370,7 +412,7 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
                 }
 
                 if span.gen_ai_finish_reasons.is_empty() {
-                    // 3. Try gen_ai.output.messages JSON (PydanticAI)
+                    // 3. Try gen_ai.output.messages JSON (PydanticAI), one per output message
                     if let Some(output_msgs) = span_attrs.get(keys::GEN_AI_OUTPUT_MESSAGES) {
                         if let Ok(msgs) = serde_json::from_str::<Vec<JsonValue>>(output_msgs) {
                             for msg in &msgs {
@@ -378,7 +420,6 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
                                     msg.get("finish_reason").and_then(|r| r.as_str())
                                 {
                                     span.gen_ai_finish_reasons.push(reason.to_string());
-                                    break;
                                 }
                             }
                         }
@@ -418,6 +459,7 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
                 span.observation_type = Some(attributes::detect_observation_type(
                     &otlp_span.name,
                     &span_attrs,
+                    &ingestion.observation_type_patterns,
                 ));
                 span.span_category =
                     Some(attributes::categorize_span(&otlp_span.name, &span_attrs));
@@ -492,6 +534,11 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
                     // OTEL SDKs propagate error status up the span tree, so every
                     // ancestor gets status_message. Only exception events and
                     // gen_ai.tool.status carry real error details for feed display.
+
+                    attributes::downgrade_non_error_exception(
+                        &mut span,
+                        &ingestion.non_error_exception_types,
+                    );
                 }
 
                 // Metadata
@@ -508,6 +555,54 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
     spans
 }
 
+/// Drop spans whose detected framework and resource `telemetry.sdk.name` are
+/// both missing from the allowlist (lowercase entries, matched case-insensitively).
+///
+/// Later stages walk the request alongside `spans`, so the returned per-OTLP-span
+/// keep mask (request order) tells them which spans to skip. Returns None when
+/// every span is kept.
+pub(super) fn retain_allowed_frameworks(
+    request: &ExportTraceServiceRequest,
+    spans: &mut Vec<SpanData>,
+    allowlist: &[String],
+) -> Option<Vec<bool>> {
+    let allowed = |name: &str| allowlist.contains(&name.to_ascii_lowercase());
+    let mut keep = Vec::with_capacity(spans.len());
+    let mut span_idx = 0;
+    for resource_spans in &request.resource_spans {
+        let sdk_allowed = resource_spans
+            .resource
+            .as_ref()
+            .and_then(|r| {
+                r.attributes
+                    .iter()
+                    .find(|kv| kv.key == keys::TELEMETRY_SDK_NAME)
+            })
+            .and_then(|kv| kv.value.as_ref())
+            .is_some_and(|v| allowed(&any_value_to_string(v)));
+        for scope_spans in &resource_spans.scope_spans {
+            for _ in &scope_spans.spans {
+                let framework = spans[span_idx].framework.unwrap_or_default();
+                keep.push(sdk_allowed || allowed(framework.as_str()));
+                span_idx += 1;
+            }
+        }
+    }
+    if keep.iter().all(|k| *k) {
+        return None;
+    }
+
+    let mut flags = keep.iter();
+    spans.retain(|_| *flags.next().unwrap_or(&true));
+    Some(keep)
+}
+
+/// Advance a keep mask walk; true when the next OTLP span was kept.
+pub(super) fn next_kept(mask: &mut Option<std::slice::Iter<'_, bool>>) -> bool {
+    mask.as_mut()
+        .is_none_or(|flags| flags.next().copied().unwrap_or(true))
+}
+
 // ============================================================================
 // PIPELINE STEP 1b: MESSAGE EXTRACTION
 // ============================================================================
@@ -516,12 +611,15 @@ pub(super) fn extract_attributes_batch(request: &ExportTraceServiceRequest) -> V
 ///
 /// Pipeline Step 1b: Extracts raw messages and tool definitions from OTEL events and span attributes.
 /// Should be called after `extract_attributes_batch` with the corresponding spans.
+/// OTLP spans dropped by `keep` (see `retain_allowed_frameworks`) are skipped.
 ///
 /// Returns a tuple of (messages, tool_definitions, tool_names) where each inner Vec corresponds to a span.
 #[allow(clippy::type_complexity)]
 pub(super) fn extract_messages_batch(
     request: &ExportTraceServiceRequest,
-    spans: &[SpanData],
+    keep: Option<&[bool]>,
+    spans: &mut [SpanData],
+    ingestion: &IngestionConfig,
 ) -> (
     Vec<Vec<RawMessage>>,
     Vec<Vec<RawToolDefinition>>,
@@ -531,20 +629,44 @@ pub(super) fn extract_messages_batch(
     let mut all_tool_definitions = Vec::new();
     let mut all_tool_names = Vec::new();
     let mut span_idx = 0;
+    let mut keep = keep.map(<[bool]>::iter);
 
     for resource_spans in &request.resource_spans {
         for scope_spans in &resource_spans.scope_spans {
             for otlp_span in &scope_spans.spans {
+                if !next_kept(&mut keep) {
+                    continue;
+                }
                 let span_attrs = extract_attributes(&otlp_span.attributes);
-                let span = &spans[span_idx];
+                let span = &mut spans[span_idx];
                 span_idx += 1;
 
-                let (raw_messages, tool_definitions, tool_names) =
+                let (mut raw_messages, tool_definitions, tool_names) =
                     messages::extract_messages_for_span(
                         otlp_span,
                         &span_attrs,
                         span.timestamp_start,
                     );
+                if span.clock_shift > TimeDelta::zero() {
+                    messages::shift_event_times(&mut raw_messages, span.clock_shift);
+                }
+                if raw_messages.is_empty()
+                    && let Some(marker) = messages::extract_side_effect_marker(
+                        span,
+                        span.timestamp_start,
+                        &ingestion.marker_denylist,
+                        ingestion.marker_keep_url_query,
+                    )
+                {
+                    raw_messages.push(marker);
+                }
+                messages::redact_tool_results(
+                    &mut raw_messages,
+                    span.gen_ai_tool_name.as_deref(),
+                    &ingestion.redact_tool_results,
+                );
+                span.ingest_warnings
+                    .extend(messages::malformed_message_warnings(&span_attrs));
                 all_messages.push(raw_messages);
                 all_tool_definitions.push(tool_definitions);
                 all_tool_names.push(tool_names);
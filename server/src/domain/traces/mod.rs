@@ -6,18 +6,30 @@
 //! - `enrich` - Stage 3: Cost calculation and preview extraction
 //! - `persist` - Stage 4: Build raw span JSON, SSE publishing, DuckDB writes
 //! - `pipeline` - Pipeline orchestrator
+//! - `notify` - Processed-span stream bridged to per-project SSE broadcasts
+//! - `token_rate` - Per-session token rate alerts for runaway agents
+//! - `replay` - In-memory OTLP-to-feed replay (tests only)
 //!
 //! Note: Stage 2 (SideML) is in the `domain::sideml` module.
 
 mod enrich;
 mod extract;
+mod notify;
 mod persist;
 mod pipeline;
+#[cfg(test)]
+pub(crate) mod replay;
+mod token_rate;
+mod trace_completion;
 
 // Public API - only types needed by external modules
 pub use extract::{MessageSource, RawMessage};
+pub use notify::NotificationBridge;
 pub use persist::SseSpanEvent;
 pub use pipeline::TracePipeline;
+pub use token_rate::TokenRateAlert;
+pub use trace_completion::TraceComplete;
 
 // Internal re-exports for use within domain crate
 pub(crate) use extract::SpanData;
+pub(crate) use extract::redact_span_events;
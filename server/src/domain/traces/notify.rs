@@ -0,0 +1,364 @@
+//! Processed-span notifications for UI fan-out
+//!
+//! Decouples the trace pipeline from per-project SSE broadcasts:
+//!
+//! ```text
+//! TracePipeline ──▶ processed_traces (stream) ──▶ NotificationBridge ──▶ sse_spans:{project_id} (broadcast)
+//! ```
+//!
+//! After a successful DuckDB write the pipeline publishes one lightweight
+//! `ProcessedSpans` message per batch. The bridge consumes it through a consumer
+//! group, so each message is republished once across all instances, and acks it
+//! after the broadcast publish. Messages left pending by a crashed instance are
+//! claimed on the same schedule as the trace pipeline.
+//!
+//! The in-memory backend gains nothing from the extra hop (stream and broadcast
+//! share the process), so `notify_processed` broadcasts directly unless the
+//! backend reports durable streams.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prost::Message as _;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use super::persist::{SseSpanEvent, publish_sse_events};
+use super::pipeline::{CLAIM_INTERVAL_SECS, CLAIM_MAX_COUNT, CLAIM_MIN_IDLE_MS, consumer_name};
+use crate::core::constants::TOPIC_PROCESSED_TRACES;
+use crate::core::{TopicMessage, TopicService};
+use crate::data::topics::{StreamAcker, StreamClaimer, StreamTopic, TopicError};
+
+/// Consumer group for the notification bridge
+const CONSUMER_GROUP: &str = "sse_bridge";
+
+/// Spans persisted by one pipeline batch
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProcessedSpans {
+    #[prost(message, repeated, tag = "1")]
+    pub spans: Vec<ProcessedSpan>,
+}
+
+/// Identifiers of a single persisted span
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProcessedSpan {
+    #[prost(string, optional, tag = "1")]
+    pub project_id: Option<String>,
+    #[prost(string, tag = "2")]
+    pub trace_id: String,
+    #[prost(string, tag = "3")]
+    pub span_id: String,
+    #[prost(string, optional, tag = "4")]
+    pub session_id: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub user_id: Option<String>,
+}
+
+impl TopicMessage for ProcessedSpans {
+    fn size_bytes(&self) -> usize {
+        prost::Message::encoded_len(self)
+    }
+}
+
+impl From<&SseSpanEvent> for ProcessedSpan {
+    fn from(event: &SseSpanEvent) -> Self {
+        Self {
+            project_id: event.project_id.clone(),
+            trace_id: event.trace_id.clone(),
+            span_id: event.span_id.clone(),
+            session_id: event.session_id.clone(),
+            user_id: event.user_id.clone(),
+        }
+    }
+}
+
+impl From<ProcessedSpan> for SseSpanEvent {
+    fn from(span: ProcessedSpan) -> Self {
+        Self {
+            project_id: span.project_id,
+            trace_id: span.trace_id,
+            span_id: span.span_id,
+            session_id: span.session_id,
+            user_id: span.user_id,
+        }
+    }
+}
+
+/// Announce persisted spans to UI subscribers.
+///
+/// Routes through the processed-trace stream when the backend has durable
+/// streams, falling back to a direct broadcast if the stream publish fails.
+pub(super) async fn notify_processed(events: &[SseSpanEvent], topics: &TopicService) {
+    if events.is_empty() {
+        return;
+    }
+    if !topics.capabilities().durable_streams {
+        publish_sse_events(events, topics).await;
+        return;
+    }
+
+    let msg = ProcessedSpans {
+        spans: events.iter().map(ProcessedSpan::from).collect(),
+    };
+    let topic = topics.stream_topic::<ProcessedSpans>(TOPIC_PROCESSED_TRACES);
+    if let Err(e) = topic.publish(&msg).await {
+        tracing::warn!(error = %e, "Failed to publish processed spans, broadcasting directly");
+        publish_sse_events(events, topics).await;
+    }
+}
+
+/// Republishes processed-trace stream messages to per-project broadcast topics.
+///
+/// Notifications are best-effort: a message is acked once its broadcast publish
+/// was attempted, since redelivering a stale UI notification has no value.
+pub struct NotificationBridge {
+    topics: Arc<TopicService>,
+    worker_id: Option<String>,
+}
+
+impl NotificationBridge {
+    pub fn new(topics: Arc<TopicService>, worker_id: Option<String>) -> Self {
+        Self { topics, worker_id }
+    }
+
+    /// Spawn the bridge task. Stops on shutdown signal or when the stream closes.
+    pub fn start(
+        self,
+        topic: StreamTopic<ProcessedSpans>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        let consumer = consumer_name(self.worker_id.as_deref());
+
+        tokio::spawn(async move {
+            let mut subscriber = match topic.subscribe(CONSUMER_GROUP, &consumer).await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to subscribe to processed trace topic");
+                    return;
+                }
+            };
+            let acker = subscriber.acker();
+            let claimer = subscriber.claimer();
+
+            tracing::debug!(
+                consumer = %consumer,
+                group = CONSUMER_GROUP,
+                "NotificationBridge started"
+            );
+
+            let mut claim_interval =
+                tokio::time::interval(Duration::from_secs(CLAIM_INTERVAL_SECS));
+            claim_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = claim_interval.tick() => {
+                        self.claim_stuck_messages(&claimer, &acker, &consumer, CLAIM_MIN_IDLE_MS)
+                            .await;
+                    }
+                    result = subscriber.recv() => match result {
+                        Ok((msg_id, msg)) => {
+                            let events: Vec<SseSpanEvent> =
+                                msg.spans.into_iter().map(SseSpanEvent::from).collect();
+                            publish_sse_events(&events, &self.topics).await;
+                            if let Err(e) = acker.ack(&msg_id).await {
+                                tracing::warn!(error = %e, msg_id = %msg_id, "Failed to ack processed spans");
+                            }
+                        }
+                        Err(TopicError::Lagged(n)) => {
+                            tracing::warn!(lagged = n, "NotificationBridge lagged");
+                        }
+                        Err(TopicError::ChannelClosed) => break,
+                        Err(e) => {
+                            tracing::error!(error = %e, "NotificationBridge receive error");
+                            break;
+                        }
+                    }
+                }
+            }
+
+            tracing::debug!("NotificationBridge stopped");
+        })
+    }
+
+    /// Republish and ack notifications left pending by other (possibly crashed) consumers.
+    async fn claim_stuck_messages(
+        &self,
+        claimer: &StreamClaimer,
+        acker: &StreamAcker,
+        consumer: &str,
+        min_idle_ms: u64,
+    ) {
+        let messages = match claimer.claim(consumer, min_idle_ms, CLAIM_MAX_COUNT).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to claim stuck processed spans");
+                return;
+            }
+        };
+        if messages.is_empty() {
+            return;
+        }
+        tracing::debug!(count = messages.len(), "Claimed stuck processed spans");
+
+        for msg in messages {
+            match ProcessedSpans::decode(&msg.payload[..]) {
+                Ok(decoded) => {
+                    let events: Vec<SseSpanEvent> =
+                        decoded.spans.into_iter().map(SseSpanEvent::from).collect();
+                    publish_sse_events(&events, &self.topics).await;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, msg_id = %msg.id, "Failed to decode claimed processed spans, acking to discard");
+                }
+            }
+            if let Err(e) = acker.ack(&msg.id).await {
+                tracing::warn!(error = %e, msg_id = %msg.id, "Failed to ack claimed processed spans");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bridge_broadcasts_and_acks() {
+        let topics = Arc::new(TopicService::new());
+        let mut sse = topics
+            .broadcast_topic::<SseSpanEvent>("sse_spans:p1")
+            .subscribe()
+            .await
+            .unwrap();
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = NotificationBridge::new(topics.clone(), Some("bridge-test".to_string()))
+            .start(topics.stream_topic(TOPIC_PROCESSED_TRACES), shutdown_rx);
+
+        let stream = topics.stream_topic::<ProcessedSpans>(TOPIC_PROCESSED_TRACES);
+        stream
+            .publish(&ProcessedSpans {
+                spans: vec![ProcessedSpan {
+                    project_id: Some("p1".to_string()),
+                    trace_id: "t1".to_string(),
+                    span_id: "s1".to_string(),
+                    session_id: None,
+                    user_id: None,
+                }],
+            })
+            .await
+            .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(1), sse.recv())
+            .await
+            .expect("broadcast not received")
+            .unwrap();
+        assert_eq!(event.trace_id, "t1");
+        assert_eq!(event.span_id, "s1");
+
+        // Delivered to the bridge, then acked after the broadcast
+        assert!(
+            stream
+                .last_delivered(CONSUMER_GROUP, "bridge-test")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        let mut pending = u64::MAX;
+        for _ in 0..50 {
+            pending = topics
+                .stream_stats(TOPIC_PROCESSED_TRACES, CONSUMER_GROUP)
+                .await
+                .unwrap()
+                .pending;
+            if pending == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(pending, 0);
+
+        shutdown_tx.send(true).unwrap();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bridge_claims_pending_from_other_consumer() {
+        let topics = Arc::new(TopicService::new());
+        let mut sse = topics
+            .broadcast_topic::<SseSpanEvent>("sse_spans:p1")
+            .subscribe()
+            .await
+            .unwrap();
+
+        let stream = topics.stream_topic::<ProcessedSpans>(TOPIC_PROCESSED_TRACES);
+        // A consumer that reads but never acks (crashed instance)
+        let mut crashed = stream.subscribe(CONSUMER_GROUP, "crashed").await.unwrap();
+        stream
+            .publish(&ProcessedSpans {
+                spans: vec![ProcessedSpan {
+                    project_id: Some("p1".to_string()),
+                    trace_id: "t1".to_string(),
+                    span_id: "s1".to_string(),
+                    session_id: None,
+                    user_id: None,
+                }],
+            })
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(1), crashed.recv())
+            .await
+            .expect("message not delivered")
+            .unwrap();
+
+        let claiming = stream.subscribe(CONSUMER_GROUP, "survivor").await.unwrap();
+        let bridge = NotificationBridge::new(topics.clone(), None);
+        bridge
+            .claim_stuck_messages(&claiming.claimer(), &claiming.acker(), "survivor", 0)
+            .await;
+
+        let event = tokio::time::timeout(Duration::from_secs(1), sse.recv())
+            .await
+            .expect("claimed broadcast not received")
+            .unwrap();
+        assert_eq!(event.span_id, "s1");
+        let stats = topics
+            .stream_stats(TOPIC_PROCESSED_TRACES, CONSUMER_GROUP)
+            .await
+            .unwrap();
+        assert_eq!(stats.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn test_notify_broadcasts_directly_without_durable_streams() {
+        let topics = TopicService::new();
+        assert!(!topics.capabilities().durable_streams);
+        let mut sse = topics
+            .broadcast_topic::<SseSpanEvent>("sse_spans:p1")
+            .subscribe()
+            .await
+            .unwrap();
+
+        let event = SseSpanEvent {
+            project_id: Some("p1".to_string()),
+            trace_id: "t1".to_string(),
+            span_id: "s1".to_string(),
+            session_id: None,
+            user_id: None,
+        };
+        notify_processed(&[event], &topics).await;
+
+        let received = tokio::time::timeout(Duration::from_secs(1), sse.recv())
+            .await
+            .expect("broadcast not received")
+            .unwrap();
+        assert_eq!(received.span_id, "s1");
+    }
+}
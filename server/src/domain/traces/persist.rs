@@ -15,6 +15,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use base64::prelude::*;
+use chrono::{DateTime, TimeDelta, Utc};
 use futures::stream::StreamExt;
 use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
 use opentelemetry_proto::tonic::trace::v1::Span;
@@ -25,7 +26,9 @@ use super::enrich::SpanEnrichment;
 use super::extract::files::{
     ExtractedFile, FileExtractionCache, extract_and_replace_files, extract_and_replace_files_cached,
 };
-use super::extract::{RawMessage, RawToolDefinition, RawToolNames, SpanData};
+use super::extract::{
+    RawMessage, RawToolDefinition, RawToolNames, SpanData, next_kept, redact_raw_span_tool_results,
+};
 use crate::core::constants::{
     DEFAULT_PROJECT_ID, FILE_HASH_ALGORITHM, FILES_MAX_CONCURRENT_FINALIZATION,
 };
@@ -33,6 +36,7 @@ use crate::core::{TopicMessage, TopicService};
 use crate::data::AnalyticsService;
 use crate::data::files::FileService;
 use crate::data::types::{NormalizedSpan, json_to_pre_serialized};
+use crate::utils::compress::compress_json;
 use crate::utils::otlp::{build_attributes_json, extract_attributes};
 use crate::utils::retry::{DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_ATTEMPTS, retry_with_backoff_async};
 use crate::utils::time::nanos_to_iso;
@@ -113,7 +117,9 @@ pub(super) struct BatchInput {
 /// The actual file I/O (temp write, metadata upsert, finalization) is deferred.
 pub(super) fn prepare_batch(
     request: &ExportTraceServiceRequest,
+    keep: Option<&[bool]>,
     input: BatchInput,
+    redact_tool_results: &[String],
     files_enabled: bool,
     file_cache: Option<&FileExtractionCache>,
 ) -> (Vec<NormalizedSpan>, Vec<PendingFileWrite>) {
@@ -133,11 +139,13 @@ pub(super) fn prepare_batch(
     // BEFORE serialization, eliminating the serialize→deserialize→re-serialize round-trip.
     let (db_spans, raw_span_files) = flatten(
         request,
+        keep,
         input.spans,
         processed_messages,
         input.tool_definitions,
         input.tool_names,
         input.enrichments,
+        redact_tool_results,
         files_enabled,
         file_cache,
     );
@@ -146,6 +154,17 @@ pub(super) fn prepare_batch(
     (db_spans, pending_files)
 }
 
+/// Compress the stored message and tool definition JSON of each span.
+///
+/// Runs after file extraction so only `#!B64!#` references are compressed.
+/// Empty arrays stay plain so content filters on `'[]'` keep working.
+pub(super) fn compress_stored_json(spans: &mut [NormalizedSpan]) {
+    for span in spans {
+        span.messages = span.messages.take().map(compress_json);
+        span.tool_definitions = span.tool_definitions.take().map(compress_json);
+    }
+}
+
 // ============================================================================
 // SSE PUBLISHING
 // ============================================================================
@@ -525,11 +544,41 @@ pub(super) async fn write_to_duckdb(
 ) -> bool {
     let span_count = spans.len();
     let repo = analytics.repository();
+    persist_with_retry(span_count, || repo.insert_spans(spans.clone())).await
+}
 
-    let result = retry_with_backoff_async(DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_DELAY_MS, || {
-        repo.insert_spans(spans.clone())
-    })
-    .await;
+/// Stamp each span's retention expiry (`now + ttl` of its project) right
+/// before the write. Spans of projects without a TTL are left unstamped.
+///
+/// Expiry is fixed at ingestion: changing retention later only affects spans
+/// ingested afterwards.
+pub(super) fn stamp_expiry(spans: &mut [NormalizedSpan], ttls: &HashMap<String, TimeDelta>) {
+    if ttls.is_empty() {
+        return;
+    }
+    let now = Utc::now();
+    for span in spans {
+        if let Some(ttl) = span.project_id.as_ref().and_then(|p| ttls.get(p)) {
+            span.expires_at = Some(
+                now.checked_add_signed(*ttl)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            );
+        }
+    }
+}
+
+/// Run a persistence operation with bounded exponential backoff.
+///
+/// Returns true once `insert` succeeds, false after `DEFAULT_MAX_ATTEMPTS`
+/// failures (transient errors such as a locked database are retried).
+pub(super) async fn persist_with_retry<F, Fut, E>(span_count: usize, insert: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let result =
+        retry_with_backoff_async(DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_DELAY_MS, insert).await;
 
     match result {
         Ok(attempts) => {
@@ -575,11 +624,13 @@ pub(super) async fn write_to_duckdb(
 #[allow(clippy::too_many_arguments)]
 fn flatten(
     request: &ExportTraceServiceRequest,
+    keep: Option<&[bool]>,
     span_data: Vec<SpanData>,
     messages: Vec<Vec<RawMessage>>,
     tool_definitions: Vec<Vec<RawToolDefinition>>,
     tool_names: Vec<Vec<RawToolNames>>,
     enrichments: Vec<SpanEnrichment>,
+    redact_tool_results: &[String],
     files_enabled: bool,
     file_cache: Option<&FileExtractionCache>,
 ) -> (Vec<NormalizedSpan>, Vec<PendingFileWrite>) {
@@ -598,7 +649,8 @@ fn flatten(
         None => extract_and_replace_files(json),
     };
 
-    // Iterate request in same order as normalize_batch
+    // Iterate request in same order as normalize_batch, skipping dropped spans
+    let mut keep = keep.map(<[bool]>::iter);
     for resource_spans in &request.resource_spans {
         let resource_attrs = resource_spans
             .resource
@@ -608,6 +660,9 @@ fn flatten(
 
         for scope_spans in &resource_spans.scope_spans {
             for otlp_span in &scope_spans.spans {
+                if !next_kept(&mut keep) {
+                    continue;
+                }
                 if let Some(((((mut span, msgs), tools), tnames), enrichment)) = iter.next() {
                     let messages_str =
                         Some(serde_json::to_string(&msgs).expect("JsonValue is always valid JSON"));
@@ -620,6 +675,11 @@ fn flatten(
                     );
 
                     let mut raw_span_json = build_raw_span_json(otlp_span, &resource_attrs);
+                    redact_raw_span_tool_results(
+                        &mut raw_span_json,
+                        span.gen_ai_tool_name.as_deref(),
+                        redact_tool_results,
+                    );
 
                     // Extract files from JSON values in-memory BEFORE serialization.
                     // This avoids the costly serialize→deserialize→re-serialize round-trip.
@@ -764,6 +824,7 @@ fn to_normalized_span(
         gen_ai_request_model: span.gen_ai_request_model,
         gen_ai_response_model: span.gen_ai_response_model,
         gen_ai_response_id: span.gen_ai_response_id,
+        gen_ai_raw_model: span.gen_ai_raw_model,
 
         // GenAI request parameters
         gen_ai_temperature: span.gen_ai_temperature,
@@ -773,6 +834,10 @@ fn to_normalized_span(
         gen_ai_frequency_penalty: span.gen_ai_frequency_penalty,
         gen_ai_presence_penalty: span.gen_ai_presence_penalty,
         gen_ai_stop_sequences: span.gen_ai_stop_sequences,
+        gen_ai_reasoning_effort: span.gen_ai_reasoning_effort,
+        gen_ai_verbosity: span.gen_ai_verbosity,
+        gen_ai_embedding_dimensions: span.gen_ai_embedding_dimensions,
+        gen_ai_encoding_format: span.gen_ai_encoding_format,
 
         // GenAI response
         gen_ai_finish_reasons: span.gen_ai_finish_reasons,
@@ -796,7 +861,9 @@ fn to_normalized_span(
         gen_ai_usage_cache_read_tokens: span.gen_ai_usage_cache_read_tokens,
         gen_ai_usage_cache_write_tokens: span.gen_ai_usage_cache_write_tokens,
         gen_ai_usage_reasoning_tokens: span.gen_ai_usage_reasoning_tokens,
+        gen_ai_usage_web_search_calls: span.gen_ai_usage_web_search_calls,
         gen_ai_usage_details: json_to_pre_serialized(&span.gen_ai_usage_details),
+        gen_ai_usage_merged_from: span.gen_ai_usage_merged_from,
 
         // Enrichment data (costs)
         gen_ai_cost_input: enrichment.input_cost,
@@ -804,7 +871,9 @@ fn to_normalized_span(
         gen_ai_cost_cache_read: enrichment.cache_read_cost,
         gen_ai_cost_cache_write: enrichment.cache_write_cost,
         gen_ai_cost_reasoning: enrichment.reasoning_cost,
+        gen_ai_cost_tool: enrichment.tool_cost,
         gen_ai_cost_total: enrichment.total_cost,
+        gen_ai_cost_source: enrichment.cost_source.map(|s| s.as_str().to_string()),
 
         // Enrichment data (previews)
         input_preview: enrichment.input_preview.clone(),
@@ -843,8 +912,17 @@ fn to_normalized_span(
         // Raw span JSON (includes attributes and resource.attributes)
         raw_span,
 
+        // Extraction warnings (None when clean, stored as "[]")
+        ingest_warnings: (!span.ingest_warnings.is_empty()).then(|| {
+            serde_json::to_string(&span.ingest_warnings)
+                .expect("IngestWarning is always serializable")
+        }),
+
         // Ingestion time (populated by DB default, not set during span creation)
         ingested_at: None,
+
+        // Retention expiry (stamped by the pipeline right before the write)
+        expires_at: None,
     }
 }
 
@@ -982,6 +1060,7 @@ fn build_resource_attributes(attrs: &HashMap<String, String>) -> JsonValue {
 mod tests {
     use super::*;
     use crate::domain::traces::MessageSource;
+    use crate::domain::traces::enrich::CostSource;
     use chrono::Utc;
     use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans};
     use serde_json::json;
@@ -1048,11 +1127,13 @@ mod tests {
         let enrichments: Vec<SpanEnrichment> = vec![];
         let (result, _) = flatten(
             &request,
+            None,
             spans,
             messages,
             tool_definitions,
             tool_names,
             enrichments,
+            &[],
             false,
             None,
         );
@@ -1069,11 +1150,13 @@ mod tests {
         let enrichments = vec![make_enrichment()];
         let (result, _) = flatten(
             &request,
+            None,
             spans,
             messages,
             tool_definitions,
             tool_names,
             enrichments,
+            &[],
             false,
             None,
         );
@@ -1095,11 +1178,13 @@ mod tests {
         let enrichments = vec![make_enrichment(), make_enrichment(), make_enrichment()];
         let (result, _) = flatten(
             &request,
+            None,
             spans,
             messages,
             tool_definitions,
             tool_names,
             enrichments,
+            &[],
             false,
             None,
         );
@@ -1121,11 +1206,13 @@ mod tests {
 
         let (result, _) = flatten(
             &request,
+            None,
             spans,
             messages,
             tool_definitions,
             tool_names,
             enrichments,
+            &[],
             false,
             None,
         );
@@ -1151,7 +1238,9 @@ mod tests {
         let enrichments = vec![SpanEnrichment {
             input_cost: 0.001,
             output_cost: 0.002,
+            tool_cost: 0.0005,
             total_cost: 0.003,
+            cost_source: Some(CostSource::Reported),
             input_preview: Some("Hello".to_string()),
             output_preview: Some("Hi".to_string()),
             ..Default::default()
@@ -1159,19 +1248,89 @@ mod tests {
 
         let (result, _) = flatten(
             &request,
+            None,
             spans,
             messages,
             tool_definitions,
             tool_names,
             enrichments,
+            &[],
             false,
             None,
         );
 
         assert_eq!(result[0].gen_ai_cost_input, 0.001);
         assert_eq!(result[0].gen_ai_cost_output, 0.002);
+        assert_eq!(result[0].gen_ai_cost_tool, 0.0005);
         assert_eq!(result[0].gen_ai_cost_total, 0.003);
+        assert_eq!(result[0].gen_ai_cost_source.as_deref(), Some("reported"));
+        assert_eq!(result[0].gen_ai_usage_details, None);
         assert_eq!(result[0].input_preview, Some("Hello".to_string()));
         assert_eq!(result[0].output_preview, Some("Hi".to_string()));
     }
+
+    #[test]
+    fn test_flatten_redacts_tool_results_in_raw_span() {
+        use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue, any_value};
+        use opentelemetry_proto::tonic::trace::v1::span::Event;
+
+        use crate::core::constants::REDACTED_TOOL_OUTPUT;
+
+        let string_kv = |key: &str, value: &str| KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        };
+        let mut request = make_request(2);
+        let spans = &mut request.resource_spans[0].scope_spans[0].spans;
+        // Chat span: the result sits in a JSON-encoded attribute and in an event
+        spans[0].attributes.push(string_kv(
+            "gen_ai.input.messages",
+            &json!([
+                {"role": "assistant", "tool_calls": [{"id": "call_1", "type": "function",
+                    "function": {"name": "read_secret", "arguments": "{}"}}]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "hunter2"}
+            ])
+            .to_string(),
+        ));
+        spans[0].events.push(Event {
+            name: "gen_ai.tool.message".to_string(),
+            attributes: vec![string_kv("id", "call_1"), string_kv("content", "hunter2")],
+            ..Default::default()
+        });
+        // Tool execution span of the sensitive tool
+        spans[1]
+            .attributes
+            .push(string_kv("gen_ai.tool.call.result", "hunter2"));
+
+        let mut tool_span = make_span("span2");
+        tool_span.gen_ai_tool_name = Some("read_secret".to_string());
+        let (result, _) = flatten(
+            &request,
+            None,
+            vec![make_span("span1"), tool_span],
+            vec![vec![], vec![]],
+            vec![vec![], vec![]],
+            vec![vec![], vec![]],
+            vec![make_enrichment(), make_enrichment()],
+            &["read_secret".to_string()],
+            false,
+            None,
+        );
+
+        for span in &result {
+            let raw_span = span.raw_span.as_deref().unwrap();
+            assert!(!raw_span.contains("hunter2"), "{raw_span}");
+            assert!(raw_span.contains(REDACTED_TOOL_OUTPUT));
+        }
+        // Tool calls themselves are kept
+        assert!(
+            result[0]
+                .raw_span
+                .as_deref()
+                .unwrap()
+                .contains("read_secret")
+        );
+    }
 }
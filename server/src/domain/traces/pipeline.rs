@@ -27,42 +27,74 @@
 //! | 2. SideML   | `&[Vec<RawMessage>]`                         | `Vec<Vec<SideMLMessage>>`                           | `sideml`       |
 //! | 3. Enrich   | `&[SpanData]`, `&[Vec<SideMLMessage>]`       | `Vec<SpanEnrichment>`                               | `enrich.rs`    |
 //! | 4. Persist  | `&Request`, `SpanData`, `RawMessage`, ...    | `()`                                                | `persist.rs`   |
+//!
+//! ## Dead Letters
+//!
+//! Messages that fail to persist `MAX_DELIVERY_ATTEMPTS` times are copied to
+//! the `traces_dlq` stream and acked. Nothing consumes that stream; operators
+//! move entries back onto `traces` once the cause is fixed (see the
+//! configuration reference for the Redis command).
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::TimeDelta;
+use moka::sync::Cache;
 use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
 use prost::Message;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use uuid::Uuid;
 
-use super::enrich::enrich_batch;
+use super::enrich::{enrich_batch, estimate_missing_usage, merge_split_usage};
 use super::extract::files::FileExtractionCache;
-use super::extract::{extract_attributes_batch, extract_messages_batch};
+use super::extract::{
+    extract_attributes_batch, extract_messages_batch, parse_observation_type,
+    retain_allowed_frameworks,
+};
+use super::notify::notify_processed;
 use super::persist::{
-    BatchInput, PendingFileWrite, SseSpanEvent, persist_extracted_files, prepare_batch,
-    publish_sse_events, write_to_duckdb,
+    BatchInput, PendingFileWrite, SseSpanEvent, compress_stored_json, persist_extracted_files,
+    prepare_batch, stamp_expiry, write_to_duckdb,
 };
+use super::token_rate::{TokenRateAlert, TokenRateMonitor, publish_token_rate_alerts};
+use super::trace_completion::{TraceCompletionTracker, publish_trace_completions};
 use crate::core::TopicService;
-use crate::data::AnalyticsService;
+use crate::core::config::IngestionConfig;
+use crate::core::constants::{
+    DEFAULT_ORG_ID, DEFAULT_PROJECT_ID, PROJECT_ORG_CACHE_MAX_ENTRIES, TOPIC_TRACES_DLQ,
+    TRACE_COMPLETE_CHECK_INTERVAL_SECS,
+};
+use crate::data::cache::CacheService;
 use crate::data::files::FileService;
-use crate::data::topics::{StreamTopic, TopicError};
+use crate::data::topics::{StreamAcker, StreamClaimer, StreamTopic, TopicError, consumer_id};
 use crate::data::types::NormalizedSpan;
+use crate::data::{AnalyticsService, TransactionalService};
 use crate::domain::pricing::PricingService;
+use crate::domain::pricing::estimate::BpeApproxEstimator;
 use crate::domain::sideml::to_sideml_batch;
+use crate::utils::otlp::{
+    PROJECT_ID_ATTR, any_value_to_string, assign_unscoped_project_id_traces, has_unscoped_traces,
+    unscoped_org_ids,
+};
 
 /// Consumer group name for trace pipeline
 const CONSUMER_GROUP: &str = "trace_pipeline";
 
 /// Interval for claiming stuck messages (seconds)
-const CLAIM_INTERVAL_SECS: u64 = 30;
+pub(super) const CLAIM_INTERVAL_SECS: u64 = 30;
 
 /// Minimum idle time before claiming a message (milliseconds)
-const CLAIM_MIN_IDLE_MS: u64 = 60_000;
+pub(super) const CLAIM_MIN_IDLE_MS: u64 = 60_000;
 
 /// Maximum number of messages to claim at once
-const CLAIM_MAX_COUNT: usize = 100;
+pub(super) const CLAIM_MAX_COUNT: usize = 100;
+
+/// Failed persistence attempts before a message moves to the dead-letter stream
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+/// Upper bound on tracked failing message IDs (reset when exceeded)
+const MAX_TRACKED_FAILURES: usize = 10_000;
 
 /// Maximum number of requests to batch before processing
 const PIPELINE_BATCH_MAX_SIZE: usize = 1024;
@@ -70,6 +102,12 @@ const PIPELINE_BATCH_MAX_SIZE: usize = 1024;
 /// Timeout for collecting additional messages into a batch (microseconds)
 const PIPELINE_BATCH_DRAIN_TIMEOUT_US: u64 = 5_000;
 
+/// Latency histogram for the CPU stages of a batch (extract through prepare)
+const METRIC_PREPARE: &str = "traces.prepare";
+
+/// Latency histogram for the DuckDB write and file persistence of a batch
+const METRIC_PERSIST: &str = "traces.persist";
+
 // ============================================================================
 // PIPELINE PROCESSOR
 // ============================================================================
@@ -87,9 +125,25 @@ pub struct TracePipeline {
     pricing: Arc<PricingService>,
     topics: Arc<TopicService>,
     file_service: Arc<FileService>,
+    ingestion: IngestionConfig,
     /// Cross-batch cache for base64 extraction.
     /// Avoids redundant decode + BLAKE3 for repeated images across spans/batches.
     file_cache: FileExtractionCache,
+    /// Runaway-agent detection (None when no token rate threshold is configured)
+    token_rate: Option<TokenRateMonitor>,
+    /// Quiet-period trace completion events (None when not configured)
+    trace_completion: Option<TraceCompletionTracker>,
+    /// Project lookups for org-scoped pricing and retention
+    database: Arc<TransactionalService>,
+    /// Repository cache for org default-project and retention lookups
+    cache: Arc<CacheService>,
+    /// Cached `project_id -> org_id` (projects never change organization)
+    project_orgs: Cache<String, String>,
+    /// Default span TTL stamped as `expires_at` when the org sets no
+    /// `retention_days` (None when time-based retention is off)
+    span_ttl: Option<TimeDelta>,
+    /// Whether the analytics backend stores `expires_at` (DuckDB only)
+    span_expiry: bool,
 }
 
 impl TracePipeline {
@@ -98,13 +152,148 @@ impl TracePipeline {
         pricing: Arc<PricingService>,
         topics: Arc<TopicService>,
         file_service: Arc<FileService>,
+        database: Arc<TransactionalService>,
+        cache: Arc<CacheService>,
+        ingestion: IngestionConfig,
+        retention_max_age_minutes: Option<u64>,
     ) -> Self {
+        for (pattern, name) in &ingestion.observation_type_patterns {
+            if parse_observation_type(name).is_none() {
+                tracing::warn!(
+                    pattern = %pattern,
+                    observation_type = %name,
+                    "Ignoring otel.ingestion.observation_type_patterns entry with an unknown observation type"
+                );
+            }
+        }
+        let token_rate = ingestion
+            .token_rate_alert_per_minute
+            .map(TokenRateMonitor::new);
+        let trace_completion = ingestion
+            .trace_complete_after_secs
+            .map(TraceCompletionTracker::new);
+        let span_ttl = retention_max_age_minutes.map(|minutes| {
+            i64::try_from(minutes)
+                .ok()
+                .and_then(TimeDelta::try_minutes)
+                .unwrap_or(TimeDelta::MAX)
+        });
+        let span_expiry = analytics.supports_span_expiry();
         Self {
             analytics,
             pricing,
             topics,
             file_service,
+            ingestion,
             file_cache: FileExtractionCache::new(),
+            token_rate,
+            trace_completion,
+            database,
+            cache,
+            project_orgs: Cache::new(PROJECT_ORG_CACHE_MAX_ENTRIES),
+            span_ttl,
+            span_expiry,
+        }
+    }
+
+    /// Resolve the owning org of every project in the requests.
+    ///
+    /// Only needed for org-scoped pricing, so this is a no-op unless deployment
+    /// mappings are configured. Unknown projects are left out (priced without org).
+    async fn resolve_project_orgs(
+        &self,
+        requests: &[ExportTraceServiceRequest],
+    ) -> HashMap<String, String> {
+        if !self.pricing.has_deployment_models() {
+            return HashMap::new();
+        }
+        let project_ids: HashSet<String> = requests
+            .iter()
+            .flat_map(|r| &r.resource_spans)
+            .filter_map(|rs| rs.resource.as_ref())
+            .flat_map(|r| &r.attributes)
+            .filter(|kv| kv.key == PROJECT_ID_ATTR)
+            .filter_map(|kv| kv.value.as_ref().map(any_value_to_string))
+            .collect();
+
+        let mut resolved = HashMap::new();
+        for project_id in project_ids {
+            if let Some(org_id) = self.project_org(&project_id).await {
+                resolved.insert(project_id, org_id);
+            }
+        }
+        resolved
+    }
+
+    /// Owning org of a project, cached (None when the project is unknown).
+    async fn project_org(&self, project_id: &str) -> Option<String> {
+        if let Some(org_id) = self.project_orgs.get(project_id) {
+            return Some(org_id);
+        }
+        match self
+            .database
+            .repository()
+            .get_project(None, project_id)
+            .await
+        {
+            Ok(Some(project)) => {
+                self.project_orgs
+                    .insert(project_id.to_string(), project.organization_id.clone());
+                Some(project.organization_id)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!(%project_id, error = %e, "Failed to resolve project org");
+                None
+            }
+        }
+    }
+
+    /// TTL of each project's new spans: the org's `retention_days`, else the
+    /// global retention age. Empty when the backend has no span expiry.
+    async fn project_span_ttls(&self, spans: &[NormalizedSpan]) -> HashMap<String, TimeDelta> {
+        if !self.span_expiry {
+            return HashMap::new();
+        }
+        let project_ids: HashSet<&str> = spans
+            .iter()
+            .filter_map(|s| s.project_id.as_deref())
+            .collect();
+
+        let mut ttls = HashMap::new();
+        for project_id in project_ids {
+            let ttl = match self.project_org(project_id).await {
+                Some(org_id) => {
+                    org_span_ttl(&self.database, &self.cache, &org_id, self.span_ttl).await
+                }
+                None => self.span_ttl,
+            };
+            if let Some(ttl) = ttl {
+                ttls.insert(project_id.to_string(), ttl);
+            }
+        }
+        ttls
+    }
+
+    /// Token rate alerts raised by a batch of spans about to be persisted.
+    fn observe_token_rate(&self, spans: &[NormalizedSpan]) -> Vec<TokenRateAlert> {
+        self.token_rate
+            .as_ref()
+            .map(|monitor| monitor.observe(spans))
+            .unwrap_or_default()
+    }
+
+    fn observe_trace_activity(&self, spans: &[NormalizedSpan]) {
+        if let Some(tracker) = &self.trace_completion {
+            tracker.observe(spans, chrono::Utc::now());
+        }
+    }
+
+    /// Broadcast traces that received no spans for the completion quiet period
+    async fn publish_completed_traces(&self) {
+        if let Some(tracker) = &self.trace_completion {
+            let completed = tracker.take_completed(chrono::Utc::now());
+            publish_trace_completions(&completed, &self.topics).await;
         }
     }
 
@@ -114,15 +303,29 @@ impl TracePipeline {
     /// - Messages are acknowledged after successful processing
     /// - Unacknowledged messages are re-delivered on restart
     /// - Stuck messages are claimed after CLAIM_MIN_IDLE_MS
+    /// - Messages failing MAX_DELIVERY_ATTEMPTS times move to the dead-letter stream
     pub fn start(
         self,
         topic: StreamTopic<ExportTraceServiceRequest>,
         mut shutdown_rx: watch::Receiver<bool>,
     ) -> JoinHandle<()> {
-        // Generate unique consumer name: {uuid}:{pid}
-        let consumer = format!("{}:{}", Uuid::new_v4(), std::process::id());
+        let consumer = consumer_name(self.ingestion.worker_id.as_deref());
 
         tokio::spawn(async move {
+            // A stable consumer name resumes its previous position (unacked first)
+            if self.ingestion.worker_id.is_some() {
+                match topic.last_delivered(CONSUMER_GROUP, &consumer).await {
+                    Ok(last_id) => tracing::info!(
+                        consumer = %consumer,
+                        last_delivered = ?last_id,
+                        "Resuming trace stream consumer"
+                    ),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to read trace stream consumer position")
+                    }
+                }
+            }
+
             // Subscribe with consumer group
             let mut subscriber = match topic.subscribe(CONSUMER_GROUP, &consumer).await {
                 Ok(s) => s,
@@ -135,6 +338,10 @@ impl TracePipeline {
             // Get acker and claimer for message operations (Send + Sync)
             let acker = subscriber.acker();
             let claimer = subscriber.claimer();
+            let dead_letter = self
+                .topics
+                .stream_topic::<ExportTraceServiceRequest>(TOPIC_TRACES_DLQ);
+            let mut failures = FailedDeliveries::default();
 
             tracing::debug!(
                 consumer = %consumer,
@@ -147,6 +354,9 @@ impl TracePipeline {
                 tokio::time::interval(Duration::from_secs(CLAIM_INTERVAL_SECS));
             // Don't count the initial tick
             claim_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut completion_interval =
+                tokio::time::interval(Duration::from_secs(TRACE_COMPLETE_CHECK_INTERVAL_SECS));
+            completion_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
             let mut shutdown_requested = false;
 
@@ -156,13 +366,15 @@ impl TracePipeline {
                     match tokio::time::timeout(Duration::from_millis(100), subscriber.recv()).await
                     {
                         Ok(Ok((msg_id, msg))) => {
-                            if self.run(&msg).await {
-                                if let Err(e) = acker.ack(&msg_id).await {
-                                    tracing::warn!(error = %e, msg_id = %msg_id, "Failed to ack during drain");
-                                }
-                            } else {
-                                tracing::warn!(msg_id = %msg_id, "Skipping ack during drain: write failed");
-                            }
+                            let persisted = self.run(&msg).await;
+                            settle(
+                                persisted,
+                                &[(msg_id.as_str(), &msg)],
+                                &acker,
+                                &mut failures,
+                                &dead_letter,
+                            )
+                            .await;
                             continue;
                         }
                         Ok(Err(TopicError::Lagged(n))) => {
@@ -199,7 +411,12 @@ impl TracePipeline {
                     }
                     _ = claim_interval.tick() => {
                         // Periodically claim stuck messages from other consumers
-                        self.claim_stuck_messages(&claimer, &acker, &consumer).await;
+                        self.claim_stuck_messages(&claimer, &acker, &consumer, &mut failures, &dead_letter)
+                            .await;
+                        continue;
+                    }
+                    _ = completion_interval.tick(), if self.trace_completion.is_some() => {
+                        self.publish_completed_traces().await;
                         continue;
                     }
                 };
@@ -223,24 +440,19 @@ impl TracePipeline {
                     tracing::debug!(batch_size, "Processing batched requests");
                 }
 
-                // Phase 3: Process entire batch (one DuckDB write)
-                let msg_ids: Vec<String> = batch.iter().map(|(id, _)| id.clone()).collect();
-                let requests: Vec<ExportTraceServiceRequest> =
-                    batch.into_iter().map(|(_, req)| req).collect();
-                let db_ok = self.run_batch(&requests).await;
+                // Phase 3: Process entire batch (one DuckDB write, retried with backoff)
+                let (msg_ids, requests): (Vec<String>, Vec<ExportTraceServiceRequest>) =
+                    batch.into_iter().unzip();
+                let persisted = self.run_batch(&requests).await;
 
                 // Phase 4: Acknowledge only on successful DuckDB write.
                 // On failure, messages remain pending for redelivery (at-least-once).
-                if db_ok {
-                    if let Err(e) = acker.ack_batch(&msg_ids).await {
-                        tracing::warn!(error = %e, count = msg_ids.len(), "Failed to batch ack messages");
-                    }
-                } else {
-                    tracing::warn!(
-                        count = msg_ids.len(),
-                        "Skipping ack: analytics write failed, messages will be redelivered"
-                    );
-                }
+                let messages: Vec<(&str, &ExportTraceServiceRequest)> = msg_ids
+                    .iter()
+                    .map(String::as_str)
+                    .zip(requests.iter())
+                    .collect();
+                settle(persisted, &messages, &acker, &mut failures, &dead_letter).await;
             }
 
             tracing::debug!("TracePipeline shutdown complete");
@@ -253,9 +465,11 @@ impl TracePipeline {
     /// claimed from other (possibly crashed) consumers, processed, and acknowledged.
     async fn claim_stuck_messages(
         &self,
-        claimer: &crate::data::topics::StreamClaimer,
-        acker: &crate::data::topics::StreamAcker,
+        claimer: &StreamClaimer,
+        acker: &StreamAcker,
         consumer: &str,
+        failures: &mut FailedDeliveries,
+        dead_letter: &StreamTopic<ExportTraceServiceRequest>,
     ) {
         match claimer
             .claim(consumer, CLAIM_MIN_IDLE_MS, CLAIM_MAX_COUNT)
@@ -272,13 +486,15 @@ impl TracePipeline {
                     // Decode and process the claimed message
                     match ExportTraceServiceRequest::decode(&msg.payload[..]) {
                         Ok(request) => {
-                            if self.run(&request).await {
-                                if let Err(e) = acker.ack(&msg.id).await {
-                                    tracing::warn!(error = %e, msg_id = %msg.id, "Failed to ack claimed message");
-                                }
-                            } else {
-                                tracing::warn!(msg_id = %msg.id, "Skipping ack for claimed message: write failed");
-                            }
+                            let persisted = self.run(&request).await;
+                            settle(
+                                persisted,
+                                &[(msg.id.as_str(), &request)],
+                                acker,
+                                failures,
+                                dead_letter,
+                            )
+                            .await;
                         }
                         Err(e) => {
                             tracing::error!(error = %e, msg_id = %msg.id, "Failed to decode claimed message, acking to discard");
@@ -313,9 +529,13 @@ impl TracePipeline {
     async fn run_batch(&self, requests: &[ExportTraceServiceRequest]) -> bool {
         let t_batch_start = std::time::Instant::now();
 
+        let scoped = scope_unscoped(&self.database, &self.cache, requests).await;
+        let requests = scoped.as_deref().unwrap_or(requests);
+        let project_orgs = &self.resolve_project_orgs(requests).await;
         let pricing = &self.pricing;
         let files_enabled = self.file_service.is_enabled();
         let file_cache = &self.file_cache;
+        let ingestion = &self.ingestion;
 
         // Process requests in parallel using scoped threads.
         // base64 extraction can take 100ms-1s per request for image-heavy spans,
@@ -342,8 +562,10 @@ impl TracePipeline {
                                             process_request(
                                                 request,
                                                 pricing,
+                                                project_orgs,
+                                                ingestion,
                                                 files_enabled,
-                                                file_cache,
+                                                Some(file_cache),
                                             )
                                         },
                                     )) {
@@ -387,8 +609,10 @@ impl TracePipeline {
                                                     process_request(
                                                         request,
                                                         pricing,
+                                                        project_orgs,
+                                                        ingestion,
                                                         files_enabled,
-                                                        file_cache,
+                                                        Some(file_cache),
                                                     )
                                                 }),
                                             ) {
@@ -430,12 +654,16 @@ impl TracePipeline {
         if all_db_spans.is_empty() {
             return true;
         }
+        let span_ttls = self.project_span_ttls(&all_db_spans).await;
+        stamp_expiry(&mut all_db_spans, &span_ttls);
 
         let t_prepare_done = std::time::Instant::now();
         let span_count = all_db_spans.len();
 
         // Build SSE events before write (captures span metadata)
         let sse_events: Vec<SseSpanEvent> = all_db_spans.iter().map(SseSpanEvent::from).collect();
+        let token_rate_alerts = self.observe_token_rate(&all_db_spans);
+        self.observe_trace_activity(&all_db_spans);
 
         // DuckDB write + file persistence in parallel, SSE after both complete
         let (db_ok, _) = tokio::join!(write_to_duckdb(all_db_spans, &self.analytics), async {
@@ -446,8 +674,16 @@ impl TracePipeline {
 
         let t_persist_done = std::time::Instant::now();
 
+        let metrics = self.topics.stream_metrics();
+        metrics.observe(METRIC_PREPARE, t_prepare_done.duration_since(t_batch_start));
+        metrics.observe(
+            METRIC_PERSIST,
+            t_persist_done.duration_since(t_prepare_done),
+        );
+
         if db_ok {
-            publish_sse_events(&sse_events, &self.topics).await;
+            notify_processed(&sse_events, &self.topics).await;
+            publish_token_rate_alerts(&token_rate_alerts, &self.topics).await;
         }
 
         tracing::debug!(
@@ -468,23 +704,36 @@ impl TracePipeline {
     ///
     /// Returns true if the DuckDB write succeeded, false otherwise.
     async fn run(&self, request: &ExportTraceServiceRequest) -> bool {
+        let scoped =
+            scope_unscoped(&self.database, &self.cache, std::slice::from_ref(request)).await;
+        let request = scoped.as_ref().map_or(request, |r| &r[0]);
+        let project_orgs = self
+            .resolve_project_orgs(std::slice::from_ref(request))
+            .await;
         let result = process_request(
             request,
             &self.pricing,
+            &project_orgs,
+            &self.ingestion,
             self.file_service.is_enabled(),
-            &self.file_cache,
+            Some(&self.file_cache),
         );
-        if let Some((db_spans, pending_files)) = result {
+        if let Some((mut db_spans, pending_files)) = result {
             if db_spans.is_empty() {
                 return true;
             }
+            let span_ttls = self.project_span_ttls(&db_spans).await;
+            stamp_expiry(&mut db_spans, &span_ttls);
             let sse_events: Vec<SseSpanEvent> = db_spans.iter().map(SseSpanEvent::from).collect();
+            let token_rate_alerts = self.observe_token_rate(&db_spans);
+            self.observe_trace_activity(&db_spans);
             let (db_ok, _) = tokio::join!(
                 write_to_duckdb(db_spans, &self.analytics),
                 persist_extracted_files(pending_files, &self.file_service)
             );
             if db_ok {
-                publish_sse_events(&sse_events, &self.topics).await;
+                notify_processed(&sse_events, &self.topics).await;
+                publish_token_rate_alerts(&token_rate_alerts, &self.topics).await;
             }
             db_ok
         } else {
@@ -493,6 +742,151 @@ impl TracePipeline {
     }
 }
 
+/// Give resources sent without a project id their organization's default
+/// project, or `DEFAULT_PROJECT_ID` when none is configured.
+///
+/// The organization comes from the API key of an authenticated gRPC export
+/// (stamped as `sideseat.org_id`); anonymous exports use the default org.
+/// Org lookups go through the repository cache, which the org API invalidates.
+/// Returns None (no copy) when every resource is already scoped.
+async fn scope_unscoped(
+    database: &TransactionalService,
+    cache: &CacheService,
+    requests: &[ExportTraceServiceRequest],
+) -> Option<Vec<ExportTraceServiceRequest>> {
+    if !requests.iter().any(has_unscoped_traces) {
+        return None;
+    }
+    let org_ids: HashSet<String> = requests
+        .iter()
+        .flat_map(unscoped_org_ids)
+        .map(|org_id| org_id.unwrap_or_else(|| DEFAULT_ORG_ID.to_string()))
+        .collect();
+    let mut defaults = HashMap::new();
+    for org_id in org_ids {
+        match database
+            .repository()
+            .get_organization(Some(cache), &org_id)
+            .await
+        {
+            Ok(org) => {
+                if let Some(project_id) = org.and_then(|o| o.default_project_id) {
+                    defaults.insert(org_id, project_id);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(%org_id, error = %e, "Failed to resolve default project for unscoped traces");
+            }
+        }
+    }
+    let project_for_org = |org_id: Option<&str>| {
+        defaults
+            .get(org_id.unwrap_or(DEFAULT_ORG_ID))
+            .map_or(DEFAULT_PROJECT_ID, String::as_str)
+    };
+    Some(
+        requests
+            .iter()
+            .map(|request| {
+                let mut request = request.clone();
+                assign_unscoped_project_id_traces(&mut request, project_for_org);
+                request
+            })
+            .collect(),
+    )
+}
+
+/// Persistence failure counts for pending messages, tracked per consumer.
+///
+/// Redelivery happens through claims, so counts are approximate across
+/// consumers; they only need to bound how long a poison message is retried.
+#[derive(Default)]
+struct FailedDeliveries {
+    counts: HashMap<String, u32>,
+}
+
+impl FailedDeliveries {
+    /// Record a failed attempt. Returns true once the message is exhausted.
+    fn record(&mut self, msg_id: &str) -> bool {
+        if self.counts.len() >= MAX_TRACKED_FAILURES && !self.counts.contains_key(msg_id) {
+            self.counts.clear();
+        }
+        let count = self.counts.entry(msg_id.to_string()).or_insert(0);
+        *count += 1;
+        if *count >= MAX_DELIVERY_ATTEMPTS {
+            self.counts.remove(msg_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn clear(&mut self, msg_id: &str) {
+        self.counts.remove(msg_id);
+    }
+}
+
+/// Ack persisted messages; leave failed ones pending for redelivery.
+///
+/// A message that fails `MAX_DELIVERY_ATTEMPTS` times is copied to the
+/// dead-letter stream and acked, so a poison batch is not retried forever.
+async fn settle(
+    persisted: bool,
+    messages: &[(&str, &ExportTraceServiceRequest)],
+    acker: &StreamAcker,
+    failures: &mut FailedDeliveries,
+    dead_letter: &StreamTopic<ExportTraceServiceRequest>,
+) {
+    if persisted {
+        let msg_ids: Vec<String> = messages.iter().map(|(id, _)| id.to_string()).collect();
+        for id in &msg_ids {
+            failures.clear(id);
+        }
+        if let Err(e) = acker.ack_batch(&msg_ids).await {
+            tracing::warn!(error = %e, count = msg_ids.len(), "Failed to batch ack messages");
+        }
+        return;
+    }
+
+    tracing::warn!(
+        count = messages.len(),
+        "Skipping ack: analytics write failed, messages will be redelivered"
+    );
+    for (msg_id, request) in messages {
+        if !failures.record(msg_id) {
+            continue;
+        }
+        match dead_letter.publish(request).await {
+            Ok(_) => {
+                tracing::error!(
+                    msg_id,
+                    attempts = MAX_DELIVERY_ATTEMPTS,
+                    "Moved trace message to dead-letter stream"
+                );
+                if let Err(e) = acker.ack(msg_id).await {
+                    tracing::warn!(error = %e, msg_id, "Failed to ack dead-lettered message");
+                }
+            }
+            Err(e) => {
+                tracing::error!(error = %e, msg_id, "Failed to dead-letter message, leaving pending");
+            }
+        }
+    }
+}
+
+/// Stream consumer name: the configured worker ID, or [`consumer_id`] when unset.
+///
+/// Generated names carry a random per-process token, so a restarted process never
+/// resumes under its predecessor's name, even when hostname and pid repeat. The
+/// predecessor's unacked messages are left for `claim_stuck_messages` after
+/// `CLAIM_MIN_IDLE_MS`.
+pub(super) fn consumer_name(worker_id: Option<&str>) -> String {
+    match worker_id {
+        Some(id) => id.to_string(),
+        None => consumer_id(),
+    }
+}
+
 // ============================================================================
 // PER-REQUEST PROCESSING (free function for thread safety)
 // ============================================================================
@@ -501,30 +895,46 @@ impl TracePipeline {
 ///
 /// Pure CPU work: extract attributes, messages, sideml, enrich, prepare.
 /// Returns NormalizedSpans + pending file writes, or None if no spans.
-fn process_request(
+pub(super) fn process_request(
     request: &ExportTraceServiceRequest,
     pricing: &PricingService,
+    project_orgs: &HashMap<String, String>,
+    ingestion: &IngestionConfig,
     files_enabled: bool,
-    file_cache: &FileExtractionCache,
+    file_cache: Option<&FileExtractionCache>,
 ) -> Option<(Vec<NormalizedSpan>, Vec<PendingFileWrite>)> {
     // Stage 1a: Extract Attributes
-    let spans = extract_attributes_batch(request);
+    let mut spans = extract_attributes_batch(request, ingestion);
+    let keep = if ingestion.framework_allowlist.is_empty() {
+        None
+    } else {
+        retain_allowed_frameworks(request, &mut spans, &ingestion.framework_allowlist)
+    };
     if spans.is_empty() {
         return None;
     }
 
     // Stage 1b: Extract Messages, Tool Definitions, and Tool Names
-    let (raw_messages, tool_definitions, tool_names) = extract_messages_batch(request, &spans);
+    let (raw_messages, tool_definitions, tool_names) =
+        extract_messages_batch(request, keep.as_deref(), &mut spans, ingestion);
 
     // Stage 2: SideML Conversion
     let messages = to_sideml_batch(&raw_messages);
 
-    // Stage 3: Enrich
-    let enrichments = enrich_batch(&spans, &messages, pricing);
+    // Stage 3: Enrich (optionally estimate usage first so it feeds cost calculation)
+    if ingestion.estimate_missing_usage {
+        estimate_missing_usage(&mut spans, &messages, &BpeApproxEstimator);
+    }
+    // After estimation: split spans each report some usage, so neither is estimated
+    if ingestion.merge_split_usage {
+        merge_split_usage(&mut spans);
+    }
+    let enrichments = enrich_batch(&spans, &messages, pricing, project_orgs);
 
     // Stage 4: Prepare (CPU-only file extraction + flatten to NormalizedSpan)
-    let (db_spans, pending_files) = prepare_batch(
+    let (mut db_spans, pending_files) = prepare_batch(
         request,
+        keep.as_deref(),
         BatchInput {
             spans,
             messages: raw_messages,
@@ -532,9 +942,365 @@ fn process_request(
             tool_names,
             enrichments,
         },
+        &ingestion.redact_tool_results,
         files_enabled,
-        Some(file_cache),
+        file_cache,
     );
+    if ingestion.compress_messages {
+        compress_stored_json(&mut db_spans);
+    }
 
     Some((db_spans, pending_files))
 }
+
+/// TTL of an org's new spans: its `retention_days`, else `default_ttl`.
+async fn org_span_ttl(
+    database: &TransactionalService,
+    cache: &CacheService,
+    org_id: &str,
+    default_ttl: Option<TimeDelta>,
+) -> Option<TimeDelta> {
+    let retention_days = match database
+        .repository()
+        .get_organization(Some(cache), org_id)
+        .await
+    {
+        Ok(org) => org.and_then(|o| o.retention_days),
+        Err(e) => {
+            tracing::warn!(%org_id, error = %e, "Failed to load org retention");
+            None
+        }
+    };
+    retention_days
+        .map(|days| TimeDelta::try_days(days).unwrap_or(TimeDelta::MAX))
+        .or(default_ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::super::persist::persist_with_retry;
+    use super::*;
+    use crate::core::constants::TOPIC_TRACES;
+
+    async fn receive_one(
+        topics: &TopicService,
+    ) -> (StreamAcker, String, ExportTraceServiceRequest) {
+        let topic = topics.stream_topic::<ExportTraceServiceRequest>(TOPIC_TRACES);
+        topic
+            .publish(&ExportTraceServiceRequest::default())
+            .await
+            .unwrap();
+        let mut subscriber = topic.subscribe(CONSUMER_GROUP, "worker-1").await.unwrap();
+        let (msg_id, request) = subscriber.recv().await.unwrap();
+        (subscriber.acker(), msg_id, request)
+    }
+
+    async fn pending(topics: &TopicService) -> u64 {
+        topics
+            .stream_stats(TOPIC_TRACES, CONSUMER_GROUP)
+            .await
+            .unwrap()
+            .pending
+    }
+
+    #[tokio::test]
+    async fn test_transient_persist_failure_acks_once_after_retry() {
+        let topics = TopicService::new();
+        let dead_letter = topics.stream_topic::<ExportTraceServiceRequest>(TOPIC_TRACES_DLQ);
+        let (acker, msg_id, request) = receive_one(&topics).await;
+        assert_eq!(pending(&topics).await, 1);
+
+        let attempts = &AtomicU32::new(0);
+        let stored = &Mutex::new(Vec::new());
+        let request = &request;
+        let persisted = persist_with_retry(1, move || async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                return Err("database is locked");
+            }
+            stored.lock().unwrap().push(request.clone());
+            Ok(())
+        })
+        .await;
+
+        assert!(persisted);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(stored.lock().unwrap().len(), 1);
+
+        let mut failures = FailedDeliveries::default();
+        settle(
+            persisted,
+            &[(msg_id.as_str(), request)],
+            &acker,
+            &mut failures,
+            &dead_letter,
+        )
+        .await;
+
+        assert_eq!(pending(&topics).await, 0);
+        assert!(failures.counts.is_empty());
+        let dlq = topics.stream_stats(TOPIC_TRACES_DLQ, "any").await.unwrap();
+        assert_eq!(dlq.length, 0);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_message_moves_to_dead_letter() {
+        let topics = TopicService::new();
+        let dead_letter = topics.stream_topic::<ExportTraceServiceRequest>(TOPIC_TRACES_DLQ);
+        let (acker, msg_id, request) = receive_one(&topics).await;
+        let mut failures = FailedDeliveries::default();
+
+        for _ in 1..MAX_DELIVERY_ATTEMPTS {
+            settle(
+                false,
+                &[(msg_id.as_str(), &request)],
+                &acker,
+                &mut failures,
+                &dead_letter,
+            )
+            .await;
+            assert_eq!(pending(&topics).await, 1, "stays pending for redelivery");
+        }
+
+        settle(
+            false,
+            &[(msg_id.as_str(), &request)],
+            &acker,
+            &mut failures,
+            &dead_letter,
+        )
+        .await;
+
+        assert_eq!(pending(&topics).await, 0);
+        let dlq = topics.stream_stats(TOPIC_TRACES_DLQ, "any").await.unwrap();
+        assert_eq!(dlq.length, 1);
+    }
+
+    /// In-memory SQLite database with the full schema, plus a memory cache
+    async fn sqlite_with_cache() -> (TransactionalService, CacheService) {
+        use crate::core::config::{CacheBackendType, CacheConfig, EvictionPolicy};
+        use crate::data::sqlite::SqliteService;
+
+        let pool = sqlx::SqlitePool::connect(":memory:").await.unwrap();
+        for statement in crate::data::sqlite::schema::SCHEMA
+            .split(';')
+            .filter(|s| !s.trim().is_empty())
+        {
+            sqlx::query(statement.trim()).execute(&pool).await.unwrap();
+        }
+        let database = TransactionalService::Sqlite(Arc::new(SqliteService::from_pool(pool)));
+        let cache = CacheService::new(&CacheConfig {
+            backend: CacheBackendType::Memory,
+            max_entries: 100,
+            eviction_policy: EvictionPolicy::TinyLfu,
+            redis_url: None,
+            redis_pool_size: 1,
+        })
+        .await
+        .unwrap();
+        (database, cache)
+    }
+
+    #[tokio::test]
+    async fn test_unscoped_spans_land_in_org_default_project() {
+        use opentelemetry_proto::tonic::resource::v1::Resource;
+        use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans, Span};
+
+        use crate::core::constants::DEFAULT_USER_ID;
+        use crate::utils::otlp::{make_project_id_attr, stamp_unscoped_org_id_traces};
+
+        let (database, cache) = sqlite_with_cache().await;
+
+        let resource_spans = |resource: Option<Resource>, span_id: &[u8]| ResourceSpans {
+            resource,
+            scope_spans: vec![ScopeSpans {
+                spans: vec![Span {
+                    trace_id: b"trace1__________".to_vec(),
+                    span_id: span_id.to_vec(),
+                    name: "chat".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![
+                resource_spans(None, b"unscoped"),
+                resource_spans(
+                    Some(Resource {
+                        attributes: vec![make_project_id_attr("explicit")],
+                        ..Default::default()
+                    }),
+                    b"scoped__",
+                ),
+            ],
+        };
+        let project_ids = |request: &ExportTraceServiceRequest| {
+            extract_attributes_batch(request, &IngestionConfig::default())
+                .into_iter()
+                .map(|s| s.project_id)
+                .collect::<Vec<_>>()
+        };
+
+        // No default configured: the built-in default project
+        let scoped = scope_unscoped(&database, &cache, std::slice::from_ref(&request))
+            .await
+            .unwrap();
+        assert_eq!(
+            project_ids(&scoped[0]),
+            [
+                Some(DEFAULT_PROJECT_ID.to_string()),
+                Some("explicit".to_string())
+            ]
+        );
+
+        let repo = database.repository();
+        let inbox = repo
+            .create_project(None, DEFAULT_ORG_ID, "Inbox")
+            .await
+            .unwrap();
+        repo.set_org_default_project(Some(&cache), DEFAULT_ORG_ID, Some(&inbox.id))
+            .await
+            .unwrap();
+
+        let scoped = scope_unscoped(&database, &cache, std::slice::from_ref(&request))
+            .await
+            .unwrap();
+        assert_eq!(
+            project_ids(&scoped[0]),
+            [Some(inbox.id.clone()), Some("explicit".to_string())]
+        );
+
+        // Fully scoped requests are left alone
+        assert!(scope_unscoped(&database, &cache, &scoped).await.is_none());
+
+        // An authenticated export uses its own org's default project
+        let other = repo
+            .create_organization_with_owner(None, "Other", "other", DEFAULT_USER_ID)
+            .await
+            .unwrap();
+        let triage = repo
+            .create_project(None, &other.id, "Triage")
+            .await
+            .unwrap();
+        repo.set_org_default_project(Some(&cache), &other.id, Some(&triage.id))
+            .await
+            .unwrap();
+        let mut authenticated = request.clone();
+        stamp_unscoped_org_id_traces(&mut authenticated, Some(&other.id));
+        let scoped = scope_unscoped(&database, &cache, std::slice::from_ref(&authenticated))
+            .await
+            .unwrap();
+        assert_eq!(
+            project_ids(&scoped[0]),
+            [Some(triage.id.clone()), Some("explicit".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_org_retention_days_overrides_global_span_ttl() {
+        let (database, cache) = sqlite_with_cache().await;
+        let global = TimeDelta::try_minutes(60);
+
+        assert_eq!(
+            org_span_ttl(&database, &cache, DEFAULT_ORG_ID, global).await,
+            global
+        );
+
+        database
+            .repository()
+            .set_org_retention_days(Some(&cache), DEFAULT_ORG_ID, Some(7))
+            .await
+            .unwrap();
+        assert_eq!(
+            org_span_ttl(&database, &cache, DEFAULT_ORG_ID, global).await,
+            TimeDelta::try_days(7)
+        );
+        // Applies without a global retention age too
+        assert_eq!(
+            org_span_ttl(&database, &cache, DEFAULT_ORG_ID, None).await,
+            TimeDelta::try_days(7)
+        );
+
+        // Unknown orgs fall back to the global age
+        assert_eq!(
+            org_span_ttl(&database, &cache, "missing", global).await,
+            global
+        );
+    }
+
+    #[test]
+    fn test_framework_allowlist_drops_other_sources() {
+        use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue, any_value};
+        use opentelemetry_proto::tonic::resource::v1::Resource;
+        use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans, Span};
+
+        let string_kv = |key: &str, value: &str| KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        };
+        let span = |span_id: &[u8], name: &str, attributes: Vec<KeyValue>| Span {
+            trace_id: b"trace1__________".to_vec(),
+            span_id: span_id.to_vec(),
+            name: name.to_string(),
+            attributes,
+            ..Default::default()
+        };
+        let resource_spans = |sdk: &str, spans: Vec<Span>| ResourceSpans {
+            resource: Some(Resource {
+                attributes: vec![string_kv("telemetry.sdk.name", sdk)],
+                ..Default::default()
+            }),
+            scope_spans: vec![ScopeSpans {
+                spans,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![
+                resource_spans(
+                    "opentelemetry",
+                    vec![
+                        span(
+                            b"langchn_",
+                            "chain",
+                            vec![string_kv("langchain.chain.name", "qa")],
+                        ),
+                        span(b"httpget_", "GET /health", vec![]),
+                    ],
+                ),
+                resource_spans("my-agent-sdk", vec![span(b"customsd", "step", vec![])]),
+            ],
+        };
+        let span_ids = |ingestion: &IngestionConfig| {
+            process_request(
+                &request,
+                &PricingService::init_for_test().unwrap(),
+                &HashMap::new(),
+                ingestion,
+                false,
+                None,
+            )
+            .map(|(spans, _)| spans.into_iter().map(|s| s.span_id).collect::<Vec<_>>())
+            .unwrap_or_default()
+        };
+
+        // Empty allowlist accepts everything
+        assert_eq!(span_ids(&IngestionConfig::default()).len(), 3);
+
+        let ingestion = IngestionConfig {
+            framework_allowlist: vec!["langchain".to_string(), "my-agent-sdk".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            span_ids(&ingestion),
+            [hex::encode(b"langchn_"), hex::encode(b"customsd")]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! In-memory ingestion replay (test support)
+//!
+//! Runs an OTLP export through the same stages as the trace pipeline
+//! (extract, SideML, enrich, prepare) and feeds the persistence-shaped rows
+//! straight into the feed pipeline, without a database or running server.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use prost::Message;
+
+use super::pipeline::process_request;
+use crate::core::config::IngestionConfig;
+use crate::data::types::{MessageSpanRow, NormalizedSpan};
+use crate::domain::pricing::PricingService;
+use crate::domain::sideml::{FeedOptions, FeedResult, process_spans};
+
+/// Process an OTLP trace export and return the resulting feed.
+///
+/// Uses embedded pricing data and default ingestion settings. File extraction
+/// is skipped, so inline base64 content stays in the messages.
+pub fn replay_request(request: &ExportTraceServiceRequest, options: &FeedOptions) -> FeedResult {
+    let pricing = PricingService::init_for_test().expect("embedded pricing data must parse");
+    let spans = process_request(
+        request,
+        &pricing,
+        &HashMap::new(),
+        &IngestionConfig::default(),
+        false,
+        None,
+    )
+    .map(|(spans, _)| spans)
+    .unwrap_or_default();
+
+    let mut rows: Vec<MessageSpanRow> = spans
+        .into_iter()
+        .filter(has_feed_content)
+        .map(to_message_row)
+        .collect();
+    // Same order as the messages query
+    rows.sort_by_key(|r| r.span_timestamp);
+
+    process_spans(rows, options)
+}
+
+/// Decode a protobuf-encoded `ExportTraceServiceRequest` and replay it.
+pub fn replay_otlp_bytes(
+    bytes: &[u8],
+    options: &FeedOptions,
+) -> Result<FeedResult, prost::DecodeError> {
+    let request = ExportTraceServiceRequest::decode(bytes)?;
+    Ok(replay_request(&request, options))
+}
+
+/// Mirrors the messages query content filter (spans with messages, tools, or errors).
+fn has_feed_content(span: &NormalizedSpan) -> bool {
+    let non_empty = |json: &Option<String>| json.as_deref().is_some_and(|s| s != "[]");
+    non_empty(&span.messages)
+        || non_empty(&span.tool_definitions)
+        || non_empty(&span.tool_names)
+        || span.status_code.as_deref() == Some("ERROR")
+        || non_empty(&span.ingest_warnings)
+}
+
+/// Shape a prepared span like a row read back by the messages query.
+fn to_message_row(span: NormalizedSpan) -> MessageSpanRow {
+    let or_empty = |json: Option<String>| json.unwrap_or_else(|| "[]".to_string());
+    MessageSpanRow {
+        trace_id: span.trace_id,
+        span_id: span.span_id,
+        parent_span_id: span.parent_span_id,
+        span_timestamp: span.timestamp_start,
+        span_end_timestamp: span.timestamp_end,
+        messages_json: or_empty(span.messages),
+        tool_definitions_json: or_empty(span.tool_definitions),
+        tool_names_json: or_empty(span.tool_names),
+        ingest_warnings_json: or_empty(span.ingest_warnings),
+        model: span.gen_ai_request_model,
+        provider: span.gen_ai_system,
+        status_code: span.status_code,
+        exception_type: span.exception_type,
+        exception_message: span.exception_message,
+        exception_stacktrace: span.exception_stacktrace,
+        input_tokens: span.gen_ai_usage_input_tokens,
+        output_tokens: span.gen_ai_usage_output_tokens,
+        total_tokens: span.gen_ai_usage_total_tokens,
+        cost_total: span.gen_ai_cost_total,
+        observation_type: span.observation_type.map(|o| o.as_str().to_string()),
+        session_id: span.session_id,
+        ingested_at: span.ingested_at.unwrap_or_else(Utc::now),
+        environment: span.environment,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::sideml::{ChatRole, ContentBlock};
+
+    const OPENAI_CHAT_FIXTURE: &str = include_str!("../../../data/fixtures/openai_chat.otlp.json");
+
+    #[test]
+    fn test_replay_fixture_produces_conversation() {
+        let request: ExportTraceServiceRequest = serde_json::from_str(OPENAI_CHAT_FIXTURE).unwrap();
+        let result = replay_otlp_bytes(&request.encode_to_vec(), &FeedOptions::default()).unwrap();
+
+        let blocks: Vec<(ChatRole, &str)> = result
+            .messages
+            .iter()
+            .map(|b| match &b.content {
+                ContentBlock::Text { text } => (b.role, text.as_str()),
+                other => panic!("unexpected block: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (ChatRole::System, "You are a terse assistant."),
+                (ChatRole::User, "What is the capital of France?"),
+                (ChatRole::Assistant, "Paris."),
+            ]
+        );
+        assert!(
+            result
+                .messages
+                .iter()
+                .all(|b| b.model.as_deref() == Some("gpt-4o"))
+        );
+        assert!(result.metadata.total_cost > 0.0);
+    }
+}
@@ -0,0 +1,183 @@
+//! Runaway-agent detection from per-session token rates
+//!
+//! Each persisted span adds its total tokens to a sliding window keyed by
+//! project and session. When the tokens in the last minute exceed the configured
+//! threshold, a [`TokenRateAlert`] is broadcast on
+//! `token_rate_alerts:{project_id}`. Alerts are debounced per session so a loop
+//! that keeps running produces one alert per cooldown, not one per batch.
+//!
+//! Windows use span timestamps rather than wall-clock time, so delayed batches
+//! are measured by when the tokens were actually spent. State is per instance.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::{
+    TOKEN_RATE_ALERT_COOLDOWN_SECS, TOKEN_RATE_WINDOW_SECS, TOPIC_TOKEN_RATE_ALERTS,
+};
+use crate::core::{TopicMessage, TopicService};
+use crate::data::types::NormalizedSpan;
+
+/// Upper bound on tracked sessions (reset when exceeded)
+const MAX_TRACKED_SESSIONS: usize = 10_000;
+
+/// Broadcast when a session spends tokens faster than the configured rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenRateAlert {
+    pub project_id: String,
+    pub session_id: String,
+    /// Tokens spent within the sliding window ending at `window_end`
+    pub tokens_per_minute: i64,
+    pub threshold_per_minute: u64,
+    pub window_end: DateTime<Utc>,
+    /// Trace of the span that pushed the session over the threshold
+    pub trace_id: String,
+}
+
+impl TopicMessage for TokenRateAlert {
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.project_id.len()
+            + self.session_id.len()
+            + self.trace_id.len()
+    }
+}
+
+#[derive(Default)]
+struct SessionWindow {
+    /// (span timestamp, tokens) inside the current window
+    samples: Vec<(DateTime<Utc>, i64)>,
+    last_alert: Option<DateTime<Utc>>,
+}
+
+/// Sliding-window token rate tracker for all sessions seen by this instance.
+pub(super) struct TokenRateMonitor {
+    threshold_per_minute: u64,
+    sessions: Mutex<HashMap<(String, String), SessionWindow>>,
+}
+
+impl TokenRateMonitor {
+    pub fn new(threshold_per_minute: u64) -> Self {
+        Self {
+            threshold_per_minute,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add a batch of persisted spans and return alerts for sessions that
+    /// crossed the threshold.
+    pub fn observe(&self, spans: &[NormalizedSpan]) -> Vec<TokenRateAlert> {
+        let window = TimeDelta::seconds(TOKEN_RATE_WINDOW_SECS);
+        let cooldown = TimeDelta::seconds(TOKEN_RATE_ALERT_COOLDOWN_SECS);
+        let mut alerts = Vec::new();
+        let mut sessions = self.sessions.lock();
+
+        for span in spans {
+            let Some(session_id) = span.session_id.as_deref().filter(|s| !s.is_empty()) else {
+                continue;
+            };
+            if span.gen_ai_usage_total_tokens <= 0 {
+                continue;
+            }
+            let project_id = span.project_id.clone().unwrap_or_default();
+            let key = (project_id, session_id.to_string());
+            if sessions.len() >= MAX_TRACKED_SESSIONS && !sessions.contains_key(&key) {
+                sessions.clear();
+            }
+            let state = sessions.entry(key).or_default();
+
+            let at = span.timestamp_end.unwrap_or(span.timestamp_start);
+            state.samples.push((at, span.gen_ai_usage_total_tokens));
+            let window_end = state.samples.iter().map(|(ts, _)| *ts).max().unwrap_or(at);
+            state.samples.retain(|(ts, _)| *ts > window_end - window);
+
+            let tokens: i64 = state.samples.iter().map(|(_, t)| t).sum();
+            if tokens <= self.threshold_per_minute as i64 {
+                continue;
+            }
+            if state
+                .last_alert
+                .is_some_and(|last| window_end - last < cooldown)
+            {
+                continue;
+            }
+            state.last_alert = Some(window_end);
+            alerts.push(TokenRateAlert {
+                project_id: span.project_id.clone().unwrap_or_default(),
+                session_id: session_id.to_string(),
+                tokens_per_minute: tokens,
+                threshold_per_minute: self.threshold_per_minute,
+                window_end,
+                trace_id: span.trace_id.clone(),
+            });
+        }
+
+        alerts
+    }
+}
+
+/// Log and broadcast token rate alerts to their per-project topics.
+pub(super) async fn publish_token_rate_alerts(alerts: &[TokenRateAlert], topics: &TopicService) {
+    for alert in alerts {
+        tracing::warn!(
+            project_id = %alert.project_id,
+            session_id = %alert.session_id,
+            tokens_per_minute = alert.tokens_per_minute,
+            threshold = alert.threshold_per_minute,
+            "Session token rate above threshold (possible runaway agent)"
+        );
+        let topic_name = format!("{}:{}", TOPIC_TOKEN_RATE_ALERTS, alert.project_id);
+        let topic = topics.broadcast_topic::<TokenRateAlert>(&topic_name);
+        if let Err(e) = topic.publish(alert).await {
+            tracing::warn!(error = %e, "Failed to publish token rate alert");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_span(session_id: &str, at: DateTime<Utc>, tokens: i64) -> NormalizedSpan {
+        NormalizedSpan {
+            project_id: Some("p1".to_string()),
+            trace_id: format!("trace-{}", at.timestamp_millis()),
+            session_id: Some(session_id.to_string()),
+            timestamp_start: at,
+            timestamp_end: Some(at),
+            gen_ai_usage_total_tokens: tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_burst_above_threshold_fires_single_alert() {
+        let monitor = TokenRateMonitor::new(50_000);
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        // 20 spans x 10k tokens within 40 seconds, delivered over several batches
+        let spans: Vec<_> = (0..20)
+            .map(|i| make_span("loop", t0 + TimeDelta::seconds(2 * i), 10_000))
+            .collect();
+        let alerts: Vec<_> = spans
+            .chunks(3)
+            .flat_map(|batch| monitor.observe(batch))
+            .collect();
+
+        assert_eq!(alerts.len(), 1, "alerts are debounced per session");
+        let alert = &alerts[0];
+        assert_eq!(alert.session_id, "loop");
+        assert_eq!(alert.project_id, "p1");
+        assert!(alert.tokens_per_minute > 50_000);
+        assert_eq!(alert.threshold_per_minute, 50_000);
+
+        // A steady session under the threshold never alerts
+        let calm: Vec<_> = (0..20)
+            .map(|i| make_span("calm", t0 + TimeDelta::seconds(30 * i), 10_000))
+            .collect();
+        assert!(monitor.observe(&calm).is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+//! Trace completion detection from ingestion activity
+//!
+//! Spans of one trace can arrive over several batches, and async tool work may
+//! export its spans well after the agent span ended. Every persisted span marks
+//! its trace as active; once a trace has received no new spans for the
+//! configured quiet period, a [`TraceComplete`] event is broadcast on
+//! `trace_complete:{project_id}` so clients can stop expecting updates.
+//!
+//! Activity is measured by arrival (wall-clock) time, not span timestamps, as
+//! the point is to wait out late exports. Clients receive the events on the
+//! project SSE stream.
+//!
+//! State is per process. When several workers share a Redis stream, the spans
+//! of one trace can be split between them: each reports its own completion
+//! (with `span_count` covering only its share), and one may report before
+//! another's late spans arrive. Treat the event as a hint, not a guarantee.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::{MAX_TRACKED_TRACES, TOPIC_TRACE_COMPLETE};
+use crate::core::{TopicMessage, TopicService};
+use crate::data::types::NormalizedSpan;
+
+/// Broadcast once a trace has been quiet for the configured period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceComplete {
+    pub project_id: String,
+    pub trace_id: String,
+    /// Session of the trace's spans, if any
+    pub session_id: Option<String>,
+    /// Spans received for the trace by this instance while it was active
+    pub span_count: u64,
+    /// Arrival time of the most recent span
+    pub last_span_at: DateTime<Utc>,
+}
+
+impl TopicMessage for TraceComplete {
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.project_id.len()
+            + self.trace_id.len()
+            + self.session_id.as_ref().map_or(0, |s| s.len())
+    }
+}
+
+/// (project_id, trace_id)
+type TraceKey = (String, String);
+
+struct TraceActivity {
+    session_id: Option<String>,
+    last_span_at: DateTime<Utc>,
+    span_count: u64,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    traces: HashMap<TraceKey, TraceActivity>,
+    /// Traces ordered by last activity, so expiry and eviction pop from the front
+    by_activity: BTreeSet<(DateTime<Utc>, TraceKey)>,
+}
+
+/// Tracks active traces and reports those that went quiet.
+pub(super) struct TraceCompletionTracker {
+    quiet_period: TimeDelta,
+    state: Mutex<TrackerState>,
+}
+
+impl TraceCompletionTracker {
+    pub fn new(quiet_period_secs: u64) -> Self {
+        Self {
+            quiet_period: i64::try_from(quiet_period_secs)
+                .ok()
+                .and_then(TimeDelta::try_seconds)
+                .unwrap_or(TimeDelta::MAX),
+            state: Mutex::new(TrackerState::default()),
+        }
+    }
+
+    /// Mark the traces of a persisted batch as active at `now`.
+    pub fn observe(&self, spans: &[NormalizedSpan], now: DateTime<Utc>) {
+        let mut state = self.state.lock();
+        let TrackerState {
+            traces,
+            by_activity,
+        } = &mut *state;
+        for span in spans {
+            let key = (
+                span.project_id.clone().unwrap_or_default(),
+                span.trace_id.clone(),
+            );
+            match traces.get_mut(&key) {
+                Some(activity) => {
+                    // Spans of one batch share `now`: reorder only on the first
+                    if activity.last_span_at != now {
+                        by_activity.remove(&(activity.last_span_at, key.clone()));
+                        by_activity.insert((now, key));
+                        activity.last_span_at = now;
+                    }
+                    if activity.session_id.is_none() {
+                        activity.session_id = span.session_id.clone();
+                    }
+                    activity.span_count += 1;
+                }
+                None => {
+                    if traces.len() >= MAX_TRACKED_TRACES
+                        && let Some((_, oldest)) = by_activity.pop_first()
+                    {
+                        tracing::debug!(trace_id = %oldest.1, "Trace completion tracker full, evicting oldest trace");
+                        traces.remove(&oldest);
+                    }
+                    by_activity.insert((now, key.clone()));
+                    traces.insert(
+                        key,
+                        TraceActivity {
+                            session_id: span.session_id.clone(),
+                            last_span_at: now,
+                            span_count: 1,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Remove and return traces with no new spans for the quiet period.
+    pub fn take_completed(&self, now: DateTime<Utc>) -> Vec<TraceComplete> {
+        let mut completed = Vec::new();
+        let mut state = self.state.lock();
+        while let Some((last_span_at, _)) = state.by_activity.first()
+            && now - *last_span_at >= self.quiet_period
+        {
+            let Some((_, key)) = state.by_activity.pop_first() else {
+                break;
+            };
+            if let Some(activity) = state.traces.remove(&key) {
+                let (project_id, trace_id) = key;
+                completed.push(TraceComplete {
+                    project_id,
+                    trace_id,
+                    session_id: activity.session_id,
+                    span_count: activity.span_count,
+                    last_span_at: activity.last_span_at,
+                });
+            }
+        }
+        completed
+    }
+}
+
+/// Broadcast trace completion events to their per-project topics.
+pub(super) async fn publish_trace_completions(events: &[TraceComplete], topics: &TopicService) {
+    for event in events {
+        tracing::trace!(
+            project_id = %event.project_id,
+            trace_id = %event.trace_id,
+            span_count = event.span_count,
+            "Trace complete"
+        );
+        let topic_name = format!("{}:{}", TOPIC_TRACE_COMPLETE, event.project_id);
+        let topic = topics.broadcast_topic::<TraceComplete>(&topic_name);
+        if let Err(e) = topic.publish(event).await {
+            tracing::warn!(error = %e, "Failed to publish trace completion");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_span(trace_id: &str) -> NormalizedSpan {
+        NormalizedSpan {
+            project_id: Some("p1".to_string()),
+            trace_id: trace_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_completion_fires_only_after_quiet_period() {
+        let tracker = TraceCompletionTracker::new(30);
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        tracker.observe(&[make_span("t1"), make_span("t1")], t0);
+        assert!(
+            tracker
+                .take_completed(t0 + TimeDelta::seconds(20))
+                .is_empty()
+        );
+
+        // A late span restarts the quiet period
+        tracker.observe(&[make_span("t1")], t0 + TimeDelta::seconds(25));
+        assert!(
+            tracker
+                .take_completed(t0 + TimeDelta::seconds(40))
+                .is_empty()
+        );
+
+        let completed = tracker.take_completed(t0 + TimeDelta::seconds(55));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].project_id, "p1");
+        assert_eq!(completed[0].trace_id, "t1");
+        assert_eq!(completed[0].span_count, 3);
+        assert_eq!(completed[0].last_span_at, t0 + TimeDelta::seconds(25));
+
+        // Reported once
+        assert!(
+            tracker
+                .take_completed(t0 + TimeDelta::seconds(120))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_full_tracker_evicts_oldest_trace() {
+        let tracker = TraceCompletionTracker::new(30);
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        tracker.observe(&[make_span("oldest")], t0);
+        let fill: Vec<_> = (1..MAX_TRACKED_TRACES)
+            .map(|i| make_span(&format!("t{i}")))
+            .collect();
+        tracker.observe(&fill, t0 + TimeDelta::seconds(1));
+        tracker.observe(&[make_span("newest")], t0 + TimeDelta::seconds(2));
+
+        let completed = tracker.take_completed(t0 + TimeDelta::seconds(60));
+        assert_eq!(completed.len(), MAX_TRACKED_TRACES);
+        assert!(completed.iter().any(|c| c.trace_id == "newest"));
+        assert!(!completed.iter().any(|c| c.trace_id == "oldest"));
+    }
+
+    #[test]
+    fn test_huge_quiet_period_does_not_panic() {
+        let tracker = TraceCompletionTracker::new(u64::MAX);
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        tracker.observe(&[make_span("t1")], t0);
+        assert!(tracker.take_completed(t0 + TimeDelta::days(365)).is_empty());
+    }
+
+    #[test]
+    fn test_completion_carries_session_id() {
+        let tracker = TraceCompletionTracker::new(30);
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut with_session = make_span("t1");
+        with_session.session_id = Some("s1".to_string());
+
+        tracker.observe(&[make_span("t1"), with_session], t0);
+        let completed = tracker.take_completed(t0 + TimeDelta::seconds(30));
+        assert_eq!(completed[0].session_id.as_deref(), Some("s1"));
+    }
+}
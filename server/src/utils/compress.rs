@@ -0,0 +1,92 @@
+//! Compression of stored JSON columns
+//!
+//! Compressed values are zstd + base64 wrapped in a JSON string (`"z1:..."`),
+//! so they stay valid JSON for the column type. Plain rows start with `[` or
+//! `{`, which makes the leading `"` the header byte that tells them apart.
+
+use std::borrow::Cow;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Envelope prefix of a compressed value (header byte `"` + format version)
+const COMPRESSED_PREFIX: &str = "\"z1:";
+
+/// Values shorter than this are stored as-is (not worth the envelope)
+const MIN_COMPRESS_LEN: usize = 256;
+
+/// zstd level: favors ingestion throughput over ratio
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compress a JSON document for storage.
+///
+/// Returns the input unchanged when it is short or compression does not
+/// make it smaller.
+pub fn compress_json(json: String) -> String {
+    if json.len() < MIN_COMPRESS_LEN {
+        return json;
+    }
+    let Ok(compressed) = zstd::encode_all(json.as_bytes(), ZSTD_LEVEL) else {
+        return json;
+    };
+    let encoded = format!("{COMPRESSED_PREFIX}{}\"", STANDARD.encode(compressed));
+    if encoded.len() < json.len() {
+        encoded
+    } else {
+        json
+    }
+}
+
+/// Decompress a stored JSON document, passing plain rows through untouched.
+///
+/// A corrupt envelope yields an empty string so callers report a parse error
+/// for the row instead of parsing the envelope as data.
+pub fn decompress_json(stored: &str) -> Cow<'_, str> {
+    let Some(payload) = stored
+        .strip_prefix(COMPRESSED_PREFIX)
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return Cow::Borrowed(stored);
+    };
+    let decoded = STANDARD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| zstd::decode_all(bytes.as_slice()).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok());
+    match decoded {
+        Some(json) => Cow::Owned(json),
+        None => {
+            tracing::warn!("Failed to decompress stored JSON");
+            Cow::Borrowed("")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_large_json() {
+        let json =
+            serde_json::json!([{"role": "user", "content": "hello ".repeat(200)}]).to_string();
+        let stored = compress_json(json.clone());
+        assert!(stored.starts_with(COMPRESSED_PREFIX));
+        assert!(stored.len() < json.len());
+        // Envelope is itself valid JSON
+        assert!(serde_json::from_str::<String>(&stored).is_ok());
+        assert_eq!(decompress_json(&stored), json);
+    }
+
+    #[test]
+    fn test_small_and_plain_values_pass_through() {
+        assert_eq!(compress_json("[]".to_string()), "[]");
+        let plain = r#"[{"role":"user","content":"hi"}]"#;
+        assert!(matches!(decompress_json(plain), Cow::Borrowed(s) if s == plain));
+    }
+
+    #[test]
+    fn test_corrupt_envelope_is_empty() {
+        assert_eq!(decompress_json("\"z1:not-base64!\""), "");
+    }
+}
@@ -16,6 +16,10 @@ use serde_json::Value as JsonValue;
 
 pub const PROJECT_ID_ATTR: &str = "sideseat.project_id";
 
+/// Organization of an authenticated gRPC export, stamped on resources without a
+/// project_id so the trace pipeline can pick that org's default project
+pub const ORG_ID_ATTR: &str = "sideseat.org_id";
+
 // ============================================================================
 // SHARED ATTRIBUTE KEYS
 // ============================================================================
@@ -135,6 +139,103 @@ pub fn inject_project_id_traces(request: &mut ExportTraceServiceRequest, project
     }
 }
 
+/// Whether any resource in the request carries no project_id
+pub fn has_unscoped_traces(request: &ExportTraceServiceRequest) -> bool {
+    request.resource_spans.iter().any(|resource_spans| {
+        !resource_spans
+            .resource
+            .as_ref()
+            .is_some_and(|r| r.attributes.iter().any(|kv| kv.key == PROJECT_ID_ATTR))
+    })
+}
+
+/// Tag resources that carry no project_id with the ingesting organization.
+///
+/// Org ids sent by the client are dropped first, so only an authenticated org
+/// (or none) reaches the pipeline.
+pub fn stamp_unscoped_org_id_traces(request: &mut ExportTraceServiceRequest, org_id: Option<&str>) {
+    for resource_spans in &mut request.resource_spans {
+        let Some(ref mut resource) = resource_spans.resource else {
+            continue;
+        };
+        resource.attributes.retain(|kv| kv.key != ORG_ID_ATTR);
+    }
+    let Some(org_id) = org_id else {
+        return;
+    };
+    let attr = KeyValue {
+        key: ORG_ID_ATTR.to_string(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(org_id.to_string())),
+        }),
+    };
+    for resource_spans in &mut request.resource_spans {
+        let resource = resource_spans.resource.get_or_insert_with(Default::default);
+        if !resource
+            .attributes
+            .iter()
+            .any(|kv| kv.key == PROJECT_ID_ATTR)
+        {
+            resource.attributes.push(attr.clone());
+        }
+    }
+}
+
+/// Organizations stamped on the unscoped resources of a request (None for
+/// resources sent without authentication)
+pub fn unscoped_org_ids(request: &ExportTraceServiceRequest) -> Vec<Option<String>> {
+    request
+        .resource_spans
+        .iter()
+        .filter_map(|resource_spans| {
+            let attributes = resource_spans
+                .resource
+                .as_ref()
+                .map_or(&[][..], |r| &r.attributes[..]);
+            if attributes.iter().any(|kv| kv.key == PROJECT_ID_ATTR) {
+                return None;
+            }
+            Some(
+                attributes
+                    .iter()
+                    .find(|kv| kv.key == ORG_ID_ATTR)
+                    .and_then(|kv| kv.value.as_ref())
+                    .map(any_value_to_string),
+            )
+        })
+        .collect()
+}
+
+/// Assign project_id to resources that carry none, leaving scoped resources as sent.
+///
+/// `project_for_org` maps a resource's stamped organization (see
+/// [`stamp_unscoped_org_id_traces`]) to its project. The org tag is removed.
+pub fn assign_unscoped_project_id_traces<'a>(
+    request: &mut ExportTraceServiceRequest,
+    project_for_org: impl Fn(Option<&str>) -> &'a str,
+) {
+    for resource_spans in &mut request.resource_spans {
+        let resource = resource_spans.resource.get_or_insert_with(Default::default);
+        if resource
+            .attributes
+            .iter()
+            .any(|kv| kv.key == PROJECT_ID_ATTR)
+        {
+            continue;
+        }
+        let org_id = resource
+            .attributes
+            .iter()
+            .find(|kv| kv.key == ORG_ID_ATTR)
+            .and_then(|kv| kv.value.as_ref())
+            .map(any_value_to_string);
+        resource.attributes.retain(|kv| kv.key != ORG_ID_ATTR);
+        resource
+            .attributes
+            .push(make_project_id_attr(project_for_org(org_id.as_deref())));
+    }
+}
+
 /// Inject project_id into resource attributes for metrics
 pub fn inject_project_id_metrics(request: &mut ExportMetricsServiceRequest, project_id: &str) {
     let attr = make_project_id_attr(project_id);
@@ -606,4 +707,48 @@ mod tests {
             assert!(json_obj.contains_key(key), "JSON missing key: {}", key);
         }
     }
+
+    #[test]
+    fn test_stamp_unscoped_org_id_drops_client_org_ids() {
+        use opentelemetry_proto::tonic::resource::v1::Resource;
+        use opentelemetry_proto::tonic::trace::v1::ResourceSpans;
+
+        let org_attr = |org_id: &str| KeyValue {
+            key: ORG_ID_ATTR.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(org_id.to_string())),
+            }),
+        };
+        let resource = |attributes: Vec<KeyValue>| ResourceSpans {
+            resource: Some(Resource {
+                attributes,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut request = ExportTraceServiceRequest {
+            resource_spans: vec![
+                resource(vec![org_attr("spoofed")]),
+                resource(vec![make_project_id_attr("explicit"), org_attr("spoofed")]),
+            ],
+        };
+
+        stamp_unscoped_org_id_traces(&mut request, None);
+        assert_eq!(unscoped_org_ids(&request), vec![None]);
+
+        stamp_unscoped_org_id_traces(&mut request, Some("org-1"));
+        assert_eq!(unscoped_org_ids(&request), vec![Some("org-1".to_string())]);
+
+        assign_unscoped_project_id_traces(&mut request, |org_id| {
+            assert_eq!(org_id, Some("org-1"));
+            "inbox"
+        });
+        assert!(!has_unscoped_traces(&request));
+        let attributes = &request.resource_spans[0]
+            .resource
+            .as_ref()
+            .unwrap()
+            .attributes;
+        assert!(attributes.iter().all(|kv| kv.key != ORG_ID_ATTR));
+    }
 }
@@ -8,4 +8,12 @@ export { OrganizationsClient } from "./client";
 export { organizationKeys } from "./keys";
 
 // Types
-export type { Organization, OrgWithRole, ListOrgsParams, PaginatedResponse } from "./types";
+export type {
+  FeatureFlags,
+  OrgBudget,
+  Organization,
+  OrgWithRole,
+  ListOrgsParams,
+  PaginatedResponse,
+  ProjectSpend,
+} from "./types";
@@ -6,6 +6,9 @@ export interface Organization {
   slug: string;
   created_at: string;
   updated_at: string;
+  monthly_limit_usd?: number;
+  default_project_id?: string;
+  retention_days?: number;
 }
 
 export interface OrgWithRole extends Organization {
@@ -16,3 +19,22 @@ export interface ListOrgsParams {
   page?: number;
   limit?: number;
 }
+
+export interface FeatureFlags {
+  anonymize_content: boolean;
+}
+
+export interface ProjectSpend {
+  project_id: string;
+  spent_usd: number;
+}
+
+export interface OrgBudget {
+  period_start: string;
+  period_end: string;
+  monthly_limit_usd: number | null;
+  spent_usd: number;
+  remaining_usd: number | null;
+  exceeded: boolean;
+  projects: ProjectSpend[];
+}
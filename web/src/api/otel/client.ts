@@ -20,6 +20,10 @@ import type {
   SseParams,
   SseSpanEvent,
   SSEHandlers,
+  Timeseries,
+  TimeseriesParams,
+  ToolStatsParams,
+  ToolStatsResponse,
   TraceDetail,
   TraceSummary,
   FeedMessagesParams,
@@ -138,6 +142,18 @@ export class OtelClient {
     );
   }
 
+  /** Get the exact input sent to a span, history included */
+  async getSpanPrompt(
+    projectId: string,
+    spanId: string,
+    params?: { anonymize?: boolean },
+  ): Promise<MessagesResponse> {
+    return this.client.get<MessagesResponse>(
+      `${this.basePath(projectId)}/spans/${spanId}/prompt`,
+      params as Record<string, unknown>,
+    );
+  }
+
   // === Sessions ===
   async listSessions(
     projectId: string,
@@ -237,6 +253,20 @@ export class OtelClient {
     );
   }
 
+  async getTimeseries(projectId: string, params: TimeseriesParams): Promise<Timeseries> {
+    return this.client.get<Timeseries>(
+      `${this.basePath(projectId)}/timeseries`,
+      params as unknown as Record<string, unknown>,
+    );
+  }
+
+  async getToolStats(projectId: string, params: ToolStatsParams): Promise<ToolStatsResponse> {
+    return this.client.get<ToolStatsResponse>(
+      `${this.basePath(projectId)}/tools/stats`,
+      params as unknown as Record<string, unknown>,
+    );
+  }
+
   // === Feed ===
   async getFeedMessages(
     projectId: string,
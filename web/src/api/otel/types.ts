@@ -48,6 +48,8 @@ export interface TraceSummary {
   input_preview: string | null;
   output_preview: string | null;
   has_error: boolean;
+  primary_model: string | null;
+  models: string[];
 }
 
 export interface TraceDetail extends TraceSummary {
@@ -88,10 +90,24 @@ export interface SpanSummary {
   cache_write_cost: number;
   reasoning_cost: number;
   total_cost: number;
+  /** Where the stored costs came from (absent when the span has no cost) */
+  cost_source?: "computed" | "reported";
+  /** Embedding output dimensions (embedding spans only) */
+  embedding_dimensions?: number;
+  /** Embedding encoding format, e.g. "float" or "base64" (embedding spans only) */
+  encoding_format?: string;
+  /** Requested reasoning effort, e.g. "low" or "high" (reasoning models only) */
+  reasoning_effort?: string;
+  /** Requested response verbosity, e.g. "low" or "high" */
+  verbosity?: string;
+  /** Model string as reported, when an ingestion alias rewrote it */
+  raw_model?: string;
   event_count: number;
   link_count: number;
   input_preview: string | null;
   output_preview: string | null;
+  /** Span attributes selected by the otel.promoted_attributes config (omitted when none match) */
+  custom_attributes?: Record<string, unknown>;
   /** Raw OTLP span JSON (only present when include_raw_span=true) */
   raw_span?: Record<string, unknown>;
 }
@@ -230,10 +246,13 @@ export interface Filter {
 
 // === Query Parameters ===
 
+export type TraceSort = "time_desc" | "cost_desc" | "cost_asc" | "tokens_desc" | "duration_desc";
+
 export interface ListTracesParams {
   page?: number;
   limit?: number;
   order_by?: string;
+  sort?: TraceSort;
   session_id?: string;
   user_id?: string;
   environment?: string | string[];
@@ -359,9 +378,15 @@ export interface Block {
   /** Message category for semantic filtering */
   category: MessageCategory;
 
-  // For deduplication
-  content_hash: string;
+  /** Stable content hash for client-side diffing (only when hashes are requested) */
+  content_hash?: string;
   is_semantic: boolean;
+  /** Span carrying the full system prompt this block repeats (collapsed) */
+  repeat_of?: string;
+  /** Milliseconds since the previous block (only when gaps are requested) */
+  gap_ms?: number;
+  /** Shared by tool calls requested together in one assistant turn */
+  parallel_group?: string;
 }
 
 export interface MessagesMetadata {
@@ -370,10 +395,47 @@ export interface MessagesMetadata {
   total_cost: number;
   start_time: string;
   end_time: string | null;
+  content_sizes: ContentSizeStats;
+  warnings: FeedWarning[];
+}
+
+export type FeedWarningKind =
+  | "parse_error"
+  | "hierarchy_cycle"
+  | "hierarchy_too_deep"
+  | "session_too_large"
+  | "prefix_truncated"
+  | "future_timestamp";
+
+export interface FeedWarning {
+  type: FeedWarningKind;
+  trace_id: string;
+  span_id: string;
+  message: string;
+}
+
+export interface ContentSizeStats {
+  total_bytes: number;
+  largest_block_bytes: number;
+  text_bytes: number;
+  media_bytes: number;
+  tool_bytes: number;
+  other_bytes: number;
+}
+
+/** Consecutive same-role blocks of one span batch (blocks `start..start + block_count`) */
+export interface Turn {
+  role: string;
+  trace_id: string;
+  span_id: string;
+  start: number;
+  block_count: number;
 }
 
 export interface MessagesResponse {
   messages: Block[];
+  /** Only present when turns are requested */
+  turns?: Turn[];
   metadata: MessagesMetadata;
   tool_definitions: Record<string, unknown>[];
   tool_names: string[];
@@ -436,12 +498,65 @@ export interface ProjectStats {
     bucket: string;
     avg_duration_ms: number;
   }>;
+  cost_extrapolation?: {
+    estimated: boolean;
+    sampling_rate: number;
+    multiplier: number;
+  };
 }
 
 export interface ProjectStatsParams {
   from_timestamp: string;
   to_timestamp: string;
   timezone?: string;
+  extrapolate_cost?: boolean;
+}
+
+export type TimeseriesInterval = "minute" | "hour" | "day";
+export type TimeseriesMetric = "cost" | "tokens" | "spans" | "errors";
+
+export interface Timeseries {
+  period: {
+    from: string;
+    to: string;
+  };
+  interval: TimeseriesInterval;
+  metric: TimeseriesMetric;
+  timezone: string;
+  buckets: Array<{
+    bucket: string;
+    value: number;
+  }>;
+}
+
+export interface TimeseriesParams {
+  from_timestamp: string;
+  to_timestamp: string;
+  timezone?: string;
+  interval?: TimeseriesInterval;
+  metric?: TimeseriesMetric;
+}
+
+export interface ToolStats {
+  tool_name: string;
+  call_count: number;
+  error_count: number;
+  /** Percentage of calls that ended with ERROR status */
+  error_rate: number;
+  avg_duration_ms: number | null;
+}
+
+export interface ToolStatsResponse {
+  period: {
+    from: string;
+    to: string;
+  };
+  tools: ToolStats[];
+}
+
+export interface ToolStatsParams {
+  from_timestamp: string;
+  to_timestamp: string;
 }
 
 // === Feed API ===
@@ -456,10 +571,13 @@ export interface FeedMessagesMetadata {
   span_count: number;
   total_tokens: number;
   total_cost: number;
+  warnings: FeedWarning[];
 }
 
 export interface FeedMessagesResponse {
   data: Block[];
+  /** Only present when turns are requested */
+  turns?: Turn[];
   pagination: FeedPagination;
   metadata: FeedMessagesMetadata;
   tool_definitions: Record<string, unknown>[];